@@ -200,6 +200,7 @@ fn make_rrf_inputs(size: usize) -> (Vec<SearchResult>, Vec<VectorSearchResult>)
         let doc_type = match result_type {
             SearchResultType::Tweet => "tweet",
             SearchResultType::Like => "like",
+            SearchResultType::Bookmark => "bookmark",
             SearchResultType::DirectMessage => "dm",
             SearchResultType::GrokMessage => "grok",
         };
@@ -212,6 +213,7 @@ fn make_rrf_inputs(size: usize) -> (Vec<SearchResult>, Vec<VectorSearchResult>)
             created_at: Utc::now(),
             score: usize_to_f32(size.saturating_sub(i) + 1),
             highlights: Vec::new(),
+            match_ranges: Vec::new(),
             metadata: serde_json::Value::Null,
         });
 
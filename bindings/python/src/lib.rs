@@ -0,0 +1,142 @@
+//! Python bindings for xf.
+//!
+//! A thin layer over [`xf::api`]: index an archive, open it, search it and
+//! read its statistics. Results cross into Python as plain dicts and lists,
+//! in the same shape as `xf search --format json`.
+//!
+//! ```python
+//! import xf
+//!
+//! xf.index("twitter-archive")
+//! archive = xf.Archive()
+//! for hit in archive.search("rust", types=["tweet"], since="last year"):
+//!     print(hit["created_at"], hit["text"])
+//! ```
+
+use chrono::{DateTime, Utc};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use xf::api::{Client, DocType, SearchRequest};
+use xf::date_parser::parse_date_flexible;
+
+/// Convert a serializable value to Python objects via the `json` module.
+fn to_python(py: Python<'_>, value: &impl Serialize) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+/// Names accepted in `types`, matching `xf search --types`.
+fn doc_type(name: &str) -> PyResult<DocType> {
+    match name {
+        "tweet" => Ok(DocType::Tweet),
+        "like" => Ok(DocType::Like),
+        "dm" => Ok(DocType::DirectMessage),
+        "grok" => Ok(DocType::GrokMessage),
+        other => Err(PyValueError::new_err(format!(
+            "unknown type {other:?}; expected one of \"tweet\", \"like\", \"dm\", \"grok\""
+        ))),
+    }
+}
+
+fn date(value: Option<&str>, prefer_end: bool) -> PyResult<Option<DateTime<Utc>>> {
+    value
+        .map(|value| {
+            parse_date_flexible(value, prefer_end)
+                .map_err(|e| PyValueError::new_err(format!("invalid date {value:?}: {e}")))
+        })
+        .transpose()
+}
+
+/// Index an extracted archive, like `xf index`.
+///
+/// Returns a dict of how many documents of each type were stored. Paths
+/// default to the locations `xf` itself uses.
+#[pyfunction]
+#[pyo3(signature = (archive_path, db_path=None, index_path=None))]
+fn index(
+    py: Python<'_>,
+    archive_path: PathBuf,
+    db_path: Option<PathBuf>,
+    index_path: Option<PathBuf>,
+) -> PyResult<PyObject> {
+    let summary = xf::api::index_archive(
+        archive_path,
+        db_path.unwrap_or_else(xf::default_db_path),
+        index_path.unwrap_or_else(xf::default_index_path),
+    )?;
+    to_python(py, &summary)
+}
+
+/// An archive indexed by `xf index` or `xf.index()`.
+#[pyclass(module = "xf")]
+struct Archive {
+    // The SQLite connection is not `Sync`, so calls from Python threads take turns
+    client: Mutex<Client>,
+}
+
+impl Archive {
+    fn client(&self) -> PyResult<MutexGuard<'_, Client>> {
+        self.client
+            .lock()
+            .map_err(|_| PyRuntimeError::new_err("archive is unusable after a panic"))
+    }
+}
+
+#[pymethods]
+impl Archive {
+    #[new]
+    #[pyo3(signature = (db_path=None, index_path=None))]
+    fn new(db_path: Option<PathBuf>, index_path: Option<PathBuf>) -> PyResult<Self> {
+        Ok(Self {
+            client: Mutex::new(Client::open(
+                db_path.unwrap_or_else(xf::default_db_path),
+                index_path.unwrap_or_else(xf::default_index_path),
+            )?),
+        })
+    }
+
+    /// Keyword search, best matches first, as a list of dicts.
+    ///
+    /// `since` and `until` accept the same dates as `xf search`, such as
+    /// "2023-06-01" or "last month".
+    #[pyo3(signature = (query, types=None, limit=SearchRequest::DEFAULT_LIMIT, since=None, until=None))]
+    fn search(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        types: Option<Vec<String>>,
+        limit: usize,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let request = SearchRequest {
+            types: types
+                .unwrap_or_default()
+                .iter()
+                .map(|name| doc_type(name))
+                .collect::<PyResult<_>>()?,
+            limit,
+            since: date(since, false)?,
+            until: date(until, true)?,
+            ..SearchRequest::new(query)
+        };
+        to_python(py, &self.client()?.search(&request)?)
+    }
+
+    /// Record counts and date range, as a dict.
+    fn stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.client()?.stats()?)
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "xf")]
+fn xf_python(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    module.add_function(wrap_pyfunction!(index, module)?)?;
+    module.add_class::<Archive>()?;
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! Archive and `--since`/`--until` dates: `cargo +nightly fuzz run parse_date`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use xf::ArchiveParser;
+use xf::date_parser::{parse_date_flexible, parse_period};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let _ = ArchiveParser::parse_date(input);
+    let _ = parse_date_flexible(input, false);
+    let _ = parse_date_flexible(input, true);
+    let _ = parse_period(input);
+});
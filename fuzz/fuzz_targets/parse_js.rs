@@ -0,0 +1,16 @@
+//! Archive data files: `cargo +nightly fuzz run parse_js fuzz/corpus/parse_js`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use xf::parser::{ArchiveParser, parse_js_data};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(content) = std::str::from_utf8(data) else {
+        return;
+    };
+    let _ = parse_js_data(content);
+    let _ = ArchiveParser::tweets_from_js(content);
+    let _ = ArchiveParser::likes_from_js(content);
+    let _ = ArchiveParser::direct_messages_from_js(content);
+});
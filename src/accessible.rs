@@ -0,0 +1,794 @@
+//! Plain labeled output for screen readers (`--accessible`).
+//!
+//! The text renderers lean on box drawing, sparklines, bar charts and color
+//! badges, which screen readers either skip or read out symbol by symbol.
+//! These renderers say the same things as short labeled sentences, one per
+//! line, such as `Result 3 of 20. Type: tweet. Date: March 4, 2024.` Charts
+//! are described by their notable values instead of drawn.
+
+use crate::doctor::{CheckCategory, CheckStatus, HealthCheck};
+use crate::i18n::{Msg, t};
+use crate::model::{ArchiveStats, SearchResult, SearchResultType};
+use crate::stats_analytics::{
+    ContentStats, CooccurTarget, CooccurrenceStats, EngagementStats, FollowerChurnStats,
+    LikesStats, PeriodComparison, SourceStats, StyleStats, TemporalStats,
+};
+use crate::{format_number, format_number_u64, format_number_usize};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::fmt::Write;
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Spoken name of a result type.
+const fn type_name(result_type: SearchResultType) -> &'static str {
+    match result_type {
+        SearchResultType::Tweet => "tweet",
+        SearchResultType::Like => "like",
+        SearchResultType::Bookmark => "bookmark",
+        SearchResultType::DirectMessage => "direct message",
+        SearchResultType::GrokMessage => "Grok message",
+    }
+}
+
+fn spoken_date(date: DateTime<Utc>) -> String {
+    date.format("%B %-d, %Y").to_string()
+}
+
+fn spoken_naive_date(date: NaiveDate) -> String {
+    date.format("%B %-d, %Y").to_string()
+}
+
+/// A section heading, with a blank line before it.
+fn heading(lines: &mut Vec<String>, title: &str) {
+    lines.push(String::new());
+    lines.push(format!("{}.", title.trim_end_matches(':')));
+}
+
+/// Collapse line breaks so a text reads as one line.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `count` followed by `noun`, pluralized with an `s` unless it is 1.
+pub(crate) fn count_of(count: u64, noun: &str) -> String {
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{} {noun}{plural}", format_number_u64(count))
+}
+
+/// `count` replies, spelled out.
+fn replies_count(count: u64) -> String {
+    let noun = if count == 1 { "reply" } else { "replies" };
+    format!("{} {noun}", format_number_u64(count))
+}
+
+// ============================================================================
+// Search
+// ============================================================================
+
+/// Search results, one labeled block per result.
+#[must_use]
+pub fn search_results(query: &str, results: &[SearchResult]) -> Vec<String> {
+    let total = results.len();
+    let mut lines = vec![format!(
+        "Found {} {} for \"{query}\".",
+        format_number_usize(total),
+        if total == 1 { "result" } else { "results" }
+    )];
+
+    for (i, result) in results.iter().enumerate() {
+        let mut header = format!(
+            "Result {} of {total}. Type: {}.",
+            i + 1,
+            type_name(result.result_type)
+        );
+        if result.created_at.timestamp() > 0 {
+            let _ = write!(header, " Date: {}.", spoken_date(result.created_at));
+        }
+        let _ = write!(header, " ID: {}.", result.id);
+        if result
+            .metadata
+            .get("pinned")
+            .and_then(serde_json::Value::as_bool)
+            == Some(true)
+        {
+            header.push_str(" Pinned.");
+        }
+
+        lines.push(String::new());
+        lines.push(header);
+        lines.push(format!("Text: {}", one_line(&result.text)));
+        if let Some(notes) = result.metadata.get("notes").and_then(|n| n.as_array()) {
+            for note in notes.iter().filter_map(|n| n.as_str()) {
+                lines.push(format!("Note: {}", one_line(note)));
+            }
+        }
+    }
+    lines
+}
+
+/// The message shown when a search finds nothing.
+#[must_use]
+pub fn no_results(query: &str) -> Vec<String> {
+    vec![
+        format!("No results found for \"{query}\"."),
+        "Try different keywords, check the spelling, or remove filters.".to_string(),
+    ]
+}
+
+// ============================================================================
+// Stats
+// ============================================================================
+
+/// Archive counts and tweet date range.
+#[must_use]
+pub fn overview(stats: &ArchiveStats) -> Vec<String> {
+    let mut lines = vec![format!("{}.", t(Msg::Overview))];
+    for (label, count) in [
+        (Msg::Tweets, stats.tweets_count),
+        (Msg::Likes, stats.likes_count),
+        (Msg::DmConversations, stats.dm_conversations_count),
+        (Msg::DmMessages, stats.dms_count),
+        (Msg::GrokMessages, stats.grok_messages_count),
+        (Msg::Followers, stats.followers_count),
+        (Msg::Following, stats.following_count),
+        (Msg::Blocks, stats.blocks_count),
+        (Msg::Mutes, stats.mutes_count),
+    ] {
+        lines.push(format!("{} {}.", t(label), format_number(count)));
+    }
+    if let (Some(first), Some(last)) = (stats.first_tweet_date, stats.last_tweet_date) {
+        lines.push(format!("{} {}.", t(Msg::FirstTweet), spoken_date(first)));
+        lines.push(format!("{} {}.", t(Msg::LastTweet), spoken_date(last)));
+    }
+    lines
+}
+
+/// A titled list of values and counts, such as top hashtags.
+#[must_use]
+pub fn counts(title: Msg, items: &[(String, usize)]) -> Vec<String> {
+    let mut lines = Vec::with_capacity(items.len() + 2);
+    heading(&mut lines, t(title));
+    if items.is_empty() {
+        lines.push("None.".to_string());
+    }
+    for (i, (value, count)) in items.iter().enumerate() {
+        lines.push(format!(
+            "{}. {value}: {}.",
+            i + 1,
+            format_number_usize(*count)
+        ));
+    }
+    lines
+}
+
+/// Posting frequency, busiest day and hour, and the longest gap.
+#[must_use]
+pub fn temporal(stats: &TemporalStats) -> Vec<String> {
+    let mut lines = Vec::new();
+    heading(&mut lines, t(Msg::TemporalPatterns));
+    lines.push(format!(
+        "Active days: {} of {} days in range.",
+        format_number_u64(stats.active_days_count),
+        format_number_u64(stats.total_days_in_range)
+    ));
+    lines.push(format!(
+        "Average tweets per active day: {:.1}.",
+        stats.avg_tweets_per_active_day
+    ));
+    if let Some(day) = stats.most_active_day {
+        lines.push(format!(
+            "Most active day: {}, with {}.",
+            spoken_naive_date(day),
+            count_of(stats.most_active_day_count, "tweet")
+        ));
+    }
+    lines.push(format!(
+        "Most active hour: {:02}:00 UTC, with {}.",
+        stats.most_active_hour,
+        count_of(stats.most_active_hour_count, "tweet")
+    ));
+    if stats.longest_gap_days > 1 {
+        let mut gap = format!(
+            "Longest gap: {} days",
+            format_number(stats.longest_gap_days)
+        );
+        if let (Some(start), Some(end)) = (stats.longest_gap_start, stats.longest_gap_end) {
+            let _ = write!(
+                gap,
+                ", from {} to {}",
+                spoken_naive_date(start),
+                spoken_naive_date(end)
+            );
+        }
+        gap.push('.');
+        lines.push(gap);
+    }
+    for shift in &stats.activity_shifts {
+        let (hours, direction) = shift.direction();
+        let mut line = format!(
+            "Posting hours shifted about {hours} hours {direction} of usual from {} to {}, over {}",
+            spoken_naive_date(shift.start),
+            spoken_naive_date(shift.end),
+            count_of(shift.tweets, "tweet")
+        );
+        if let Some(client) = &shift.client {
+            let _ = write!(line, ", mostly from {client}");
+        }
+        line.push_str(if shift.client_changed {
+            "; likely travel."
+        } else {
+            "."
+        });
+        lines.push(line);
+    }
+    let weekdays: Vec<String> = WEEKDAYS
+        .iter()
+        .zip(stats.dow_distribution)
+        .map(|(day, count)| format!("{day} {}", format_number_u64(count)))
+        .collect();
+    lines.push(format!("Tweets by day of week: {}.", weekdays.join(", ")));
+    lines
+}
+
+/// Engagement totals, the likes distribution and top tweets.
+#[must_use]
+pub fn engagement(stats: &EngagementStats) -> Vec<String> {
+    let mut lines = Vec::new();
+    heading(&mut lines, t(Msg::EngagementAnalytics));
+    lines.push(format!(
+        "Total likes: {}. Total retweets: {}. Total replies: {}.",
+        format_number_u64(stats.total_likes),
+        format_number_u64(stats.total_retweets),
+        format_number_u64(stats.total_replies)
+    ));
+    if let Some(quotes) = stats.total_quotes {
+        lines.push(format!("Total quotes: {}.", format_number_u64(quotes)));
+    }
+    lines.push(format!(
+        "Average engagement per tweet: {:.1}. Median: {}.",
+        stats.avg_engagement,
+        format_number_u64(stats.median_engagement)
+    ));
+    for bucket in &stats.likes_histogram {
+        lines.push(format!(
+            "Tweets with {} likes: {}, {:.1} percent.",
+            bucket.label,
+            format_number_u64(bucket.count),
+            bucket.percentage
+        ));
+    }
+    let total = stats.top_tweets.len();
+    for (i, tweet) in stats.top_tweets.iter().enumerate() {
+        let quotes = tweet
+            .quotes
+            .map(|quotes| format!(", {}", count_of(quotes, "quote")))
+            .unwrap_or_default();
+        lines.push(format!(
+            "Top tweet {} of {total}. {}, {}, {}{quotes}. Date: {}. Text: {}",
+            i + 1,
+            count_of(tweet.likes, "like"),
+            count_of(tweet.retweets, "retweet"),
+            replies_count(tweet.replies),
+            spoken_date(tweet.created_at),
+            one_line(&tweet.text_preview)
+        ));
+    }
+    lines
+}
+
+/// Media, link and reply ratios, tweet length and top tags.
+#[must_use]
+pub fn content(stats: &ContentStats) -> Vec<String> {
+    let mut lines = Vec::new();
+    heading(&mut lines, t(Msg::ContentAnalysis));
+    lines.push(format!(
+        "Tweets with media: {:.1} percent. With links: {:.1} percent. Replies: {:.1} percent.",
+        stats.media_ratio, stats.link_ratio, stats.reply_ratio
+    ));
+    lines.push(format!(
+        "Self-threads: {}. Standalone tweets: {}.",
+        format_number_u64(stats.thread_count),
+        format_number_u64(stats.standalone_count)
+    ));
+    lines.push(format!(
+        "Average tweet length: {:.1} characters.",
+        stats.avg_tweet_length
+    ));
+    for bucket in &stats.length_distribution {
+        lines.push(format!(
+            "Tweets of {} characters: {}, {:.1} percent.",
+            bucket.label,
+            format_number_u64(bucket.count),
+            bucket.percentage
+        ));
+    }
+    for (label, prefix, tags) in [
+        ("Top hashtags", "#", &stats.top_hashtags),
+        ("Top mentions", "@", &stats.top_mentions),
+    ] {
+        if !tags.is_empty() {
+            let tags: Vec<String> = tags
+                .iter()
+                .take(6)
+                .map(|tag| format!("{prefix}{} {}", tag.tag, format_number_u64(tag.count)))
+                .collect();
+            lines.push(format!("{label}: {}.", tags.join(", ")));
+        }
+    }
+    lines
+}
+
+/// Like totals, most-liked authors and overlap with followed accounts.
+#[must_use]
+pub fn likes(stats: &LikesStats) -> Vec<String> {
+    let mut lines = Vec::new();
+    heading(&mut lines, t(Msg::LikesAnalytics));
+    lines.push(format!(
+        "Total likes: {}. Attributed to an author: {}. Unique authors: {}.",
+        format_number_u64(stats.total_likes),
+        format_number_u64(stats.attributed_likes),
+        format_number_u64(stats.unique_authors)
+    ));
+    if let Some(busiest) = stats.monthly_volume.iter().max_by_key(|month| month.count) {
+        lines.push(format!(
+            "Busiest month for likes: {}, with {}.",
+            busiest.month,
+            format_number_u64(busiest.count)
+        ));
+    }
+    let total = stats.top_authors.len();
+    for (i, author) in stats.top_authors.iter().enumerate() {
+        let followed = if author.followed == Some(true) {
+            " Followed."
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "Author {} of {total}: @{}, {}.{followed}",
+            i + 1,
+            author.screen_name,
+            count_of(author.count, "like")
+        ));
+    }
+    let overlap = &stats.following_overlap;
+    if overlap.resolved_authors > 0 {
+        lines.push(format!(
+            "Followed: {} of {} resolved liked authors, {:.1} percent, with {} likes.",
+            format_number_u64(overlap.followed_authors),
+            format_number_u64(overlap.resolved_authors),
+            overlap.followed_ratio,
+            format_number_u64(overlap.likes_to_followed)
+        ));
+    }
+    lines
+}
+
+/// Follower counts per archive snapshot and the longest-standing followers.
+#[must_use]
+pub fn followers(stats: &FollowerChurnStats) -> Vec<String> {
+    let mut lines = Vec::new();
+    heading(&mut lines, t(Msg::FollowerChurn));
+    if stats.snapshots.is_empty() {
+        lines.push("No follower snapshots yet. Each xf index run records one.".to_string());
+    }
+    for snapshot in &stats.snapshots {
+        let mut line = format!(
+            "Snapshot of {}: {}, {} gained, {} lost.",
+            spoken_date(snapshot.archive_generated_at),
+            count_of(snapshot.followers, "follower"),
+            format_number_u64(snapshot.gained),
+            format_number_u64(snapshot.lost)
+        );
+        if let Some(ratio) = snapshot.follow_back_ratio {
+            let _ = write!(line, " Follow-back: {ratio:.1} percent.");
+        }
+        lines.push(line);
+    }
+    if stats.snapshots.len() > 1 {
+        for follower in &stats.longest_standing {
+            lines.push(format!(
+                "Longest-standing follower: {}, since {}, in {} snapshots.",
+                follower
+                    .user_link
+                    .as_deref()
+                    .unwrap_or(&follower.account_id),
+                spoken_date(follower.since),
+                format_number_u64(follower.snapshots_present)
+            ));
+        }
+    }
+    lines
+}
+
+/// Writing style per year.
+#[must_use]
+pub fn style(stats: &StyleStats) -> Vec<String> {
+    let mut lines = Vec::new();
+    heading(&mut lines, t(Msg::WritingStyle));
+    for year in &stats.years {
+        lines.push(format!(
+            "{}: {}, {:.1} words per sentence, vocabulary {}, reading grade {:.1}, \
+             {:.1} percent questions, {:.1} percent exclamations.",
+            year.year,
+            count_of(year.tweet_count, "tweet"),
+            year.avg_sentence_length,
+            format_number_u64(year.vocabulary_size),
+            year.reading_grade,
+            year.question_ratio,
+            year.exclamation_ratio
+        ));
+    }
+    lines
+}
+
+/// Clients tweets were posted from, then client kinds per year.
+#[must_use]
+pub fn sources(stats: &SourceStats) -> Vec<String> {
+    let mut lines = Vec::new();
+    heading(&mut lines, t(Msg::Sources));
+    lines.push(format!(
+        "{} of {} record their client.",
+        count_of(stats.total_tweets - stats.unknown_count, "tweet"),
+        format_number_u64(stats.total_tweets)
+    ));
+    for source in &stats.sources {
+        let mut line = format!(
+            "{} ({}): {}, {:.1} percent",
+            source.source,
+            source.kind.label(),
+            count_of(source.count, "tweet"),
+            source.percentage
+        );
+        if let (Some(first), Some(last)) = (source.first_used, source.last_used) {
+            let _ = write!(
+                line,
+                ", from {} to {}",
+                spoken_date(first),
+                spoken_date(last)
+            );
+        }
+        line.push('.');
+        lines.push(line);
+    }
+    for year in &stats.years {
+        lines.push(format!(
+            "{}: {}, {} mobile, {} web, {} automated, {} other.",
+            year.year,
+            count_of(year.total, "tweet"),
+            format_number_u64(year.mobile),
+            format_number_u64(year.web),
+            format_number_u64(year.automated),
+            format_number_u64(year.other)
+        ));
+    }
+    lines
+}
+
+/// Hashtags and terms that co-occur with a target.
+#[must_use]
+pub fn cooccurrence(stats: &CooccurrenceStats) -> Vec<String> {
+    let mut lines = Vec::new();
+    heading(&mut lines, &format!("Co-occurrence with {}", stats.target));
+    lines.push(format!(
+        "Tweets: {} of {}.",
+        format_number_u64(stats.target_count),
+        format_number_u64(stats.total_tweets)
+    ));
+    if stats.target_count == 0 {
+        lines.push(format!(
+            "No tweets contain this {}.",
+            match stats.target_kind {
+                CooccurTarget::Hashtag => "hashtag",
+                CooccurTarget::Term => "term",
+            }
+        ));
+        return lines;
+    }
+    for (title, prefix, items) in [
+        ("Hashtag", "#", &stats.hashtags),
+        ("Term", "", &stats.terms),
+    ] {
+        if items.is_empty() {
+            lines.push(format!("{title}s: none."));
+        }
+        for item in items {
+            lines.push(format!(
+                "{title} {prefix}{}: together {} times, lift {:.2}, PMI {:.2}.",
+                item.item,
+                format_number_u64(item.count),
+                item.lift,
+                item.pmi
+            ));
+        }
+    }
+    lines
+}
+
+/// Two periods side by side, one metric per line.
+#[must_use]
+pub fn period_comparison(cmp: &PeriodComparison) -> Vec<String> {
+    let (a, b) = (&cmp.a, &cmp.b);
+    let mut lines = Vec::new();
+    heading(&mut lines, t(Msg::PeriodComparison));
+    let rows = [
+        (
+            "Tweets",
+            format_number_u64(a.tweet_count),
+            format_number_u64(b.tweet_count),
+        ),
+        (
+            "Active days",
+            format_number_u64(a.active_days),
+            format_number_u64(b.active_days),
+        ),
+        (
+            "Tweets per active day",
+            format!("{:.1}", a.avg_tweets_per_active_day),
+            format!("{:.1}", b.avg_tweets_per_active_day),
+        ),
+        (
+            "Average engagement",
+            format!("{:.1}", a.avg_engagement),
+            format!("{:.1}", b.avg_engagement),
+        ),
+        (
+            "Likes received",
+            format_number_u64(a.total_likes),
+            format_number_u64(b.total_likes),
+        ),
+        (
+            "Media",
+            format!("{:.1} percent", a.media_ratio),
+            format!("{:.1} percent", b.media_ratio),
+        ),
+        (
+            "Links",
+            format!("{:.1} percent", a.link_ratio),
+            format!("{:.1} percent", b.link_ratio),
+        ),
+        (
+            "Replies",
+            format!("{:.1} percent", a.reply_ratio),
+            format!("{:.1} percent", b.reply_ratio),
+        ),
+        (
+            "Most active hour",
+            format!("{:02}:00 UTC", a.most_active_hour),
+            format!("{:02}:00 UTC", b.most_active_hour),
+        ),
+    ];
+    for (label, a_value, b_value) in rows {
+        lines.push(format!(
+            "{label}: {a_value} in {}, {b_value} in {}.",
+            a.label, b.label
+        ));
+    }
+    if !cmp.hashtags_new.is_empty() {
+        lines.push(format!(
+            "Hashtags new in {}: {}.",
+            b.label,
+            cmp.hashtags_new.join(", ")
+        ));
+    }
+    if !cmp.hashtags_dropped.is_empty() {
+        lines.push(format!(
+            "Hashtags dropped since {}: {}.",
+            a.label,
+            cmp.hashtags_dropped.join(", ")
+        ));
+    }
+    lines
+}
+
+// ============================================================================
+// Doctor
+// ============================================================================
+
+const fn category_name(category: CheckCategory) -> &'static str {
+    match category {
+        CheckCategory::Archive => "archive",
+        CheckCategory::Database => "database",
+        CheckCategory::Index => "index",
+        CheckCategory::Performance => "performance",
+    }
+}
+
+const fn status_name(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Pass => "passed",
+        CheckStatus::Warning => "warning",
+        CheckStatus::Error => "error",
+    }
+}
+
+/// Health checks, their tally and the suggestions to act on.
+#[must_use]
+pub fn doctor(checks: &[HealthCheck], suggestions: &[String], runtime_ms: u64) -> Vec<String> {
+    let total = checks.len();
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    let mut lines = vec![format!("Health check. {total} checks.")];
+
+    for (i, check) in checks.iter().enumerate() {
+        // Some messages are tables spread over several lines
+        let message = check
+            .message
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("; ");
+        lines.push(format!(
+            "Check {} of {total}. Category: {}. Status: {}. {}: {}.",
+            i + 1,
+            category_name(check.category),
+            status_name(check.status),
+            check.name,
+            message.trim_end_matches('.')
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(format!(
+        "Summary: {} passed, {} warnings, {} errors, in {runtime_ms} milliseconds.",
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warning),
+        count(CheckStatus::Error)
+    ));
+    for suggestion in suggestions {
+        lines.push(format!("Suggestion: {suggestion}"));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats_analytics::ActivityShift;
+    use chrono::TimeZone;
+
+    fn result(result_type: SearchResultType, text: &str) -> SearchResult {
+        SearchResult {
+            result_type,
+            id: "123".to_string(),
+            text: text.to_string(),
+            created_at: Utc.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap(),
+            score: 1.0,
+            highlights: vec!["<b>hello</b>".to_string()],
+            match_ranges: Vec::new(),
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    fn has_no_decoration(lines: &[String]) -> bool {
+        lines.iter().all(|line| {
+            !line.contains('\x1b')
+                && !line.chars().any(|c| {
+                    matches!(c, '─' | '═' | '│' | '•' | '✓' | '⚠' | '✗' | '♥')
+                        || ('▁'..='█').contains(&c)
+                })
+        })
+    }
+
+    #[test]
+    fn search_results_are_numbered_and_labeled() {
+        let results = [
+            result(SearchResultType::Tweet, "hello\nworld"),
+            result(SearchResultType::DirectMessage, "hello there"),
+        ];
+        let lines = search_results("hello", &results);
+
+        assert_eq!(lines[0], "Found 2 results for \"hello\".");
+        assert!(
+            lines
+                .contains(&"Result 1 of 2. Type: tweet. Date: March 4, 2024. ID: 123.".to_string())
+        );
+        assert!(lines.contains(&"Text: hello world".to_string()));
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.starts_with("Result 2 of 2. Type: direct message."))
+        );
+        assert!(has_no_decoration(&lines));
+    }
+
+    #[test]
+    fn undated_results_omit_the_date() {
+        let mut undated = result(SearchResultType::Like, "liked");
+        undated.created_at = DateTime::<Utc>::UNIX_EPOCH;
+        let lines = search_results("liked", &[undated]);
+        assert_eq!(lines[2], "Result 1 of 1. Type: like. ID: 123.");
+    }
+
+    #[test]
+    fn temporal_describes_charts_in_words() {
+        let stats = TemporalStats {
+            daily_counts: Vec::new(),
+            hourly_distribution: [0; 24],
+            dow_distribution: [1, 2, 3, 4, 5, 6, 7],
+            longest_gap_days: 3,
+            longest_gap_start: NaiveDate::from_ymd_opt(2024, 1, 1),
+            longest_gap_end: NaiveDate::from_ymd_opt(2024, 1, 4),
+            most_active_day: NaiveDate::from_ymd_opt(2024, 2, 1),
+            most_active_day_count: 9,
+            most_active_hour: 7,
+            most_active_hour_count: 4,
+            avg_tweets_per_active_day: 1.5,
+            active_days_count: 20,
+            total_days_in_range: 40,
+            activity_shifts: vec![ActivityShift {
+                start: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(2024, 3, 9).unwrap(),
+                tweets: 12,
+                shift_hours: -8.0,
+                client: Some("Twitter for iPhone".to_string()),
+                mobile_share: 1.0,
+                client_changed: true,
+            }],
+        };
+        let lines = temporal(&stats);
+
+        assert!(lines.contains(&"Most active hour: 07:00 UTC, with 4 tweets.".to_string()));
+        assert!(lines.contains(
+            &"Longest gap: 3 days, from January 1, 2024 to January 4, 2024.".to_string()
+        ));
+        assert!(lines.iter().any(|l| l.contains("Sunday 1, Monday 2")));
+        assert!(
+            lines.contains(
+                &"Posting hours shifted about 8 hours east of usual from March 1, 2024 to \
+              March 9, 2024, over 12 tweets, mostly from Twitter for iPhone; likely travel."
+                    .to_string()
+            )
+        );
+        assert!(has_no_decoration(&lines));
+    }
+
+    #[test]
+    fn doctor_checks_are_numbered_with_status_words() {
+        let checks = vec![
+            HealthCheck {
+                category: CheckCategory::Database,
+                name: "Integrity".to_string(),
+                status: CheckStatus::Pass,
+                message: "ok".to_string(),
+                suggestion: None,
+            },
+            HealthCheck {
+                category: CheckCategory::Index,
+                name: "Index Directory".to_string(),
+                status: CheckStatus::Warning,
+                message: "No index found.".to_string(),
+                suggestion: Some("Run xf index".to_string()),
+            },
+        ];
+        let lines = doctor(&checks, &["Run xf index".to_string()], 12);
+
+        assert_eq!(
+            lines[2],
+            "Check 2 of 2. Category: index. Status: warning. Index Directory: No index found."
+        );
+        assert!(
+            lines.contains(
+                &"Summary: 1 passed, 1 warnings, 0 errors, in 12 milliseconds.".to_string()
+            )
+        );
+        assert_eq!(lines.last().unwrap(), "Suggestion: Run xf index");
+        assert!(has_no_decoration(&lines));
+    }
+
+    #[test]
+    fn counts_are_numbered() {
+        let items = vec![("#rust".to_string(), 3), ("#go".to_string(), 1)];
+        let lines = counts(Msg::TopHashtags, &items);
+        assert_eq!(lines[1], "Top Hashtags.");
+        assert_eq!(lines[2], "1. #rust: 3.");
+        assert_eq!(counts(Msg::Places, &[])[2], "None.");
+    }
+}
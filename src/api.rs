@@ -0,0 +1,450 @@
+//! Stable library API for other Rust tools (`client` feature).
+//!
+//! Everything here is plain data plus a small [`Client`] over an indexed
+//! archive, with no clap or terminal-output types. Build xf as a dependency
+//! with `default-features = false, features = ["client"]` to leave the CLI
+//! out entirely.
+//!
+//! ```no_run
+//! use xf::api::{Client, DocType, SearchRequest};
+//!
+//! let client = Client::open(xf::default_db_path(), xf::default_index_path())?;
+//! let request = SearchRequest {
+//!     types: vec![DocType::Tweet],
+//!     ..SearchRequest::new("rust")
+//! };
+//! for result in client.search(&request)? {
+//!     println!("{} {}", result.id, result.text);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::index_manifest::IndexManifest;
+use crate::model::{RawKind, SnapshotKind};
+use crate::parser::ArchiveParser;
+use crate::search::SearchEngine;
+use crate::storage::Storage;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+pub use crate::model::{
+    ArchiveStats, DirectMessage, Like, SearchResult, SearchResultType, Tweet, TweetMedia, TweetUrl,
+    UserMention,
+};
+pub use crate::search::DocType;
+
+/// A keyword search over the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchRequest {
+    /// Query in the same syntax as `xf search`
+    pub query: String,
+    /// Document types to search; empty searches all of them
+    pub types: Vec<DocType>,
+    /// Maximum number of results
+    pub limit: usize,
+    /// Only results created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only results created at or before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl SearchRequest {
+    /// Default number of results, matching `xf search`.
+    pub const DEFAULT_LIMIT: usize = 20;
+
+    /// A request for `query` across all document types.
+    #[must_use]
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            types: Vec::new(),
+            limit: Self::DEFAULT_LIMIT,
+            since: None,
+            until: None,
+        }
+    }
+}
+
+/// Documents stored by [`index_archive`], by type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IndexSummary {
+    pub tweets: usize,
+    pub likes: usize,
+    pub bookmarks: usize,
+    pub dms: usize,
+    pub grok_messages: usize,
+    pub followers: usize,
+    pub following: usize,
+    pub blocks: usize,
+    pub mutes: usize,
+    pub community_notes: usize,
+    pub lists: usize,
+}
+
+/// A step of [`index_archive_with_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexStage {
+    Tweets,
+    Likes,
+    Bookmarks,
+    Dms,
+    Grok,
+    Followers,
+    Following,
+    Blocks,
+    Mutes,
+    CommunityNotes,
+    Lists,
+    /// Committing the search index
+    Commit,
+}
+
+/// Progress reported by [`index_archive_with_progress`].
+///
+/// Serializes as `{"event": "started", "stage": "tweets"}` and
+/// `{"event": "finished", "stage": "tweets", "count": 1234}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum IndexProgress {
+    Started {
+        stage: IndexStage,
+    },
+    /// The stage stored `count` records
+    Finished {
+        stage: IndexStage,
+        count: usize,
+    },
+}
+
+/// Writer memory for [`index_archive`], matching `xf index`'s default.
+const INDEX_WRITER_BYTES: usize = 256_000_000;
+
+/// Index an extracted archive into `db_path` and `index_path`, like
+/// `xf index` with its defaults.
+///
+/// Semantic embeddings are not generated; run `xf embed` afterwards for
+/// semantic and hybrid search. Keyword search through [`Client`] works
+/// immediately.
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be parsed or the database or
+/// index cannot be written.
+pub fn index_archive(
+    archive_path: impl AsRef<Path>,
+    db_path: impl AsRef<Path>,
+    index_path: impl AsRef<Path>,
+) -> Result<IndexSummary> {
+    index_archive_with_progress(archive_path, db_path, index_path, |_| {})
+}
+
+/// [`index_archive`], calling `on_progress` as each stage starts and
+/// finishes.
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be parsed or the database or
+/// index cannot be written.
+#[allow(clippy::too_many_lines)]
+pub fn index_archive_with_progress(
+    archive_path: impl AsRef<Path>,
+    db_path: impl AsRef<Path>,
+    index_path: impl AsRef<Path>,
+    mut on_progress: impl FnMut(IndexProgress),
+) -> Result<IndexSummary> {
+    let (archive_path, db_path, index_path) =
+        (archive_path.as_ref(), db_path.as_ref(), index_path.as_ref());
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::create_dir_all(index_path)?;
+
+    let parser = ArchiveParser::new(archive_path);
+    let mut storage = Storage::open(db_path)?;
+    let search = SearchEngine::open(index_path)?;
+    let mut writer = search.writer(INDEX_WRITER_BYTES)?;
+
+    let manifest = parser.parse_manifest()?;
+    storage.store_archive_info(&manifest)?;
+    storage.store_profile_version(&manifest, parser.parse_profile()?.as_ref())?;
+    storage.store_screen_name_changes(&parser.parse_screen_name_changes()?)?;
+    let seen_at = manifest.generation_date;
+
+    // Run one stage, reporting its start and how many records it stored
+    let mut stage = |stage: IndexStage, work: &mut dyn FnMut() -> Result<usize>| {
+        on_progress(IndexProgress::Started { stage });
+        let count = work()?;
+        on_progress(IndexProgress::Finished { stage, count });
+        Ok::<_, anyhow::Error>(count)
+    };
+
+    let tweets = stage(IndexStage::Tweets, &mut || {
+        let tweets = parser.parse_tweets()?;
+        storage.store_tweets(&tweets)?;
+        storage.store_raw_json(RawKind::Tweet, &parser.parse_raw_records(RawKind::Tweet)?)?;
+        storage.record_first_seen("tweet", tweets.iter().map(|t| t.id.as_str()), seen_at)?;
+        search.index_tweets(&mut writer, &tweets)?;
+        Ok(tweets.len())
+    })?;
+
+    let likes = stage(IndexStage::Likes, &mut || {
+        let likes = parser.parse_likes()?;
+        storage.store_likes(&likes)?;
+        storage.store_raw_json(RawKind::Like, &parser.parse_raw_records(RawKind::Like)?)?;
+        storage.record_first_seen("like", likes.iter().map(|l| l.tweet_id.as_str()), seen_at)?;
+        search.index_likes(&mut writer, &likes)?;
+        Ok(likes.len())
+    })?;
+
+    let bookmarks = stage(IndexStage::Bookmarks, &mut || {
+        let bookmarks = parser.parse_bookmarks()?;
+        storage.store_bookmarks(&bookmarks)?;
+        search.index_bookmarks(&mut writer, &bookmarks)?;
+        Ok(bookmarks.len())
+    })?;
+
+    let dms = stage(IndexStage::Dms, &mut || {
+        let conversations = parser.parse_direct_messages()?;
+        storage.store_dm_conversations(&conversations)?;
+        storage.store_raw_json(
+            RawKind::DirectMessage,
+            &parser.parse_raw_records(RawKind::DirectMessage)?,
+        )?;
+        storage.record_first_seen(
+            "dm",
+            conversations
+                .iter()
+                .flat_map(|c| c.messages.iter().map(|m| m.id.as_str())),
+            seen_at,
+        )?;
+        search.index_dms(&mut writer, &conversations)?;
+        Ok(conversations.iter().map(|c| c.messages.len()).sum())
+    })?;
+
+    let grok_messages = stage(IndexStage::Grok, &mut || {
+        let grok_messages = parser.parse_grok_messages()?;
+        storage.store_grok_messages(&grok_messages)?;
+        search.index_grok_messages(&mut writer, &grok_messages)?;
+        Ok(grok_messages.len())
+    })?;
+
+    let snapshot_id = storage.begin_snapshot(&manifest)?;
+    let followers = stage(IndexStage::Followers, &mut || {
+        let followers = parser.parse_followers()?;
+        storage.store_followers(&followers)?;
+        storage.store_snapshot_accounts(
+            snapshot_id,
+            SnapshotKind::Follower,
+            followers.iter().map(|f| f.account_id.as_str()),
+        )?;
+        Ok(followers.len())
+    })?;
+    let following = stage(IndexStage::Following, &mut || {
+        let following = parser.parse_following()?;
+        storage.store_following(&following)?;
+        storage.store_snapshot_accounts(
+            snapshot_id,
+            SnapshotKind::Following,
+            following.iter().map(|f| f.account_id.as_str()),
+        )?;
+        Ok(following.len())
+    })?;
+
+    let blocks = stage(IndexStage::Blocks, &mut || {
+        let blocks = parser.parse_blocks()?;
+        storage.store_blocks(&blocks)?;
+        Ok(blocks.len())
+    })?;
+    let mutes = stage(IndexStage::Mutes, &mut || {
+        let mutes = parser.parse_mutes()?;
+        storage.store_mutes(&mutes)?;
+        Ok(mutes.len())
+    })?;
+
+    let community_notes = stage(IndexStage::CommunityNotes, &mut || {
+        let notes = parser.parse_community_notes()?;
+        storage.store_community_notes(&notes)
+    })?;
+    let lists = stage(IndexStage::Lists, &mut || {
+        let lists = parser.parse_lists()?;
+        storage.store_lists(&lists)
+    })?;
+
+    stage(IndexStage::Commit, &mut || {
+        storage.rebuild_account_handles()?;
+        storage.refresh_stats_summaries()?;
+        writer.commit()?;
+        search.reload()?;
+        IndexManifest::build(archive_path, &storage, &search)?.write(index_path)?;
+        Ok(0)
+    })?;
+
+    Ok(IndexSummary {
+        tweets,
+        likes,
+        bookmarks,
+        dms,
+        grok_messages,
+        followers,
+        following,
+        blocks,
+        mutes,
+        community_notes,
+        lists,
+    })
+}
+
+/// Run `search` for `request`, applying its date range.
+///
+/// Date filters apply after ranking, so the search is widened until enough
+/// results survive or the whole index has been considered.
+pub(crate) fn search_in_range(
+    request: &SearchRequest,
+    engine: &SearchEngine,
+    mut search: impl FnMut(Option<&[DocType]>, usize) -> Result<Vec<SearchResult>>,
+) -> Result<Vec<SearchResult>> {
+    let types = (!request.types.is_empty()).then_some(request.types.as_slice());
+    if request.since.is_none() && request.until.is_none() {
+        return search(types, request.limit);
+    }
+    let in_range = |result: &SearchResult| {
+        request.since.is_none_or(|since| result.created_at >= since)
+            && request.until.is_none_or(|until| result.created_at <= until)
+    };
+
+    let max_docs = usize::try_from(engine.doc_count()).unwrap_or(usize::MAX);
+    let mut fetch_limit = request.limit.min(max_docs);
+    loop {
+        let mut results = search(types, fetch_limit)?;
+        results.retain(in_range);
+        if results.len() >= request.limit || fetch_limit >= max_docs {
+            results.truncate(request.limit);
+            return Ok(results);
+        }
+        fetch_limit = fetch_limit
+            .saturating_mul(2)
+            .max(fetch_limit.saturating_add(1))
+            .min(max_docs);
+    }
+}
+
+/// Read-only access to an archive indexed by `xf index`.
+pub struct Client {
+    storage: Storage,
+    search: SearchEngine,
+}
+
+impl Client {
+    /// Open the database and search index written by `xf index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either cannot be opened.
+    pub fn open(db_path: impl AsRef<Path>, index_path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            storage: Storage::open(db_path)?,
+            search: SearchEngine::open(index_path)?,
+        })
+    }
+
+    /// Run a keyword search, best matches first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query cannot be parsed or the search fails.
+    pub fn search(&self, request: &SearchRequest) -> Result<Vec<SearchResult>> {
+        search_in_range(request, &self.search, |types, limit| {
+            self.search.search(&request.query, types, limit)
+        })
+    }
+
+    /// Record counts and date range of the archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statistics queries fail.
+    pub fn stats(&self) -> Result<ArchiveStats> {
+        self.storage.get_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tweet(id: &str, timestamp: i64) -> Tweet {
+        Tweet {
+            created_at: DateTime::from_timestamp(timestamp, 0).unwrap(),
+            ..Tweet::fixture(id, &format!("rust note {id}"))
+        }
+    }
+
+    #[test]
+    fn test_client_search_filters_dates_and_reports_stats() {
+        let dir = TempDir::new().unwrap();
+        let (db_path, index_path) = (dir.path().join("xf.db"), dir.path().join("index"));
+        let tweets: Vec<Tweet> = (0..5)
+            .map(|i| tweet(&i.to_string(), 1_600_000_000 + i * 86_400))
+            .collect();
+        let mut storage = Storage::open(&db_path).unwrap();
+        storage.store_tweets(&tweets).unwrap();
+        let engine = SearchEngine::open(&index_path).unwrap();
+        let mut writer = engine.writer(15_000_000).unwrap();
+        engine.index_tweets(&mut writer, &tweets).unwrap();
+        writer.commit().unwrap();
+        drop((storage, engine));
+
+        let client = Client::open(&db_path, &index_path).unwrap();
+        assert_eq!(client.search(&SearchRequest::new("rust")).unwrap().len(), 5);
+
+        let request = SearchRequest {
+            limit: 1,
+            since: Some(tweets[3].created_at),
+            ..SearchRequest::new("rust")
+        };
+        let results = client.search(&request).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].created_at >= tweets[3].created_at);
+
+        assert_eq!(client.stats().unwrap().tweets_count, 5);
+    }
+
+    #[test]
+    fn test_index_archive_then_search() {
+        let dir = TempDir::new().unwrap();
+        let data = dir.path().join("archive").join("data");
+        std::fs::create_dir_all(&data).unwrap();
+        std::fs::write(
+            data.join("manifest.js"),
+            r#"window.__THAR_CONFIG = {"userInfo": {"accountId": "1", "userName": "me"},
+                "archiveInfo": {"generationDate": "2024-01-01T00:00:00.000Z"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            data.join("tweets.js"),
+            r#"window.YTD.tweets.part0 = [{"tweet": {"id_str": "7",
+                "created_at": "Fri Jan 09 15:12:21 +0000 2026", "full_text": "hello from rust"}}]"#,
+        )
+        .unwrap();
+        let (db_path, index_path) = (dir.path().join("xf.db"), dir.path().join("index"));
+
+        let summary = index_archive(dir.path().join("archive"), &db_path, &index_path).unwrap();
+        assert_eq!(
+            summary,
+            IndexSummary {
+                tweets: 1,
+                ..IndexSummary::default()
+            }
+        );
+
+        let client = Client::open(&db_path, &index_path).unwrap();
+        assert_eq!(
+            client.search(&SearchRequest::new("rust")).unwrap()[0].id,
+            "7"
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! Facade for embedding xf in a desktop app (`client` feature).
+//!
+//! [`App`] holds one open archive: the database, the search index and a
+//! lazily loaded vector index for semantic search. It is `Send + Sync`, so
+//! a GUI shell such as Tauri can keep it in managed state and call it from
+//! command handlers on any thread. Results and progress events serialize to
+//! the same JSON as the CLI, ready to forward to a web view.
+//!
+//! ```no_run
+//! use xf::app::{App, SearchMode};
+//! use xf::api::SearchRequest;
+//!
+//! let app = App::index(
+//!     "twitter-archive",
+//!     xf::default_db_path(),
+//!     xf::default_index_path(),
+//!     |event| println!("{}", serde_json::to_string(&event).unwrap()),
+//! )?;
+//! let hits = app.search(&SearchRequest::new("rust"), SearchMode::Hybrid)?;
+//! let conversations = app.conversations(Some(50))?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::api::{IndexProgress, SearchRequest, index_archive_with_progress, search_in_range};
+use crate::audit::{self, AuditEvent};
+use crate::canonicalize::{StopwordMode, canonicalize_for_embedding_with};
+use crate::config::Config;
+use crate::embedder::Embedder;
+use crate::embedding_model;
+use crate::hybrid;
+use crate::model::{ArchiveStats, DirectMessage, DmConversationSummary, Like, SearchResult, Tweet};
+use crate::search::SearchEngine;
+use crate::storage::Storage;
+use crate::vector::{SemanticIndex, VectorIndexCache};
+use anyhow::{Result, bail};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
+pub use crate::api::{IndexStage, IndexSummary};
+pub use crate::hybrid::SearchMode;
+
+/// Command name recorded in the audit log for reads through [`App`].
+const AUDIT_COMMAND: &str = "app";
+
+/// An open archive.
+pub struct App {
+    db_path: PathBuf,
+    index_path: PathBuf,
+    // SQLite connections are not `Sync`; calls take turns on this one
+    storage: Mutex<Storage>,
+    search: SearchEngine,
+    vectors: VectorIndexCache,
+    stopwords: StopwordMode,
+    embedding_model: String,
+    embedder: OnceLock<Box<dyn Embedder>>,
+}
+
+impl App {
+    /// Open an archive indexed by `xf index` (or [`App::index`]).
+    ///
+    /// The vector index and the `embeddings.model` embedder are loaded on
+    /// the first semantic or hybrid search.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database or search index cannot be opened.
+    pub fn open(db_path: impl Into<PathBuf>, index_path: impl Into<PathBuf>) -> Result<Self> {
+        let (db_path, index_path) = (db_path.into(), index_path.into());
+        let config = Config::load();
+        Ok(Self {
+            storage: Mutex::new(Storage::open(&db_path)?),
+            search: SearchEngine::open(&index_path)?,
+            vectors: VectorIndexCache::new(),
+            stopwords: config.indexing.stopwords,
+            embedding_model: config.embeddings.model,
+            embedder: OnceLock::new(),
+            db_path,
+            index_path,
+        })
+    }
+
+    /// Index an extracted archive, reporting progress, then open it.
+    ///
+    /// Embeddings are not generated; until `xf embed` runs, semantic search
+    /// finds nothing and hybrid search returns keyword matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if indexing fails or the result cannot be opened.
+    pub fn index(
+        archive_path: impl AsRef<Path>,
+        db_path: impl Into<PathBuf>,
+        index_path: impl Into<PathBuf>,
+        on_progress: impl FnMut(IndexProgress),
+    ) -> Result<Self> {
+        let (db_path, index_path) = (db_path.into(), index_path.into());
+        index_archive_with_progress(archive_path, &db_path, &index_path, on_progress)?;
+        Self::open(db_path, index_path)
+    }
+
+    /// Database path this archive was opened from.
+    #[must_use]
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Search index path this archive was opened from.
+    #[must_use]
+    pub fn index_path(&self) -> &Path {
+        &self.index_path
+    }
+
+    /// The vector index cache, for hosts that want to preload it or show
+    /// whether embeddings exist.
+    #[must_use]
+    pub const fn vector_cache(&self) -> &VectorIndexCache {
+        &self.vectors
+    }
+
+    /// Search in `mode`, best matches first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query cannot be parsed, semantic search is
+    /// requested without embeddings, the configured embedding model cannot
+    /// be loaded or differs from the stored embeddings' model, or the search
+    /// fails.
+    pub fn search(&self, request: &SearchRequest, mode: SearchMode) -> Result<Vec<SearchResult>> {
+        let results = match mode {
+            SearchMode::Auto => {
+                let choice = self.choose_mode(request)?;
+                return self.search(request, choice.mode);
+            }
+            SearchMode::Lexical => search_in_range(request, &self.search, |types, limit| {
+                self.search.search(&request.query, types, limit)
+            })?,
+            SearchMode::Semantic => {
+                let vectors = self.load_vectors()?;
+                let types = (!request.types.is_empty()).then_some(request.types.as_slice());
+                if !self.vectors.has_embeddings_for(types) {
+                    bail!("No embeddings for these types; run `xf embed` first");
+                }
+                let embedder = self.embedder()?;
+                let canonical_query = self.canonical_query(&request.query);
+                search_in_range(request, &self.search, |types, limit| {
+                    hybrid::semantic_search(
+                        &self.search,
+                        vectors,
+                        embedder,
+                        &canonical_query,
+                        types,
+                        limit,
+                    )
+                })?
+            }
+            SearchMode::Hybrid => {
+                let vectors = self.load_vectors()?;
+                let embedder = self.embedder()?;
+                let canonical_query = self.canonical_query(&request.query);
+                search_in_range(request, &self.search, |types, limit| {
+                    hybrid::hybrid_search(
+                        &self.search,
+                        Some(vectors),
+                        embedder,
+                        &request.query,
+                        &canonical_query,
+                        types,
+                        limit,
+                    )
+                })?
+            }
+        };
+        audit::record_dm_results(&self.storage(), AUDIT_COMMAND, &request.query, &results)?;
+        Ok(results)
+    }
+
+    /// DM conversations, most recently active first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn conversations(&self, limit: Option<usize>) -> Result<Vec<DmConversationSummary>> {
+        let storage = self.storage();
+        let conversations = storage.get_dm_conversation_summaries(limit)?;
+        audit::record(
+            &storage,
+            AUDIT_COMMAND,
+            AuditEvent::ReadDms,
+            "conversations",
+            conversations.len(),
+        )?;
+        drop(storage);
+        Ok(conversations)
+    }
+
+    /// Messages of one conversation, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn conversation(&self, conversation_id: &str) -> Result<Vec<DirectMessage>> {
+        let storage = self.storage();
+        let messages = storage.get_conversation_messages(conversation_id)?;
+        audit::record(
+            &storage,
+            AUDIT_COMMAND,
+            AuditEvent::ReadDms,
+            conversation_id,
+            messages.len(),
+        )?;
+        drop(storage);
+        Ok(messages)
+    }
+
+    /// Record counts and date range of the archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statistics queries fail.
+    pub fn stats(&self) -> Result<ArchiveStats> {
+        self.storage().get_stats()
+    }
+
+    /// A tweet by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn tweet(&self, id: &str) -> Result<Option<Tweet>> {
+        self.storage().get_tweet(id)
+    }
+
+    /// The thread containing tweet `id`, from its earliest stored ancestor
+    /// through every reply, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn thread(&self, id: &str) -> Result<Vec<Tweet>> {
+        self.storage().get_tweet_thread(id)
+    }
+
+    /// The user's tweets, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn tweets(&self, limit: Option<usize>) -> Result<Vec<Tweet>> {
+        self.storage().get_all_tweets(limit)
+    }
+
+    /// Liked tweets, in archive order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn likes(&self, limit: Option<usize>) -> Result<Vec<Like>> {
+        self.storage().get_all_likes(limit)
+    }
+
+    /// Direct messages from every conversation, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn dms(&self, limit: Option<usize>) -> Result<Vec<DirectMessage>> {
+        let storage = self.storage();
+        let dms = storage.get_all_dms(limit)?;
+        audit::record(
+            &storage,
+            AUDIT_COMMAND,
+            AuditEvent::ReadDms,
+            "dms",
+            dms.len(),
+        )?;
+        drop(storage);
+        Ok(dms)
+    }
+
+    /// IDs of documents hidden with `xf hide`, by document type.
+    ///
+    /// Search and lookups return hidden documents; hosts that honor
+    /// `xf hide` drop them with this.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn hidden_ids(&self) -> Result<HashMap<String, HashSet<String>>> {
+        self.storage().get_hidden_ids()
+    }
+
+    fn storage(&self) -> MutexGuard<'_, Storage> {
+        // A panic mid-query leaves nothing half-written in the connection
+        self.storage.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// [`App::search`] without documents hidden with `xf hide`, still
+    /// returning up to `request.limit` results.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same reasons as [`App::search`].
+    pub fn search_visible(
+        &self,
+        mut request: SearchRequest,
+        mode: SearchMode,
+    ) -> Result<Vec<SearchResult>> {
+        // Over-fetch by the hidden count so dropping them still fills the page
+        let hidden = self.hidden_ids()?;
+        let limit = request.limit;
+        request.limit += hidden.values().map(HashSet::len).sum::<usize>();
+        let mut results = self.search(&request, mode)?;
+        results.retain(|result| {
+            hidden
+                .get(&result.result_type.to_string())
+                .is_none_or(|ids| !ids.contains(&result.id))
+        });
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// The mode [`SearchMode::Auto`] picks for `request`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vector index cannot be loaded or a term
+    /// lookup fails.
+    pub fn choose_mode(&self, request: &SearchRequest) -> Result<hybrid::AutoModeChoice> {
+        self.load_vectors()?;
+        let types = (!request.types.is_empty()).then_some(request.types.as_slice());
+        hybrid::choose_search_mode(
+            &request.query,
+            self.vectors.has_embeddings_for(types),
+            |term| Ok(!self.search.search(term, types, 1)?.is_empty()),
+        )
+    }
+
+    fn load_vectors(&self) -> Result<&SemanticIndex> {
+        let storage = self.storage();
+        let (index, _loaded_now) = self
+            .vectors
+            .load(&storage, &self.db_path, &self.index_path)?;
+        Ok(index)
+    }
+
+    /// The `embeddings.model` embedder, loaded on first use and checked
+    /// against the model the stored embeddings were built with.
+    fn embedder(&self) -> Result<&dyn Embedder> {
+        if let Some(embedder) = self.embedder.get() {
+            return Ok(embedder.as_ref());
+        }
+        let embedder = embedding_model::load_embedder(&self.embedding_model)?;
+        let stored = self.storage().embedding_model()?;
+        if let Some(reason) = stored.and_then(|(model, dimension)| {
+            embedding_model::incompatibility(&model, dimension, embedder.as_ref())
+        }) {
+            bail!("{reason} Run `xf embed` to re-embed with the configured model.");
+        }
+        Ok(self.embedder.get_or_init(|| embedder).as_ref())
+    }
+
+    fn canonical_query(&self, query: &str) -> String {
+        canonicalize_for_embedding_with(query, self.stopwords)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const fn assert_send_sync<T: Send + Sync>() {}
+    const _: () = assert_send_sync::<App>();
+
+    #[test]
+    fn test_index_reports_progress_then_searches_and_lists() {
+        let dir = TempDir::new().unwrap();
+        let data = dir.path().join("archive").join("data");
+        std::fs::create_dir_all(&data).unwrap();
+        std::fs::write(
+            data.join("manifest.js"),
+            r#"window.__THAR_CONFIG = {"userInfo": {"accountId": "1", "userName": "me"},
+                "archiveInfo": {"generationDate": "2024-01-01T00:00:00.000Z"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            data.join("tweets.js"),
+            r#"window.YTD.tweets.part0 = [{"tweet": {"id_str": "7",
+                "created_at": "Fri Jan 09 15:12:21 +0000 2026", "full_text": "hello from rust"}}]"#,
+        )
+        .unwrap();
+
+        let mut events = Vec::new();
+        let app = App::index(
+            dir.path().join("archive"),
+            dir.path().join("xf.db"),
+            dir.path().join("index"),
+            |event| events.push(event),
+        )
+        .unwrap();
+        assert_eq!(
+            events[..2],
+            [
+                IndexProgress::Started {
+                    stage: IndexStage::Tweets
+                },
+                IndexProgress::Finished {
+                    stage: IndexStage::Tweets,
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(
+            events.last(),
+            Some(&IndexProgress::Finished {
+                stage: IndexStage::Commit,
+                count: 0
+            })
+        );
+
+        let request = SearchRequest::new("rust");
+        assert_eq!(
+            app.search(&request, SearchMode::Lexical).unwrap()[0].id,
+            "7"
+        );
+        // No embeddings yet: hybrid falls back to keyword matches
+        assert_eq!(app.search(&request, SearchMode::Hybrid).unwrap()[0].id, "7");
+        assert!(app.search(&request, SearchMode::Semantic).is_err());
+        assert_eq!(app.choose_mode(&request).unwrap().mode, SearchMode::Lexical);
+        assert_eq!(app.search(&request, SearchMode::Auto).unwrap()[0].id, "7");
+        assert!(app.conversations(None).unwrap().is_empty());
+        assert_eq!(app.stats().unwrap().tweets_count, 1);
+        assert_eq!(
+            app.tweet("7").unwrap().unwrap().full_text,
+            "hello from rust"
+        );
+        assert_eq!(app.thread("7").unwrap().len(), 1);
+        assert_eq!(app.tweets(None).unwrap().len(), 1);
+        assert!(app.likes(Some(10)).unwrap().is_empty());
+        assert!(app.hidden_ids().unwrap().is_empty());
+    }
+}
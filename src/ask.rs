@@ -0,0 +1,222 @@
+//! Retrieval-augmented answers over the archive (`xf ask`).
+//!
+//! Relevant documents are retrieved with hybrid search, packed into a
+//! numbered context block within a character budget, and sent to the
+//! configured LLM with instructions to answer only from that context and
+//! cite excerpts as `[n]`. Citations map back to document IDs and dates.
+
+use crate::llm::ChatMessage;
+use crate::model::{SearchResult, SearchResultType};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// Longest excerpt taken from a single document, in characters.
+const MAX_EXCERPT_CHARS: usize = 600;
+
+const SYSTEM_PROMPT: &str = "You answer questions about the user's own X (Twitter) archive. \
+Use only the numbered archive excerpts provided. Cite the excerpts that support each \
+statement with their numbers in square brackets, like [2] or [1][4]. Mention dates when \
+they matter. If the excerpts do not answer the question, say so plainly.";
+
+/// A numbered archive excerpt that the answer may cite.
+#[derive(Debug, Clone, Serialize)]
+pub struct Citation {
+    pub number: usize,
+    pub id: String,
+    #[serde(rename = "type")]
+    pub result_type: SearchResultType,
+    pub created_at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// Context block sent to the model plus the excerpts it contains.
+#[derive(Debug, Clone)]
+pub struct AskContext {
+    pub block: String,
+    pub citations: Vec<Citation>,
+}
+
+/// Final answer with the excerpts it cites.
+#[derive(Debug, Clone, Serialize)]
+pub struct AskAnswer {
+    pub question: String,
+    pub answer: String,
+    pub citations: Vec<Citation>,
+}
+
+/// Pack search results into a numbered context block.
+///
+/// Results are taken in rank order until `max_chars` is reached; the first
+/// result is always included so the model has something to work with.
+#[must_use]
+pub fn build_context(results: &[SearchResult], max_chars: usize) -> AskContext {
+    let mut block = String::new();
+    let mut citations = Vec::new();
+
+    for result in results {
+        let number = citations.len() + 1;
+        let excerpt = excerpt(&result.text);
+        let mut entry = String::new();
+        let _ = writeln!(
+            entry,
+            "[{number}] {} {} ({}): {excerpt}",
+            result.result_type,
+            result.id,
+            result.created_at.format("%Y-%m-%d")
+        );
+        if !citations.is_empty() && block.len() + entry.len() > max_chars {
+            break;
+        }
+        block.push_str(&entry);
+        citations.push(Citation {
+            number,
+            id: result.id.clone(),
+            result_type: result.result_type,
+            created_at: result.created_at,
+            text: excerpt,
+        });
+    }
+
+    AskContext { block, citations }
+}
+
+/// Build the chat messages for a question over `context`.
+#[must_use]
+pub fn build_messages(question: &str, context: &AskContext) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage::system(SYSTEM_PROMPT),
+        ChatMessage::user(format!(
+            "Archive excerpts:\n{}\nQuestion: {question}",
+            context.block
+        )),
+    ]
+}
+
+/// Attach the excerpts an answer cites (all excerpts if it cites none).
+#[must_use]
+pub fn into_answer(question: &str, answer: String, context: AskContext) -> AskAnswer {
+    let cited = cited_numbers(&answer);
+    let citations = if cited.is_empty() {
+        context.citations
+    } else {
+        context
+            .citations
+            .into_iter()
+            .filter(|c| cited.contains(&c.number))
+            .collect()
+    };
+    AskAnswer {
+        question: question.to_string(),
+        answer,
+        citations,
+    }
+}
+
+/// Extract citation numbers such as `[3]` or `[1, 4]`, sorted and deduplicated.
+#[must_use]
+pub fn cited_numbers(answer: &str) -> Vec<usize> {
+    let mut numbers = Vec::new();
+    let mut rest = answer;
+    while let Some(start) = rest.find('[') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let inner = &rest[..end];
+        let parsed: Option<Vec<usize>> = inner
+            .split(',')
+            .map(|part| part.trim().parse().ok())
+            .collect();
+        if let Some(parsed) = parsed {
+            numbers.extend(parsed);
+        }
+        rest = &rest[end + 1..];
+    }
+    numbers.sort_unstable();
+    numbers.dedup();
+    numbers
+}
+
+fn excerpt(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= MAX_EXCERPT_CHARS {
+        return flat;
+    }
+    let mut cut: String = flat.chars().take(MAX_EXCERPT_CHARS).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn result(id: &str, text: &str) -> SearchResult {
+        SearchResult {
+            result_type: SearchResultType::Tweet,
+            id: id.to_string(),
+            text: text.to_string(),
+            created_at: Utc.with_ymd_and_hms(2021, 3, 4, 12, 0, 0).unwrap(),
+            score: 1.0,
+            highlights: vec![],
+            match_ranges: Vec::new(),
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_build_context_numbers_and_budget() {
+        let results = vec![
+            result("1", "async rust\nis hard"),
+            result("2", "tokio is great"),
+            result("3", &"x".repeat(500)),
+        ];
+        let context = build_context(&results, 120);
+        assert_eq!(context.citations.len(), 2);
+        assert!(
+            context
+                .block
+                .starts_with("[1] tweet 1 (2021-03-04): async rust is hard\n")
+        );
+        assert!(
+            context
+                .block
+                .contains("[2] tweet 2 (2021-03-04): tokio is great")
+        );
+
+        // The first result is kept even when it alone exceeds the budget
+        assert_eq!(build_context(&results[2..], 10).citations.len(), 1);
+    }
+
+    #[test]
+    fn test_cited_numbers() {
+        assert_eq!(
+            cited_numbers("Yes [2], and later [1, 3][2]. See [note]."),
+            vec![1, 2, 3]
+        );
+        assert!(cited_numbers("No citations here").is_empty());
+    }
+
+    #[test]
+    fn test_into_answer_keeps_cited_sources() {
+        let results = vec![result("1", "a"), result("2", "b"), result("3", "c")];
+        let context = build_context(&results, 10_000);
+        let answer = into_answer("q?", "Only b [2].".to_string(), context.clone());
+        let ids: Vec<&str> = answer.citations.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["2"]);
+
+        let uncited = into_answer("q?", "Not sure.".to_string(), context);
+        assert_eq!(uncited.citations.len(), 3);
+    }
+
+    #[test]
+    fn test_build_messages_includes_question_and_context() {
+        let context = build_context(&[result("9", "hello")], 1000);
+        let messages = build_messages("what did I say?", &context);
+        assert_eq!(messages[0].role, "system");
+        assert!(messages[1].content.contains("[1] tweet 9"));
+        assert!(messages[1].content.ends_with("Question: what did I say?"));
+    }
+}
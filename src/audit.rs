@@ -0,0 +1,76 @@
+//! Optional audit log of data access (`xf audit`).
+//!
+//! With `audit.enabled` set, commands that show DMs or export data append an
+//! entry to the database's `audit_log` table. Nothing is recorded by default.
+
+use crate::config::Config;
+use crate::model::{SearchResult, SearchResultType};
+use crate::storage::Storage;
+use anyhow::Result;
+use std::sync::OnceLock;
+
+/// Kinds of access the audit log records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEvent {
+    /// Direct messages were shown or sent to a model.
+    ReadDms,
+    /// Data was exported.
+    Export,
+}
+
+impl AuditEvent {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadDms => "read_dms",
+            Self::Export => "export",
+        }
+    }
+}
+
+/// Whether auditing is enabled in the loaded configuration.
+#[must_use]
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| Config::load().audit.enabled)
+}
+
+/// Record an access if auditing is enabled.
+///
+/// # Errors
+///
+/// Returns an error if the entry cannot be written; commands fail rather
+/// than access data without a record.
+pub fn record(
+    storage: &Storage,
+    command: &str,
+    event: AuditEvent,
+    detail: &str,
+    count: usize,
+) -> Result<()> {
+    if !enabled() {
+        return Ok(());
+    }
+    storage.record_audit(command, event.as_str(), detail, count)
+}
+
+/// Record the DMs among `results`, if any.
+///
+/// # Errors
+///
+/// Returns an error if the entry cannot be written.
+pub fn record_dm_results(
+    storage: &Storage,
+    command: &str,
+    detail: &str,
+    results: &[SearchResult],
+) -> Result<()> {
+    let dms = results
+        .iter()
+        .filter(|r| r.result_type == SearchResultType::DirectMessage)
+        .count();
+    if dms == 0 {
+        return Ok(());
+    }
+    record(storage, command, AuditEvent::ReadDms, detail, dms)
+}
@@ -0,0 +1,203 @@
+//! iCalendar export of tweet activity (`xf export calendar`).
+//!
+//! Each day with tweets becomes an all-day event summarizing how many were
+//! posted and which got the most engagement. Tweets at or above an
+//! engagement threshold can also be added as individual events. Days are
+//! UTC dates.
+
+use crate::clip_to_width;
+use crate::model::Tweet;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Longest line, in bytes, before folding (RFC 5545 section 3.1).
+const MAX_LINE_BYTES: usize = 75;
+
+/// Columns of tweet text used as an individual event's title.
+const TITLE_WIDTH: usize = 60;
+
+/// Render `tweets` as an iCalendar file.
+///
+/// With `min_engagement`, tweets whose likes plus retweets reach it also get
+/// their own 15-minute event at the time they were posted.
+#[must_use]
+pub fn render(tweets: &[Tweet], min_engagement: Option<i64>, now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//xf//X archive activity//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:X archive activity".to_string(),
+    ];
+
+    let mut days: BTreeMap<NaiveDate, Vec<&Tweet>> = BTreeMap::new();
+    for tweet in tweets {
+        days.entry(tweet.created_at.date_naive())
+            .or_default()
+            .push(tweet);
+    }
+    for (day, tweets) in &days {
+        let top = tweets
+            .iter()
+            .max_by_key(|t| (engagement(t), std::cmp::Reverse(t.created_at)))
+            .copied();
+        let count = tweets.len();
+        let mut description = String::new();
+        if let Some(top) = top {
+            let _ = write!(
+                description,
+                "Most engaged: {}\n{}\n{}",
+                top.full_text,
+                engagement_line(top),
+                status_url(top)
+            );
+        }
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:xf-day-{}@xf", day.format("%Y%m%d")),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART;VALUE=DATE:{}", day.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (*day + Duration::days(1)).format("%Y%m%d")
+            ),
+            format!(
+                "SUMMARY:{}",
+                escape_text(&format!(
+                    "{count} tweet{}",
+                    if count == 1 { "" } else { "s" }
+                ))
+            ),
+            format!("DESCRIPTION:{}", escape_text(&description)),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    if let Some(min_engagement) = min_engagement {
+        let mut notable: Vec<&Tweet> = tweets
+            .iter()
+            .filter(|t| engagement(t) >= min_engagement)
+            .collect();
+        notable.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        for tweet in notable {
+            let title = clip_to_width(&tweet.full_text, TITLE_WIDTH);
+            let title = if title.len() < tweet.full_text.len() {
+                format!("{}…", title.trim_end())
+            } else {
+                title.to_string()
+            };
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:xf-tweet-{}@xf", tweet.id),
+                format!("DTSTAMP:{stamp}"),
+                format!("DTSTART:{}", tweet.created_at.format("%Y%m%dT%H%M%SZ")),
+                "DURATION:PT15M".to_string(),
+                format!("SUMMARY:{}", escape_text(&title)),
+                format!(
+                    "DESCRIPTION:{}",
+                    escape_text(&format!("{}\n{}", tweet.full_text, engagement_line(tweet)))
+                ),
+                format!("URL:{}", status_url(tweet)),
+                "TRANSP:TRANSPARENT".to_string(),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+const fn engagement(tweet: &Tweet) -> i64 {
+    tweet.favorite_count + tweet.retweet_count
+}
+
+fn engagement_line(tweet: &Tweet) -> String {
+    format!(
+        "{} likes, {} retweets",
+        tweet.favorite_count, tweet.retweet_count
+    )
+}
+
+fn status_url(tweet: &Tweet) -> String {
+    format!("https://x.com/i/status/{}", tweet.id)
+}
+
+/// Escape a TEXT property value.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into CRLF-terminated lines of at most 75 bytes,
+/// without splitting a UTF-8 character.
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > MAX_LINE_BYTES {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn tweet(id: &str, day: u32, hour: u32, text: &str, likes: i64) -> Tweet {
+        Tweet {
+            created_at: Utc.with_ymd_and_hms(2021, 3, day, hour, 0, 0).unwrap(),
+            favorite_count: likes,
+            ..Tweet::fixture(id, text)
+        }
+    }
+
+    #[test]
+    fn test_daily_events_and_notable_tweets() {
+        let tweets = vec![
+            tweet("1", 4, 9, "morning, all", 2),
+            tweet("2", 4, 18, "evening; big news", 50),
+            tweet("3", 5, 12, "next day", 0),
+        ];
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let ics = render(&tweets, Some(10), now);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert!(ics.contains("DTSTART;VALUE=DATE:20210304\r\nDTEND;VALUE=DATE:20210305\r\n"));
+        assert!(ics.contains("SUMMARY:2 tweets\r\n"));
+        assert!(ics.contains("SUMMARY:1 tweet\r\n"));
+        assert!(ics.contains("DESCRIPTION:Most engaged: evening\\; big news\\n50 likes\\,"));
+        assert!(ics.contains("UID:xf-tweet-2@xf\r\n"));
+        assert!(ics.contains("DTSTART:20210304T180000Z\r\n"));
+
+        let daily_only = render(&tweets, None, now);
+        assert_eq!(daily_only.matches("BEGIN:VEVENT").count(), 2);
+    }
+
+    #[test]
+    fn test_fold_keeps_lines_short_and_characters_whole() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(80));
+        let folded = fold(&line);
+        assert!(folded.split("\r\n").all(|l| l.len() <= MAX_LINE_BYTES));
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), line);
+    }
+}
@@ -5,12 +5,19 @@
 //!
 //! # Pipeline
 //!
-//! 1. **Unicode NFC normalization** - Ensures consistent character representation
-//! 2. **Markdown stripping** - Removes formatting syntax (bold, italic, links)
-//! 3. **Code block collapsing** - Keeps first 20 + last 10 lines of code
-//! 4. **Whitespace normalization** - Collapses runs, trims edges
-//! 5. **Low-signal filtering** - Removes short acknowledgments ("OK", "Done")
-//! 6. **Truncation** - Limits to 2000 characters
+//! 1. **Unicode NFKC normalization** - Ensures consistent character representation
+//!    (composed accents, full-width forms, ligatures)
+//! 2. **Diacritic folding** - Drops Latin-script accents so "café" and "cafe" match;
+//!    other scripts' combining signs are kept
+//! 3. **Entity placeholders** - URLs become `<url>`, @mentions become `<user>`, and
+//!    hashtags keep their text without the `#`
+//! 4. **Markdown stripping** - Removes formatting syntax (bold, italic, links)
+//! 5. **Code block collapsing** - Keeps first 20 + last 10 lines of code
+//! 6. **Whitespace normalization** - Collapses runs, trims edges
+//! 7. **Stopword removal** (optional) - Drops common English/Spanish/French/
+//!    Portuguese/German function words, see [`StopwordMode`]
+//! 8. **Low-signal filtering** - Removes short acknowledgments ("OK", "Done", "Gracias")
+//! 9. **Truncation** - Limits to 2000 characters
 //!
 //! # Usage
 //!
@@ -23,6 +30,7 @@
 //! ```
 
 use ring::digest::{self, SHA256};
+use serde::{Deserialize, Serialize};
 use unicode_normalization::UnicodeNormalization;
 
 /// Maximum characters to keep after canonicalization.
@@ -54,27 +62,109 @@ const LOW_SIGNAL_CONTENT: &[&str] = &[
     "lgtm",
     "👍",
     "✓",
+    "gracias",
+    "gracias.",
+    "muchas gracias",
+    "vale",
+    "si",
+    "listo",
+    "de nada",
+    "merci",
+    "obrigado",
+    "obrigada",
+    "danke",
 ];
 
+/// Common function words dropped when [`StopwordMode::Remove`] is set.
+///
+/// Entries are lowercase and accent-folded to match folded text.
+const STOPWORDS: &[&str] = &[
+    // English
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "i", "in",
+    "is", "it", "its", "me", "my", "not", "of", "on", "or", "so", "that", "the", "this", "to",
+    "was", "we", "were", "with", "you", "your", // Spanish
+    "al", "como", "con", "del", "el", "ella", "en", "es", "esta", "este", "la", "las", "le", "lo",
+    "los", "mas", "mi", "muy", "para", "pero", "por", "que", "se", "sin", "su", "sus", "un", "una",
+    "uno", "y", "ya", "yo", // French
+    "au", "aux", "ce", "ces", "dans", "des", "du", "et", "il", "je", "les", "mais", "ne", "nous",
+    "ou", "pas", "pour", "qui", "sur", "une", "vous", // Portuguese
+    "ao", "da", "das", "do", "dos", "em", "na", "no", "nos", "os", "um", "uma",
+    // German
+    "auf", "das", "dem", "den", "der", "die", "ein", "eine", "ich", "ist", "mit", "nicht", "sie",
+    "und", "von", "zu",
+];
+
+/// How stopwords are handled during canonicalization.
+///
+/// Changing this changes the canonical text (and content hashes), so stored
+/// embeddings are refreshed on the next `xf index`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StopwordMode {
+    /// Keep all words (default).
+    #[default]
+    Keep,
+    /// Drop common function words in the supported languages.
+    Remove,
+}
+
+impl std::str::FromStr for StopwordMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "keep" => Ok(Self::Keep),
+            "remove" => Ok(Self::Remove),
+            other => Err(format!(
+                "unknown stopword mode '{other}' (expected keep or remove)"
+            )),
+        }
+    }
+}
+
 /// Canonicalize text for embedding.
 ///
 /// Applies the full preprocessing pipeline to ensure consistent,
-/// deterministic embeddings.
+/// deterministic embeddings. Stopwords are kept.
 #[must_use]
 pub fn canonicalize_for_embedding(text: &str) -> String {
-    // Step 1: Unicode NFC normalization
-    let normalized: String = text.nfc().collect();
+    canonicalize_for_embedding_with(text, StopwordMode::Keep)
+}
 
-    // Step 2: Strip markdown and collapse code blocks
-    let stripped = strip_markdown_and_code(&normalized);
+/// Canonicalize text for embedding with explicit stopword handling.
+///
+/// Documents and queries must use the same mode for their embeddings to be
+/// comparable.
+#[must_use]
+pub fn canonicalize_for_embedding_with(text: &str, stopwords: StopwordMode) -> String {
+    // Step 1: Unicode NFKC normalization
+    let normalized: String = text.nfkc().collect();
 
-    // Step 3: Normalize whitespace
-    let whitespace_normalized = normalize_whitespace(&stripped);
+    // Step 2: Fold Latin-script diacritics
+    let folded = fold_diacritics(&normalized);
 
-    // Step 4: Filter low-signal content
+    // Step 3: Replace URLs and mentions, unwrap hashtags
+    let placeheld: String = folded
+        .lines()
+        .map(replace_entities)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Step 4: Strip markdown and collapse code blocks
+    let stripped = strip_markdown_and_code(&placeheld);
+
+    // Step 5: Normalize whitespace
+    let mut whitespace_normalized = normalize_whitespace(&stripped);
+
+    // Step 6: Optionally drop stopwords
+    if stopwords == StopwordMode::Remove {
+        whitespace_normalized = remove_stopwords(&whitespace_normalized);
+    }
+
+    // Step 7: Filter low-signal content
     let filtered = filter_low_signal(&whitespace_normalized);
 
-    // Step 5: Truncate to max length
+    // Step 8: Truncate to max length
     truncate_to_chars(&filtered, MAX_EMBED_CHARS)
 }
 
@@ -94,7 +184,7 @@ pub fn content_hash_hex(text: &str) -> String {
     hex_encode(&hash)
 }
 
-fn hex_encode(bytes: &[u8]) -> String {
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
     use std::fmt::Write;
 
     let mut out = String::with_capacity(bytes.len() * 2);
@@ -104,6 +194,92 @@ fn hex_encode(bytes: &[u8]) -> String {
     out
 }
 
+/// Remove combining diacritical marks (U+0300–U+036F) after decomposition.
+///
+/// Only the Latin/Greek/Cyrillic accent block is dropped; combining signs of
+/// other scripts (Devanagari vowel signs, Thai tone marks, ...) carry meaning
+/// and are kept.
+fn fold_diacritics(text: &str) -> String {
+    text.nfd()
+        .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+        .nfc()
+        .collect()
+}
+
+/// Replace URLs with `<url>` and @mentions with `<user>`, and strip `#` from hashtags.
+fn replace_entities(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut prev: Option<char> = None;
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        let at_boundary = prev.is_none_or(|p| !p.is_alphanumeric() && p != '_');
+
+        if at_boundary && is_url_start(rest) {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            // Keep trailing punctuation (closing parens, commas) outside the URL
+            let url_len = rest[..end]
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', ')'])
+                .len();
+            result.push_str("<url>");
+            prev = Some('>');
+            rest = &rest[url_len..];
+            continue;
+        }
+
+        if at_boundary && (c == '@' || c == '#') {
+            let body_len = rest[1..]
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                .unwrap_or(rest.len() - 1);
+            if body_len > 0 {
+                let body = &rest[1..=body_len];
+                if c == '@' {
+                    result.push_str("<user>");
+                } else {
+                    // Underscores would be split into spaces by markdown stripping anyway
+                    result.push_str(body);
+                }
+                prev = body.chars().last();
+                rest = &rest[1 + body_len..];
+                continue;
+            }
+        }
+
+        result.push(c);
+        prev = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    result
+}
+
+fn is_url_start(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    ["http://", "https://", "www."].iter().any(|prefix| {
+        bytes
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix.as_bytes()))
+    })
+}
+
+/// Whether a lowercase word is a stopword in any supported language.
+pub(crate) fn is_stopword(word: &str) -> bool {
+    STOPWORDS.contains(&word)
+}
+
+/// Drop stopwords, comparing on the lowercase word without edge punctuation.
+fn remove_stopwords(text: &str) -> String {
+    text.split(' ')
+        .filter(|word| {
+            let core = word
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            !is_stopword(&core)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Strip markdown formatting and collapse code blocks.
 fn strip_markdown_and_code(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
@@ -473,6 +649,76 @@ Thanks for reading!
         assert!(result.contains("code"));
     }
 
+    #[test]
+    fn test_accent_folding_multilingual() {
+        // Spanish/French/Portuguese accents fold; composed and decomposed agree
+        assert_eq!(
+            canonicalize_for_embedding("Mañana café en São Paulo, très bien"),
+            "Manana cafe en Sao Paulo, tres bien"
+        );
+        assert_eq!(
+            canonicalize_for_embedding("cafe\u{0301}"),
+            canonicalize_for_embedding("café")
+        );
+        // Full-width forms and ligatures normalize via NFKC
+        assert_eq!(canonicalize_for_embedding("ＡＢＣ ﬁnal"), "ABC final");
+        // Non-Latin combining signs are preserved
+        assert_eq!(canonicalize_for_embedding("हिन्दी भाषा"), "हिन्दी भाषा");
+        assert_eq!(
+            canonicalize_for_embedding("日本語のテキスト"),
+            "日本語のテキスト"
+        );
+    }
+
+    #[test]
+    fn test_entity_placeholders() {
+        let result = canonicalize_for_embedding(
+            "¡Hola @maria_lopez! Mira https://t.co/AbC123, #Programación y #rust_lang (www.example.com)",
+        );
+        assert_eq!(
+            result,
+            "¡Hola <user>! Mira <url>, Programacion y rust lang (<url>)"
+        );
+
+        // Emails, C#, and markdown headers are not entities
+        assert_eq!(
+            canonicalize_for_embedding("mail me@example.org about C#"),
+            "mail me@example.org about C#"
+        );
+        assert_eq!(canonicalize_for_embedding("# Título"), "Titulo");
+        // A hashtag opening a line is treated like any other hashtag
+        assert_eq!(
+            canonicalize_for_embedding("#rust es genial"),
+            "rust es genial"
+        );
+    }
+
+    #[test]
+    fn test_stopword_modes() {
+        let text = "El gato and the dog están en la casa";
+        assert_eq!(
+            canonicalize_for_embedding(text),
+            "El gato and the dog estan en la casa"
+        );
+        assert_eq!(
+            canonicalize_for_embedding_with(text, StopwordMode::Remove),
+            "gato dog estan casa"
+        );
+        assert_eq!("remove".parse::<StopwordMode>(), Ok(StopwordMode::Remove));
+        assert!("sometimes".parse::<StopwordMode>().is_err());
+    }
+
+    #[test]
+    fn test_spanish_low_signal() {
+        for text in ["Gracias", "¡Gracias!", "Sí", "vale"] {
+            let result = canonicalize_for_embedding(text);
+            assert!(
+                result.is_empty() || text == "¡Gracias!",
+                "Expected '{text}' to be filtered"
+            );
+        }
+    }
+
     #[test]
     fn test_empty_input() {
         let result = canonicalize_for_embedding("");
@@ -0,0 +1,328 @@
+//! SVG chart rendering for archive analytics.
+//!
+//! Renders the series behind `xf stats` (monthly activity, hourly and
+//! day-of-week distributions, engagement trend, likes histogram) as
+//! standalone SVG files suitable for reports. Charts are built as plain SVG
+//! markup so no rendering backend or font files are needed.
+
+use crate::stats_analytics::{EngagementStats, TemporalStats};
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 400.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 50.0;
+const MARGIN_BOTTOM: f64 = 70.0;
+const Y_TICKS: usize = 5;
+const MAX_X_LABELS: usize = 12;
+const BAR_COLOR: &str = "#1d9bf0";
+const LINE_COLOR: &str = "#f91880";
+
+/// Chart style for a series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    Bar,
+    Line,
+}
+
+/// Render a single-series chart as an SVG document.
+///
+/// `labels` and `values` must have the same length; extra entries on either
+/// side are ignored.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn render_svg(title: &str, kind: ChartKind, labels: &[String], values: &[f64]) -> String {
+    let n = labels.len().min(values.len());
+    let plot_w = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_h = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let max = nice_max(values[..n].iter().copied().fold(0.0, f64::max));
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect width="100%" height="100%" fill="#ffffff"/>"##
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="28" font-size="16" font-weight="bold" text-anchor="middle">{}</text>"#,
+        WIDTH / 2.0,
+        escape_xml(title)
+    );
+
+    // Y grid lines and tick labels
+    for tick in 0..=Y_TICKS {
+        let value = max * tick as f64 / Y_TICKS as f64;
+        let y = MARGIN_TOP + plot_h - plot_h * tick as f64 / Y_TICKS as f64;
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{MARGIN_LEFT}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#e1e8ed"/>"##,
+            WIDTH - MARGIN_RIGHT
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+            MARGIN_LEFT - 8.0,
+            y + 4.0,
+            format_tick(value)
+        );
+    }
+
+    // Axes
+    let _ = writeln!(
+        svg,
+        r##"<line x1="{MARGIN_LEFT}" y1="{MARGIN_TOP}" x2="{MARGIN_LEFT}" y2="{:.1}" stroke="#536471"/>"##,
+        MARGIN_TOP + plot_h
+    );
+    let _ = writeln!(
+        svg,
+        r##"<line x1="{MARGIN_LEFT}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#536471"/>"##,
+        MARGIN_TOP + plot_h,
+        WIDTH - MARGIN_RIGHT,
+        MARGIN_TOP + plot_h
+    );
+
+    if n > 0 {
+        let slot = plot_w / n as f64;
+        let y_for = |v: f64| (v / max).mul_add(-plot_h, MARGIN_TOP + plot_h);
+
+        match kind {
+            ChartKind::Bar => {
+                let bar_w = (slot * 0.8).max(1.0);
+                for (i, &v) in values[..n].iter().enumerate() {
+                    let x = slot.mul_add(i as f64 + 0.1, MARGIN_LEFT);
+                    let y = y_for(v);
+                    let _ = writeln!(
+                        svg,
+                        r#"<rect x="{x:.1}" y="{y:.1}" width="{bar_w:.1}" height="{:.1}" fill="{BAR_COLOR}"><title>{}: {}</title></rect>"#,
+                        MARGIN_TOP + plot_h - y,
+                        escape_xml(&labels[i]),
+                        format_tick(v)
+                    );
+                }
+            }
+            ChartKind::Line => {
+                let points: Vec<String> = values[..n]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &v)| {
+                        format!(
+                            "{:.1},{:.1}",
+                            slot.mul_add(i as f64 + 0.5, MARGIN_LEFT),
+                            y_for(v)
+                        )
+                    })
+                    .collect();
+                let _ = writeln!(
+                    svg,
+                    r#"<polyline points="{}" fill="none" stroke="{LINE_COLOR}" stroke-width="2"/>"#,
+                    points.join(" ")
+                );
+            }
+        }
+
+        // X labels, thinned so they do not overlap
+        let step = n.div_ceil(MAX_X_LABELS).max(1);
+        for i in (0..n).step_by(step) {
+            let x = slot.mul_add(i as f64 + 0.5, MARGIN_LEFT);
+            let y = MARGIN_TOP + plot_h + 16.0;
+            let _ = writeln!(
+                svg,
+                r#"<text x="{x:.1}" y="{y:.1}" text-anchor="end" transform="rotate(-35 {x:.1} {y:.1})">{}</text>"#,
+                escape_xml(&labels[i])
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Write the standard set of analytics charts into `dir`.
+///
+/// Returns the paths of the files written.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be created or a file cannot be written.
+#[allow(clippy::cast_precision_loss)]
+pub fn write_stats_charts(
+    dir: &Path,
+    temporal: &TemporalStats,
+    engagement: &EngagementStats,
+) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create chart directory {}", dir.display()))?;
+
+    let mut monthly: std::collections::BTreeMap<String, u64> = std::collections::BTreeMap::new();
+    for day in &temporal.daily_counts {
+        *monthly
+            .entry(day.date.format("%Y-%m").to_string())
+            .or_default() += day.count;
+    }
+
+    let hours: Vec<String> = (0..24).map(|h| format!("{h:02}")).collect();
+    let days: Vec<String> = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    let charts = [
+        (
+            "activity_monthly.svg",
+            render_svg(
+                "Tweets per month",
+                ChartKind::Bar,
+                &monthly.keys().cloned().collect::<Vec<_>>(),
+                &monthly.values().map(|&c| c as f64).collect::<Vec<_>>(),
+            ),
+        ),
+        (
+            "hourly.svg",
+            render_svg(
+                "Tweets by hour of day",
+                ChartKind::Bar,
+                &hours,
+                &temporal
+                    .hourly_distribution
+                    .iter()
+                    .map(|&c| c as f64)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+        (
+            "day_of_week.svg",
+            render_svg(
+                "Tweets by day of week",
+                ChartKind::Bar,
+                &days,
+                &temporal
+                    .dow_distribution
+                    .iter()
+                    .map(|&c| c as f64)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+        (
+            "engagement_monthly.svg",
+            render_svg(
+                "Average engagement per tweet (monthly)",
+                ChartKind::Line,
+                &engagement
+                    .monthly_trend
+                    .iter()
+                    .map(|m| m.month.clone())
+                    .collect::<Vec<_>>(),
+                &engagement
+                    .monthly_trend
+                    .iter()
+                    .map(|m| m.avg_engagement)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+        (
+            "likes_histogram.svg",
+            render_svg(
+                "Likes per tweet",
+                ChartKind::Bar,
+                &engagement
+                    .likes_histogram
+                    .iter()
+                    .map(|b| b.label.clone())
+                    .collect::<Vec<_>>(),
+                &engagement
+                    .likes_histogram
+                    .iter()
+                    .map(|b| b.count as f64)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    ];
+
+    let mut written = Vec::with_capacity(charts.len());
+    for (name, svg) in charts {
+        let path = dir.join(name);
+        std::fs::write(&path, svg)
+            .with_context(|| format!("Failed to write chart {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Round an axis maximum up to 1, 2, or 5 times a power of ten.
+fn nice_max(max: f64) -> f64 {
+    if max <= 0.0 || !max.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(max.log10().floor());
+    let normalized = max / magnitude;
+    let nice = if normalized <= 1.0 {
+        1.0
+    } else if normalized <= 2.0 {
+        2.0
+    } else if normalized <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+fn format_tick(value: f64) -> String {
+    if value.fract().abs() < f64::EPSILON {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_max() {
+        assert!((nice_max(0.0) - 1.0).abs() < f64::EPSILON);
+        assert!((nice_max(7.0) - 10.0).abs() < f64::EPSILON);
+        assert!((nice_max(130.0) - 200.0).abs() < f64::EPSILON);
+        assert!((nice_max(450.0) - 500.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_render_bar_chart() {
+        let labels = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let svg = render_svg("Counts <test>", ChartKind::Bar, &labels, &[1.0, 3.0, 2.0]);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect x=").count(), 3);
+        assert!(svg.contains("Counts &lt;test&gt;"));
+    }
+
+    #[test]
+    fn test_render_line_chart_empty_series() {
+        let svg = render_svg("Empty", ChartKind::Line, &[], &[]);
+        assert!(svg.contains("Empty"));
+        assert!(!svg.contains("<polyline"));
+    }
+}
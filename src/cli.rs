@@ -39,6 +39,7 @@ Quick start:
   xf export tweets --format csv   # Export tweets to CSV
   xf doctor                       # Check archive/index health
 "#)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Cli {
     /// Path to the database file
     #[arg(long, env = "XF_DB", global = true)]
@@ -48,6 +49,15 @@ pub struct Cli {
     #[arg(long, env = "XF_INDEX", global = true)]
     pub index: Option<PathBuf>,
 
+    /// Keyword search engine (default: search.engine, else tantivy)
+    #[arg(
+        long,
+        value_enum,
+        global = true,
+        long_help = "Keyword search engine. tantivy (the default) searches the index directory; sqlite searches the database's FTS tables, so `xf index --engine sqlite` skips building the Tantivy index. Both return the same results model; relevance scores differ.\nOverrides search.engine.\nExample: xf index ~/archive --engine sqlite && xf search rust --engine sqlite"
+    )]
+    pub engine: Option<crate::search::SearchBackend>,
+
     /// Output format
     #[arg(long, short = 'f', default_value = "text", global = true)]
     pub format: OutputFormat,
@@ -64,27 +74,92 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub no_color: bool,
 
+    /// Plain labeled lines for screen readers, without box drawing, charts or color
+    #[arg(
+        long,
+        env = "XF_ACCESSIBLE",
+        global = true,
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub accessible: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+// Parsed once per run, so boxing the larger argument structs would buy nothing
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Import and index an X data archive from a zip file
     Import(ImportArgs),
 
+    /// Check an archive against its manifest for missing or truncated files
+    Verify(VerifyArgs),
+
+    /// Re-check the index against the manifest written when it was built
+    VerifyIndex(VerifyIndexArgs),
+
     /// Index an X data archive
     Index(IndexArgs),
 
+    /// Regenerate semantic embeddings from the indexed database
+    Embed(EmbedArgs),
+
     /// Search the indexed archive
     Search(SearchArgs),
 
+    /// Scan stored text for a literal string or regex, bypassing the indexes
+    Grep(GrepArgs),
+
+    /// Answer a question from your archive using a configured LLM endpoint
+    Ask(AskArgs),
+
+    /// Summarize a tweet thread or DM conversation
+    Summarize(SummarizeArgs),
+
+    /// Classify tweets into your own categories by example
+    Tag(TagArgs),
+
+    /// Attach notes to tweets, DMs, likes, and Grok messages
+    Note(NoteArgs),
+
+    /// Save documents into named collections and export them
+    Collect(CollectArgs),
+
+    /// Hide documents from search, list, export, and stats
+    Hide(HideArgs),
+
+    /// Pin documents to the top of matching searches
+    Pin(PinArgs),
+
+    /// Save searches and report their new matches after re-indexing
+    Alerts(AlertsArgs),
+
+    /// Re-run, list, or remove searches saved with `xf search --save`
+    Saved(SavedArgs),
+
+    /// Show the audit log of DM reads and exports
+    Audit(AuditArgs),
+
+    /// Export every tweet, like, and DM referencing a person
+    Extract(ExtractArgs),
+
+    /// Find likes of your own tweets and your tweets others sent you in DMs
+    Overlap(OverlapArgs),
+
     /// Show archive statistics
     Stats(StatsArgs),
 
+    /// Show how often a query matched over time
+    Trend(TrendArgs),
+
     /// Show information about a specific tweet
     Tweet(TweetArgs),
 
+    /// Show your profile and how it changed across indexed archives
+    Profile(ProfileArgs),
+
     /// List available data in the archive
     List(ListArgs),
 
@@ -103,8 +178,32 @@ pub enum Commands {
     /// Check archive, database, and index health
     Doctor(DoctorArgs),
 
+    /// Re-parse epoch-zero tweet and DM dates from the archive's JSON
+    BackfillDates(BackfillDatesArgs),
+
+    /// Resolve short links (t.co and other shorteners) in stored tweets, DMs, and likes
+    Enrich(EnrichArgs),
+
+    /// Run import, index, search, stats, and export on a synthetic archive to check this build
+    Selftest(SelftestArgs),
+
+    /// Show the current setup: paths, sizes, counts, and versions
+    Info,
+
+    /// Show disk usage of the database, index, embeddings, and archive
+    Du(DuArgs),
+
+    /// Delete derived data (embeddings, FTS tables, search index) to reclaim space
+    Prune(PruneArgs),
+
     /// Launch interactive REPL mode
     Shell(ShellArgs),
+
+    /// Browse the archive in a full-screen terminal UI
+    Tui(TuiArgs),
+
+    /// Serve the archive to other programs (MCP over stdio, or a JSON HTTP API)
+    Serve(ServeArgs),
 }
 
 #[derive(Args, Debug)]
@@ -112,7 +211,10 @@ pub enum Commands {
   xf import ~/Downloads/twitter-2026-01-09-abc123.zip
   xf import archive.zip -o ~/my_x_data
   xf import archive.zip --no-index
+  xf import archive.zip --no-media          # Text only, a fraction of the disk space
+  xf import archive.zip --media-only        # Add the media later
 "#)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ImportArgs {
     /// Path to the X data archive zip file
     pub zip_file: PathBuf,
@@ -128,6 +230,50 @@ pub struct ImportArgs {
     /// Overwrite existing extraction
     #[arg(long, short = 'F')]
     pub force: bool,
+
+    /// Refuse archives that expand to more than this (e.g. 200GB; default 64GB)
+    #[arg(long, value_name = "SIZE")]
+    pub max_size: Option<String>,
+
+    /// Number of parallel workers for extraction and indexing (0 = one per CPU)
+    #[arg(long, short = 'j', default_value = "0")]
+    pub jobs: usize,
+
+    /// Skip media folders (`tweets_media/`, DM media, ...); search only needs the text
+    #[arg(long)]
+    pub no_media: bool,
+
+    /// Extract only media folders, into an existing extraction (e.g. one made with --no-media)
+    #[arg(long, conflicts_with_all = ["no_media", "force"])]
+    pub media_only: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf verify ~/Downloads/twitter-2026-01-09-abc123.zip
+  xf verify ~/my_x_history
+  xf verify                                 # The configured archive
+"#)]
+pub struct VerifyArgs {
+    /// Archive zip file or extracted directory (default: configured archive)
+    pub archive: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf verify-index                                   # Counts, versions and source hashes
+  xf verify-index --archive ~/my_x_history          # Archive moved since indexing
+  xf verify-index --against other-machine.json      # Same archive, same index?
+
+The manifest is xf-index-manifest.json in the index directory."#)]
+pub struct VerifyIndexArgs {
+    /// Extracted archive to re-hash (default: the path recorded at index time)
+    #[arg(long)]
+    pub archive: Option<PathBuf>,
+
+    /// Another machine's manifest to compare fingerprints with
+    #[arg(long, value_name = "MANIFEST")]
+    pub against: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -150,6 +296,26 @@ pub struct IndexArgs {
     /// Number of parallel workers
     #[arg(long, short = 'j', default_value = "0")]
     pub jobs: usize,
+
+    /// Tweets only, without FTS rows, source JSON, embeddings, or prefix matching (for small devices)
+    #[arg(long, conflicts_with_all = ["only", "skip"])]
+    pub lite: bool,
+
+    /// Commit the search index every N documents, so a crash loses less work
+    /// (0 = only at the end; default: `indexing.commit_every`)
+    #[arg(long, value_name = "N")]
+    pub commit_every: Option<usize>,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf embed                         # Embed the types listed in embeddings.types
+  xf embed --types tweet,dm        # Embed only tweets and DMs
+"#)]
+pub struct EmbedArgs {
+    /// Document types to embed (overrides `embeddings.types`); other types lose their embeddings
+    #[arg(long, short = 't', value_delimiter = ',')]
+    pub types: Option<Vec<SearchType>>,
 }
 
 #[derive(Args, Debug)]
@@ -159,12 +325,34 @@ pub struct IndexArgs {
   xf search "meeting" --types dm       # Search DMs
   xf search "2024" --since "last week" # Recent content
   xf search "bug" --limit 50           # More results
+  xf search "coffee" --place portland  # Geotagged tweets from a place
+  xf search "" --source ifttt          # Tweets posted by an automation
+  xf search "" --no-alt-text          # Media posted without alt text
+  xf search "sunset" --scope supplemental
+  xf search "rust" --list 1234567890   # Tweets to members of a list
+                                       # Match alt text, poll options and notes only
+  xf search "" --types dm --active-since 2020-03 --active-until 2020-03 --context
+                                       # DM threads active in March 2020
+  xf search --queries-file queries.txt --format jsonl  # Many queries at once
+  xf search "rust" --since "last month" --no-replies --save rust-recent
+                                       # Save the search, then: xf saved run rust-recent
 "#)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct SearchArgs {
     /// Search query
+    #[arg(required_unless_present_any = ["schema", "queries_file"], default_value = "")]
     pub query: String,
 
-    /// Filter by data type (tweet, like, dm, grok, all)
+    /// Run every query in FILE (one per line, `-` for stdin) in one process.
+    ///
+    /// Blank lines and lines starting with `#` are skipped. With
+    /// `--format jsonl` each result carries a `query` field; `--format json`
+    /// prints `{"query", "results"}` per query:
+    ///   xf search --queries-file queries.txt --format jsonl
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["query", "context"])]
+    pub queries_file: Option<PathBuf>,
+
+    /// Filter by data type (tweet, like, bookmark, dm, grok, all)
     #[arg(long, short = 't', value_delimiter = ',')]
     pub types: Option<Vec<SearchType>>,
 
@@ -209,13 +397,564 @@ pub struct SearchArgs {
     #[arg(long, short = 'c')]
     pub context: bool,
 
+    /// Skip building highlighted snippets.
+    ///
+    /// Faster on large result sets. JSON results still carry
+    /// `match_ranges`, the byte offsets of matched words in `text`:
+    ///   xf search "rust" --no-snippets --format json
+    #[arg(long)]
+    pub no_snippets: bool,
+
+    /// Don't print the matches-by-year histogram above text results
+    #[arg(long)]
+    pub no_histogram: bool,
+
+    /// Show N characters around the best match instead of the full text.
+    ///
+    /// For long Grok messages and note tweets, where a match can be buried
+    /// deep in the text. Applies to every output format; in JSON,
+    /// `match_ranges` point into the shortened text:
+    ///   xf search "borrow checker" --types grok --context-chars 300
+    #[arg(long, value_name = "N", conflicts_with = "context")]
+    pub context_chars: Option<usize>,
+
     /// Fields to include in output
     #[arg(long, value_delimiter = ',')]
     pub fields: Option<Vec<String>>,
 
-    /// Search mode: lexical (keyword), semantic (meaning), or hybrid (both)
+    /// Print the JSON Schema of `--format json` results and exit
+    #[arg(long)]
+    pub schema: bool,
+
+    /// Search mode: lexical (keyword), semantic (meaning), hybrid (both), or auto (picked from the query; see why with --verbose)
     #[arg(long, short = 'm', default_value = "hybrid")]
     pub mode: crate::hybrid::SearchMode,
+
+    /// Text that keyword matches may come from
+    #[arg(
+        long,
+        default_value = "all",
+        long_help = "Text that keyword matches may come from: main (the document's own text), supplemental (text attached to it: poll options, media alt text and your notes), or all. Results always show the main text.\n\nIndexes built before supplemental text existed need 'xf index --force' for main or supplemental.\nExample: --scope supplemental"
+    )]
+    pub scope: crate::search::SearchScope,
+
+    /// Drop semantic/hybrid results scoring below this (overrides `search.min_score`)
+    #[arg(
+        long,
+        value_name = "SCORE",
+        long_help = "Drop results scoring below this threshold before pagination (0.0 - 1.0). Overrides search.min_score.\n\nSemantic mode compares cosine similarity; hybrid mode compares the fused RRF score (at most about 0.033). Not applicable to lexical mode.\nExample: --mode semantic --min-score 0.3"
+    )]
+    pub min_score: Option<f32>,
+
+    /// Re-score the top candidates with a local cross-encoder (needs the `rerank` feature)
+    #[arg(
+        long,
+        long_help = "Re-score the top candidates (search.rerank_candidates, default 50) with a local cross-encoder model for better top-10 precision. Slower than plain search.\n\nThe model directory (search.rerank_model) must contain model.onnx and tokenizer.json. Requires an xf build with the `rerank` feature and an ONNX Runtime library (set ORT_DYLIB_PATH if it is not on the library path).\nExample: xf search \"async runtimes\" --rerank"
+    )]
+    pub rerank: bool,
+
+    /// Only show tweets carrying this tag (see `xf tag`)
+    #[arg(long, value_name = "TAG")]
+    pub tag: Option<String>,
+
+    /// Only show tweets whose images and videos all have alt text
+    #[arg(long, conflicts_with = "no_alt_text")]
+    pub has_alt_text: bool,
+
+    /// Only show tweets with an image or video missing alt text
+    #[arg(long)]
+    pub no_alt_text: bool,
+
+    /// Only show geotagged tweets whose place or country contains NAME
+    #[arg(long, value_name = "NAME")]
+    pub place: Option<String>,
+
+    /// Only show geotagged tweets inside a box
+    #[arg(
+        long,
+        value_name = "BOX",
+        allow_hyphen_values = true,
+        long_help = "Only show geotagged tweets whose coordinates fall inside a box, given as min_lon,min_lat,max_lon,max_lat in degrees. Tweets tagged with a place but no point use the centre of the place.\nExample: --geo-bbox -123.0,45.3,-122.3,45.7"
+    )]
+    pub geo_bbox: Option<String>,
+
+    /// Only show tweets replying to or mentioning members of this list (see `xf list lists`)
+    #[arg(long, value_name = "LIST_ID")]
+    pub list: Option<String>,
+
+    /// Only show tweets posted from a client whose name contains PATTERN (e.g. iphone, ifttt)
+    #[arg(long, value_name = "PATTERN")]
+    pub source: Option<String>,
+
+    /// Only show DMs from conversations active on or after this date
+    #[arg(
+        long,
+        value_name = "DATE",
+        conflicts_with_all = ["tag", "has_alt_text", "no_alt_text", "place", "geo_bbox", "list", "source"],
+        long_help = "Only show DMs from conversations whose last message is on or after this date, whichever message matched. Unlike --since, a match from before the date still counts when its conversation carried on past it.\n\nFormats: 2024-01-15, 2024-01, \"last year\"\nExample: --active-since 2020-03"
+    )]
+    pub active_since: Option<String>,
+
+    /// Only show DMs from conversations active on or before this date
+    #[arg(
+        long,
+        value_name = "DATE",
+        conflicts_with_all = ["tag", "has_alt_text", "no_alt_text", "place", "geo_bbox", "list", "source"],
+        long_help = "Only show DMs from conversations whose first message is on or before this date, whichever message matched. Combine with --active-since to find every thread that was active during a period.\n\nFormats: 2024-01-15, 2024-01, \"last year\"\nExample: --active-since 2020-03 --active-until 2020-03"
+    )]
+    pub active_until: Option<String>,
+
+    /// Include documents hidden with `xf hide`
+    #[arg(long)]
+    pub hidden: bool,
+
+    /// Only search documents already in the archive as of this date
+    #[arg(
+        long,
+        value_name = "DATE",
+        long_help = "Only search tweets, likes, and DMs that were in the latest indexed archive generated on or before this date, to see the archive as it was before later archives were indexed.\n\nFormats: 2024-01-15, 2024-01, \"last year\"\nExample: --as-of 2023-06"
+    )]
+    pub as_of: Option<String>,
+
+    /// Save this search under NAME once it has run
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with = "queries_file",
+        long_help = "Save this search under NAME once it has run; a search that fails is not saved. The query, --types, --mode, --sort and the filters (dates, replies, tags, places, sources, lists, scope, score, hidden, as-of) are saved; output options such as --limit and --format are not.\n\nRelative dates are saved as typed, so \"last month\" moves with each run.\nExample: xf search \"rust\" --since \"last month\" --save rust-recent\nThen: xf saved run rust-recent\n\nSaved searches are alerts too, so xf alerts run rust-recent reports new matches of the same search."
+    )]
+    pub save: Option<String>,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf grep "C++"                        # Exact text, punctuation included
+  xf grep -i "todo:" --types tweet     # Ignore case, tweets only
+  xf grep -E "v\d+\.\d+" --limit 20     # Regular expression
+  xf grep -c "rust"                    # Match counts per type only
+
+Reads every stored tweet, like, bookmark, DM and Grok message instead of the
+search index, so it also finds partial words and symbols, and it shows what a
+search should return when checking an index for gaps. Hidden documents are
+left out unless --hidden is given.
+"#)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct GrepArgs {
+    /// Text to find (a regular expression with --regex)
+    pub pattern: String,
+
+    /// Treat the pattern as a regular expression
+    #[arg(long, short = 'E')]
+    pub regex: bool,
+
+    /// Ignore case when matching
+    #[arg(long, short = 'i')]
+    pub ignore_case: bool,
+
+    /// Filter by data type (tweet, like, bookmark, dm, grok, all)
+    #[arg(long, short = 't', value_delimiter = ',')]
+    pub types: Option<Vec<SearchType>>,
+
+    /// Maximum number of results (0 = all)
+    #[arg(long, short = 'n', default_value = "0")]
+    pub limit: usize,
+
+    /// Print the number of matches per type instead of the matches
+    #[arg(long, short = 'c')]
+    pub count: bool,
+
+    /// Include documents hidden with `xf hide`
+    #[arg(long)]
+    pub hidden: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf ask "what did I think about rust async?"
+  xf ask "what did I think about rust async?" --since 2021-01 --until 2021-12
+  xf ask "who recommended that book?" --types dm --dry-run
+
+Setup (any OpenAI-compatible endpoint; nothing is sent until configured):
+  xf config --set llm.endpoint=http://localhost:11434/v1
+  xf config --set llm.model=llama3.1
+"#)]
+pub struct AskArgs {
+    /// Question to answer
+    pub question: String,
+
+    /// Filter retrieved documents by type (tweet, like, bookmark, dm, grok, all)
+    #[arg(long, short = 't', value_delimiter = ',')]
+    pub types: Option<Vec<SearchType>>,
+
+    /// Number of documents to retrieve as context
+    #[arg(long, short = 'n', default_value = "20")]
+    pub limit: usize,
+
+    /// Only use documents from this date onwards
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    /// Only use documents until this date
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<String>,
+
+    /// Print the prompt that would be sent instead of calling the endpoint
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf summarize 1234567890               # thread containing this tweet
+  xf summarize 1234-5678                # DM conversation
+  xf summarize 1234567890 --extractive  # never call the LLM endpoint
+
+Uses the configured llm.endpoint when set; otherwise (or if the call
+fails) the most representative messages are picked extractively.
+"#)]
+pub struct SummarizeArgs {
+    /// Tweet ID (summarizes its thread) or DM conversation ID
+    pub id: String,
+
+    /// Skip the LLM endpoint and build an extractive summary
+    #[arg(long)]
+    pub extractive: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf tag define work --examples "sprint planning" "code review" "standup notes"
+  xf tag define travel --examples 1234567890123456789 "flight to lisbon" --threshold 0.25
+  xf tag apply
+  xf tag list
+  xf search "deadline" --tag work
+"#)]
+pub struct TagArgs {
+    #[command(subcommand)]
+    pub action: TagAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TagAction {
+    /// Define (or redefine) a tag from example texts or tweet IDs
+    Define {
+        /// Tag name (letters, digits, '-' and '_')
+        name: String,
+
+        /// Example texts or tweet IDs that belong in this category
+        #[arg(long, short = 'e', num_args = 1.., required = true)]
+        examples: Vec<String>,
+
+        /// Minimum similarity to the examples for a tweet to be tagged (default 0.3)
+        #[arg(long)]
+        threshold: Option<f32>,
+    },
+
+    /// Classify all tweets into the defined tags
+    Apply {
+        /// Only apply these tags (default: all)
+        names: Vec<String>,
+    },
+
+    /// List defined tags with their tweet counts
+    List,
+
+    /// Remove a tag definition and its assignments
+    Remove {
+        /// Tag name
+        name: String,
+    },
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf note add 1234567890123456789 "source for the rust talk"
+  xf note add 1234-5678 "follow up in March" --type dm
+  xf note list
+  xf note list 1234567890123456789
+  xf note remove 3
+
+Notes are searchable (xf search matches note text), shown in xf tweet and
+search output, and exported with: xf export notes
+"#)]
+pub struct NoteArgs {
+    #[command(subcommand)]
+    pub action: NoteAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum NoteAction {
+    /// Attach a note to a document
+    Add {
+        /// Document ID (tweet, like, DM, or Grok message)
+        doc_id: String,
+
+        /// Note text
+        text: String,
+
+        /// Document type, when the ID is ambiguous (e.g. a liked tweet you also posted)
+        #[arg(long = "type", short = 't', value_name = "TYPE")]
+        doc_type: Option<SearchType>,
+    },
+
+    /// List notes, optionally only those on one document
+    List {
+        /// Document ID
+        doc_id: Option<String>,
+    },
+
+    /// Delete a note by its number (see `xf note list`)
+    Remove {
+        /// Note number
+        id: i64,
+    },
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf collect add 1234567890123456789 1234567890123456790 --to rust-talk
+  xf collect list
+  xf collect show rust-talk
+  xf collect export rust-talk -o rust-talk.md
+  xf collect export rust-talk --format json -o rust-talk.json
+  xf collect remove 1234567890123456790 --from rust-talk
+"#)]
+pub struct CollectArgs {
+    #[command(subcommand)]
+    pub action: CollectAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CollectAction {
+    /// Add documents to a collection (created if it does not exist)
+    Add {
+        /// Document IDs (tweets, likes, DMs, or Grok messages)
+        #[arg(required = true)]
+        doc_ids: Vec<String>,
+
+        /// Collection name
+        #[arg(long, value_name = "COLLECTION")]
+        to: String,
+
+        /// Document type, when an ID is ambiguous (e.g. a liked tweet you also posted)
+        #[arg(long = "type", short = 't', value_name = "TYPE")]
+        doc_type: Option<SearchType>,
+    },
+
+    /// Remove a document from a collection
+    Remove {
+        /// Document ID
+        doc_id: String,
+
+        /// Collection name
+        #[arg(long, value_name = "COLLECTION")]
+        from: String,
+    },
+
+    /// List collections with their sizes
+    List,
+
+    /// Show the documents in a collection
+    Show {
+        /// Collection name
+        name: String,
+    },
+
+    /// Export a collection as Markdown (default) or JSON/CSV via --format
+    Export {
+        /// Collection name
+        name: String,
+
+        /// Output file path (stdout if not specified)
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+
+    /// Delete a collection (the documents themselves are untouched)
+    Delete {
+        /// Collection name
+        name: String,
+    },
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf hide 1234567890123456789
+  xf hide 1234-5678 --type dm
+  xf hide --list
+  xf hide 1234567890123456789 --undo
+  xf search "rust" --hidden        # include hidden documents
+"#)]
+pub struct HideArgs {
+    /// Document IDs (tweets, likes, DMs, or Grok messages)
+    #[arg(required_unless_present = "list")]
+    pub doc_ids: Vec<String>,
+
+    /// Document type, when an ID is ambiguous (e.g. a liked tweet you also posted)
+    #[arg(long = "type", short = 't', value_name = "TYPE")]
+    pub doc_type: Option<SearchType>,
+
+    /// Unhide the documents instead
+    #[arg(long)]
+    pub undo: bool,
+
+    /// List hidden documents
+    #[arg(long, conflicts_with_all = ["doc_ids", "undo"])]
+    pub list: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf pin 1234567890123456789                        # first whenever it matches a search
+  xf pin 1234567890123456789 --query "async rust"   # first for this query, even if it doesn't match
+  xf pin --list
+  xf pin 1234567890123456789 --undo
+"#)]
+pub struct PinArgs {
+    /// Document IDs (tweets, likes, DMs, or Grok messages)
+    #[arg(required_unless_present = "list")]
+    pub doc_ids: Vec<String>,
+
+    /// Only pin for this query (case and spacing are ignored)
+    #[arg(long, value_name = "QUERY")]
+    pub query: Option<String>,
+
+    /// Document type, when an ID is ambiguous (e.g. a liked tweet you also posted)
+    #[arg(long = "type", short = 't', value_name = "TYPE")]
+    pub doc_type: Option<SearchType>,
+
+    /// Unpin the documents instead (only the --query pin, if given)
+    #[arg(long)]
+    pub undo: bool,
+
+    /// List pins
+    #[arg(long, conflicts_with_all = ["doc_ids", "undo", "query"])]
+    pub list: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf alerts add rust-jobs "rust AND (hiring OR job)"
+  xf alerts add deploys "deploy" --types dm
+  xf alerts run                     # exits 1 when any alert has new matches
+  xf alerts run rust-jobs --format json
+  xf alerts list
+  xf alerts remove rust-jobs
+"#)]
+pub struct AlertsArgs {
+    #[command(subcommand)]
+    pub action: AlertsAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AlertsAction {
+    /// Save a search as an alert
+    Add {
+        /// Alert name
+        name: String,
+
+        /// Search query (same syntax as xf search, including AND, OR, NOT)
+        query: String,
+
+        /// Filter by data type (tweet, like, bookmark, dm, grok, all)
+        #[arg(long, short = 't', value_delimiter = ',')]
+        types: Option<Vec<SearchType>>,
+    },
+
+    /// Delete an alert
+    Remove {
+        /// Alert name
+        name: String,
+    },
+
+    /// List alerts with when they last ran
+    List,
+
+    /// Run alerts and show matches that are new since their last run.
+    ///
+    /// The first run of an alert records its current matches without
+    /// reporting them. Exits with status 1 when any alert has new matches,
+    /// so it can follow `xf index` in a script.
+    Run {
+        /// Alerts to run (all if none are given)
+        names: Vec<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf search "rust" --since "last month" --no-replies --save rust-recent
+  xf saved run rust-recent
+  xf saved run rust-recent --limit 50 --format json
+  xf saved list
+  xf saved remove rust-recent
+"#)]
+pub struct SavedArgs {
+    #[command(subcommand)]
+    pub action: SavedAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SavedAction {
+    /// Run a saved search
+    Run {
+        /// Saved search name
+        name: String,
+
+        /// Maximum number of results
+        #[arg(long, short = 'n', default_value = "20")]
+        limit: usize,
+    },
+
+    /// List saved searches, including alerts
+    List,
+
+    /// Delete a saved search, like `xf alerts remove`
+    Remove {
+        /// Saved search name
+        name: String,
+    },
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf config --set audit.enabled=true   # start recording
+  xf audit
+  xf audit --since 2024-06-01 --command export
+  xf audit --format json
+"#)]
+pub struct AuditArgs {
+    /// Only show entries from this date onwards
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    /// Only show entries for this command (e.g. search, export)
+    #[arg(long, value_name = "NAME")]
+    pub command: Option<String>,
+
+    /// Maximum number of entries
+    #[arg(long, short = 'n', default_value = "50")]
+    pub limit: usize,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf extract --about @alice -o alice.json
+  xf extract --about "Alice Smith" --format compact -o alice.jsonl
+  xf extract --about 783214 -o subject.json    # numeric account ID
+
+Formats (global --format):
+  json, json-pretty, text   One JSON document (default)
+  jsonl, compact            JSON Lines: a subject line, then one record per line
+"#)]
+pub struct ExtractArgs {
+    /// Handle (@alice), display name, or numeric account ID of the person
+    #[arg(long, value_name = "HANDLE|NAME")]
+    pub about: String,
+
+    /// Output file path (stdout if not specified)
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -233,6 +972,10 @@ pub struct StatsArgs {
     #[arg(long)]
     pub mentions: bool,
 
+    /// Show places tweeted from with counts (geotagged tweets only)
+    #[arg(long)]
+    pub places: bool,
+
     /// Show temporal analytics (activity patterns, gaps, sparklines)
     #[arg(long)]
     pub temporal: bool,
@@ -245,15 +988,110 @@ pub struct StatsArgs {
     #[arg(long)]
     pub content: bool,
 
+    /// Show likes analytics (most-liked authors, like volume, overlap with following)
+    #[arg(long)]
+    pub likes: bool,
+
+    /// Show follower churn across indexed archives (gained/lost, follow-back ratio)
+    #[arg(long)]
+    pub followers: bool,
+
+    /// Show writing-style metrics per year (sentence length, vocabulary, reading level)
+    #[arg(long)]
+    pub style: bool,
+
+    /// Show the clients tweets were posted from (phone, web, automation) and how they changed by year
+    #[arg(long)]
+    pub sources: bool,
+
+    /// Compare two periods side by side (e.g. --compare 2019 2023, --compare 2020-01..2020-06 2024)
+    #[arg(long, num_args = 2, value_names = ["PERIOD_A", "PERIOD_B"])]
+    pub compare: Option<Vec<String>>,
+
+    /// Show hashtags and words that appear alongside a hashtag or word (e.g. "#rust", "async")
+    #[arg(long, value_name = "TAG_OR_TERM")]
+    pub cooccur: Option<String>,
+
+    /// Render temporal, hourly, and engagement charts as SVG files into this directory
+    #[arg(long, value_name = "DIR")]
+    pub chart_out: Option<PathBuf>,
+
+    /// Directory for per-series CSV files (used with --format csv)
+    #[arg(long, short = 'o', value_name = "DIR")]
+    pub output: Option<PathBuf>,
+
     /// Number of top items to show
     #[arg(long, short = 'n', default_value = "10")]
     pub top: usize,
+
+    /// Count documents hidden with `xf hide`
+    #[arg(long)]
+    pub hidden: bool,
+
+    /// Only count documents already in the archive as of this date
+    #[arg(
+        long,
+        value_name = "DATE",
+        long_help = "Only count tweets, likes, and DMs that were in the latest indexed archive generated on or before this date, to see the archive as it was before later archives were indexed.\n\nFormats: 2024-01-15, 2024-01, \"last year\"\nExample: --as-of 2023-06"
+    )]
+    pub as_of: Option<String>,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf profile                         # Profile as of the latest indexed archive
+  xf profile --history               # Name, bio, avatar, and banner changes over time
+  xf profile --history --format json
+"#)]
+pub struct ProfileArgs {
+    /// Show what changed between indexed archives, oldest first
+    #[arg(
+        long,
+        long_help = "Show what changed between indexed archives, oldest first.\n\nAn archive holds only the profile as it was when the archive was generated, so each `xf index` run records one version. Index newer archives into the same database to extend the history."
+    )]
+    pub history: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf overlap                       # Self-likes and DM shares of your tweets
+  xf overlap --limit 0             # Show every match
+  xf overlap --format json
+"#)]
+pub struct OverlapArgs {
+    /// Maximum entries to show per section in text output (0 = all)
+    #[arg(long, short = 'n', default_value = "20")]
+    pub limit: usize,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf trend rust                    # Monthly matches for "rust"
+  xf trend '"rust lang"' --interval week
+  xf trend rust --types tweet --format json
+"#)]
+pub struct TrendArgs {
+    /// Search query
+    pub query: String,
+
+    /// Bucket width for the series
+    #[arg(long, short = 'i', default_value = "month")]
+    pub interval: crate::stats_analytics::TrendInterval,
+
+    /// Filter by data type (tweet, like, bookmark, dm, grok, all)
+    #[arg(long, short = 't', value_delimiter = ',')]
+    pub types: Option<Vec<SearchType>>,
 }
 
 #[derive(Args, Debug)]
 pub struct TweetArgs {
     /// Tweet ID to show
-    pub id: String,
+    #[arg(required_unless_present = "ids_file")]
+    pub id: Option<String>,
+
+    /// Read tweet IDs (one per line) from a file, or `-` for stdin; prints JSON Lines
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["id", "thread"])]
+    pub ids_file: Option<PathBuf>,
 
     /// Show thread context (replies)
     #[arg(long, short = 't')]
@@ -262,6 +1100,10 @@ pub struct TweetArgs {
     /// Show engagement metrics
     #[arg(long, short = 'e')]
     pub engagement: bool,
+
+    /// Show where the tweet ranks among your tweets by likes, retweets, replies and quotes
+    #[arg(long, conflicts_with_all = ["ids_file", "thread"])]
+    pub percentile: bool,
 }
 
 #[derive(Args, Debug)]
@@ -273,24 +1115,139 @@ pub struct ListArgs {
     /// Limit number of items
     #[arg(long, short = 'n', default_value = "50")]
     pub limit: usize,
+
+    /// Filter tweets, likes, DMs, or community notes with a database full-text (FTS5) query; no search index needed
+    #[arg(long = "match", value_name = "FTS_QUERY")]
+    pub match_query: Option<String>,
+
+    /// Sort order for --match results
+    #[arg(long, short = 's', requires = "match_query")]
+    pub sort: Option<SortOrder>,
+
+    /// Only list conversations with this account (ID or @handle; conversations only)
+    #[arg(long, value_name = "ACCOUNT")]
+    pub with: Option<String>,
+
+    /// Only list conversations with at least N messages (conversations only)
+    #[arg(long, value_name = "N")]
+    pub min_messages: Option<u64>,
+
+    /// Order conversations by recent activity, oldest first, or message count
+    #[arg(long, value_name = "ORDER")]
+    pub by: Option<ConversationSort>,
+
+    /// Only list tweets posted from a client whose name contains PATTERN (tweets only)
+    #[arg(long, value_name = "PATTERN")]
+    pub source: Option<String>,
+
+    /// Record the members of this list (lists only; archives do not include them)
+    #[arg(long, value_name = "LIST_ID", requires = "members_file")]
+    pub list: Option<String>,
+
+    /// File with one member account ID or @handle per line, or `-` for stdin (with --list)
+    #[arg(long, value_name = "FILE", requires = "list")]
+    pub members_file: Option<PathBuf>,
+
+    /// Include documents hidden with `xf hide`
+    #[arg(long)]
+    pub hidden: bool,
+
+    /// Only list documents already in the archive as of this date
+    #[arg(
+        long,
+        value_name = "DATE",
+        long_help = "Only list tweets, likes, and DMs that were in the latest indexed archive generated on or before this date, to see the archive as it was before later archives were indexed.\n\nFormats: 2024-01-15, 2024-01, \"last year\"\nExample: --as-of 2023-06"
+    )]
+    pub as_of: Option<String>,
 }
 
 #[derive(Args, Debug)]
+#[command(after_help = r#"Formats (global --format):
+  json, json-pretty, text   Pretty-printed JSON (default)
+  csv                       CSV with a header row
+  jsonl, compact            JSON Lines, one record per line
+
+DM transcripts (--transcript, ignores --format):
+  xf export dms --conversation <id> --transcript whatsapp
+  xf export dms --conversation <id> --transcript markdown -o chat.md
+  xf export dms --transcript html -o dms.html
+
+DM attachments (--with-media, -o is a directory):
+  xf export dms --with-media -o dms/                      # dms/dms.json + dms/media/
+  xf export dms --conversation <id> --transcript html --with-media -o chat/
+
+Calendar (.ics, ignores --format):
+  xf export calendar -o tweets.ics                       # one event per day with tweets
+  xf export calendar --min-engagement 100 -o tweets.ics  # plus popular tweets
+
+Source JSON (each record's original archive object under "raw"):
+  xf export tweets --include-raw -o tweets.json
+
+JSON Schema of the output (all targets when none is given):
+  xf export tweets --schema
+  xf export --schema
+"#)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ExportArgs {
     /// What to export
+    #[arg(required_unless_present = "schema", default_value = "all")]
     pub what: ExportTarget,
 
     /// Output file path (stdout if not specified)
     #[arg(long, short = 'o')]
     pub output: Option<PathBuf>,
 
-    /// Export format
-    #[arg(long, short = 'f', default_value = "json")]
-    pub format: ExportFormat,
-
     /// Limit number of items
     #[arg(long, short = 'n')]
     pub limit: Option<usize>,
+
+    /// Export only tweets from this date onwards (tweets and calendar only)
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    /// Export only tweets until this date (tweets and calendar only)
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<String>,
+
+    /// Export only tweets using this hashtag, with or without `#` (tweets and calendar only)
+    #[arg(long, value_name = "TAG")]
+    pub hashtag: Option<String>,
+
+    /// Export only this DM conversation (dms only; see `xf list conversations`)
+    #[arg(long, value_name = "ID")]
+    pub conversation: Option<String>,
+
+    /// Write DMs as a readable chat transcript instead of data (dms only)
+    #[arg(long, value_name = "STYLE")]
+    pub transcript: Option<crate::transcript::TranscriptStyle>,
+
+    /// Copy DM attachments from the archive into the `-o` directory (dms only)
+    #[arg(
+        long,
+        requires = "output",
+        long_help = "Copy DM attachments from the archive into the `-o` directory (dms only).\n\nThe export is written to <dir>/dms.<ext> and each attachment to <dir>/media/<message id>.<ext>, numbered when a message has several. Copied attachments' media URLs point at their files, so the directory is self-contained. The archive is --archive, or paths.archive from the config."
+    )]
+    pub with_media: bool,
+
+    /// Archive directory to copy DM attachments from (overrides config)
+    #[arg(long, requires = "with_media")]
+    pub archive: Option<PathBuf>,
+
+    /// Also add an event for each tweet with at least N likes + retweets (calendar only)
+    #[arg(long, value_name = "N")]
+    pub min_engagement: Option<i64>,
+
+    /// Include documents hidden with `xf hide`
+    #[arg(long)]
+    pub hidden: bool,
+
+    /// Add each record's original archive JSON as a `raw` field (tweets, likes, DMs, all)
+    #[arg(long, conflicts_with = "transcript")]
+    pub include_raw: bool,
+
+    /// Print the JSON Schema of this export's `--format json` output and exit
+    #[arg(long)]
+    pub schema: bool,
 }
 
 #[derive(Args, Debug)]
@@ -323,6 +1280,134 @@ pub struct DoctorArgs {
     /// Apply safe, idempotent repairs when issues are found
     #[arg(long)]
     pub fix: bool,
+
+    /// Re-link self-reply threads whose parent IDs lost precision in the export
+    #[arg(long)]
+    pub fix_threads: bool,
+
+    /// Skip the performance benchmarks, or compare them against reference
+    /// timings for this class of hardware
+    #[arg(long, value_enum)]
+    pub benchmark: Option<BenchmarkMode>,
+
+    /// Hardware tier for `--benchmark compare` (default: detected from CPU cores and RAM)
+    #[arg(long, value_enum)]
+    pub tier: Option<crate::doctor::HardwareTier>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BenchmarkMode {
+    /// Compare timings against bundled laptop/desktop/server baselines
+    Compare,
+    /// Don't run the performance benchmarks
+    Skip,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf selftest                               # Check this build before indexing a real archive
+  xf selftest --keep --workdir /tmp/xf-st   # Keep the synthetic archive, database, and index
+
+Runs in a scratch directory with its own database, index, and config; your
+data and settings are never read or touched.
+"#)]
+pub struct SelftestArgs {
+    /// Scratch directory for the run (default: a new directory under the system temp dir)
+    #[arg(long, value_name = "DIR")]
+    pub workdir: Option<PathBuf>,
+
+    /// Keep the scratch directory afterwards for inspection
+    #[arg(long)]
+    pub keep: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf backfill-dates                                   # Use the configured archive
+  xf backfill-dates --archive ~/my_twitter_data --dry-run
+
+Fixes the rows 'xf doctor' reports under "Epoch-zero dates" without a full re-index.
+"#)]
+pub struct BackfillDatesArgs {
+    /// Path to the X data archive directory (overrides config)
+    #[arg(long)]
+    pub archive: Option<PathBuf>,
+
+    /// Show how many dates would be repaired without writing them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf enrich --unshorten                       # Resolve every pending short link
+  xf enrich --unshorten --limit 500           # Resolve 500 now, the rest on a later run
+  xf enrich --unshorten --rate 0.5            # One request every two seconds
+  xf enrich --unshorten --retry-failed        # Try links that failed before again
+  xf enrich --unshorten --dry-run             # Count pending links without fetching
+
+Requests go only to the shorteners and the sites their links redirect to.
+Resolved links are stored and reapplied when the archive is re-indexed.
+"#)]
+pub struct EnrichArgs {
+    /// Follow short links to their final URLs
+    #[arg(long)]
+    pub unshorten: bool,
+
+    /// Maximum requests per second
+    #[arg(long, default_value = "2")]
+    pub rate: f64,
+
+    /// Resolve at most this many links in this run (0 = all)
+    #[arg(long, short = 'n', default_value = "0")]
+    pub limit: usize,
+
+    /// Also retry links that could not be reached before
+    #[arg(long)]
+    pub retry_failed: bool,
+
+    /// Seconds to wait for each link before giving up
+    #[arg(long, default_value = "10")]
+    pub timeout: u64,
+
+    /// Count the pending short links without fetching them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DuArgs {
+    /// Path to the X data archive directory (overrides config)
+    #[arg(long)]
+    pub archive: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf prune --embeddings                     # Rebuild later with: xf embed
+  xf prune --fts                            # Rebuild later with: xf doctor --fix
+  xf prune --index-segments                 # Rebuild later with: xf index --force
+  xf prune --embeddings --fts --dry-run     # Show what would be reclaimed
+
+Source tables (tweets, likes, DMs, ...) are never touched.
+"#)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct PruneArgs {
+    /// Delete embeddings and the vector index (semantic search needs them)
+    #[arg(long)]
+    pub embeddings: bool,
+
+    /// Empty the `SQLite` FTS tables (`xf list --match` needs them)
+    #[arg(long)]
+    pub fts: bool,
+
+    /// Delete the Tantivy search index segments (`xf search` needs them)
+    #[arg(long)]
+    pub index_segments: bool,
+
+    /// Show what would be deleted without deleting it
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Args, Debug)]
@@ -344,16 +1429,70 @@ pub struct ShellArgs {
     pub history_file: Option<PathBuf>,
 }
 
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf tui
+  xf tui "rust async"                       # Start with a search
+  xf tui --mode lexical
+
+Keys:
+  Enter      Search, or open the selected tweet's thread / DM's conversation
+  Tab        Cycle type filter (All, Tweets, Likes, Bookmarks, DMs, Grok)
+  ↑↓ PgUp PgDn Home End   Move through results or scroll a thread
+  /          Back to the search box
+  Esc        Close a thread or conversation
+  q          Quit (Ctrl-C anywhere)
+"#)]
+pub struct TuiArgs {
+    /// Search to run on start
+    pub query: Option<String>,
+
+    /// Search mode: hybrid (default), lexical, semantic, or auto
+    #[arg(long, short = 'm', default_value = "hybrid")]
+    pub mode: crate::hybrid::SearchMode,
+}
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+  xf serve --mcp                            # Answer MCP requests on stdin/stdout
+  xf serve --http                           # JSON API on http://127.0.0.1:8765
+  xf serve --http --addr 127.0.0.1:9000 --allow-origin http://localhost:5173
+
+MCP client configuration:
+  {"mcpServers": {"xf": {"command": "xf", "args": ["serve", "--mcp"]}}}
+"#)]
+pub struct ServeArgs {
+    /// Speak the Model Context Protocol on stdin/stdout, offering search,
+    /// `get_tweet`, `get_thread` and stats tools
+    #[arg(long, conflicts_with = "http")]
+    pub mcp: bool,
+
+    /// Serve a JSON HTTP API: /search, /tweet/{id}, /thread/{id}, /stats, /export
+    #[arg(long)]
+    pub http: bool,
+
+    /// Address for --http to listen on (the API has no authentication)
+    #[arg(long, default_value = "127.0.0.1:8765")]
+    pub addr: String,
+
+    /// Let a web UI served from this origin call the --http API (CORS)
+    #[arg(long, value_name = "ORIGIN", requires = "http")]
+    pub allow_origin: Option<String>,
+}
+
 #[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
 pub enum DataType {
     Tweet,
     Like,
+    Bookmark,
     Dm,
     Grok,
     Follower,
     Following,
     Block,
     Mute,
+    CommunityNote,
+    List,
     All,
 }
 
@@ -361,6 +1500,7 @@ pub enum DataType {
 pub enum SearchType {
     Tweet,
     Like,
+    Bookmark,
     Dm,
     Grok,
     All,
@@ -369,7 +1509,13 @@ pub enum SearchType {
 impl SearchType {
     #[must_use]
     pub fn all_content() -> Vec<Self> {
-        vec![Self::Tweet, Self::Like, Self::Dm, Self::Grok]
+        vec![
+            Self::Tweet,
+            Self::Like,
+            Self::Bookmark,
+            Self::Dm,
+            Self::Grok,
+        ]
     }
 }
 
@@ -379,12 +1525,15 @@ impl DataType {
         vec![
             Self::Tweet,
             Self::Like,
+            Self::Bookmark,
             Self::Dm,
             Self::Grok,
             Self::Follower,
             Self::Following,
             Self::Block,
             Self::Mute,
+            Self::CommunityNote,
+            Self::List,
         ]
     }
 }
@@ -395,6 +1544,9 @@ pub enum OutputFormat {
     Text,
     Json,
     JsonPretty,
+    /// JSON Lines: one record per line where `compact` already means JSON
+    /// Lines or for search results; compact JSON elsewhere
+    Jsonl,
     Compact,
     Csv,
 }
@@ -408,6 +1560,17 @@ pub enum SortOrder {
     Engagement,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum ConversationSort {
+    /// Most recent message first
+    #[default]
+    Recent,
+    /// Earliest first message first
+    Oldest,
+    /// Most messages first
+    Messages,
+}
+
 #[derive(ValueEnum, Clone, Debug, Default)]
 pub enum ListTarget {
     #[default]
@@ -420,6 +1583,11 @@ pub enum ListTarget {
     Following,
     Blocks,
     Mutes,
+    /// Community Notes (formerly Birdwatch) you wrote
+    #[value(alias = "notes")]
+    CommunityNotes,
+    /// Lists you created, belong to, or follow
+    Lists,
 }
 
 #[derive(ValueEnum, Clone, Debug, Default)]
@@ -430,6 +1598,8 @@ pub enum ExportTarget {
     Dms,
     Followers,
     Following,
+    Notes,
+    Calendar,
     All,
 }
 
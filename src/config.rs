@@ -17,20 +17,56 @@
 //! [search]
 //! default_limit = 20
 //! highlight = true
+//! engine = "tantivy"
+//!
+//! [storage]
+//! profile = "auto"
 //!
 //! [indexing]
 //! parallel = true
 //! buffer_size_mb = 256
+//! stopwords = "keep"
+//!
+//! [embeddings]
+//! types = ["tweet", "dm"]
+//! model = "hash"
+//!
+//! [llm]
+//! endpoint = "http://localhost:11434/v1"
+//! model = "llama3.1"
+//!
+//! [audit]
+//! enabled = true
 //!
 //! [output]
 //! format = "text"
 //! colors = true
+//! language = "auto"
 //! ```
 
+use crate::canonicalize::StopwordMode;
+use crate::search::SearchBackend;
+use crate::storage::StorageProfile;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tracing::{debug, info, warn};
 
+/// Environment variables read by [`Config::load`].
+pub(crate) const ENV_OVERRIDES: &[&str] = &[
+    "XF_DB",
+    "XF_INDEX",
+    "XF_ARCHIVE",
+    "XF_LIMIT",
+    "XF_LLM_ENDPOINT",
+    "XF_LLM_MODEL",
+    "XF_FORMAT",
+    "XF_NO_COLOR",
+    "XF_QUIET",
+    "XF_BUFFER_MB",
+    "XF_THREADS",
+    "XF_STORAGE_PROFILE",
+];
+
 /// Main configuration structure for xf.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
@@ -39,8 +75,16 @@ pub struct Config {
     pub paths: PathsConfig,
     /// Search behavior configuration.
     pub search: SearchConfig,
+    /// Database tuning configuration.
+    pub storage: StorageConfig,
     /// Indexing behavior configuration.
     pub indexing: IndexingConfig,
+    /// Embedding generation configuration.
+    pub embeddings: EmbeddingsConfig,
+    /// LLM endpoint configuration (used by `xf ask`).
+    pub llm: LlmConfig,
+    /// Audit log configuration (used by `xf audit`).
+    pub audit: AuditConfig,
     /// Output formatting configuration.
     pub output: OutputConfig,
 }
@@ -80,6 +124,27 @@ pub struct SearchConfig {
 
     /// Cache size for search results (number of queries).
     pub cache_size: usize,
+
+    /// Directory holding the cross-encoder used by `--rerank`
+    /// (`model.onnx` + `tokenizer.json`; default: `<data dir>/models/reranker`).
+    pub rerank_model: Option<PathBuf>,
+
+    /// Number of top candidates `--rerank` re-scores.
+    pub rerank_candidates: usize,
+
+    /// Keyword search engine: `tantivy` (default) or `sqlite`, which searches
+    /// the database's FTS tables and skips building the Tantivy index.
+    pub engine: SearchBackend,
+}
+
+/// Database tuning configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// `SQLite` tuning profile: auto, default, low-memory, or fast.
+    /// `auto` picks one from system memory.
+    /// Environment variable: `XF_STORAGE_PROFILE`
+    pub profile: StorageProfile,
 }
 
 /// Indexing behavior configuration.
@@ -89,14 +154,72 @@ pub struct IndexingConfig {
     /// Enable parallel parsing (uses all CPU cores).
     pub parallel: bool,
 
-    /// Memory buffer size for indexing (in MB).
+    /// Memory budget for the search index writer (in MB, at least 15).
     pub buffer_size_mb: usize,
 
+    /// Commit the search index every N documents while indexing (0 = only at the end).
+    pub commit_every: usize,
+
     /// Number of threads for parallel operations (0 = auto).
     pub threads: usize,
 
     /// Skip specific data types during indexing.
     pub skip_types: Vec<String>,
+
+    /// Stopword handling for embeddings: keep or remove.
+    /// Changes take effect for stored documents on the next `xf index`.
+    pub stopwords: StopwordMode,
+}
+
+/// Document types that can carry embeddings.
+pub const EMBEDDING_DOC_TYPES: &[&str] = &["tweet", "like", "bookmark", "dm", "grok"];
+
+/// Embedding generation configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingsConfig {
+    /// Document types to embed for semantic search (tweet, like, bookmark, dm, grok).
+    /// Types left out are searchable lexically only.
+    pub types: Vec<String>,
+
+    /// Embedding model: `hash` (built in) or a local ONNX model directory,
+    /// by path or by name under `<data dir>/models/embeddings`.
+    /// Changing it re-embeds everything on the next `xf embed`.
+    pub model: String,
+}
+
+/// LLM endpoint configuration.
+///
+/// Unset by default: xf never contacts a model server unless an endpoint is
+/// configured. The API key, if any, is read from `XF_LLM_API_KEY` and is
+/// never written to the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlmConfig {
+    /// Base URL of an OpenAI-compatible API (e.g. `http://localhost:11434/v1`).
+    /// Environment variable: `XF_LLM_ENDPOINT`
+    pub endpoint: Option<String>,
+
+    /// Model name sent with each request (empty = server default).
+    /// Environment variable: `XF_LLM_MODEL`
+    pub model: String,
+
+    /// Request timeout in seconds.
+    pub timeout_secs: u64,
+
+    /// Maximum characters of archive text sent as context.
+    pub max_context_chars: usize,
+}
+
+/// Audit log configuration.
+///
+/// Off by default. When enabled, commands that read DMs or export data
+/// record what they accessed in the database's `audit_log` table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Record DM reads and exports.
+    pub enabled: bool,
 }
 
 /// Output formatting configuration.
@@ -114,6 +237,10 @@ pub struct OutputConfig {
 
     /// Show timing information for operations.
     pub timings: bool,
+
+    /// Language for human-readable output: auto, en, es, or ja.
+    /// `auto` follows `LC_ALL`, `LC_MESSAGES` or `LANG`.
+    pub language: String,
 }
 
 impl Default for SearchConfig {
@@ -124,6 +251,9 @@ impl Default for SearchConfig {
             fuzzy: false,
             min_score: 0.0,
             cache_size: 1000,
+            rerank_model: None,
+            rerank_candidates: crate::rerank::DEFAULT_RERANK_CANDIDATES,
+            engine: SearchBackend::Tantivy,
         }
     }
 }
@@ -133,8 +263,41 @@ impl Default for IndexingConfig {
         Self {
             parallel: true,
             buffer_size_mb: 256,
+            commit_every: 100_000,
             threads: 0, // Auto-detect
             skip_types: vec![],
+            stopwords: StopwordMode::Keep,
+        }
+    }
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            types: EMBEDDING_DOC_TYPES
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            model: crate::embedding_model::HASH_MODEL.to_string(),
+        }
+    }
+}
+
+impl EmbeddingsConfig {
+    /// Whether documents of this type (`tweet`, `like`, `dm`, `grok`) are embedded.
+    #[must_use]
+    pub fn includes(&self, doc_type: &str) -> bool {
+        self.types.iter().any(|t| t.eq_ignore_ascii_case(doc_type))
+    }
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            model: String::new(),
+            timeout_secs: 120,
+            max_context_chars: 12_000,
         }
     }
 }
@@ -146,6 +309,7 @@ impl Default for OutputConfig {
             colors: true,
             quiet: false,
             timings: false,
+            language: "auto".to_string(),
         }
     }
 }
@@ -213,6 +377,16 @@ impl Config {
         dirs::config_dir().map(|p| p.join("xf").join("config.toml"))
     }
 
+    /// Names of the `XF_*` environment variables currently overriding settings.
+    #[must_use]
+    pub fn env_overrides() -> Vec<&'static str> {
+        ENV_OVERRIDES
+            .iter()
+            .copied()
+            .filter(|name| std::env::var_os(name).is_some())
+            .collect()
+    }
+
     /// Apply environment variable overrides.
     fn apply_env_overrides(&mut self) {
         // Path overrides
@@ -233,6 +407,14 @@ impl Config {
             }
         }
 
+        // LLM overrides
+        if let Ok(endpoint) = std::env::var("XF_LLM_ENDPOINT") {
+            self.llm.endpoint = Some(endpoint);
+        }
+        if let Ok(model) = std::env::var("XF_LLM_MODEL") {
+            self.llm.model = model;
+        }
+
         // Output overrides
         if let Ok(format) = std::env::var("XF_FORMAT") {
             self.output.format = format;
@@ -244,6 +426,14 @@ impl Config {
             self.output.quiet = true;
         }
 
+        // Storage overrides
+        if let Ok(profile) = std::env::var("XF_STORAGE_PROFILE") {
+            match profile.parse() {
+                Ok(profile) => self.storage.profile = profile,
+                Err(e) => warn!("Ignoring XF_STORAGE_PROFILE: {e}"),
+            }
+        }
+
         // Indexing overrides
         if let Ok(buffer) = std::env::var("XF_BUFFER_MB") {
             if let Ok(n) = buffer.parse() {
@@ -261,6 +451,12 @@ impl Config {
         self.paths.db = self.paths.db.clone().map(expand_tilde_path);
         self.paths.index = self.paths.index.clone().map(expand_tilde_path);
         self.paths.archive = self.paths.archive.clone().map(expand_tilde_path);
+        self.search.rerank_model = self.search.rerank_model.clone().map(expand_tilde_path);
+        if self.embeddings.model.starts_with('~') {
+            self.embeddings.model = expand_tilde_path(PathBuf::from(&self.embeddings.model))
+                .to_string_lossy()
+                .into_owned();
+        }
     }
 
     /// Merge another config into this one (other takes precedence).
@@ -282,20 +478,46 @@ impl Config {
         self.search.fuzzy = other.search.fuzzy;
         self.search.min_score = other.search.min_score;
         self.search.cache_size = other.search.cache_size;
+        if other.search.rerank_model.is_some() {
+            self.search.rerank_model = other.search.rerank_model;
+        }
+        self.search.rerank_candidates = other.search.rerank_candidates;
+        self.search.engine = other.search.engine;
+
+        // Storage
+        self.storage.profile = other.storage.profile;
 
         // Indexing
         self.indexing.parallel = other.indexing.parallel;
         self.indexing.buffer_size_mb = other.indexing.buffer_size_mb;
+        self.indexing.commit_every = other.indexing.commit_every;
         self.indexing.threads = other.indexing.threads;
         if !other.indexing.skip_types.is_empty() {
             self.indexing.skip_types = other.indexing.skip_types;
         }
+        self.indexing.stopwords = other.indexing.stopwords;
+
+        // Embeddings
+        self.embeddings.types = other.embeddings.types;
+        self.embeddings.model = other.embeddings.model;
+
+        // LLM
+        if other.llm.endpoint.is_some() {
+            self.llm.endpoint = other.llm.endpoint;
+        }
+        self.llm.model = other.llm.model;
+        self.llm.timeout_secs = other.llm.timeout_secs;
+        self.llm.max_context_chars = other.llm.max_context_chars;
+
+        // Audit
+        self.audit.enabled = other.audit.enabled;
 
         // Output
         self.output.format = other.output.format;
         self.output.colors = other.output.colors;
         self.output.quiet = other.output.quiet;
         self.output.timings = other.output.timings;
+        self.output.language = other.output.language;
     }
 
     /// Get the database path, using defaults if not configured.
@@ -371,6 +593,31 @@ mod tests {
         assert_eq!(config.search.default_limit, 20);
         assert!(config.indexing.parallel);
         assert!(config.output.colors);
+        assert_eq!(config.embeddings.types, EMBEDDING_DOC_TYPES);
+        assert!(config.llm.endpoint.is_none());
+        assert!(!config.audit.enabled);
+        assert_eq!(config.storage.profile, StorageProfile::Auto);
+    }
+
+    #[test]
+    fn test_storage_profile_from_toml() {
+        let config: Config = toml::from_str("[storage]\nprofile = \"low-memory\"\n").unwrap();
+        assert_eq!(config.storage.profile, StorageProfile::LowMemory);
+        assert!(toml::from_str::<Config>("[storage]\nprofile = \"huge\"\n").is_err());
+    }
+
+    #[test]
+    fn test_embeddings_types_from_toml() {
+        let config: Config = toml::from_str("[embeddings]\ntypes = [\"tweet\", \"DM\"]\n").unwrap();
+        assert!(config.embeddings.includes("tweet"));
+        assert!(config.embeddings.includes("dm"));
+        assert!(!config.embeddings.includes("like"));
+        assert!(!config.embeddings.includes("grok"));
+        assert_eq!(config.embeddings.model, "hash");
+
+        let config: Config = toml::from_str("[embeddings]\nmodel = \"e5-small\"\n").unwrap();
+        assert_eq!(config.embeddings.model, "e5-small");
+        assert_eq!(config.embeddings.types, EMBEDDING_DOC_TYPES);
     }
 
     #[test]
@@ -85,6 +85,12 @@ fn parse_human_date_with_base(
         return Ok(parsed);
     }
 
+    // chrono-english slices words by byte and panics inside multi-byte
+    // characters; none of its vocabulary is outside ASCII anyway
+    if !normalized.is_ascii() {
+        return Err(anyhow!("Could not parse date expression: '{trimmed}'"));
+    }
+
     match parse_date_string(&normalized, base, Dialect::Us) {
         Ok(dt) => {
             debug!(input = trimmed, "Parsed natural language date");
@@ -135,6 +141,53 @@ pub fn parse_date_flexible(input: &str, prefer_end: bool) -> Result<DateTime<Utc
     Err(anyhow!("Could not parse '{input}' as date"))
 }
 
+/// Parse a period expression into an inclusive date range.
+///
+/// Accepts a bare year (`2019`), anything [`parse_human_date`] understands
+/// (`2023-05`, `Q1 2024`, `last month`), or two expressions joined by `..`
+/// (`2019-03..2020-06`).
+///
+/// # Errors
+/// Returns an error if either side cannot be parsed or the range is inverted.
+pub fn parse_period(input: &str) -> Result<ParsedDate> {
+    let trimmed = input.trim();
+
+    if let Some((from, to)) = trimmed.split_once("..") {
+        let start = parse_period(from)?.start();
+        let end = parse_period(to)?.end();
+        if end < start {
+            return Err(anyhow!("Period '{trimmed}' ends before it starts"));
+        }
+        return Ok(ParsedDate::Range { start, end });
+    }
+
+    if let Some(parsed) = try_parse_year(trimmed) {
+        debug!(input = trimmed, "Parsed year range");
+        return Ok(parsed);
+    }
+
+    if is_strict_iso_date(trimmed) {
+        if let Some(parsed) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| range_for_dates(date, date))
+        {
+            return Ok(parsed);
+        }
+    }
+
+    parse_human_date(trimmed, false)
+}
+
+fn try_parse_year(input: &str) -> Option<ParsedDate> {
+    if input.len() != 4 || !input.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let year = input.parse::<i32>().ok()?;
+    let start = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single()?;
+    let end = Utc.with_ymd_and_hms(year, 12, 31, 23, 59, 59).single()?;
+    Some(ParsedDate::Range { start, end })
+}
+
 fn is_strict_iso_date(input: &str) -> bool {
     NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d").is_ok()
 }
@@ -596,6 +649,12 @@ mod tests {
         );
     }
 
+    #[test]
+    fn non_ascii_expression_is_an_error_not_a_panic() {
+        assert!(parse_human_date("𐌭", false).is_err());
+        assert!(parse_date_flexible("1 é", false).is_err());
+    }
+
     #[test]
     fn parse_iso_datetime_rfc3339() {
         let parsed = parse_date_flexible("2024-01-15T12:34:56Z", false).expect("parsed rfc3339");
@@ -843,4 +902,28 @@ mod tests {
         let message = format!("{err}");
         assert!(message.contains("not-a-real-date"));
     }
+
+    #[test]
+    fn parse_period_year_and_range() {
+        let year = parse_period("2019").expect("parsed year");
+        assert_eq!(
+            year.start(),
+            Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).single().unwrap()
+        );
+        assert_eq!(
+            year.end(),
+            Utc.with_ymd_and_hms(2019, 12, 31, 23, 59, 59)
+                .single()
+                .unwrap()
+        );
+
+        let range = parse_period("2019-03..2020").expect("parsed range");
+        assert_eq!(
+            range.start(),
+            Utc.with_ymd_and_hms(2019, 3, 1, 0, 0, 0).single().unwrap()
+        );
+        assert_eq!(range.end(), year.end() + Duration::days(366));
+
+        assert!(parse_period("2021..2020").is_err());
+    }
 }
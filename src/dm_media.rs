@@ -0,0 +1,265 @@
+//! DM attachments for self-contained exports (`xf export dms --with-media`).
+//!
+//! X archives keep DM attachments in `data/direct_messages_media/` and
+//! `data/direct_messages_group_media/`, named `<message id>-<file name>`
+//! after the last segment of the message's media URL. This module finds
+//! those files for exported messages and copies them into the export
+//! directory under names derived from the message ID alone.
+
+use crate::model::DirectMessage;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Archive folders holding DM attachments, relative to the archive root.
+pub const MEDIA_DIRS: &[&str] = &[
+    "data/direct_messages_media",
+    "data/direct_messages_group_media",
+];
+
+/// Folder inside the export directory that receives the copies.
+pub const EXPORT_MEDIA_DIR: &str = "media";
+
+/// One attachment copied into an export.
+#[derive(Debug, Clone, Serialize)]
+pub struct CopiedMedia {
+    pub message_id: String,
+    pub url: String,
+    /// Path relative to the export directory.
+    pub file: String,
+}
+
+/// What [`copy_media`] did.
+#[derive(Debug, Default)]
+pub struct MediaCopySummary {
+    pub copied: Vec<CopiedMedia>,
+    /// Attachments with no file in the archive: (message id, media URL).
+    pub missing: Vec<(String, String)>,
+    pub bytes: u64,
+}
+
+impl MediaCopySummary {
+    /// Exported path for each copied media URL, keyed by (message id, URL).
+    #[must_use]
+    pub fn local_paths(&self) -> HashMap<(&str, &str), &str> {
+        self.copied
+            .iter()
+            .map(|m| ((m.message_id.as_str(), m.url.as_str()), m.file.as_str()))
+            .collect()
+    }
+}
+
+/// Name of a media URL's file in the archive: `<message id>-<last segment>`.
+///
+/// Query strings and fragments are ignored, so video URLs such as
+/// `.../vid/1280x720/clip.mp4?tag=1` map to `<id>-clip.mp4`.
+#[must_use]
+pub fn archive_file_name(message_id: &str, url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let name = path.trim_end_matches('/').rsplit('/').next()?;
+    (!name.is_empty() && !name.contains(':')).then(|| format!("{message_id}-{name}"))
+}
+
+/// Name for the `index`th of `count` attachments of a message in an export.
+///
+/// A single attachment is `<message id>.<ext>`; several are numbered from 1.
+#[must_use]
+pub fn export_file_name(message_id: &str, index: usize, count: usize, source: &Path) -> String {
+    let stem = if count > 1 {
+        format!("{message_id}-{}", index + 1)
+    } else {
+        message_id.to_string()
+    };
+    match source.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{stem}.{}", ext.to_ascii_lowercase()),
+        None => stem,
+    }
+}
+
+/// DM attachment files found in an archive, by file name.
+#[derive(Debug, Default)]
+pub struct MediaIndex {
+    files: HashMap<String, PathBuf>,
+}
+
+impl MediaIndex {
+    /// List the DM media folders under `archive`. Missing folders are
+    /// skipped, as in archives imported with `--no-media`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a media folder exists but cannot be read.
+    pub fn scan(archive: &Path) -> Result<Self> {
+        let mut files = HashMap::new();
+        for dir in MEDIA_DIRS {
+            let dir = archive.join(dir);
+            if !dir.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(&dir)
+                .with_context(|| format!("Failed to read {}", dir.display()))?
+            {
+                let entry = entry?;
+                if entry.file_type()?.is_file() {
+                    files.insert(
+                        entry.file_name().to_string_lossy().into_owned(),
+                        entry.path(),
+                    );
+                }
+            }
+        }
+        Ok(Self { files })
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The archive file holding one of a message's attachments.
+    #[must_use]
+    pub fn find(&self, message_id: &str, url: &str) -> Option<&Path> {
+        archive_file_name(message_id, url)
+            .and_then(|name| self.files.get(&name))
+            .map(PathBuf::as_path)
+    }
+}
+
+/// Copy the attachments of `messages` into `<export_dir>/media/`.
+///
+/// # Errors
+///
+/// Returns an error if the media folder cannot be created or a file cannot
+/// be copied.
+pub fn copy_media(
+    index: &MediaIndex,
+    messages: &[DirectMessage],
+    export_dir: &Path,
+) -> Result<MediaCopySummary> {
+    let media_dir = export_dir.join(EXPORT_MEDIA_DIR);
+    let mut summary = MediaCopySummary::default();
+
+    for dm in messages {
+        for (i, url) in dm.media_urls.iter().enumerate() {
+            let Some(source) = index.find(&dm.id, url) else {
+                summary.missing.push((dm.id.clone(), url.clone()));
+                continue;
+            };
+            if summary.copied.is_empty() {
+                std::fs::create_dir_all(&media_dir)
+                    .with_context(|| format!("Failed to create {}", media_dir.display()))?;
+            }
+            let name = export_file_name(&dm.id, i, dm.media_urls.len(), source);
+            summary.bytes += std::fs::copy(source, media_dir.join(&name))
+                .with_context(|| format!("Failed to copy {}", source.display()))?;
+            summary.copied.push(CopiedMedia {
+                message_id: dm.id.clone(),
+                url: url.clone(),
+                file: format!("{EXPORT_MEDIA_DIR}/{name}"),
+            });
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Point each copied attachment's media URL at its exported file.
+///
+/// URLs with no copied file are left as they were.
+pub fn relink(messages: &mut [DirectMessage], summary: &MediaCopySummary) {
+    let local = summary.local_paths();
+    for dm in messages {
+        for url in &mut dm.media_urls {
+            if let Some(file) = local.get(&(dm.id.as_str(), url.as_str())) {
+                *url = (*file).to_string();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn dm(id: &str, media_urls: &[&str]) -> DirectMessage {
+        DirectMessage {
+            id: id.to_string(),
+            conversation_id: "1-2".to_string(),
+            sender_id: "1".to_string(),
+            recipient_id: "2".to_string(),
+            text: String::new(),
+            created_at: Utc::now(),
+            urls: Vec::new(),
+            media_urls: media_urls.iter().map(|url| (*url).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn archive_file_name_uses_last_url_segment() {
+        assert_eq!(
+            archive_file_name(
+                "100",
+                "https://ton.twitter.com/1.1/ton/data/dm/100/200/AbC.jpg"
+            )
+            .as_deref(),
+            Some("100-AbC.jpg")
+        );
+        assert_eq!(
+            archive_file_name(
+                "100",
+                "https://video.twimg.com/dm_video/1/vid/1280x720/clip.mp4?tag=1"
+            )
+            .as_deref(),
+            Some("100-clip.mp4")
+        );
+        assert_eq!(archive_file_name("100", "https://"), None);
+    }
+
+    #[test]
+    fn export_file_name_numbers_multiple_attachments() {
+        let source = Path::new("100-AbC.JPG");
+        assert_eq!(export_file_name("100", 0, 1, source), "100.jpg");
+        assert_eq!(export_file_name("100", 1, 2, source), "100-2.jpg");
+        assert_eq!(export_file_name("100", 0, 1, Path::new("100-raw")), "100");
+    }
+
+    #[test]
+    fn copy_media_copies_found_files_and_relinks() {
+        let archive = TempDir::new().unwrap();
+        let media = archive.path().join(MEDIA_DIRS[0]);
+        std::fs::create_dir_all(&media).unwrap();
+        std::fs::write(media.join("100-AbC.jpg"), b"jpeg").unwrap();
+        let export = TempDir::new().unwrap();
+
+        let mut messages = vec![
+            dm(
+                "100",
+                &["https://ton.twitter.com/1.1/ton/data/dm/100/200/AbC.jpg"],
+            ),
+            dm(
+                "101",
+                &["https://ton.twitter.com/1.1/ton/data/dm/101/201/gone.png"],
+            ),
+        ];
+        let index = MediaIndex::scan(archive.path()).unwrap();
+        let summary = copy_media(&index, &messages, export.path()).unwrap();
+
+        assert_eq!(summary.copied.len(), 1);
+        assert_eq!(summary.bytes, 4);
+        assert_eq!(
+            summary.missing,
+            vec![("101".to_string(), messages[1].media_urls[0].clone())]
+        );
+        assert_eq!(
+            std::fs::read(export.path().join("media/100.jpg")).unwrap(),
+            b"jpeg"
+        );
+
+        relink(&mut messages, &summary);
+        assert_eq!(messages[0].media_urls, vec!["media/100.jpg"]);
+        assert!(messages[1].media_urls[0].starts_with("https://"));
+    }
+}
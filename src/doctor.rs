@@ -557,8 +557,12 @@ fn percentile(sorted: &[f64], pct: usize) -> f64 {
 ///
 /// Opens the index multiple times and measures load latency.
 #[must_use]
-#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
 pub fn benchmark_index_load(index_path: &Path) -> HealthCheck {
+    index_load_check(&measure_index_load(index_path))
+}
+
+/// Open the index a few times, returning load latency or the open error.
+fn measure_index_load(index_path: &Path) -> Result<LatencyStats, String> {
     let mut durations = Vec::with_capacity(3);
 
     // Fewer iterations for load test since it's more expensive
@@ -569,19 +573,26 @@ pub fn benchmark_index_load(index_path: &Path) -> HealthCheck {
 
         match result {
             Ok(_) => durations.push(elapsed_ms),
-            Err(e) => {
-                return HealthCheck {
-                    category: CheckCategory::Performance,
-                    name: "Index Load Time".to_string(),
-                    status: CheckStatus::Error,
-                    message: format!("Failed to load index: {e}"),
-                    suggestion: Some("Verify index exists and is not corrupted".to_string()),
-                };
-            }
+            Err(e) => return Err(e.to_string()),
         }
     }
 
-    let latency_stats = LatencyStats::from_durations(&mut durations);
+    Ok(LatencyStats::from_durations(&mut durations))
+}
+
+fn index_load_check(load: &Result<LatencyStats, String>) -> HealthCheck {
+    let latency_stats = match load {
+        Ok(latency_stats) => latency_stats,
+        Err(e) => {
+            return HealthCheck {
+                category: CheckCategory::Performance,
+                name: BenchmarkKind::IndexLoad.name().to_string(),
+                status: CheckStatus::Error,
+                message: format!("Failed to load index: {e}"),
+                suggestion: Some("Verify index exists and is not corrupted".to_string()),
+            };
+        }
+    };
     let median = latency_stats.p50_ms;
 
     let (check_status, suggestion) = if median < thresholds::LOAD_ACCEPTABLE_MS {
@@ -600,121 +611,148 @@ pub fn benchmark_index_load(index_path: &Path) -> HealthCheck {
 
     HealthCheck {
         category: CheckCategory::Performance,
-        name: "Index Load Time".to_string(),
+        name: BenchmarkKind::IndexLoad.name().to_string(),
         status: check_status,
         message: format!("{median:.0}ms"),
         suggestion,
     }
 }
 
-/// Benchmark simple single-word queries.
-#[must_use]
-pub fn benchmark_simple_query(engine: &SearchEngine) -> HealthCheck {
-    let test_queries = ["the", "and", "test", "hello", "world"];
-    let mut durations = Vec::with_capacity(thresholds::BENCHMARK_ITERATIONS * test_queries.len());
+const SIMPLE_QUERIES: &[&str] = &["the", "and", "test", "hello", "world"];
+const PHRASE_QUERIES: &[&str] = &["\"hello world\"", "\"the quick\"", "\"test message\""];
+const COMPLEX_QUERIES: &[&str] = &[
+    "hello AND world",
+    "test OR example",
+    "NOT spam",
+    "(hello OR hi) AND world",
+];
+const FTS5_QUERIES: &[&str] = &["the", "test", "hello", "and"];
+
+/// Run each query [`thresholds::BENCHMARK_ITERATIONS`] times and collect latencies.
+fn time_queries(queries: &[&str], mut run: impl FnMut(&str)) -> LatencyStats {
+    let mut durations = Vec::with_capacity(thresholds::BENCHMARK_ITERATIONS * queries.len());
 
-    for query in test_queries {
+    for query in queries {
         for _ in 0..thresholds::BENCHMARK_ITERATIONS {
             let start = Instant::now();
-            let _ = engine.search(query, None, 10);
+            run(query);
             durations.push(start.elapsed().as_secs_f64() * 1000.0);
         }
     }
 
-    let latency = LatencyStats::from_durations(&mut durations);
-    let (check_status, suggestion) = evaluate_query_latency(&latency);
+    LatencyStats::from_durations(&mut durations)
+}
+
+fn time_engine_queries(engine: &SearchEngine, queries: &[&str]) -> LatencyStats {
+    time_queries(queries, |query| {
+        let _ = engine.search(query, None, 10);
+    })
+}
+
+fn time_fts5_queries(storage: &crate::Storage) -> LatencyStats {
+    // Use search_tweets which queries via FTS5
+    time_queries(FTS5_QUERIES, |query| {
+        let _ = storage.search_tweets(query, 10);
+    })
+}
+
+fn query_latency_check(kind: BenchmarkKind, latency: &LatencyStats) -> HealthCheck {
+    let (check_status, suggestion) = evaluate_query_latency(latency);
 
     HealthCheck {
         category: CheckCategory::Performance,
-        name: "Simple Query Latency".to_string(),
+        name: kind.name().to_string(),
         status: check_status,
         message: latency.format_summary(),
         suggestion,
     }
 }
 
+/// Benchmark simple single-word queries.
+#[must_use]
+pub fn benchmark_simple_query(engine: &SearchEngine) -> HealthCheck {
+    query_latency_check(
+        BenchmarkKind::SimpleQuery,
+        &time_engine_queries(engine, SIMPLE_QUERIES),
+    )
+}
+
 /// Benchmark phrase queries (multi-word, quoted).
 #[must_use]
 pub fn benchmark_phrase_query(engine: &SearchEngine) -> HealthCheck {
-    let test_queries = ["\"hello world\"", "\"the quick\"", "\"test message\""];
-    let mut durations = Vec::with_capacity(thresholds::BENCHMARK_ITERATIONS * test_queries.len());
-
-    for query in test_queries {
-        for _ in 0..thresholds::BENCHMARK_ITERATIONS {
-            let start = Instant::now();
-            let _ = engine.search(query, None, 10);
-            durations.push(start.elapsed().as_secs_f64() * 1000.0);
-        }
-    }
-
-    let latency = LatencyStats::from_durations(&mut durations);
-    let (check_status, suggestion) = evaluate_query_latency(&latency);
-
-    HealthCheck {
-        category: CheckCategory::Performance,
-        name: "Phrase Query Latency".to_string(),
-        status: check_status,
-        message: latency.format_summary(),
-        suggestion,
-    }
+    query_latency_check(
+        BenchmarkKind::PhraseQuery,
+        &time_engine_queries(engine, PHRASE_QUERIES),
+    )
 }
 
 /// Benchmark complex boolean queries.
 #[must_use]
 pub fn benchmark_complex_query(engine: &SearchEngine) -> HealthCheck {
-    let test_queries = [
-        "hello AND world",
-        "test OR example",
-        "NOT spam",
-        "(hello OR hi) AND world",
-    ];
-    let mut durations = Vec::with_capacity(thresholds::BENCHMARK_ITERATIONS * test_queries.len());
-
-    for query in test_queries {
-        for _ in 0..thresholds::BENCHMARK_ITERATIONS {
-            let start = Instant::now();
-            let _ = engine.search(query, None, 10);
-            durations.push(start.elapsed().as_secs_f64() * 1000.0);
-        }
-    }
-
-    let latency = LatencyStats::from_durations(&mut durations);
-    let (check_status, suggestion) = evaluate_query_latency(&latency);
-
-    HealthCheck {
-        category: CheckCategory::Performance,
-        name: "Complex Query Latency".to_string(),
-        status: check_status,
-        message: latency.format_summary(),
-        suggestion,
-    }
+    query_latency_check(
+        BenchmarkKind::ComplexQuery,
+        &time_engine_queries(engine, COMPLEX_QUERIES),
+    )
 }
 
 /// Benchmark FTS5 queries via `SQLite`.
 #[must_use]
 pub fn benchmark_fts5_query(storage: &crate::Storage) -> HealthCheck {
-    let test_queries = ["the", "test", "hello", "and"];
-    let mut durations = Vec::with_capacity(thresholds::BENCHMARK_ITERATIONS * test_queries.len());
+    query_latency_check(BenchmarkKind::Fts5Query, &time_fts5_queries(storage))
+}
 
-    for query in test_queries {
-        for _ in 0..thresholds::BENCHMARK_ITERATIONS {
-            let start = Instant::now();
-            // Use search_tweets which queries via FTS5
-            let _ = storage.search_tweets(query, 10);
-            durations.push(start.elapsed().as_secs_f64() * 1000.0);
-        }
-    }
+/// Timing of a brute-force top-k scan over the stored embeddings.
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticTiming {
+    pub latency: LatencyStats,
+    /// Number of vectors scanned per query.
+    pub vectors: usize,
+    /// Whether the scan ran over the memory-mapped `vector.idx` sidecar
+    /// rather than vectors decoded from `SQLite`.
+    pub mapped: bool,
+}
 
-    let latency = LatencyStats::from_durations(&mut durations);
-    let (check_status, suggestion) = evaluate_query_latency(&latency);
+/// Time top-k semantic scans without touching the index directory.
+///
+/// Unlike [`crate::vector::SemanticIndex::open`], a missing or stale sidecar
+/// is not rebuilt: the scan falls back to `SQLite` so the timing shows what
+/// searches pay until the sidecar is fixed. Returns `None` when there are no
+/// embeddings.
+#[must_use]
+pub fn measure_semantic_query(
+    index_path: &Path,
+    storage: &crate::Storage,
+) -> Option<SemanticTiming> {
+    use crate::vector::{MmapVectorIndex, SemanticIndex, VECTOR_INDEX_FILENAME, VectorIndex};
 
-    HealthCheck {
-        category: CheckCategory::Performance,
-        name: "FTS5 Query Latency".to_string(),
-        status: check_status,
-        message: latency.format_summary(),
-        suggestion,
+    let expected = usize::try_from(storage.embedding_count().ok()?).unwrap_or(usize::MAX);
+    if expected == 0 {
+        return None;
     }
+
+    let index = match MmapVectorIndex::open(&index_path.join(VECTOR_INDEX_FILENAME)) {
+        Ok(index) if index.len() == expected => SemanticIndex::Mapped(index),
+        _ => SemanticIndex::InMemory(VectorIndex::load_from_storage(storage).ok()?),
+    };
+    let dimension = index.dimension();
+    if dimension == 0 {
+        return None;
+    }
+
+    // Any unit vector costs the same to score against every record
+    #[allow(clippy::cast_precision_loss)]
+    let component = 1.0 / (dimension as f32).sqrt();
+    let query = vec![component; dimension];
+
+    let latency = time_queries(&["query"], |_| {
+        let _ = index.search_top_k(&query, 10, None);
+    });
+
+    Some(SemanticTiming {
+        latency,
+        vectors: index.len(),
+        mapped: matches!(index, SemanticIndex::Mapped(_)),
+    })
 }
 
 /// Evaluate query latency against thresholds.
@@ -742,11 +780,14 @@ fn evaluate_query_latency(latency: &LatencyStats) -> (CheckStatus, Option<String
 /// Run all performance benchmarks.
 ///
 /// Returns a vector of health checks covering index load time,
-/// simple/phrase/complex query latencies, and FTS5 performance.
+/// simple/phrase/complex query latencies, and FTS5 performance. With a
+/// `baseline` tier, semantic search is timed too and every timing is compared
+/// against that tier's reference numbers (`xf doctor --benchmark compare`).
 pub fn run_performance_benchmarks(
     index_path: &Path,
     engine: &SearchEngine,
     storage: &crate::Storage,
+    baseline: Option<HardwareTier>,
 ) -> Vec<HealthCheck> {
     let mut checks = Vec::with_capacity(5);
 
@@ -754,29 +795,267 @@ pub fn run_performance_benchmarks(
 
     // Index load time
     debug!("Benchmarking index load time");
-    checks.push(benchmark_index_load(index_path));
+    let load = measure_index_load(index_path);
+    checks.push(index_load_check(&load));
 
     // Simple queries
     debug!("Benchmarking simple queries");
-    checks.push(benchmark_simple_query(engine));
+    let simple = time_engine_queries(engine, SIMPLE_QUERIES);
+    checks.push(query_latency_check(BenchmarkKind::SimpleQuery, &simple));
 
     // Phrase queries
     debug!("Benchmarking phrase queries");
-    checks.push(benchmark_phrase_query(engine));
+    let phrase = time_engine_queries(engine, PHRASE_QUERIES);
+    checks.push(query_latency_check(BenchmarkKind::PhraseQuery, &phrase));
 
     // Complex boolean queries
     debug!("Benchmarking complex queries");
-    checks.push(benchmark_complex_query(engine));
+    let complex = time_engine_queries(engine, COMPLEX_QUERIES);
+    checks.push(query_latency_check(BenchmarkKind::ComplexQuery, &complex));
 
     // FTS5 queries
     debug!("Benchmarking FTS5 queries");
-    checks.push(benchmark_fts5_query(storage));
+    let fts5 = time_fts5_queries(storage);
+    checks.push(query_latency_check(BenchmarkKind::Fts5Query, &fts5));
+
+    if let Some(tier) = baseline {
+        debug!("Benchmarking semantic queries");
+        let semantic = measure_semantic_query(index_path, storage);
+
+        let mut timings = vec![
+            (BenchmarkKind::SimpleQuery, simple),
+            (BenchmarkKind::PhraseQuery, phrase),
+            (BenchmarkKind::ComplexQuery, complex),
+            (BenchmarkKind::Fts5Query, fts5),
+        ];
+        if let Ok(load) = load {
+            timings.push((BenchmarkKind::IndexLoad, load));
+        }
+        checks.extend(compare_with_baseline(tier, &timings, semantic.as_ref()));
+    }
 
     info!("Performance benchmarks complete: {} checks", checks.len());
 
     checks
 }
 
+// ============================================================================
+// Hardware Baselines (`xf doctor --benchmark compare`)
+// ============================================================================
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// Within this multiple of the baseline a timing counts as expected.
+const BASELINE_SLACK: f64 = 2.0;
+
+/// Vectors per unit of the semantic baseline, which grows linearly with the
+/// number of embeddings scanned.
+const SEMANTIC_BASELINE_VECTORS: usize = 10_000;
+
+/// Class of machine whose reference timings a benchmark is compared against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum HardwareTier {
+    /// Up to 8 cores or under 16 GiB of RAM
+    Laptop,
+    /// 8+ cores and 16+ GiB of RAM
+    Desktop,
+    /// 16+ cores and 64+ GiB of RAM
+    Server,
+}
+
+impl HardwareTier {
+    /// The tier's name as shown in output and accepted by `--tier`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Laptop => "laptop",
+            Self::Desktop => "desktop",
+            Self::Server => "server",
+        }
+    }
+
+    /// The tier of a machine with `cores` logical CPUs and `memory` bytes of
+    /// RAM (`None` = unknown, which never rules a tier in).
+    #[must_use]
+    pub const fn classify(cores: usize, memory: Option<u64>) -> Self {
+        match memory {
+            Some(bytes) if cores >= 16 && bytes >= 64 * GIB => Self::Server,
+            Some(bytes) if cores >= 8 && bytes >= 16 * GIB => Self::Desktop,
+            _ => Self::Laptop,
+        }
+    }
+
+    /// How much faster than a laptop this tier runs the doctor benchmarks.
+    #[must_use]
+    pub const fn speedup(self) -> f64 {
+        match self {
+            Self::Laptop => 1.0,
+            Self::Desktop => 2.0,
+            Self::Server => 3.0,
+        }
+    }
+
+    /// The tier of this machine.
+    #[must_use]
+    pub fn detect() -> Self {
+        let cores = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+        Self::classify(cores, crate::storage::total_memory_bytes())
+    }
+}
+
+impl std::fmt::Display for HardwareTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A timed doctor benchmark with a bundled reference baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchmarkKind {
+    IndexLoad,
+    SimpleQuery,
+    PhraseQuery,
+    ComplexQuery,
+    Fts5Query,
+    SemanticQuery,
+}
+
+impl BenchmarkKind {
+    /// Name of the benchmark's health check.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::IndexLoad => "Index Load Time",
+            Self::SimpleQuery => "Simple Query Latency",
+            Self::PhraseQuery => "Phrase Query Latency",
+            Self::ComplexQuery => "Complex Query Latency",
+            Self::Fts5Query => "FTS5 Query Latency",
+            Self::SemanticQuery => "Semantic Query Latency",
+        }
+    }
+
+    /// Reference p50 in milliseconds on a warm cache.
+    ///
+    /// Measured on the 17,500-record `tests/fixtures/perf_corpus` with release
+    /// builds on a laptop and scaled by [`HardwareTier::speedup`]; the
+    /// semantic figure is per 10,000 mmapped vectors.
+    #[must_use]
+    pub fn baseline_p50_ms(self, tier: HardwareTier) -> f64 {
+        let laptop_ms = match self {
+            Self::IndexLoad => 40.0,
+            Self::SimpleQuery => 2.0,
+            Self::PhraseQuery | Self::Fts5Query => 3.0,
+            Self::ComplexQuery => 4.0,
+            Self::SemanticQuery => 8.0,
+        };
+        laptop_ms / tier.speedup()
+    }
+
+    /// What to try when this benchmark is `ratio` times slower than expected.
+    fn slow_suggestion(self, ratio: f64, semantic_mapped: bool) -> String {
+        match self {
+            Self::IndexLoad => format!(
+                "Index load {ratio:.0}x slower than expected — index on a network or spinning disk? Move the index directory to local SSD"
+            ),
+            Self::SimpleQuery | Self::PhraseQuery | Self::ComplexQuery => format!(
+                "Keyword search {ratio:.0}x slower than expected — index fragmented into many segments? Rebuild with 'xf index --force'"
+            ),
+            Self::Fts5Query => format!(
+                "FTS5 search {ratio:.0}x slower than expected — SQLite cache too small? Set storage.profile = \"fast\" and run 'xf doctor --fix'"
+            ),
+            Self::SemanticQuery if !semantic_mapped => format!(
+                "Semantic search {ratio:.0}x slower than expected — embeddings not mmapped; run 'xf index --force' to rebuild vector.idx"
+            ),
+            Self::SemanticQuery => format!(
+                "Semantic search {ratio:.0}x slower than expected — vector.idx paged out or on slow storage? Move the index directory to local SSD"
+            ),
+        }
+    }
+}
+
+/// Compare benchmark p50s against the reference timings for `tier`.
+///
+/// Each timing within [`BASELINE_SLACK`] times its baseline passes; slower
+/// ones warn with a suggestion aimed at the usual cause. A semantic timing
+/// over `SQLite` instead of the mmapped sidecar always warns.
+#[must_use]
+pub fn compare_with_baseline(
+    tier: HardwareTier,
+    timings: &[(BenchmarkKind, LatencyStats)],
+    semantic: Option<&SemanticTiming>,
+) -> Vec<HealthCheck> {
+    let mut checks = Vec::with_capacity(timings.len() + 2);
+
+    checks.push(HealthCheck {
+        category: CheckCategory::Performance,
+        name: "Baseline Tier".to_string(),
+        status: CheckStatus::Pass,
+        message: format!("Comparing against {tier} reference timings"),
+        suggestion: None,
+    });
+
+    for (kind, latency) in timings {
+        checks.push(baseline_check(tier, *kind, latency.p50_ms, 1.0, true));
+    }
+
+    if let Some(semantic) = semantic {
+        #[allow(clippy::cast_precision_loss)]
+        let scale = (semantic.vectors as f64 / SEMANTIC_BASELINE_VECTORS as f64).max(1.0);
+        let mut check = baseline_check(
+            tier,
+            BenchmarkKind::SemanticQuery,
+            semantic.latency.p50_ms,
+            scale,
+            semantic.mapped,
+        );
+        check.message = format!("{}, {} vectors", check.message, semantic.vectors);
+        if !semantic.mapped {
+            check.message.push_str(", loaded from SQLite");
+            if check.status.is_ok() {
+                check.status = CheckStatus::Warning;
+                check.suggestion = Some(
+                    "Semantic search is not using the mmapped vector.idx; run 'xf index --force' to rebuild it"
+                        .to_string(),
+                );
+            }
+        }
+        checks.push(check);
+    }
+
+    checks
+}
+
+fn baseline_check(
+    tier: HardwareTier,
+    kind: BenchmarkKind,
+    p50_ms: f64,
+    scale: f64,
+    semantic_mapped: bool,
+) -> HealthCheck {
+    let expected_ms = kind.baseline_p50_ms(tier) * scale;
+    let ratio = p50_ms / expected_ms;
+
+    let (status, verdict, suggestion) = if ratio <= BASELINE_SLACK {
+        (CheckStatus::Pass, "as expected".to_string(), None)
+    } else {
+        (
+            CheckStatus::Warning,
+            format!("{ratio:.1}x slower"),
+            Some(kind.slow_suggestion(ratio, semantic_mapped)),
+        )
+    };
+
+    HealthCheck {
+        category: CheckCategory::Performance,
+        name: format!("Baseline: {}", kind.name()),
+        status,
+        message: format!("p50={p50_ms:.1}ms vs {tier} {expected_ms:.1}ms ({verdict})"),
+        suggestion,
+    }
+}
+
 // ============================================================================
 // Tests (xf-11.4.6)
 // ============================================================================
@@ -811,21 +1090,8 @@ mod tests {
     /// Create a minimal Tweet for testing.
     fn make_tweet(id: &str, text: &str, created_at: chrono::DateTime<Utc>) -> crate::Tweet {
         crate::Tweet {
-            id: id.into(),
             created_at,
-            full_text: text.into(),
-            source: None,
-            favorite_count: 0,
-            retweet_count: 0,
-            lang: None,
-            in_reply_to_status_id: None,
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: Vec::new(),
-            user_mentions: Vec::new(),
-            urls: Vec::new(),
-            media: Vec::new(),
+            ..crate::Tweet::fixture(id, text)
         }
     }
 
@@ -1049,6 +1315,76 @@ mod tests {
         assert!(!CheckStatus::Error.is_ok());
     }
 
+    // ======================== Hardware Baseline Tests ========================
+
+    fn latency_p50(p50_ms: f64) -> LatencyStats {
+        LatencyStats::from_durations(&mut [p50_ms])
+    }
+
+    #[test]
+    fn test_hardware_tier_classify() {
+        assert_eq!(
+            HardwareTier::classify(4, Some(8 * GIB)),
+            HardwareTier::Laptop
+        );
+        assert_eq!(HardwareTier::classify(16, None), HardwareTier::Laptop);
+        assert_eq!(
+            HardwareTier::classify(8, Some(32 * GIB)),
+            HardwareTier::Desktop
+        );
+        assert_eq!(
+            HardwareTier::classify(64, Some(256 * GIB)),
+            HardwareTier::Server
+        );
+    }
+
+    #[test]
+    fn test_compare_with_baseline_flags_slow_timings() {
+        let timings = [
+            (BenchmarkKind::SimpleQuery, latency_p50(1.5)),
+            (BenchmarkKind::Fts5Query, latency_p50(30.0)),
+        ];
+        let checks = compare_with_baseline(HardwareTier::Laptop, &timings, None);
+
+        assert_eq!(checks[0].name, "Baseline Tier");
+        assert_eq!(checks[1].status, CheckStatus::Pass);
+        assert_eq!(checks[2].status, CheckStatus::Warning);
+        assert!(checks[2].message.contains("10.0x slower"));
+        assert!(
+            checks[2]
+                .suggestion
+                .as_deref()
+                .unwrap()
+                .starts_with("FTS5 search 10x")
+        );
+    }
+
+    #[test]
+    fn test_compare_with_baseline_semantic_scales_and_flags_unmapped() {
+        let mapped = SemanticTiming {
+            latency: latency_p50(30.0),
+            vectors: 50_000,
+            mapped: true,
+        };
+        let checks = compare_with_baseline(HardwareTier::Laptop, &[], Some(&mapped));
+        assert_eq!(checks[1].status, CheckStatus::Pass, "{}", checks[1].message);
+
+        let unmapped = SemanticTiming {
+            latency: latency_p50(800.0),
+            vectors: 10_000,
+            mapped: false,
+        };
+        let checks = compare_with_baseline(HardwareTier::Laptop, &[], Some(&unmapped));
+        assert_eq!(checks[1].status, CheckStatus::Warning);
+        assert!(
+            checks[1]
+                .suggestion
+                .as_deref()
+                .unwrap()
+                .contains("embeddings not mmapped")
+        );
+    }
+
     // ======================== Full Validation Tests ========================
 
     #[test]
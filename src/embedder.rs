@@ -49,8 +49,9 @@ pub struct EmbedderInfo {
 /// - [`HashEmbedder`](crate::hash_embedder::HashEmbedder): Fast, deterministic
 ///   hash-based embeddings using FNV-1a. Always available, ~0ms per embedding.
 ///
-/// - `FastEmbedder` (optional): ML-based semantic embeddings using `MiniLM`.
-///   Requires model files, ~5ms per embedding.
+/// - `OnnxEmbedder` (feature `onnx-embeddings`): sentence-embedding models
+///   such as `MiniLM` or E5, loaded from a local directory. See
+///   [`embedding_model`](crate::embedding_model).
 pub trait Embedder: Send + Sync {
     /// Embed a single text into a dense vector.
     ///
@@ -73,6 +74,18 @@ pub trait Embedder: Send + Sync {
         texts.iter().map(|t| self.embed(t)).collect()
     }
 
+    /// Embed a search query.
+    ///
+    /// Defaults to [`embed`](Self::embed); models trained with separate
+    /// query and passage prefixes (such as E5) override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is invalid or embedding fails.
+    fn embed_query(&self, text: &str) -> EmbedderResult<Vec<f32>> {
+        self.embed(text)
+    }
+
     /// Get the output dimension of embeddings.
     fn dimension(&self) -> usize;
 
@@ -0,0 +1,425 @@
+//! Embedder selection from `embeddings.model`.
+//!
+//! `hash` (the default) is the built-in [`HashEmbedder`]. Any other value
+//! names a local sentence-embedding model: a directory containing
+//! `model.onnx` and `tokenizer.json` (for example an ONNX export of
+//! `all-MiniLM-L6-v2` or `multilingual-e5-small`), given as a path or as a
+//! directory name under [`default_models_dir`]. Nothing is ever downloaded.
+//!
+//! The ONNX backend is behind the `onnx-embeddings` feature. Stored
+//! embeddings record the model and dimension that produced them, so a
+//! model change is detected before vectors from two models are compared.
+
+use crate::embedder::{Embedder, EmbedderResult};
+use crate::hash_embedder::HashEmbedder;
+use std::path::{Path, PathBuf};
+
+/// `embeddings.model` value selecting the built-in hash embedder.
+pub const HASH_MODEL: &str = "hash";
+
+/// Default directory holding named models: `<data dir>/models/embeddings`.
+#[must_use]
+pub fn default_models_dir() -> PathBuf {
+    crate::default_data_dir().join("models").join("embeddings")
+}
+
+/// Directory of the model named by `model`: the value itself when it looks
+/// like a path, otherwise a directory under [`default_models_dir`].
+#[must_use]
+pub fn model_dir(model: &str) -> PathBuf {
+    if model.contains(['/', '\\']) {
+        PathBuf::from(model)
+    } else {
+        default_models_dir().join(model)
+    }
+}
+
+/// Load the embedder selected by `model` (an `embeddings.model` value).
+///
+/// # Errors
+///
+/// Returns [`EmbedderError::Unavailable`](crate::embedder::EmbedderError::Unavailable)
+/// if the model files are missing, fail to load, or this build lacks the
+/// `onnx-embeddings` feature.
+pub fn load_embedder(model: &str) -> EmbedderResult<Box<dyn Embedder>> {
+    let model = model.trim();
+    if model.is_empty() || model.eq_ignore_ascii_case(HASH_MODEL) {
+        return Ok(Box::new(HashEmbedder::default()));
+    }
+    load_model(&model_dir(model))
+}
+
+#[cfg(feature = "onnx-embeddings")]
+fn load_model(model_dir: &Path) -> EmbedderResult<Box<dyn Embedder>> {
+    Ok(Box::new(OnnxEmbedder::load(model_dir)?))
+}
+
+#[cfg(not(feature = "onnx-embeddings"))]
+fn load_model(model_dir: &Path) -> EmbedderResult<Box<dyn Embedder>> {
+    Err(crate::embedder::EmbedderError::Unavailable(format!(
+        "xf was built without the `onnx-embeddings` feature needed for {}",
+        model_dir.display()
+    )))
+}
+
+/// Why embeddings stored with `stored_model` and `stored_dimension` cannot
+/// be compared with vectors from `embedder`, or `None` if they can.
+#[must_use]
+pub fn incompatibility(
+    stored_model: &str,
+    stored_dimension: usize,
+    embedder: &dyn Embedder,
+) -> Option<String> {
+    if stored_model == embedder.id() && stored_dimension == embedder.dimension() {
+        return None;
+    }
+    Some(format!(
+        "Embeddings were built with {stored_model} ({stored_dimension} dimensions), but embeddings.model selects {} ({} dimensions).",
+        embedder.id(),
+        embedder.dimension()
+    ))
+}
+
+#[cfg(feature = "onnx-embeddings")]
+pub use onnx::OnnxEmbedder;
+
+#[cfg(feature = "onnx-embeddings")]
+mod onnx {
+    use crate::embedder::{Embedder, EmbedderError, EmbedderResult, l2_normalize};
+    use ort::session::{Session, SessionInputValue};
+    use ort::value::Tensor;
+    use parking_lot::Mutex;
+    use std::borrow::Cow;
+    use std::path::Path;
+    use tokenizers::{Encoding, Tokenizer, TruncationParams};
+
+    /// Longest text fed to the model, in tokens.
+    const MAX_SEQUENCE_LEN: usize = 512;
+
+    /// Texts embedded per inference call.
+    const BATCH_SIZE: usize = 32;
+
+    /// ONNX sentence-embedding model (BERT-style inputs).
+    ///
+    /// Token embeddings are mean-pooled over the attention mask, unless the
+    /// model already outputs a pooled `sentence_embedding`. Models whose
+    /// directory name contains `e5` get the `query: ` / `passage: ` prefixes
+    /// they were trained with.
+    pub struct OnnxEmbedder {
+        session: Mutex<Session>,
+        tokenizer: Tokenizer,
+        input_names: Vec<String>,
+        output_index: usize,
+        prefixes: Option<(&'static str, &'static str)>,
+        dimension: usize,
+        id: String,
+    }
+
+    impl OnnxEmbedder {
+        /// Load `model.onnx` and `tokenizer.json` from `model_dir`.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`EmbedderError::Unavailable`] if either file is missing
+        /// or fails to load (including a missing ONNX Runtime library).
+        pub fn load(model_dir: &Path) -> EmbedderResult<Self> {
+            let model_path = model_dir.join("model.onnx");
+            let tokenizer_path = model_dir.join("tokenizer.json");
+            for path in [&model_path, &tokenizer_path] {
+                if !path.exists() {
+                    return Err(EmbedderError::Unavailable(format!(
+                        "embedding model file not found: {}",
+                        path.display()
+                    )));
+                }
+            }
+
+            let mut tokenizer = Tokenizer::from_file(&tokenizer_path).map_err(|e| {
+                EmbedderError::Unavailable(format!("failed to load tokenizer: {e}"))
+            })?;
+            tokenizer
+                .with_truncation(Some(TruncationParams {
+                    max_length: MAX_SEQUENCE_LEN,
+                    ..TruncationParams::default()
+                }))
+                .map_err(|e| EmbedderError::Internal(format!("tokenizer truncation: {e}")))?;
+            tokenizer.with_padding(None);
+
+            let session = Session::builder()
+                .and_then(|builder| builder.commit_from_file(&model_path))
+                .map_err(|e| {
+                    EmbedderError::Unavailable(format!(
+                        "failed to load {}: {e}",
+                        model_path.display()
+                    ))
+                })?;
+            let input_names = session.inputs.iter().map(|i| i.name.clone()).collect();
+            let output_names: Vec<&str> = session.outputs.iter().map(|o| o.name.as_str()).collect();
+            let output_index = ["sentence_embedding", "last_hidden_state"]
+                .iter()
+                .find_map(|wanted| output_names.iter().position(|name| name == wanted))
+                .unwrap_or(0);
+
+            let name = model_dir
+                .file_name()
+                .map_or_else(|| "model".to_string(), |n| n.to_string_lossy().into_owned());
+            let prefixes = name
+                .to_ascii_lowercase()
+                .contains("e5")
+                .then_some(("query: ", "passage: "));
+
+            let mut embedder = Self {
+                session: Mutex::new(session),
+                tokenizer,
+                input_names,
+                output_index,
+                prefixes,
+                dimension: 0,
+                id: format!("onnx:{name}"),
+            };
+            // The output width is only known after a first inference
+            embedder.dimension = embedder
+                .run_batch(&["dimension probe"])?
+                .first()
+                .map_or(0, Vec::len);
+            if embedder.dimension == 0 {
+                return Err(EmbedderError::Unavailable(format!(
+                    "{} produced an empty embedding",
+                    model_path.display()
+                )));
+            }
+            Ok(embedder)
+        }
+
+        fn run_batch(&self, texts: &[&str]) -> EmbedderResult<Vec<Vec<f32>>> {
+            let encodings = self
+                .tokenizer
+                .encode_batch(texts.to_vec(), true)
+                .map_err(|e| EmbedderError::EmbeddingFailed(format!("tokenization: {e}")))?;
+
+            let batch = encodings.len();
+            let seq_len = encodings.iter().map(Encoding::len).max().unwrap_or(0);
+            let mut input_ids = vec![0_i64; batch * seq_len];
+            let mut attention_mask = vec![0_i64; batch * seq_len];
+            let mut token_type_ids = vec![0_i64; batch * seq_len];
+            for (row, encoding) in encodings.iter().enumerate() {
+                let tokens = encoding
+                    .get_ids()
+                    .iter()
+                    .zip(encoding.get_attention_mask())
+                    .zip(encoding.get_type_ids());
+                for (col, ((&id, &mask), &type_id)) in tokens.enumerate() {
+                    let i = row * seq_len + col;
+                    input_ids[i] = i64::from(id);
+                    attention_mask[i] = i64::from(mask);
+                    token_type_ids[i] = i64::from(type_id);
+                }
+            }
+
+            let mut inputs: Vec<(Cow<'_, str>, SessionInputValue<'_>)> = Vec::new();
+            for name in &self.input_names {
+                let data = match name.as_str() {
+                    "input_ids" => &input_ids,
+                    "attention_mask" => &attention_mask,
+                    "token_type_ids" => &token_type_ids,
+                    other => {
+                        return Err(EmbedderError::Internal(format!(
+                            "unsupported embedding model input '{other}'"
+                        )));
+                    }
+                };
+                let tensor = Tensor::from_array(([batch, seq_len], data.clone()))
+                    .map_err(|e| EmbedderError::Internal(format!("input tensor: {e}")))?;
+                inputs.push((Cow::from(name.as_str()), tensor.into()));
+            }
+
+            let mut session = self.session.lock();
+            let outputs = session
+                .run(inputs)
+                .map_err(|e| EmbedderError::EmbeddingFailed(format!("inference: {e}")))?;
+            let (shape, data) = outputs[self.output_index]
+                .try_extract_tensor::<f32>()
+                .map_err(|e| EmbedderError::Internal(format!("output tensor: {e}")))?;
+            let shape: Vec<usize> = shape
+                .iter()
+                .map(|&d| usize::try_from(d).unwrap_or(0))
+                .collect();
+            let data = data.to_vec();
+            drop(outputs);
+            drop(session);
+
+            let mut embeddings = match shape.as_slice() {
+                // Already pooled: [batch, dimension]
+                &[rows, width] if rows == batch && width > 0 => {
+                    data.chunks(width).map(<[f32]>::to_vec).collect()
+                }
+                // Token embeddings: [batch, tokens, dimension]
+                &[rows, tokens, width] if rows == batch && tokens == seq_len => {
+                    mean_pool(&data, &attention_mask, batch, seq_len, width)
+                }
+                other => {
+                    return Err(EmbedderError::Internal(format!(
+                        "unexpected embedding output shape {other:?}"
+                    )));
+                }
+            };
+            for embedding in &mut embeddings {
+                l2_normalize(embedding);
+            }
+            Ok(embeddings)
+        }
+
+        fn embed_prefixed(
+            &self,
+            texts: &[&str],
+            prefix: Option<&str>,
+        ) -> EmbedderResult<Vec<Vec<f32>>> {
+            let prefix = prefix.unwrap_or_default();
+            let prefixed: Vec<String> =
+                texts.iter().map(|text| format!("{prefix}{text}")).collect();
+            let texts: Vec<&str> = prefixed.iter().map(String::as_str).collect();
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for chunk in texts.chunks(BATCH_SIZE) {
+                embeddings.extend(self.run_batch(chunk)?);
+            }
+            Ok(embeddings)
+        }
+    }
+
+    /// Average each row's token embeddings over its attention mask.
+    #[allow(clippy::cast_precision_loss)]
+    fn mean_pool(
+        data: &[f32],
+        attention_mask: &[i64],
+        batch: usize,
+        seq_len: usize,
+        width: usize,
+    ) -> Vec<Vec<f32>> {
+        (0..batch)
+            .map(|row| {
+                let mut sum = vec![0.0_f32; width];
+                let mut count = 0_usize;
+                for col in 0..seq_len {
+                    if attention_mask[row * seq_len + col] == 0 {
+                        continue;
+                    }
+                    let start = (row * seq_len + col) * width;
+                    for (total, value) in sum.iter_mut().zip(&data[start..start + width]) {
+                        *total += value;
+                    }
+                    count += 1;
+                }
+                if count > 0 {
+                    for total in &mut sum {
+                        *total /= count as f32;
+                    }
+                }
+                sum
+            })
+            .collect()
+    }
+
+    impl Embedder for OnnxEmbedder {
+        fn embed(&self, text: &str) -> EmbedderResult<Vec<f32>> {
+            self.embed_batch(&[text])?
+                .pop()
+                .ok_or_else(|| EmbedderError::Internal("no embedding returned".to_string()))
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> EmbedderResult<Vec<Vec<f32>>> {
+            if texts.iter().any(|text| text.is_empty()) {
+                return Err(EmbedderError::InvalidInput("empty text".to_string()));
+            }
+            self.embed_prefixed(texts, self.prefixes.map(|(_, passage)| passage))
+        }
+
+        fn embed_query(&self, text: &str) -> EmbedderResult<Vec<f32>> {
+            if text.is_empty() {
+                return Err(EmbedderError::InvalidInput("empty text".to_string()));
+            }
+            self.embed_prefixed(&[text], self.prefixes.map(|(query, _)| query))?
+                .pop()
+                .ok_or_else(|| EmbedderError::Internal("no embedding returned".to_string()))
+        }
+
+        fn dimension(&self) -> usize {
+            self.dimension
+        }
+
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn is_semantic(&self) -> bool {
+            true
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::mean_pool;
+
+        #[test]
+        fn mean_pool_averages_unmasked_tokens() {
+            // Two rows of three tokens with two dimensions each
+            let data = [
+                1.0, 2.0, 3.0, 4.0, 9.0, 9.0, //
+                2.0, 0.0, 9.0, 9.0, 9.0, 9.0,
+            ];
+            let mask = [1, 1, 0, 1, 0, 0];
+            assert_eq!(
+                mean_pool(&data, &mask, 2, 3, 2),
+                [vec![2.0, 3.0], vec![2.0, 0.0]]
+            );
+            assert_eq!(
+                mean_pool(&data, &[0; 6], 2, 3, 2),
+                [vec![0.0; 2], vec![0.0; 2]]
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedder::EmbedderError;
+
+    #[test]
+    fn hash_model_loads_the_hash_embedder() {
+        for model in ["hash", "HASH", ""] {
+            let embedder = load_embedder(model).unwrap();
+            assert_eq!(embedder.id(), HashEmbedder::default().id());
+            assert!(!embedder.is_semantic());
+        }
+    }
+
+    #[test]
+    fn model_names_resolve_under_the_models_dir() {
+        assert_eq!(
+            model_dir("all-MiniLM-L6-v2"),
+            default_models_dir().join("all-MiniLM-L6-v2")
+        );
+        assert_eq!(model_dir("/opt/models/e5"), PathBuf::from("/opt/models/e5"));
+    }
+
+    #[test]
+    fn missing_model_is_unavailable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let err = load_embedder(&dir.path().join("missing").to_string_lossy())
+            .err()
+            .unwrap();
+        assert!(matches!(err, EmbedderError::Unavailable(_)));
+    }
+
+    #[test]
+    fn incompatibility_compares_model_and_dimension() {
+        let embedder = HashEmbedder::default();
+        assert_eq!(incompatibility("fnv1a-384", 384, &embedder), None);
+        assert!(
+            incompatibility("onnx:all-MiniLM-L6-v2", 384, &embedder)
+                .unwrap()
+                .contains("onnx:all-MiniLM-L6-v2")
+        );
+        assert!(incompatibility("fnv1a-384", 256, &embedder).is_some());
+    }
+}
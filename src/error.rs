@@ -3,6 +3,7 @@
 //! Provides structured error handling with detailed context for better
 //! diagnostics and user experience.
 
+use crate::i18n::{Msg, t};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -57,6 +58,7 @@ pub enum XfError {
     DatabaseLocked { path: PathBuf },
 
     /// Database operation failed.
+    #[cfg(feature = "native")]
     #[error("Database error: {0}")]
     DatabaseError(#[from] rusqlite::Error),
 
@@ -80,6 +82,7 @@ pub enum XfError {
     SearchError(String),
 
     /// Tantivy-specific error.
+    #[cfg(feature = "native")]
     #[error("Search engine error: {0}")]
     TantivyError(#[from] tantivy::TantivyError),
 
@@ -314,7 +317,35 @@ where
 // CLI Error Formatting Utilities
 // =============================================================================
 
-use colored::Colorize;
+#[cfg(feature = "cli")]
+pub(crate) use colored::Colorize;
+
+/// Plain-text stand-in for `colored` when built without the `cli` feature.
+#[cfg(not(feature = "cli"))]
+#[cfg_attr(not(feature = "native"), allow(dead_code))]
+pub(crate) trait Colorize: std::fmt::Display {
+    fn red(&self) -> String {
+        self.to_string()
+    }
+    fn green(&self) -> String {
+        self.to_string()
+    }
+    fn yellow(&self) -> String {
+        self.to_string()
+    }
+    fn cyan(&self) -> String {
+        self.to_string()
+    }
+    fn bold(&self) -> String {
+        self.to_string()
+    }
+    fn dimmed(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+impl<T: std::fmt::Display + ?Sized> Colorize for T {}
 
 /// Format a structured CLI error with explanation and suggestions.
 ///
@@ -338,9 +369,9 @@ pub fn format_error(title: &str, explanation: &str, suggestions: &[&str]) -> Str
     if !suggestions.is_empty() {
         output.push_str("\n\n   ");
         if suggestions.len() == 1 {
-            let _ = write!(output, "{} {}", "Hint:".cyan(), suggestions[0]);
+            let _ = write!(output, "{} {}", t(Msg::Hint).cyan(), suggestions[0]);
         } else {
-            let _ = write!(output, "{}:", "Try".cyan());
+            let _ = write!(output, "{}:", t(Msg::Try).cyan());
             for suggestion in suggestions {
                 let _ = write!(output, "\n     {} {}", "•".dimmed(), suggestion);
             }
@@ -452,7 +483,7 @@ pub fn format_unknown_value_error(kind: &str, input: &str, valid_options: &[&str
 }
 
 /// Standard valid data types for type filtering.
-pub const VALID_DATA_TYPES: &[&str] = &["tweet", "like", "dm", "grok"];
+pub const VALID_DATA_TYPES: &[&str] = &["tweet", "like", "bookmark", "dm", "grok"];
 
 /// Standard valid output fields for --fields.
 pub const VALID_OUTPUT_FIELDS: &[&str] = &[
@@ -462,6 +493,7 @@ pub const VALID_OUTPUT_FIELDS: &[&str] = &[
     "created_at",
     "score",
     "highlights",
+    "match_ranges",
     "metadata",
 ];
 
@@ -478,14 +510,28 @@ pub const VALID_CONFIG_KEYS: &[&str] = &[
     "search.fuzzy",
     "search.min_score",
     "search.cache_size",
+    "search.rerank_model",
+    "search.rerank_candidates",
+    "search.engine",
+    "storage.profile",
     "indexing.parallel",
     "indexing.buffer_size_mb",
+    "indexing.commit_every",
     "indexing.threads",
     "indexing.skip_types",
+    "indexing.stopwords",
+    "embeddings.types",
+    "embeddings.model",
+    "llm.endpoint",
+    "llm.model",
+    "llm.timeout_secs",
+    "llm.max_context_chars",
+    "audit.enabled",
     "output.format",
     "output.colors",
     "output.quiet",
     "output.timings",
+    "output.language",
 ];
 
 #[cfg(test)]
@@ -521,6 +567,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "native")]
     fn test_from_rusqlite_error() {
         // This test verifies the From impl exists
         fn accepts_xf_error(_: XfError) {}
@@ -0,0 +1,302 @@
+//! Subject extraction (`xf extract --about`).
+//!
+//! Gathers every tweet, like, and DM that references one person into a
+//! single bundle, the archive-side counterpart of a data subject access
+//! request. A subject is given as a handle (`@alice`), a display name
+//! (`Alice Smith`), or a numeric account ID. Account IDs are learned from
+//! tweet mentions and replies so DM conversations with the person are
+//! included even when their messages never mention them by name.
+
+use crate::model::{DirectMessage, Like, Tweet};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// The person an extraction is about.
+#[derive(Debug, Clone)]
+pub struct Subject {
+    /// Lowercased handle or name, without a leading `@`.
+    term: String,
+    account_ids: HashSet<String>,
+}
+
+impl Subject {
+    /// Parse `--about`; `None` if nothing is left after trimming `@`.
+    #[must_use]
+    pub fn new(about: &str) -> Option<Self> {
+        let term = about
+            .trim()
+            .trim_start_matches('@')
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if term.is_empty() {
+            return None;
+        }
+        let mut account_ids = HashSet::new();
+        if term.chars().all(|c| c.is_ascii_digit()) {
+            account_ids.insert(term.clone());
+        }
+        Some(Self { term, account_ids })
+    }
+
+    /// Account IDs known to belong to the subject, sorted.
+    #[must_use]
+    pub fn account_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.account_ids.iter().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Learn the subject's account IDs from mentions of and replies to them.
+    pub fn resolve_account_ids(&mut self, tweets: &[Tweet]) {
+        for tweet in tweets {
+            for mention in &tweet.user_mentions {
+                let name_matches = mention
+                    .name
+                    .as_deref()
+                    .is_some_and(|name| self.is_name(name));
+                if (self.is_name(&mention.screen_name) || name_matches) && !mention.id.is_empty() {
+                    self.account_ids.insert(mention.id.clone());
+                }
+            }
+            if let (Some(name), Some(id)) = (
+                tweet.in_reply_to_screen_name.as_deref(),
+                tweet.in_reply_to_user_id.as_deref(),
+            ) {
+                if self.is_name(name) && !id.is_empty() {
+                    self.account_ids.insert(id.to_string());
+                }
+            }
+        }
+    }
+
+    /// Whether a tweet mentions, replies to, or names the subject.
+    #[must_use]
+    pub fn tweet_references(&self, tweet: &Tweet) -> bool {
+        tweet.user_mentions.iter().any(|m| {
+            self.account_ids.contains(&m.id)
+                || self.is_name(&m.screen_name)
+                || m.name.as_deref().is_some_and(|name| self.is_name(name))
+        }) || tweet
+            .in_reply_to_user_id
+            .as_ref()
+            .is_some_and(|id| self.account_ids.contains(id))
+            || tweet
+                .in_reply_to_screen_name
+                .as_deref()
+                .is_some_and(|name| self.is_name(name))
+            || self.text_references(&tweet.full_text)
+    }
+
+    /// Whether a liked tweet was posted by or names the subject.
+    #[must_use]
+    pub fn like_references(&self, like: &Like) -> bool {
+        let by_subject = like.expanded_url.as_deref().is_some_and(|url| {
+            url.to_lowercase()
+                .contains(&format!("/{}/status/", self.term))
+        });
+        by_subject
+            || like
+                .full_text
+                .as_deref()
+                .is_some_and(|text| self.text_references(text))
+    }
+
+    fn is_name(&self, name: &str) -> bool {
+        name.trim().trim_start_matches('@').to_lowercase() == self.term
+    }
+
+    /// Whole-word, case-insensitive match of the handle or name in `text`.
+    fn text_references(&self, text: &str) -> bool {
+        if self.term.chars().all(|c| c.is_ascii_digit()) {
+            return false;
+        }
+        let text = text.to_lowercase();
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        text.match_indices(&self.term).any(|(start, matched)| {
+            let before = text[..start].chars().next_back();
+            let after = text[start + matched.len()..].chars().next();
+            !before.is_some_and(is_word) && !after.is_some_and(is_word)
+        })
+    }
+}
+
+/// Everything in the archive that references a subject.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectBundle {
+    /// The `--about` value as given.
+    pub about: String,
+    /// Account IDs attributed to the subject.
+    pub account_ids: Vec<String>,
+    pub generated_at: DateTime<Utc>,
+    pub tweets: Vec<Tweet>,
+    pub likes: Vec<Like>,
+    /// Messages in conversations with the subject, plus any other DM naming them.
+    pub dms: Vec<DirectMessage>,
+}
+
+impl SubjectBundle {
+    /// Total number of records in the bundle.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tweets.len() + self.likes.len() + self.dms.len()
+    }
+
+    /// Whether nothing referencing the subject was found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Select the tweets, likes, and DMs that reference `subject`.
+#[must_use]
+pub fn extract(
+    about: &str,
+    mut subject: Subject,
+    mut tweets: Vec<Tweet>,
+    mut likes: Vec<Like>,
+    mut dms: Vec<DirectMessage>,
+) -> SubjectBundle {
+    subject.resolve_account_ids(&tweets);
+
+    tweets.retain(|tweet| subject.tweet_references(tweet));
+    tweets.sort_by_key(|t| t.created_at);
+    likes.retain(|like| subject.like_references(like));
+
+    // Whole conversations the subject took part in
+    let conversations: HashSet<String> = dms
+        .iter()
+        .filter(|dm| {
+            subject.account_ids.contains(&dm.sender_id)
+                || subject.account_ids.contains(&dm.recipient_id)
+        })
+        .map(|dm| dm.conversation_id.clone())
+        .collect();
+    dms.retain(|dm| {
+        conversations.contains(&dm.conversation_id) || subject.text_references(&dm.text)
+    });
+    dms.sort_by(|a, b| {
+        a.conversation_id
+            .cmp(&b.conversation_id)
+            .then_with(|| a.created_at.cmp(&b.created_at))
+    });
+
+    SubjectBundle {
+        about: about.to_string(),
+        account_ids: subject.account_ids(),
+        generated_at: Utc::now(),
+        tweets,
+        likes,
+        dms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::UserMention;
+
+    fn tweet(id: &str, text: &str, mentions: &[(&str, &str)]) -> Tweet {
+        Tweet {
+            user_mentions: mentions
+                .iter()
+                .map(|(id, screen_name)| UserMention {
+                    id: (*id).to_string(),
+                    screen_name: (*screen_name).to_string(),
+                    name: None,
+                })
+                .collect(),
+            ..Tweet::fixture(id, text)
+        }
+    }
+
+    fn dm(id: &str, conversation: &str, sender: &str, text: &str) -> DirectMessage {
+        DirectMessage {
+            id: id.to_string(),
+            conversation_id: conversation.to_string(),
+            sender_id: sender.to_string(),
+            recipient_id: "1".to_string(),
+            text: text.to_string(),
+            created_at: Utc::now(),
+            urls: Vec::new(),
+            media_urls: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_subject_parsing() {
+        assert!(Subject::new(" @ ").is_none());
+        assert_eq!(Subject::new("@Alice").unwrap().term, "alice");
+        assert_eq!(Subject::new("Alice   Smith").unwrap().term, "alice smith");
+        assert_eq!(Subject::new("42").unwrap().account_ids(), vec!["42"]);
+    }
+
+    #[test]
+    fn test_text_references_whole_words() {
+        let subject = Subject::new("@alice").unwrap();
+        assert!(subject.text_references("lunch with @Alice today"));
+        assert!(subject.text_references("Alice, are you coming?"));
+        assert!(!subject.text_references("malice aforethought"));
+        assert!(!subject.text_references("alice_fan posted again"));
+    }
+
+    #[test]
+    fn test_extract_follows_account_ids_into_dms() {
+        let tweets = vec![
+            tweet("10", "great talk @alice", &[("42", "alice")]),
+            tweet("11", "nothing to see", &[]),
+        ];
+        let likes = vec![
+            Like {
+                tweet_id: "20".to_string(),
+                full_text: Some("a post".to_string()),
+                expanded_url: Some("https://twitter.com/Alice/status/20".to_string()),
+            },
+            Like {
+                tweet_id: "21".to_string(),
+                full_text: Some("unrelated".to_string()),
+                expanded_url: Some("https://twitter.com/bob/status/21".to_string()),
+            },
+        ];
+        let dms = vec![
+            dm("30", "c1", "42", "hi"),
+            dm("31", "c1", "1", "hello back"),
+            dm("32", "c2", "7", "did alice reply?"),
+            dm("33", "c3", "7", "unrelated"),
+        ];
+
+        let bundle = extract(
+            "@alice",
+            Subject::new("@alice").unwrap(),
+            tweets,
+            likes,
+            dms,
+        );
+        assert_eq!(bundle.account_ids, vec!["42"]);
+        let ids = |items: Vec<&str>| items.into_iter().map(String::from).collect::<Vec<_>>();
+        assert_eq!(
+            bundle
+                .tweets
+                .iter()
+                .map(|t| t.id.clone())
+                .collect::<Vec<_>>(),
+            ids(vec!["10"])
+        );
+        assert_eq!(
+            bundle
+                .likes
+                .iter()
+                .map(|l| l.tweet_id.clone())
+                .collect::<Vec<_>>(),
+            ids(vec!["20"])
+        );
+        assert_eq!(
+            bundle.dms.iter().map(|d| d.id.clone()).collect::<Vec<_>>(),
+            ids(vec!["30", "31", "32"])
+        );
+        assert_eq!(bundle.len(), 5);
+    }
+}
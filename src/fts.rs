@@ -0,0 +1,808 @@
+//! Keyword search over the `SQLite` FTS5 tables (`search.engine = "sqlite"`).
+//!
+//! `xf index` fills the FTS tables whichever engine is selected, so this
+//! engine needs no Tantivy index: with it, indexing skips Tantivy entirely,
+//! which saves the time and disk space it costs on small machines. Results
+//! have the same IDs, text and metadata as [`crate::search::SearchEngine`]'s.
+//!
+//! Queries are translated to FTS5 syntax: words match as prefixes (unless
+//! the query contains a phrase), `"quoted phrases"` match exactly, `AND`,
+//! `OR` and `NOT` are kept, and `-word` excludes. As with Tantivy, words
+//! without an operator match any of them, best BM25 score first; scores are
+//! FTS5's and not comparable with Tantivy's.
+
+use crate::model::{SearchResult, SearchResultType, TextRange, match_ranges};
+use crate::search::{DocLookup, DocType, SearchScope, TextIndex};
+use crate::storage::Storage;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use rusqlite::{Connection, params};
+use std::collections::{HashMap, HashSet};
+use std::hash::BuildHasher;
+use std::path::Path;
+
+/// Snippet match markers, replaced by `<b>`/`</b>` after HTML escaping.
+const MATCH_START: char = '\u{1}';
+const MATCH_END: char = '\u{2}';
+/// Words of context FTS5 keeps around matches in a snippet.
+const SNIPPET_TOKENS: u32 = 24;
+
+/// Where one document type lives: its FTS table and base table.
+struct Source {
+    doc_type: DocType,
+    fts: &'static str,
+    /// FTS column holding the text; the others hold the ID and the
+    /// supplemental text
+    column: &'static str,
+    /// Base table, aliased `d`
+    table: &'static str,
+    /// Expression for the text on `d`, as in `columns`
+    text: &'static str,
+    /// Joins `fts` (aliased `f`) to `d`
+    join: &'static str,
+    /// ID (the chat ID for Grok), text, `created_at`, metadata JSON, Grok sender
+    columns: &'static str,
+    /// Condition on `d` for documents the Tantivy index would hold
+    filter: &'static str,
+    /// Condition on `d` matching `?1`; Grok lookups match `?1` chat and `?2` sender
+    lookup: &'static str,
+}
+
+const SOURCES: [Source; 5] = [
+    Source {
+        doc_type: DocType::Tweet,
+        fts: "fts_tweets",
+        column: "full_text",
+        table: "tweets",
+        text: "d.full_text",
+        join: "d.id = f.tweet_id",
+        columns: "d.id, d.full_text, d.created_at, json_object('favorite_count', d.favorite_count, \
+                  'retweet_count', d.retweet_count, 'in_reply_to', d.in_reply_to_screen_name, \
+                  'hashtags', json(d.hashtags_json), 'source', d.source, \
+                  'place', json_extract(d.geo_json, '$.place')), NULL",
+        filter: "1",
+        lookup: "d.id = ?1",
+    },
+    Source {
+        doc_type: DocType::Like,
+        fts: "fts_likes",
+        column: "full_text",
+        table: "likes",
+        text: "d.full_text",
+        join: "d.tweet_id = f.tweet_id",
+        columns: "d.tweet_id, d.full_text, NULL, json_object('expanded_url', d.expanded_url), NULL",
+        filter: "d.full_text IS NOT NULL AND d.full_text != ''",
+        lookup: "d.tweet_id = ?1",
+    },
+    Source {
+        doc_type: DocType::Bookmark,
+        fts: "fts_bookmarks",
+        column: "full_text",
+        table: "bookmarks",
+        text: "d.full_text",
+        join: "d.tweet_id = f.tweet_id",
+        columns: "d.tweet_id, d.full_text, NULL, json_object('expanded_url', d.expanded_url), NULL",
+        filter: "d.full_text IS NOT NULL AND d.full_text != ''",
+        lookup: "d.tweet_id = ?1",
+    },
+    Source {
+        doc_type: DocType::DirectMessage,
+        fts: "fts_dms",
+        column: "text",
+        table: "direct_messages",
+        text: "d.text",
+        join: "d.id = f.dm_id",
+        columns: "d.id, d.text, d.created_at, json_object('conversation_id', d.conversation_id, \
+                  'sender_id', d.sender_id, 'recipient_id', d.recipient_id), NULL",
+        filter: "1",
+        lookup: "d.id = ?1",
+    },
+    Source {
+        doc_type: DocType::GrokMessage,
+        fts: "fts_grok",
+        column: "message",
+        table: "grok_messages",
+        text: "xf_unpack(d.message)",
+        join: "d.id = CAST(f.grok_id AS INTEGER)",
+        columns: "d.chat_id, xf_unpack(d.message), d.created_at, json_object('chat_id', d.chat_id, \
+                  'sender', d.sender, 'grok_mode', d.grok_mode), d.sender",
+        filter: "1",
+        lookup: "d.chat_id = ?1 AND d.sender = ?2",
+    },
+];
+
+/// Keyword search over the FTS tables of one database.
+pub struct FtsIndex {
+    storage: Storage,
+    snippets: bool,
+    scope: SearchScope,
+}
+
+impl FtsIndex {
+    #[must_use]
+    pub const fn new(storage: Storage) -> Self {
+        Self {
+            storage,
+            snippets: true,
+            scope: SearchScope::All,
+        }
+    }
+
+    /// Open the database at `db_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened.
+    pub fn open(db_path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Storage::open(db_path.as_ref())?))
+    }
+
+    fn sources(doc_types: Option<&[DocType]>) -> impl Iterator<Item = &'static Source> + '_ {
+        SOURCES
+            .iter()
+            .filter(move |source| doc_types.is_none_or(|types| types.contains(&source.doc_type)))
+    }
+
+    /// FTS column filter for the search scope.
+    fn columns(&self, source: &Source) -> String {
+        match self.scope {
+            SearchScope::Main => source.column.to_string(),
+            SearchScope::Supplemental => "supplemental".to_string(),
+            SearchScope::All => format!("{{{} supplemental}}", source.column),
+        }
+    }
+
+    /// Best `limit` matches of `expression` in one source; all documents
+    /// (unranked) without an expression.
+    fn search_source(
+        &self,
+        source: &Source,
+        expression: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let Source {
+            fts,
+            table,
+            join,
+            columns,
+            filter,
+            ..
+        } = source;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let conn = self.storage.connection();
+        let Some(expression) = expression else {
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {columns} FROM {table} d WHERE {filter} LIMIT ?1"
+            ))?;
+            let rows =
+                stmt.query_map(params![limit], |row| result_from_row(source.doc_type, row))?;
+            return Ok(rows.collect::<rusqlite::Result<_>>()?);
+        };
+
+        let snippet = if self.snippets {
+            format!("snippet({fts}, 1, char(1), char(2), '', {SNIPPET_TOKENS})")
+        } else {
+            "NULL".to_string()
+        };
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {columns}, -bm25({fts}, 0.0, 1.0, 1.0), {snippet} \
+             FROM {fts} f JOIN {table} d ON {join} \
+             WHERE {fts} MATCH ?1 AND {filter} \
+             ORDER BY bm25({fts}, 0.0, 1.0, 1.0) LIMIT ?2"
+        ))?;
+        let rows = stmt.query_map(params![expression, limit], |row| {
+            let mut result = result_from_row(source.doc_type, row)?;
+            #[allow(clippy::cast_possible_truncation)]
+            let score = row.get::<_, f64>(5)? as f32;
+            result.score = score;
+            let snippet: Option<String> = row.get(6)?;
+            result.highlights = snippet
+                .filter(|snippet| snippet.contains(MATCH_START))
+                .map(|snippet| vec![snippet_html(&snippet)])
+                .unwrap_or_default();
+            Ok(result)
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn get_one(&self, source: &Source, id: &str) -> Result<Option<SearchResult>> {
+        Ok(find_by_id(self.storage.connection(), source, id)?.map(|(_, result)| result))
+    }
+}
+
+/// Rowids of the documents of `source` with result IDs `ids`; unknown IDs
+/// are ignored.
+fn excluded_rowids<'a>(
+    conn: &Connection,
+    source: &Source,
+    ids: impl Iterator<Item = &'a str>,
+) -> Result<HashSet<i64>> {
+    let mut rowids = HashSet::new();
+    for id in ids {
+        if let Some((rowid, _)) = find_by_id(conn, source, id)? {
+            rowids.insert(rowid);
+        }
+    }
+    Ok(rowids)
+}
+
+/// The document of `source` whose result ID is `id`, with its rowid.
+fn find_by_id(conn: &Connection, source: &Source, id: &str) -> Result<Option<(i64, SearchResult)>> {
+    let Source {
+        table,
+        columns,
+        filter,
+        lookup,
+        ..
+    } = source;
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {columns}, d.rowid FROM {table} d WHERE {lookup} AND {filter}"
+    ))?;
+    let mut rows = if source.doc_type == DocType::GrokMessage {
+        // Chat ID, seconds, nanoseconds and sender joined by underscores
+        let mut parts = id.rsplitn(4, '_');
+        let (Some(sender), Some(_), Some(_), Some(chat_id)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Ok(None);
+        };
+        stmt.query(params![chat_id, sender])?
+    } else {
+        stmt.query(params![id])?
+    };
+    while let Some(row) = rows.next()? {
+        let result = result_from_row(source.doc_type, row)?;
+        if result.id == id {
+            return Ok(Some((row.get(5)?, result)));
+        }
+    }
+    Ok(None)
+}
+
+impl TextIndex for FtsIndex {
+    fn search(
+        &self,
+        query: &str,
+        doc_types: Option<&[DocType]>,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let empty = query.trim().is_empty();
+        if limit == 0 || (!empty && match_expression(query, "text").is_none()) {
+            return Ok(Vec::new());
+        }
+
+        let mut results = Vec::new();
+        for source in Self::sources(doc_types) {
+            let expression = (!empty)
+                .then(|| match_expression(query, &self.columns(source)))
+                .flatten();
+            results.extend(self.search_source(source, expression.as_deref(), limit)?);
+        }
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit);
+        if !empty {
+            for result in &mut results {
+                result.match_ranges = match_ranges(&result.text, query);
+            }
+        }
+        Ok(results)
+    }
+
+    fn get_by_ids(&self, lookups: &[DocLookup<'_>]) -> Result<Vec<Option<SearchResult>>> {
+        lookups
+            .iter()
+            .map(|lookup| {
+                for source in &SOURCES {
+                    if lookup
+                        .doc_type
+                        .is_some_and(|doc_type| doc_type != source.doc_type.as_str())
+                    {
+                        continue;
+                    }
+                    if let Some(result) = self.get_one(source, lookup.id)? {
+                        return Ok(Some(result));
+                    }
+                }
+                Ok(None)
+            })
+            .collect()
+    }
+
+    fn match_timestamps(
+        &self,
+        query: &str,
+        doc_types: Option<&[DocType]>,
+        excluded: &[(&str, &str)],
+    ) -> Result<Vec<i64>> {
+        let empty = query.trim().is_empty();
+        let conn = self.storage.connection();
+        let mut timestamps = Vec::new();
+        for source in Self::sources(doc_types) {
+            let Source {
+                fts,
+                table,
+                join,
+                filter,
+                ..
+            } = source;
+            // Likes and bookmarks carry no timestamp
+            let created_at = if matches!(source.doc_type, DocType::Like | DocType::Bookmark) {
+                "NULL"
+            } else {
+                "d.created_at"
+            };
+            let rows: Vec<(i64, Option<String>)> = if empty {
+                let mut stmt = conn.prepare_cached(&format!(
+                    "SELECT d.rowid, {created_at} FROM {table} d WHERE {filter}"
+                ))?;
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<_>>()?
+            } else {
+                let Some(expression) = match_expression(query, &self.columns(source)) else {
+                    return Ok(Vec::new());
+                };
+                let mut stmt = conn.prepare_cached(&format!(
+                    "SELECT d.rowid, {created_at} FROM {fts} f JOIN {table} d ON {join} \
+                     WHERE {fts} MATCH ?1 AND {filter}"
+                ))?;
+                stmt.query_map(params![expression], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<_>>()?
+            };
+            let skipped = excluded_rowids(
+                conn,
+                source,
+                excluded
+                    .iter()
+                    .filter(|(doc_type, _)| *doc_type == source.doc_type.as_str())
+                    .map(|&(_, id)| id),
+            )?;
+            timestamps.extend(
+                rows.iter()
+                    .filter(|(rowid, _)| !skipped.contains(rowid))
+                    .map(|(_, created_at)| parse_created_at(created_at.as_deref()).timestamp()),
+            );
+        }
+        Ok(timestamps)
+    }
+
+    fn doc_count(&self) -> Result<u64> {
+        let mut count = 0;
+        for Source { table, filter, .. } in &SOURCES {
+            let rows: i64 = self.storage.connection().query_row(
+                &format!("SELECT COUNT(*) FROM {table} d WHERE {filter}"),
+                [],
+                |row| row.get(0),
+            )?;
+            count += u64::try_from(rows).unwrap_or(0);
+        }
+        Ok(count)
+    }
+
+    fn set_snippets(&mut self, enabled: bool) {
+        self.snippets = enabled;
+    }
+
+    fn set_scope(&mut self, scope: SearchScope) {
+        self.scope = scope;
+    }
+}
+
+/// Documents read by [`scan_documents`] and the ones that matched.
+#[derive(Debug, Default)]
+pub struct Scan {
+    /// Matches in table order, up to the scan's limit
+    pub results: Vec<SearchResult>,
+    /// Matches per scanned type, including those past the limit
+    pub matched: Vec<(DocType, usize)>,
+    /// Documents whose text was checked
+    pub scanned: usize,
+}
+
+impl Scan {
+    /// All matches, including those past the limit.
+    #[must_use]
+    pub fn total_matched(&self) -> usize {
+        self.matched.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// Check the stored text of every document of `doc_types` with `find`,
+/// without using the FTS tables or the Tantivy index.
+///
+/// `find` returns the match ranges in a text, empty when it does not
+/// match; texts are checked in parallel. Results carry the ranges as
+/// `match_ranges` and come in table order (tweets, likes, bookmarks, DMs,
+/// Grok), `limit` at most; `Some(0)` only counts matches. Documents in
+/// `excluded` (IDs by doc type, e.g. hidden ones) are neither scanned nor
+/// counted.
+///
+/// # Errors
+///
+/// Returns an error if a table cannot be read.
+pub fn scan_documents<S: BuildHasher, T: BuildHasher>(
+    storage: &Storage,
+    doc_types: Option<&[DocType]>,
+    limit: Option<usize>,
+    excluded: &HashMap<String, HashSet<String, T>, S>,
+    find: impl Fn(&str) -> Vec<TextRange> + Sync,
+) -> Result<Scan> {
+    let conn = storage.connection();
+    let mut scan = Scan::default();
+    for source in FtsIndex::sources(doc_types) {
+        let Source {
+            table,
+            text,
+            columns,
+            filter,
+            ..
+        } = source;
+        let skipped = excluded_rowids(
+            conn,
+            source,
+            excluded
+                .get(source.doc_type.as_str())
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        )?;
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT d.rowid, {text} FROM {table} d WHERE {filter}"
+        ))?;
+        let rows: Vec<(i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter(|row| !matches!(row, Ok((rowid, _)) if skipped.contains(rowid)))
+            .collect::<rusqlite::Result<_>>()?;
+        scan.scanned += rows.len();
+
+        let matches: Vec<(i64, Vec<TextRange>)> = rows
+            .par_iter()
+            .filter_map(|(rowid, text)| {
+                let ranges = find(text);
+                (!ranges.is_empty()).then_some((*rowid, ranges))
+            })
+            .collect();
+        scan.matched.push((source.doc_type, matches.len()));
+
+        let mut lookup = conn.prepare_cached(&format!(
+            "SELECT {columns} FROM {table} d WHERE d.rowid = ?1"
+        ))?;
+        for (rowid, ranges) in matches {
+            if limit.is_some_and(|limit| scan.results.len() >= limit) {
+                break;
+            }
+            let mut result =
+                lookup.query_row(params![rowid], |row| result_from_row(source.doc_type, row))?;
+            result.match_ranges = ranges;
+            scan.results.push(result);
+        }
+    }
+    Ok(scan)
+}
+
+/// Build a result from the `columns` of a [`Source`].
+fn result_from_row(doc_type: DocType, row: &rusqlite::Row<'_>) -> rusqlite::Result<SearchResult> {
+    let id: String = row.get(0)?;
+    let created_at = parse_created_at(row.get::<_, Option<String>>(2)?.as_deref());
+    let (id, result_type) = match doc_type {
+        DocType::Tweet => (id, SearchResultType::Tweet),
+        DocType::Like => (id, SearchResultType::Like),
+        DocType::Bookmark => (id, SearchResultType::Bookmark),
+        DocType::DirectMessage => (id, SearchResultType::DirectMessage),
+        // Same ID format as the Tantivy index and embeddings
+        DocType::GrokMessage => (
+            format!(
+                "{id}_{}_{}_{}",
+                created_at.timestamp(),
+                created_at.timestamp_subsec_nanos(),
+                row.get::<_, String>(4)?
+            ),
+            SearchResultType::GrokMessage,
+        ),
+    };
+    let metadata: String = row.get(3)?;
+    Ok(SearchResult {
+        result_type,
+        id,
+        text: row.get(1)?,
+        // The Tantivy index keeps whole seconds
+        created_at: DateTime::from_timestamp(created_at.timestamp(), 0).unwrap_or_default(),
+        score: 1.0,
+        highlights: Vec::new(),
+        match_ranges: Vec::new(),
+        metadata: serde_json::from_str(&metadata).unwrap_or_default(),
+    })
+}
+
+fn parse_created_at(value: Option<&str>) -> DateTime<Utc> {
+    value
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        .map_or(DateTime::UNIX_EPOCH, |dt| dt.with_timezone(&Utc))
+}
+
+/// HTML for a snippet: text escaped, matches in `<b>`, like Tantivy's snippets.
+fn snippet_html(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len() + 16);
+    for c in snippet.chars() {
+        match c {
+            MATCH_START => html.push_str("<b>"),
+            MATCH_END => html.push_str("</b>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#x27;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+fn quote(text: &str, prefix: bool) -> String {
+    let star = if prefix { "*" } else { "" };
+    format!("\"{}\"{star}", text.replace('"', "\"\""))
+}
+
+/// Translate an `xf search` query into an FTS5 expression on `column`.
+///
+/// Returns `None` when nothing in the query can match, such as a query of
+/// only punctuation or only exclusions.
+#[must_use]
+pub fn match_expression(query: &str, column: &str) -> Option<String> {
+    // Like Tantivy's prefix field, prefixes only apply to queries without phrases
+    let prefix = !query.contains('"');
+    let mut include = String::new();
+    let mut exclude = Vec::new();
+    let mut operator = "OR";
+    let mut negate = false;
+    let mut rest = query.trim_start();
+    while !rest.is_empty() {
+        let (token, phrase, tail) = rest.strip_prefix('"').map_or_else(
+            || {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '"')
+                    .unwrap_or(rest.len());
+                (&rest[..end], false, &rest[end..])
+            },
+            |after| {
+                let end = after.find('"').unwrap_or(after.len());
+                (&after[..end], true, after.get(end + 1..).unwrap_or(""))
+            },
+        );
+        rest = tail.trim_start();
+
+        let mut excluded = std::mem::take(&mut negate);
+        let mut text = token;
+        if !phrase {
+            match token {
+                "AND" | "OR" => {
+                    operator = if token == "AND" { "AND" } else { "OR" };
+                    continue;
+                }
+                "NOT" => {
+                    negate = true;
+                    continue;
+                }
+                _ => {}
+            }
+            excluded |= token.starts_with('-');
+            text = token.trim_start_matches(['-', '+']);
+            if text.is_empty() {
+                // A sign in front of a phrase
+                negate = excluded;
+                continue;
+            }
+        }
+
+        let text = text.replace(['(', ')'], " ");
+        if !text.chars().any(char::is_alphanumeric) {
+            continue;
+        }
+        let term = quote(&text, prefix && !phrase);
+        if excluded {
+            exclude.push(term);
+        } else {
+            if !include.is_empty() {
+                include.push(' ');
+                include.push_str(operator);
+                include.push(' ');
+            }
+            include.push_str(&term);
+        }
+        operator = "OR";
+    }
+
+    if include.is_empty() {
+        None
+    } else if exclude.is_empty() {
+        Some(format!("{column} : ({include})"))
+    } else {
+        Some(format!(
+            "{column} : (({include}) NOT ({}))",
+            exclude.join(" OR ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DirectMessage, DmConversation, GrokMessage, Like, Tweet};
+    use crate::search::SearchEngine;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_match_expression() {
+        assert_eq!(
+            match_expression("rust lang", "t").as_deref(),
+            Some(r#"t : ("rust"* OR "lang"*)"#)
+        );
+        assert_eq!(
+            match_expression(r#""hello world" AND rust -java"#, "t").as_deref(),
+            Some(r#"t : (("hello world" AND "rust") NOT ("java"))"#)
+        );
+        assert_eq!(
+            match_expression(r#"rust NOT -"a b" x"#, "t").as_deref(),
+            Some(r#"t : (("rust" OR "x") NOT ("a b"))"#)
+        );
+        assert_eq!(match_expression("-rust ???", "t"), None);
+    }
+
+    /// The same documents in both engines give the same results.
+    #[test]
+    fn test_matches_tantivy_results() {
+        let mut storage = Storage::open_memory().unwrap();
+        let engine = SearchEngine::open_memory().unwrap();
+        let mut writer = engine.writer(15_000_000).unwrap();
+        let at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+
+        let tweets = vec![Tweet {
+            created_at: at,
+            source: Some("web".to_string()),
+            favorite_count: 4,
+            retweet_count: 1,
+            in_reply_to_screen_name: Some("friend".to_string()),
+            hashtags: vec!["rust".to_string()],
+            ..Tweet::fixture("1", "Learning Rust <today>")
+        }];
+        let likes = vec![Like {
+            tweet_id: "2".to_string(),
+            full_text: Some("rustaceans unite".to_string()),
+            expanded_url: None,
+        }];
+        let conversations = vec![DmConversation {
+            conversation_id: "c".to_string(),
+            messages: vec![DirectMessage {
+                id: "3".to_string(),
+                conversation_id: "c".to_string(),
+                sender_id: "a".to_string(),
+                recipient_id: "b".to_string(),
+                text: "rust meetup tonight".to_string(),
+                created_at: at,
+                urls: Vec::new(),
+                media_urls: Vec::new(),
+            }],
+        }];
+        let grok = vec![GrokMessage {
+            chat_id: "chat".to_string(),
+            message: "explain rust lifetimes".to_string(),
+            sender: "user".to_string(),
+            created_at: at,
+            grok_mode: None,
+        }];
+        storage.store_tweets(&tweets).unwrap();
+        storage.store_likes(&likes).unwrap();
+        storage.store_dm_conversations(&conversations).unwrap();
+        storage.store_grok_messages(&grok).unwrap();
+        engine.index_tweets(&mut writer, &tweets).unwrap();
+        engine.index_likes(&mut writer, &likes).unwrap();
+        engine.index_dms(&mut writer, &conversations).unwrap();
+        engine.index_grok_messages(&mut writer, &grok).unwrap();
+        writer.commit().unwrap();
+        engine.reload().unwrap();
+        let fts = FtsIndex::new(storage);
+        let tantivy: &dyn TextIndex = &engine;
+
+        let key = |results: Vec<SearchResult>| {
+            let mut results: Vec<_> = results
+                .into_iter()
+                .map(|r| (r.result_type, r.id, r.text, r.created_at, r.metadata))
+                .collect();
+            results.sort_by(|a, b| a.1.cmp(&b.1));
+            results
+        };
+        for query in ["rust", "rus", "meetup OR lifetimes", "", "nothing"] {
+            assert_eq!(
+                key(fts.search(query, None, 10).unwrap()),
+                key(tantivy.search(query, None, 10).unwrap()),
+                "query {query:?}"
+            );
+        }
+        assert_eq!(fts.doc_count().unwrap(), tantivy.doc_count().unwrap());
+
+        let hits = fts.search("learning", Some(&[DocType::Tweet]), 10).unwrap();
+        assert_eq!(hits[0].highlights, ["<b>Learning</b> Rust &lt;today&gt;"]);
+
+        let grok_id = &fts.search("lifetimes", None, 1).unwrap()[0].id;
+        let lookups = [
+            DocLookup::with_type(grok_id, "grok"),
+            DocLookup::new("2"),
+            DocLookup::with_type("2", "tweet"),
+        ];
+        let found: Vec<_> = fts
+            .get_by_ids(&lookups)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.map(|r| r.id))
+            .collect();
+        assert_eq!(found, [Some(grok_id.clone()), Some("2".to_string()), None]);
+
+        let mut timestamps = fts.match_timestamps("rust", None, &[]).unwrap();
+        timestamps.sort_unstable();
+        let mut expected = tantivy.match_timestamps("rust", None, &[]).unwrap();
+        expected.sort_unstable();
+        assert_eq!(timestamps, expected);
+    }
+
+    #[test]
+    fn test_search_scope_covers_notes() {
+        let mut storage = Storage::open_memory().unwrap();
+        let at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        storage
+            .store_likes(&[Like {
+                tweet_id: "2".to_string(),
+                full_text: Some("a thread on borrow checking".to_string()),
+                expanded_url: None,
+            }])
+            .unwrap();
+        storage
+            .store_grok_messages(&[GrokMessage {
+                chat_id: "chat".to_string(),
+                message: "explain lifetimes".to_string(),
+                sender: "user".to_string(),
+                created_at: at,
+                grok_mode: None,
+            }])
+            .unwrap();
+        let mut fts = FtsIndex::new(storage);
+        let grok_id = fts.search("lifetimes", None, 1).unwrap()[0].id.clone();
+        // Exclusions match the type as well as the ID
+        let excluded = |pairs: &[(&str, &str)]| fts.match_timestamps("", None, pairs).unwrap();
+        assert_eq!(excluded(&[("tweet", "2")]).len(), 2);
+        assert!(excluded(&[("like", "2"), ("grok", &grok_id)]).is_empty());
+        fts.storage
+            .add_note("2", "like", "reread before the talk")
+            .unwrap();
+        fts.storage
+            .add_note(&grok_id, "grok", "good talk material")
+            .unwrap();
+        // Rebuilding the FTS tables keeps the notes
+        fts.storage.rebuild_fts_tables().unwrap();
+
+        let ids = |fts: &FtsIndex, query: &str| -> Vec<String> {
+            let mut ids: Vec<_> = fts
+                .search(query, None, 10)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(&fts, "talk"), ["2", grok_id.as_str()]);
+        fts.set_scope(SearchScope::Main);
+        assert!(ids(&fts, "talk").is_empty());
+        assert_eq!(ids(&fts, "borrow"), ["2"]);
+        fts.set_scope(SearchScope::Supplemental);
+        assert!(ids(&fts, "borrow").is_empty());
+        assert_eq!(ids(&fts, "rere"), ["2"]);
+        assert_eq!(
+            fts.match_timestamps("material", None, &[]).unwrap().len(),
+            1
+        );
+
+        let note = fts.storage.get_notes(Some("2"), None).unwrap().remove(0);
+        fts.storage.delete_note(note.id).unwrap();
+        assert_eq!(ids(&fts, "talk"), [grok_id.as_str()]);
+    }
+}
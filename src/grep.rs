@@ -0,0 +1,186 @@
+//! Literal and regex scans over stored text (`xf grep`).
+//!
+//! Unlike `xf search`, a scan reads every document's text straight from the
+//! database and matches it byte for byte, so it finds what the tokenizers
+//! cannot express (punctuation, partial words, `C++`, `@handle:`) and what a
+//! stale or incomplete index would miss. It is the ground truth to check
+//! `xf search` against, at the cost of reading every row.
+
+use crate::fts::{Scan, scan_documents};
+use crate::model::TextRange;
+use crate::search::DocType;
+use crate::storage::Storage;
+use anyhow::{Context, Result};
+use memchr::memmem::Finder;
+use regex::{Regex, RegexBuilder};
+use std::collections::{HashMap, HashSet};
+use std::hash::BuildHasher;
+
+/// What to look for in each document's text.
+#[derive(Debug)]
+pub enum Pattern {
+    /// Exact bytes, found with a SIMD substring search
+    Literal(Finder<'static>),
+    /// ASCII text matched ignoring ASCII case; the needle is lowercased
+    LiteralIgnoreAsciiCase(Finder<'static>),
+    Regex(Regex),
+}
+
+impl Pattern {
+    /// A literal pattern. Ignoring case uses a fast ASCII comparison when
+    /// `needle` is ASCII and a Unicode-aware regex otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics only if an escaped literal fails to compile as a regex (a bug
+    /// in the `regex` crate).
+    #[must_use]
+    pub fn literal(needle: &str, ignore_case: bool) -> Self {
+        if !ignore_case {
+            return Self::Literal(Finder::new(needle.as_bytes()).into_owned());
+        }
+        if needle.is_ascii() {
+            let lower = needle.to_ascii_lowercase();
+            return Self::LiteralIgnoreAsciiCase(Finder::new(lower.as_bytes()).into_owned());
+        }
+        Self::Regex(
+            RegexBuilder::new(&regex::escape(needle))
+                .case_insensitive(true)
+                .build()
+                .expect("escaped literal is a valid regex"),
+        )
+    }
+
+    /// A regular expression in the `regex` crate's syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pattern` is not a valid regular expression.
+    pub fn regex(pattern: &str, ignore_case: bool) -> Result<Self> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .with_context(|| format!("Invalid regular expression: {pattern}"))?;
+        Ok(Self::Regex(regex))
+    }
+
+    /// Byte ranges of the non-overlapping matches in `text`.
+    #[must_use]
+    pub fn find(&self, text: &str) -> Vec<TextRange> {
+        let range = |start: usize, len: usize| TextRange {
+            start,
+            end: start + len,
+        };
+        match self {
+            Self::Literal(finder) => finder
+                .find_iter(text.as_bytes())
+                .map(|start| range(start, finder.needle().len()))
+                .collect(),
+            Self::LiteralIgnoreAsciiCase(finder) => {
+                // ASCII lowercasing keeps byte offsets, so ranges map back to `text`
+                let lower = text.to_ascii_lowercase();
+                finder
+                    .find_iter(lower.as_bytes())
+                    .map(|start| range(start, finder.needle().len()))
+                    .collect()
+            }
+            Self::Regex(regex) => regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| range(m.start(), m.len()))
+                .collect(),
+        }
+    }
+}
+
+/// Scan the stored documents of `doc_types` for `pattern`.
+///
+/// Results come in table order, `limit` at most (`None` for all); the scan
+/// still counts every match. Documents in `hidden` (IDs by doc type) are
+/// skipped.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be read.
+pub fn grep<S: BuildHasher, T: BuildHasher>(
+    storage: &Storage,
+    pattern: &Pattern,
+    doc_types: Option<&[DocType]>,
+    limit: Option<usize>,
+    hidden: &HashMap<String, HashSet<String, T>, S>,
+) -> Result<Scan> {
+    scan_documents(storage, doc_types, limit, hidden, |text| pattern.find(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Like, SearchResultType, Tweet};
+
+    fn spans(pattern: &Pattern, text: &str) -> Vec<(usize, usize)> {
+        pattern
+            .find(text)
+            .into_iter()
+            .map(|r| (r.start, r.end))
+            .collect()
+    }
+
+    #[test]
+    fn literal_matches_bytes_and_ignores_ascii_case_on_request() {
+        let text = "Ünïcode C++ and c++";
+        assert_eq!(spans(&Pattern::literal("C++", false), text), vec![(10, 13)]);
+        assert_eq!(
+            spans(&Pattern::literal("C++", true), text),
+            vec![(10, 13), (18, 21)]
+        );
+        assert_eq!(spans(&Pattern::literal("ünï", true), text), vec![(0, 5)]);
+        assert!(Pattern::literal("rust", false).find(text).is_empty());
+    }
+
+    #[test]
+    fn regex_skips_empty_matches_and_reports_bad_patterns() {
+        let pattern = Pattern::regex(r"v\d+(\.\d+)?", false).unwrap();
+        assert_eq!(spans(&pattern, "v1 and v2.5"), vec![(0, 2), (7, 11)]);
+        assert!(Pattern::regex("x*", false).unwrap().find("abc").is_empty());
+        assert!(Pattern::regex("(", false).is_err());
+    }
+
+    #[test]
+    fn grep_scans_tables_and_counts_past_the_limit() {
+        let mut storage = Storage::open_memory().unwrap();
+        storage
+            .store_tweets(&[
+                Tweet::fixture("1", "shipping v2.0!"),
+                Tweet::fixture("2", "nothing here"),
+                Tweet::fixture("3", "v2.0 notes"),
+            ])
+            .unwrap();
+        storage
+            .store_likes(&[Like {
+                tweet_id: "9".to_string(),
+                full_text: Some("liked v2.0".to_string()),
+                expanded_url: None,
+            }])
+            .unwrap();
+        let pattern = Pattern::literal("v2.0", false);
+
+        let none: HashMap<String, HashSet<String>> = HashMap::new();
+        let scan = grep(&storage, &pattern, None, None, &none).unwrap();
+        assert_eq!(scan.scanned, 4);
+        assert_eq!(scan.total_matched(), 3);
+        assert_eq!(scan.results[2].result_type, SearchResultType::Like);
+        assert_eq!(scan.results[2].match_ranges[0].start, 6);
+
+        let tweets = Some(&[DocType::Tweet][..]);
+        let scan = grep(&storage, &pattern, tweets, Some(1), &none).unwrap();
+        assert_eq!(scan.scanned, 3);
+        assert_eq!(scan.matched, vec![(DocType::Tweet, 2)]);
+        assert_eq!(scan.results.len(), 1);
+
+        let hidden = HashMap::from([("tweet".to_string(), HashSet::from(["1".to_string()]))]);
+        let scan = grep(&storage, &pattern, tweets, None, &hidden).unwrap();
+        assert_eq!(scan.scanned, 2);
+        assert_eq!(scan.matched, vec![(DocType::Tweet, 1)]);
+        assert_eq!(scan.results[0].id, "3");
+    }
+}
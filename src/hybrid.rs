@@ -20,12 +20,30 @@
 //! 1. RRF score (descending)
 //! 2. Appears in both lists (bonus)
 //! 3. Document ID (ascending)
-
-use crate::model::{SearchResult, SearchResultType};
-use crate::vector::VectorSearchResult;
-use clap::ValueEnum;
+//!
+//! # Query Embedding Cache
+//!
+//! Query embeddings are memoized in a small LRU keyed by embedder and
+//! canonicalized query, so refining a query in a long-running session does
+//! not re-embed text it has already seen.
+//!
+//! # Conversation Windows
+//!
+//! DM threads are also embedded as whole windows of consecutive messages
+//! (doc type `dm_conv`). A window hit is folded into a `dm` hit on the
+//! window's representative message and flagged with `conversation_match`
+//! metadata, so a conversation can surface even when no single message
+//! matches the query well.
+
+use crate::canonicalize::is_stopword;
+use crate::embedder::{Embedder, EmbedderResult};
+use crate::model::{SearchResult, SearchResultType, match_ranges};
+use crate::search::{DocLookup, DocType, TextIndex};
+use crate::vector::{SemanticIndex, VectorSearchResult};
+use anyhow::Result;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, PoisonError};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct DocKey<'a> {
@@ -47,8 +65,12 @@ const RRF_K: f32 = 60.0;
 /// source to ensure good coverage after fusion.
 pub const CANDIDATE_MULTIPLIER: usize = 3;
 
+/// Vector doc type for embeddings of whole DM conversation windows.
+pub const DM_CONVERSATION_DOC_TYPE: &str = "dm_conv";
+
 /// Search mode for hybrid search.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum SearchMode {
     /// Keyword-only search using BM25.
     Lexical,
@@ -57,6 +79,8 @@ pub enum SearchMode {
     /// Hybrid search combining lexical and semantic with RRF (default).
     #[default]
     Hybrid,
+    /// Pick lexical, semantic or hybrid from the query itself.
+    Auto,
 }
 
 impl std::fmt::Display for SearchMode {
@@ -65,6 +89,7 @@ impl std::fmt::Display for SearchMode {
             Self::Lexical => write!(f, "lexical"),
             Self::Semantic => write!(f, "semantic"),
             Self::Hybrid => write!(f, "hybrid"),
+            Self::Auto => write!(f, "auto"),
         }
     }
 }
@@ -77,13 +102,138 @@ impl std::str::FromStr for SearchMode {
             "lexical" | "keyword" | "bm25" => Ok(Self::Lexical),
             "semantic" | "vector" | "embedding" => Ok(Self::Semantic),
             "hybrid" | "rrf" | "both" => Ok(Self::Hybrid),
+            "auto" => Ok(Self::Auto),
             _ => Err(format!(
-                "unknown search mode: '{s}'. Use 'lexical', 'semantic', or 'hybrid'"
+                "unknown search mode: '{s}'. Use 'lexical', 'semantic', 'hybrid', or 'auto'"
             )),
         }
     }
 }
 
+/// Content words from which `auto` treats a query as a description and
+/// searches by meaning.
+const AUTO_SEMANTIC_MIN_TERMS: usize = 6;
+
+/// The mode [`SearchMode::Auto`] picked for a query, and why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoModeChoice {
+    /// Lexical, semantic or hybrid; never `Auto`.
+    pub mode: SearchMode,
+    /// Short explanation, shown in verbose output.
+    pub reason: &'static str,
+}
+
+impl AutoModeChoice {
+    const fn new(mode: SearchMode, reason: &'static str) -> Self {
+        Self { mode, reason }
+    }
+}
+
+/// Pick a search mode for `query`.
+///
+/// Query syntax (quotes, operators, fields, wildcards) and exact identifiers
+/// (handles, hashtags, URLs, IDs) need keyword search, as does any query when
+/// there are no embeddings to search. Otherwise the query's content words
+/// decide: words that never appear in the archive can only be found by
+/// meaning, a single known keyword is best matched exactly, a long
+/// description is searched semantically, and anything in between uses
+/// hybrid search. `is_known_term` reports whether a lowercase word occurs in
+/// the indexed text.
+///
+/// # Errors
+///
+/// Returns an error if `is_known_term` fails.
+pub fn choose_search_mode<F>(
+    query: &str,
+    has_embeddings: bool,
+    mut is_known_term: F,
+) -> Result<AutoModeChoice>
+where
+    F: FnMut(&str) -> Result<bool>,
+{
+    if query.trim().is_empty() {
+        return Ok(AutoModeChoice::new(SearchMode::Lexical, "empty query"));
+    }
+    if has_query_syntax(query) {
+        return Ok(AutoModeChoice::new(
+            SearchMode::Lexical,
+            "query uses quotes or operators",
+        ));
+    }
+    if query.split_whitespace().any(is_identifier) {
+        return Ok(AutoModeChoice::new(
+            SearchMode::Lexical,
+            "query names a handle, hashtag, URL or ID",
+        ));
+    }
+    if !has_embeddings {
+        return Ok(AutoModeChoice::new(SearchMode::Lexical, "no embeddings"));
+    }
+
+    let mut terms: Vec<String> = Vec::new();
+    for word in query
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+    {
+        if word.chars().count() >= 2 && !is_stopword(&word) && !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+    if terms.is_empty() {
+        return Ok(AutoModeChoice::new(
+            SearchMode::Hybrid,
+            "query has only common words",
+        ));
+    }
+
+    let mut unknown = 0;
+    for term in &terms {
+        if !is_known_term(term)? {
+            unknown += 1;
+        }
+    }
+    let choice = if unknown == terms.len() {
+        AutoModeChoice::new(SearchMode::Semantic, "no query word appears in the archive")
+    } else if unknown > 0 {
+        AutoModeChoice::new(
+            SearchMode::Hybrid,
+            "some query words do not appear in the archive",
+        )
+    } else if terms.len() == 1 {
+        AutoModeChoice::new(SearchMode::Lexical, "single keyword")
+    } else if terms.len() >= AUTO_SEMANTIC_MIN_TERMS {
+        AutoModeChoice::new(SearchMode::Semantic, "descriptive query")
+    } else {
+        AutoModeChoice::new(SearchMode::Hybrid, "short multi-word query")
+    };
+    Ok(choice)
+}
+
+/// Whether the query uses keyword-search syntax: phrases, boolean
+/// operators, required/excluded terms, field filters or wildcards.
+fn has_query_syntax(query: &str) -> bool {
+    query.contains('"')
+        || query.split_whitespace().any(|token| {
+            matches!(token, "AND" | "OR" | "NOT")
+                || (token.len() > 1 && (token.starts_with('+') || token.starts_with('-')))
+                || token.contains('*')
+                || (token.contains(':') && !is_url(token))
+        })
+}
+
+/// Whether a token is a handle, hashtag, URL or numeric ID.
+fn is_identifier(token: &str) -> bool {
+    let token = token.trim_end_matches([',', '.', '?', '!']);
+    (token.len() > 1 && (token.starts_with('@') || token.starts_with('#')))
+        || is_url(token)
+        || (token.len() >= 5 && token.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_url(token: &str) -> bool {
+    let lower = token.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("www.")
+}
+
 /// Hybrid score tracking for RRF fusion.
 #[derive(Debug, Default, Clone)]
 struct HybridScore {
@@ -114,6 +264,7 @@ const fn result_type_str(result_type: SearchResultType) -> &'static str {
     match result_type {
         SearchResultType::Tweet => "tweet",
         SearchResultType::Like => "like",
+        SearchResultType::Bookmark => "bookmark",
         SearchResultType::DirectMessage => "dm",
         SearchResultType::GrokMessage => "grok",
     }
@@ -212,6 +363,302 @@ pub const fn candidate_count(limit: usize, offset: usize) -> usize {
         .saturating_mul(CANDIDATE_MULTIPLIER)
 }
 
+/// Number of query embeddings kept by the process-wide cache.
+pub const QUERY_EMBEDDING_CACHE_SIZE: usize = 64;
+
+/// Least-recently-used cache of canonicalized query → embedding.
+///
+/// Entries are keyed by embedder id as well as query text, so switching
+/// embedders never returns a vector from a different model.
+#[derive(Debug)]
+pub struct QueryEmbeddingCache {
+    capacity: usize,
+    /// (`embedder_id`, `canonical_query`, embedding), most recently used last.
+    entries: Vec<(String, String, Vec<f32>)>,
+}
+
+impl QueryEmbeddingCache {
+    /// Create an empty cache holding at most `capacity` embeddings.
+    #[must_use]
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Number of cached embeddings.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up an embedding, marking it as most recently used.
+    pub fn get(&mut self, embedder_id: &str, canonical_query: &str) -> Option<Vec<f32>> {
+        let pos = self
+            .entries
+            .iter()
+            .position(|(id, query, _)| id == embedder_id && query == canonical_query)?;
+        let entry = self.entries.remove(pos);
+        let embedding = entry.2.clone();
+        self.entries.push(entry);
+        Some(embedding)
+    }
+
+    /// Insert an embedding, evicting the least recently used entry if full.
+    pub fn insert(&mut self, embedder_id: &str, canonical_query: &str, embedding: Vec<f32>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries
+            .retain(|(id, query, _)| !(id == embedder_id && query == canonical_query));
+        if self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((
+            embedder_id.to_string(),
+            canonical_query.to_string(),
+            embedding,
+        ));
+    }
+}
+
+static QUERY_EMBEDDINGS: Mutex<QueryEmbeddingCache> =
+    Mutex::new(QueryEmbeddingCache::new(QUERY_EMBEDDING_CACHE_SIZE));
+
+/// Embed a canonicalized query, reusing the process-wide cache.
+///
+/// # Errors
+///
+/// Returns an error if the embedder fails on a cache miss.
+pub fn embed_query(embedder: &dyn Embedder, canonical_query: &str) -> EmbedderResult<Vec<f32>> {
+    let cached = QUERY_EMBEDDINGS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(embedder.id(), canonical_query);
+    if let Some(embedding) = cached {
+        return Ok(embedding);
+    }
+
+    // Embed without holding the lock so slow embedders don't block other callers
+    let embedding = embedder.embed_query(canonical_query)?;
+    QUERY_EMBEDDINGS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(embedder.id(), canonical_query, embedding.clone());
+    Ok(embedding)
+}
+
+/// Run a hybrid (lexical + semantic, RRF-fused) search for the top `limit` results.
+///
+/// Without a vector index, or when the query has no embeddable words, this
+/// is a lexical search. `embedder` must be the model the vector index was
+/// built with.
+///
+/// # Errors
+///
+/// Returns an error if the lexical search or the document lookup fails.
+pub fn hybrid_search(
+    search_engine: &dyn TextIndex,
+    vector_index: Option<&SemanticIndex>,
+    embedder: &dyn Embedder,
+    query: &str,
+    canonical_query: &str,
+    doc_types: Option<&[DocType]>,
+    limit: usize,
+) -> Result<Vec<SearchResult>> {
+    let candidate_count = candidate_count(limit, 0);
+
+    // Get lexical results
+    let lexical_results = search_engine.search(query, doc_types, candidate_count)?;
+
+    // Get semantic results (if embeddings exist and query canonicalizes)
+    let (semantic_results, conversation_ids) = get_semantic_results(
+        vector_index,
+        embedder,
+        canonical_query,
+        doc_types,
+        candidate_count,
+    );
+
+    let fused = rrf_fuse(&lexical_results, &semantic_results, limit, 0);
+
+    // Convert fused hits back to SearchResults
+    let mut lookups = Vec::new();
+    let mut lookup_indices = Vec::new();
+    for (idx, hit) in fused.iter().enumerate() {
+        if hit.lexical_rank.is_none() {
+            let lookup = if hit.doc_type.is_empty() {
+                DocLookup::new(hit.doc_id)
+            } else {
+                DocLookup::with_type(hit.doc_id, hit.doc_type)
+            };
+            lookups.push(lookup);
+            lookup_indices.push(idx);
+        }
+    }
+
+    let fetched = if lookups.is_empty() {
+        Vec::new()
+    } else {
+        search_engine.get_by_ids(&lookups)?
+    };
+
+    let mut fetched_by_index = vec![None; fused.len()];
+    for (idx, result) in lookup_indices.into_iter().zip(fetched) {
+        fetched_by_index[idx] = result;
+    }
+
+    let mut results = Vec::new();
+    for (idx, hit) in fused.iter().enumerate() {
+        // Prefer lexical result (has full data)
+        if let Some(rank) = hit.lexical_rank {
+            let mut result = lexical_results[rank].clone();
+            result.score = hit.score;
+            results.push(result);
+        } else if let Some(mut result) = fetched_by_index[idx].take() {
+            result.score = hit.score;
+            // Semantic matches may still share some words with the query
+            result.match_ranges = match_ranges(&result.text, query);
+            results.push(result);
+        }
+    }
+    mark_conversation_matches(&mut results, &conversation_ids);
+    Ok(results)
+}
+
+/// Get semantic search results from the vector index.
+///
+/// Returns no hits if vector index is None, query is empty, or embedding fails.
+fn get_semantic_results(
+    vector_index: Option<&SemanticIndex>,
+    embedder: &dyn Embedder,
+    canonical_query: &str,
+    doc_types: Option<&[DocType]>,
+    candidate_count: usize,
+) -> (Vec<VectorSearchResult>, HashSet<String>) {
+    let Some(vector_index) = vector_index else {
+        return (Vec::new(), HashSet::new());
+    };
+
+    if canonical_query.is_empty() {
+        return (Vec::new(), HashSet::new());
+    }
+
+    let Ok(query_embedding) = embed_query(embedder, canonical_query) else {
+        return (Vec::new(), HashSet::new());
+    };
+
+    let type_strs = doc_types.map(vector_doc_types);
+    let hits = vector_index.search_top_k(&query_embedding, candidate_count, type_strs.as_deref());
+    fold_conversation_hits(hits)
+}
+
+/// Vector doc types to search for the requested result types.
+///
+/// DM searches also cover conversation-window embeddings.
+fn vector_doc_types(types: &[DocType]) -> Vec<&'static str> {
+    let mut strs: Vec<&'static str> = types.iter().map(|t| t.as_str()).collect();
+    if types.contains(&DocType::DirectMessage) {
+        strs.push(DM_CONVERSATION_DOC_TYPE);
+    }
+    strs
+}
+
+/// Fold conversation-window hits into `dm` hits on their representative
+/// message, keeping the best-scoring hit per document.
+///
+/// Hits must be sorted by descending score. Returns the folded hits and the
+/// IDs of messages that were surfaced by their conversation window.
+fn fold_conversation_hits(
+    hits: Vec<VectorSearchResult>,
+) -> (Vec<VectorSearchResult>, HashSet<String>) {
+    let mut seen: HashSet<(String, &'static str)> = HashSet::with_capacity(hits.len());
+    let mut conversation_ids = HashSet::new();
+    let mut folded = Vec::with_capacity(hits.len());
+    for mut hit in hits {
+        let from_conversation = hit.doc_type == DM_CONVERSATION_DOC_TYPE;
+        if from_conversation {
+            hit.doc_type = DocType::DirectMessage.as_str();
+        }
+        if !seen.insert((hit.doc_id.clone(), hit.doc_type)) {
+            continue;
+        }
+        if from_conversation {
+            conversation_ids.insert(hit.doc_id.clone());
+        }
+        folded.push(hit);
+    }
+    (folded, conversation_ids)
+}
+
+/// Flag DM results that were surfaced by a conversation-window embedding.
+fn mark_conversation_matches(results: &mut [SearchResult], conversation_ids: &HashSet<String>) {
+    if conversation_ids.is_empty() {
+        return;
+    }
+    for result in results {
+        if result.result_type == SearchResultType::DirectMessage
+            && conversation_ids.contains(&result.id)
+        {
+            result.metadata["conversation_match"] = serde_json::json!(true);
+        }
+    }
+}
+
+/// Semantic-only search: the nearest `candidate_count` embeddings, resolved
+/// to full results from the search index. `embedder` must be the model the
+/// vector index was built with.
+///
+/// # Errors
+///
+/// Returns an error if the query cannot be embedded or the lookup fails.
+pub fn semantic_search(
+    search_engine: &dyn TextIndex,
+    vector_index: &SemanticIndex,
+    embedder: &dyn Embedder,
+    canonical_query: &str,
+    doc_types: Option<&[DocType]>,
+    candidate_count: usize,
+) -> Result<Vec<SearchResult>> {
+    if canonical_query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let query_embedding = embed_query(embedder, canonical_query)?;
+    let type_strs = doc_types.map(vector_doc_types);
+    let (semantic_hits, conversation_ids) = fold_conversation_hits(vector_index.search_top_k(
+        &query_embedding,
+        candidate_count,
+        type_strs.as_deref(),
+    ));
+
+    let lookups: Vec<_> = semantic_hits
+        .iter()
+        .map(|hit| DocLookup::with_type(&hit.doc_id, hit.doc_type))
+        .collect();
+    let fetched = search_engine.get_by_ids(&lookups)?;
+
+    // Look up full results from search engine by doc_id + type
+    let mut results: Vec<SearchResult> = semantic_hits
+        .into_iter()
+        .zip(fetched)
+        .filter_map(|(hit, result)| {
+            result.map(|mut result| {
+                result.score = hit.score;
+                result
+            })
+        })
+        .collect();
+    mark_conversation_matches(&mut results, &conversation_ids);
+    Ok(results)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,6 +673,7 @@ mod tests {
             created_at: Utc::now(),
             score,
             highlights: vec![],
+            match_ranges: Vec::new(),
             metadata: serde_json::Value::Null,
         }
     }
@@ -235,7 +683,9 @@ mod tests {
         match doc_type {
             "like" => "like",
             "dm" => "dm",
+            "dm_conv" => DM_CONVERSATION_DOC_TYPE,
             "grok" => "grok",
+            "bookmark" => "bookmark",
             _ => "tweet", // Default for "tweet" and unknown types
         }
     }
@@ -513,9 +963,71 @@ mod tests {
         );
         assert_eq!("hybrid".parse::<SearchMode>().unwrap(), SearchMode::Hybrid);
         assert_eq!("rrf".parse::<SearchMode>().unwrap(), SearchMode::Hybrid);
+        assert_eq!("auto".parse::<SearchMode>().unwrap(), SearchMode::Auto);
         assert!("invalid".parse::<SearchMode>().is_err());
     }
 
+    fn choose(query: &str, known: &[&str]) -> AutoModeChoice {
+        choose_search_mode(query, true, |term| Ok(known.contains(&term))).unwrap()
+    }
+
+    #[test]
+    fn test_auto_mode_keeps_syntax_and_identifiers_lexical() {
+        let known = ["rust", "async"];
+        for query in [
+            "\"rust async\"",
+            "rust AND async",
+            "rust -async",
+            "asy*",
+            "from:alice rust",
+            "@alice",
+            "#rustlang",
+            "https://example.com/post",
+            "1234567890",
+            "  ",
+        ] {
+            assert_eq!(choose(query, &known).mode, SearchMode::Lexical, "{query}");
+        }
+        // URLs are not field filters
+        assert_eq!(
+            choose("see https://x.com", &known).mode,
+            SearchMode::Lexical
+        );
+    }
+
+    #[test]
+    fn test_auto_mode_uses_terms_and_length() {
+        let known = [
+            "rust",
+            "async",
+            "feeling",
+            "overwhelmed",
+            "work",
+            "deadlines",
+        ];
+        assert_eq!(choose("rust", &known).mode, SearchMode::Lexical);
+        assert_eq!(choose("the rust", &known).mode, SearchMode::Lexical);
+        assert_eq!(choose("rust async", &known).mode, SearchMode::Hybrid);
+        assert_eq!(choose("rust tokio", &known).mode, SearchMode::Hybrid);
+        assert_eq!(choose("tokio runtime", &known).mode, SearchMode::Semantic);
+        assert_eq!(
+            choose(
+                "feeling overwhelmed at work by rust async deadlines",
+                &known
+            )
+            .mode,
+            SearchMode::Semantic
+        );
+        assert_eq!(choose("the and of", &known).mode, SearchMode::Hybrid);
+    }
+
+    #[test]
+    fn test_auto_mode_without_embeddings_is_lexical() {
+        let choice = choose_search_mode("tokio runtime", false, |_| Ok(false)).unwrap();
+        assert_eq!(choice.mode, SearchMode::Lexical);
+        assert_eq!(choice.reason, "no embeddings");
+    }
+
     #[test]
     fn test_candidate_count() {
         assert_eq!(candidate_count(10, 0), 30); // 10 * 3
@@ -539,6 +1051,36 @@ mod tests {
         assert!(matching.iter().any(|hit| hit.doc_type == "like"));
     }
 
+    #[test]
+    fn test_fold_conversation_hits() {
+        let hits = vec![
+            make_semantic_hit("m1", 0.9, "dm_conv"),
+            make_semantic_hit("m2", 0.8, "dm"),
+            make_semantic_hit("m1", 0.7, "dm"),
+            make_semantic_hit("m2", 0.6, "dm_conv"),
+            make_semantic_hit("m1", 0.5, "tweet"),
+        ];
+
+        let (folded, conversation_ids) = fold_conversation_hits(hits);
+
+        let keys: Vec<_> = folded
+            .iter()
+            .map(|h| (h.doc_id.as_str(), h.doc_type))
+            .collect();
+        assert_eq!(keys, vec![("m1", "dm"), ("m2", "dm"), ("m1", "tweet")]);
+        assert!((folded[0].score - 0.9).abs() < f32::EPSILON);
+        assert_eq!(conversation_ids, HashSet::from(["m1".to_string()]));
+    }
+
+    #[test]
+    fn test_vector_doc_types_include_conversations_for_dms() {
+        assert_eq!(vector_doc_types(&[DocType::Tweet]), vec!["tweet"]);
+        assert_eq!(
+            vector_doc_types(&[DocType::DirectMessage]),
+            vec!["dm", DM_CONVERSATION_DOC_TYPE]
+        );
+    }
+
     #[test]
     fn test_rrf_isomorphic_legacy_randomized() {
         let mut seed = 42u64;
@@ -600,4 +1142,29 @@ mod tests {
         let semantic_ptr = semantic[0].doc_id.as_ptr();
         assert_eq!(fused_ptr, semantic_ptr);
     }
+
+    #[test]
+    fn test_query_embedding_cache_evicts_least_recent() {
+        let mut cache = QueryEmbeddingCache::new(2);
+        cache.insert("hash", "rust", vec![1.0]);
+        cache.insert("hash", "async", vec![2.0]);
+
+        // Touch "rust" so "async" becomes least recently used
+        assert_eq!(cache.get("hash", "rust"), Some(vec![1.0]));
+        cache.insert("hash", "tokio", vec![3.0]);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("hash", "async"), None);
+        assert_eq!(cache.get("hash", "tokio"), Some(vec![3.0]));
+        assert_eq!(cache.get("other-model", "rust"), None);
+    }
+
+    #[test]
+    fn test_embed_query_matches_embedder() {
+        let embedder = crate::hash_embedder::HashEmbedder::default();
+        let first = embed_query(&embedder, "cached query text").unwrap();
+        let second = embed_query(&embedder, "cached query text").unwrap();
+        assert_eq!(first, embedder.embed("cached query text").unwrap());
+        assert_eq!(first, second);
+    }
 }
@@ -0,0 +1,391 @@
+//! Translated CLI messages.
+//!
+//! Human-readable output for the quickstart, common errors and `xf stats`
+//! labels comes from the catalog below rather than string literals. The
+//! language is chosen once at startup from `output.language`, falling back
+//! to the `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables, and
+//! defaults to English. JSON output is never translated.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// A language the CLI has messages for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+    Japanese,
+}
+
+impl Language {
+    /// Every supported language, in catalog column order.
+    pub const ALL: [Self; 3] = [Self::English, Self::Spanish, Self::Japanese];
+
+    /// ISO 639-1 code, as accepted by `output.language`.
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+            Self::Japanese => "ja",
+        }
+    }
+
+    /// Language of a POSIX locale such as `es_MX.UTF-8` or `ja`.
+    ///
+    /// Returns `None` for `C`, `POSIX`, empty values and languages without
+    /// a catalog.
+    #[must_use]
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|lang| lang.code().eq_ignore_ascii_case(language))
+    }
+
+    /// Resolve an `output.language` setting.
+    ///
+    /// `auto` (or an empty value) uses the first of `LC_ALL`, `LC_MESSAGES`
+    /// and `LANG` that is set, like other POSIX tools.
+    #[must_use]
+    pub fn resolve(setting: &str) -> Self {
+        Self::resolve_with(setting, |name| std::env::var(name).ok())
+    }
+
+    fn resolve_with(setting: &str, env: impl Fn(&str) -> Option<String>) -> Self {
+        let setting = setting.trim();
+        if !setting.is_empty() && !setting.eq_ignore_ascii_case("auto") {
+            return Self::from_locale(setting).unwrap_or_default();
+        }
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(env)
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_locale(s.trim())
+            .ok_or_else(|| format!("unknown language '{s}' (expected auto, en, es, or ja)"))
+    }
+}
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+/// Set the language for this process. Only the first call has an effect.
+pub fn set_language(language: Language) {
+    let _ = LANGUAGE.set(language);
+}
+
+/// The language messages are shown in; English until [`set_language`].
+#[must_use]
+pub fn language() -> Language {
+    LANGUAGE.get().copied().unwrap_or_default()
+}
+
+/// Text of `msg` in the current language.
+#[must_use]
+pub fn t(msg: Msg) -> &'static str {
+    msg.text(language())
+}
+
+macro_rules! catalog {
+    ($($(#[$doc:meta])* $name:ident => [$en:expr, $es:expr, $ja:expr $(,)?],)*) => {
+        /// A translatable message. Variants are listed with their English,
+        /// Spanish and Japanese text.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Msg {
+            $($(#[$doc])* $name,)*
+        }
+
+        impl Msg {
+            /// Every message in the catalog.
+            pub const ALL: &[Self] = &[$(Self::$name,)*];
+
+            /// Text of this message in `language`.
+            #[must_use]
+            pub const fn text(self, language: Language) -> &'static str {
+                let texts = match self {
+                    $(Self::$name => [$en, $es, $ja],)*
+                };
+                match language {
+                    Language::English => texts[0],
+                    Language::Spanish => texts[1],
+                    Language::Japanese => texts[2],
+                }
+            }
+        }
+    };
+}
+
+catalog! {
+    // Error formatting
+    Hint => ["Hint:", "Sugerencia:", "ヒント:"],
+    Try => ["Try", "Pruebe", "次を試してください"],
+    NoArchiveTitle => [
+        "No archive indexed yet",
+        "Todavía no hay ningún archivo indexado",
+        "まだアーカイブがインデックスされていません",
+    ],
+    NoArchiveExplanation => [
+        "You need to index your X data archive first.",
+        "Primero debe indexar su archivo de datos de X.",
+        "まず X のデータアーカイブをインデックスしてください。",
+    ],
+    NoArchiveBeforeSearch => [
+        "Before searching, you need to index your X data archive.",
+        "Antes de buscar, debe indexar su archivo de datos de X.",
+        "検索する前に X のデータアーカイブをインデックスしてください。",
+    ],
+    RunIndex => [
+        "Run: xf index ~/Downloads/twitter-archive",
+        "Ejecute: xf index ~/Downloads/twitter-archive",
+        "実行: xf index ~/Downloads/twitter-archive",
+    ],
+    DownloadStep => [
+        "1. Download your data from x.com/settings/download_your_data",
+        "1. Descargue sus datos desde x.com/settings/download_your_data",
+        "1. x.com/settings/download_your_data からデータをダウンロード",
+    ],
+    IndexStep => [
+        "2. Run: xf index ~/Downloads/twitter-archive",
+        "2. Ejecute: xf index ~/Downloads/twitter-archive",
+        "2. 実行: xf index ~/Downloads/twitter-archive",
+    ],
+    ShellStep => [
+        "3. Then run: xf shell",
+        "3. Luego ejecute: xf shell",
+        "3. その後に実行: xf shell",
+    ],
+    SearchAgainStep => [
+        "Then try your search again!",
+        "¡Luego vuelva a intentar la búsqueda!",
+        "その後、もう一度検索してください。",
+    ],
+
+    // Quickstart
+    Tagline => [
+        "Ultra-fast CLI for searching your X data archive",
+        "CLI ultrarrápida para buscar en su archivo de datos de X",
+        "X のデータアーカイブを検索する超高速 CLI",
+    ],
+    GettingStarted => ["Getting Started", "Primeros pasos", "はじめに"],
+    DownloadFrom => [
+        "Download your archive from:",
+        "Descargue su archivo desde:",
+        "アーカイブのダウンロード:",
+    ],
+    DownloadWait => [
+        "(X emails you when it's ready, usually 24-48 hours)",
+        "(X le avisa por correo cuando está listo, normalmente en 24-48 horas)",
+        "(準備ができると X からメールが届きます。通常 24〜48 時間)",
+    ],
+    ExtractArchive => ["Extract Your Archive", "Descomprima su archivo", "アーカイブを展開"],
+    IndexData => ["Index Your Data", "Indexe sus datos", "データをインデックス"],
+    DefaultPath => [
+        "# Uses default path: /data/projects/my_twitter_data",
+        "# Ruta predeterminada: /data/projects/my_twitter_data",
+        "# 既定のパス: /data/projects/my_twitter_data",
+    ],
+    CustomPath => [
+        "# Or specify a custom path",
+        "# O indique otra ruta",
+        "# 別のパスも指定可能",
+    ],
+    IndexDuration => [
+        "(Takes ~5-30 seconds depending on archive size)",
+        "(Tarda ~5-30 segundos según el tamaño del archivo)",
+        "(アーカイブのサイズに応じて約 5〜30 秒)",
+    ],
+    ExampleSearches => ["Example Searches", "Búsquedas de ejemplo", "検索の例"],
+    FindAboutMl => ["# Find tweets about ML", "# Tweets sobre ML", "# ML のツイート"],
+    SearchDms => ["# Search your DMs", "# Buscar en sus DM", "# DM を検索"],
+    DmsWithContext => [
+        "# DMs with full convo",
+        "# DM con la conversación",
+        "# 会話全体つきの DM",
+    ],
+    LikedTweets => ["# Tweets you liked", "# Tweets que le gustaron", "# いいねしたツイート"],
+    RecentOnly => ["# Recent tweets only", "# Solo tweets recientes", "# 最近のツイートのみ"],
+    JsonOutput => ["# JSON output", "# Salida JSON", "# JSON 出力"],
+    MoreCommands => ["More Commands", "Más comandos", "その他のコマンド"],
+    ArchiveOverview => [
+        "# Archive overview (counts, date range)",
+        "# Resumen (conteos, fechas)",
+        "# 概要 (件数、期間)",
+    ],
+    FullDashboard => [
+        "# Full analytics dashboard",
+        "# Panel de análisis completo",
+        "# 詳細な分析ダッシュボード",
+    ],
+    BrowseRecent => [
+        "# Browse recent tweets",
+        "# Ver tweets recientes",
+        "# 最近のツイートを表示",
+    ],
+    AllDmThreads => [
+        "# See all DM threads",
+        "# Ver todas las conversaciones",
+        "# DM スレッド一覧",
+    ],
+    ViewThread => [
+        "# View a tweet thread",
+        "# Ver un hilo",
+        "# スレッドを表示",
+    ],
+    ExportCsv => ["# Export to CSV", "# Exportar a CSV", "# CSV に書き出し"],
+    InteractiveRepl => [
+        "# Interactive REPL mode",
+        "# Modo interactivo (REPL)",
+        "# 対話モード (REPL)",
+    ],
+    CheckHealth => [
+        "# Check archive/index health",
+        "# Revisar archivo e índice",
+        "# アーカイブと索引を診断",
+    ],
+    Documentation => ["Documentation:", "Documentación:", "ドキュメント:"],
+    /// `{cmd}` is replaced with `xf --help`.
+    AllOptions => [
+        "Run {cmd} for all options",
+        "Ejecute {cmd} para ver todas las opciones",
+        "すべてのオプションは {cmd} で確認できます",
+    ],
+
+    // xf stats
+    AnalyticsDashboard => [
+        "ARCHIVE ANALYTICS DASHBOARD",
+        "PANEL DE ANÁLISIS DEL ARCHIVO",
+        "アーカイブ分析ダッシュボード",
+    ],
+    Overview => ["Overview", "Resumen", "概要"],
+    Tweets => ["Tweets:", "Tweets:", "ツイート:"],
+    Likes => ["Likes:", "Me gusta:", "いいね:"],
+    DmConversations => ["DM Conversations:", "Conversaciones DM:", "DM の会話:"],
+    DmMessages => ["DM Messages:", "Mensajes DM:", "DM メッセージ:"],
+    GrokMessages => ["Grok Messages:", "Mensajes de Grok:", "Grok メッセージ:"],
+    Followers => ["Followers:", "Seguidores:", "フォロワー:"],
+    Following => ["Following:", "Siguiendo:", "フォロー中:"],
+    Blocks => ["Blocks:", "Bloqueos:", "ブロック:"],
+    Mutes => ["Mutes:", "Silenciados:", "ミュート:"],
+    FirstTweet => ["First tweet:", "Primer tweet:", "最初のツイート:"],
+    LastTweet => ["Last tweet:", "Último tweet:", "最新のツイート:"],
+    TweetsByMonth => ["Tweets by Month", "Tweets por mes", "月別ツイート数"],
+    TopHashtags => ["Top Hashtags", "Hashtags principales", "よく使うハッシュタグ"],
+    TopMentions => ["Top Mentions", "Menciones principales", "よく使うメンション"],
+    Places => ["Places", "Lugares", "場所"],
+    NoGeotags => [
+        "No geotagged tweets (re-run xf index if this archive was indexed before geotags were kept)",
+        "No hay tweets con ubicación (vuelva a ejecutar xf index si el archivo se indexó antes de guardar las ubicaciones)",
+        "位置情報つきのツイートはありません (位置情報の保存前にインデックスした場合は xf index を再実行してください)",
+    ],
+    TemporalPatterns => ["Temporal Patterns", "Patrones temporales", "時間的パターン"],
+    EngagementAnalytics => [
+        "Engagement Analytics",
+        "Análisis de interacción",
+        "エンゲージメント分析",
+    ],
+    ContentAnalysis => ["Content Analysis", "Análisis de contenido", "コンテンツ分析"],
+    LikesAnalytics => ["Likes Analytics", "Análisis de me gusta", "いいね分析"],
+    FollowerChurn => ["Follower Churn", "Rotación de seguidores", "フォロワーの増減"],
+    WritingStyle => ["Writing Style", "Estilo de escritura", "文体"],
+    Sources => ["Sources", "Clientes de publicación", "投稿元クライアント"],
+    PeriodComparison => ["Period Comparison", "Comparación de periodos", "期間の比較"],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect();
+        move |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+    }
+
+    #[test]
+    fn locales_map_to_languages() {
+        assert_eq!(
+            Language::from_locale("es_MX.UTF-8"),
+            Some(Language::Spanish)
+        );
+        assert_eq!(
+            Language::from_locale("ja_JP.eucJP"),
+            Some(Language::Japanese)
+        );
+        assert_eq!(Language::from_locale("EN-gb"), Some(Language::English));
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), None);
+        assert_eq!(Language::from_locale("C.UTF-8"), None);
+        assert_eq!(Language::from_locale(""), None);
+    }
+
+    #[test]
+    fn setting_overrides_environment() {
+        let vars = env(&[("LANG", "ja_JP.UTF-8")]);
+        assert_eq!(Language::resolve_with("es", &vars), Language::Spanish);
+        assert_eq!(Language::resolve_with("auto", &vars), Language::Japanese);
+        assert_eq!(Language::resolve_with("", &vars), Language::Japanese);
+    }
+
+    #[test]
+    fn environment_follows_posix_precedence() {
+        let vars = env(&[
+            ("LC_ALL", ""),
+            ("LC_MESSAGES", "es_ES.UTF-8"),
+            ("LANG", "ja_JP.UTF-8"),
+        ]);
+        assert_eq!(Language::resolve_with("auto", vars), Language::Spanish);
+        assert_eq!(
+            Language::resolve_with("auto", env(&[("LANG", "fr_FR.UTF-8")])),
+            Language::English
+        );
+        assert_eq!(Language::resolve_with("auto", env(&[])), Language::English);
+    }
+
+    #[test]
+    fn language_parses_from_config_values() {
+        assert_eq!("ja".parse::<Language>(), Ok(Language::Japanese));
+        assert_eq!(" es ".parse::<Language>(), Ok(Language::Spanish));
+        assert!("klingon".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn every_message_is_translated() {
+        for msg in Msg::ALL {
+            let english = msg.text(Language::English);
+            for language in Language::ALL {
+                let text = msg.text(language);
+                assert!(!text.is_empty(), "{msg:?} is empty in {language}");
+                assert_eq!(
+                    text.contains("{cmd}"),
+                    english.contains("{cmd}"),
+                    "{msg:?} placeholders differ in {language}"
+                );
+                assert_eq!(
+                    text.ends_with(':'),
+                    english.ends_with(':'),
+                    "{msg:?} label punctuation differs in {language}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,1017 @@
+//! Archive extraction (`xf import`).
+//!
+//! X archives are extracted entry by entry into the output directory. Entry
+//! names are untrusted: media files can have very long names, and names that
+//! are not valid UTF-8 are decoded by the zip reader as CP437 or with
+//! replacement characters. Every name is rebuilt into a relative path that any
+//! supported filesystem accepts, and an entry that still cannot be written is
+//! skipped and reported rather than aborting the whole import.
+//!
+//! Sizes are untrusted too. Before anything is written the archive's entry
+//! count, declared total size, and compression ratios are checked against
+//! [`Limits`], the files actually planned for writing are totalled again, and
+//! each entry is cut off at its declared size while it decompresses, so a zip
+//! bomb stops with an error instead of filling the disk.
+
+use crate::error::format_error;
+use crate::format_bytes;
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Longest file or directory name written, in bytes.
+pub const MAX_COMPONENT_LEN: usize = 255;
+
+/// Default limit on the total uncompressed size of an archive (`--max-size`).
+pub const DEFAULT_MAX_SIZE: u64 = 64 * 1024 * 1024 * 1024;
+
+/// Default limit on the number of entries in an archive.
+pub const DEFAULT_MAX_ENTRIES: usize = 1_000_000;
+
+/// Default limit on an entry's uncompressed-to-compressed size ratio.
+pub const DEFAULT_MAX_RATIO: u64 = 1_000;
+
+/// Entries smaller than this are exempt from the ratio check.
+const RATIO_CHECK_MIN_SIZE: u64 = 1024 * 1024;
+
+/// Quotas an archive must stay within to be extracted.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Total uncompressed bytes.
+    pub max_size: u64,
+    /// Number of entries, including directories.
+    pub max_entries: usize,
+    /// Uncompressed-to-compressed ratio of any entry over 1 MB.
+    pub max_ratio: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_size: DEFAULT_MAX_SIZE,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_ratio: DEFAULT_MAX_RATIO,
+        }
+    }
+}
+
+/// Names Windows reserves for devices, with or without an extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Which entries to extract, by whether they are media.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MediaSelection {
+    /// Everything in the archive.
+    #[default]
+    All,
+    /// Everything except media folders (`--no-media`).
+    Skip,
+    /// Only media folders (`--media-only`).
+    Only,
+}
+
+impl MediaSelection {
+    /// Whether the entry named `name` should be extracted.
+    #[must_use]
+    pub fn includes(self, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Skip => !is_media(name),
+            Self::Only => is_media(name),
+        }
+    }
+}
+
+/// Whether an entry lives in a media folder.
+///
+/// X archives keep media in `data/*_media/` folders (`tweets_media`,
+/// `direct_messages_media`, `direct_messages_group_media`, `profile_media`,
+/// and so on); any folder named that way counts.
+#[must_use]
+pub fn is_media(name: &str) -> bool {
+    let is_dir = name.ends_with(['/', '\\']);
+    let mut components: Vec<&str> = name
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty())
+        .collect();
+    if !is_dir {
+        components.pop();
+    }
+    components
+        .iter()
+        .any(|component| component.to_ascii_lowercase().ends_with("_media"))
+}
+
+/// What an extraction wrote.
+#[derive(Debug, Default)]
+pub struct ExtractSummary {
+    /// Number of files written.
+    pub files: usize,
+    /// Uncompressed bytes written.
+    pub bytes: u64,
+    /// Entries written under a different name: (entry name, relative path).
+    pub renamed: Vec<(String, PathBuf)>,
+    /// Entries that could not be extracted: (entry name, reason).
+    pub skipped: Vec<(String, String)>,
+    /// Files left out by the [`MediaSelection`].
+    pub excluded: usize,
+    /// Uncompressed bytes of the files left out.
+    pub excluded_bytes: u64,
+}
+
+/// Check an archive's declared entry count and sizes against `limits`
+/// without decompressing anything, returning the total uncompressed size of
+/// the entries `media` selects.
+///
+/// # Errors
+///
+/// Returns an error describing the first limit the archive exceeds.
+pub fn check_limits<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    limits: &Limits,
+    media: MediaSelection,
+) -> Result<u64> {
+    if archive.len() > limits.max_entries {
+        anyhow::bail!(
+            "{}",
+            format_error(
+                "Too many entries in archive",
+                &format!(
+                    "The archive lists {} entries; at most {} are extracted.",
+                    archive.len(),
+                    limits.max_entries
+                ),
+                &["Check that this is an X data archive from x.com"],
+            )
+        );
+    }
+
+    let mut total: u64 = 0;
+    for i in 0..archive.len() {
+        let Ok(entry) = archive.by_index_raw(i) else {
+            continue;
+        };
+        if !media.includes(&entry_name(entry.name_raw(), entry.name())) {
+            continue;
+        }
+        let size = entry.size();
+        let compressed = entry.compressed_size().max(1);
+        if size > RATIO_CHECK_MIN_SIZE && size / compressed > limits.max_ratio {
+            anyhow::bail!(
+                "{}",
+                format_error(
+                    "Suspicious compression ratio",
+                    &format!(
+                        "'{}' expands from {} to {}, more than {}x; the archive looks like a zip bomb.",
+                        entry.name(),
+                        format_bytes(compressed),
+                        format_bytes(size),
+                        limits.max_ratio
+                    ),
+                    &["Check that this is an X data archive from x.com"],
+                )
+            );
+        }
+        total = total.saturating_add(size);
+    }
+    if total > limits.max_size {
+        anyhow::bail!("{}", size_limit_error(total, limits.max_size));
+    }
+    Ok(total)
+}
+
+fn size_limit_error(size: u64, max_size: u64) -> String {
+    format_error(
+        "Archive too large",
+        &format!(
+            "The archive expands to more than {} (it claims {}).",
+            format_bytes(max_size),
+            format_bytes(size)
+        ),
+        &[
+            "Raise the limit with --max-size, e.g. --max-size 200GB",
+            "Check that you have enough free disk space",
+        ],
+    )
+}
+
+/// Read-ahead size for the small reads zip parsing makes.
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// An archive file that clones into independent readers.
+///
+/// Each clone keeps its own position and buffer and reads with positional
+/// I/O on one shared handle, so extraction workers can decompress entries at
+/// the same time without reopening the archive or taking a lock.
+#[derive(Debug, Clone)]
+pub struct ArchiveFile {
+    file: Arc<File>,
+    len: u64,
+    pos: u64,
+    buf: Vec<u8>,
+    /// File offset of `buf[0]`.
+    buf_start: u64,
+}
+
+impl ArchiveFile {
+    /// Open `path` for reading.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or its size read.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(long_path(path))?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            file: Arc::new(file),
+            len,
+            pos: 0,
+            buf: Vec::new(),
+            buf_start: 0,
+        })
+    }
+
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        #[cfg(unix)]
+        return std::os::unix::fs::FileExt::read_at(&*self.file, buf, offset);
+        #[cfg(windows)]
+        return std::os::windows::fs::FileExt::seek_read(&*self.file, buf, offset);
+    }
+}
+
+impl Read for ArchiveFile {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let buffered =
+            self.pos >= self.buf_start && self.pos < self.buf_start + self.buf.len() as u64;
+        if !buffered {
+            if out.len() >= READ_BUFFER_SIZE {
+                let read = self.read_at(out, self.pos)?;
+                self.pos += read as u64;
+                return Ok(read);
+            }
+            let mut buf = std::mem::take(&mut self.buf);
+            buf.resize(READ_BUFFER_SIZE, 0);
+            let read = self.read_at(&mut buf, self.pos)?;
+            buf.truncate(read);
+            self.buf = buf;
+            self.buf_start = self.pos;
+            if read == 0 {
+                return Ok(0);
+            }
+        }
+
+        let offset = usize::try_from(self.pos - self.buf_start).unwrap_or(usize::MAX);
+        let available = &self.buf[offset..];
+        let read = available.len().min(out.len());
+        out[..read].copy_from_slice(&available[..read]);
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for ArchiveFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+        self.pos = target
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek position"))?;
+        Ok(self.pos)
+    }
+}
+
+/// A file entry to write, decided before any worker starts.
+struct PlannedFile {
+    index: usize,
+    name: String,
+    relative: PathBuf,
+    renamed: bool,
+    declared: u64,
+}
+
+/// Extract every entry of `archive` into `output_dir`.
+///
+/// Call [`check_limits`] first. Entry names are resolved and directories
+/// created in archive order on the calling thread; file contents are then
+/// written in parallel on the rayon pool, each capped at its declared size.
+/// `progress` is called with the number of bytes handled since the last
+/// call; together they add up to the total [`check_limits`] returns for an
+/// archive extracted without errors.
+///
+/// # Errors
+///
+/// Returns an error if the output directory cannot be created, the files
+/// exceed the size limit, or an entry decompresses past its declared size.
+/// Other problems with individual entries are recorded in
+/// [`ExtractSummary::skipped`].
+pub fn extract_archive<R: Read + Seek + Clone + Send + Sync>(
+    archive: &zip::ZipArchive<R>,
+    output_dir: &Path,
+    limits: &Limits,
+    media: MediaSelection,
+    progress: impl Fn(u64) + Sync,
+) -> Result<ExtractSummary> {
+    let output_dir = std::path::absolute(output_dir).unwrap_or_else(|_| output_dir.to_path_buf());
+    fs::create_dir_all(long_path(&output_dir))
+        .with_context(|| format!("Failed to create '{}'", output_dir.display()))?;
+
+    let mut summary = ExtractSummary::default();
+    let (plan, directories) = plan_extraction(&mut archive.clone(), media, &mut summary, &progress);
+
+    let planned_size = plan
+        .iter()
+        .fold(0_u64, |total, file| total.saturating_add(file.declared));
+    if planned_size > limits.max_size {
+        anyhow::bail!("{}", size_limit_error(planned_size, limits.max_size));
+    }
+
+    // Parents sort before their children, so each directory exists before
+    // anything is written into it
+    for (relative, entry) in directories {
+        if let Err(e) = fs::create_dir_all(long_path(&output_dir.join(&relative))) {
+            // Files under it fail on their own; only report explicit entries
+            if let Some(name) = entry {
+                summary.skipped.push((name, e.to_string()));
+            }
+        }
+    }
+
+    let outcomes: Vec<io::Result<u64>> = plan
+        .par_iter()
+        .map_init(
+            || archive.clone(),
+            |reader, file| {
+                let mut entry = reader.by_index(file.index).map_err(io::Error::from)?;
+                let outpath = long_path(&output_dir.join(&file.relative));
+                write_entry(&mut entry, &outpath, file.declared, &progress)
+            },
+        )
+        .collect();
+
+    for (file, outcome) in plan.into_iter().zip(outcomes) {
+        match outcome {
+            Ok(bytes) if bytes > file.declared => {
+                let _ = fs::remove_file(long_path(&output_dir.join(&file.relative)));
+                anyhow::bail!(
+                    "{}",
+                    format_error(
+                        "Corrupt or malicious archive",
+                        &format!(
+                            "'{}' decompresses to more than the {} it declares.",
+                            file.name,
+                            format_bytes(file.declared)
+                        ),
+                        &[
+                            "Check that this is an X data archive from x.com",
+                            &format!(
+                                "Remove the partial extraction in '{}'",
+                                output_dir.display()
+                            ),
+                        ],
+                    )
+                );
+            }
+            Ok(bytes) => {
+                summary.files += 1;
+                summary.bytes += bytes;
+                if file.renamed {
+                    summary.renamed.push((file.name, file.relative));
+                }
+            }
+            Err(e) => {
+                // Don't leave a truncated file behind
+                let _ = fs::remove_file(long_path(&output_dir.join(&file.relative)));
+                summary.skipped.push((file.name, e.to_string()));
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Resolve every entry to a file to write or a directory to create, in
+/// archive order, recording entries that can't be extracted at all.
+///
+/// Directories map to the name of their explicit entry, if they have one.
+fn plan_extraction<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    media: MediaSelection,
+    summary: &mut ExtractSummary,
+    progress: &impl Fn(u64),
+) -> (Vec<PlannedFile>, BTreeMap<PathBuf, Option<String>>) {
+    let mut plan = Vec::new();
+    let mut directories: BTreeMap<PathBuf, Option<String>> = BTreeMap::new();
+    let mut taken: HashSet<PathBuf> = HashSet::new();
+
+    for index in 0..archive.len() {
+        let entry = match archive.by_index_raw(index) {
+            Ok(entry) => entry,
+            Err(e) => {
+                summary
+                    .skipped
+                    .push((format!("entry #{index}"), e.to_string()));
+                continue;
+            }
+        };
+        let lossy = std::str::from_utf8(entry.name_raw()).is_err();
+        let name = entry_name(entry.name_raw(), entry.name());
+        if !media.includes(&name) {
+            if !entry.is_dir() {
+                summary.excluded += 1;
+                summary.excluded_bytes += entry.size();
+            }
+            continue;
+        }
+        let Some(mut relative) = sanitize_entry_path(&name) else {
+            progress(entry.size());
+            summary
+                .skipped
+                .push((name, "name has no usable path components".to_string()));
+            continue;
+        };
+
+        if entry.is_symlink() {
+            progress(entry.size());
+            summary
+                .skipped
+                .push((name, "symbolic links are not extracted".to_string()));
+            continue;
+        }
+        if entry.is_dir() {
+            directories.insert(relative, Some(name));
+            continue;
+        }
+
+        // Two entries can sanitize to the same path; keep both
+        if taken.contains(&relative) {
+            relative = disambiguate(&relative, &name);
+        }
+        taken.insert(relative.clone());
+        if let Some(parent) = relative.parent().filter(|p| !p.as_os_str().is_empty()) {
+            directories.entry(parent.to_path_buf()).or_insert(None);
+        }
+        let renamed = lossy || relative_name(&relative) != name.trim_start_matches(['/', '\\']);
+        plan.push(PlannedFile {
+            index,
+            name,
+            relative,
+            renamed,
+            declared: entry.size(),
+        });
+    }
+
+    (plan, directories)
+}
+
+/// Write at most `limit + 1` bytes of `entry`, so a result over `limit`
+/// means the entry is larger than it declared.
+fn write_entry(
+    entry: &mut impl Read,
+    outpath: &Path,
+    limit: u64,
+    progress: &impl Fn(u64),
+) -> io::Result<u64> {
+    // Never write through a link planted in the output directory
+    if fs::symlink_metadata(outpath).is_ok_and(|m| m.file_type().is_symlink()) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "destination is a symbolic link",
+        ));
+    }
+    let mut outfile = File::create(outpath)?;
+    let mut reader = ProgressReader {
+        inner: entry.take(limit.saturating_add(1)),
+        progress,
+    };
+    io::copy(&mut reader, &mut outfile)
+}
+
+/// Reports every chunk read, so large media files move the progress bar
+/// while they are being written rather than only when they finish.
+struct ProgressReader<'a, R, F> {
+    inner: R,
+    progress: &'a F,
+}
+
+impl<R: Read, F: Fn(u64)> Read for ProgressReader<'_, R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        (self.progress)(read as u64);
+        Ok(read)
+    }
+}
+
+/// Recover an entry's name from its raw bytes.
+///
+/// Many zip tools store UTF-8 names without setting the UTF-8 flag, which the
+/// zip reader then decodes as CP437. Raw bytes that are valid UTF-8 are used
+/// as-is; anything else falls back to the reader's lossy decoding.
+#[must_use]
+pub fn entry_name(raw: &[u8], decoded: &str) -> String {
+    std::str::from_utf8(raw).map_or_else(|_| decoded.to_string(), str::to_string)
+}
+
+/// Turn an entry name into a safe relative path.
+///
+/// Both `/` and `\` separate components. Empty, `.` and `..` components are
+/// dropped so nothing is written outside the output directory, and each
+/// remaining component is made valid on Windows and capped at
+/// [`MAX_COMPONENT_LEN`] bytes. Returns `None` if nothing is left.
+#[must_use]
+pub fn sanitize_entry_path(name: &str) -> Option<PathBuf> {
+    let path: PathBuf = name
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != "." && *component != "..")
+        .filter_map(sanitize_component)
+        .collect();
+    // Every component must be a plain name, or the path could leave the
+    // output directory
+    let contained = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    (contained && path.components().next().is_some()).then_some(path)
+}
+
+fn sanitize_component(component: &str) -> Option<String> {
+    let mut cleaned: String = component
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    // Windows drops trailing dots and spaces, which can merge names
+    let trimmed_len = cleaned.trim_end_matches(['.', ' ']).len();
+    cleaned.truncate(trimmed_len);
+    if cleaned.is_empty() {
+        return None;
+    }
+
+    let stem = cleaned.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        cleaned.insert(0, '_');
+    }
+
+    Some(truncate_component(&cleaned, component))
+}
+
+/// Shorten a component to [`MAX_COMPONENT_LEN`] bytes, keeping its extension
+/// and a hash of the original so truncated names stay distinct.
+fn truncate_component(cleaned: &str, original: &str) -> String {
+    if cleaned.len() <= MAX_COMPONENT_LEN {
+        return cleaned.to_string();
+    }
+    let extension = Path::new(cleaned)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.len() <= 16)
+        .map(|ext| format!(".{ext}"))
+        .unwrap_or_default();
+    let suffix = format!("~{:08x}{extension}", name_hash(original));
+    let mut end = MAX_COMPONENT_LEN - suffix.len();
+    while !cleaned.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{suffix}", &cleaned[..end])
+}
+
+/// Give `relative` a distinct file name derived from the entry name.
+fn disambiguate(relative: &Path, name: &str) -> PathBuf {
+    let file_name = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (stem, extension) = file_name
+        .rsplit_once('.')
+        .map_or((file_name.as_str(), String::new()), |(stem, ext)| {
+            (stem, format!(".{ext}"))
+        });
+    let renamed = format!("{stem}~{:08x}{extension}", name_hash(name));
+    relative.with_file_name(truncate_component(&renamed, name))
+}
+
+/// 32-bit FNV-1a, enough to tell apart names that sanitize alike.
+fn name_hash(name: &str) -> u32 {
+    name.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+fn relative_name(relative: &Path) -> String {
+    relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Make a Windows path absolute and prefix it with `\\?\` so paths longer
+/// than `MAX_PATH` (260 characters) can be opened.
+#[cfg(windows)]
+#[must_use]
+pub fn long_path(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    // Paths that aren't valid Unicode can't be rebuilt as text
+    let Some(text) = path.to_str() else {
+        return path;
+    };
+    if text.starts_with(r"\\?\") {
+        return path;
+    }
+    let text = text.replace('/', "\\");
+    text.strip_prefix(r"\\").map_or_else(
+        || PathBuf::from(format!(r"\\?\{text}")),
+        |unc| PathBuf::from(format!(r"\\?\UNC\{unc}")),
+    )
+}
+
+/// Paths on other platforms have no `MAX_PATH` limit and are returned as-is.
+#[cfg(not(windows))]
+#[must_use]
+pub fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use tempfile::TempDir;
+
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xffff_ffff_u32;
+        for &byte in data {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xedb8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    /// Build a stored zip with raw (possibly non-UTF-8) names and no UTF-8 flag.
+    fn raw_zip(entries: &[(&[u8], &[u8])]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut central = Vec::new();
+        for (name, data) in entries {
+            let offset = u32::try_from(out.len()).unwrap();
+            let crc = crc32(data);
+            let size = u32::try_from(data.len()).unwrap();
+            let name_len = u16::try_from(name.len()).unwrap();
+
+            out.extend_from_slice(&0x0403_4b50_u32.to_le_bytes());
+            out.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            out.extend_from_slice(&crc.to_le_bytes());
+            out.extend_from_slice(&size.to_le_bytes());
+            out.extend_from_slice(&size.to_le_bytes());
+            out.extend_from_slice(&name_len.to_le_bytes());
+            out.extend_from_slice(&0_u16.to_le_bytes());
+            out.extend_from_slice(name);
+            out.extend_from_slice(data);
+
+            central.extend_from_slice(&0x0201_4b50_u32.to_le_bytes());
+            central.extend_from_slice(&[20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            central.extend_from_slice(&crc.to_le_bytes());
+            central.extend_from_slice(&size.to_le_bytes());
+            central.extend_from_slice(&size.to_le_bytes());
+            central.extend_from_slice(&name_len.to_le_bytes());
+            central.extend_from_slice(&[0; 12]);
+            central.extend_from_slice(&offset.to_le_bytes());
+            central.extend_from_slice(name);
+        }
+        let central_offset = u32::try_from(out.len()).unwrap();
+        let central_len = u32::try_from(central.len()).unwrap();
+        let count = u16::try_from(entries.len()).unwrap();
+        out.extend_from_slice(&central);
+        out.extend_from_slice(&0x0605_4b50_u32.to_le_bytes());
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&count.to_le_bytes());
+        out.extend_from_slice(&count.to_le_bytes());
+        out.extend_from_slice(&central_len.to_le_bytes());
+        out.extend_from_slice(&central_offset.to_le_bytes());
+        out.extend_from_slice(&0_u16.to_le_bytes());
+        out
+    }
+
+    fn extract_bytes(bytes: Vec<u8>, dir: &Path) -> ExtractSummary {
+        let archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        extract_archive(
+            &archive,
+            dir,
+            &Limits::default(),
+            MediaSelection::All,
+            |_| {},
+        )
+        .unwrap()
+    }
+
+    fn zip_of(entries: &[(&str, &[u8])]) -> zip::ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        zip::ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let tweets = (0..40_000)
+            .map(|i| format!("{{\"id_str\": \"{i}\", \"full_text\": \"tweet number {i}\"}},"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut archive = zip_of(&[
+            ("data/tweets.js", tweets.as_bytes()),
+            ("media/a.jpg", b"jpg"),
+        ]);
+        assert!(check_limits(&mut archive, &Limits::default(), MediaSelection::All).is_ok());
+
+        let too_few_entries = Limits {
+            max_entries: 1,
+            ..Limits::default()
+        };
+        let err = check_limits(&mut archive, &too_few_entries, MediaSelection::All).unwrap_err();
+        assert!(err.to_string().contains("Too many entries"));
+
+        let too_small = Limits {
+            max_size: 1024,
+            ..Limits::default()
+        };
+        let err = check_limits(&mut archive, &too_small, MediaSelection::All).unwrap_err();
+        assert!(err.to_string().contains("--max-size"));
+
+        let strict_ratio = Limits {
+            max_ratio: 2,
+            ..Limits::default()
+        };
+        let err = check_limits(&mut archive, &strict_ratio, MediaSelection::All).unwrap_err();
+        assert!(err.to_string().contains("zip bomb"));
+    }
+
+    #[test]
+    fn test_progress_adds_up_to_declared_total() {
+        let dir = TempDir::new().unwrap();
+        let media = vec![7_u8; 300_000];
+        let mut archive = zip_of(&[("data/tweets.js", b"[]"), ("media/clip.mp4", &media)]);
+        let total = check_limits(&mut archive, &Limits::default(), MediaSelection::All).unwrap();
+
+        let reported = std::sync::atomic::AtomicU64::new(0);
+        let summary = extract_archive(
+            &archive,
+            dir.path(),
+            &Limits::default(),
+            MediaSelection::All,
+            |bytes| {
+                reported.fetch_add(bytes, std::sync::atomic::Ordering::Relaxed);
+            },
+        )
+        .unwrap();
+        assert_eq!(total, 300_002);
+        assert_eq!(reported.into_inner(), total);
+        assert_eq!(summary.bytes, total);
+    }
+
+    #[test]
+    fn test_parallel_extraction_from_archive_file() {
+        let dir = TempDir::new().unwrap();
+        let zip_path = dir.path().join("archive.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("data/", options).unwrap();
+        for i in 0..200 {
+            writer
+                .start_file(format!("tweets_media/{i}-photo.jpg"), options)
+                .unwrap();
+            writer.write_all(format!("image {i}").as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let out = dir.path().join("out");
+        let mut archive = zip::ZipArchive::new(ArchiveFile::open(&zip_path).unwrap()).unwrap();
+        let total = check_limits(&mut archive, &Limits::default(), MediaSelection::All).unwrap();
+        let summary = extract_archive(
+            &archive,
+            &out,
+            &Limits::default(),
+            MediaSelection::All,
+            |_| {},
+        )
+        .unwrap();
+
+        assert!(summary.skipped.is_empty(), "{:?}", summary.skipped);
+        assert_eq!(summary.files, 200);
+        assert_eq!(summary.bytes, total);
+        assert!(out.join("data").is_dir());
+        assert_eq!(
+            fs::read_to_string(out.join("tweets_media/137-photo.jpg")).unwrap(),
+            "image 137"
+        );
+    }
+
+    #[test]
+    fn test_media_selection() {
+        assert!(is_media("data/tweets_media/1-abc.jpg"));
+        assert!(is_media("data/direct_messages_group_media/"));
+        assert!(!is_media("data/tweets.js"));
+        assert!(!is_media("data/social_media.js"));
+
+        let entries: [(&str, &[u8]); 3] = [
+            ("data/tweets.js", b"[]"),
+            ("data/tweets_media/1-a.jpg", b"jpg"),
+            ("data/direct_messages_media/2-b.mp4", b"video"),
+        ];
+        let dir = TempDir::new().unwrap();
+        let mut archive = zip_of(&entries);
+        assert_eq!(
+            check_limits(&mut archive, &Limits::default(), MediaSelection::Skip).unwrap(),
+            2
+        );
+        let text = dir.path().join("text");
+        let summary = extract_archive(
+            &archive,
+            &text,
+            &Limits::default(),
+            MediaSelection::Skip,
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(
+            (summary.files, summary.excluded, summary.excluded_bytes),
+            (1, 2, 8)
+        );
+        assert!(text.join("data/tweets.js").exists());
+        assert!(!text.join("data/tweets_media").exists());
+
+        let summary = extract_archive(
+            &archive,
+            &text,
+            &Limits::default(),
+            MediaSelection::Only,
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!((summary.files, summary.excluded), (2, 1));
+        assert!(text.join("data/tweets_media/1-a.jpg").exists());
+        assert!(text.join("data/direct_messages_media/2-b.mp4").exists());
+    }
+
+    #[test]
+    fn test_extract_stops_at_size_limit() {
+        let dir = TempDir::new().unwrap();
+        let archive = zip_of(&[("data/a.js", &[b'a'; 600]), ("data/b.js", &[b'b'; 600])]);
+        let limits = Limits {
+            max_size: 1000,
+            ..Limits::default()
+        };
+
+        let err = extract_archive(&archive, dir.path(), &limits, MediaSelection::All, |_| {})
+            .unwrap_err();
+        assert!(err.to_string().contains("Archive too large"));
+        assert!(!dir.path().join("data").exists());
+    }
+
+    #[test]
+    fn test_extract_skips_symlinks() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer
+            .add_symlink("data/link.js", "/etc/passwd", options)
+            .unwrap();
+        writer.start_file("data/tweets.js", options).unwrap();
+        writer.write_all(b"[]").unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let summary = extract_bytes(bytes, dir.path());
+        assert_eq!(summary.files, 1);
+        assert_eq!(summary.skipped.len(), 1);
+        assert!(fs::symlink_metadata(dir.path().join("data/link.js")).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_entry_path() {
+        assert_eq!(
+            sanitize_entry_path("data/tweets.js"),
+            Some(PathBuf::from("data").join("tweets.js"))
+        );
+        assert_eq!(
+            sanitize_entry_path("/../data\\..\\evil.js"),
+            Some(PathBuf::from("data").join("evil.js"))
+        );
+        assert_eq!(
+            sanitize_entry_path("media/a:b?.jpg"),
+            Some(PathBuf::from("media").join("a_b_.jpg"))
+        );
+        assert_eq!(
+            sanitize_entry_path("media/CON.txt"),
+            Some(PathBuf::from("media").join("_CON.txt"))
+        );
+        assert_eq!(
+            sanitize_entry_path("media/trailing. "),
+            Some(PathBuf::from("media").join("trailing"))
+        );
+        assert_eq!(sanitize_entry_path("../.."), None);
+    }
+
+    #[test]
+    fn test_long_component_keeps_extension_and_stays_distinct() {
+        let a = format!("{}a.jpg", "é".repeat(200));
+        let b = format!("{}b.jpg", "é".repeat(200));
+        let short_a = sanitize_component(&a).unwrap();
+        let short_b = sanitize_component(&b).unwrap();
+        assert!(short_a.len() <= MAX_COMPONENT_LEN);
+        assert!(
+            Path::new(&short_a)
+                .extension()
+                .is_some_and(|ext| ext == "jpg")
+        );
+        assert_ne!(short_a, short_b);
+    }
+
+    #[test]
+    fn test_entry_name_prefers_raw_utf8() {
+        // UTF-8 bytes stored without the UTF-8 flag decode as CP437 mojibake
+        assert_eq!(entry_name("café".as_bytes(), "cafÃ©"), "café");
+        assert_eq!(entry_name(b"caf\x82", "café"), "café");
+    }
+
+    #[test]
+    fn test_extract_problem_archive() {
+        let long_name = format!("media/{}.mp4", "x".repeat(400));
+        let bytes = raw_zip(&[
+            (b"data/tweets.js", b"window.YTD.tweets.part0 = []"),
+            (long_name.as_bytes(), b"video"),
+            (b"media/caf\xc3\xa9.jpg", b"utf8"),
+            (b"media/caf\x82.jpg", b"cp437"),
+            (b"media/bad\xff\xfe.jpg", b"invalid"),
+            (b"media/a:b.png", b"first"),
+            (b"media/a_b.png", b"second"),
+        ]);
+        let dir = TempDir::new().unwrap();
+        let summary = extract_bytes(bytes, dir.path());
+
+        assert!(summary.skipped.is_empty(), "{:?}", summary.skipped);
+        assert_eq!(summary.files, 7);
+        assert!(dir.path().join("data/tweets.js").exists());
+        assert_eq!(
+            fs::read(dir.path().join("media/café.jpg")).unwrap(),
+            b"utf8".to_vec()
+        );
+        let media: Vec<String> = fs::read_dir(dir.path().join("media"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(media.len(), 6);
+        assert!(media.iter().all(|n| n.len() <= MAX_COMPONENT_LEN));
+        assert!(
+            media
+                .iter()
+                .any(|n| Path::new(n).extension().is_some_and(|ext| ext == "mp4"))
+        );
+        assert!(media.iter().any(|n| n.starts_with("a_b~")));
+        assert_eq!(summary.renamed.len(), 5);
+    }
+
+    #[test]
+    fn test_extract_skips_unwritable_entries() {
+        let dir = TempDir::new().unwrap();
+        // A file where the entry expects a directory
+        fs::write(dir.path().join("media"), "not a dir").unwrap();
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("media/photo.jpg", options).unwrap();
+        writer.write_all(b"jpg").unwrap();
+        writer.start_file("data/tweets.js", options).unwrap();
+        writer.write_all(b"[]").unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let summary = extract_bytes(bytes, dir.path());
+        assert_eq!(summary.files, 1);
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].0, "media/photo.jpg");
+        assert!(dir.path().join("data/tweets.js").exists());
+    }
+}
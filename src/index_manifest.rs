@@ -0,0 +1,603 @@
+//! Reproducibility manifest for an index (`xf verify-index`).
+//!
+//! After indexing, xf writes [`INDEX_MANIFEST_FILENAME`] into the index
+//! directory: a SHA-256 of every archive data file, record counts per type,
+//! and the versions that shaped the index (xf, database schema, Tantivy
+//! index format, embedder). The fingerprint hashes all of that except the
+//! machine-specific archive path and time, so two machines that indexed the
+//! same archive with the same build report the same fingerprint.
+
+use crate::canonicalize::hex_encode;
+use crate::doctor::{CheckCategory, CheckStatus, HealthCheck};
+use crate::import::long_path;
+use crate::parser::ArchiveParser;
+use crate::search::TextIndex;
+use crate::storage::{SCHEMA_VERSION, Storage};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use ring::digest::{self, SHA256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Manifest file name inside the index directory.
+pub const INDEX_MANIFEST_FILENAME: &str = "xf-index-manifest.json";
+
+/// Differences listed per check before summarizing the rest.
+const MAX_LISTED_DIFFERENCES: usize = 5;
+
+/// An archive data file the index was built from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceFile {
+    /// Path inside the archive, e.g. `data/tweets.js`
+    pub path: String,
+    pub sha256: String,
+    pub bytes: u64,
+}
+
+/// What an index was built from and with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexManifest {
+    /// SHA-256 over every field below except `archive_path` and `indexed_at`
+    pub fingerprint: String,
+    pub xf_version: String,
+    pub schema_version: i32,
+    /// Tantivy version and on-disk index format
+    pub index_format: String,
+    /// Embedder that produced the stored embeddings, if any
+    pub embedder: Option<String>,
+    pub archive_path: PathBuf,
+    pub indexed_at: DateTime<Utc>,
+    pub sources: Vec<SourceFile>,
+    /// Stored records per type
+    pub counts: BTreeMap<String, i64>,
+    /// Documents in the search index
+    pub search_documents: u64,
+}
+
+/// The reproducible part of a manifest, hashed into its fingerprint.
+#[derive(Serialize)]
+struct Fingerprinted<'a> {
+    xf_version: &'a str,
+    schema_version: i32,
+    index_format: &'a str,
+    embedder: Option<&'a str>,
+    sources: &'a [SourceFile],
+    counts: &'a BTreeMap<String, i64>,
+    search_documents: u64,
+}
+
+impl IndexManifest {
+    /// Describe the index just built from `archive_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an archive file cannot be read or the database
+    /// cannot be queried.
+    pub fn build(archive_path: &Path, storage: &Storage, search: &dyn TextIndex) -> Result<Self> {
+        let mut manifest = Self {
+            fingerprint: String::new(),
+            xf_version: String::new(),
+            schema_version: 0,
+            index_format: String::new(),
+            embedder: None,
+            archive_path: archive_path
+                .canonicalize()
+                .unwrap_or_else(|_| archive_path.to_path_buf()),
+            indexed_at: Utc::now(),
+            sources: hash_sources(archive_path)?,
+            counts: BTreeMap::new(),
+            search_documents: 0,
+        };
+        manifest.refresh(storage, search)?;
+        Ok(manifest)
+    }
+
+    /// Re-record counts and versions, keeping the source hashes. Used after
+    /// `xf embed` changes the embeddings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be queried.
+    pub fn refresh(&mut self, storage: &Storage, search: &dyn TextIndex) -> Result<()> {
+        self.xf_version = env!("CARGO_PKG_VERSION").to_string();
+        self.schema_version = SCHEMA_VERSION;
+        self.index_format = tantivy::version_string().to_string();
+        self.counts = counts(storage)?;
+        self.embedder = current_embedder(storage)?;
+        self.search_documents = search.doc_count()?;
+        self.fingerprint = self.compute_fingerprint()?;
+        Ok(())
+    }
+
+    fn compute_fingerprint(&self) -> Result<String> {
+        let json = serde_json::to_vec(&Fingerprinted {
+            xf_version: &self.xf_version,
+            schema_version: self.schema_version,
+            index_format: &self.index_format,
+            embedder: self.embedder.as_deref(),
+            sources: &self.sources,
+            counts: &self.counts,
+            search_documents: self.search_documents,
+        })?;
+        Ok(sha256_hex(&json))
+    }
+
+    /// Write the manifest into `index_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn write(&self, index_path: &Path) -> Result<()> {
+        let path = index_path.join(INDEX_MANIFEST_FILENAME);
+        std::fs::write(&path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("write {}", path.display()))
+    }
+
+    /// Read the manifest in `index_path`, if one was written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load(index_path: &Path) -> Result<Option<Self>> {
+        let path = index_path.join(INDEX_MANIFEST_FILENAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::load_file(&path).map(Some)
+    }
+
+    /// Read a manifest file, such as one copied from another machine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load_file(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("parse {}", path.display()))
+    }
+}
+
+/// Hash every `data/*.js` file of an extracted archive, sorted by path.
+///
+/// # Errors
+///
+/// Returns an error if the data directory cannot be listed or a file read.
+pub fn hash_sources(archive_path: &Path) -> Result<Vec<SourceFile>> {
+    let data_dir = archive_path.join("data");
+    ArchiveParser::new(archive_path)
+        .list_data_files()?
+        .into_iter()
+        .map(|name| {
+            let bytes = std::fs::read(long_path(&data_dir.join(&name)))
+                .with_context(|| format!("read data/{name}"))?;
+            Ok(SourceFile {
+                path: format!("data/{name}"),
+                sha256: sha256_hex(&bytes),
+                bytes: bytes.len() as u64,
+            })
+        })
+        .collect()
+}
+
+/// Stored record counts per type.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be queried.
+pub fn counts(storage: &Storage) -> Result<BTreeMap<String, i64>> {
+    let stats = storage.get_stats()?;
+    Ok([
+        ("tweets", stats.tweets_count),
+        ("likes", stats.likes_count),
+        ("dms", stats.dms_count),
+        ("dm_conversations", stats.dm_conversations_count),
+        ("grok_messages", stats.grok_messages_count),
+        ("followers", stats.followers_count),
+        ("following", stats.following_count),
+        ("blocks", stats.blocks_count),
+        ("mutes", stats.mutes_count),
+        ("embeddings", storage.embedding_count()?),
+    ]
+    .into_iter()
+    .map(|(name, count)| (name.to_string(), count))
+    .collect())
+}
+
+fn current_embedder(storage: &Storage) -> Result<Option<String>> {
+    Ok(storage.embedding_model()?.map(|(model, _)| model))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex_encode(digest::digest(&SHA256, bytes).as_ref())
+}
+
+/// Check `manifest` against the index on disk.
+///
+/// Source files are re-hashed from `archive_path`, falling back to the path
+/// recorded at index time; when neither exists that check is skipped with a
+/// warning. With `against`, the fingerprints of the two manifests are
+/// compared as well.
+#[must_use]
+pub fn verify(
+    manifest: &IndexManifest,
+    storage: &Storage,
+    search: &dyn TextIndex,
+    archive_path: Option<&Path>,
+    against: Option<&IndexManifest>,
+) -> Vec<HealthCheck> {
+    let mut checks = vec![
+        verify_fingerprint(manifest),
+        verify_versions(manifest, storage),
+        verify_counts(manifest, storage),
+        verify_search_documents(manifest, search),
+        verify_sources(manifest, archive_path.unwrap_or(&manifest.archive_path)),
+    ];
+    if let Some(other) = against {
+        checks.push(compare(manifest, other));
+    }
+    checks
+}
+
+fn check(
+    name: &str,
+    status: CheckStatus,
+    message: String,
+    suggestion: Option<&str>,
+) -> HealthCheck {
+    HealthCheck {
+        category: CheckCategory::Index,
+        name: name.to_string(),
+        status,
+        message,
+        suggestion: suggestion.map(String::from),
+    }
+}
+
+/// "a, b, c and 2 more"
+fn list(items: &[String]) -> String {
+    let mut out = items
+        .iter()
+        .take(MAX_LISTED_DIFFERENCES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > MAX_LISTED_DIFFERENCES {
+        let _ = write!(out, " and {} more", items.len() - MAX_LISTED_DIFFERENCES);
+    }
+    out
+}
+
+fn verify_fingerprint(manifest: &IndexManifest) -> HealthCheck {
+    match manifest.compute_fingerprint() {
+        Ok(fingerprint) if fingerprint == manifest.fingerprint => {
+            check("Manifest fingerprint", CheckStatus::Pass, fingerprint, None)
+        }
+        Ok(_) => check(
+            "Manifest fingerprint",
+            CheckStatus::Error,
+            "Manifest contents do not match its fingerprint; it was edited after indexing"
+                .to_string(),
+            Some("Re-index to write a fresh manifest: xf index --force"),
+        ),
+        Err(err) => check(
+            "Manifest fingerprint",
+            CheckStatus::Error,
+            format!("Could not hash manifest: {err}"),
+            None,
+        ),
+    }
+}
+
+fn verify_versions(manifest: &IndexManifest, storage: &Storage) -> HealthCheck {
+    // Missing embeddings are reported by the count check
+    let current_embedder = match current_embedder(storage) {
+        Ok(Some(model)) => Some(model),
+        _ => manifest.embedder.clone(),
+    };
+    let mut differences = Vec::new();
+    if manifest.xf_version != env!("CARGO_PKG_VERSION") {
+        differences.push(format!(
+            "xf {} (now {})",
+            manifest.xf_version,
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+    if manifest.schema_version != SCHEMA_VERSION {
+        differences.push(format!(
+            "schema {} (now {SCHEMA_VERSION})",
+            manifest.schema_version
+        ));
+    }
+    if manifest.index_format != tantivy::version_string() {
+        differences.push(format!(
+            "{} (now {})",
+            manifest.index_format,
+            tantivy::version_string()
+        ));
+    }
+    if manifest.embedder != current_embedder {
+        differences.push(format!(
+            "embedder {} (now {})",
+            manifest.embedder.as_deref().unwrap_or("none"),
+            current_embedder.as_deref().unwrap_or("none")
+        ));
+    }
+
+    if differences.is_empty() {
+        check(
+            "Versions",
+            CheckStatus::Pass,
+            format!("xf {}, schema {SCHEMA_VERSION}", manifest.xf_version),
+            None,
+        )
+    } else {
+        check(
+            "Versions",
+            CheckStatus::Warning,
+            format!("Indexed by a different build: {}", list(&differences)),
+            Some("Re-index with this build for comparable results: xf index --force"),
+        )
+    }
+}
+
+fn verify_counts(manifest: &IndexManifest, storage: &Storage) -> HealthCheck {
+    let current = match counts(storage) {
+        Ok(current) => current,
+        Err(err) => {
+            return check(
+                "Record counts",
+                CheckStatus::Error,
+                format!("Could not count records: {err}"),
+                None,
+            );
+        }
+    };
+    let differences: Vec<String> = manifest
+        .counts
+        .iter()
+        .filter_map(|(name, &expected)| {
+            let actual = current.get(name).copied().unwrap_or(0);
+            (actual != expected).then(|| format!("{name} {expected} → {actual}"))
+        })
+        .collect();
+
+    if differences.is_empty() {
+        let total: i64 = current
+            .iter()
+            .filter(|(name, _)| name.as_str() != "embeddings")
+            .map(|(_, count)| count)
+            .sum();
+        check(
+            "Record counts",
+            CheckStatus::Pass,
+            format!("{total} records match the manifest"),
+            None,
+        )
+    } else {
+        check(
+            "Record counts",
+            CheckStatus::Error,
+            format!("Database changed since indexing: {}", list(&differences)),
+            Some("Re-index to rebuild from the archive: xf index --force"),
+        )
+    }
+}
+
+fn verify_search_documents(manifest: &IndexManifest, search: &dyn TextIndex) -> HealthCheck {
+    let actual = match search.doc_count() {
+        Ok(actual) => actual,
+        Err(err) => {
+            return check(
+                "Search index",
+                CheckStatus::Error,
+                format!("Failed to count documents: {err}"),
+                Some("Re-index to rebuild the search index: xf index --force"),
+            );
+        }
+    };
+    if actual == manifest.search_documents {
+        check(
+            "Search index",
+            CheckStatus::Pass,
+            format!("{actual} documents"),
+            None,
+        )
+    } else {
+        check(
+            "Search index",
+            CheckStatus::Error,
+            format!(
+                "{actual} documents, manifest recorded {}",
+                manifest.search_documents
+            ),
+            Some("Re-index to rebuild the search index: xf index --force"),
+        )
+    }
+}
+
+fn verify_sources(manifest: &IndexManifest, archive_path: &Path) -> HealthCheck {
+    if !archive_path.join("data").is_dir() {
+        return check(
+            "Source files",
+            CheckStatus::Warning,
+            format!(
+                "Archive not found at {}; source hashes not re-checked",
+                archive_path.display()
+            ),
+            Some("Point at the extracted archive: xf verify-index --archive <path>"),
+        );
+    }
+    let current = match hash_sources(archive_path) {
+        Ok(current) => current,
+        Err(err) => {
+            return check(
+                "Source files",
+                CheckStatus::Error,
+                format!("Could not hash archive files: {err}"),
+                None,
+            );
+        }
+    };
+
+    let recorded: BTreeMap<&str, &SourceFile> = manifest
+        .sources
+        .iter()
+        .map(|file| (file.path.as_str(), file))
+        .collect();
+    let found: BTreeMap<&str, &SourceFile> = current
+        .iter()
+        .map(|file| (file.path.as_str(), file))
+        .collect();
+    let mut differences = Vec::new();
+    for (path, file) in &recorded {
+        match found.get(path) {
+            None => differences.push(format!("{path} missing")),
+            Some(now) if now.sha256 != file.sha256 => differences.push(format!("{path} changed")),
+            Some(_) => {}
+        }
+    }
+    differences.extend(
+        found
+            .keys()
+            .filter(|path| !recorded.contains_key(*path))
+            .map(|path| format!("{path} added")),
+    );
+
+    if differences.is_empty() {
+        check(
+            "Source files",
+            CheckStatus::Pass,
+            format!(
+                "{} files in {} match",
+                recorded.len(),
+                archive_path.display()
+            ),
+            None,
+        )
+    } else {
+        check(
+            "Source files",
+            CheckStatus::Error,
+            format!(
+                "Archive differs from what was indexed: {}",
+                list(&differences)
+            ),
+            Some("Re-index the archive: xf index --force"),
+        )
+    }
+}
+
+fn compare(manifest: &IndexManifest, other: &IndexManifest) -> HealthCheck {
+    if manifest.fingerprint == other.fingerprint {
+        return check(
+            "Compared manifest",
+            CheckStatus::Pass,
+            "Equivalent index: fingerprints match".to_string(),
+            None,
+        );
+    }
+    let mut differences = Vec::new();
+    if (
+        &manifest.xf_version,
+        manifest.schema_version,
+        &manifest.index_format,
+        &manifest.embedder,
+    ) != (
+        &other.xf_version,
+        other.schema_version,
+        &other.index_format,
+        &other.embedder,
+    ) {
+        differences.push("versions".to_string());
+    }
+    if manifest.sources != other.sources {
+        differences.push("source files".to_string());
+    }
+    if manifest.counts != other.counts {
+        differences.push("record counts".to_string());
+    }
+    if manifest.search_documents != other.search_documents {
+        differences.push("search documents".to_string());
+    }
+    if differences.is_empty() {
+        differences.push("fingerprint only (a manifest was edited)".to_string());
+    }
+    check(
+        "Compared manifest",
+        CheckStatus::Error,
+        format!("Indexes differ in {}", list(&differences)),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchEngine;
+    use tempfile::TempDir;
+
+    fn archive(dir: &Path, tweets_js: &str) -> PathBuf {
+        let data = dir.join("archive").join("data");
+        std::fs::create_dir_all(&data).unwrap();
+        std::fs::write(data.join("tweets.js"), tweets_js).unwrap();
+        std::fs::write(data.join("like.js"), "window.YTD.like.part0 = []").unwrap();
+        dir.join("archive")
+    }
+
+    fn status_of(checks: &[HealthCheck], name: &str) -> CheckStatus {
+        checks.iter().find(|c| c.name == name).unwrap().status
+    }
+
+    #[test]
+    fn test_manifest_round_trip_and_verify() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = archive(dir.path(), "window.YTD.tweets.part0 = []");
+        let storage = Storage::open(dir.path().join("xf.db")).unwrap();
+        let search = SearchEngine::open(dir.path().join("index")).unwrap();
+
+        let manifest = IndexManifest::build(&archive_path, &storage, &search).unwrap();
+        assert_eq!(manifest.sources.len(), 2);
+        assert_eq!(manifest.sources[0].path, "data/like.js");
+        assert_eq!(manifest.embedder, None);
+        manifest.write(&dir.path().join("index")).unwrap();
+        let loaded = IndexManifest::load(&dir.path().join("index"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded, manifest);
+
+        let checks = verify(&loaded, &storage, &search, None, Some(&manifest));
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Pass));
+
+        // An edited source file and an edited manifest are both caught
+        std::fs::write(archive_path.join("data/tweets.js"), "changed").unwrap();
+        let rebuilt = IndexManifest::build(&archive_path, &storage, &search).unwrap();
+        let mut edited = loaded;
+        edited.search_documents += 1;
+        let checks = verify(&edited, &storage, &search, None, Some(&rebuilt));
+        assert_eq!(status_of(&checks, "Source files"), CheckStatus::Error);
+        assert_eq!(
+            status_of(&checks, "Manifest fingerprint"),
+            CheckStatus::Error
+        );
+        assert_eq!(status_of(&checks, "Search index"), CheckStatus::Error);
+        assert_eq!(status_of(&checks, "Compared manifest"), CheckStatus::Error);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_machine_specific_fields() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = archive(dir.path(), "window.YTD.tweets.part0 = []");
+        let storage = Storage::open(dir.path().join("xf.db")).unwrap();
+        let search = SearchEngine::open(dir.path().join("index")).unwrap();
+
+        let first = IndexManifest::build(&archive_path, &storage, &search).unwrap();
+        let copy = dir.path().join("elsewhere");
+        std::fs::rename(&archive_path, &copy).unwrap();
+        let second = IndexManifest::build(&copy, &storage, &search).unwrap();
+        assert_ne!(first.archive_path, second.archive_path);
+        assert_eq!(first.fingerprint, second.fingerprint);
+    }
+}
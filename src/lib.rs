@@ -5,32 +5,110 @@
 //!
 //! # Modules
 //!
-//! - [`cli`] - Command-line interface definitions
+//! - [`api`] - Stable library API without CLI dependencies (`client` feature)
+//! - [`app`] - Open-archive facade for desktop GUIs (`client` feature)
+//! - [`server`] - MCP and HTTP servers over an archive (`xf serve`)
+//! - [`tui`] - Full-screen terminal browser (`xf tui`)
+//! - [`cli`] - Command-line interface definitions (`cli` feature, on by default)
 //! - [`error`] - Custom error types with rich context
 //! - [`model`] - Data models for X archive data
 //! - [`parser`] - Archive parsing and data extraction
 //! - [`search`] - Tantivy-based full-text search engine
+//! - [`fts`] - `SQLite` FTS5 search, for running without a Tantivy index
 //! - [`storage`] - `SQLite` storage layer
-
+//! - [`watchdog`] - Index anomalies noted by commands, shown as a banner by later ones
+//! - [`lite`] - In-memory search with no native dependencies
+//!
+//! # Features
+//!
+//! `native` (on by default through `cli`) builds storage, indexing and
+//! everything built on them. With `--no-default-features` only the portable
+//! core remains (models, [`parser`], [`lite`] and the text helpers), which
+//! also compiles for `wasm32-unknown-unknown`.
+
+#[cfg(feature = "native")]
+pub mod accessible;
+#[cfg(feature = "client")]
+pub mod api;
+#[cfg(feature = "client")]
+pub mod app;
+#[cfg(feature = "native")]
+pub mod ask;
+#[cfg(feature = "native")]
+pub mod audit;
+pub mod calendar;
+#[cfg(feature = "native")]
 pub mod canonicalize;
+#[cfg(feature = "native")]
+pub mod charts;
+#[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "native")]
 pub mod config;
 pub mod date_parser;
+#[cfg(feature = "native")]
+pub mod dm_media;
+#[cfg(feature = "native")]
 pub mod doctor;
 pub mod embedder;
+#[cfg(feature = "native")]
+pub mod embedding_model;
 pub mod error;
+pub mod extract;
+#[cfg(feature = "native")]
+pub mod fts;
+#[cfg(feature = "native")]
+pub mod grep;
 pub mod hash_embedder;
+#[cfg(feature = "native")]
 pub mod hybrid;
+pub mod i18n;
+#[cfg(feature = "native")]
+pub mod import;
+#[cfg(feature = "native")]
+pub mod index_manifest;
+pub mod lite;
+#[cfg(feature = "native")]
+pub mod llm;
 pub mod logging;
 pub mod model;
+pub mod overlap;
 pub mod parser;
 pub mod perf;
+#[cfg(feature = "cli")]
 pub mod repl;
+#[cfg(feature = "native")]
+pub mod rerank;
+#[cfg(feature = "cli")]
+pub mod schema;
+#[cfg(feature = "native")]
 pub mod search;
+#[cfg(feature = "native")]
+pub mod selftest;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "native")]
 pub mod stats_analytics;
+#[cfg(feature = "native")]
 pub mod storage;
+#[cfg(feature = "native")]
+pub mod summarize;
+pub mod synthetic;
+#[cfg(feature = "native")]
+pub mod tagging;
+pub mod transcript;
+#[cfg(feature = "cli")]
+pub mod tui;
+#[cfg(feature = "native")]
+pub mod unshorten;
+#[cfg(feature = "native")]
 pub mod vector;
+#[cfg(feature = "native")]
+pub mod verify;
+#[cfg(feature = "native")]
+pub mod watchdog;
 
+#[cfg(feature = "cli")]
 pub use cli::*;
 pub use error::{
     Result, ResultExt, VALID_CONFIG_KEYS, VALID_DATA_TYPES, VALID_OUTPUT_FIELDS, XfError,
@@ -38,10 +116,14 @@ pub use error::{
 };
 pub use model::*;
 pub use parser::ArchiveParser;
+#[cfg(feature = "native")]
 pub use search::SearchEngine;
+#[cfg(feature = "native")]
 pub use storage::Storage;
 
 use chrono::{DateTime, Datelike, Utc};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 /// Default database filename
 pub const DEFAULT_DB_NAME: &str = "xf.db";
@@ -200,6 +282,8 @@ pub fn format_duration(duration: std::time::Duration) -> String {
 ///
 /// This function creates embeddings for tweets, likes, DMs, and Grok messages
 /// using the hash-based embedder, storing them in the `SQLite` embeddings table.
+/// Stopwords are kept and every document type is embedded; see
+/// [`generate_embeddings_with`].
 ///
 /// # Errors
 ///
@@ -209,39 +293,138 @@ pub fn format_duration(duration: std::time::Duration) -> String {
 /// # Panics
 ///
 /// Panics only if the progress bar template is invalid (a programming error).
-#[allow(clippy::too_many_lines)]
+#[cfg(feature = "native")]
 pub fn generate_embeddings(storage: &Storage, show_progress: bool) -> Result<()> {
-    use crate::canonicalize::{canonicalize_for_embedding, content_hash};
-    use crate::embedder::Embedder;
-    use crate::hash_embedder::HashEmbedder;
-    use colored::Colorize;
+    generate_embeddings_with(
+        storage,
+        show_progress,
+        canonicalize::StopwordMode::Keep,
+        config::EMBEDDING_DOC_TYPES,
+        &hash_embedder::HashEmbedder::default(),
+    )
+}
+
+/// Generate embeddings with explicit stopword handling and document types.
+///
+/// Queries must be canonicalized with the same [`canonicalize::StopwordMode`]
+/// for semantic search to compare like with like. Only documents whose type
+/// (`tweet`, `like`, `dm`, `grok`) is listed in `types` are embedded; existing
+/// embeddings for other types are removed so semantic search never returns
+/// stale vectors for opted-out types.
+///
+/// DMs are additionally embedded as conversation windows (doc type
+/// `dm_conv`), keyed by each window's longest message.
+///
+/// Embeddings are recorded with `embedder`'s ID and dimension. If the stored
+/// embeddings came from a different model, they are all replaced.
+///
+/// # Errors
+///
+/// Returns an error if any storage query fails or if embedding generation
+/// encounters an unexpected failure.
+///
+/// # Panics
+///
+/// Panics only if the progress bar template is invalid (a programming error).
+#[cfg(feature = "native")]
+#[allow(clippy::too_many_lines)]
+pub fn generate_embeddings_with(
+    storage: &Storage,
+    show_progress: bool,
+    stopwords: canonicalize::StopwordMode,
+    types: &[&str],
+    embedder: &dyn embedder::Embedder,
+) -> Result<()> {
+    use crate::canonicalize::{canonicalize_for_embedding_with, content_hash};
+    use crate::error::Colorize;
     use indicatif::{ProgressBar, ProgressStyle};
     use rayon::prelude::*;
     use std::collections::{HashMap, HashSet};
     use std::time::Instant;
-    use tracing::warn;
 
     // Type alias for embedding records: (doc_id, doc_type, embedding, content_hash)
     type EmbedRecord = (String, String, Vec<f32>, Option<[u8; 32]>);
 
     const EMBED_CHUNK_SIZE: usize = 1000;
     const STORE_BATCH_SIZE: usize = 100;
+    const EMBED_BATCH_SIZE: usize = 32;
     let embed_start = Instant::now();
-    let embedder = HashEmbedder::default();
+    let model = embedder.id();
 
     if show_progress {
         println!();
         println!("{}", "Generating semantic embeddings...".bold().cyan());
     }
 
-    // Fetch all collections first to pre-allocate
-    let tweets = storage.get_all_tweets(None)?;
-    let likes = storage.get_all_likes(None)?;
-    let dms = storage.get_all_dms(None)?;
-    let grok_msgs = storage.get_all_grok_messages(None)?;
+    // Vectors from different models are not comparable
+    if let Some((stored_model, stored_dimension)) = storage.embedding_model()? {
+        if embedding_model::incompatibility(&stored_model, stored_dimension, embedder).is_some() {
+            storage.clear_embeddings()?;
+            if show_progress {
+                println!(
+                    "  {} Embedding model changed from {stored_model} to {model}; re-embedding everything",
+                    "·".dimmed()
+                );
+            }
+        }
+    }
+
+    let wants = |doc_type: &str| types.iter().any(|t| t.eq_ignore_ascii_case(doc_type));
+
+    // Drop embeddings for opted-out types
+    let mut removed_count = 0;
+    for doc_type in config::EMBEDDING_DOC_TYPES {
+        if !wants(doc_type) {
+            removed_count += storage.delete_embeddings_by_type(doc_type)?;
+        }
+    }
+    if !wants("dm") {
+        removed_count += storage.delete_embeddings_by_type(hybrid::DM_CONVERSATION_DOC_TYPE)?;
+    }
+    if show_progress && removed_count > 0 {
+        println!(
+            "  {} {} embeddings removed for excluded types",
+            "·".dimmed(),
+            format_number_usize(removed_count).dimmed()
+        );
+    }
+
+    // Fetch the enabled collections first to pre-allocate
+    let tweets = if wants("tweet") {
+        storage.get_all_tweets(None)?
+    } else {
+        Vec::new()
+    };
+    let likes = if wants("like") {
+        storage.get_all_likes(None)?
+    } else {
+        Vec::new()
+    };
+    let bookmarks = if wants("bookmark") {
+        storage.get_all_bookmarks(None)?
+    } else {
+        Vec::new()
+    };
+    let dms = if wants("dm") {
+        storage.get_all_dms(None)?
+    } else {
+        Vec::new()
+    };
+    let grok_msgs = if wants("grok") {
+        storage.get_all_grok_messages(None)?
+    } else {
+        Vec::new()
+    };
+
+    let conversation_windows = dm_conversation_windows(&dms);
 
     // Pre-allocate with known capacity
-    let capacity = tweets.len() + likes.len() + dms.len() + grok_msgs.len();
+    let capacity = tweets.len()
+        + likes.len()
+        + bookmarks.len()
+        + dms.len()
+        + conversation_windows.len()
+        + grok_msgs.len();
     let mut docs: Vec<(String, String, &'static str)> = Vec::with_capacity(capacity);
 
     // Tweets
@@ -258,6 +441,13 @@ pub fn generate_embeddings(storage: &Storage, show_progress: bool) -> Result<()>
         }
     }
 
+    // Bookmarks (only if they have text)
+    for bookmark in &bookmarks {
+        if let Some(text) = bookmark.full_text.as_deref().filter(|t| !t.is_empty()) {
+            docs.push((bookmark.tweet_id.clone(), text.to_string(), "bookmark"));
+        }
+    }
+
     // DMs
     for dm in &dms {
         if !dm.text.is_empty() {
@@ -265,6 +455,18 @@ pub fn generate_embeddings(storage: &Storage, show_progress: bool) -> Result<()>
         }
     }
 
+    // DM conversation windows; windows that no longer exist are dropped
+    if wants("dm") {
+        let window_ids: HashSet<String> = conversation_windows
+            .iter()
+            .map(|(doc_id, _)| doc_id.clone())
+            .collect();
+        storage.retain_embeddings_by_type(hybrid::DM_CONVERSATION_DOC_TYPE, &window_ids)?;
+    }
+    for (doc_id, text) in conversation_windows {
+        docs.push((doc_id, text, hybrid::DM_CONVERSATION_DOC_TYPE));
+    }
+
     // Grok messages
     for msg in &grok_msgs {
         if !msg.message.is_empty() {
@@ -319,7 +521,7 @@ pub fn generate_embeddings(storage: &Storage, show_progress: bool) -> Result<()>
 
         for (doc_id, text, doc_type) in chunk {
             // Canonicalize text
-            let canonical = canonicalize_for_embedding(text);
+            let canonical = canonicalize_for_embedding_with(text, stopwords);
             if canonical.is_empty() {
                 skipped_count += 1;
                 if let Some(ref pb) = pb {
@@ -351,45 +553,30 @@ pub fn generate_embeddings(storage: &Storage, show_progress: bool) -> Result<()>
             }
         }
 
-        let mut batch_cache: HashMap<[u8; 32], Vec<f32>> = HashMap::new();
-        let mut needed_hashes: Vec<[u8; 32]> = Vec::new();
-        let mut needed_hashes_set: HashSet<[u8; 32]> = HashSet::new();
-        for (_, _, _, hash) in &candidates {
-            if existing_hashes.contains(hash) && needed_hashes_set.insert(*hash) {
-                needed_hashes.push(*hash);
-            }
-        }
-
-        if !needed_hashes.is_empty() {
-            let fetched = storage.load_embeddings_by_hashes(&needed_hashes)?;
-            for (hash, embedding) in fetched {
-                batch_cache.insert(hash, embedding);
-            }
-        }
-
+        // Content already in the vector store only needs a document link.
+        let mut links: Vec<(String, String, [u8; 32])> = Vec::new();
         let mut new_hashes: Vec<(String, String, [u8; 32])> = Vec::new();
         let mut new_hashes_set: HashSet<[u8; 32]> = HashSet::new();
-        for (doc_id, _doc_type, canonical, hash) in &candidates {
-            if batch_cache.contains_key(hash) {
+        let mut pending: Vec<(String, &'static str, [u8; 32])> = Vec::new();
+        for (doc_id, doc_type, canonical, hash) in candidates {
+            if existing_hashes.contains(&hash) {
+                links.push((doc_id, doc_type.to_string(), hash));
+                reused_count += 1;
                 continue;
             }
-            if new_hashes_set.insert(*hash) {
-                new_hashes.push((doc_id.clone(), canonical.clone(), *hash));
+            if new_hashes_set.insert(hash) {
+                new_hashes.push((doc_id.clone(), canonical, hash));
+            } else {
+                reused_count += 1;
             }
+            pending.push((doc_id, doc_type, hash));
         }
 
+        let mut batch_cache: HashMap<[u8; 32], Vec<f32>> = HashMap::new();
         if !new_hashes.is_empty() {
             let computed_embeddings: Vec<([u8; 32], Vec<f32>)> = new_hashes
-                .par_iter()
-                .filter_map(
-                    |(doc_id, canonical, hash)| match embedder.embed(canonical) {
-                        Ok(embedding) => Some((*hash, embedding)),
-                        Err(e) => {
-                            warn!("Failed to embed doc {}: {}", doc_id, e);
-                            None
-                        }
-                    },
-                )
+                .par_chunks(EMBED_BATCH_SIZE)
+                .flat_map_iter(|texts| embed_texts(embedder, texts))
                 .collect();
 
             for (hash, embedding) in computed_embeddings {
@@ -397,20 +584,9 @@ pub fn generate_embeddings(storage: &Storage, show_progress: bool) -> Result<()>
             }
         }
 
-        let mut seen_new_hashes: HashSet<[u8; 32]> = HashSet::new();
-        for (doc_id, doc_type, _canonical, hash) in candidates {
-            // Reuse an existing embedding if identical content exists.
-            if let Some(existing_embedding) = batch_cache.get(&hash) {
-                batch.push((
-                    doc_id,
-                    doc_type.to_string(),
-                    existing_embedding.clone(),
-                    Some(hash),
-                ));
-
-                if existing_hashes.contains(&hash) || !seen_new_hashes.insert(hash) {
-                    reused_count += 1;
-                }
+        for (doc_id, doc_type, hash) in pending {
+            if let Some(embedding) = batch_cache.get(&hash) {
+                batch.push((doc_id, doc_type.to_string(), embedding.clone(), Some(hash)));
             } else {
                 skipped_count += 1;
             }
@@ -419,12 +595,19 @@ pub fn generate_embeddings(storage: &Storage, show_progress: bool) -> Result<()>
         // Store batch
         if !batch.is_empty() {
             for chunk in batch.chunks(STORE_BATCH_SIZE) {
-                storage.store_embeddings_batch(chunk)?;
+                storage.store_embeddings_batch(chunk, model)?;
                 stored_count += chunk.len();
             }
+            existing_hashes.extend(batch_cache.into_keys());
+        }
+        if !links.is_empty() {
+            stored_count += storage.link_embeddings(&links, model)?;
         }
     }
 
+    // Documents whose text changed leave their old vectors behind
+    storage.prune_embedding_vectors()?;
+
     if let Some(pb) = pb {
         pb.finish_and_clear();
     }
@@ -464,12 +647,136 @@ pub fn generate_embeddings(storage: &Storage, show_progress: bool) -> Result<()>
     Ok(())
 }
 
+/// Embed `(doc_id, canonical text, hash)` entries in one batch, falling back
+/// to one text at a time when the batch fails so a single bad document only
+/// loses its own embedding.
+#[cfg(feature = "native")]
+fn embed_texts(
+    embedder: &dyn embedder::Embedder,
+    texts: &[(String, String, [u8; 32])],
+) -> Vec<([u8; 32], Vec<f32>)> {
+    let batch: Vec<&str> = texts.iter().map(|(_, text, _)| text.as_str()).collect();
+    if let Ok(embeddings) = embedder.embed_batch(&batch) {
+        if embeddings.len() == texts.len() {
+            return texts
+                .iter()
+                .map(|(_, _, hash)| *hash)
+                .zip(embeddings)
+                .collect();
+        }
+    }
+
+    texts
+        .iter()
+        .filter_map(|(doc_id, text, hash)| match embedder.embed(text) {
+            Ok(embedding) => Some((*hash, embedding)),
+            Err(e) => {
+                tracing::warn!("Failed to embed doc {}: {}", doc_id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Gap between DMs that starts a new conversation window.
+#[cfg(feature = "native")]
+const DM_WINDOW_GAP_HOURS: i64 = 6;
+
+/// Maximum number of messages in one conversation window.
+#[cfg(feature = "native")]
+const DM_WINDOW_MAX_MESSAGES: usize = 30;
+
+/// Split DM threads into windows of consecutive messages for embedding.
+///
+/// A window closes after a quiet gap of [`DM_WINDOW_GAP_HOURS`] or once it
+/// holds [`DM_WINDOW_MAX_MESSAGES`] messages. Windows with fewer than two
+/// non-empty messages add nothing over the per-message embeddings and are
+/// skipped. Each window is returned as `(doc_id, text)`, where `doc_id` is
+/// the window's longest message (earliest on ties) so hits resolve to a
+/// real DM.
+#[cfg(feature = "native")]
+fn dm_conversation_windows(dms: &[model::DirectMessage]) -> Vec<(String, String)> {
+    let mut by_conversation: std::collections::BTreeMap<&str, Vec<&model::DirectMessage>> =
+        std::collections::BTreeMap::new();
+    for dm in dms.iter().filter(|dm| !dm.text.trim().is_empty()) {
+        by_conversation
+            .entry(dm.conversation_id.as_str())
+            .or_default()
+            .push(dm);
+    }
+
+    let gap = chrono::Duration::hours(DM_WINDOW_GAP_HOURS);
+    let mut windows = Vec::new();
+    for messages in by_conversation.values_mut() {
+        messages.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        let mut start = 0;
+        for end in 1..=messages.len() {
+            let closes = end == messages.len()
+                || end - start == DM_WINDOW_MAX_MESSAGES
+                || messages[end].created_at - messages[end - 1].created_at > gap;
+            if !closes {
+                continue;
+            }
+            let window = &messages[start..end];
+            start = end;
+            if window.len() < 2 {
+                continue;
+            }
+            let representative = window
+                .iter()
+                .enumerate()
+                .max_by_key(|(idx, dm)| (dm.text.chars().count(), std::cmp::Reverse(*idx)))
+                .map(|(_, dm)| dm.id.clone())
+                .unwrap_or_default();
+            let text = window
+                .iter()
+                .map(|dm| dm.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            windows.push((representative, text));
+        }
+    }
+    windows
+}
+
 /// Escape text for CSV by sanitizing newlines and quotes.
 #[must_use]
 pub fn csv_escape_text(text: &str) -> String {
     text.replace('"', "\"\"").replace(['\n', '\r'], " ")
 }
 
+/// Render search results as a Markdown document titled `title`, quoting
+/// each result's text under a heading with its type, ID, and date.
+#[must_use]
+pub fn results_markdown(title: &str, documents: &[SearchResult]) -> String {
+    let mut lines = vec![format!("# {title}")];
+    for document in documents {
+        let mut heading = format!("## {} {}", document.result_type, document.id);
+        if document.created_at.timestamp() > 0 {
+            heading.push_str(
+                &document
+                    .created_at
+                    .format(" (%Y-%m-%d %H:%M UTC)")
+                    .to_string(),
+            );
+        }
+        lines.push(String::new());
+        lines.push(heading);
+        lines.push(String::new());
+        lines.extend(document.text.lines().map(|line| format!("> {line}")));
+        if let Some(notes) = document.metadata.get("notes").and_then(|n| n.as_array()) {
+            lines.push(String::new());
+            lines.extend(
+                notes
+                    .iter()
+                    .filter_map(|n| n.as_str())
+                    .map(|note| format!("- Note: {note}")),
+            );
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
 /// Format a long identifier as a short token (e.g., 1234...6789).
 #[must_use]
 pub fn format_short_id(id: &str) -> String {
@@ -482,6 +789,65 @@ pub fn format_short_id(id: &str) -> String {
     format!("{start}...{end}")
 }
 
+/// Width of `text` in terminal columns.
+///
+/// Wide characters (CJK, most emoji) take two columns and combining marks
+/// take none, so this is what alignment should be based on rather than
+/// `len()` or `chars().count()`.
+#[must_use]
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true).map(UnicodeWidthStr::width).sum()
+}
+
+/// Longest prefix of `text` that fits in `max_width` terminal columns.
+///
+/// Cuts only between grapheme clusters, so flags, ZWJ emoji sequences and
+/// accented letters are never split.
+#[must_use]
+pub fn clip_to_width(text: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (offset, grapheme) in text.grapheme_indices(true) {
+        width += UnicodeWidthStr::width(grapheme);
+        if width > max_width {
+            return &text[..offset];
+        }
+    }
+    text
+}
+
+/// Truncate `text` to at most `max_width` terminal columns, ending with
+/// `...` when anything was cut.
+///
+/// When `max_width` is 3 or less there is no room for the ellipsis and the
+/// text is clipped instead.
+#[must_use]
+pub fn truncate_display(text: &str, max_width: usize) -> String {
+    if display_width(text) <= max_width {
+        text.to_string()
+    } else if max_width <= 3 {
+        clip_to_width(text, max_width).to_string()
+    } else {
+        format!("{}...", clip_to_width(text, max_width - 3))
+    }
+}
+
+/// Like [`truncate_display`], but flattens line breaks first so the result
+/// fits on a single output line.
+#[must_use]
+pub fn truncate_line(text: &str, max_width: usize) -> String {
+    truncate_display(&text.replace('\n', " ").replace('\r', ""), max_width)
+}
+
+/// Pad `text` with trailing spaces to `width` terminal columns.
+///
+/// Use this instead of `{:<width$}` for user text, which pads by `char`
+/// count and misaligns wide characters.
+#[must_use]
+pub fn pad_display(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
+
 /// Format bytes into a human-friendly string.
 #[must_use]
 pub fn format_bytes(bytes: u64) -> String {
@@ -509,12 +875,53 @@ fn format_bytes_with_unit(bytes: u64, unit: u64, suffix: &str) -> String {
     format!("{whole}.{tenths} {suffix}")
 }
 
-#[cfg(test)]
+/// Parse a human-friendly size such as `500MB`, `20 GB`, `1.5G`, or `4096`.
+///
+/// Units are binary (`1KB` = 1024 bytes), matching [`format_bytes`].
+#[must_use]
+pub fn parse_bytes(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+    let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => BYTES_PER_KB,
+        "M" | "MB" | "MIB" => BYTES_PER_MB,
+        "G" | "GB" | "GIB" => BYTES_PER_GB,
+        "T" | "TB" | "TIB" => BYTES_PER_GB * 1024,
+        _ => return None,
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let mut bytes = whole.checked_mul(multiplier)?;
+    if !fraction.is_empty() {
+        let digits = u32::try_from(fraction.len()).ok()?;
+        let fraction: u64 = fraction.parse().ok()?;
+        let scale = 10_u64.checked_pow(digits)?;
+        bytes = bytes.checked_add(fraction.checked_mul(multiplier)? / scale)?;
+    }
+    Some(bytes)
+}
+
+#[cfg(all(test, feature = "native"))]
 mod tests {
     use super::{
-        Like, Storage, Tweet, TweetUrl, csv_escape_text, format_bytes_i64, format_duration,
-        format_number, format_relative_date_with_base, format_short_id, generate_embeddings,
+        Like, Storage, Tweet, TweetUrl, clip_to_width, csv_escape_text, display_width,
+        dm_conversation_windows, format_bytes_i64, format_duration, format_number,
+        format_relative_date_with_base, format_short_id, generate_embeddings,
+        generate_embeddings_with, pad_display, parse_bytes, truncate_display, truncate_line,
     };
+    use crate::canonicalize::StopwordMode;
     use crate::canonicalize::{canonicalize_for_embedding, content_hash};
     use crate::embedder::Embedder;
     use crate::hash_embedder::HashEmbedder;
@@ -598,32 +1005,75 @@ mod tests {
         assert_eq!(format_short_id("1234567890123"), "1234...0123");
     }
 
+    #[test]
+    fn display_width_counts_terminal_columns() {
+        assert_eq!(display_width("hello"), 5);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("🇯🇵"), 2);
+        assert_eq!(display_width("👩\u{200d}💻"), 2);
+    }
+
+    #[test]
+    fn clip_to_width_keeps_grapheme_clusters_whole() {
+        assert_eq!(clip_to_width("🇯🇵🇫🇷", 3), "🇯🇵");
+        assert_eq!(clip_to_width("👩\u{200d}💻 hi", 1), "");
+        assert_eq!(clip_to_width("👩\u{200d}💻 hi", 3), "👩\u{200d}💻 ");
+        assert_eq!(clip_to_width("cafe\u{301}!", 4), "cafe\u{301}");
+        assert_eq!(clip_to_width("日本語", 5), "日本");
+    }
+
+    #[test]
+    fn truncate_display_respects_width_and_ellipsis() {
+        assert_eq!(truncate_display("short", 10), "short");
+        assert_eq!(truncate_display("hello world", 8), "hello...");
+        assert_eq!(truncate_display("hello", 2), "he");
+        assert_eq!(truncate_display("日本語のテキスト", 9), "日本語...");
+        assert_eq!(truncate_display("🇯🇵🇯🇵🇯🇵", 5), "🇯🇵...");
+        for max in 0..12 {
+            let cut = truncate_display("日本語 text 🇯🇵 más", max);
+            assert!(display_width(&cut) <= max, "{cut:?} wider than {max}");
+        }
+    }
+
+    #[test]
+    fn truncate_line_flattens_line_breaks() {
+        assert_eq!(truncate_line("one\r\ntwo", 20), "one two");
+        assert_eq!(truncate_line("one\ntwo three", 9), "one tw...");
+    }
+
+    #[test]
+    fn pad_display_pads_by_columns() {
+        assert_eq!(pad_display("ab", 4), "ab  ");
+        assert_eq!(pad_display("日本", 6), "日本  ");
+        assert_eq!(pad_display("toolong", 3), "toolong");
+    }
+
     #[test]
     fn format_bytes_i64_clamps_negative() {
         assert_eq!(format_bytes_i64(-5), "0 B");
     }
 
+    #[test]
+    fn parse_bytes_accepts_units_and_fractions() {
+        assert_eq!(parse_bytes("4096"), Some(4096));
+        assert_eq!(parse_bytes("500MB"), Some(500 * 1024 * 1024));
+        assert_eq!(parse_bytes("20 gb"), Some(20 * 1024 * 1024 * 1024));
+        assert_eq!(parse_bytes("1.5K"), Some(1536));
+        assert_eq!(parse_bytes(""), None);
+        assert_eq!(parse_bytes("12 parsecs"), None);
+        assert_eq!(parse_bytes("1.2.3GB"), None);
+    }
+
     fn seed_storage(storage: &mut Storage, base: DateTime<Utc>) -> Result<()> {
         let tweet = Tweet {
-            id: "t1".to_string(),
             created_at: base,
-            full_text: "hello world".to_string(),
-            source: None,
-            favorite_count: 0,
-            retweet_count: 0,
-            lang: None,
-            in_reply_to_status_id: None,
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: Vec::new(),
-            user_mentions: Vec::new(),
             urls: vec![TweetUrl {
                 url: "https://example.com".to_string(),
                 expanded_url: None,
                 display_url: None,
             }],
-            media: Vec::new(),
+            ..Tweet::fixture("t1", "hello world")
         };
         storage.store_tweets(&[tweet])?;
 
@@ -725,7 +1175,7 @@ mod tests {
         }
 
         if !batch.is_empty() {
-            storage.store_embeddings_batch(&batch)?;
+            storage.store_embeddings_batch(&batch, HashEmbedder::default().id())?;
         }
 
         Ok(())
@@ -763,4 +1213,199 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn generate_embeddings_stores_identical_content_once() -> Result<()> {
+        let base = Utc
+            .with_ymd_and_hms(2025, 1, 10, 12, 0, 0)
+            .single()
+            .unwrap();
+        let mut storage = Storage::open_memory()?;
+        seed_storage(&mut storage, base)?;
+        storage.store_likes(&[Like {
+            tweet_id: "l2".to_string(),
+            full_text: Some("hello world".to_string()),
+            expanded_url: None,
+        }])?;
+
+        generate_embeddings(&storage, false)?;
+        assert_eq!(storage.embedding_count()?, 5);
+        assert_eq!(storage.embedding_vector_count()?, 4);
+        assert_eq!(
+            storage.get_embedding_hash("l2", "like")?,
+            storage.get_embedding_hash("t1", "tweet")?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn generate_embeddings_respects_type_opt_out() -> Result<()> {
+        let base = Utc
+            .with_ymd_and_hms(2025, 1, 10, 12, 0, 0)
+            .single()
+            .unwrap();
+        let mut storage = Storage::open_memory()?;
+        seed_storage(&mut storage, base)?;
+
+        generate_embeddings(&storage, false)?;
+        assert_eq!(storage.embedding_count()?, 4);
+
+        // Narrowing the types drops stale embeddings for the excluded ones
+        generate_embeddings_with(
+            &storage,
+            false,
+            StopwordMode::Keep,
+            &["tweet", "dm"],
+            &HashEmbedder::default(),
+        )?;
+        let mut types: Vec<String> = storage
+            .load_all_embeddings()?
+            .into_iter()
+            .map(|(_, doc_type, _)| doc_type)
+            .collect();
+        types.sort();
+        assert_eq!(types, vec!["dm", "tweet"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn generate_embeddings_replaces_vectors_from_another_model() -> Result<()> {
+        let base = Utc
+            .with_ymd_and_hms(2025, 1, 10, 12, 0, 0)
+            .single()
+            .unwrap();
+        let mut storage = Storage::open_memory()?;
+        seed_storage(&mut storage, base)?;
+
+        generate_embeddings(&storage, false)?;
+        assert_eq!(
+            storage.embedding_model()?,
+            Some(("fnv1a-384".to_string(), 384))
+        );
+
+        let embedder = HashEmbedder::new(256);
+        generate_embeddings_with(
+            &storage,
+            false,
+            StopwordMode::Keep,
+            crate::config::EMBEDDING_DOC_TYPES,
+            &embedder,
+        )?;
+        assert_eq!(storage.embedding_count()?, 4);
+        assert_eq!(
+            storage.embedding_model()?,
+            Some(("fnv1a-256".to_string(), 256))
+        );
+        assert_eq!(
+            storage.get_embedding("t1", "tweet")?.map(|e| e.len()),
+            Some(256)
+        );
+
+        Ok(())
+    }
+
+    fn dm_at(id: &str, conversation_id: &str, text: &str, at: DateTime<Utc>) -> DirectMessage {
+        DirectMessage {
+            id: id.to_string(),
+            conversation_id: conversation_id.to_string(),
+            sender_id: "u1".to_string(),
+            recipient_id: "u2".to_string(),
+            text: text.to_string(),
+            created_at: at,
+            urls: Vec::new(),
+            media_urls: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dm_conversation_windows_split_on_gaps() {
+        let base = Utc
+            .with_ymd_and_hms(2025, 1, 10, 12, 0, 0)
+            .single()
+            .unwrap();
+        let dms = vec![
+            dm_at(
+                "a2",
+                "conv1",
+                "sounds good, see you at the trailhead",
+                base + Duration::minutes(5),
+            ),
+            dm_at("a1", "conv1", "hike on saturday?", base),
+            dm_at("a3", "conv1", "", base + Duration::minutes(6)),
+            // Lone message after a long gap: no window of its own
+            dm_at(
+                "a4",
+                "conv1",
+                "did you get home ok?",
+                base + Duration::hours(30),
+            ),
+            dm_at("b1", "conv2", "same", base),
+            dm_at("b2", "conv2", "same", base + Duration::minutes(1)),
+        ];
+
+        let windows = dm_conversation_windows(&dms);
+
+        assert_eq!(
+            windows,
+            vec![
+                (
+                    "a2".to_string(),
+                    "hike on saturday?\nsounds good, see you at the trailhead".to_string()
+                ),
+                ("b1".to_string(), "same\nsame".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_embeddings_tracks_dm_conversation_windows() -> Result<()> {
+        let base = Utc
+            .with_ymd_and_hms(2025, 1, 10, 12, 0, 0)
+            .single()
+            .unwrap();
+        let mut storage = Storage::open_memory()?;
+        let messages = vec![
+            dm_at("m1", "conv1", "trying a new sourdough starter", base),
+            dm_at(
+                "m2",
+                "conv1",
+                "how is the crumb?",
+                base + Duration::minutes(3),
+            ),
+        ];
+        storage.store_dm_conversations(&[DmConversation {
+            conversation_id: "conv1".to_string(),
+            messages,
+        }])?;
+
+        generate_embeddings(&storage, false)?;
+        assert!(storage.get_embedding_hash("m1", "dm_conv")?.is_some());
+
+        // A longer message moves the window's representative
+        storage.store_dm_conversations(&[DmConversation {
+            conversation_id: "conv1".to_string(),
+            messages: vec![dm_at(
+                "m3",
+                "conv1",
+                "the crumb is open and glossy, best loaf so far",
+                base + Duration::minutes(5),
+            )],
+        }])?;
+        generate_embeddings(&storage, false)?;
+        assert!(storage.get_embedding_hash("m1", "dm_conv")?.is_none());
+        assert!(storage.get_embedding_hash("m3", "dm_conv")?.is_some());
+
+        generate_embeddings_with(
+            &storage,
+            false,
+            StopwordMode::Keep,
+            &["tweet"],
+            &HashEmbedder::default(),
+        )?;
+        assert!(storage.get_embedding_hash("m3", "dm_conv")?.is_none());
+
+        Ok(())
+    }
 }
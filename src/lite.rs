@@ -0,0 +1,290 @@
+//! In-memory keyword search for small archives (`xf::lite`).
+//!
+//! Unlike [`crate::search`], this needs neither Tantivy nor `SQLite`, so it
+//! builds with `--no-default-features`, including for `wasm32-unknown-unknown`.
+//! A browser page can parse archive files it was handed as text (see
+//! [`crate::ArchiveParser::tweets_from_js`]) and search them client-side.
+//!
+//! Every query word must match a word in the document, either exactly or
+//! as a prefix; results are ranked with BM25. Phrases, boolean operators
+//! and field filters from `xf search` are not supported.
+
+use crate::model::{
+    DirectMessage, DmMeta, Like, LikeMeta, ResultMeta, SearchResult, SearchResultType, Tweet,
+    TweetMeta, match_ranges,
+};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// BM25 term frequency saturation.
+const K1: f32 = 1.2;
+/// BM25 length normalization.
+const B: f32 = 0.75;
+/// Score weight of a prefix match relative to an exact word match.
+const PREFIX_WEIGHT: f32 = 0.5;
+
+struct LiteDoc {
+    result_type: SearchResultType,
+    id: String,
+    text: String,
+    created_at: DateTime<Utc>,
+    metadata: serde_json::Value,
+    words: usize,
+}
+
+/// Documents and a word index, all in memory.
+#[derive(Default)]
+pub struct LiteIndex {
+    docs: Vec<LiteDoc>,
+    /// Word to (document, occurrences), ordered so prefixes are a range scan
+    postings: BTreeMap<String, Vec<(usize, u32)>>,
+    seen: HashSet<(SearchResultType, String)>,
+    total_words: usize,
+}
+
+impl LiteIndex {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of indexed documents.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Add tweets, skipping IDs already indexed. Returns how many were added.
+    pub fn add_tweets(&mut self, tweets: &[Tweet]) -> usize {
+        tweets
+            .iter()
+            .filter(|tweet| {
+                self.add(
+                    SearchResultType::Tweet,
+                    &tweet.id,
+                    &tweet.full_text,
+                    tweet.created_at,
+                    &ResultMeta::Tweet(TweetMeta::from(*tweet)),
+                )
+            })
+            .count()
+    }
+
+    /// Add likes that have text, skipping IDs already indexed.
+    pub fn add_likes(&mut self, likes: &[Like]) -> usize {
+        likes
+            .iter()
+            .filter(|like| {
+                let Some(text) = like.full_text.as_deref().filter(|t| !t.is_empty()) else {
+                    return false;
+                };
+                // Likes carry no timestamp in the archive
+                self.add(
+                    SearchResultType::Like,
+                    &like.tweet_id,
+                    text,
+                    DateTime::<Utc>::UNIX_EPOCH,
+                    &ResultMeta::Like(LikeMeta::from(*like)),
+                )
+            })
+            .count()
+    }
+
+    /// Add direct messages, skipping IDs already indexed.
+    pub fn add_direct_messages(&mut self, messages: &[DirectMessage]) -> usize {
+        messages
+            .iter()
+            .filter(|dm| {
+                self.add(
+                    SearchResultType::DirectMessage,
+                    &dm.id,
+                    &dm.text,
+                    dm.created_at,
+                    &ResultMeta::DirectMessage(DmMeta::from(*dm)),
+                )
+            })
+            .count()
+    }
+
+    fn add(
+        &mut self,
+        result_type: SearchResultType,
+        id: &str,
+        text: &str,
+        created_at: DateTime<Utc>,
+        metadata: &ResultMeta,
+    ) -> bool {
+        if !self.seen.insert((result_type, id.to_string())) {
+            return false;
+        }
+        let doc = self.docs.len();
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        let mut words = 0;
+        for word in words_of(text) {
+            *counts.entry(word).or_default() += 1;
+            words += 1;
+        }
+        for (word, count) in counts {
+            self.postings.entry(word).or_default().push((doc, count));
+        }
+        self.total_words += words;
+        self.docs.push(LiteDoc {
+            result_type,
+            id: id.to_string(),
+            text: text.to_string(),
+            created_at,
+            metadata: metadata.to_value(),
+            words,
+        });
+        true
+    }
+
+    /// Search for documents containing every word of `query`, best first.
+    ///
+    /// `types` limits the document types searched; `None` searches all.
+    #[must_use]
+    pub fn search(
+        &self,
+        query: &str,
+        types: Option<&[SearchResultType]>,
+        limit: usize,
+    ) -> Vec<SearchResult> {
+        let terms: Vec<String> = words_of(query).collect();
+        if terms.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let (doc_count, avg_words) = (
+            self.docs.len() as f32,
+            self.total_words as f32 / self.docs.len().max(1) as f32,
+        );
+
+        // Per document: summed score and how many query terms matched
+        let mut scores: HashMap<usize, (f32, usize)> = HashMap::new();
+        for term in &terms {
+            let mut term_scores: HashMap<usize, f32> = HashMap::new();
+            for (word, postings) in self.postings.range(term.clone()..) {
+                if !word.starts_with(term.as_str()) {
+                    break;
+                }
+                let weight = if word == term { 1.0 } else { PREFIX_WEIGHT };
+                #[allow(clippy::cast_precision_loss)]
+                let idf = ((doc_count - postings.len() as f32 + 0.5)
+                    / (postings.len() as f32 + 0.5))
+                    .ln_1p();
+                for &(doc, count) in postings {
+                    #[allow(clippy::cast_precision_loss)]
+                    let (tf, len) = (count as f32, self.docs[doc].words as f32);
+                    let norm = K1.mul_add(B.mul_add(len / avg_words, 1.0 - B), tf);
+                    let bm25 = idf * tf * (K1 + 1.0) / norm;
+                    let best = term_scores.entry(doc).or_default();
+                    *best = best.max(weight * bm25);
+                }
+            }
+            for (doc, score) in term_scores {
+                let entry = scores.entry(doc).or_default();
+                entry.0 += score;
+                entry.1 += 1;
+            }
+        }
+
+        let mut hits: Vec<(usize, f32)> = scores
+            .into_iter()
+            .filter(|(doc, (_, matched))| {
+                *matched == terms.len()
+                    && types.is_none_or(|types| types.contains(&self.docs[*doc].result_type))
+            })
+            .map(|(doc, (score, _))| (doc, score))
+            .collect();
+        hits.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| self.docs[b.0].created_at.cmp(&self.docs[a.0].created_at))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        hits.truncate(limit);
+
+        hits.into_iter()
+            .map(|(doc, score)| {
+                let doc = &self.docs[doc];
+                SearchResult {
+                    result_type: doc.result_type,
+                    id: doc.id.clone(),
+                    text: doc.text.clone(),
+                    created_at: doc.created_at,
+                    score,
+                    highlights: Vec::new(),
+                    match_ranges: match_ranges(&doc.text, query),
+                    metadata: doc.metadata.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Lowercased runs of letters, digits and underscores.
+fn words_of(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArchiveParser;
+
+    const TWEETS_JS: &str = r#"window.YTD.tweets.part0 = [
+        {"tweet": {"id_str": "1", "created_at": "Fri Jan 09 15:12:21 +0000 2026",
+                   "full_text": "Rust makes systems programming fun", "favorite_count": "3"}},
+        {"tweet": {"id_str": "2", "created_at": "Sat Jan 10 09:00:00 +0000 2026",
+                   "full_text": "Rustaceans unite: rust rust rust"}},
+        {"tweet": {"id_str": "3", "created_at": "Sun Jan 11 09:00:00 +0000 2026",
+                   "full_text": "Nothing to see here"}}
+    ]"#;
+
+    #[test]
+    fn test_search_parsed_archive_text() {
+        let tweets = ArchiveParser::tweets_from_js(TWEETS_JS).unwrap();
+        let mut index = LiteIndex::new();
+        assert_eq!(index.add_tweets(&tweets), 3);
+        assert_eq!(index.add_tweets(&tweets), 0);
+
+        let results = index.search("rust", None, 10);
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["2", "1"]);
+        assert_eq!(results[1].metadata["favorite_count"], 3);
+
+        // Prefixes match, and every word must be present
+        assert_eq!(index.search("rustac", None, 10)[0].id, "2");
+        assert_eq!(index.search("rust fun", None, 10).len(), 1);
+        assert!(index.search("rust", None, 0).is_empty());
+    }
+
+    #[test]
+    fn test_search_filters_types() {
+        let mut index = LiteIndex::new();
+        index.add_likes(&[
+            Like {
+                tweet_id: "10".to_string(),
+                full_text: Some("a liked rust post".to_string()),
+                expanded_url: None,
+            },
+            Like {
+                tweet_id: "11".to_string(),
+                full_text: None,
+                expanded_url: None,
+            },
+        ]);
+        index.add_tweets(&ArchiveParser::tweets_from_js(TWEETS_JS).unwrap());
+        assert_eq!(index.len(), 4);
+
+        let likes = index.search("rust", Some(&[SearchResultType::Like]), 10);
+        assert_eq!(likes.len(), 1);
+        assert_eq!(likes[0].id, "10");
+    }
+}
@@ -0,0 +1,202 @@
+//! Minimal client for OpenAI-compatible chat completion endpoints.
+//!
+//! Works with local servers (Ollama, llama.cpp, vLLM) as well as hosted
+//! APIs. Nothing here runs unless the user configures `llm.endpoint`.
+
+use crate::config::LlmConfig;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Environment variable holding the API key for the LLM endpoint.
+pub const API_KEY_ENV: &str = "XF_LLM_API_KEY";
+
+/// Longest error body echoed back from the endpoint.
+const MAX_ERROR_BODY_CHARS: usize = 300;
+
+/// A single chat message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChatMessage {
+    pub role: &'static str,
+    pub content: String,
+}
+
+impl ChatMessage {
+    #[must_use]
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: "system",
+            content: content.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user",
+            content: content.into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    messages: &'a [ChatMessage],
+    temperature: f32,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatChoiceMessage {
+    content: Option<String>,
+}
+
+/// Client for the configured chat completion endpoint.
+pub struct LlmClient {
+    url: String,
+    model: Option<String>,
+    api_key: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl LlmClient {
+    /// Build a client from config; returns `None` when no endpoint is configured.
+    #[must_use]
+    pub fn from_config(config: &LlmConfig) -> Option<Self> {
+        let endpoint = config.endpoint.as_deref().map(str::trim)?;
+        if endpoint.is_empty() {
+            return None;
+        }
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build();
+        Some(Self {
+            url: chat_completions_url(endpoint),
+            model: (!config.model.is_empty()).then(|| config.model.clone()),
+            api_key: std::env::var(API_KEY_ENV)
+                .ok()
+                .filter(|key| !key.is_empty()),
+            agent,
+        })
+    }
+
+    /// The chat completions URL requests are sent to.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Send a chat request and return the assistant's reply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the endpoint is unreachable, responds with a
+    /// non-success status, or returns a body without a reply.
+    pub fn chat(&self, messages: &[ChatMessage]) -> Result<String> {
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json");
+        if let Some(key) = &self.api_key {
+            request = request.set("Authorization", &format!("Bearer {key}"));
+        }
+
+        let body = ChatRequest {
+            model: self.model.as_deref(),
+            messages,
+            temperature: 0.2,
+            stream: false,
+        };
+        let response = match request.send_json(&body) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                let detail = response.into_string().unwrap_or_default();
+                let detail: String = detail.chars().take(MAX_ERROR_BODY_CHARS).collect();
+                bail!("LLM endpoint returned HTTP {code}: {}", detail.trim());
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to reach {}", self.url));
+            }
+        };
+
+        let parsed: ChatResponse = response
+            .into_json()
+            .context("LLM endpoint returned an unexpected response")?;
+        reply_text(parsed)
+    }
+}
+
+fn reply_text(response: ChatResponse) -> Result<String> {
+    let reply = response
+        .choices
+        .into_iter()
+        .find_map(|choice| choice.message.content)
+        .map(|content| content.trim().to_string())
+        .unwrap_or_default();
+    if reply.is_empty() {
+        bail!("LLM endpoint returned an empty reply");
+    }
+    Ok(reply)
+}
+
+/// Accept either an API base (`.../v1`) or the full completions URL.
+fn chat_completions_url(endpoint: &str) -> String {
+    let base = endpoint.trim_end_matches('/');
+    if base.ends_with("/chat/completions") {
+        base.to_string()
+    } else {
+        format!("{base}/chat/completions")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_completions_url() {
+        assert_eq!(
+            chat_completions_url("http://localhost:11434/v1/"),
+            "http://localhost:11434/v1/chat/completions"
+        );
+        assert_eq!(
+            chat_completions_url("https://api.example.com/v1/chat/completions"),
+            "https://api.example.com/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_reply_text() {
+        let response: ChatResponse = serde_json::from_str(
+            r#"{"choices":[{"message":{"role":"assistant","content":"  Yes [1].\n"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(reply_text(response).unwrap(), "Yes [1].");
+
+        let empty: ChatResponse = serde_json::from_str(r#"{"choices":[]}"#).unwrap();
+        assert!(reply_text(empty).is_err());
+    }
+
+    #[test]
+    fn test_client_requires_endpoint() {
+        let mut config = LlmConfig::default();
+        assert!(LlmClient::from_config(&config).is_none());
+
+        config.endpoint = Some("http://localhost:8080/v1".to_string());
+        let client = LlmClient::from_config(&config).unwrap();
+        assert_eq!(client.url(), "http://localhost:8080/v1/chat/completions");
+        assert!(client.model.is_none());
+    }
+}
@@ -4,10 +4,12 @@
 //! from the JavaScript export format.
 
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// A tweet from the archive
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Tweet {
     pub id: String,
     pub created_at: DateTime<Utc>,
@@ -24,10 +26,150 @@ pub struct Tweet {
     pub user_mentions: Vec<UserMention>,
     pub urls: Vec<TweetUrl>,
     pub media: Vec<TweetMedia>,
+    /// Where the tweet was posted from, for geotagged tweets
+    #[serde(default)]
+    pub geo: Option<TweetGeo>,
+    /// Poll attached to the tweet
+    #[serde(default)]
+    pub poll: Option<TweetPoll>,
+    /// Replies received, when the export records it
+    #[serde(default)]
+    pub reply_count: Option<i64>,
+    /// Quote tweets received, when the export records it
+    #[serde(default)]
+    pub quote_count: Option<i64>,
+}
+
+impl Tweet {
+    /// Poll options and media alt text, one per line, for the search
+    /// indexes; `None` when the tweet has neither.
+    #[must_use]
+    pub fn attached_text(&self) -> Option<String> {
+        let options = self.poll.iter().flat_map(|poll| &poll.options);
+        let lines: Vec<&str> = options
+            .map(|o| o.label.as_str())
+            .chain(self.media.iter().filter_map(|m| m.alt_text.as_deref()))
+            .filter(|line| !line.is_empty())
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+impl Tweet {
+    /// A plain tweet posted now, for tests; set other fields with
+    /// `Tweet { favorite_count: 5, ..Tweet::fixture("1", "text") }`.
+    pub(crate) fn fixture(id: &str, text: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            created_at: Utc::now(),
+            full_text: text.to_string(),
+            source: None,
+            favorite_count: 0,
+            retweet_count: 0,
+            lang: None,
+            in_reply_to_status_id: None,
+            in_reply_to_user_id: None,
+            in_reply_to_screen_name: None,
+            is_retweet: false,
+            hashtags: Vec::new(),
+            user_mentions: Vec::new(),
+            urls: Vec::new(),
+            media: Vec::new(),
+            geo: None,
+            poll: None,
+            reply_count: None,
+            quote_count: None,
+        }
+    }
+}
+
+/// A poll on a tweet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TweetPoll {
+    pub options: Vec<PollOption>,
+    /// When voting closed, or closes
+    pub end_datetime: Option<DateTime<Utc>>,
+    /// Whether voting had ended when the archive was made
+    pub counts_are_final: bool,
+}
+
+/// One choice in a [`TweetPoll`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PollOption {
+    pub label: String,
+    /// Votes, when the archive recorded them
+    pub votes: Option<i64>,
+}
+
+/// Location attached to a geotagged tweet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TweetGeo {
+    /// Place name, e.g. "Portland, OR"
+    pub place: Option<String>,
+    pub country: Option<String>,
+    /// Exact point when the tweet shared one, else the centre of the place
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// A latitude/longitude rectangle for `--geo-bbox`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+impl GeoBox {
+    /// Parse `min_lon,min_lat,max_lon,max_lat`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message when there are not four numbers or they are out of
+    /// range.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let numbers = value
+            .split(',')
+            .map(|part| part.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("'{value}' is not a list of numbers"))?;
+        let [min_lon, min_lat, max_lon, max_lat] = numbers[..] else {
+            return Err(format!("expected 4 numbers, got {}", numbers.len()));
+        };
+        if !(-180.0..=180.0).contains(&min_lon) || !(-180.0..=180.0).contains(&max_lon) {
+            return Err("longitudes must be between -180 and 180".to_string());
+        }
+        if !(-90.0..=90.0).contains(&min_lat) || !(-90.0..=90.0).contains(&max_lat) {
+            return Err("latitudes must be between -90 and 90".to_string());
+        }
+        if min_lat > max_lat {
+            return Err("min_lat is greater than max_lat".to_string());
+        }
+        Ok(Self {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+        })
+    }
+
+    /// Whether the point lies inside. A box whose `min_lon` is east of its
+    /// `max_lon` crosses the antimeridian.
+    #[must_use]
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        let in_lon = if self.min_lon <= self.max_lon {
+            (self.min_lon..=self.max_lon).contains(&longitude)
+        } else {
+            longitude >= self.min_lon || longitude <= self.max_lon
+        };
+        in_lon && (self.min_lat..=self.max_lat).contains(&latitude)
+    }
 }
 
 /// A user mention in a tweet
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UserMention {
     pub id: String,
     pub screen_name: String,
@@ -35,7 +177,7 @@ pub struct UserMention {
 }
 
 /// A URL in a tweet
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TweetUrl {
     pub url: String,
     pub expanded_url: Option<String>,
@@ -43,22 +185,49 @@ pub struct TweetUrl {
 }
 
 /// Media attached to a tweet
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TweetMedia {
     pub id: String,
     pub media_type: String,
     pub url: String,
     pub local_path: Option<String>,
+    /// Description written for screen readers
+    #[serde(default)]
+    pub alt_text: Option<String>,
 }
 
 /// A liked tweet
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Like {
     pub tweet_id: String,
     pub full_text: Option<String>,
     pub expanded_url: Option<String>,
 }
 
+/// A bookmarked tweet
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Bookmark {
+    pub tweet_id: String,
+    pub full_text: Option<String>,
+    pub expanded_url: Option<String>,
+}
+
+/// Archive record types whose source JSON is kept alongside the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawKind {
+    Tweet,
+    Like,
+    DirectMessage,
+}
+
+/// One record's original archive JSON, keyed by the record's id
+/// (tweet id, liked tweet id, or message id).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawRecord {
+    pub id: String,
+    pub json: String,
+}
+
 /// A direct message conversation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DmConversation {
@@ -77,7 +246,7 @@ pub struct DmConversationSummary {
 }
 
 /// A direct message
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DirectMessage {
     pub id: String,
     pub conversation_id: String,
@@ -90,14 +259,14 @@ pub struct DirectMessage {
 }
 
 /// A follower
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Follower {
     pub account_id: String,
     pub user_link: Option<String>,
 }
 
 /// A following (account the user follows)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Following {
     pub account_id: String,
     pub user_link: Option<String>,
@@ -117,6 +286,59 @@ pub struct Mute {
     pub user_link: Option<String>,
 }
 
+/// How the archive owner relates to a list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListRelation {
+    /// Lists the owner created (`lists-created.js`)
+    Created,
+    /// Lists the owner was added to (`lists-member.js`)
+    Member,
+    /// Lists the owner follows (`lists-subscribed.js`)
+    Subscribed,
+}
+
+impl ListRelation {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Member => "member",
+            Self::Subscribed => "subscribed",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "created" => Some(Self::Created),
+            "member" => Some(Self::Member),
+            "subscribed" => Some(Self::Subscribed),
+            _ => None,
+        }
+    }
+}
+
+/// A list the owner created, belongs to, or follows.
+///
+/// Archives only record each list's URL; members are recorded separately
+/// with `xf list lists --list <ID> --members-file <FILE>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserList {
+    pub list_id: String,
+    pub url: String,
+    pub relation: ListRelation,
+}
+
+/// A stored list with every relation to it and its recorded member count
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListSummary {
+    pub list_id: String,
+    pub url: String,
+    pub relations: Vec<ListRelation>,
+    pub members: usize,
+}
+
 /// Account information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
@@ -138,6 +360,98 @@ pub struct Profile {
     pub header_url: Option<String>,
 }
 
+/// The profile as of one indexed archive (see `xf profile --history`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileVersion {
+    /// Generation date of the archive this was read from
+    pub archive_generated_at: DateTime<Utc>,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub website: Option<String>,
+    pub location: Option<String>,
+    pub avatar_url: Option<String>,
+    pub header_url: Option<String>,
+}
+
+/// A handle change from screen-name-change.js
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenNameChange {
+    pub account_id: String,
+    pub changed_at: DateTime<Utc>,
+    pub changed_from: String,
+    pub changed_to: String,
+}
+
+/// A handle an account was seen using, and over which period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountHandle {
+    pub account_id: String,
+    pub screen_name: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Resolves account IDs to the handle they were known by at a given time.
+#[derive(Debug, Clone, Default)]
+pub struct HandleResolver {
+    /// Handles per account, ordered by `first_seen`
+    handles: HashMap<String, Vec<AccountHandle>>,
+}
+
+impl HandleResolver {
+    /// Build a resolver from recorded handles.
+    #[must_use]
+    pub fn new(handles: Vec<AccountHandle>) -> Self {
+        let mut by_account: HashMap<String, Vec<AccountHandle>> = HashMap::new();
+        for handle in handles {
+            by_account
+                .entry(handle.account_id.clone())
+                .or_default()
+                .push(handle);
+        }
+        for handles in by_account.values_mut() {
+            handles.sort_by_key(|h| (h.first_seen, h.last_seen));
+        }
+        Self {
+            handles: by_account,
+        }
+    }
+
+    /// The handle `account_id` used at `at`: one seen around that time,
+    /// else the last one seen before it, else the earliest known.
+    #[must_use]
+    pub fn resolve(&self, account_id: &str, at: DateTime<Utc>) -> Option<&str> {
+        let handles = self.handles.get(account_id)?;
+        handles
+            .iter()
+            .rev()
+            .find(|h| h.first_seen <= at && at <= h.last_seen)
+            .or_else(|| handles.iter().rev().find(|h| h.first_seen <= at))
+            .or_else(|| handles.first())
+            .map(|h| h.screen_name.as_str())
+    }
+
+    /// The most recently seen handle for `account_id`.
+    #[must_use]
+    pub fn latest(&self, account_id: &str) -> Option<&str> {
+        self.handles
+            .get(account_id)?
+            .iter()
+            .max_by_key(|h| (h.last_seen, h.first_seen))
+            .map(|h| h.screen_name.as_str())
+    }
+
+    /// The most recently seen handle of every known account.
+    #[must_use]
+    pub fn latest_names(&self) -> HashMap<String, String> {
+        self.handles
+            .keys()
+            .filter_map(|id| Some((id.clone(), self.latest(id)?.to_string())))
+            .collect()
+    }
+}
+
 /// Grok chat message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GrokMessage {
@@ -148,6 +462,18 @@ pub struct GrokMessage {
     pub grok_mode: Option<String>,
 }
 
+/// A Community Note (formerly Birdwatch note) you wrote, from community-note.js
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommunityNote {
+    pub id: String,
+    /// The tweet the note was written on
+    pub tweet_id: Option<String>,
+    pub text: String,
+    /// How you classified the tweet, e.g. `MisinformedOrPotentiallyMisleading`
+    pub classification: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
 /// Archive metadata from manifest.js
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchiveInfo {
@@ -159,6 +485,127 @@ pub struct ArchiveInfo {
     pub is_partial: bool,
 }
 
+/// A point-in-time record of an indexed archive, taken on each `xf index` run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: i64,
+    pub taken_at: DateTime<Utc>,
+    pub archive_generated_at: DateTime<Utc>,
+}
+
+/// A user-defined tag category (`xf tag define`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagDefinition {
+    pub name: String,
+    /// Example texts or tweet IDs describing the category
+    pub examples: Vec<String>,
+    /// Minimum cosine similarity to the examples' centroid
+    pub threshold: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A user note attached to an archive document (`xf note add`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Note {
+    pub id: i64,
+    pub doc_id: String,
+    /// Document type: `tweet`, `like`, `dm`, or `grok`
+    pub doc_type: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A named set of saved documents (`xf collect`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub name: String,
+    pub items: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A document saved in a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionItem {
+    pub doc_id: String,
+    /// Document type: `tweet`, `like`, `dm`, or `grok`
+    pub doc_type: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// A document hidden from search, list, export, and stats
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HiddenDocument {
+    pub doc_id: String,
+    /// Document type: `tweet`, `like`, `dm`, or `grok`
+    pub doc_type: String,
+    pub hidden_at: DateTime<Utc>,
+}
+
+/// A document pinned to the top of matching searches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pin {
+    pub doc_id: String,
+    /// Document type: `tweet`, `like`, `dm`, or `grok`
+    pub doc_type: String,
+    /// Normalized query the pin applies to; `None` pins it whenever it matches
+    pub query: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A named search, added by `xf alerts add` or `xf search --save`. `xf alerts
+/// run` checks it for new matches; `xf saved run` runs it as a full search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub name: String,
+    pub query: String,
+    /// Document types searched (`tweet`, `like`, `dm`, `grok`); empty for all
+    pub types: Vec<String>,
+    /// Search mode (`lexical`, `semantic`, `hybrid`, or `auto`); alerts
+    /// added with `xf alerts add` are lexical
+    pub mode: String,
+    /// Sort order (`relevance`, `date`, `date-desc`, or `engagement`)
+    pub sort: String,
+    /// Filter options as command-line arguments, e.g. `["--since", "last month"]`.
+    /// Relative dates are kept as typed, so they move with each run.
+    pub filters: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    /// When the alert last ran; `None` until its first run
+    pub last_run_at: Option<DateTime<Utc>>,
+}
+
+/// An entry in the audit log of data access
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub at: DateTime<Utc>,
+    /// Command that accessed the data (`search`, `export`, ...)
+    pub command: String,
+    /// What happened: `read_dms` or `export`
+    pub event: String,
+    /// What was accessed: the query, export target, or destination
+    pub detail: String,
+    /// Number of records involved
+    pub count: i64,
+}
+
+/// Account lists captured per snapshot
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotKind {
+    Follower,
+    Following,
+}
+
+impl SnapshotKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Follower => "follower",
+            Self::Following => "following",
+        }
+    }
+}
+
 /// Statistics about the indexed archive
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchiveStats {
@@ -177,23 +624,340 @@ pub struct ArchiveStats {
 }
 
 /// Search result item
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SearchResult {
     pub result_type: SearchResultType,
     pub id: String,
     pub text: String,
     pub created_at: DateTime<Utc>,
     pub score: f32,
+    /// HTML snippets around the matches, in `<b>` tags; empty when snippets
+    /// are turned off
     pub highlights: Vec<String>,
+    /// Byte ranges of matched query words in `text`, in order, for UIs that
+    /// highlight matches themselves
+    #[serde(default)]
+    pub match_ranges: Vec<TextRange>,
+    /// Fields of [`ResultMeta`] for `result_type`; read them with
+    /// [`SearchResult::meta`]. The CLI may add `pinned` and `notes`.
+    #[schemars(with = "ResultMeta")]
     pub metadata: serde_json::Value,
 }
 
+impl SearchResult {
+    /// Typed `metadata` for this result's type.
+    ///
+    /// `None` when the metadata is missing or does not match the type, as
+    /// for results built without an index lookup.
+    #[must_use]
+    pub fn meta(&self) -> Option<ResultMeta> {
+        match self.result_type {
+            SearchResultType::Tweet => self.tweet_meta().map(ResultMeta::Tweet),
+            SearchResultType::Like => self.like_meta().map(ResultMeta::Like),
+            SearchResultType::Bookmark => self.bookmark_meta().map(ResultMeta::Bookmark),
+            SearchResultType::DirectMessage => self.dm_meta().map(ResultMeta::DirectMessage),
+            SearchResultType::GrokMessage => self.grok_meta().map(ResultMeta::GrokMessage),
+        }
+    }
+
+    /// Tweet metadata, if this is a tweet result.
+    #[must_use]
+    pub fn tweet_meta(&self) -> Option<TweetMeta> {
+        self.typed_meta(SearchResultType::Tweet)
+    }
+
+    /// Like metadata, if this is a like result.
+    #[must_use]
+    pub fn like_meta(&self) -> Option<LikeMeta> {
+        self.typed_meta(SearchResultType::Like)
+    }
+
+    /// Bookmark metadata, if this is a bookmark result.
+    #[must_use]
+    pub fn bookmark_meta(&self) -> Option<BookmarkMeta> {
+        self.typed_meta(SearchResultType::Bookmark)
+    }
+
+    /// DM metadata, if this is a DM result.
+    #[must_use]
+    pub fn dm_meta(&self) -> Option<DmMeta> {
+        self.typed_meta(SearchResultType::DirectMessage)
+    }
+
+    /// Grok metadata, if this is a Grok result.
+    #[must_use]
+    pub fn grok_meta(&self) -> Option<GrokMeta> {
+        self.typed_meta(SearchResultType::GrokMessage)
+    }
+
+    /// Cut `text` down to `max_chars` characters around the best match.
+    ///
+    /// The window is placed to cover as many `match_ranges` as it can,
+    /// centered on the first of them; without matches it starts at the
+    /// beginning. Cut ends are marked with `…`, `match_ranges` are moved to
+    /// the new text and `highlights` are dropped, since the window takes
+    /// their place. Shorter texts are left alone.
+    pub fn trim_to_context(&mut self, max_chars: usize) {
+        // Byte offset of each character
+        let offsets: Vec<usize> = self.text.char_indices().map(|(i, _)| i).collect();
+        let char_count = offsets.len();
+        if char_count <= max_chars {
+            return;
+        }
+        let char_at = |byte: usize| offsets.partition_point(|&offset| offset < byte);
+        let spans: Vec<(usize, usize)> = self
+            .match_ranges
+            .iter()
+            .map(|range| (char_at(range.start), char_at(range.end)))
+            .collect();
+
+        let (mut best_start, mut best_count) = (0, 0);
+        for &(start, end) in &spans {
+            let start = start
+                .saturating_sub(max_chars.saturating_sub(end - start) / 2)
+                .min(char_count - max_chars);
+            let count = spans
+                .iter()
+                .filter(|&&(from, to)| from >= start && to <= start + max_chars)
+                .count();
+            if count > best_count {
+                (best_start, best_count) = (start, count);
+            }
+        }
+
+        let best_end = best_start + max_chars;
+        let (from, to) = (
+            offsets[best_start],
+            offsets.get(best_end).copied().unwrap_or(self.text.len()),
+        );
+        let prefix = if best_start > 0 { "…" } else { "" };
+        let suffix = if best_end < char_count { "…" } else { "" };
+        self.text = format!("{prefix}{}{suffix}", &self.text[from..to]);
+        self.match_ranges = self
+            .match_ranges
+            .iter()
+            .filter(|range| range.start >= from && range.end <= to)
+            .map(|range| TextRange {
+                start: range.start - from + prefix.len(),
+                end: range.end - from + prefix.len(),
+            })
+            .collect();
+        self.highlights.clear();
+    }
+
+    fn typed_meta<T: serde::de::DeserializeOwned>(
+        &self,
+        result_type: SearchResultType,
+    ) -> Option<T> {
+        if self.result_type != result_type {
+            return None;
+        }
+        T::deserialize(&self.metadata).ok()
+    }
+}
+
+/// Byte range `start..end` of a match in a result's `text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TextRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Byte ranges of the words in `text` that `query` matches.
+///
+/// Words are runs of letters and digits, compared ignoring case. Like
+/// `xf search`, a query without a `"phrase"` also matches words starting
+/// with a query word of two or more characters. Operators (`AND`, `OR`,
+/// `NOT`) and excluded words (`-word`, `NOT word`) match nothing.
+#[must_use]
+pub fn match_ranges(text: &str, query: &str) -> Vec<TextRange> {
+    let prefix = !query.contains('"');
+    let mut terms: Vec<String> = Vec::new();
+    let mut excluded = false;
+    for (i, part) in query.split('"').enumerate() {
+        // Odd parts are inside quotes
+        if i % 2 == 1 {
+            if !std::mem::take(&mut excluded) {
+                terms.extend(words(part).into_iter().map(|(_, word)| word.to_lowercase()));
+            }
+            continue;
+        }
+        for token in part.split_whitespace() {
+            match token {
+                "AND" | "OR" => {}
+                "NOT" => excluded = true,
+                _ => {
+                    if !std::mem::take(&mut excluded) && !token.starts_with('-') {
+                        terms.extend(
+                            words(token)
+                                .into_iter()
+                                .map(|(_, word)| word.to_lowercase()),
+                        );
+                    }
+                }
+            }
+        }
+        // `-"phrase"` excludes the phrase that follows
+        excluded |= part.ends_with('-');
+    }
+
+    words(text)
+        .into_iter()
+        .filter(|(_, word)| {
+            let word = word.to_lowercase();
+            terms.iter().any(|term| {
+                *term == word || (prefix && term.chars().nth(1).is_some() && word.starts_with(term))
+            })
+        })
+        .map(|(start, word)| TextRange {
+            start,
+            end: start + word.len(),
+        })
+        .collect()
+}
+
+/// Runs of letters and digits with their byte offsets.
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(from)) => {
+                words.push((from, &text[from..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        words.push((from, &text[from..]));
+    }
+    words
+}
+
+/// `metadata` of a tweet result.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TweetMeta {
+    pub favorite_count: i64,
+    pub retweet_count: i64,
+    /// Screen name of the account replied to; `null` unless a reply
+    pub in_reply_to: Option<String>,
+    pub hashtags: Vec<String>,
+    /// Client the tweet was posted from
+    pub source: Option<String>,
+    /// Place name of a geotagged tweet
+    pub place: Option<String>,
+}
+
+/// `metadata` of a like result.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LikeMeta {
+    pub expanded_url: Option<String>,
+}
+
+/// `metadata` of a bookmark result.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct BookmarkMeta {
+    pub expanded_url: Option<String>,
+}
+
+/// `metadata` of a direct message result.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DmMeta {
+    pub conversation_id: String,
+    pub sender_id: String,
+    pub recipient_id: String,
+}
+
+/// `metadata` of a Grok message result.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GrokMeta {
+    pub chat_id: String,
+    pub sender: String,
+    pub grok_mode: Option<String>,
+}
+
+/// Typed `metadata` of a [`SearchResult`], one shape per result type.
+///
+/// Serializes to the bare fields of its variant, as in search output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ResultMeta {
+    Tweet(TweetMeta),
+    Like(LikeMeta),
+    Bookmark(BookmarkMeta),
+    DirectMessage(DmMeta),
+    GrokMessage(GrokMeta),
+}
+
+impl ResultMeta {
+    /// The JSON stored in [`SearchResult::metadata`].
+    #[must_use]
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+impl From<&Tweet> for TweetMeta {
+    fn from(tweet: &Tweet) -> Self {
+        Self {
+            favorite_count: tweet.favorite_count,
+            retweet_count: tweet.retweet_count,
+            in_reply_to: tweet.in_reply_to_screen_name.clone(),
+            hashtags: tweet.hashtags.clone(),
+            source: tweet.source.clone(),
+            place: tweet.geo.as_ref().and_then(|geo| geo.place.clone()),
+        }
+    }
+}
+
+impl From<&Like> for LikeMeta {
+    fn from(like: &Like) -> Self {
+        Self {
+            expanded_url: like.expanded_url.clone(),
+        }
+    }
+}
+
+impl From<&Bookmark> for BookmarkMeta {
+    fn from(bookmark: &Bookmark) -> Self {
+        Self {
+            expanded_url: bookmark.expanded_url.clone(),
+        }
+    }
+}
+
+impl From<&DirectMessage> for DmMeta {
+    fn from(dm: &DirectMessage) -> Self {
+        Self {
+            conversation_id: dm.conversation_id.clone(),
+            sender_id: dm.sender_id.clone(),
+            recipient_id: dm.recipient_id.clone(),
+        }
+    }
+}
+
+impl From<&GrokMessage> for GrokMeta {
+    fn from(message: &GrokMessage) -> Self {
+        Self {
+            chat_id: message.chat_id.clone(),
+            sender: message.sender.clone(),
+            grok_mode: message.grok_mode.clone(),
+        }
+    }
+}
+
 /// Type of search result
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SearchResultType {
     Tweet,
     Like,
+    Bookmark,
     DirectMessage,
     GrokMessage,
 }
@@ -203,6 +967,7 @@ impl std::fmt::Display for SearchResultType {
         match self {
             Self::Tweet => write!(f, "tweet"),
             Self::Like => write!(f, "like"),
+            Self::Bookmark => write!(f, "bookmark"),
             Self::DirectMessage => write!(f, "dm"),
             Self::GrokMessage => write!(f, "grok"),
         }
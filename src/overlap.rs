@@ -0,0 +1,187 @@
+//! Overlap between your tweets and the rest of the archive (`xf overlap`).
+//!
+//! Reports two kinds of duplication found by joining on tweet IDs: likes of
+//! your own tweets (self-likes), and your tweets that other people shared
+//! with you in DMs as status links.
+
+use crate::model::{DirectMessage, Tweet};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// One of your tweets as linked in a DM someone else sent.
+#[derive(Debug, Clone, Serialize)]
+pub struct DmQuote {
+    pub tweet: Tweet,
+    pub message_id: String,
+    pub conversation_id: String,
+    pub sender_id: String,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Self-likes and DM quotes of your tweets.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlapReport {
+    /// Your account ID, when the archive recorded it.
+    pub account_id: Option<String>,
+    pub self_likes: Vec<Tweet>,
+    pub dm_quotes: Vec<DmQuote>,
+}
+
+/// Status IDs linked from `text`, in order of appearance.
+///
+/// Matches `/status/<id>` and `/statuses/<id>` path segments, so both
+/// `x.com/<user>/status/<id>` and `twitter.com/i/web/status/<id>` links count.
+#[must_use]
+pub fn status_ids(text: &str) -> Vec<&str> {
+    let mut ids = Vec::new();
+    for marker in ["/status/", "/statuses/"] {
+        for (start, _) in text.match_indices(marker) {
+            let rest = &text[start + marker.len()..];
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if end > 0 {
+                ids.push((start, &rest[..end]));
+            }
+        }
+    }
+    ids.sort_by_key(|(start, _)| *start);
+    ids.into_iter().map(|(_, id)| id).collect()
+}
+
+/// Status IDs a message links to, from its expanded URLs and its text.
+#[must_use]
+pub fn linked_status_ids(dm: &DirectMessage) -> Vec<&str> {
+    let mut ids: Vec<&str> = dm
+        .urls
+        .iter()
+        .filter_map(|url| url.expanded_url.as_deref())
+        .flat_map(status_ids)
+        .chain(status_ids(&dm.text))
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+    ids
+}
+
+/// Messages not sent by `account_id`, each with the status IDs it links to.
+///
+/// With no account ID every message counts, since sent and received
+/// messages cannot be told apart.
+#[must_use]
+pub fn received_links<'a>(
+    account_id: Option<&str>,
+    dms: &'a [DirectMessage],
+) -> Vec<(&'a DirectMessage, Vec<&'a str>)> {
+    dms.iter()
+        .filter(|dm| account_id.is_none_or(|id| dm.sender_id != id))
+        .map(|dm| (dm, linked_status_ids(dm)))
+        .filter(|(_, ids)| !ids.is_empty())
+        .collect()
+}
+
+/// Pair each received link with the tweet it points at, newest message first.
+///
+/// `tweets` holds your tweets for the linked IDs; links to anyone else's
+/// tweets match none of them and are dropped.
+#[must_use]
+pub fn dm_quotes(links: &[(&DirectMessage, Vec<&str>)], tweets: &[Tweet]) -> Vec<DmQuote> {
+    let tweets: HashMap<&str, &Tweet> = tweets.iter().map(|t| (t.id.as_str(), t)).collect();
+    let mut quotes: Vec<DmQuote> = links
+        .iter()
+        .flat_map(|(dm, ids)| {
+            ids.iter().filter_map(|id| {
+                tweets.get(*id).map(|tweet| DmQuote {
+                    tweet: (*tweet).clone(),
+                    message_id: dm.id.clone(),
+                    conversation_id: dm.conversation_id.clone(),
+                    sender_id: dm.sender_id.clone(),
+                    sent_at: dm.created_at,
+                })
+            })
+        })
+        .collect();
+    quotes.sort_by_key(|q| std::cmp::Reverse(q.sent_at));
+    quotes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TweetUrl;
+    use chrono::TimeZone;
+
+    fn tweet(id: &str) -> Tweet {
+        Tweet::fixture(id, &format!("tweet {id}"))
+    }
+
+    fn dm(id: &str, sender: &str, text: &str, expanded: Option<&str>, day: u32) -> DirectMessage {
+        DirectMessage {
+            id: id.to_string(),
+            conversation_id: "1-2".to_string(),
+            sender_id: sender.to_string(),
+            recipient_id: "1".to_string(),
+            text: text.to_string(),
+            created_at: Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            urls: expanded
+                .map(|url| TweetUrl {
+                    url: "https://t.co/x".to_string(),
+                    expanded_url: Some(url.to_string()),
+                    display_url: None,
+                })
+                .into_iter()
+                .collect(),
+            media_urls: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn status_ids_finds_every_link_form() {
+        assert_eq!(
+            status_ids("see https://x.com/me/status/123?s=20 and twitter.com/i/web/status/456"),
+            vec!["123", "456"]
+        );
+        assert_eq!(
+            status_ids("https://twitter.com/me/statuses/789/"),
+            vec!["789"]
+        );
+        assert!(status_ids("https://x.com/me/status/ and /status/abc").is_empty());
+    }
+
+    #[test]
+    fn received_links_skip_own_messages_and_duplicates() {
+        let dms = vec![
+            dm(
+                "m1",
+                "2",
+                "https://x.com/me/status/10",
+                Some("https://x.com/me/status/10"),
+                1,
+            ),
+            dm("m2", "1", "https://x.com/me/status/11", None, 2),
+            dm("m3", "2", "no links here", None, 3),
+        ];
+
+        let links = received_links(Some("1"), &dms);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].0.id, "m1");
+        assert_eq!(links[0].1, vec!["10"]);
+
+        assert_eq!(received_links(None, &dms).len(), 2);
+    }
+
+    #[test]
+    fn dm_quotes_keep_only_known_tweets_newest_first() {
+        let dms = vec![
+            dm("m1", "2", "https://x.com/me/status/10", None, 1),
+            dm("m2", "3", "https://x.com/other/status/99", None, 2),
+            dm("m3", "3", "again https://x.com/me/status/10", None, 3),
+        ];
+        let links = received_links(Some("1"), &dms);
+        let quotes = dm_quotes(&links, &[tweet("10")]);
+        let ids: Vec<&str> = quotes.iter().map(|q| q.message_id.as_str()).collect();
+        assert_eq!(ids, vec!["m3", "m1"]);
+        assert!(quotes.iter().all(|q| q.tweet.id == "10"));
+    }
+}
@@ -3,20 +3,169 @@
 //! Handles parsing the JavaScript-wrapped JSON format used in X data exports.
 //! Files are formatted as: `window.YTD.<datatype>.part0 = [...]`
 
+#[cfg(feature = "native")]
+use crate::import::long_path;
 use crate::model::{
-    Account, ArchiveInfo, Block, DirectMessage, DmConversation, Follower, Following, GrokMessage,
-    Like, Mute, Profile, Tweet, TweetMedia, TweetUrl, UserMention,
+    Account, ArchiveInfo, Block, Bookmark, CommunityNote, DirectMessage, DmConversation, Follower,
+    Following, GrokMessage, Like, ListRelation, Mute, PollOption, Profile, RawKind, RawRecord,
+    ScreenNameChange, Tweet, TweetGeo, TweetMedia, TweetPoll, TweetUrl, UserList, UserMention,
 };
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use glob::glob;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use rayon::prelude::*;
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::info;
 use walkdir::WalkDir;
 
+/// Archives are only read from disk natively; elsewhere paths are used as given.
+#[cfg(not(feature = "native"))]
+fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// Extract the JSON from a JS-wrapped archive file.
+///
+/// # Errors
+///
+/// Returns an error if there is no `=` or the JSON after it is invalid.
+pub fn parse_js_data(content: &str) -> Result<Value> {
+    // Format: window.YTD.<type>.part<n> = [...]
+    // Extract everything after the first '=' and trim whitespace/semicolon.
+    let mut parts = content.splitn(2, '=');
+    let _prefix = parts
+        .next()
+        .context("Invalid JS file format: missing prefix")?;
+    let json_part = parts
+        .next()
+        .context("Invalid JS file format: no '=' found")?;
+
+    let mut json_str = json_part.trim();
+    if let Some(stripped) = json_str.strip_suffix(';') {
+        json_str = stripped.trim_end();
+    }
+
+    serde_json::from_str(json_str).context("Failed to parse JSON from JS file")
+}
+
+/// Month names in the languages X renders archives in, lowercased and
+/// without trailing dots.
+const MONTH_NAMES: &[(&str, u32)] = &[
+    ("jan", 1),
+    ("janv", 1),
+    ("ene", 1),
+    ("gen", 1),
+    ("feb", 2),
+    ("fév", 2),
+    ("févr", 2),
+    ("fev", 2),
+    ("mar", 3),
+    ("mars", 3),
+    ("mär", 3),
+    ("mrz", 3),
+    ("apr", 4),
+    ("avr", 4),
+    ("abr", 4),
+    ("may", 5),
+    ("mai", 5),
+    ("mag", 5),
+    ("jun", 6),
+    ("juin", 6),
+    ("giu", 6),
+    ("jul", 7),
+    ("juil", 7),
+    ("lug", 7),
+    ("aug", 8),
+    ("août", 8),
+    ("aout", 8),
+    ("ago", 8),
+    ("sep", 9),
+    ("sept", 9),
+    ("set", 9),
+    ("oct", 10),
+    ("okt", 10),
+    ("out", 10),
+    ("ott", 10),
+    ("nov", 11),
+    ("dec", 12),
+    ("déc", 12),
+    ("dez", 12),
+    ("dic", 12),
+];
+
+fn month_number(token: &str) -> Option<u32> {
+    let token = token.trim_end_matches(['.', ',']).to_lowercase();
+    MONTH_NAMES
+        .iter()
+        .find(|(name, _)| *name == token)
+        .map(|(_, month)| *month)
+}
+
+/// `YYYY-MM-DD[ HH:MM:SS]` without an offset, read as UTC.
+fn parse_naive_utc(value: &str) -> Option<DateTime<Utc>> {
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .map(|naive| naive.and_utc())
+}
+
+/// Unix timestamps in seconds (10 digits) or milliseconds (13 digits).
+fn parse_unix_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let number: i64 = value.parse().ok()?;
+    match value.len() {
+        10 => DateTime::from_timestamp(number, 0),
+        13 => DateTime::from_timestamp_millis(number),
+        _ => None,
+    }
+}
+
+/// X's `Wed Oct 10 20:19:24 +0000 2018` layout with the weekday missing or
+/// in any language, the month in one of [`MONTH_NAMES`], the day before or
+/// after the month, and the offset missing (UTC) or spelled `UTC`/`GMT`.
+fn parse_localized_x_date(value: &str) -> Option<DateTime<Utc>> {
+    let mut tokens: Vec<&str> = value.split(' ').collect();
+    // Weekday names carry no information and vary by locale
+    if tokens.len() > 4
+        && month_number(tokens[0]).is_none()
+        && tokens[0]
+            .chars()
+            .all(|c| c.is_alphabetic() || c == '.' || c == ',')
+    {
+        tokens.remove(0);
+    }
+    let (month, day, time, rest) = match tokens.as_slice() {
+        [first, second, time, rest @ ..] => match (month_number(first), month_number(second)) {
+            (Some(month), None) => (month, *second, *time, rest),
+            (None, Some(month)) => (month, *first, *time, rest),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let day: u32 = day.trim_end_matches(['.', ',']).parse().ok()?;
+    let (offset, year) = match rest {
+        [offset, year] => (*offset, *year),
+        [year] => ("+0000", *year),
+        _ => return None,
+    };
+    let offset = match offset {
+        "UTC" | "GMT" | "Z" => "+0000",
+        other => other,
+    };
+    let normalized = format!("{year}-{month:02}-{day:02} {time} {offset}");
+    DateTime::parse_from_str(&normalized, "%Y-%m-%d %H:%M:%S %z")
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
 /// Parser for X archive data
 pub struct ArchiveParser {
     archive_path: std::path::PathBuf,
@@ -32,27 +181,17 @@ impl ArchiveParser {
     /// Parse the JavaScript file format and extract JSON
     #[allow(clippy::unused_self)]
     fn parse_js_file(&self, content: &str) -> Result<Value> {
-        // Format: window.YTD.<type>.part<n> = [...]
-        // Extract everything after the first '=' and trim whitespace/semicolon.
-        let mut parts = content.splitn(2, '=');
-        let _prefix = parts
-            .next()
-            .context("Invalid JS file format: missing prefix")?;
-        let json_part = parts
-            .next()
-            .context("Invalid JS file format: no '=' found")?;
-
-        let mut json_str = json_part.trim();
-        if let Some(stripped) = json_str.strip_suffix(';') {
-            json_str = stripped.trim_end();
-        }
+        parse_js_data(content)
+    }
 
-        serde_json::from_str(json_str).context("Failed to parse JSON from JS file")
+    /// Path of a file in the archive's `data` directory.
+    fn data_path(&self, filename: &str) -> PathBuf {
+        long_path(&self.archive_path.join("data").join(filename))
     }
 
     /// Read and parse a JS data file
     fn read_data_file(&self, filename: &str) -> Result<Value> {
-        let path = self.archive_path.join("data").join(filename);
+        let path = self.data_path(filename);
         if !path.exists() {
             return Ok(Value::Array(vec![]));
         }
@@ -63,7 +202,7 @@ impl ArchiveParser {
 
     /// Read and parse a required JS data file.
     fn read_required_data_file(&self, filename: &str) -> Result<Value> {
-        let path = self.archive_path.join("data").join(filename);
+        let path = self.data_path(filename);
         if !path.exists() {
             anyhow::bail!("Required archive file missing: {}", path.display());
         }
@@ -87,6 +226,31 @@ impl ArchiveParser {
             .map(|dt| dt.with_timezone(&Utc))
     }
 
+    /// Parse a date from any archive file, whatever layout it was written in.
+    ///
+    /// Besides X's usual `Wed Oct 10 20:19:24 +0000 2018` and ISO 8601, this
+    /// accepts RFC 2822, `YYYY-MM-DD HH:MM:SS` (taken as UTC), Unix
+    /// timestamps in seconds or milliseconds, and the X layout with extra
+    /// spaces, no offset, or a weekday and month in another language. The Unix
+    /// epoch itself is treated as missing, since exports use it as a
+    /// placeholder.
+    #[must_use]
+    pub fn parse_date(date_str: &str) -> Option<DateTime<Utc>> {
+        let value = date_str.split_whitespace().collect::<Vec<_>>().join(" ");
+        let parsed = Self::parse_x_date(&value)
+            .or_else(|| Self::parse_iso_date(&value))
+            .or_else(|| {
+                DateTime::parse_from_rfc2822(&value)
+                    .or_else(|_| DateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S%.f %z"))
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc))
+            })
+            .or_else(|| parse_naive_utc(&value))
+            .or_else(|| parse_unix_timestamp(&value))
+            .or_else(|| parse_localized_x_date(&value))?;
+        (parsed.timestamp() != 0).then_some(parsed)
+    }
+
     /// Parse archive metadata from manifest.js.
     ///
     /// # Errors
@@ -114,7 +278,7 @@ impl ArchiveParser {
             archive_size_bytes: Self::parse_i64(&archive_info["sizeBytes"]).unwrap_or(0),
             generation_date: archive_info["generationDate"]
                 .as_str()
-                .and_then(Self::parse_iso_date)
+                .and_then(Self::parse_date)
                 .unwrap_or_else(|| DateTime::<Utc>::from_timestamp(0, 0).unwrap_or_else(Utc::now)),
             is_partial: archive_info["isPartialArchive"].as_bool().unwrap_or(false),
         })
@@ -128,13 +292,7 @@ impl ArchiveParser {
     pub fn parse_tweets(&self) -> Result<Vec<Tweet>> {
         info!("Parsing tweets...");
 
-        let mut files = Vec::new();
-        let tweets_path = self.archive_path.join("data").join("tweets.js");
-        if tweets_path.exists() {
-            files.push(tweets_path);
-        }
-        files.extend(self.collect_data_files("tweets-part*.js")?);
-
+        let files = self.source_files(RawKind::Tweet)?;
         if files.is_empty() {
             info!("No tweet files found.");
             return Ok(Vec::new());
@@ -146,51 +304,7 @@ impl ArchiveParser {
         for path in files {
             let content = std::fs::read_to_string(&path)
                 .with_context(|| format!("Failed to read {}", path.display()))?;
-            let data = self.parse_js_file(&content)?;
-            let Some(items) = data.as_array() else {
-                continue;
-            };
-
-            let file_tweets: Vec<Tweet> = items
-                .par_iter()
-                .filter_map(|item| {
-                    let tweet = &item["tweet"];
-                    Some(Tweet {
-                        id: tweet["id_str"].as_str()?.to_string(),
-                        created_at: tweet["created_at"].as_str().and_then(Self::parse_x_date)?,
-                        full_text: tweet["full_text"].as_str()?.to_string(),
-                        source: tweet["source"].as_str().map(|s| {
-                            // Extract text from HTML anchor tag
-                            s.split('>')
-                                .nth(1)
-                                .and_then(|s| s.split('<').next())
-                                .unwrap_or(s)
-                                .to_string()
-                        }),
-                        favorite_count: Self::parse_i64(&tweet["favorite_count"]).unwrap_or(0),
-                        retweet_count: Self::parse_i64(&tweet["retweet_count"]).unwrap_or(0),
-                        lang: tweet["lang"].as_str().map(String::from),
-                        in_reply_to_status_id: tweet["in_reply_to_status_id_str"]
-                            .as_str()
-                            .map(String::from),
-                        in_reply_to_user_id: tweet["in_reply_to_user_id_str"]
-                            .as_str()
-                            .map(String::from),
-                        in_reply_to_screen_name: tweet["in_reply_to_screen_name"]
-                            .as_str()
-                            .map(String::from),
-                        is_retweet: tweet["retweeted"].as_bool().unwrap_or(false),
-                        hashtags: Self::parse_hashtags(&tweet["entities"]["hashtags"]),
-                        user_mentions: Self::parse_user_mentions(
-                            &tweet["entities"]["user_mentions"],
-                        ),
-                        urls: Self::parse_urls(&tweet["entities"]["urls"]),
-                        media: Self::parse_media(&tweet["entities"]["media"]),
-                    })
-                })
-                .collect();
-
-            for tweet in file_tweets {
+            for tweet in Self::tweets_from_js(&content)? {
                 if seen_ids.insert(tweet.id.clone()) {
                     tweets.push(tweet);
                 }
@@ -201,6 +315,69 @@ impl ArchiveParser {
         Ok(tweets)
     }
 
+    /// Parse the tweets in one `tweets.js` file's content.
+    ///
+    /// Unlike [`Self::parse_tweets`], this reads nothing from disk, so it
+    /// also works where archive files arrive as text (e.g. a browser upload).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is not a JS-wrapped JSON file.
+    pub fn tweets_from_js(content: &str) -> Result<Vec<Tweet>> {
+        let data = parse_js_data(content)?;
+        let Some(items) = data.as_array() else {
+            return Ok(Vec::new());
+        };
+
+        Ok(items
+            .par_iter()
+            .filter_map(|item| {
+                let tweet = &item["tweet"];
+                Some(Tweet {
+                    id: tweet["id_str"].as_str()?.to_string(),
+                    created_at: tweet["created_at"].as_str().and_then(Self::parse_date)?,
+                    full_text: tweet["full_text"].as_str()?.to_string(),
+                    source: tweet["source"].as_str().map(|s| {
+                        // Extract text from HTML anchor tag
+                        s.split('>')
+                            .nth(1)
+                            .and_then(|s| s.split('<').next())
+                            .unwrap_or(s)
+                            .to_string()
+                    }),
+                    favorite_count: Self::parse_i64(&tweet["favorite_count"]).unwrap_or(0),
+                    retweet_count: Self::parse_i64(&tweet["retweet_count"]).unwrap_or(0),
+                    lang: tweet["lang"].as_str().map(String::from),
+                    in_reply_to_status_id: Self::parse_id(
+                        &tweet["in_reply_to_status_id_str"],
+                        &tweet["in_reply_to_status_id"],
+                    ),
+                    in_reply_to_user_id: Self::parse_id(
+                        &tweet["in_reply_to_user_id_str"],
+                        &tweet["in_reply_to_user_id"],
+                    ),
+                    in_reply_to_screen_name: tweet["in_reply_to_screen_name"]
+                        .as_str()
+                        .map(String::from),
+                    is_retweet: tweet["retweeted"].as_bool().unwrap_or(false),
+                    hashtags: Self::parse_hashtags(&tweet["entities"]["hashtags"]),
+                    user_mentions: Self::parse_user_mentions(&tweet["entities"]["user_mentions"]),
+                    urls: Self::parse_urls(&tweet["entities"]["urls"]),
+                    // `entities` lists only the first photo of a multi-photo tweet
+                    media: Self::parse_media(if tweet["extended_entities"]["media"].is_array() {
+                        &tweet["extended_entities"]["media"]
+                    } else {
+                        &tweet["entities"]["media"]
+                    }),
+                    geo: Self::parse_geo(tweet),
+                    poll: Self::parse_poll(&tweet["card"]),
+                    reply_count: Self::parse_i64(&tweet["reply_count"]),
+                    quote_count: Self::parse_i64(&tweet["quote_count"]),
+                })
+            })
+            .collect())
+    }
+
     fn parse_hashtags(value: &Value) -> Vec<String> {
         Self::as_array_or_empty(value)
             .iter()
@@ -246,15 +423,119 @@ impl ArchiveParser {
                         .or_else(|| m["media_url"].as_str())?
                         .to_string(),
                     local_path: None,
+                    alt_text: m["ext_alt_text"]
+                        .as_str()
+                        .map(str::trim)
+                        .filter(|alt| !alt.is_empty())
+                        .map(String::from),
                 })
             })
             .collect()
     }
 
+    /// Read the location of a geotagged tweet.
+    ///
+    /// `geo` holds `[lat, lon]` and `coordinates` holds `[lon, lat]`, both
+    /// only when an exact point was shared; otherwise the centre of the
+    /// place's bounding box stands in.
+    fn parse_geo(tweet: &Value) -> Option<TweetGeo> {
+        let point = |value: &Value| -> Option<(f64, f64)> {
+            let pair = Self::as_array_or_empty(value);
+            Some((
+                Self::parse_f64(pair.first()?)?,
+                Self::parse_f64(pair.get(1)?)?,
+            ))
+        };
+        let place = &tweet["place"];
+        let coordinates = point(&tweet["geo"]["coordinates"])
+            .or_else(|| point(&tweet["coordinates"]["coordinates"]).map(|(lon, lat)| (lat, lon)))
+            .or_else(|| {
+                let corners: Vec<(f64, f64)> =
+                    Self::as_array_or_empty(&place["bounding_box"]["coordinates"])
+                        .iter()
+                        .flat_map(Self::as_array_or_empty)
+                        .filter_map(point)
+                        .collect();
+                #[allow(clippy::cast_precision_loss)]
+                let n = corners.len() as f64;
+                (!corners.is_empty()).then(|| {
+                    let (lon, lat) = corners
+                        .iter()
+                        .fold((0.0, 0.0), |(x, y), (lon, lat)| (x + lon, y + lat));
+                    (lat / n, lon / n)
+                })
+            });
+        let name = place["full_name"]
+            .as_str()
+            .or_else(|| place["name"].as_str())
+            .filter(|name| !name.is_empty())
+            .map(String::from);
+        if name.is_none() && coordinates.is_none() {
+            return None;
+        }
+        Some(TweetGeo {
+            place: name,
+            country: place["country"]
+                .as_str()
+                .filter(|country| !country.is_empty())
+                .map(String::from),
+            latitude: coordinates.map(|(lat, _)| lat),
+            longitude: coordinates.map(|(_, lon)| lon),
+        })
+    }
+
+    /// Read a poll card.
+    ///
+    /// `binding_values` is either an object keyed by name or, in newer
+    /// exports, a list of `{"key", "value"}` pairs. Polls have up to four
+    /// `choiceN_label` / `choiceN_count` bindings.
+    fn parse_poll(card: &Value) -> Option<TweetPoll> {
+        if !card["name"].as_str()?.starts_with("poll") {
+            return None;
+        }
+        let bindings = &card["binding_values"];
+        let binding = |key: &str| -> Option<&Value> {
+            match bindings {
+                Value::Array(pairs) => pairs
+                    .iter()
+                    .find(|pair| pair["key"] == key)
+                    .map(|pair| &pair["value"]),
+                _ => bindings.get(key),
+            }
+        };
+        let string = |key: &str| binding(key).and_then(|v| v["string_value"].as_str());
+
+        let options: Vec<PollOption> = (1..=4)
+            .map_while(|n| {
+                Some(PollOption {
+                    label: string(&format!("choice{n}_label"))?.to_string(),
+                    votes: string(&format!("choice{n}_count")).and_then(|c| c.parse().ok()),
+                })
+            })
+            .collect();
+        if options.is_empty() {
+            return None;
+        }
+        Some(TweetPoll {
+            options,
+            end_datetime: string("end_datetime_utc").and_then(Self::parse_date),
+            counts_are_final: binding("counts_are_final")
+                .and_then(|v| v["boolean_value"].as_bool())
+                .unwrap_or(false),
+        })
+    }
+
     fn as_array_or_empty(value: &Value) -> &[Value] {
         value.as_array().map_or(&[], |items| items.as_slice())
     }
 
+    fn parse_f64(value: &Value) -> Option<f64> {
+        value
+            .as_f64()
+            .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+            .filter(|n| n.is_finite())
+    }
+
     fn parse_i64(value: &Value) -> Option<i64> {
         if let Some(n) = value.as_i64() {
             return Some(n);
@@ -262,6 +543,18 @@ impl ArchiveParser {
         value.as_str().and_then(|s| s.parse().ok())
     }
 
+    /// Read an ID from its `_str` field, falling back to the numeric field.
+    ///
+    /// The numeric form may have lost precision in the export;
+    /// `xf doctor --fix-threads` re-links self-replies affected by that.
+    fn parse_id(string_value: &Value, value: &Value) -> Option<String> {
+        string_value
+            .as_str()
+            .or_else(|| value.as_str())
+            .map(String::from)
+            .or_else(|| value.as_number().map(ToString::to_string))
+    }
+
     /// Parse all likes from like.js or likes.js.
     ///
     /// # Errors
@@ -270,16 +563,7 @@ impl ArchiveParser {
     pub fn parse_likes(&self) -> Result<Vec<Like>> {
         info!("Parsing likes...");
 
-        let mut files = Vec::new();
-        let like_path = self.archive_path.join("data").join("like.js");
-        if like_path.exists() {
-            files.push(like_path);
-        }
-        let likes_path = self.archive_path.join("data").join("likes.js");
-        if likes_path.exists() {
-            files.push(likes_path);
-        }
-
+        let files = self.source_files(RawKind::Like)?;
         if files.is_empty() {
             info!("No like files found.");
             return Ok(Vec::new());
@@ -291,24 +575,7 @@ impl ArchiveParser {
         for path in files {
             let content = std::fs::read_to_string(&path)
                 .with_context(|| format!("Failed to read {}", path.display()))?;
-            let data = self.parse_js_file(&content)?;
-            let Some(items) = data.as_array() else {
-                continue;
-            };
-
-            let file_likes: Vec<Like> = items
-                .par_iter()
-                .filter_map(|item| {
-                    let like = &item["like"];
-                    Some(Like {
-                        tweet_id: like["tweetId"].as_str()?.to_string(),
-                        full_text: like["fullText"].as_str().map(String::from),
-                        expanded_url: like["expandedUrl"].as_str().map(String::from),
-                    })
-                })
-                .collect();
-
-            for like in file_likes {
+            for like in Self::likes_from_js(&content)? {
                 if seen_ids.insert(like.tweet_id.clone()) {
                     likes.push(like);
                 }
@@ -319,6 +586,60 @@ impl ArchiveParser {
         Ok(likes)
     }
 
+    /// Parse the likes in one `like.js` file's content, without disk access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is not a JS-wrapped JSON file.
+    pub fn likes_from_js(content: &str) -> Result<Vec<Like>> {
+        let data = parse_js_data(content)?;
+        let Some(items) = data.as_array() else {
+            return Ok(Vec::new());
+        };
+
+        Ok(items
+            .par_iter()
+            .filter_map(|item| {
+                let like = &item["like"];
+                Some(Like {
+                    tweet_id: like["tweetId"].as_str()?.to_string(),
+                    full_text: like["fullText"].as_str().map(String::from),
+                    expanded_url: like["expandedUrl"].as_str().map(String::from),
+                })
+            })
+            .collect())
+    }
+
+    /// Parse bookmarked tweets from bookmark.js.
+    ///
+    /// Entries carry the tweet ID and, in newer archives, the tweet text and
+    /// link like `like.js` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bookmarks file cannot be read or parsed.
+    pub fn parse_bookmarks(&self) -> Result<Vec<Bookmark>> {
+        info!("Parsing bookmark.js...");
+        let data = self.read_data_file("bookmark.js")?;
+
+        let mut seen_ids: HashSet<String> = HashSet::new();
+        let bookmarks: Vec<Bookmark> = Self::as_array_or_empty(&data)
+            .iter()
+            .filter_map(|item| {
+                let b = &item["bookmark"];
+                Some(Bookmark {
+                    tweet_id: b["tweetId"].as_str()?.to_string(),
+                    full_text: b["fullText"].as_str().map(String::from),
+                    expanded_url: b["expandedUrl"].as_str().map(String::from),
+                })
+            })
+            .filter(|bookmark| seen_ids.insert(bookmark.tweet_id.clone()))
+            .collect();
+
+        info!("Parsed {} bookmarks", bookmarks.len());
+        Ok(bookmarks)
+    }
+
     /// Parse direct messages from direct-messages.js.
     ///
     /// # Errors
@@ -327,13 +648,7 @@ impl ArchiveParser {
     pub fn parse_direct_messages(&self) -> Result<Vec<DmConversation>> {
         info!("Parsing direct messages...");
 
-        let mut files = Vec::new();
-        let dm_path = self.archive_path.join("data").join("direct-messages.js");
-        if dm_path.exists() {
-            files.push(dm_path);
-        }
-        files.extend(self.collect_data_files("direct-messages-group*.js")?);
-
+        let files = self.source_files(RawKind::DirectMessage)?;
         if files.is_empty() {
             info!("No direct message files found.");
             return Ok(Vec::new());
@@ -345,69 +660,12 @@ impl ArchiveParser {
         for path in files {
             let content = std::fs::read_to_string(&path)
                 .with_context(|| format!("Failed to read {}", path.display()))?;
-            let data = self.parse_js_file(&content)?;
-            let Some(items) = data.as_array() else {
-                continue;
-            };
-
-            for item in items {
-                let conv = &item["dmConversation"];
-                let Some(conversation_id) = conv["conversationId"].as_str().map(String::from)
-                else {
-                    continue;
-                };
-
-                if let Some(messages) = conv["messages"].as_array() {
-                    for msg in messages {
-                        let mc = &msg["messageCreate"];
-                        let Some(id) = mc["id"].as_str().map(String::from) else {
-                            continue;
-                        };
-                        if !seen_ids.insert(id.clone()) {
-                            continue;
-                        }
-
-                        let Some(sender_id) = mc["senderId"].as_str().map(String::from) else {
-                            continue;
-                        };
-                        let Some(recipient_id) = mc["recipientId"].as_str().map(String::from)
-                        else {
-                            continue;
-                        };
-                        let Some(text) = mc["text"].as_str().map(String::from) else {
-                            continue;
-                        };
-                        let Some(created_at) =
-                            mc["createdAt"].as_str().and_then(Self::parse_iso_date)
-                        else {
-                            continue;
-                        };
-
-                        let media_urls = mc["mediaUrls"]
-                            .as_array()
-                            .map(|arr| {
-                                arr.iter()
-                                    .filter_map(|u| u.as_str().map(String::from))
-                                    .collect()
-                            })
-                            .unwrap_or_default();
-
-                        let dm = DirectMessage {
-                            id,
-                            conversation_id: conversation_id.clone(),
-                            sender_id,
-                            recipient_id,
-                            text,
-                            created_at,
-                            urls: Self::parse_dm_urls(&mc["urls"]),
-                            media_urls,
-                        };
-
-                        conversations
-                            .entry(conversation_id.clone())
-                            .or_default()
-                            .push(dm);
-                    }
+            for dm in Self::direct_messages_from_js(&content)? {
+                if seen_ids.insert(dm.id.clone()) {
+                    conversations
+                        .entry(dm.conversation_id.clone())
+                        .or_default()
+                        .push(dm);
                 }
             }
         }
@@ -434,6 +692,60 @@ impl ArchiveParser {
         Ok(output)
     }
 
+    /// Parse the messages in one `direct-messages.js` file's content,
+    /// without disk access. Messages are in file order, not grouped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is not a JS-wrapped JSON file.
+    pub fn direct_messages_from_js(content: &str) -> Result<Vec<DirectMessage>> {
+        let data = parse_js_data(content)?;
+        let Some(items) = data.as_array() else {
+            return Ok(Vec::new());
+        };
+
+        let mut dms = Vec::new();
+        for item in items {
+            let conv = &item["dmConversation"];
+            let Some(conversation_id) = conv["conversationId"].as_str() else {
+                continue;
+            };
+            for msg in conv["messages"].as_array().into_iter().flatten() {
+                let mc = &msg["messageCreate"];
+                let (Some(id), Some(sender_id), Some(recipient_id), Some(text), Some(created_at)) = (
+                    mc["id"].as_str(),
+                    mc["senderId"].as_str(),
+                    mc["recipientId"].as_str(),
+                    mc["text"].as_str(),
+                    mc["createdAt"].as_str().and_then(Self::parse_date),
+                ) else {
+                    continue;
+                };
+
+                let media_urls = mc["mediaUrls"]
+                    .as_array()
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|u| u.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                dms.push(DirectMessage {
+                    id: id.to_string(),
+                    conversation_id: conversation_id.to_string(),
+                    sender_id: sender_id.to_string(),
+                    recipient_id: recipient_id.to_string(),
+                    text: text.to_string(),
+                    created_at,
+                    urls: Self::parse_dm_urls(&mc["urls"]),
+                    media_urls,
+                });
+            }
+        }
+        Ok(dms)
+    }
+
     fn parse_dm_urls(value: &Value) -> Vec<TweetUrl> {
         Self::as_array_or_empty(value)
             .iter()
@@ -543,6 +855,41 @@ impl ArchiveParser {
         Ok(mutes)
     }
 
+    /// Parse lists from lists-created.js, lists-member.js, and
+    /// lists-subscribed.js.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a lists file cannot be read or parsed.
+    pub fn parse_lists(&self) -> Result<Vec<UserList>> {
+        info!("Parsing lists...");
+        let mut lists = Vec::new();
+        for (file, relation) in [
+            ("lists-created.js", ListRelation::Created),
+            ("lists-member.js", ListRelation::Member),
+            ("lists-subscribed.js", ListRelation::Subscribed),
+        ] {
+            let data = self.read_data_file(file)?;
+            lists.extend(Self::as_array_or_empty(&data).iter().filter_map(|item| {
+                let url = item["userListInfo"]["url"].as_str()?.trim();
+                let list_id = url
+                    .rsplit_once("/lists/")?
+                    .1
+                    .split(['/', '?'])
+                    .next()
+                    .filter(|id| !id.is_empty())?;
+                Some(UserList {
+                    list_id: list_id.to_string(),
+                    url: url.to_string(),
+                    relation,
+                })
+            }));
+        }
+
+        info!("Parsed {} lists", lists.len());
+        Ok(lists)
+    }
+
     /// Parse account info from account.js.
     ///
     /// # Errors
@@ -559,7 +906,7 @@ impl ArchiveParser {
                 username: a["username"].as_str().unwrap_or_default().to_string(),
                 display_name: a["accountDisplayName"].as_str().map(String::from),
                 email: a["email"].as_str().map(String::from),
-                created_at: a["createdAt"].as_str().and_then(Self::parse_iso_date),
+                created_at: a["createdAt"].as_str().and_then(Self::parse_date),
                 created_via: a["createdVia"].as_str().map(String::from),
             }
         });
@@ -591,6 +938,74 @@ impl ArchiveParser {
         Ok(profile)
     }
 
+    /// Parse handle changes from screen-name-change.js.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the screen name change file cannot be read or parsed.
+    pub fn parse_screen_name_changes(&self) -> Result<Vec<ScreenNameChange>> {
+        info!("Parsing screen-name-change.js...");
+        let data = self.read_data_file("screen-name-change.js")?;
+
+        let changes: Vec<ScreenNameChange> = Self::as_array_or_empty(&data)
+            .iter()
+            .filter_map(|item| {
+                let entry = &item["screenNameChange"];
+                let change = &entry["screenNameChange"];
+                Some(ScreenNameChange {
+                    account_id: entry["accountId"].as_str()?.to_string(),
+                    changed_at: change["changedAt"].as_str().and_then(Self::parse_date)?,
+                    changed_from: change["changedFrom"].as_str()?.to_string(),
+                    changed_to: change["changedTo"].as_str()?.to_string(),
+                })
+            })
+            .collect();
+
+        info!("Parsed {} screen name changes", changes.len());
+        Ok(changes)
+    }
+
+    /// Parse Community Notes you wrote from community-note.js.
+    ///
+    /// Entries without an ID or text are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the community notes file cannot be read or parsed.
+    pub fn parse_community_notes(&self) -> Result<Vec<CommunityNote>> {
+        info!("Parsing community-note.js...");
+        let data = self.read_data_file("community-note.js")?;
+
+        let notes: Vec<CommunityNote> = Self::as_array_or_empty(&data)
+            .iter()
+            .filter_map(|item| {
+                // Older exports use the Birdwatch name
+                let note = item
+                    .get("communityNote")
+                    .or_else(|| item.get("birdwatchNote"))?;
+                let summary = &note["summary"];
+                let text = summary["text"]
+                    .as_str()
+                    .or_else(|| summary.as_str())
+                    .or_else(|| note["text"].as_str())?
+                    .trim();
+                if text.is_empty() {
+                    return None;
+                }
+                Some(CommunityNote {
+                    id: Self::parse_id(&note["noteId"], &note["id"])?,
+                    tweet_id: Self::parse_id(&note["tweetId"], &note["tweet_id"]),
+                    text: text.to_string(),
+                    classification: note["classification"].as_str().map(String::from),
+                    created_at: note["createdAt"].as_str().and_then(Self::parse_date),
+                })
+            })
+            .collect();
+
+        info!("Parsed {} community notes", notes.len());
+        Ok(notes)
+    }
+
     /// Parse Grok chat messages from grok-chat-item.js.
     ///
     /// # Errors
@@ -608,7 +1023,7 @@ impl ArchiveParser {
                     chat_id: g["chatId"].as_str()?.to_string(),
                     message: g["message"].as_str()?.to_string(),
                     sender: g["sender"].as_str().unwrap_or("unknown").to_string(),
-                    created_at: g["createdAt"].as_str().and_then(Self::parse_iso_date)?,
+                    created_at: g["createdAt"].as_str().and_then(Self::parse_date)?,
                     grok_mode: g["grokMode"].as_str().map(String::from),
                 })
             })
@@ -624,7 +1039,7 @@ impl ArchiveParser {
     ///
     /// Returns an error if the archive directory cannot be read.
     pub fn list_data_files(&self) -> Result<Vec<String>> {
-        let data_path = self.archive_path.join("data");
+        let data_path = long_path(&self.archive_path.join("data"));
         let mut files = Vec::new();
 
         for entry in WalkDir::new(&data_path).max_depth(1) {
@@ -645,15 +1060,109 @@ impl ArchiveParser {
         Ok(files)
     }
 
-    fn collect_data_files(&self, pattern: &str) -> Result<Vec<std::path::PathBuf>> {
-        let data_dir = self.archive_path.join("data");
-        // Escape the directory path to safely handle characters like [, ], * in the path itself
-        let escaped_dir = glob::Pattern::escape(data_dir.to_string_lossy().as_ref());
-        let full_pattern = format!("{}{}{}", escaped_dir, std::path::MAIN_SEPARATOR, pattern);
+    /// Collect each record's original JSON object for `kind`, so fields the
+    /// models don't carry (cards, edit history, coordinates, ...) survive.
+    ///
+    /// Reads the same files as the matching `parse_*` method, and like it
+    /// keeps the first occurrence of an id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a data file cannot be read or parsed.
+    pub fn parse_raw_records(&self, kind: RawKind) -> Result<Vec<RawRecord>> {
+        let mut records = Vec::new();
+        let mut seen_ids: HashSet<String> = HashSet::new();
+
+        for path in self.source_files(kind)? {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            for record in Self::raw_records_from_js(kind, &content)? {
+                if seen_ids.insert(record.id.clone()) {
+                    records.push(record);
+                }
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Extract the raw records of one data file's content, without disk access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is not a JS-wrapped JSON file.
+    pub fn raw_records_from_js(kind: RawKind, content: &str) -> Result<Vec<RawRecord>> {
+        let data = parse_js_data(content)?;
+        let Some(items) = data.as_array() else {
+            return Ok(Vec::new());
+        };
+
+        let record = |value: &Value, id_key: &str| {
+            value[id_key].as_str().map(|id| RawRecord {
+                id: id.to_string(),
+                json: value.to_string(),
+            })
+        };
+
+        Ok(match kind {
+            RawKind::Tweet => items
+                .iter()
+                .filter_map(|item| record(&item["tweet"], "id_str"))
+                .collect(),
+            RawKind::Like => items
+                .iter()
+                .filter_map(|item| record(&item["like"], "tweetId"))
+                .collect(),
+            RawKind::DirectMessage => items
+                .iter()
+                .filter_map(|item| item["dmConversation"]["messages"].as_array())
+                .flatten()
+                .filter_map(|message| record(&message["messageCreate"], "id"))
+                .collect(),
+        })
+    }
+
+    /// The data files holding records of `kind`, in parse order.
+    fn source_files(&self, kind: RawKind) -> Result<Vec<PathBuf>> {
+        let (names, pattern): (&[&str], Option<&str>) = match kind {
+            RawKind::Tweet => (&["tweets.js"], Some("tweets-part*.js")),
+            RawKind::Like => (&["like.js", "likes.js"], None),
+            RawKind::DirectMessage => (&["direct-messages.js"], Some("direct-messages-group*.js")),
+        };
+
+        let mut files: Vec<PathBuf> = names
+            .iter()
+            .map(|name| self.data_path(name))
+            .filter(|path| path.exists())
+            .collect();
+        if let Some(pattern) = pattern {
+            files.extend(self.collect_data_files(pattern)?);
+        }
+        Ok(files)
+    }
+
+    fn collect_data_files(&self, pattern: &str) -> Result<Vec<PathBuf>> {
+        let data_dir = long_path(&self.archive_path.join("data"));
+        let pattern = glob::Pattern::new(pattern)
+            .map_err(|e| anyhow::anyhow!("Invalid glob pattern: {e}"))?;
+        if !data_dir.is_dir() {
+            return Ok(Vec::new());
+        }
 
-        let mut paths: Vec<_> = glob(&full_pattern)
-            .map_err(|e| anyhow::anyhow!("Invalid glob pattern: {e}"))?
+        // Match file names only, so the archive's own path (which may not be
+        // valid UTF-8 or may contain glob metacharacters) never goes through
+        // the pattern.
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(&data_dir)
+            .with_context(|| format!("Failed to read {}", data_dir.display()))?
             .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.path().is_file())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| pattern.matches(name))
+            })
+            .map(|entry| entry.path())
             .collect();
         paths.sort();
         Ok(paths)
@@ -663,7 +1172,7 @@ impl ArchiveParser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{Datelike, Timelike};
+    use chrono::{Datelike, TimeZone, Timelike};
     use tempfile::TempDir;
 
     #[test]
@@ -707,6 +1216,47 @@ mod tests {
         assert!(date.is_none());
     }
 
+    #[test]
+    fn test_parse_date_accepts_legacy_and_localized_layouts() {
+        let expected = Utc.with_ymd_and_hms(2018, 10, 10, 20, 19, 24).unwrap();
+        for input in [
+            "Wed Oct 10 20:19:24 +0000 2018",
+            "Wed  Oct 10 20:19:24 +0000 2018",
+            "Wed Oct 10 22:19:24 +0200 2018",
+            "Oct 10 20:19:24 +0000 2018",
+            "Wed Oct 10 20:19:24 UTC 2018",
+            "Wed Oct 10 20:19:24 2018",
+            "mer. oct. 10 20:19:24 +0000 2018",
+            "Mi. 10 Okt. 20:19:24 +0000 2018",
+            "2018-10-10T20:19:24Z",
+            "2018-10-10T20:19:24.000Z",
+            "2018-10-10 20:19:24 +0000",
+            "2018-10-10 20:19:24",
+            "Wed, 10 Oct 2018 20:19:24 +0000",
+            "1539202764",
+            "1539202764000",
+        ] {
+            assert_eq!(ArchiveParser::parse_date(input), Some(expected), "{input}");
+        }
+        assert_eq!(
+            ArchiveParser::parse_date("mié dic 10 20:19:24 +0000 2018"),
+            Some(Utc.with_ymd_and_hms(2018, 12, 10, 20, 19, 24).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_date_treats_epoch_as_missing() {
+        assert_eq!(ArchiveParser::parse_date("0"), None);
+        assert_eq!(ArchiveParser::parse_date("2018"), None);
+        assert_eq!(ArchiveParser::parse_date("1970-01-01T00:00:00Z"), None);
+        assert_eq!(
+            ArchiveParser::parse_date("Thu Jan 01 00:00:00 +0000 1970"),
+            None
+        );
+        assert_eq!(ArchiveParser::parse_date("yesterday"), None);
+        assert_eq!(ArchiveParser::parse_date(""), None);
+    }
+
     #[test]
     fn test_parse_js_file_valid() {
         let temp_dir = TempDir::new().unwrap();
@@ -974,6 +1524,7 @@ mod tests {
                     "source": "<a href=\"https://x.com\">X Web App</a>",
                     "favorite_count": "42",
                     "retweet_count": "7",
+                    "quote_count": 3,
                     "lang": "en",
                     "entities": {
                         "hashtags": [{"text": "test"}],
@@ -993,6 +1544,8 @@ mod tests {
         assert_eq!(tweets[0].full_text, "Hello world! #test @mention");
         assert_eq!(tweets[0].favorite_count, 42);
         assert_eq!(tweets[0].retweet_count, 7);
+        assert_eq!(tweets[0].quote_count, Some(3));
+        assert_eq!(tweets[0].reply_count, None);
         assert_eq!(tweets[0].hashtags, vec!["test".to_string()]);
         assert_eq!(tweets[0].user_mentions.len(), 1);
         assert_eq!(tweets[0].user_mentions[0].screen_name, "mention");
@@ -1058,6 +1611,34 @@ mod tests {
         assert!(ids.contains("t2"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_tweet_parts_in_non_utf8_directory() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir
+            .path()
+            .join(std::ffi::OsStr::from_bytes(b"archive-\xff[1]"));
+        let data_dir = archive.join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let part = r#"window.YTD.tweets.part0 = [
+            {
+                "tweet": {
+                    "id_str": "t1",
+                    "created_at": "Fri Jan 10 12:00:00 +0000 2025",
+                    "full_text": "From a part file",
+                    "entities": {"hashtags": [], "user_mentions": [], "urls": []}
+                }
+            }
+        ]"#;
+        std::fs::write(data_dir.join("tweets-part1.js"), part).unwrap();
+
+        let tweets = ArchiveParser::new(&archive).parse_tweets().unwrap();
+        assert_eq!(tweets.len(), 1);
+        assert_eq!(tweets[0].id, "t1");
+    }
+
     #[test]
     fn test_parse_tweets_with_retweet() {
         let temp_dir = TempDir::new().unwrap();
@@ -1087,6 +1668,121 @@ mod tests {
         assert!(tweets[0].is_retweet);
     }
 
+    #[test]
+    fn test_parse_tweets_geo() {
+        let content = r#"window.YTD.tweets.part0 = [
+            {"tweet": {"id_str": "1", "created_at": "Fri Jan 10 12:00:00 +0000 2014",
+                "full_text": "exact point",
+                "geo": {"type": "Point", "coordinates": ["45.52", "-122.68"]},
+                "coordinates": {"type": "Point", "coordinates": ["-122.68", "45.52"]},
+                "place": {"full_name": "Portland, OR", "name": "Portland",
+                          "country": "United States"}}},
+            {"tweet": {"id_str": "2", "created_at": "Fri Jan 10 12:00:00 +0000 2014",
+                "full_text": "place only",
+                "place": {"name": "Berlin", "country": "Germany", "bounding_box":
+                    {"type": "Polygon", "coordinates": [[[13.0, 52.0], [14.0, 52.0],
+                                                         [14.0, 53.0], [13.0, 53.0]]]}}}},
+            {"tweet": {"id_str": "3", "created_at": "Fri Jan 10 12:00:00 +0000 2014",
+                "full_text": "nowhere", "geo": null, "place": null}}
+        ]"#;
+        let tweets = ArchiveParser::tweets_from_js(content).unwrap();
+        let geo = |id: &str| tweets.iter().find(|t| t.id == id).unwrap().geo.clone();
+
+        assert_eq!(
+            geo("1"),
+            Some(TweetGeo {
+                place: Some("Portland, OR".to_string()),
+                country: Some("United States".to_string()),
+                latitude: Some(45.52),
+                longitude: Some(-122.68),
+            })
+        );
+        assert_eq!(
+            geo("2"),
+            Some(TweetGeo {
+                place: Some("Berlin".to_string()),
+                country: Some("Germany".to_string()),
+                latitude: Some(52.5),
+                longitude: Some(13.5),
+            })
+        );
+        assert_eq!(geo("3"), None);
+    }
+
+    #[test]
+    fn test_parse_tweets_poll() {
+        let content = r#"window.YTD.tweets.part0 = [
+            {"tweet": {"id_str": "1", "created_at": "Fri Jan 10 12:00:00 +0000 2020",
+                "full_text": "tabs or spaces?",
+                "card": {"name": "poll2choice_text_only", "binding_values": {
+                    "choice1_label": {"type": "STRING", "string_value": "Tabs"},
+                    "choice1_count": {"type": "STRING", "string_value": "12"},
+                    "choice2_label": {"type": "STRING", "string_value": "Spaces"},
+                    "choice2_count": {"type": "STRING", "string_value": "30"},
+                    "end_datetime_utc": {"type": "STRING", "string_value": "2020-01-11T12:00:00Z"},
+                    "counts_are_final": {"type": "BOOLEAN", "boolean_value": true}}}}},
+            {"tweet": {"id_str": "2", "created_at": "Fri Jan 10 12:00:00 +0000 2020",
+                "full_text": "vim or emacs?",
+                "card": {"name": "poll2choice_text_only", "binding_values": [
+                    {"key": "choice1_label", "value": {"string_value": "vim"}},
+                    {"key": "choice2_label", "value": {"string_value": "emacs"}}]}}},
+            {"tweet": {"id_str": "3", "created_at": "Fri Jan 10 12:00:00 +0000 2020",
+                "full_text": "a link", "card": {"name": "summary_large_image"}}}
+        ]"#;
+        let tweets = ArchiveParser::tweets_from_js(content).unwrap();
+        let poll = |id: &str| tweets.iter().find(|t| t.id == id).unwrap().poll.clone();
+
+        let first = poll("1").unwrap();
+        assert_eq!(
+            first.options,
+            vec![
+                PollOption {
+                    label: "Tabs".to_string(),
+                    votes: Some(12)
+                },
+                PollOption {
+                    label: "Spaces".to_string(),
+                    votes: Some(30)
+                },
+            ]
+        );
+        assert!(first.counts_are_final);
+        assert_eq!(
+            first.end_datetime.unwrap().to_rfc3339(),
+            "2020-01-11T12:00:00+00:00"
+        );
+
+        let second = poll("2").unwrap();
+        assert_eq!(second.options[1].label, "emacs");
+        assert_eq!(second.options[1].votes, None);
+        assert!(!second.counts_are_final);
+        assert_eq!(poll("3"), None);
+    }
+
+    #[test]
+    fn test_parse_tweets_media_alt_text() {
+        let content = r#"window.YTD.tweets.part0 = [
+            {"tweet": {"id_str": "1", "created_at": "Fri Jan 10 12:00:00 +0000 2020",
+                "full_text": "two photos",
+                "entities": {"media": [{"id_str": "m1", "type": "photo",
+                    "media_url_https": "https://pbs.twimg.com/media/a.jpg"}]},
+                "extended_entities": {"media": [
+                    {"id_str": "m1", "type": "photo", "ext_alt_text": "A red bicycle",
+                     "media_url_https": "https://pbs.twimg.com/media/a.jpg"},
+                    {"id_str": "m2", "type": "photo", "ext_alt_text": " ",
+                     "media_url_https": "https://pbs.twimg.com/media/b.jpg"}]}}}
+        ]"#;
+        let tweets = ArchiveParser::tweets_from_js(content).unwrap();
+        let alt: Vec<Option<&str>> = tweets[0]
+            .media
+            .iter()
+            .map(|m| m.alt_text.as_deref())
+            .collect();
+
+        assert_eq!(alt, [Some("A red bicycle"), None]);
+        assert_eq!(tweets[0].attached_text().as_deref(), Some("A red bicycle"));
+    }
+
     #[test]
     fn test_parse_tweets_reply() {
         let temp_dir = TempDir::new().unwrap();
@@ -1120,6 +1816,37 @@ mod tests {
         assert_eq!(tweets[0].in_reply_to_screen_name, Some("user".to_string()));
     }
 
+    #[test]
+    fn test_parse_tweets_reply_numeric_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let content = r#"window.YTD.tweets.part0 = [
+            {
+                "tweet": {
+                    "id_str": "222",
+                    "created_at": "Fri Jan 10 12:00:00 +0000 2025",
+                    "full_text": "continuing the thread",
+                    "in_reply_to_status_id": 1234567890123456800,
+                    "in_reply_to_user_id": 999,
+                    "in_reply_to_screen_name": "me",
+                    "entities": {"hashtags": [], "user_mentions": [], "urls": []}
+                }
+            }
+        ]"#;
+        std::fs::write(data_dir.join("tweets.js"), content).unwrap();
+
+        let parser = ArchiveParser::new(temp_dir.path());
+        let tweets = parser.parse_tweets().unwrap();
+
+        assert_eq!(
+            tweets[0].in_reply_to_status_id.as_deref(),
+            Some("1234567890123456800")
+        );
+        assert_eq!(tweets[0].in_reply_to_user_id.as_deref(), Some("999"));
+    }
+
     #[test]
     fn test_parse_likes_full() {
         let temp_dir = TempDir::new().unwrap();
@@ -1156,6 +1883,36 @@ mod tests {
         assert_eq!(likes[1].full_text, None);
     }
 
+    #[test]
+    fn test_parse_bookmarks_dedups_by_tweet_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let content = r#"window.YTD.bookmark.part0 = [
+            {
+                "bookmark": {
+                    "tweetId": "555",
+                    "fullText": "Saved for later",
+                    "expandedUrl": "https://x.com/user/status/555"
+                }
+            },
+            { "bookmark": { "tweetId": "556" } },
+            { "bookmark": { "tweetId": "555" } },
+            { "bookmark": {} }
+        ]"#;
+        std::fs::write(data_dir.join("bookmark.js"), content).unwrap();
+
+        let parser = ArchiveParser::new(temp_dir.path());
+        let bookmarks = parser.parse_bookmarks().unwrap();
+
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].tweet_id, "555");
+        assert_eq!(bookmarks[0].full_text.as_deref(), Some("Saved for later"));
+        assert_eq!(bookmarks[1].tweet_id, "556");
+        assert_eq!(bookmarks[1].full_text, None);
+    }
+
     #[test]
     fn test_parse_likes_fallback_likes_js() {
         let temp_dir = TempDir::new().unwrap();
@@ -1185,6 +1942,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_raw_records_keeps_unmodeled_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        std::fs::write(
+            data_dir.join("tweets.js"),
+            r#"window.YTD.tweets.part0 = [
+                {"tweet": {"id_str": "1", "full_text": "first",
+                    "edit_info": {"initial": {"editTweetIds": ["1"]}},
+                    "coordinates": {"type": "Point", "coordinates": [1.5, 2.5]}}},
+                {"tweet": {"full_text": "no id"}}
+            ]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            data_dir.join("tweets-part1.js"),
+            r#"window.YTD.tweets.part1 = [
+                {"tweet": {"id_str": "1", "full_text": "duplicate"}},
+                {"tweet": {"id_str": "2", "full_text": "second"}}
+            ]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            data_dir.join("direct-messages.js"),
+            r#"window.YTD.direct_messages.part0 = [
+                {"dmConversation": {"conversationId": "a-b", "messages": [
+                    {"messageCreate": {"id": "m1", "text": "hi", "reactions": [{"reactionKey": "like"}]}}
+                ]}}
+            ]"#,
+        )
+        .unwrap();
+
+        let parser = ArchiveParser::new(temp_dir.path());
+
+        let tweets = parser.parse_raw_records(RawKind::Tweet).unwrap();
+        let ids: Vec<&str> = tweets.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        let first: Value = serde_json::from_str(&tweets[0].json).unwrap();
+        assert_eq!(first["full_text"], "first");
+        assert_eq!(first["coordinates"]["coordinates"][1], 2.5);
+        assert_eq!(first["edit_info"]["initial"]["editTweetIds"][0], "1");
+
+        let dms = parser.parse_raw_records(RawKind::DirectMessage).unwrap();
+        assert_eq!(dms.len(), 1);
+        assert_eq!(dms[0].id, "m1");
+        let message: Value = serde_json::from_str(&dms[0].json).unwrap();
+        assert_eq!(message["reactions"][0]["reactionKey"], "like");
+
+        assert!(parser.parse_raw_records(RawKind::Like).unwrap().is_empty());
+    }
+
     #[test]
     fn test_parse_direct_messages_full() {
         let temp_dir = TempDir::new().unwrap();
@@ -1415,6 +2225,102 @@ mod tests {
         assert_eq!(mutes[0].account_id, "555");
     }
 
+    #[test]
+    fn test_parse_lists() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        std::fs::write(
+            data_dir.join("lists-created.js"),
+            r#"window.YTD.lists_created.part0 = [
+            {"userListInfo": {"url": "https://twitter.com/i/lists/111"}},
+            {"userListInfo": {}}
+        ]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            data_dir.join("lists-subscribed.js"),
+            r#"window.YTD.lists_subscribed.part0 = [
+            {"userListInfo": {"url": "https://x.com/i/lists/222/members"}}
+        ]"#,
+        )
+        .unwrap();
+
+        let parser = ArchiveParser::new(temp_dir.path());
+        let lists = parser.parse_lists().unwrap();
+
+        assert_eq!(lists.len(), 2);
+        assert_eq!(lists[0].list_id, "111");
+        assert_eq!(lists[0].relation, ListRelation::Created);
+        assert_eq!(lists[1].list_id, "222");
+        assert_eq!(lists[1].relation, ListRelation::Subscribed);
+    }
+
+    #[test]
+    fn test_parse_screen_name_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let content = r#"window.YTD.screen_name_change.part0 = [
+            {"screenNameChange": {"accountId": "1", "screenNameChange": {
+                "changedAt": "2019-04-02T10:00:00.000Z",
+                "changedFrom": "old_me",
+                "changedTo": "me"
+            }}},
+            {"screenNameChange": {"accountId": "1", "screenNameChange": {"changedTo": "x"}}}
+        ]"#;
+        std::fs::write(data_dir.join("screen-name-change.js"), content).unwrap();
+
+        let parser = ArchiveParser::new(temp_dir.path());
+        let changes = parser.parse_screen_name_changes().unwrap();
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].account_id, "1");
+        assert_eq!(changes[0].changed_from, "old_me");
+        assert_eq!(changes[0].changed_to, "me");
+        assert_eq!(
+            changes[0].changed_at.to_rfc3339(),
+            "2019-04-02T10:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_community_notes() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let content = r#"window.YTD.community_note.part0 = [
+            {"communityNote": {
+                "noteId": "1500000000000000001",
+                "tweetId": "1400000000000000000",
+                "createdAt": "2023-03-04T05:06:07.000Z",
+                "classification": "MisinformedOrPotentiallyMisleading",
+                "summary": {"text": "The photo is from 2015. "}
+            }},
+            {"birdwatchNote": {"noteId": "2", "summary": "Legacy note"}},
+            {"communityNote": {"noteId": "3", "summary": {"text": "  "}}}
+        ]"#;
+        std::fs::write(data_dir.join("community-note.js"), content).unwrap();
+
+        let parser = ArchiveParser::new(temp_dir.path());
+        let notes = parser.parse_community_notes().unwrap();
+
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].id, "1500000000000000001");
+        assert_eq!(notes[0].tweet_id.as_deref(), Some("1400000000000000000"));
+        assert_eq!(notes[0].text, "The photo is from 2015.");
+        assert_eq!(
+            notes[0].classification.as_deref(),
+            Some("MisinformedOrPotentiallyMisleading")
+        );
+        assert!(notes[0].created_at.is_some());
+        assert_eq!(notes[1].text, "Legacy note");
+        assert_eq!(notes[1].created_at, None);
+    }
+
     // =========================================================================
     // Edge Case Tests
     // =========================================================================
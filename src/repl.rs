@@ -1,6 +1,7 @@
 //! Interactive REPL for xf.
 //!
 //! Provides a command-driven shell with history, basic search, and help.
+//! Results can be marked across searches and exported as a set.
 
 use anyhow::{Context, Result};
 use colored::Colorize;
@@ -16,9 +17,11 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use tracing::{debug, info, trace, warn};
 
+use crate::audit::{self, AuditEvent};
+use crate::search::TextIndex;
 use crate::{
-    CONTENT_DIVIDER_WIDTH, SearchEngine, SearchResult, Storage, csv_escape_text, format_number,
-    format_number_usize, format_relative_date, format_short_id,
+    CONTENT_DIVIDER_WIDTH, SearchResult, Storage, csv_escape_text, display_width, format_number,
+    format_number_usize, format_relative_date, format_short_id, results_markdown, truncate_line,
 };
 
 /// Configuration for the REPL session.
@@ -48,7 +51,7 @@ impl Default for ReplConfig {
 /// REPL session state.
 pub struct ReplSession {
     storage: Storage,
-    search: SearchEngine,
+    search: Box<dyn TextIndex>,
     last_results: Vec<SearchResult>,
     last_query: Option<String>,
     history_path: Option<PathBuf>,
@@ -63,6 +66,9 @@ pub struct ReplSession {
     last_selected: Option<usize>,
     /// Named variables (for $name)
     named_vars: HashMap<String, String>,
+    /// Results marked for export, in the order they were marked; kept
+    /// across searches
+    marked: Vec<SearchResult>,
 }
 
 #[derive(Default)]
@@ -76,15 +82,39 @@ enum PromptContext {
 
 #[derive(Debug)]
 enum Command {
-    Search { query: String },
-    List { target: ListTarget },
-    Refine { filter: String },
+    Search {
+        query: String,
+    },
+    List {
+        target: ListTarget,
+    },
+    Refine {
+        filter: String,
+    },
     More,
-    Show { index: usize },
-    Export { format: ExportFormat },
+    Show {
+        index: usize,
+    },
+    Mark {
+        selection: Selection,
+    },
+    Unmark {
+        selection: Selection,
+    },
+    Marked,
+    Export {
+        format: ExportFormat,
+        marked: bool,
+        path: Option<PathBuf>,
+    },
     Stats,
-    Help { command: Option<String> },
-    Set { name: String, value: String },
+    Help {
+        command: Option<String>,
+    },
+    Set {
+        name: String,
+        value: String,
+    },
     Quit,
 }
 
@@ -103,7 +133,17 @@ enum ListTarget {
 #[derive(Debug, Clone, Copy)]
 enum ExportFormat {
     Json,
+    Jsonl,
     Csv,
+    Markdown,
+}
+
+/// Results picked by `mark` and `unmark`.
+#[derive(Debug, PartialEq, Eq)]
+enum Selection {
+    All,
+    /// 1-based result numbers, in the order given
+    Numbers(Vec<usize>),
 }
 
 // =============================================================================
@@ -112,8 +152,8 @@ enum ExportFormat {
 
 /// Commands available in the REPL for completion.
 const COMMANDS: &[&str] = &[
-    "search", "s", "list", "l", "refine", "r", "more", "m", "show", "export", "e", "stats", "set",
-    "help", "h", "?", "quit", "exit", "q",
+    "search", "s", "list", "l", "refine", "r", "more", "m", "show", "mark", "unmark", "marked",
+    "export", "e", "stats", "set", "help", "h", "?", "quit", "exit", "q",
 ];
 
 /// List targets for completion.
@@ -135,7 +175,7 @@ const LIST_TARGETS: &[&str] = &[
 ];
 
 /// Export formats for completion.
-const EXPORT_FORMATS: &[&str] = &["json", "csv"];
+const EXPORT_FORMATS: &[&str] = &["json", "jsonl", "csv", "md", "marked"];
 
 /// Tab completion helper for xf REPL.
 #[derive(Default)]
@@ -270,7 +310,8 @@ impl XfCompleter {
         let prefix_lower = prefix.to_lowercase();
         // Only primary commands, not aliases
         let topics = [
-            "search", "list", "refine", "more", "show", "export", "stats", "quit",
+            "search", "list", "refine", "more", "show", "mark", "unmark", "marked", "export",
+            "stats", "quit",
         ];
         topics
             .iter()
@@ -332,7 +373,7 @@ impl Helper for XfCompleter {}
 /// # Errors
 ///
 /// Returns an error if readline setup, history persistence, or command execution fails.
-pub fn run(storage: Storage, search: SearchEngine, repl_config: ReplConfig) -> Result<()> {
+pub fn run(storage: Storage, search: Box<dyn TextIndex>, repl_config: ReplConfig) -> Result<()> {
     let rl_config = Config::builder()
         .history_ignore_space(true)
         .history_ignore_dups(true)?
@@ -373,6 +414,7 @@ pub fn run(storage: Storage, search: SearchEngine, repl_config: ReplConfig) -> R
         prompt_str: repl_config.prompt,
         last_selected: None,
         named_vars: HashMap::new(),
+        marked: Vec::new(),
     };
 
     // Load history if enabled
@@ -436,7 +478,12 @@ impl ReplSession {
             PromptContext::Normal => self.prompt_str.clone(),
             PromptContext::WithResults(n) => {
                 let count = format_number_usize(*n);
-                format!("{base} [{count}]> ")
+                if self.marked.is_empty() {
+                    format!("{base} [{count}]> ")
+                } else {
+                    let marked = format_number_usize(self.marked.len());
+                    format!("{base} [{count} · {marked} marked]> ")
+                }
             }
             PromptContext::InConversation(id) => {
                 let snippet = id.get(..8.min(id.len())).unwrap_or(id);
@@ -487,8 +534,21 @@ impl ReplSession {
             Command::Show { index } => {
                 self.run_show(index)?;
             }
-            Command::Export { format } => {
-                self.run_export(format)?;
+            Command::Mark { selection } => {
+                self.run_mark(&selection);
+            }
+            Command::Unmark { selection } => {
+                self.run_unmark(&selection);
+            }
+            Command::Marked => {
+                self.run_marked();
+            }
+            Command::Export {
+                format,
+                marked,
+                path,
+            } => {
+                self.run_export(format, marked, path.as_deref())?;
             }
             Command::Stats => {
                 self.run_stats()?;
@@ -593,6 +653,12 @@ impl ReplSession {
     fn run_search(&mut self, query: &str) -> Result<()> {
         let results = self.search.search(query, None, 100)?;
         let count = results.len();
+        audit::record_dm_results(
+            &self.storage,
+            "shell",
+            &format!("query \"{query}\""),
+            &results,
+        )?;
         self.last_results = results;
         self.last_query = Some(query.to_string());
         self.current_offset = 0;
@@ -604,7 +670,7 @@ impl ReplSession {
             format_number_usize(count).bold(),
             "results".dimmed()
         );
-        print_results(&self.last_results, 0, self.page_size);
+        print_results(&self.last_results, 0, self.page_size, &self.marked);
         Ok(())
     }
 
@@ -656,7 +722,7 @@ impl ReplSession {
                     "tweets".dimmed()
                 );
                 for tweet in tweets.iter().take(self.page_size) {
-                    let text = truncate_text(&tweet.full_text, 60);
+                    let text = truncate_line(&tweet.full_text, 60);
                     println!(
                         "  {} {}",
                         format_relative_date(tweet.created_at).dimmed(),
@@ -683,7 +749,7 @@ impl ReplSession {
                 );
                 for like in likes.iter().take(self.page_size) {
                     let text = like.full_text.as_deref().unwrap_or("[no text]");
-                    let text = truncate_text(text, 60);
+                    let text = truncate_line(text, 60);
                     println!("  {text}");
                 }
                 if likes.len() > self.page_size {
@@ -699,13 +765,20 @@ impl ReplSession {
             }
             ListTarget::Dms => {
                 let dms = self.storage.get_all_dms(None)?;
+                audit::record(
+                    &self.storage,
+                    "shell",
+                    AuditEvent::ReadDms,
+                    "dms",
+                    dms.len().min(self.page_size),
+                )?;
                 println!(
                     "{} {}",
                     format_number_usize(dms.len()).bold(),
                     "DM messages".dimmed()
                 );
                 for dm in dms.iter().take(self.page_size) {
-                    let text = truncate_text(&dm.text, 60);
+                    let text = truncate_line(&dm.text, 60);
                     println!(
                         "  {} {}",
                         format_relative_date(dm.created_at).dimmed(),
@@ -846,7 +919,7 @@ impl ReplSession {
             "results".dimmed(),
             filter.yellow()
         );
-        print_results(&self.last_results, 0, self.page_size);
+        print_results(&self.last_results, 0, self.page_size, &self.marked);
         Ok(())
     }
 
@@ -868,7 +941,12 @@ impl ReplSession {
         self.current_offset = new_offset;
         debug!(offset = new_offset, total, "Showing more results");
 
-        print_results(&self.last_results, self.current_offset, self.page_size);
+        print_results(
+            &self.last_results,
+            self.current_offset,
+            self.page_size,
+            &self.marked,
+        );
         let start = self.current_offset + 1;
         let end = (self.current_offset + self.page_size).min(total);
         println!(
@@ -923,49 +1001,223 @@ impl ReplSession {
         Ok(())
     }
 
-    fn run_export(&self, format: ExportFormat) -> Result<()> {
+    /// The current results a selection refers to, or `None` (with a
+    /// message printed) if it is out of range.
+    fn select(&self, selection: &Selection) -> Option<Vec<&SearchResult>> {
         if self.last_results.is_empty() {
-            println!("{}", "No results to export. Run a search first.".yellow());
-            return Ok(());
+            println!("{}", "No results. Run a search first.".yellow());
+            return None;
         }
-
-        debug!(
-            ?format,
-            count = self.last_results.len(),
-            "Exporting results"
-        );
-
-        match format {
-            ExportFormat::Json => {
-                let json = serde_json::to_string_pretty(&self.last_results)?;
-                println!("{json}");
-            }
-            ExportFormat::Csv => {
-                println!("id,type,score,created_at,text");
-                for r in &self.last_results {
-                    let created = r.created_at.to_rfc3339();
-                    // Escape quotes and replace newlines/carriage returns for valid CSV
-                    let text_escaped = csv_escape_text(&r.text);
+        match selection {
+            Selection::All => Some(self.last_results.iter().collect()),
+            Selection::Numbers(numbers) => {
+                if let Some(bad) = numbers.iter().find(|&&n| n > self.last_results.len()) {
                     println!(
-                        "{},{},{:.2},{},\"{}\"",
-                        r.id, r.result_type, r.score, created, text_escaped
+                        "{}",
+                        format!("Invalid index {bad}. Use 1-{}.", self.last_results.len()).red()
                     );
+                    return None;
                 }
+                Some(numbers.iter().map(|&n| &self.last_results[n - 1]).collect())
             }
         }
+    }
 
+    fn run_mark(&mut self, selection: &Selection) {
+        let Some(selected) = self.select(selection) else {
+            return;
+        };
+        let selected: Vec<SearchResult> = selected.into_iter().cloned().collect();
+        let before = self.marked.len();
+        for result in selected {
+            if !is_marked(&self.marked, &result) {
+                self.marked.push(result);
+            }
+        }
+        let added = self.marked.len() - before;
+        debug!(added, total = self.marked.len(), "Marked results");
         println!(
-            "{}",
+            "Marked {} results {}",
+            format_number_usize(added).bold(),
             format!(
-                "Exported {} results",
-                format_number_usize(self.last_results.len())
+                "({} marked in total)",
+                format_number_usize(self.marked.len())
             )
             .dimmed()
         );
+    }
+
+    fn run_unmark(&mut self, selection: &Selection) {
+        let before = self.marked.len();
+        if *selection == Selection::All {
+            self.marked.clear();
+        } else {
+            let Some(selected) = self.select(selection) else {
+                return;
+            };
+            let keys: Vec<(crate::SearchResultType, String)> = selected
+                .into_iter()
+                .map(|r| (r.result_type, r.id.clone()))
+                .collect();
+            self.marked.retain(|m| {
+                !keys
+                    .iter()
+                    .any(|(t, id)| m.result_type == *t && m.id == *id)
+            });
+        }
+        println!(
+            "Unmarked {} results {}",
+            format_number_usize(before - self.marked.len()).bold(),
+            format!("({} still marked)", format_number_usize(self.marked.len())).dimmed()
+        );
+    }
+
+    fn run_marked(&self) {
+        if self.marked.is_empty() {
+            println!(
+                "{}",
+                "Nothing marked. Use 'mark <numbers>' after a search.".yellow()
+            );
+            return;
+        }
+        println!(
+            "{} {}",
+            format_number_usize(self.marked.len()).bold(),
+            "marked".dimmed()
+        );
+        print_results(&self.marked, 0, self.marked.len(), &[]);
+    }
+
+    fn run_export(
+        &self,
+        format: ExportFormat,
+        marked: bool,
+        path: Option<&std::path::Path>,
+    ) -> Result<()> {
+        let (results, title) = if marked {
+            (&self.marked, "Marked results".to_string())
+        } else {
+            (
+                &self.last_results,
+                format!(
+                    "Results for \"{}\"",
+                    self.last_query.as_deref().unwrap_or_default()
+                ),
+            )
+        };
+        if results.is_empty() {
+            let hint = if marked {
+                "Nothing marked. Use 'mark <numbers>' after a search."
+            } else {
+                "No results to export. Run a search first."
+            };
+            println!("{}", hint.yellow());
+            return Ok(());
+        }
+
+        debug!(?format, marked, count = results.len(), "Exporting results");
+        let destination = path.map_or_else(|| "stdout".to_string(), |p| p.display().to_string());
+        let what = if marked {
+            "marked results".to_string()
+        } else {
+            format!(
+                "results for \"{}\"",
+                self.last_query.as_deref().unwrap_or_default()
+            )
+        };
+        audit::record(
+            &self.storage,
+            "shell",
+            AuditEvent::Export,
+            &format!("{what} to {destination}"),
+            results.len(),
+        )?;
+
+        let rendered = render_export(results, format, &title)?;
+        if let Some(path) = path {
+            std::fs::write(path, rendered + "\n")
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        } else {
+            println!("{rendered}");
+        }
+
+        let count = format_number_usize(results.len());
+        let summary = if path.is_some() {
+            format!("Exported {count} results to {destination}")
+        } else {
+            format!("Exported {count} results")
+        };
+        println!("{}", summary.dimmed());
         Ok(())
     }
 }
 
+/// Whether `result` is already in the marked set.
+fn is_marked(marked: &[SearchResult], result: &SearchResult) -> bool {
+    marked
+        .iter()
+        .any(|m| m.result_type == result.result_type && m.id == result.id)
+}
+
+/// Render results for `export`, without a trailing newline.
+fn render_export(results: &[SearchResult], format: ExportFormat, title: &str) -> Result<String> {
+    Ok(match format {
+        ExportFormat::Json => serde_json::to_string_pretty(results)?,
+        ExportFormat::Jsonl => results
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<serde_json::Result<Vec<_>>>()?
+            .join("\n"),
+        ExportFormat::Csv => {
+            let mut lines = vec!["id,type,score,created_at,text".to_string()];
+            for r in results {
+                let created = r.created_at.to_rfc3339();
+                // Escape quotes and replace newlines/carriage returns for valid CSV
+                let text_escaped = csv_escape_text(&r.text);
+                lines.push(format!(
+                    "{},{},{:.2},{},\"{}\"",
+                    r.id, r.result_type, r.score, created, text_escaped
+                ));
+            }
+            lines.join("\n")
+        }
+        ExportFormat::Markdown => results_markdown(title, results).trim_end().to_string(),
+    })
+}
+
+/// Parse `mark`/`unmark` arguments: `all`, or numbers and ranges such as
+/// `1 3 5-7` (commas also separate).
+fn parse_selection(args: &[&str]) -> Result<Selection> {
+    if args == ["all"] {
+        return Ok(Selection::All);
+    }
+    let mut numbers = Vec::new();
+    for piece in args.iter().flat_map(|arg| arg.split(',')) {
+        if piece.is_empty() {
+            continue;
+        }
+        let parse = |n: &str| -> Result<usize> {
+            n.parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| anyhow::anyhow!("Invalid number: {n}"))
+        };
+        if let Some((start, end)) = piece.split_once('-') {
+            let (start, end) = (parse(start)?, parse(end)?);
+            if start > end {
+                anyhow::bail!("Invalid range: {piece}");
+            }
+            numbers.extend(start..=end);
+        } else {
+            numbers.push(parse(piece)?);
+        }
+    }
+    if numbers.is_empty() {
+        anyhow::bail!("Usage: mark <numbers|all> (e.g. mark 1 3 5-7)");
+    }
+    Ok(Selection::Numbers(numbers))
+}
+
 fn parse_command(input: &str) -> Result<Command> {
     let parts: Vec<&str> = input.split_whitespace().collect();
     if parts.is_empty() {
@@ -1002,15 +1254,14 @@ fn parse_command(input: &str) -> Result<Command> {
                 .map_err(|_| anyhow::anyhow!("Invalid number: {idx_str}"))?;
             Ok(Command::Show { index })
         }
-        "export" | "e" => {
-            let fmt = parts.get(1).copied().unwrap_or("json");
-            let format = match fmt {
-                "json" => ExportFormat::Json,
-                "csv" => ExportFormat::Csv,
-                _ => anyhow::bail!("Unknown export format: {fmt}. Use 'json' or 'csv'."),
-            };
-            Ok(Command::Export { format })
-        }
+        "mark" => Ok(Command::Mark {
+            selection: parse_selection(&parts[1..])?,
+        }),
+        "unmark" => Ok(Command::Unmark {
+            selection: parse_selection(&parts[1..])?,
+        }),
+        "marked" => Ok(Command::Marked),
+        "export" | "e" => parse_export(input),
         "stats" => Ok(Command::Stats),
         "set" => {
             if parts.len() < 3 {
@@ -1044,6 +1295,42 @@ fn parse_command(input: &str) -> Result<Command> {
     }
 }
 
+/// Parse `export [format] [marked] [> file]`.
+fn parse_export(input: &str) -> Result<Command> {
+    let (spec, path) = match input.split_once('>') {
+        Some((spec, path)) => {
+            let path = path.trim();
+            if path.is_empty() {
+                anyhow::bail!("Usage: export [format] [marked] > <file>");
+            }
+            (spec, Some(PathBuf::from(path)))
+        }
+        None => (input, None),
+    };
+    let mut format = ExportFormat::Json;
+    let mut marked = false;
+    for arg in spec.split_whitespace().skip(1) {
+        format = match arg {
+            "marked" => {
+                marked = true;
+                continue;
+            }
+            "json" => ExportFormat::Json,
+            "jsonl" => ExportFormat::Jsonl,
+            "csv" => ExportFormat::Csv,
+            "md" | "markdown" => ExportFormat::Markdown,
+            _ => {
+                anyhow::bail!("Unknown export format: {arg}. Use 'json', 'jsonl', 'csv', or 'md'.")
+            }
+        };
+    }
+    Ok(Command::Export {
+        format,
+        marked,
+        path,
+    })
+}
+
 fn parse_list_target(s: &str) -> Result<ListTarget> {
     match s {
         "tweets" | "t" => Ok(ListTarget::Tweets),
@@ -1060,11 +1347,25 @@ fn parse_list_target(s: &str) -> Result<ListTarget> {
     }
 }
 
-fn print_results(results: &[SearchResult], offset: usize, page_size: usize) {
+/// Print a page of results; once anything is marked, marked results get a
+/// leading `*`.
+fn print_results(
+    results: &[SearchResult],
+    offset: usize,
+    page_size: usize,
+    marked: &[SearchResult],
+) {
     for (idx, result) in results.iter().skip(offset).take(page_size).enumerate() {
-        let text = truncate_text(&result.text, 80);
+        let text = truncate_line(&result.text, 80);
+        let flag = if marked.is_empty() {
+            String::new()
+        } else if is_marked(marked, result) {
+            "*".green().bold().to_string()
+        } else {
+            " ".to_string()
+        };
         println!(
-            "{:>3}. [{}] {}",
+            "{flag}{:>3}. [{}] {}",
             offset + idx + 1,
             result.result_type.to_string().cyan(),
             text
@@ -1083,20 +1384,6 @@ fn print_results(results: &[SearchResult], offset: usize, page_size: usize) {
     }
 }
 
-fn truncate_text(text: &str, max_len: usize) -> String {
-    let text = text.replace('\n', " ").replace('\r', "");
-    let char_count = text.chars().count();
-    if char_count <= max_len {
-        text
-    } else if max_len <= 3 {
-        // Can't fit any text + "...", just truncate without ellipsis
-        text.chars().take(max_len).collect()
-    } else {
-        let truncated: String = text.chars().take(max_len - 3).collect();
-        format!("{truncated}...")
-    }
-}
-
 /// Print the startup banner with archive statistics.
 fn print_startup_banner(storage: &Storage) {
     const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -1131,8 +1418,7 @@ fn print_startup_banner(storage: &Storage) {
 
     // Archive info
     let archive_line = format!("  Archive: @{username}");
-    // Use chars().count() for proper alignment with potential non-ASCII usernames
-    let padding = 60usize.saturating_sub(archive_line.chars().count());
+    let padding = 60usize.saturating_sub(display_width(&archive_line));
     println!(
         "{}{}{}{}",
         "│".dimmed(),
@@ -1149,8 +1435,7 @@ fn print_startup_banner(storage: &Storage) {
             format_number(s.dms_count),
             format_number(s.likes_count)
         );
-        // Use chars().count() since "•" is a multi-byte UTF-8 character
-        let padding = 60usize.saturating_sub(stats_line.chars().count());
+        let padding = 60usize.saturating_sub(display_width(&stats_line));
         println!(
             "{}{}{}{}",
             "│".dimmed(),
@@ -1211,12 +1496,22 @@ fn print_help(command: Option<&str>) {
             println!("  Show full details of a result by its number");
             println!("  Example: show 1");
         }
+        Some("mark" | "unmark" | "marked") => {
+            println!(
+                "{}",
+                "mark <numbers|all> / unmark <numbers|all> / marked".cyan()
+            );
+            println!("  Mark results for export, unmark them, or list the marked set");
+            println!("  Marks are kept across searches; export them with 'export <format> marked'");
+            println!("  Example: mark 1 3 5-7");
+        }
         Some("export" | "e") => {
-            println!("{}", "export [format]".cyan());
-            println!("  Export current search results");
+            println!("{}", "export [format] [marked] [> file]".cyan());
+            println!("  Export current search results, or the marked ones");
             println!("  Aliases: e");
-            println!("  Formats: json (default), csv");
+            println!("  Formats: json (default), jsonl, csv, md");
             println!("  Example: export csv");
+            println!("  Example: export md marked > report.md");
         }
         Some("stats") => {
             println!("{}", "stats".cyan());
@@ -1245,7 +1540,8 @@ fn print_help(command: Option<&str>) {
             println!("  refine <filter> - filter current results (r)");
             println!("  more            - show next page of results (m)");
             println!("  show <number>   - show full result details");
-            println!("  export [format] - export results as json/csv (e)");
+            println!("  mark <numbers>  - mark results for export (unmark, marked)");
+            println!("  export [format] - export results as json/jsonl/csv/md (e)");
             println!("  stats           - show archive statistics");
             println!("  set <n> <val>   - set a named variable");
             println!("  help [command]  - show help (h, ?)");
@@ -1422,7 +1718,8 @@ mod tests {
         assert!(matches!(
             cmd,
             Command::Export {
-                format: ExportFormat::Json
+                format: ExportFormat::Json,
+                ..
             }
         ));
     }
@@ -1433,7 +1730,8 @@ mod tests {
         assert!(matches!(
             cmd,
             Command::Export {
-                format: ExportFormat::Csv
+                format: ExportFormat::Csv,
+                ..
             }
         ));
     }
@@ -1444,7 +1742,8 @@ mod tests {
         assert!(matches!(
             cmd,
             Command::Export {
-                format: ExportFormat::Json
+                format: ExportFormat::Json,
+                ..
             }
         ));
     }
@@ -1455,6 +1754,75 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_export_marked_to_file() {
+        let cmd = parse_command("export md marked > notes/report.md").unwrap();
+        assert!(matches!(
+            cmd,
+            Command::Export {
+                format: ExportFormat::Markdown,
+                marked: true,
+                path: Some(ref p),
+            } if p == &PathBuf::from("notes/report.md")
+        ));
+        assert!(matches!(
+            parse_command("e marked jsonl").unwrap(),
+            Command::Export {
+                format: ExportFormat::Jsonl,
+                marked: true,
+                path: None,
+            }
+        ));
+        assert!(parse_command("export csv >").is_err());
+    }
+
+    #[test]
+    fn test_parse_mark_selection() {
+        assert!(matches!(
+            parse_command("mark 1 3,5-7").unwrap(),
+            Command::Mark { selection: Selection::Numbers(ref n) } if n == &[1, 3, 5, 6, 7]
+        ));
+        assert!(matches!(
+            parse_command("unmark all").unwrap(),
+            Command::Unmark {
+                selection: Selection::All
+            }
+        ));
+        assert!(matches!(parse_command("marked").unwrap(), Command::Marked));
+        assert!(parse_command("mark").is_err());
+        assert!(parse_command("mark 0").is_err());
+        assert!(parse_command("mark 5-2").is_err());
+        assert!(parse_command("mark two").is_err());
+    }
+
+    #[test]
+    fn test_render_export_formats() {
+        let result = |id: &str, text: &str| SearchResult {
+            result_type: crate::SearchResultType::Tweet,
+            id: id.to_string(),
+            text: text.to_string(),
+            created_at: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            score: 1.0,
+            highlights: Vec::new(),
+            match_ranges: Vec::new(),
+            metadata: serde_json::Value::Null,
+        };
+        let results = vec![result("1", "first"), result("2", "second\nline")];
+
+        let jsonl = render_export(&results, ExportFormat::Jsonl, "t").unwrap();
+        assert_eq!(jsonl.lines().count(), 2);
+        assert!(jsonl.lines().all(|l| l.starts_with('{')));
+
+        let csv = render_export(&results, ExportFormat::Csv, "t").unwrap();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.ends_with("\"second line\""));
+
+        let md = render_export(&results, ExportFormat::Markdown, "Marked results").unwrap();
+        assert!(md.starts_with("# Marked results\n"));
+        assert!(md.contains("## tweet 2 (2023-11-14 22:13 UTC)\n\n> second\n> line"));
+        assert!(!md.ends_with('\n'));
+    }
+
     #[test]
     fn test_parse_stats_command() {
         let cmd = parse_command("stats").unwrap();
@@ -1528,49 +1896,49 @@ mod tests {
     // ======================== Helper Function Tests ========================
 
     #[test]
-    fn test_truncate_text_short() {
-        let result = truncate_text("short text", 80);
+    fn test_truncate_line_short() {
+        let result = truncate_line("short text", 80);
         assert_eq!(result, "short text");
     }
 
     #[test]
-    fn test_truncate_text_long() {
+    fn test_truncate_line_long() {
         let long_text = "a".repeat(100);
-        let result = truncate_text(&long_text, 50);
+        let result = truncate_line(&long_text, 50);
         assert!(result.ends_with("..."));
         assert!(result.len() <= 50);
     }
 
     #[test]
-    fn test_truncate_text_removes_newlines() {
-        let result = truncate_text("line1\nline2\rline3", 80);
+    fn test_truncate_line_removes_newlines() {
+        let result = truncate_line("line1\nline2\rline3", 80);
         assert!(!result.contains('\n'));
         assert!(!result.contains('\r'));
     }
 
     #[test]
-    fn test_truncate_text_exact_length() {
+    fn test_truncate_line_exact_length() {
         let text = "exactly ten";
-        let result = truncate_text(text, 11);
+        let result = truncate_line(text, 11);
         assert_eq!(result, text);
     }
 
     #[test]
-    fn test_truncate_text_very_small_max_len() {
+    fn test_truncate_line_very_small_max_len() {
         // When max_len <= 3, we can't fit text + "...", so just truncate
-        let result = truncate_text("hello", 2);
+        let result = truncate_line("hello", 2);
         assert_eq!(result, "he");
         assert_eq!(result.len(), 2);
 
-        let result = truncate_text("hello", 3);
+        let result = truncate_line("hello", 3);
         assert_eq!(result, "hel");
         assert_eq!(result.len(), 3);
     }
 
     #[test]
-    fn test_truncate_text_boundary_max_len() {
+    fn test_truncate_line_boundary_max_len() {
         // max_len = 4: just enough for 1 char + "..."
-        let result = truncate_text("hello", 4);
+        let result = truncate_line("hello", 4);
         assert_eq!(result, "h...");
         assert_eq!(result.len(), 4);
     }
@@ -1990,6 +2358,7 @@ mod tests {
                 score: 1.0,
                 created_at: Utc::now(),
                 highlights: vec![],
+                match_ranges: Vec::new(),
                 metadata: serde_json::json!({}),
             },
             SearchResult {
@@ -1999,6 +2368,7 @@ mod tests {
                 score: 0.9,
                 created_at: Utc::now(),
                 highlights: vec![],
+                match_ranges: Vec::new(),
                 metadata: serde_json::json!({}),
             },
             SearchResult {
@@ -2008,6 +2378,7 @@ mod tests {
                 score: 0.8,
                 created_at: Utc::now(),
                 highlights: vec![],
+                match_ranges: Vec::new(),
                 metadata: serde_json::json!({}),
             },
         ];
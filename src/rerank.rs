@@ -0,0 +1,342 @@
+//! Optional cross-encoder reranking of search candidates.
+//!
+//! A cross-encoder reads the query and a document together and scores how
+//! well they match. That is more precise than BM25 or vector similarity but
+//! much slower, so it only re-scores the top candidates of a lexical or
+//! hybrid search.
+//!
+//! The ONNX backend is behind the `rerank` feature. Models are read from a
+//! local directory containing `model.onnx` and `tokenizer.json` (for example
+//! an exported `ms-marco-MiniLM-L-6-v2`); nothing is ever downloaded.
+
+use crate::embedder::{EmbedderError, EmbedderResult};
+use crate::model::SearchResult;
+use std::path::{Path, PathBuf};
+
+/// Default number of top candidates re-scored by the cross-encoder.
+pub const DEFAULT_RERANK_CANDIDATES: usize = 50;
+
+/// Trait for reranking backends.
+///
+/// # Implementations
+///
+/// - `CrossEncoderReranker` (feature `rerank`): ONNX cross-encoder loaded
+///   from a local model directory.
+pub trait Reranker: Send + Sync {
+    /// Score each document against the query; higher means more relevant.
+    ///
+    /// Returns one raw score (logit) per document, in input order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if tokenization or model inference fails.
+    fn score(&self, query: &str, documents: &[&str]) -> EmbedderResult<Vec<f32>>;
+
+    /// Get the unique identifier for this reranker.
+    fn id(&self) -> &str;
+}
+
+/// Default model directory: `<data dir>/models/reranker`.
+#[must_use]
+pub fn default_model_dir() -> PathBuf {
+    crate::default_data_dir().join("models").join("reranker")
+}
+
+/// Re-score the first `candidates` results and reorder them by the new score.
+///
+/// Reranked results get the cross-encoder logit squashed into 0.0 - 1.0.
+/// Results past the window keep their original order and score.
+///
+/// # Errors
+///
+/// Returns an error if the reranker fails or returns the wrong number of scores.
+pub fn rerank_results(
+    reranker: &dyn Reranker,
+    query: &str,
+    results: &mut [SearchResult],
+    candidates: usize,
+) -> EmbedderResult<()> {
+    let window = candidates.min(results.len());
+    if window == 0 {
+        return Ok(());
+    }
+
+    let texts: Vec<&str> = results[..window].iter().map(|r| r.text.as_str()).collect();
+    let scores = reranker.score(query, &texts)?;
+    if scores.len() != window {
+        return Err(EmbedderError::Internal(format!(
+            "reranker {} returned {} scores for {window} documents",
+            reranker.id(),
+            scores.len()
+        )));
+    }
+
+    for (result, score) in results[..window].iter_mut().zip(scores) {
+        result.score = sigmoid(score);
+    }
+    results[..window].sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(())
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Load the cross-encoder from `model_dir`.
+///
+/// # Errors
+///
+/// Returns [`EmbedderError::Unavailable`] if the model files are missing or
+/// cannot be loaded.
+#[cfg(feature = "rerank")]
+pub fn load_reranker(model_dir: &Path) -> EmbedderResult<Box<dyn Reranker>> {
+    Ok(Box::new(CrossEncoderReranker::load(model_dir)?))
+}
+
+/// Load the cross-encoder from `model_dir`.
+///
+/// # Errors
+///
+/// Always returns [`EmbedderError::Unavailable`]: this build lacks the
+/// `rerank` feature.
+#[cfg(not(feature = "rerank"))]
+pub fn load_reranker(model_dir: &Path) -> EmbedderResult<Box<dyn Reranker>> {
+    let _ = model_dir;
+    Err(EmbedderError::Unavailable(
+        "xf was built without the `rerank` feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "rerank")]
+pub use cross_encoder::CrossEncoderReranker;
+
+#[cfg(feature = "rerank")]
+mod cross_encoder {
+    use super::Reranker;
+    use crate::embedder::{EmbedderError, EmbedderResult};
+    use ort::session::{Session, SessionInputValue};
+    use ort::value::Tensor;
+    use parking_lot::Mutex;
+    use std::borrow::Cow;
+    use std::path::Path;
+    use tokenizers::{Encoding, Tokenizer, TruncationParams};
+
+    /// Longest query + document pair fed to the model, in tokens.
+    const MAX_SEQUENCE_LEN: usize = 512;
+
+    /// Pairs scored per inference call.
+    const BATCH_SIZE: usize = 16;
+
+    /// ONNX cross-encoder (BERT-style inputs, one relevance logit per pair).
+    pub struct CrossEncoderReranker {
+        session: Mutex<Session>,
+        tokenizer: Tokenizer,
+        input_names: Vec<String>,
+        id: String,
+    }
+
+    impl CrossEncoderReranker {
+        /// Load `model.onnx` and `tokenizer.json` from `model_dir`.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`EmbedderError::Unavailable`] if either file is missing
+        /// or fails to load (including a missing ONNX Runtime library).
+        pub fn load(model_dir: &Path) -> EmbedderResult<Self> {
+            let model_path = model_dir.join("model.onnx");
+            let tokenizer_path = model_dir.join("tokenizer.json");
+            for path in [&model_path, &tokenizer_path] {
+                if !path.exists() {
+                    return Err(EmbedderError::Unavailable(format!(
+                        "reranker model file not found: {}",
+                        path.display()
+                    )));
+                }
+            }
+
+            let mut tokenizer = Tokenizer::from_file(&tokenizer_path).map_err(|e| {
+                EmbedderError::Unavailable(format!("failed to load tokenizer: {e}"))
+            })?;
+            tokenizer
+                .with_truncation(Some(TruncationParams {
+                    max_length: MAX_SEQUENCE_LEN,
+                    ..TruncationParams::default()
+                }))
+                .map_err(|e| EmbedderError::Internal(format!("tokenizer truncation: {e}")))?;
+            tokenizer.with_padding(None);
+
+            let session = Session::builder()
+                .and_then(|builder| builder.commit_from_file(&model_path))
+                .map_err(|e| {
+                    EmbedderError::Unavailable(format!(
+                        "failed to load {}: {e}",
+                        model_path.display()
+                    ))
+                })?;
+            let input_names = session.inputs.iter().map(|i| i.name.clone()).collect();
+            let name = model_dir
+                .file_name()
+                .map_or_else(|| "model".into(), |n| n.to_string_lossy());
+
+            Ok(Self {
+                session: Mutex::new(session),
+                tokenizer,
+                input_names,
+                id: format!("cross-encoder:{name}"),
+            })
+        }
+
+        fn score_batch(&self, query: &str, documents: &[&str]) -> EmbedderResult<Vec<f32>> {
+            let pairs: Vec<(&str, &str)> = documents.iter().map(|doc| (query, *doc)).collect();
+            let encodings = self
+                .tokenizer
+                .encode_batch(pairs, true)
+                .map_err(|e| EmbedderError::EmbeddingFailed(format!("tokenization: {e}")))?;
+
+            let batch = encodings.len();
+            let seq_len = encodings.iter().map(Encoding::len).max().unwrap_or(0);
+            let mut input_ids = vec![0_i64; batch * seq_len];
+            let mut attention_mask = vec![0_i64; batch * seq_len];
+            let mut token_type_ids = vec![0_i64; batch * seq_len];
+            for (row, encoding) in encodings.iter().enumerate() {
+                let tokens = encoding
+                    .get_ids()
+                    .iter()
+                    .zip(encoding.get_attention_mask())
+                    .zip(encoding.get_type_ids());
+                for (col, ((&id, &mask), &type_id)) in tokens.enumerate() {
+                    let i = row * seq_len + col;
+                    input_ids[i] = i64::from(id);
+                    attention_mask[i] = i64::from(mask);
+                    token_type_ids[i] = i64::from(type_id);
+                }
+            }
+
+            let mut inputs: Vec<(Cow<'_, str>, SessionInputValue<'_>)> = Vec::new();
+            for name in &self.input_names {
+                let data = match name.as_str() {
+                    "input_ids" => &input_ids,
+                    "attention_mask" => &attention_mask,
+                    "token_type_ids" => &token_type_ids,
+                    other => {
+                        return Err(EmbedderError::Internal(format!(
+                            "unsupported reranker model input '{other}'"
+                        )));
+                    }
+                };
+                let tensor = Tensor::from_array(([batch, seq_len], data.clone()))
+                    .map_err(|e| EmbedderError::Internal(format!("input tensor: {e}")))?;
+                inputs.push((Cow::from(name.as_str()), tensor.into()));
+            }
+
+            let mut session = self.session.lock();
+            let outputs = session
+                .run(inputs)
+                .map_err(|e| EmbedderError::EmbeddingFailed(format!("inference: {e}")))?;
+            let logits = outputs[0]
+                .try_extract_tensor::<f32>()
+                .map_err(|e| EmbedderError::Internal(format!("output tensor: {e}")))?
+                .1
+                .to_vec();
+            drop(outputs);
+            drop(session);
+
+            // Logits are [batch, labels]; the last label is "relevant" for
+            // both single-logit and two-class cross-encoders.
+            let labels = if batch == 0 { 1 } else { logits.len() / batch };
+            if labels == 0 {
+                return Err(EmbedderError::Internal("empty reranker output".to_string()));
+            }
+            Ok((0..batch)
+                .map(|row| logits[row * labels + labels - 1])
+                .collect())
+        }
+    }
+
+    impl Reranker for CrossEncoderReranker {
+        fn score(&self, query: &str, documents: &[&str]) -> EmbedderResult<Vec<f32>> {
+            let mut scores = Vec::with_capacity(documents.len());
+            for chunk in documents.chunks(BATCH_SIZE) {
+                scores.extend(self.score_batch(query, chunk)?);
+            }
+            Ok(scores)
+        }
+
+        fn id(&self) -> &str {
+            &self.id
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SearchResultType;
+    use chrono::Utc;
+
+    /// Scores documents by how many query words they contain.
+    struct WordOverlapReranker;
+
+    impl Reranker for WordOverlapReranker {
+        #[allow(clippy::cast_precision_loss)]
+        fn score(&self, query: &str, documents: &[&str]) -> EmbedderResult<Vec<f32>> {
+            Ok(documents
+                .iter()
+                .map(|doc| {
+                    query
+                        .split_whitespace()
+                        .filter(|word| doc.contains(word))
+                        .count() as f32
+                })
+                .collect())
+        }
+
+        fn id(&self) -> &'static str {
+            "word-overlap"
+        }
+    }
+
+    fn result(id: &str, text: &str, score: f32) -> SearchResult {
+        SearchResult {
+            result_type: SearchResultType::Tweet,
+            id: id.to_string(),
+            text: text.to_string(),
+            created_at: Utc::now(),
+            score,
+            highlights: vec![],
+            match_ranges: Vec::new(),
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn rerank_reorders_only_the_candidate_window() {
+        let mut results = vec![
+            result("a", "rust", 3.0),
+            result("b", "rust async runtime", 2.0),
+            result("c", "unrelated", 1.5),
+            result("d", "rust async runtime tokio", 1.0),
+        ];
+
+        rerank_results(&WordOverlapReranker, "rust async tokio", &mut results, 3).unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a", "c", "d"]);
+        assert!(results[..3].iter().all(|r| (0.0..=1.0).contains(&r.score)));
+        assert!((results[3].score - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn rerank_handles_empty_results() {
+        let mut results = Vec::new();
+        rerank_results(&WordOverlapReranker, "query", &mut results, 50).unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[cfg(not(feature = "rerank"))]
+    #[test]
+    fn load_without_feature_is_unavailable() {
+        let err = load_reranker(&default_model_dir()).err().unwrap();
+        assert!(matches!(err, EmbedderError::Unavailable(_)));
+    }
+}
@@ -0,0 +1,148 @@
+//! JSON Schemas for machine-readable output (`--schema`).
+//!
+//! Schemas are generated from the serde models, so they change only when the
+//! output does. Each schema carries the xf version it was generated by in
+//! `x-xf-version`.
+
+use crate::cli::ExportTarget;
+use crate::model::{DirectMessage, Follower, Following, Like, Note, SearchResult, Tweet};
+use schemars::{JsonSchema, Schema, schema_for};
+use serde_json::{Map, Value};
+
+/// An exported tweet, like or DM.
+#[derive(JsonSchema)]
+#[schemars(rename = "Exported{T}")]
+#[allow(dead_code)] // only used for its schema
+struct Exported<T> {
+    #[serde(flatten)]
+    record: T,
+    /// Source JSON from the archive, with `--include-raw`; `null` when none
+    /// is stored (indexed by an older xf)
+    raw: Option<Map<String, Value>>,
+}
+
+/// Shape of `xf export all --format json`.
+#[derive(JsonSchema)]
+#[allow(dead_code)] // only used for its schema
+struct AllExport {
+    tweets: Vec<Exported<Tweet>>,
+    likes: Vec<Exported<Like>>,
+    dms: Vec<Exported<DirectMessage>>,
+    followers: Vec<Follower>,
+    following: Vec<Following>,
+    notes: Vec<Note>,
+}
+
+/// Schema for `xf search --format json` output.
+#[must_use]
+pub fn search_results() -> Value {
+    versioned(schema_for!(Vec<SearchResult>), "xf search results")
+}
+
+/// Schema for `xf export <target> --format json` output.
+///
+/// With `--format compact` each line is one item of the array. Calendar
+/// exports are iCalendar text and have no schema.
+#[must_use]
+pub fn export(target: &ExportTarget) -> Option<Value> {
+    let schema = match target {
+        ExportTarget::Tweets => schema_for!(Vec<Exported<Tweet>>),
+        ExportTarget::Likes => schema_for!(Vec<Exported<Like>>),
+        ExportTarget::Dms => schema_for!(Vec<Exported<DirectMessage>>),
+        ExportTarget::Followers => schema_for!(Vec<Follower>),
+        ExportTarget::Following => schema_for!(Vec<Following>),
+        ExportTarget::Notes => schema_for!(Vec<Note>),
+        ExportTarget::All => schema_for!(AllExport),
+        ExportTarget::Calendar => return None,
+    };
+    Some(versioned(schema, "xf export"))
+}
+
+fn versioned(mut schema: Schema, title: &str) -> Value {
+    schema.insert("title".to_string(), title.into());
+    schema.insert("x-xf-version".to_string(), env!("CARGO_PKG_VERSION").into());
+    schema.to_value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SearchResultType;
+    use chrono::Utc;
+
+    /// Property names declared for the array items, resolving a `$ref`.
+    fn item_properties(schema: &Value) -> Vec<String> {
+        let items = &schema["items"];
+        let item = items["$ref"].as_str().map_or(items, |reference| {
+            let name = reference.rsplit('/').next().unwrap();
+            &schema["$defs"][name]
+        });
+        item["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_search_schema_matches_serialized_result() {
+        let result = SearchResult {
+            result_type: SearchResultType::Tweet,
+            id: "1".to_string(),
+            text: "hello".to_string(),
+            created_at: Utc::now(),
+            score: 1.0,
+            highlights: Vec::new(),
+            match_ranges: Vec::new(),
+            metadata: serde_json::json!({}),
+        };
+        let serialized = serde_json::to_value(&result).unwrap();
+
+        let schema = search_results();
+        assert_eq!(schema["x-xf-version"], env!("CARGO_PKG_VERSION"));
+        let mut properties = item_properties(&schema);
+        properties.sort();
+        let mut keys: Vec<String> = serialized.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(properties, keys);
+    }
+
+    #[test]
+    fn test_search_schema_documents_metadata_per_type() {
+        let schema = search_results();
+        assert_eq!(
+            schema["$defs"]["ResultMeta"]["anyOf"]
+                .as_array()
+                .map(Vec::len),
+            Some(5)
+        );
+        assert!(schema["$defs"]["DmMeta"]["properties"]["conversation_id"].is_object());
+    }
+
+    #[test]
+    fn test_export_schema_per_target() {
+        let tweets = export(&ExportTarget::Tweets).unwrap();
+        assert_eq!(tweets["type"], "array");
+        assert!(item_properties(&tweets).contains(&"full_text".to_string()));
+
+        // --include-raw adds an optional `raw` object to tweets, likes and DMs
+        for target in [ExportTarget::Tweets, ExportTarget::Likes, ExportTarget::Dms] {
+            let schema = export(&target).unwrap();
+            assert!(item_properties(&schema).contains(&"raw".to_string()));
+            let name = schema["items"]["$ref"]
+                .as_str()
+                .unwrap()
+                .rsplit('/')
+                .next()
+                .unwrap();
+            let required = schema["$defs"][name]["required"].as_array().unwrap();
+            assert!(!required.contains(&"raw".into()));
+        }
+
+        let all = export(&ExportTarget::All).unwrap();
+        assert!(all["properties"]["notes"].is_object());
+
+        assert!(export(&ExportTarget::Calendar).is_none());
+    }
+}
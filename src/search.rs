@@ -1,17 +1,23 @@
 //! Tantivy-based full-text search engine for X data.
 //!
 //! Provides ultra-fast search with BM25 ranking, prefix matching, and phrase queries.
+//! [`TextIndex`] is the keyword search shared with [`crate::fts::FtsIndex`], the
+//! `SQLite` engine selected by `search.engine`.
 
 use crate::doctor::{CheckCategory, CheckStatus, HealthCheck};
 use crate::format_bytes;
-use crate::model::{DmConversation, GrokMessage, Like, SearchResult, SearchResultType, Tweet};
+use crate::model::{
+    Bookmark, DmConversation, GrokMessage, Like, ResultMeta, SearchResult, SearchResultType, Tweet,
+    match_ranges,
+};
 use crate::storage::Storage;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
-use tantivy::collector::TopDocs;
+use tantivy::collector::{DocSetCollector, TopDocs};
 use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, TermQuery, TermSetQuery};
 use tantivy::schema::{
     FAST, Field, INDEXED, IndexRecordOption, STORED, STRING, Schema, TextFieldIndexing,
@@ -40,6 +46,8 @@ const FIELD_TEXT_PREFIX: &str = "text_prefix";
 const FIELD_TYPE: &str = "type";
 const FIELD_CREATED_AT: &str = "created_at";
 const FIELD_METADATA: &str = "metadata";
+const FIELD_SUPPLEMENTAL: &str = "supplemental";
+const FIELD_SUPPLEMENTAL_PREFIX: &str = "supplemental_prefix";
 
 const LARGE_INDEX_BYTES: u64 = 500 * 1024 * 1024;
 const MAX_DOC_TYPES: usize = 4;
@@ -136,6 +144,7 @@ fn doc_to_search_result(
 
     let result_type = match doc_type_str {
         "like" => SearchResultType::Like,
+        "bookmark" => SearchResultType::Bookmark,
         "dm" => SearchResultType::DirectMessage,
         "grok" => SearchResultType::GrokMessage,
         _ => SearchResultType::Tweet,
@@ -148,6 +157,7 @@ fn doc_to_search_result(
         created_at: DateTime::from_timestamp(created_at_ts, 0).unwrap_or_else(epoch_utc),
         score: 1.0,
         highlights: vec![],
+        match_ranges: Vec::new(),
         metadata: parse_metadata(metadata_str),
     };
 
@@ -159,6 +169,7 @@ fn doc_to_search_result(
 pub enum DocType {
     Tweet,
     Like,
+    Bookmark,
     DirectMessage,
     GrokMessage,
 }
@@ -192,16 +203,17 @@ impl DocType {
         match self {
             Self::Tweet => "tweet",
             Self::Like => "like",
+            Self::Bookmark => "bookmark",
             Self::DirectMessage => "dm",
             Self::GrokMessage => "grok",
         }
     }
 
-    #[allow(dead_code)]
-    fn from_str(s: &str) -> Option<Self> {
+    pub(crate) fn from_str(s: &str) -> Option<Self> {
         match s {
             "tweet" => Some(Self::Tweet),
             "like" => Some(Self::Like),
+            "bookmark" => Some(Self::Bookmark),
             "dm" => Some(Self::DirectMessage),
             "grok" => Some(Self::GrokMessage),
             _ => None,
@@ -209,6 +221,98 @@ impl DocType {
     }
 }
 
+/// Engine answering keyword searches (`search.engine`, `--engine`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum SearchBackend {
+    /// Tantivy index in the index directory (default)
+    #[default]
+    Tantivy,
+    /// `SQLite` FTS5 tables in the database; no Tantivy index is built
+    Sqlite,
+}
+
+impl std::str::FromStr for SearchBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "tantivy" => Ok(Self::Tantivy),
+            "sqlite" => Ok(Self::Sqlite),
+            other => Err(format!(
+                "unknown search engine '{other}' (expected tantivy or sqlite)"
+            )),
+        }
+    }
+}
+
+/// Which text of a document a keyword search matches (`--scope`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum SearchScope {
+    /// The document's own text
+    Main,
+    /// Text attached to it: poll options, media alt text and your notes
+    Supplemental,
+    /// Both (default)
+    #[default]
+    All,
+}
+
+/// Keyword search over indexed documents, whichever engine holds them.
+///
+/// Both engines return the same IDs, text and metadata for a document.
+pub trait TextIndex {
+    /// Best `limit` matches for `query`, optionally of `doc_types` only.
+    /// An empty query matches every document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query cannot be parsed or the search fails.
+    fn search(
+        &self,
+        query: &str,
+        doc_types: Option<&[DocType]>,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>>;
+
+    /// Documents by ID, aligned to `lookups`; missing IDs yield `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lookup fails.
+    fn get_by_ids(&self, lookups: &[DocLookup<'_>]) -> Result<Vec<Option<SearchResult>>>;
+
+    /// `created_at` of every match, `0` for documents without one (likes).
+    /// Documents in `excluded`, as `(doc type, ID)` pairs, are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query cannot be parsed or the search fails.
+    fn match_timestamps(
+        &self,
+        query: &str,
+        doc_types: Option<&[DocType]>,
+        excluded: &[(&str, &str)],
+    ) -> Result<Vec<i64>>;
+
+    /// Number of searchable documents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the count cannot be read.
+    fn doc_count(&self) -> Result<u64>;
+
+    /// Whether searches build HTML snippets in `highlights` (the default).
+    /// Turning them off saves time; `match_ranges` are still filled in.
+    fn set_snippets(&mut self, enabled: bool);
+
+    /// Which text later searches match ([`SearchScope::All`] by default).
+    fn set_scope(&mut self, scope: SearchScope);
+}
+
 /// Build the Tantivy schema
 fn build_schema() -> Schema {
     let mut schema_builder = Schema::builder();
@@ -232,7 +336,7 @@ fn build_schema() -> Schema {
             .set_tokenizer("default")
             .set_index_option(IndexRecordOption::Basic),
     );
-    schema_builder.add_text_field(FIELD_TEXT_PREFIX, prefix_options);
+    schema_builder.add_text_field(FIELD_TEXT_PREFIX, prefix_options.clone());
 
     // Document type - exact match only
     schema_builder.add_text_field(FIELD_TYPE, STRING | STORED);
@@ -243,15 +347,36 @@ fn build_schema() -> Schema {
     // Metadata JSON - stored for retrieval
     schema_builder.add_text_field(FIELD_METADATA, STORED);
 
+    // Supplemental text (poll options, alt text, notes) and its prefixes,
+    // searchable apart from the main text; results show the main text only
+    let supplemental_options = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer("default")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+    schema_builder.add_text_field(FIELD_SUPPLEMENTAL, supplemental_options);
+    schema_builder.add_text_field(FIELD_SUPPLEMENTAL_PREFIX, prefix_options);
+
     schema_builder.build()
 }
 
+/// Disk usage of one index segment.
+#[derive(Debug, Clone, Serialize)]
+pub struct SegmentUsage {
+    pub id: String,
+    pub docs: u32,
+    pub deleted_docs: u32,
+    pub bytes: u64,
+}
+
 /// Search engine wrapping Tantivy
 pub struct SearchEngine {
     index: Index,
     schema: Schema,
     reader: IndexReader,
     index_path: Option<PathBuf>,
+    snippets: bool,
+    scope: SearchScope,
 }
 
 impl SearchEngine {
@@ -264,15 +389,15 @@ impl SearchEngine {
         let index_path = index_path.as_ref();
         std::fs::create_dir_all(index_path)?;
 
-        let schema = build_schema();
-
         let index = if index_path.join("meta.json").exists() {
             Index::open_in_dir(index_path)
                 .with_context(|| format!("Failed to open index at {}", index_path.display()))?
         } else {
-            Index::create_in_dir(index_path, schema.clone())
+            Index::create_in_dir(index_path, build_schema())
                 .with_context(|| format!("Failed to create index at {}", index_path.display()))?
         };
+        // Indexes built before the supplemental fields keep their schema
+        let schema = index.schema();
 
         let reader = index
             .reader_builder()
@@ -284,6 +409,8 @@ impl SearchEngine {
             schema,
             reader,
             index_path: Some(index_path.to_path_buf()),
+            snippets: true,
+            scope: SearchScope::All,
         })
     }
 
@@ -306,6 +433,8 @@ impl SearchEngine {
             schema,
             reader,
             index_path: None,
+            snippets: true,
+            scope: SearchScope::All,
         })
     }
 
@@ -348,36 +477,89 @@ impl SearchEngine {
         )
     }
 
+    /// Supplemental text and prefix fields; `None` for indexes built before
+    /// they existed.
+    fn supplemental_fields(&self) -> Option<(Field, Field)> {
+        Some((
+            self.schema.get_field(FIELD_SUPPLEMENTAL).ok()?,
+            self.schema.get_field(FIELD_SUPPLEMENTAL_PREFIX).ok()?,
+        ))
+    }
+
     /// Index tweets.
     ///
     /// # Errors
     ///
     /// Returns an error if any document cannot be added to the index.
     pub fn index_tweets(&self, writer: &mut IndexWriter, tweets: &[Tweet]) -> Result<usize> {
+        self.add_tweets(writer, tweets, true)
+    }
+
+    /// Index tweets without prefix terms, for `xf index --lite`.
+    ///
+    /// Prefix terms are most of the index's size; without them, partial
+    /// words no longer match.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any document cannot be added to the index.
+    pub fn index_tweets_lite(&self, writer: &mut IndexWriter, tweets: &[Tweet]) -> Result<usize> {
+        self.add_tweets(writer, tweets, false)
+    }
+
+    fn add_tweets(
+        &self,
+        writer: &IndexWriter,
+        tweets: &[Tweet],
+        with_prefixes: bool,
+    ) -> Result<usize> {
         let (id_field, text_field, prefix_field, type_field, created_at_field, metadata_field) =
             self.get_fields();
+        let supplemental_fields = self.supplemental_fields();
+        self.delete_existing(writer, DocType::Tweet, tweets.iter().map(|t| t.id.as_str()))?;
 
+        let mut seen: HashSet<&str> = HashSet::new();
         let mut count = 0;
         for tweet in tweets {
-            // Generate prefix terms
-            let prefixes = generate_prefixes(&tweet.full_text);
-
-            let metadata = serde_json::json!({
-                "favorite_count": tweet.favorite_count,
-                "retweet_count": tweet.retweet_count,
-                "in_reply_to": tweet.in_reply_to_screen_name,
-                "hashtags": tweet.hashtags,
-                "source": tweet.source,
-            });
+            if !seen.insert(&tweet.id) {
+                continue;
+            }
+            let attached_text = tweet.attached_text();
+            // Generate prefix terms; older indexes take the attached text's
+            // prefixes with the tweet's
+            let prefixes = match (&attached_text, supplemental_fields) {
+                _ if !with_prefixes => String::new(),
+                (Some(attached), None) => {
+                    generate_prefixes(&format!("{}\n{attached}", tweet.full_text))
+                }
+                _ => generate_prefixes(&tweet.full_text),
+            };
 
-            writer.add_document(doc!(
+            let metadata = ResultMeta::Tweet(tweet.into()).to_value();
+
+            let mut document = doc!(
                 id_field => tweet.id.clone(),
                 text_field => tweet.full_text.clone(),
                 prefix_field => prefixes,
                 type_field => DocType::Tweet.as_str(),
                 created_at_field => tweet.created_at.timestamp(),
                 metadata_field => metadata.to_string(),
-            ))?;
+            );
+            if let Some(attached) = attached_text {
+                match supplemental_fields {
+                    Some((supplemental_field, supplemental_prefix_field)) => {
+                        if with_prefixes {
+                            document
+                                .add_text(supplemental_prefix_field, generate_prefixes(&attached));
+                        }
+                        document.add_text(supplemental_field, attached);
+                    }
+                    // A second text value: searchable, while results still
+                    // show the tweet text alone
+                    None => document.add_text(text_field, attached),
+                }
+            }
+            writer.add_document(document)?;
             count += 1;
         }
 
@@ -385,6 +567,34 @@ impl SearchEngine {
         Ok(count)
     }
 
+    /// Delete documents of `doc_type` with any of `ids`, so indexing an
+    /// archive again replaces its documents instead of adding second copies.
+    ///
+    /// The delete is ordered before documents added afterwards, so only
+    /// copies from earlier runs (or earlier batches) are removed.
+    fn delete_existing<'a>(
+        &self,
+        writer: &IndexWriter,
+        doc_type: DocType,
+        ids: impl Iterator<Item = &'a str>,
+    ) -> Result<()> {
+        let (id_field, _, _, type_field, _, _) = self.get_fields();
+        let terms: Vec<Term> = ids.map(|id| Term::from_field_text(id_field, id)).collect();
+        if terms.is_empty() {
+            return Ok(());
+        }
+        let type_query = TermQuery::new(
+            Term::from_field_text(type_field, doc_type.as_str()),
+            IndexRecordOption::Basic,
+        );
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, Box::new(TermSetQuery::new(terms))),
+            (Occur::Must, Box::new(type_query)),
+        ]);
+        writer.delete_query(Box::new(query))?;
+        Ok(())
+    }
+
     /// Index likes.
     ///
     /// # Errors
@@ -393,19 +603,23 @@ impl SearchEngine {
     pub fn index_likes(&self, writer: &mut IndexWriter, likes: &[Like]) -> Result<usize> {
         let (id_field, text_field, prefix_field, type_field, created_at_field, metadata_field) =
             self.get_fields();
+        self.delete_existing(
+            writer,
+            DocType::Like,
+            likes.iter().map(|l| l.tweet_id.as_str()),
+        )?;
 
+        let mut seen: HashSet<&str> = HashSet::new();
         let mut count = 0;
         for like in likes {
             if let Some(text) = &like.full_text {
-                if text.is_empty() {
+                if text.is_empty() || !seen.insert(&like.tweet_id) {
                     continue;
                 }
 
                 let prefixes = generate_prefixes(text);
 
-                let metadata = serde_json::json!({
-                    "expanded_url": like.expanded_url,
-                });
+                let metadata = ResultMeta::Like(like.into()).to_value();
 
                 writer.add_document(doc!(
                     id_field => like.tweet_id.clone(),
@@ -423,6 +637,51 @@ impl SearchEngine {
         Ok(count)
     }
 
+    /// Index bookmarks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any document cannot be added to the index.
+    pub fn index_bookmarks(
+        &self,
+        writer: &mut IndexWriter,
+        bookmarks: &[Bookmark],
+    ) -> Result<usize> {
+        let (id_field, text_field, prefix_field, type_field, created_at_field, metadata_field) =
+            self.get_fields();
+        self.delete_existing(
+            writer,
+            DocType::Bookmark,
+            bookmarks.iter().map(|b| b.tweet_id.as_str()),
+        )?;
+
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut count = 0;
+        for bookmark in bookmarks {
+            let Some(text) = bookmark.full_text.as_deref() else {
+                continue;
+            };
+            if text.is_empty() || !seen.insert(&bookmark.tweet_id) {
+                continue;
+            }
+
+            let metadata = ResultMeta::Bookmark(bookmark.into()).to_value();
+
+            writer.add_document(doc!(
+                id_field => bookmark.tweet_id.clone(),
+                text_field => text,
+                prefix_field => generate_prefixes(text),
+                type_field => DocType::Bookmark.as_str(),
+                created_at_field => 0i64, // Bookmarks don't have timestamps
+                metadata_field => metadata.to_string(),
+            ))?;
+            count += 1;
+        }
+
+        info!("Indexed {} bookmarks", count);
+        Ok(count)
+    }
+
     /// Index direct messages.
     ///
     /// # Errors
@@ -435,17 +694,24 @@ impl SearchEngine {
     ) -> Result<usize> {
         let (id_field, text_field, prefix_field, type_field, created_at_field, metadata_field) =
             self.get_fields();
+        self.delete_existing(
+            writer,
+            DocType::DirectMessage,
+            conversations
+                .iter()
+                .flat_map(|conv| conv.messages.iter().map(|msg| msg.id.as_str())),
+        )?;
 
+        let mut seen: HashSet<&str> = HashSet::new();
         let mut count = 0;
         for conv in conversations {
             for msg in &conv.messages {
+                if !seen.insert(&msg.id) {
+                    continue;
+                }
                 let prefixes = generate_prefixes(&msg.text);
 
-                let metadata = serde_json::json!({
-                    "conversation_id": conv.conversation_id,
-                    "sender_id": msg.sender_id,
-                    "recipient_id": msg.recipient_id,
-                });
+                let metadata = ResultMeta::DirectMessage(msg.into()).to_value();
 
                 writer.add_document(doc!(
                     id_field => msg.id.clone(),
@@ -476,27 +742,33 @@ impl SearchEngine {
         let (id_field, text_field, prefix_field, type_field, created_at_field, metadata_field) =
             self.get_fields();
 
+        // Use chat_id + timestamp_nanos + sender for better uniqueness
+        let doc_ids: Vec<String> = messages
+            .iter()
+            .map(|msg| {
+                format!(
+                    "{}_{}_{}_{}",
+                    msg.chat_id,
+                    msg.created_at.timestamp(),
+                    msg.created_at.timestamp_subsec_nanos(),
+                    msg.sender
+                )
+            })
+            .collect();
+        self.delete_existing(
+            writer,
+            DocType::GrokMessage,
+            doc_ids.iter().map(String::as_str),
+        )?;
+
         let mut count = 0;
-        for msg in messages {
+        for (msg, doc_id) in messages.iter().zip(&doc_ids) {
             let prefixes = generate_prefixes(&msg.message);
 
-            let metadata = serde_json::json!({
-                "chat_id": msg.chat_id,
-                "sender": msg.sender,
-                "grok_mode": msg.grok_mode,
-            });
-
-            // Use chat_id + timestamp_nanos + sender for better uniqueness
-            let doc_id = format!(
-                "{}_{}_{}_{}",
-                msg.chat_id,
-                msg.created_at.timestamp(),
-                msg.created_at.timestamp_subsec_nanos(),
-                msg.sender
-            );
+            let metadata = ResultMeta::GrokMessage(msg.into()).to_value();
 
             writer.add_document(doc!(
-                id_field => doc_id,
+                id_field => doc_id.clone(),
                 text_field => msg.message.clone(),
                 prefix_field => prefixes,
                 type_field => DocType::GrokMessage.as_str(),
@@ -510,25 +782,104 @@ impl SearchEngine {
         Ok(count)
     }
 
-    /// Search the index.
+    /// Re-add documents with the supplemental text `storage` now gives them,
+    /// after their notes changed. `docs` are `(doc_id, doc_type)` pairs;
+    /// documents not in the index are skipped. The writer must be committed
+    /// for the changes to show.
+    ///
+    /// Indexes built before the supplemental fields are left alone.
     ///
     /// # Errors
     ///
-    /// Returns an error if the query cannot be parsed or the search fails.
-    pub fn search(
+    /// Returns an error if the database or index cannot be read, or a
+    /// document cannot be added to the index.
+    pub fn refresh_supplemental(
+        &self,
+        writer: &IndexWriter,
+        storage: &Storage,
+        docs: &[(String, String)],
+    ) -> Result<usize> {
+        let Some((supplemental_field, supplemental_prefix_field)) = self.supplemental_fields()
+        else {
+            return Ok(0);
+        };
+        let (id_field, text_field, prefix_field, type_field, created_at_field, metadata_field) =
+            self.get_fields();
+        // Lite indexes have no prefix terms
+        let with_prefixes = !storage.is_lite_mode();
+        let searcher = self.reader.searcher();
+
+        let mut count = 0;
+        for (doc_id, doc_type) in docs {
+            let Some(doc_type) = DocType::from_str(doc_type) else {
+                continue;
+            };
+            let lookup = DocLookup::with_type(doc_id, doc_type.as_str());
+            let Some(query) = build_lookup_query(id_field, type_field, &[lookup]) else {
+                continue;
+            };
+            let Some((_, address)) = searcher
+                .search(&query, &TopDocs::with_limit(1))?
+                .into_iter()
+                .next()
+            else {
+                continue;
+            };
+            let stored: TantivyDocument = searcher.doc(address)?;
+            let text = stored
+                .get_first(text_field)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+
+            let mut document = doc!(
+                id_field => doc_id.clone(),
+                type_field => doc_type.as_str(),
+                created_at_field => stored
+                    .get_first(created_at_field)
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0),
+                metadata_field => stored
+                    .get_first(metadata_field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("{}"),
+            );
+            if with_prefixes {
+                document.add_text(prefix_field, generate_prefixes(&text));
+            }
+            document.add_text(text_field, text);
+            if let Some(supplemental) = storage.supplemental_text(doc_type.as_str(), doc_id)? {
+                if with_prefixes {
+                    document.add_text(supplemental_prefix_field, generate_prefixes(&supplemental));
+                }
+                document.add_text(supplemental_field, supplemental);
+            }
+
+            self.delete_existing(writer, doc_type, std::iter::once(doc_id.as_str()))?;
+            writer.add_document(document)?;
+            count += 1;
+        }
+
+        info!("Refreshed supplemental text of {} documents", count);
+        Ok(count)
+    }
+
+    /// Parse a query string and apply the optional type filter.
+    ///
+    /// Returns the query and whether it has terms worth highlighting.
+    fn build_query(
         &self,
         query_str: &str,
         doc_types: Option<&[DocType]>,
-        limit: usize,
-    ) -> Result<Vec<SearchResult>> {
-        if limit == 0 {
-            return Ok(Vec::new());
+    ) -> Result<(Box<dyn Query>, bool)> {
+        let (_, text_field, prefix_field, type_field, _, _) = self.get_fields();
+        let supplemental_fields = self.supplemental_fields();
+        if self.scope != SearchScope::All && supplemental_fields.is_none() {
+            anyhow::bail!(
+                "This index predates supplemental text; rebuild it with 'xf index --force' to search with --scope"
+            );
         }
-        let searcher = self.reader.searcher();
-        let (id_field, text_field, prefix_field, type_field, created_at_field, metadata_field) =
-            self.get_fields();
 
-        // Build query
         let trimmed = query_str.trim();
         let mut enable_highlights = true;
         let base_query: Box<dyn Query> = if trimmed.is_empty() {
@@ -539,11 +890,21 @@ impl SearchEngine {
             // indexed, so phrase queries would fail on it. Only include prefix_field for
             // queries without phrases to enable prefix matching (e.g., "he" matches "Hello").
             let has_phrase = trimmed.contains('"');
-            let fields = if has_phrase {
-                vec![text_field]
-            } else {
-                vec![text_field, prefix_field]
-            };
+            let mut fields = Vec::with_capacity(4);
+            if self.scope != SearchScope::Supplemental {
+                fields.push(text_field);
+                if !has_phrase {
+                    fields.push(prefix_field);
+                }
+            }
+            if let Some((supplemental_field, supplemental_prefix_field)) =
+                supplemental_fields.filter(|_| self.scope != SearchScope::Main)
+            {
+                fields.push(supplemental_field);
+                if !has_phrase {
+                    fields.push(supplemental_prefix_field);
+                }
+            }
             let query_parser = QueryParser::for_index(&self.index, fields);
             query_parser
                 .parse_query(trimmed)
@@ -575,11 +936,33 @@ impl SearchEngine {
             base_query
         };
 
+        Ok((query, enable_highlights))
+    }
+
+    /// Search the index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query cannot be parsed or the search fails.
+    pub fn search(
+        &self,
+        query_str: &str,
+        doc_types: Option<&[DocType]>,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let searcher = self.reader.searcher();
+        let (id_field, text_field, _prefix_field, type_field, created_at_field, metadata_field) =
+            self.get_fields();
+        let (query, enable_highlights) = self.build_query(query_str, doc_types)?;
+
         // Execute search
         let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
 
         // Create snippet generator for highlighting when query has terms
-        let snippet_generator = if enable_highlights {
+        let snippet_generator = if enable_highlights && self.snippets {
             Some(SnippetGenerator::create(&searcher, &query, text_field)?)
         } else {
             None
@@ -619,6 +1002,7 @@ impl SearchEngine {
 
             let result_type = match doc_type_str {
                 "like" => SearchResultType::Like,
+                "bookmark" => SearchResultType::Bookmark,
                 "dm" => SearchResultType::DirectMessage,
                 "grok" => SearchResultType::GrokMessage,
                 _ => SearchResultType::Tweet,
@@ -636,6 +1020,12 @@ impl SearchEngine {
                     }
                 });
 
+            let match_ranges = if enable_highlights {
+                match_ranges(&text, query_str)
+            } else {
+                Vec::new()
+            };
+
             results.push(SearchResult {
                 result_type,
                 id,
@@ -643,6 +1033,7 @@ impl SearchEngine {
                 created_at: DateTime::from_timestamp(created_at_ts, 0).unwrap_or_else(epoch_utc),
                 score,
                 highlights,
+                match_ranges,
                 metadata: parse_metadata(metadata_str),
             });
         }
@@ -650,6 +1041,60 @@ impl SearchEngine {
         Ok(results)
     }
 
+    /// Collect the `created_at` timestamp of every document matching a query.
+    ///
+    /// Reads the timestamp from the fast field, so no documents are loaded.
+    /// Documents without a timestamp (likes) are reported as `0`; those in
+    /// `excluded`, as `(doc type, ID)` pairs, are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query cannot be parsed or the search fails.
+    pub fn match_timestamps(
+        &self,
+        query_str: &str,
+        doc_types: Option<&[DocType]>,
+        excluded: &[(&str, &str)],
+    ) -> Result<Vec<i64>> {
+        let searcher = self.reader.searcher();
+        let (mut query, _) = self.build_query(query_str, doc_types)?;
+        if !excluded.is_empty() {
+            let (id_field, _, _, type_field, _, _) = self.get_fields();
+            let term = |field, text| -> Box<dyn Query> {
+                Box::new(TermQuery::new(
+                    Term::from_field_text(field, text),
+                    IndexRecordOption::Basic,
+                ))
+            };
+            let mut clauses = vec![(Occur::Must, query)];
+            clauses.extend(excluded.iter().map(|&(doc_type, id)| {
+                let document = BooleanQuery::new(vec![
+                    (Occur::Must, term(type_field, doc_type)),
+                    (Occur::Must, term(id_field, id)),
+                ]);
+                (Occur::MustNot, Box::new(document) as Box<dyn Query>)
+            }));
+            query = Box::new(BooleanQuery::new(clauses));
+        }
+        let addresses = searcher.search(&query, &DocSetCollector)?;
+
+        let columns = searcher
+            .segment_readers()
+            .iter()
+            .map(|reader| reader.fast_fields().i64(FIELD_CREATED_AT))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let timestamps = addresses
+            .into_iter()
+            .map(|address| {
+                columns[address.segment_ord as usize]
+                    .first(address.doc_id)
+                    .unwrap_or(0)
+            })
+            .collect();
+        Ok(timestamps)
+    }
+
     /// Get a single document by its ID.
     ///
     /// Returns the document if found, None if not found.
@@ -803,6 +1248,7 @@ impl SearchEngine {
 
             let result_type = match doc_type_str {
                 "like" => SearchResultType::Like,
+                "bookmark" => SearchResultType::Bookmark,
                 "dm" => SearchResultType::DirectMessage,
                 "grok" => SearchResultType::GrokMessage,
                 _ => SearchResultType::Tweet,
@@ -815,6 +1261,7 @@ impl SearchEngine {
                 created_at: DateTime::from_timestamp(created_at_ts, 0).unwrap_or_else(epoch_utc),
                 score: 1.0, // ID lookup has no relevance score
                 highlights: vec![],
+                match_ranges: Vec::new(),
                 metadata: parse_metadata(metadata_str),
             }))
         } else {
@@ -828,6 +1275,7 @@ impl SearchEngine {
         vec![
             self.check_index_directory(),
             self.check_index_version(),
+            self.check_supplemental_fields(),
             self.check_segment_count(),
             self.check_document_count(storage),
             self.check_sample_query(),
@@ -905,6 +1353,27 @@ impl SearchEngine {
         }
     }
 
+    fn check_supplemental_fields(&self) -> HealthCheck {
+        if self.supplemental_fields().is_some() {
+            HealthCheck {
+                category: CheckCategory::Index,
+                name: "Supplemental Text".to_string(),
+                status: CheckStatus::Pass,
+                message: "Poll options, alt text and notes indexed separately".to_string(),
+                suggestion: None,
+            }
+        } else {
+            HealthCheck {
+                category: CheckCategory::Index,
+                name: "Supplemental Text".to_string(),
+                status: CheckStatus::Warning,
+                message: "Index predates the supplemental text field; --scope is unavailable"
+                    .to_string(),
+                suggestion: Some("Run 'xf index --force' to rebuild the index".to_string()),
+            }
+        }
+    }
+
     fn check_segment_count(&self) -> HealthCheck {
         let segment_count = self.reader.searcher().segment_readers().len();
         let (status, suggestion) = if segment_count == 0 {
@@ -1065,6 +1534,42 @@ impl SearchEngine {
         self.reader.searcher().num_docs()
     }
 
+    /// Number of index segments.
+    #[must_use]
+    pub fn segment_count(&self) -> usize {
+        self.reader.searcher().segment_readers().len()
+    }
+
+    /// Disk usage of each searchable segment, largest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index metadata cannot be read.
+    pub fn segment_usage(&self) -> Result<Vec<SegmentUsage>> {
+        let mut segments: Vec<SegmentUsage> = self
+            .index
+            .searchable_segment_metas()?
+            .into_iter()
+            .map(|meta| {
+                let bytes = self.index_path.as_deref().map_or(0, |dir| {
+                    meta.list_files()
+                        .iter()
+                        .filter_map(|file| std::fs::metadata(dir.join(file)).ok())
+                        .map(|metadata| metadata.len())
+                        .sum()
+                });
+                SegmentUsage {
+                    id: meta.id().uuid_string(),
+                    docs: meta.num_docs(),
+                    deleted_docs: meta.num_deleted_docs(),
+                    bytes,
+                }
+            })
+            .collect();
+        segments.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.id.cmp(&b.id)));
+        Ok(segments)
+    }
+
     /// Delete all documents and reset the index.
     ///
     /// # Errors
@@ -1079,7 +1584,48 @@ impl SearchEngine {
     }
 }
 
-fn directory_size_bytes(path: &Path) -> std::io::Result<u64> {
+impl TextIndex for SearchEngine {
+    fn search(
+        &self,
+        query: &str,
+        doc_types: Option<&[DocType]>,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        Self::search(self, query, doc_types, limit)
+    }
+
+    fn get_by_ids(&self, lookups: &[DocLookup<'_>]) -> Result<Vec<Option<SearchResult>>> {
+        Self::get_by_ids(self, lookups)
+    }
+
+    fn match_timestamps(
+        &self,
+        query: &str,
+        doc_types: Option<&[DocType]>,
+        excluded: &[(&str, &str)],
+    ) -> Result<Vec<i64>> {
+        Self::match_timestamps(self, query, doc_types, excluded)
+    }
+
+    fn doc_count(&self) -> Result<u64> {
+        Ok(Self::doc_count(self))
+    }
+
+    fn set_snippets(&mut self, enabled: bool) {
+        self.snippets = enabled;
+    }
+
+    fn set_scope(&mut self, scope: SearchScope) {
+        self.scope = scope;
+    }
+}
+
+/// Total size of the files under a directory.
+///
+/// # Errors
+///
+/// Returns an error if the directory tree cannot be read.
+pub fn directory_size_bytes(path: &Path) -> std::io::Result<u64> {
     let mut total = 0u64;
     let mut stack = vec![path.to_path_buf()];
 
@@ -1136,25 +1682,13 @@ fn generate_prefixes(text: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::DirectMessage;
+    use crate::model::{DirectMessage, PollOption, TweetPoll};
 
     fn create_test_tweet(id: &str, text: &str) -> Tweet {
         Tweet {
-            id: id.to_string(),
-            created_at: Utc::now(),
-            full_text: text.to_string(),
             source: Some("test".to_string()),
-            favorite_count: 0,
-            retweet_count: 0,
             lang: Some("en".to_string()),
-            in_reply_to_status_id: None,
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: vec![],
-            user_mentions: vec![],
-            urls: vec![],
-            media: vec![],
+            ..Tweet::fixture(id, text)
         }
     }
 
@@ -1267,6 +1801,37 @@ mod tests {
         assert_eq!(results[0].id, "123");
     }
 
+    #[test]
+    fn test_match_timestamps() {
+        let engine = SearchEngine::open_memory().unwrap();
+        let mut writer = engine.writer(15_000_000).unwrap();
+
+        let mut tweet = create_test_tweet("1", "Rust release notes");
+        tweet.created_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let tweets = vec![tweet, create_test_tweet("2", "Unrelated")];
+        let likes = vec![create_test_like("3", Some("More rust"))];
+
+        engine.index_tweets(&mut writer, &tweets).unwrap();
+        engine.index_likes(&mut writer, &likes).unwrap();
+        writer.commit().unwrap();
+        engine.reload().unwrap();
+
+        let mut timestamps = engine.match_timestamps("rust", None, &[]).unwrap();
+        timestamps.sort_unstable();
+        assert_eq!(timestamps, vec![0, 1_700_000_000]);
+
+        let tweets_only = engine
+            .match_timestamps("rust", Some(&[DocType::Tweet]), &[])
+            .unwrap();
+        assert_eq!(tweets_only, vec![1_700_000_000]);
+
+        // Exclusions name a type and an ID: the like "3" stays unless named
+        let excluded = engine
+            .match_timestamps("rust", None, &[("tweet", "1"), ("tweet", "3")])
+            .unwrap();
+        assert_eq!(excluded, vec![0]);
+    }
+
     #[test]
     fn test_search_engine_empty_query_returns_all() {
         let engine = SearchEngine::open_memory().unwrap();
@@ -1515,27 +2080,41 @@ mod tests {
         assert_eq!(engine.doc_count(), 2);
     }
 
+    #[test]
+    fn test_reindex_replaces_existing_documents() {
+        let engine = SearchEngine::open_memory().unwrap();
+        let tweets = vec![
+            create_test_tweet("1", "Tweet one"),
+            create_test_tweet("1", "Tweet one again"),
+            create_test_tweet("2", "Tweet two"),
+        ];
+        let likes = vec![create_test_like("1", Some("Liked my own tweet"))];
+
+        for _ in 0..2 {
+            let mut writer = engine.writer(15_000_000).unwrap();
+            assert_eq!(engine.index_tweets(&mut writer, &tweets).unwrap(), 2);
+            engine.index_likes(&mut writer, &likes).unwrap();
+            writer.commit().unwrap();
+            engine.reload().unwrap();
+        }
+
+        // One copy of each tweet, and the like sharing tweet 1's ID is kept
+        assert_eq!(engine.doc_count(), 3);
+    }
+
     #[test]
     fn test_search_result_metadata() {
         let engine = SearchEngine::open_memory().unwrap();
         let mut writer = engine.writer(15_000_000).unwrap();
 
         let tweets = vec![Tweet {
-            id: "123".to_string(),
-            created_at: Utc::now(),
-            full_text: "Hello world".to_string(),
             source: Some("Web".to_string()),
             favorite_count: 10,
             retweet_count: 5,
             lang: Some("en".to_string()),
-            in_reply_to_status_id: None,
-            in_reply_to_user_id: None,
             in_reply_to_screen_name: Some("someone".to_string()),
-            is_retweet: false,
             hashtags: vec!["test".to_string()],
-            user_mentions: vec![],
-            urls: vec![],
-            media: vec![],
+            ..Tweet::fixture("123", "Hello world")
         }];
         engine.index_tweets(&mut writer, &tweets).unwrap();
         writer.commit().unwrap();
@@ -1549,6 +2128,13 @@ mod tests {
         assert_eq!(metadata["retweet_count"], 5);
         assert_eq!(metadata["in_reply_to"], "someone");
         assert_eq!(metadata["source"], "Web");
+
+        let meta = results[0].tweet_meta().unwrap();
+        assert_eq!(meta.favorite_count, 10);
+        assert_eq!(meta.in_reply_to.as_deref(), Some("someone"));
+        assert_eq!(meta.hashtags, ["test"]);
+        assert!(results[0].dm_meta().is_none());
+        assert_eq!(results[0].meta(), Some(ResultMeta::Tweet(meta)));
     }
 
     #[test]
@@ -1756,4 +2342,160 @@ mod tests {
         // The highlight should contain "Rust" (case-insensitive match)
         assert!(highlight.to_lowercase().contains("rust"));
     }
+
+    #[test]
+    fn test_search_engine_match_ranges_without_snippets() {
+        let mut engine = SearchEngine::open_memory().unwrap();
+        let mut writer = engine.writer(15_000_000).unwrap();
+        let text = "Learning Rust: rustaceans love rust";
+        engine
+            .index_tweets(&mut writer, &[create_test_tweet("1", text)])
+            .unwrap();
+        writer.commit().unwrap();
+        engine.reload().unwrap();
+
+        let ranges = |results: &[SearchResult]| {
+            results[0]
+                .match_ranges
+                .iter()
+                .map(|range| &text[range.start..range.end])
+                .collect::<Vec<_>>()
+        };
+        let results = engine.search("rust", None, 10).unwrap();
+        assert_eq!(ranges(&results), ["Rust", "rustaceans", "rust"]);
+        assert!(!results[0].highlights.is_empty());
+
+        engine.set_snippets(false);
+        let results = engine.search("rust", None, 10).unwrap();
+        assert_eq!(ranges(&results), ["Rust", "rustaceans", "rust"]);
+        assert!(results[0].highlights.is_empty());
+    }
+
+    #[test]
+    fn test_search_engine_finds_poll_options() {
+        let engine = SearchEngine::open_memory().unwrap();
+        let mut writer = engine.writer(15_000_000).unwrap();
+        let mut tweet = create_test_tweet("1", "Settle this once and for all");
+        tweet.poll = Some(TweetPoll {
+            options: ["Tabs", "Spaces"]
+                .map(|label| PollOption {
+                    label: label.to_string(),
+                    votes: None,
+                })
+                .to_vec(),
+            end_datetime: None,
+            counts_are_final: false,
+        });
+        engine.index_tweets(&mut writer, &[tweet]).unwrap();
+        writer.commit().unwrap();
+        engine.reload().unwrap();
+
+        let results = engine.search("spaces", None, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "Settle this once and for all");
+        assert_eq!(engine.search("spa", None, 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_scope_and_note_refresh() {
+        let mut storage = Storage::open_memory().unwrap();
+        let mut engine = SearchEngine::open_memory().unwrap();
+        let mut writer = engine.writer(15_000_000).unwrap();
+        let mut tweet = create_test_tweet("1", "Which editor wins?");
+        tweet.poll = Some(TweetPoll {
+            options: vec![PollOption {
+                label: "Vim".to_string(),
+                votes: None,
+            }],
+            end_datetime: None,
+            counts_are_final: false,
+        });
+        storage.store_tweets(std::slice::from_ref(&tweet)).unwrap();
+        engine.index_tweets(&mut writer, &[tweet]).unwrap();
+        writer.commit().unwrap();
+        engine.reload().unwrap();
+
+        let count =
+            |engine: &SearchEngine, query: &str| engine.search(query, None, 10).unwrap().len();
+        engine.set_scope(SearchScope::Main);
+        assert_eq!(count(&engine, "editor"), 1);
+        assert_eq!(count(&engine, "vim"), 0);
+        engine.set_scope(SearchScope::Supplemental);
+        assert_eq!(count(&engine, "editor"), 0);
+        assert_eq!(count(&engine, "vi"), 1);
+
+        storage
+            .add_note("1", "tweet", "settled the office debate")
+            .unwrap();
+        let refreshed = engine
+            .refresh_supplemental(&writer, &storage, &storage.noted_documents().unwrap())
+            .unwrap();
+        assert_eq!(refreshed, 1);
+        writer.commit().unwrap();
+        engine.reload().unwrap();
+
+        assert_eq!(count(&engine, "debate"), 1);
+        assert_eq!(count(&engine, "vim"), 1);
+        engine.set_scope(SearchScope::All);
+        let results = engine.search("office", None, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "Which editor wins?");
+        assert_eq!(engine.doc_count(), 1);
+    }
+
+    #[test]
+    fn test_match_ranges_query_syntax() {
+        let text = "Café au lait, not tea";
+        let matched = |query| {
+            match_ranges(text, query)
+                .iter()
+                .map(|range| &text[range.start..range.end])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(matched("café"), ["Café"]);
+        assert_eq!(matched("caf OR tea"), ["Café", "tea"]);
+        assert_eq!(matched("\"au lait\" -tea"), ["au", "lait"]);
+        assert_eq!(matched("lait NOT tea"), ["lait"]);
+        // Phrase queries match whole words only
+        assert!(matched("\"caf\"").is_empty());
+        assert!(matched("").is_empty());
+    }
+
+    #[test]
+    fn test_trim_to_context_windows_best_match() {
+        let text = format!(
+            "{} rust {} rust and rust {}",
+            "a".repeat(50),
+            "é".repeat(40),
+            "z".repeat(50)
+        );
+        let mut result = SearchResult {
+            result_type: SearchResultType::GrokMessage,
+            id: "1".to_string(),
+            text: text.clone(),
+            created_at: Utc::now(),
+            score: 1.0,
+            highlights: vec!["<b>rust</b>".to_string()],
+            match_ranges: match_ranges(&text, "rust"),
+            metadata: serde_json::Value::Null,
+        };
+        result.trim_to_context(30);
+
+        // The window holds the two nearby matches rather than the first one
+        assert!(result.text.starts_with('…') && result.text.ends_with('…'));
+        assert_eq!(result.text.chars().count(), 32);
+        let matched: Vec<_> = result
+            .match_ranges
+            .iter()
+            .map(|range| &result.text[range.start..range.end])
+            .collect();
+        assert_eq!(matched, ["rust", "rust"]);
+        assert!(result.highlights.is_empty());
+
+        // Short texts are untouched
+        let before = result.clone();
+        result.trim_to_context(100);
+        assert_eq!(result.text, before.text);
+        assert_eq!(result.match_ranges, before.match_ranges);
+    }
 }
@@ -0,0 +1,334 @@
+//! Built-in end-to-end check (`xf selftest`).
+//!
+//! Zips a [`SyntheticArchive`] into a scratch directory and runs the `xf`
+//! binary through import, index, embed, search, stats and export
+//! against it, comparing each output with what the archive is known to
+//! contain. Every step runs in a child process with its own database, index
+//! and home directory, so neither the user's data nor their config file can
+//! affect the outcome.
+
+use crate::config::ENV_OVERRIDES;
+use crate::parser::ArchiveParser;
+use crate::synthetic::SyntheticArchive;
+use anyhow::{Context, Result, bail, ensure};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+/// Word searched for in the lexical and semantic steps.
+pub const QUERY_WORD: &str = "garden";
+
+/// Outcome of one selftest step.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResult {
+    pub name: &'static str,
+    pub passed: bool,
+    /// What was checked, or why the step failed.
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+/// Outcome of a whole selftest run.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelftestReport {
+    pub steps: Vec<StepResult>,
+    pub passed: usize,
+    pub failed: usize,
+    /// Scratch directory; removed afterwards unless kept.
+    pub workdir: PathBuf,
+    pub runtime_ms: u64,
+}
+
+impl SelftestReport {
+    /// Whether every step passed.
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// A selftest run of the `xf` binary at `exe` inside `workdir`.
+pub struct Selftest {
+    exe: PathBuf,
+    workdir: PathBuf,
+    archive: SyntheticArchive,
+}
+
+type Step = fn(&Selftest) -> Result<String>;
+
+const STEPS: &[(&str, Step)] = &[
+    ("generate", Selftest::generate),
+    ("import", Selftest::import),
+    ("index", Selftest::index),
+    ("embed", Selftest::embed),
+    ("stats", Selftest::stats),
+    ("search (lexical)", Selftest::search_lexical),
+    ("search (semantic)", Selftest::search_semantic),
+    ("export", Selftest::export),
+];
+
+impl Selftest {
+    #[must_use]
+    pub fn new(exe: PathBuf, workdir: PathBuf) -> Self {
+        Self {
+            exe,
+            workdir,
+            archive: SyntheticArchive::default(),
+        }
+    }
+
+    /// Run every step in order, calling `on_step` as each finishes.
+    ///
+    /// A failed step skips the rest, since each builds on the one before.
+    pub fn run(&self, mut on_step: impl FnMut(&StepResult)) -> SelftestReport {
+        let start = Instant::now();
+        let mut steps = Vec::with_capacity(STEPS.len());
+
+        for (name, step) in STEPS {
+            let step_start = Instant::now();
+            let outcome = step(self);
+            let result = StepResult {
+                name,
+                passed: outcome.is_ok(),
+                detail: outcome.unwrap_or_else(|err| format!("{err:#}")),
+                duration_ms: elapsed_ms(step_start),
+            };
+            on_step(&result);
+            let passed = result.passed;
+            steps.push(result);
+            if !passed {
+                break;
+            }
+        }
+
+        let passed = steps.iter().filter(|step| step.passed).count();
+        SelftestReport {
+            failed: STEPS.len() - passed,
+            passed,
+            steps,
+            workdir: self.workdir.clone(),
+            runtime_ms: elapsed_ms(start),
+        }
+    }
+
+    fn zip_path(&self) -> PathBuf {
+        self.workdir.join("archive.zip")
+    }
+
+    fn archive_dir(&self) -> PathBuf {
+        self.workdir.join("archive")
+    }
+
+    /// Run `xf` with isolated paths and return its stdout.
+    fn xf(&self, args: &[&str]) -> Result<String> {
+        let home = self.workdir.join("home");
+        let mut command = Command::new(&self.exe);
+        command
+            .arg("--db")
+            .arg(self.workdir.join("xf.db"))
+            .arg("--index")
+            .arg(self.workdir.join("xf_index"))
+            .arg("--no-color")
+            .args(args)
+            .env("HOME", &home)
+            .env("XDG_CONFIG_HOME", home.join(".config"))
+            .env("XDG_DATA_HOME", home.join(".local/share"));
+        for name in ENV_OVERRIDES {
+            command.env_remove(name);
+        }
+
+        let output = command
+            .output()
+            .with_context(|| format!("Failed to run {}", self.exe.display()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!(
+                "'xf {}' exited with {}: {}",
+                args.join(" "),
+                output.status,
+                stderr.trim()
+            );
+        }
+        String::from_utf8(output.stdout).context("Output is not UTF-8")
+    }
+
+    fn xf_json(&self, args: &[&str]) -> Result<Value> {
+        let stdout = self.xf(args)?;
+        serde_json::from_str(&stdout)
+            .with_context(|| format!("'xf {}' did not print JSON", args.join(" ")))
+    }
+
+    fn generate(&self) -> Result<String> {
+        std::fs::create_dir_all(&self.workdir)?;
+        let files = self.archive.files();
+
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(self.zip_path())?);
+        for (name, content) in &files {
+            zip.start_file(
+                format!("data/{name}"),
+                zip::write::SimpleFileOptions::default(),
+            )?;
+            zip.write_all(content.as_bytes())?;
+        }
+        zip.finish()?;
+
+        Ok(format!("{} files, seed {}", files.len(), self.archive.seed))
+    }
+
+    fn import(&self) -> Result<String> {
+        let zip = self.zip_path();
+        let output = self.archive_dir();
+        self.xf(&[
+            "import",
+            &zip.to_string_lossy(),
+            "-o",
+            &output.to_string_lossy(),
+            "--no-index",
+        ])?;
+        for (name, _) in self.archive.files() {
+            ensure!(
+                output.join("data").join(name).is_file(),
+                "data/{name} was not extracted"
+            );
+        }
+        Ok("all data files extracted".to_string())
+    }
+
+    fn index(&self) -> Result<String> {
+        self.xf(&["index", &self.archive_dir().to_string_lossy()])?;
+        ensure!(
+            self.workdir.join("xf.db").is_file(),
+            "database was not created"
+        );
+        Ok("database and search index built".to_string())
+    }
+
+    fn embed(&self) -> Result<String> {
+        self.xf(&["embed"])?;
+        Ok("embeddings regenerated".to_string())
+    }
+
+    fn stats(&self) -> Result<String> {
+        let expected = self
+            .archive
+            .expected()
+            .context("synthetic archive has no expected counts")?;
+        let stats = self.xf_json(&["stats", "--format", "json"])?;
+        for (key, want) in [
+            ("tweets_count", expected.tweets),
+            ("likes_count", expected.likes),
+            ("dms_count", expected.dm_messages),
+            ("grok_messages_count", expected.grok_messages),
+            ("followers_count", expected.followers),
+        ] {
+            let got = stats[key].as_u64();
+            ensure!(
+                got == u64::try_from(want).ok(),
+                "{key} is {got:?}, expected {want}"
+            );
+        }
+        Ok(format!(
+            "{} tweets, {} likes, {} DMs, {} Grok messages",
+            expected.tweets, expected.likes, expected.dm_messages, expected.grok_messages
+        ))
+    }
+
+    fn search_lexical(&self) -> Result<String> {
+        let files = self.archive.files();
+        let tweets_js = files
+            .iter()
+            .find(|(name, _)| *name == "tweets.js")
+            .map(|(_, content)| content.as_str())
+            .unwrap_or_default();
+        let tweets = ArchiveParser::tweets_from_js(tweets_js)?;
+        let mut expected: Vec<String> = tweets
+            .into_iter()
+            .filter(|tweet| tweet.full_text.split(' ').any(|word| word == QUERY_WORD))
+            .map(|tweet| tweet.id)
+            .collect();
+        expected.sort();
+
+        let results = self.xf_json(&[
+            "search", QUERY_WORD, "--mode", "lexical", "--types", "tweet", "--limit", "10000",
+            "--format", "json",
+        ])?;
+        let mut found = result_ids(&results)?;
+        found.sort();
+
+        ensure!(
+            found == expected,
+            "'{QUERY_WORD}' matched {} tweets, expected {}",
+            found.len(),
+            expected.len()
+        );
+        Ok(format!("'{QUERY_WORD}' matched all {} tweets", found.len()))
+    }
+
+    fn search_semantic(&self) -> Result<String> {
+        let results = self.xf_json(&[
+            "search", QUERY_WORD, "--mode", "semantic", "--limit", "5", "--format", "json",
+        ])?;
+        let found = result_ids(&results)?;
+        ensure!(!found.is_empty(), "no semantic results for '{QUERY_WORD}'");
+        Ok(format!("{} results", found.len()))
+    }
+
+    fn export(&self) -> Result<String> {
+        let expected = self.archive.expected().map_or(0, |counts| counts.tweets);
+        let path = self.workdir.join("tweets.json");
+        self.xf(&[
+            "export",
+            "tweets",
+            "--format",
+            "json",
+            "-o",
+            &path.to_string_lossy(),
+        ])?;
+        let exported: Value = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .context("export is not valid JSON")?;
+        let count = exported.as_array().map_or(0, Vec::len);
+        ensure!(
+            count == expected,
+            "exported {count} tweets, expected {expected}"
+        );
+        Ok(format!("{count} tweets written"))
+    }
+}
+
+fn result_ids(results: &Value) -> Result<Vec<String>> {
+    let Some(items) = results.as_array() else {
+        bail!("search output is not a JSON array");
+    };
+    Ok(items
+        .iter()
+        .filter_map(|item| item["id"].as_str().map(String::from))
+        .collect())
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+/// Default scratch directory for a run: unique per process under the
+/// system temp directory.
+#[must_use]
+pub fn default_workdir() -> PathBuf {
+    std::env::temp_dir().join(format!("xf-selftest-{}", std::process::id()))
+}
+
+/// Remove a finished run's scratch directory.
+///
+/// # Errors
+///
+/// Returns an error if the directory exists and cannot be removed.
+pub fn cleanup(workdir: &Path) -> Result<()> {
+    if workdir.exists() {
+        std::fs::remove_dir_all(workdir)
+            .with_context(|| format!("Failed to remove {}", workdir.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,825 @@
+//! Archive server for other programs (`xf serve`).
+//!
+//! [`serve_mcp`] speaks the Model Context Protocol over stdio: one JSON-RPC
+//! 2.0 message per line on stdin, one response per line on stdout. LLM
+//! agents call its tools to search the archive and read tweets, threads and
+//! stats. Every call goes through [`App`], so searches run the same lexical,
+//! semantic and hybrid pipeline as `xf search`. Documents hidden with
+//! `xf hide` are never returned.
+//!
+//! [`serve_http`] answers the same questions over a small JSON HTTP API for
+//! personal web front ends:
+//!
+//! | Route | Returns |
+//! |-------|---------|
+//! | `GET /search?q=…` | [`SearchResult`](crate::model::SearchResult) list; also `mode`, `types` (comma separated), `limit`, `since`, `until` |
+//! | `GET /tweet/{id}` | [`Tweet`] |
+//! | `GET /thread/{id}` | [`Tweet`] list, oldest first |
+//! | `GET /stats` | [`ArchiveStats`](crate::model::ArchiveStats) |
+//! | `GET /export?what=…` | every tweet, like or DM (`what` = `tweets`, `likes`, `dms`); also `limit` |
+//!
+//! Failures answer with an HTTP error status and `{"error": "…"}`. The API
+//! has no authentication, so it should only listen on a loopback address.
+//! Requests whose `Host` is not that address or `localhost` are refused, so
+//! a web page can't reach the API by pointing its own domain at it (DNS
+//! rebinding).
+
+use crate::api::{DocType, SearchRequest};
+use crate::app::{App, SearchMode};
+use crate::date_parser;
+use crate::model::Tweet;
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::{debug, warn};
+
+/// MCP revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Most results one `search` call returns.
+const MAX_SEARCH_LIMIT: usize = 100;
+
+type HiddenIds = HashMap<String, HashSet<String>>;
+
+/// A JSON-RPC error answer.
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Answer MCP requests read from `input` until it closes.
+///
+/// Malformed messages, unknown methods and failing tool calls are answered
+/// on `output`; they do not stop the server.
+///
+/// # Errors
+///
+/// Returns an error if reading `input` or writing `output` fails.
+pub fn serve_mcp(app: &App, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    for line in input.lines() {
+        let line = line.context("Failed to read MCP request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_message(app, &line) {
+            serde_json::to_writer(&mut output, &response)?;
+            output.write_all(b"\n")?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The response to one message; `None` for notifications and for
+/// responses to requests this server never sends.
+fn handle_message(app: &App, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(err) => {
+            return Some(error_response(
+                &Value::Null,
+                &RpcError::new(PARSE_ERROR, format!("Parse error: {err}")),
+            ));
+        }
+    };
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        if message.get("result").is_some() || message.get("error").is_some() {
+            return None;
+        }
+        return Some(error_response(
+            id.as_ref().unwrap_or(&Value::Null),
+            &RpcError::new(INVALID_REQUEST, "Request has no method"),
+        ));
+    };
+    // Notifications (initialized, cancelled, ...) need no answer
+    let id = id?;
+
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let result = match method {
+        "initialize" => Ok(initialize(params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(app, params),
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method: {method}"),
+        )),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => error_response(&id, &err),
+    })
+}
+
+fn error_response(id: &Value, error: &RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// Agree on the client's protocol revision when this server speaks it,
+/// otherwise offer the newest one.
+fn initialize(params: &Value) -> Value {
+    let version = params["protocolVersion"]
+        .as_str()
+        .filter(|version| PROTOCOL_VERSIONS.contains(version))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "xf", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Search the user's own X (Twitter) data archive: their tweets, \
+            likes, bookmarks, DMs and Grok chats. Use search to find documents, then \
+            get_tweet or get_thread for a tweet's details and conversation.",
+    })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "search",
+            "description": "Search the archive. Returns matching documents as JSON, best match first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search query in xf syntax: words, \"exact phrases\", AND/OR/NOT, -excluded"
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["hybrid", "lexical", "semantic", "auto"],
+                        "description": "lexical matches keywords, semantic matches meaning, hybrid combines both, auto picks from the query (default: hybrid)"
+                    },
+                    "types": {
+                        "type": "array",
+                        "items": { "type": "string", "enum": ["tweet", "like", "bookmark", "dm", "grok"] },
+                        "description": "Document types to search (default: all)"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_SEARCH_LIMIT,
+                        "description": "Maximum number of results (default: 20)"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "Only documents from this date on, e.g. 2023-01-01 or \"last year\""
+                    },
+                    "until": {
+                        "type": "string",
+                        "description": "Only documents up to this date"
+                    }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "get_tweet",
+            "description": "Get one of the user's tweets by ID, with counts, links, media and reply metadata.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string", "description": "Tweet ID" } },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "get_thread",
+            "description": "Get the thread containing a tweet: its earliest archived ancestor and every archived reply, oldest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string", "description": "ID of any tweet in the thread" } },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "stats",
+            "description": "Count the archive's tweets, likes, DMs, Grok messages and followers, with its date range.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }
+    ])
+}
+
+/// Run a tool. Its failures become an `isError` result the model can read;
+/// only an unknown tool is a protocol error.
+fn call_tool(app: &App, params: &Value) -> Result<Value, RpcError> {
+    let Some(name) = params["name"].as_str() else {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            "tools/call needs a tool name",
+        ));
+    };
+    let args = &params["arguments"];
+    let outcome = match name {
+        "search" => search(app, args),
+        "get_tweet" => get_tweet(app, args),
+        "get_thread" => get_thread(app, args),
+        "stats" => app
+            .stats()
+            .and_then(|stats| Ok(serde_json::to_value(stats)?)),
+        _ => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown tool: {name}"),
+            ));
+        }
+    };
+    let (text, is_error) = match outcome {
+        Ok(value) => (value.to_string(), false),
+        Err(err) => (format!("{err:#}"), true),
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    }))
+}
+
+fn search(app: &App, args: &Value) -> Result<Value> {
+    let (request, mode) = search_request(args)?;
+    Ok(serde_json::to_value(app.search_visible(request, mode)?)?)
+}
+
+/// The request and mode described by `search` tool arguments.
+fn search_request(args: &Value) -> Result<(SearchRequest, SearchMode)> {
+    let query = required_str(args, "query")?;
+    let mode = match args["mode"].as_str() {
+        Some(mode) => mode.parse::<SearchMode>().map_err(|err| anyhow!(err))?,
+        None => SearchMode::Hybrid,
+    };
+    let types = match args["types"].as_array() {
+        Some(types) => types
+            .iter()
+            .map(|t| {
+                t.as_str().and_then(DocType::from_str).ok_or_else(|| {
+                    anyhow!("unknown document type {t}; use tweet, like, bookmark, dm or grok")
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    let limit = args["limit"]
+        .as_u64()
+        .map_or(SearchRequest::DEFAULT_LIMIT, |limit| {
+            usize::try_from(limit).unwrap_or(MAX_SEARCH_LIMIT)
+        })
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let date = |key: &str, prefer_end: bool| {
+        args[key]
+            .as_str()
+            .map(|value| {
+                date_parser::parse_date_flexible(value, prefer_end)
+                    .with_context(|| format!("{key} date '{value}' could not be parsed"))
+            })
+            .transpose()
+    };
+    let request = SearchRequest {
+        query: query.to_string(),
+        types,
+        limit,
+        since: date("since", false)?,
+        until: date("until", true)?,
+    };
+    Ok((request, mode))
+}
+
+fn get_tweet(app: &App, args: &Value) -> Result<Value> {
+    let id = required_str(args, "id")?;
+    match visible_tweet(app, id)? {
+        Some(tweet) => Ok(serde_json::to_value(tweet)?),
+        None => bail!("No tweet with ID {id} in the archive"),
+    }
+}
+
+fn get_thread(app: &App, args: &Value) -> Result<Value> {
+    let id = required_str(args, "id")?;
+    let thread = visible_thread(app, id)?;
+    if thread.is_empty() {
+        bail!("No tweet with ID {id} in the archive");
+    }
+    Ok(serde_json::to_value(thread)?)
+}
+
+/// Tweet `id`, unless it is missing or hidden.
+fn visible_tweet(app: &App, id: &str) -> Result<Option<Tweet>> {
+    let hidden = app.hidden_ids()?;
+    Ok(app
+        .tweet(id)?
+        .filter(|tweet| !is_hidden(&hidden, "tweet", &tweet.id)))
+}
+
+/// The thread containing tweet `id`, without hidden tweets.
+fn visible_thread(app: &App, id: &str) -> Result<Vec<Tweet>> {
+    let hidden = app.hidden_ids()?;
+    let mut thread = app.thread(id)?;
+    thread.retain(|tweet| !is_hidden(&hidden, "tweet", &tweet.id));
+    Ok(thread)
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    args[key]
+        .as_str()
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| anyhow!("missing required argument '{key}'"))
+}
+
+fn is_hidden(hidden: &HiddenIds, doc_type: &str, id: &str) -> bool {
+    hidden.get(doc_type).is_some_and(|ids| ids.contains(id))
+}
+
+// ============================================================================
+// HTTP
+// ============================================================================
+
+/// Answer HTTP requests on `listener`, one at a time, until the process
+/// exits.
+///
+/// With `allow_origin`, responses carry `Access-Control-Allow-Origin` so a
+/// web UI served from that origin can call the API.
+///
+/// # Errors
+///
+/// Returns an error if the listener cannot be turned into a server.
+pub fn serve_http(app: &App, listener: TcpListener, allow_origin: Option<&str>) -> Result<()> {
+    let addr = listener.local_addr()?;
+    let server = Server::from_listener(listener, None)
+        .map_err(|err| anyhow!("Failed to start HTTP server: {err}"))?;
+    for request in server.incoming_requests() {
+        let host = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Host"))
+            .map(|header| header.value.as_str());
+        let response = if host_allowed(host, addr) {
+            respond(app, request.method(), request.url())
+        } else {
+            HttpResponse::error(
+                403,
+                format!(
+                    "Host '{}' is not this server; use http://{addr}",
+                    host.unwrap_or_default()
+                ),
+            )
+        };
+        debug!(
+            method = %request.method(),
+            url = request.url(),
+            status = response.status,
+            "HTTP request"
+        );
+        if let Err(err) = send(request, &response, allow_origin) {
+            warn!("Failed to send HTTP response: {err}");
+        }
+    }
+    Ok(())
+}
+
+/// Whether a `Host` header names the server listening on `addr`: its
+/// address or `localhost`, on its port. Listening on every interface
+/// (`0.0.0.0`, `::`), any IP address on the port is accepted; names other
+/// than `localhost` never are.
+fn host_allowed(host: Option<&str>, addr: SocketAddr) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    let (name, port) = match host.rsplit_once(':') {
+        Some((name, port)) if !port.ends_with(']') => (name, port.parse::<u16>().ok()),
+        _ => (host, Some(80)),
+    };
+    if port != Some(addr.port()) {
+        return false;
+    }
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    name.eq_ignore_ascii_case("localhost")
+        || name
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip == addr.ip() || addr.ip().is_unspecified())
+}
+
+/// Status and JSON body of one HTTP response.
+#[derive(Debug)]
+struct HttpResponse {
+    status: u16,
+    body: Value,
+}
+
+impl HttpResponse {
+    fn json(body: impl Serialize) -> Self {
+        match serde_json::to_value(body) {
+            Ok(body) => Self { status: 200, body },
+            Err(err) => Self::error(500, format!("Failed to serialize response: {err}")),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    /// 200 with `result`'s value, or 500 with its error.
+    fn from_result(result: Result<impl Serialize>) -> Self {
+        result.map_or_else(|err| Self::error(500, format!("{err:#}")), Self::json)
+    }
+}
+
+fn send(request: Request, response: &HttpResponse, allow_origin: Option<&str>) -> Result<()> {
+    let mut reply = Response::from_string(response.body.to_string())
+        .with_status_code(response.status)
+        .with_header(header("Content-Type", "application/json"));
+    if response.status == 405 {
+        reply.add_header(header("Allow", "GET, OPTIONS"));
+    }
+    if let Some(origin) = allow_origin {
+        reply.add_header(header("Access-Control-Allow-Origin", origin));
+    }
+    request.respond(reply)?;
+    Ok(())
+}
+
+fn header(name: &str, value: &str) -> Header {
+    // Names are constants and values come from our own ASCII strings
+    Header::from_bytes(name, value).expect("valid HTTP header")
+}
+
+/// Route one request. `url` is the request target: path plus query string.
+fn respond(app: &App, method: &Method, url: &str) -> HttpResponse {
+    match method {
+        Method::Get => {}
+        Method::Options => {
+            return HttpResponse {
+                status: 204,
+                body: Value::Null,
+            };
+        }
+        _ => return HttpResponse::error(405, format!("{method} is not supported; use GET")),
+    }
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params: Vec<(String, String)> = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["search"] => http_search(app, &params),
+        ["tweet", id] => match visible_tweet(app, id) {
+            Ok(Some(tweet)) => HttpResponse::json(tweet),
+            Ok(None) => HttpResponse::error(404, format!("No tweet with ID {id} in the archive")),
+            Err(err) => HttpResponse::error(500, format!("{err:#}")),
+        },
+        ["thread", id] => match visible_thread(app, id) {
+            Ok(thread) if thread.is_empty() => {
+                HttpResponse::error(404, format!("No tweet with ID {id} in the archive"))
+            }
+            result => HttpResponse::from_result(result),
+        },
+        ["stats"] => HttpResponse::from_result(app.stats()),
+        ["export"] => http_export(app, &params),
+        _ => HttpResponse::error(
+            404,
+            format!(
+                "No route for {path}; try /search, /tweet/{{id}}, /thread/{{id}}, /stats or /export"
+            ),
+        ),
+    }
+}
+
+/// `/search`: query parameters map onto the `search` tool's arguments.
+fn http_search(app: &App, params: &[(String, String)]) -> HttpResponse {
+    let mut args = Map::new();
+    for (key, value) in params {
+        let arg = match key.as_str() {
+            "q" => ("query", Value::from(value.as_str())),
+            "types" => (
+                "types",
+                value.split(',').map(str::trim).map(Value::from).collect(),
+            ),
+            "limit" => match value.parse::<u64>() {
+                Ok(limit) => ("limit", Value::from(limit)),
+                Err(_) => {
+                    return HttpResponse::error(400, format!("limit '{value}' is not a number"));
+                }
+            },
+            "mode" | "since" | "until" => (key.as_str(), Value::from(value.as_str())),
+            _ => return HttpResponse::error(400, format!("Unknown search parameter '{key}'")),
+        };
+        args.insert(arg.0.to_string(), arg.1);
+    }
+    if !args.contains_key("query") {
+        return HttpResponse::error(400, "missing required parameter 'q'");
+    }
+    match search_request(&Value::Object(args)) {
+        Ok((request, mode)) => HttpResponse::from_result(app.search_visible(request, mode)),
+        Err(err) => HttpResponse::error(400, format!("{err:#}")),
+    }
+}
+
+/// `/export`: every tweet, like or DM, newest first, without hidden ones.
+fn http_export(app: &App, params: &[(String, String)]) -> HttpResponse {
+    let mut what = "tweets";
+    let mut limit = None;
+    for (key, value) in params {
+        match key.as_str() {
+            "what" => what = value,
+            "limit" => match value.parse::<usize>() {
+                Ok(n) => limit = Some(n),
+                Err(_) => {
+                    return HttpResponse::error(400, format!("limit '{value}' is not a number"));
+                }
+            },
+            _ => return HttpResponse::error(400, format!("Unknown export parameter '{key}'")),
+        }
+    }
+    let hidden = match app.hidden_ids() {
+        Ok(hidden) => hidden,
+        Err(err) => return HttpResponse::error(500, format!("{err:#}")),
+    };
+    let fetch = |doc_type: &str| {
+        limit.map(|n: usize| n.saturating_add(hidden.get(doc_type).map_or(0, HashSet::len)))
+    };
+    match what {
+        "tweets" => HttpResponse::from_result(
+            app.tweets(fetch("tweet"))
+                .map(|tweets| visible(tweets, &hidden, "tweet", |t| &t.id, limit)),
+        ),
+        "likes" => HttpResponse::from_result(
+            app.likes(fetch("like"))
+                .map(|likes| visible(likes, &hidden, "like", |l| &l.tweet_id, limit)),
+        ),
+        "dms" => HttpResponse::from_result(
+            app.dms(fetch("dm"))
+                .map(|dms| visible(dms, &hidden, "dm", |d| &d.id, limit)),
+        ),
+        _ => HttpResponse::error(
+            400,
+            format!("Cannot export '{what}'; use what=tweets, what=likes or what=dms"),
+        ),
+    }
+}
+
+/// `items` without hidden documents, cut to `limit`.
+fn visible<T>(
+    mut items: Vec<T>,
+    hidden: &HiddenIds,
+    doc_type: &str,
+    id: impl Fn(&T) -> &String,
+    limit: Option<usize>,
+) -> Vec<T> {
+    items.retain(|item| !is_hidden(hidden, doc_type, id(item)));
+    if let Some(limit) = limit {
+        items.truncate(limit);
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn indexed_app(dir: &TempDir) -> App {
+        let data = dir.path().join("archive").join("data");
+        std::fs::create_dir_all(&data).unwrap();
+        std::fs::write(
+            data.join("manifest.js"),
+            r#"window.__THAR_CONFIG = {"userInfo": {"accountId": "1", "userName": "me"},
+                "archiveInfo": {"generationDate": "2024-01-01T00:00:00.000Z"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            data.join("tweets.js"),
+            r#"window.YTD.tweets.part0 = [
+                {"tweet": {"id_str": "7", "created_at": "Fri Jan 09 15:12:21 +0000 2026",
+                    "full_text": "hello from rust"}},
+                {"tweet": {"id_str": "8", "created_at": "Fri Jan 09 15:14:00 +0000 2026",
+                    "full_text": "more rust in the thread", "in_reply_to_status_id_str": "7"}}
+            ]"#,
+        )
+        .unwrap();
+        App::index(
+            dir.path().join("archive"),
+            dir.path().join("xf.db"),
+            dir.path().join("index"),
+            |_| {},
+        )
+        .unwrap()
+    }
+
+    fn exchange(app: &App, requests: &[Value]) -> Vec<Value> {
+        let input = requests
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut output = Vec::new();
+        serve_mcp(app, input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn call(id: u64, name: &str, arguments: &Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call",
+                "params": { "name": name, "arguments": arguments } })
+    }
+
+    fn tool_json(response: &Value) -> Value {
+        assert_eq!(response["result"]["isError"], false, "{response}");
+        serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_handshake_and_tool_list() {
+        let dir = TempDir::new().unwrap();
+        let app = indexed_app(&dir);
+        let responses = exchange(
+            &app,
+            &[
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize",
+                        "params": { "protocolVersion": "2024-11-05", "capabilities": {} } }),
+                json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+                json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+                json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" }),
+            ],
+        );
+
+        // The notification gets no response
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(responses[0]["result"]["serverInfo"]["name"], "xf");
+        let tools: Vec<&str> = responses[1]["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(tools, ["search", "get_tweet", "get_thread", "stats"]);
+        assert_eq!(responses[2]["id"], 3);
+        assert_eq!(responses[2]["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_tools_read_the_archive_and_skip_hidden_tweets() {
+        let dir = TempDir::new().unwrap();
+        let app = indexed_app(&dir);
+        let responses = exchange(
+            &app,
+            &[
+                call(1, "search", &json!({ "query": "rust", "mode": "lexical" })),
+                call(2, "get_tweet", &json!({ "id": "8" })),
+                call(3, "get_thread", &json!({ "id": "8" })),
+                call(4, "stats", &json!({})),
+            ],
+        );
+        assert_eq!(tool_json(&responses[0]).as_array().unwrap().len(), 2);
+        assert_eq!(tool_json(&responses[1])["in_reply_to_status_id"], "7");
+        assert_eq!(tool_json(&responses[2]).as_array().unwrap().len(), 2);
+        assert_eq!(tool_json(&responses[3])["tweets_count"], 2);
+
+        crate::storage::Storage::open(dir.path().join("xf.db"))
+            .unwrap()
+            .hide_document("7", "tweet")
+            .unwrap();
+        let responses = exchange(
+            &app,
+            &[
+                call(1, "search", &json!({ "query": "rust", "mode": "lexical" })),
+                call(2, "get_tweet", &json!({ "id": "7" })),
+                call(3, "get_thread", &json!({ "id": "8" })),
+            ],
+        );
+        let results = tool_json(&responses[0]);
+        assert_eq!(results.as_array().unwrap().len(), 1);
+        assert_eq!(results[0]["id"], "8");
+        assert_eq!(responses[1]["result"]["isError"], true);
+        assert_eq!(tool_json(&responses[2]).as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_bad_requests_are_answered() {
+        let dir = TempDir::new().unwrap();
+        let app = indexed_app(&dir);
+        let mut output = Vec::new();
+        let input = format!(
+            "not json\n{}\n{}\n{}\n",
+            json!({ "jsonrpc": "2.0", "id": 1 }),
+            call(
+                2,
+                "search",
+                &json!({ "query": "rust", "types": ["status"] })
+            ),
+            call(3, "delete_everything", &json!({})),
+        );
+        serve_mcp(&app, input.as_bytes(), &mut output).unwrap();
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(responses[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[1]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[2]["result"]["isError"], true);
+        assert!(
+            responses[2]["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("unknown document type")
+        );
+        assert_eq!(responses[3]["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_http_routes_answer_with_json() {
+        let dir = TempDir::new().unwrap();
+        let app = indexed_app(&dir);
+        let get = |url: &str| respond(&app, &Method::Get, url);
+
+        let search = get("/search?q=rust&mode=lexical&types=tweet&limit=1");
+        assert_eq!(search.status, 200, "{:?}", search.body);
+        assert_eq!(search.body.as_array().unwrap().len(), 1);
+        assert_eq!(get("/tweet/8").body["in_reply_to_status_id"], "7");
+        assert_eq!(get("/thread/8").body.as_array().unwrap().len(), 2);
+        assert_eq!(get("/stats").body["tweets_count"], 2);
+        assert_eq!(get("/export").body.as_array().unwrap().len(), 2);
+        assert_eq!(get("/export?what=tweets&limit=1").body[0]["id"], "8");
+        assert!(
+            get("/export?what=likes")
+                .body
+                .as_array()
+                .unwrap()
+                .is_empty()
+        );
+
+        crate::storage::Storage::open(dir.path().join("xf.db"))
+            .unwrap()
+            .hide_document("8", "tweet")
+            .unwrap();
+        assert_eq!(get("/tweet/8").status, 404);
+        assert_eq!(get("/export?limit=1").body[0]["id"], "7");
+        let search = get("/search?q=rust&mode=lexical");
+        assert_eq!(search.body.as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_http_bad_requests_are_client_errors() {
+        let dir = TempDir::new().unwrap();
+        let app = indexed_app(&dir);
+        let get = |url: &str| respond(&app, &Method::Get, url);
+
+        assert_eq!(get("/search").status, 400);
+        assert_eq!(get("/search?q=rust&limit=ten").status, 400);
+        assert_eq!(get("/search?q=rust&types=status").status, 400);
+        assert_eq!(get("/search?q=rust&sort=date").status, 400);
+        assert_eq!(get("/export?what=followers").status, 400);
+        assert_eq!(get("/tweet/404").status, 404);
+        assert_eq!(get("/thread/404").status, 404);
+        let missing = get("/tweets");
+        assert_eq!(missing.status, 404);
+        assert!(missing.body["error"].as_str().unwrap().contains("/search"));
+        assert_eq!(respond(&app, &Method::Post, "/search?q=rust").status, 405);
+    }
+
+    #[test]
+    fn test_http_host_must_name_the_server() {
+        let loopback: SocketAddr = "127.0.0.1:8765".parse().unwrap();
+        assert!(host_allowed(Some("127.0.0.1:8765"), loopback));
+        assert!(host_allowed(Some("LocalHost:8765"), loopback));
+        assert!(!host_allowed(Some("localhost:9000"), loopback));
+        assert!(!host_allowed(Some("attacker.example:8765"), loopback));
+        assert!(!host_allowed(Some("10.0.0.5:8765"), loopback));
+        assert!(!host_allowed(None, loopback));
+
+        let v6: SocketAddr = "[::1]:80".parse().unwrap();
+        assert!(host_allowed(Some("[::1]"), v6));
+        assert!(host_allowed(Some("localhost"), v6));
+
+        let any: SocketAddr = "0.0.0.0:8765".parse().unwrap();
+        assert!(host_allowed(Some("192.168.1.20:8765"), any));
+        assert!(!host_allowed(Some("nas.local:8765"), any));
+    }
+}
@@ -4,11 +4,25 @@
 //! - Temporal analysis (activity patterns over time)
 //! - Engagement metrics (likes, retweets distribution)
 //! - Content analysis (media ratios, hashtags, mentions)
-
-use crate::storage::Storage;
-use crate::{Result, format_number_u64};
-use chrono::{DateTime, Datelike, NaiveDate, Utc};
+//! - Likes analysis (liked authors, like volume, overlap with following)
+//! - Follower churn across snapshots (gained/lost, follow-back ratio)
+//! - Writing style over time (sentence length, vocabulary, reading level)
+//! - Side-by-side comparison of two date ranges
+//! - CSV export of the raw series behind each section
+//! - Search trends (query matches bucketed over time)
+//! - Hashtag and term co-occurrence (lift/PMI)
+//! - Clients tweets were posted from (phone, web, automation) over time
+//! - Computing several sections at once on parallel read-only connections
+
+use crate::model::SnapshotKind;
+use crate::parser::ArchiveParser;
+use crate::storage::{LIKES_BUCKET_SQL, Storage, TWEET_ENGAGEMENT_SQL};
+use crate::{Result, ResultExt, clip_to_width, csv_escape_text, display_width, format_number_u64};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Timelike, Utc};
+use rayon::prelude::*;
 use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
 
 /// Temporal statistics showing activity patterns over time.
 #[derive(Debug, Clone, Serialize)]
@@ -39,6 +53,8 @@ pub struct TemporalStats {
     pub active_days_count: u64,
     /// Total days in archive range
     pub total_days_in_range: u64,
+    /// Stretches of days posted hours away from the usual rhythm (likely travel)
+    pub activity_shifts: Vec<ActivityShift>,
 }
 
 /// A single day's tweet count.
@@ -112,19 +128,43 @@ impl TemporalStats {
             avg_tweets_per_active_day,
             active_days_count,
             total_days_in_range,
+            activity_shifts: Self::query_activity_shifts(storage)?,
         })
     }
 
+    /// Find time-zone shifts from every tweet's timestamp and client.
+    fn query_activity_shifts(storage: &Storage) -> Result<Vec<ActivityShift>> {
+        let conn = storage.connection();
+        let mut stmt = conn.prepare(
+            "SELECT created_at, source FROM tweets WHERE created_at IS NOT NULL ORDER BY created_at",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+        let mut posts = Vec::new();
+        for row in rows {
+            let (created_at, source) = row?;
+            if let Ok(created_at) = DateTime::parse_from_rfc3339(&created_at) {
+                posts.push((created_at.with_timezone(&Utc), source));
+            }
+        }
+        Ok(detect_activity_shifts(&posts))
+    }
+
     /// Query daily tweet counts from the database.
     #[allow(clippy::cast_sign_loss)]
     fn query_daily_counts(storage: &Storage) -> Result<Vec<DailyCount>> {
-        let query = r"
+        let query = if storage.has_stats_summaries() {
+            "SELECT day, count FROM stats_daily ORDER BY day"
+        } else {
+            r"
             SELECT DATE(created_at) as day, COUNT(*) as count
             FROM tweets
             WHERE created_at IS NOT NULL
             GROUP BY day
             ORDER BY day
-        ";
+            "
+        };
 
         let conn = storage.connection();
         let mut stmt = conn.prepare(query)?;
@@ -150,13 +190,17 @@ impl TemporalStats {
     /// Query hourly distribution (tweets per hour of day).
     #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
     fn query_hourly_distribution(storage: &Storage) -> Result<[u64; 24]> {
-        let query = r"
+        let query = if storage.has_stats_summaries() {
+            "SELECT hour, count FROM stats_hourly ORDER BY hour"
+        } else {
+            r"
             SELECT CAST(strftime('%H', created_at) AS INTEGER) as hour, COUNT(*) as count
             FROM tweets
             WHERE created_at IS NOT NULL
             GROUP BY hour
             ORDER BY hour
-        ";
+            "
+        };
 
         let conn = storage.connection();
         let mut stmt = conn.prepare(query)?;
@@ -219,16 +263,272 @@ impl TemporalStats {
     }
 }
 
+// ============================================================================
+// Time-Zone Shifts
+// ============================================================================
+
+/// Days on each side of a day whose posts set its rolling posting hour.
+const SHIFT_WINDOW_DAYS: i64 = 3;
+/// Fewest posts in a rolling window (and in a shift) to judge its hours.
+const SHIFT_MIN_POSTS: u64 = 6;
+/// Smallest move of the posting hour, in hours, that counts as a shift.
+const SHIFT_MIN_HOURS: f64 = 3.0;
+/// Shortest shift reported, in days.
+const SHIFT_MIN_DAYS: i64 = 3;
+/// Longest run of days without posts a shift may span.
+const SHIFT_MAX_GAP_DAYS: i64 = 3;
+/// Least concentration (mean resultant length, 0..1) of posting hours for
+/// a mean hour to mean anything; round-the-clock posting has no rhythm.
+const SHIFT_MIN_CONCENTRATION: f64 = 0.2;
+/// Rise in the mobile share of posts, over the archive's, that counts as
+/// a change of clients.
+const SHIFT_MOBILE_RISE: f64 = 0.2;
+
+/// A stretch of days whose posting hours ran several hours off the
+/// archive's usual rhythm, as when traveling across time zones.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityShift {
+    /// First day of the shift (UTC)
+    pub start: NaiveDate,
+    /// Last day of the shift (UTC)
+    pub end: NaiveDate,
+    /// Tweets posted during the shift
+    pub tweets: u64,
+    /// How far the average posting hour moved, in hours, rounded to the
+    /// half hour. Negative means earlier in UTC, as when clocks are ahead
+    /// of home (east); positive means later (west).
+    pub shift_hours: f64,
+    /// Client most tweets of the shift were posted from
+    pub client: Option<String>,
+    /// Share of the shift's tweets posted from mobile clients (0.0-1.0)
+    pub mobile_share: f64,
+    /// Whether the clients changed too: a different main client or a much
+    /// higher mobile share than usual, which makes travel more likely than
+    /// a new sleep schedule
+    pub client_changed: bool,
+}
+
+impl ActivityShift {
+    /// Hours east (`"east"`) or west (`"west"`) of home the posting
+    /// rhythm points to.
+    #[must_use]
+    pub fn direction(&self) -> (f64, &'static str) {
+        if self.shift_hours < 0.0 {
+            (-self.shift_hours, "east")
+        } else {
+            (self.shift_hours, "west")
+        }
+    }
+}
+
+/// Sum of unit vectors for posting times of day, for circular means.
+#[derive(Debug, Clone, Copy, Default)]
+struct HourVector {
+    sin: f64,
+    cos: f64,
+    count: u64,
+}
+
+impl HourVector {
+    fn add_time(&mut self, at: DateTime<Utc>) {
+        let hour = f64::from(at.hour()) + f64::from(at.minute()) / 60.0;
+        let angle = hour / 24.0 * std::f64::consts::TAU;
+        self.sin += angle.sin();
+        self.cos += angle.cos();
+        self.count += 1;
+    }
+
+    fn add(&mut self, other: Self) {
+        self.sin += other.sin;
+        self.cos += other.cos;
+        self.count += other.count;
+    }
+
+    /// Average time of day as an angle, with how tightly posts cluster
+    /// around it (0 = spread evenly, 1 = all at one time).
+    #[allow(clippy::cast_precision_loss)]
+    fn mean(self) -> Option<(f64, f64)> {
+        if self.count == 0 {
+            return None;
+        }
+        let concentration = self.sin.hypot(self.cos) / self.count as f64;
+        Some((self.sin.atan2(self.cos), concentration))
+    }
+
+    /// Hours this vector's mean lies from `baseline` (an angle), in -12..=12.
+    fn shift_from(self, baseline: f64) -> Option<f64> {
+        let (angle, concentration) = self.mean()?;
+        if concentration < SHIFT_MIN_CONCENTRATION {
+            return None;
+        }
+        // Wrap into -PI..=PI so 23:00 and 01:00 are two hours apart
+        let diff = (angle - baseline).sin().atan2((angle - baseline).cos());
+        Some(diff / std::f64::consts::TAU * 24.0)
+    }
+}
+
+fn is_mobile_client(source: &str) -> bool {
+    ClientKind::of(source) == ClientKind::Mobile
+}
+
+/// The most used client of `posts`, and the share posted from mobile clients.
+#[allow(clippy::cast_precision_loss)]
+fn client_mix<'a>(
+    posts: impl Iterator<Item = &'a (DateTime<Utc>, Option<String>)>,
+) -> (Option<String>, f64) {
+    let mut counts: std::collections::HashMap<&str, u64> = std::collections::HashMap::new();
+    let (mut total, mut mobile) = (0u64, 0u64);
+    for (_, source) in posts {
+        total += 1;
+        if let Some(source) = source.as_deref() {
+            *counts.entry(source).or_default() += 1;
+            mobile += u64::from(is_mobile_client(source));
+        }
+    }
+    let top = counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(source, _)| source.to_string());
+    let share = if total == 0 {
+        0.0
+    } else {
+        mobile as f64 / total as f64
+    };
+    (top, share)
+}
+
+/// Find stretches of days whose posting hours moved several hours from the
+/// archive's usual rhythm.
+///
+/// Each day's rolling posting hour (a circular mean over a week of posts)
+/// is compared with the whole archive's. Days that are off by at least
+/// three hours, and whose own posts agree, form a shift once they cover
+/// three days; its size is then measured against the days outside any
+/// shift. Shifts whose clients also changed are flagged, since posting
+/// from a phone is typical while away. `posts` are (time, client) pairs in
+/// time order.
+#[must_use]
+pub fn detect_activity_shifts(posts: &[(DateTime<Utc>, Option<String>)]) -> Vec<ActivityShift> {
+    let mut home = HourVector::default();
+    let mut days: std::collections::BTreeMap<NaiveDate, HourVector> =
+        std::collections::BTreeMap::new();
+    for (at, _) in posts {
+        home.add_time(*at);
+        days.entry(at.date_naive()).or_default().add_time(*at);
+    }
+    let Some((baseline, concentration)) = home.mean() else {
+        return Vec::new();
+    };
+    if concentration < SHIFT_MIN_CONCENTRATION {
+        return Vec::new();
+    }
+
+    // Days off the usual rhythm, with the direction of the move
+    let mut flagged: Vec<(NaiveDate, bool)> = Vec::new();
+    for (&day, &own) in &days {
+        let mut window = HourVector::default();
+        let span = chrono::Duration::days(SHIFT_WINDOW_DAYS);
+        for (_, &vector) in days.range(day - span..=day + span) {
+            window.add(vector);
+        }
+        if window.count < SHIFT_MIN_POSTS {
+            continue;
+        }
+        let (Some(shift), Some(own_shift)) =
+            (window.shift_from(baseline), own.shift_from(baseline))
+        else {
+            continue;
+        };
+        // The day itself must lean the same way, so a window straddling
+        // a trip does not smear it onto the days around it
+        if shift.abs() >= SHIFT_MIN_HOURS
+            && own_shift.abs() >= SHIFT_MIN_HOURS / 2.0
+            && (shift < 0.0) == (own_shift < 0.0)
+        {
+            flagged.push((day, shift < 0.0));
+        }
+    }
+
+    // Consecutive flagged days moving the same way
+    let mut runs: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+    let mut current: Option<(NaiveDate, NaiveDate, bool)> = None;
+    for (day, east) in flagged {
+        current = match current {
+            Some((start, end, run_east))
+                if run_east == east && (day - end).num_days() <= SHIFT_MAX_GAP_DAYS + 1 =>
+            {
+                Some((start, day, east))
+            }
+            previous => {
+                if let Some((start, end, _)) = previous {
+                    runs.push((start, end));
+                }
+                Some((day, day, east))
+            }
+        };
+    }
+    if let Some((start, end, _)) = current {
+        runs.push((start, end));
+    }
+
+    runs.retain(|(start, end)| (*end - *start).num_days() + 1 >= SHIFT_MIN_DAYS);
+
+    // Measure shifts against home alone, without the days away
+    let away = |at: &DateTime<Utc>| {
+        runs.iter()
+            .any(|(start, end)| (*start..=*end).contains(&at.date_naive()))
+    };
+    let mut home = HourVector::default();
+    for (at, _) in posts.iter().filter(|(at, _)| !away(at)) {
+        home.add_time(*at);
+    }
+    let Some((baseline, _)) = home.mean() else {
+        return Vec::new();
+    };
+    let (home_client, home_mobile) = client_mix(posts.iter().filter(|(at, _)| !away(at)));
+    runs.iter()
+        .filter_map(|&(start, end)| {
+            let during = || {
+                posts
+                    .iter()
+                    .filter(move |(at, _)| (start..=end).contains(&at.date_naive()))
+            };
+            let mut vector = HourVector::default();
+            for (at, _) in during() {
+                vector.add_time(*at);
+            }
+            if vector.count < SHIFT_MIN_POSTS {
+                return None;
+            }
+            let shift = vector.shift_from(baseline)?;
+            let (client, mobile_share) = client_mix(during());
+            let client_changed = (client.is_some() && client != home_client)
+                || mobile_share >= home_mobile + SHIFT_MOBILE_RISE;
+            Some(ActivityShift {
+                start,
+                end,
+                tweets: vector.count,
+                shift_hours: (shift * 2.0).round() / 2.0,
+                client,
+                mobile_share,
+                client_changed,
+            })
+        })
+        .collect()
+}
+
 // ============================================================================
 // Engagement Analytics
 // ============================================================================
 
 /// Engagement metrics for the archive showing how tweets performed.
+///
+/// Engagement is likes + retweets + replies + quotes received.
 #[derive(Debug, Clone, Serialize)]
 pub struct EngagementStats {
     /// Distribution of likes across tweets
     pub likes_histogram: Vec<LikesBucket>,
-    /// Top N tweets by total engagement (likes + retweets)
+    /// Top N tweets by total engagement
     pub top_tweets: Vec<TopTweet>,
     /// Average engagement per tweet
     pub avg_engagement: f64,
@@ -238,6 +538,11 @@ pub struct EngagementStats {
     pub total_likes: u64,
     /// Total retweets received
     pub total_retweets: u64,
+    /// Total replies received
+    pub total_replies: u64,
+    /// Total quote tweets received; `None` when the export records no
+    /// quote counts
+    pub total_quotes: Option<u64>,
     /// Engagement trend over time (monthly averages)
     pub monthly_trend: Vec<MonthlyEngagement>,
 }
@@ -270,10 +575,24 @@ pub struct TopTweet {
     pub likes: u64,
     /// Number of retweets
     pub retweets: u64,
-    /// Total engagement (likes + retweets)
+    /// Number of replies
+    pub replies: u64,
+    /// Number of quote tweets, when the export records it
+    pub quotes: Option<u64>,
+    /// Total engagement (likes + retweets + replies + quotes)
     pub total_engagement: u64,
 }
 
+/// Archive-wide engagement sums, mean and median.
+struct EngagementTotals {
+    likes: u64,
+    retweets: u64,
+    replies: u64,
+    quotes: Option<u64>,
+    avg_engagement: f64,
+    median_engagement: u64,
+}
+
 /// Monthly engagement average.
 #[derive(Debug, Clone, Serialize)]
 pub struct MonthlyEngagement {
@@ -293,17 +612,18 @@ impl EngagementStats {
     pub fn compute(storage: &Storage, top_n: usize) -> Result<Self> {
         let likes_histogram = Self::query_likes_histogram(storage)?;
         let top_tweets = Self::query_top_tweets(storage, top_n)?;
-        let (total_likes, total_retweets, avg_engagement, median_engagement) =
-            Self::query_engagement_totals(storage)?;
+        let totals = Self::query_engagement_totals(storage)?;
         let monthly_trend = Self::query_monthly_trend(storage)?;
 
         Ok(Self {
             likes_histogram,
             top_tweets,
-            avg_engagement,
-            median_engagement,
-            total_likes,
-            total_retweets,
+            avg_engagement: totals.avg_engagement,
+            median_engagement: totals.median_engagement,
+            total_likes: totals.likes,
+            total_retweets: totals.retweets,
+            total_replies: totals.replies,
+            total_quotes: totals.quotes,
             monthly_trend,
         })
     }
@@ -321,27 +641,21 @@ impl EngagementStats {
         let total_count: i64 = conn.query_row(total_query, [], |row| row.get(0))?;
         let total_count = total_count as u64;
 
-        // Define buckets with SQL CASE logic
-        let query = r"
-            SELECT
-                CASE
-                    WHEN favorite_count = 0 THEN 0
-                    WHEN favorite_count BETWEEN 1 AND 5 THEN 1
-                    WHEN favorite_count BETWEEN 6 AND 10 THEN 2
-                    WHEN favorite_count BETWEEN 11 AND 25 THEN 3
-                    WHEN favorite_count BETWEEN 26 AND 50 THEN 4
-                    WHEN favorite_count BETWEEN 51 AND 100 THEN 5
-                    WHEN favorite_count BETWEEN 101 AND 500 THEN 6
-                    ELSE 7
-                END as bucket,
-                COUNT(*) as count
-            FROM tweets
-            WHERE favorite_count IS NOT NULL
-            GROUP BY bucket
-            ORDER BY bucket
-        ";
+        let query = if storage.has_stats_summaries() {
+            "SELECT bucket, count FROM stats_likes_buckets ORDER BY bucket".to_string()
+        } else {
+            format!(
+                r"
+                SELECT {LIKES_BUCKET_SQL} as bucket, COUNT(*) as count
+                FROM tweets
+                WHERE favorite_count IS NOT NULL
+                GROUP BY bucket
+                ORDER BY bucket
+                "
+            )
+        };
 
-        let mut stmt = conn.prepare(query)?;
+        let mut stmt = conn.prepare(&query)?;
         let rows = stmt.query_map([], |row| {
             let bucket: i64 = row.get(0)?;
             let count: i64 = row.get(1)?;
@@ -389,17 +703,20 @@ impl EngagementStats {
     /// Query top N tweets by total engagement.
     #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
     fn query_top_tweets(storage: &Storage, limit: usize) -> Result<Vec<TopTweet>> {
-        let query = r"
-            SELECT id, full_text, created_at, favorite_count, retweet_count,
-                   (COALESCE(favorite_count, 0) + COALESCE(retweet_count, 0)) as total_engagement
-            FROM tweets
-            WHERE favorite_count IS NOT NULL OR retweet_count IS NOT NULL
+        let query = format!(
+            r"
+            SELECT id, full_text, created_at, likes, retweets, replies, quotes,
+                   (COALESCE(likes, 0) + COALESCE(retweets, 0) + replies + COALESCE(quotes, 0))
+                       as total_engagement
+            FROM ({TWEET_ENGAGEMENT_SQL})
+            WHERE likes IS NOT NULL OR retweets IS NOT NULL OR replies > 0 OR quotes IS NOT NULL
             ORDER BY total_engagement DESC
             LIMIT ?
-        ";
+            "
+        );
 
         let conn = storage.connection();
-        let mut stmt = conn.prepare(query)?;
+        let mut stmt = conn.prepare(&query)?;
         let limit_i64 = i64::try_from(limit).unwrap_or(i64::MAX);
         let rows = stmt.query_map([limit_i64], |row| {
             let id: String = row.get(0)?;
@@ -407,22 +724,26 @@ impl EngagementStats {
             let created_at_str: String = row.get(2)?;
             let likes: i64 = row.get::<_, Option<i64>>(3)?.unwrap_or(0);
             let retweets: i64 = row.get::<_, Option<i64>>(4)?.unwrap_or(0);
-            let total: i64 = row.get(5)?;
-            Ok((id, full_text, created_at_str, likes, retweets, total))
+            let replies: i64 = row.get(5)?;
+            let quotes: Option<i64> = row.get(6)?;
+            let total: i64 = row.get(7)?;
+            Ok((
+                id,
+                full_text,
+                created_at_str,
+                [likes, retweets, replies],
+                quotes,
+                total,
+            ))
         })?;
 
         let mut top_tweets = Vec::new();
         for row in rows {
-            let (id, full_text, created_at_str, likes, retweets, total) = row?;
-
-            // Parse date - try ISO format first, then X format
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .or_else(|_| {
-                    DateTime::parse_from_str(&created_at_str, "%a %b %d %H:%M:%S %z %Y")
-                        .map(|dt| dt.with_timezone(&Utc))
-                })
-                .unwrap_or_else(|_| DateTime::<Utc>::from_timestamp(0, 0).unwrap());
+            let (id, full_text, created_at_str, [likes, retweets, replies], quotes, total) = row?;
+
+            // Stored as RFC 3339, but older databases may hold the archive's layout
+            let created_at = ArchiveParser::parse_date(&created_at_str)
+                .unwrap_or_else(|| DateTime::<Utc>::from_timestamp(0, 0).unwrap());
 
             // Truncate text to ~50 chars at word boundary
             let text_preview = truncate_text(&full_text, 50);
@@ -433,6 +754,8 @@ impl EngagementStats {
                 created_at,
                 likes: likes as u64,
                 retweets: retweets as u64,
+                replies: replies as u64,
+                quotes: quotes.map(|quotes| quotes as u64),
                 total_engagement: total as u64,
             });
         }
@@ -444,59 +767,84 @@ impl EngagementStats {
     ///
     /// Computes sum, average, and median engagement in two queries, reusing
     /// the count from the first query to avoid a subquery scan in the median.
-    #[allow(clippy::cast_sign_loss, clippy::cast_precision_loss)]
-    fn query_engagement_totals(storage: &Storage) -> Result<(u64, u64, f64, u64)> {
-        let query = r"
+    #[allow(clippy::cast_sign_loss)]
+    fn query_engagement_totals(storage: &Storage) -> Result<EngagementTotals> {
+        let query = format!(
+            r"
             SELECT
-                COALESCE(SUM(favorite_count), 0) as total_likes,
-                COALESCE(SUM(retweet_count), 0) as total_retweets,
-                COALESCE(AVG(favorite_count + retweet_count), 0) as avg_engagement,
+                COALESCE(SUM(likes), 0) as total_likes,
+                COALESCE(SUM(retweets), 0) as total_retweets,
+                COALESCE(SUM(replies), 0) as total_replies,
+                SUM(quotes) as total_quotes,
+                COALESCE(AVG(likes + retweets + replies + COALESCE(quotes, 0)), 0)
+                    as avg_engagement,
                 COUNT(*) as tweet_count
-            FROM tweets
-        ";
+            FROM ({TWEET_ENGAGEMENT_SQL})
+            "
+        );
 
         let conn = storage.connection();
-        let (total_likes, total_retweets, avg_engagement, tweet_count): (i64, i64, f64, i64) = conn
-            .query_row(query, [], |row| {
-                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
-            })?;
+        let (likes, retweets, replies, quotes, avg_engagement, tweet_count): (
+            i64,
+            i64,
+            i64,
+            Option<i64>,
+            f64,
+            i64,
+        ) = conn.query_row(&query, [], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })?;
 
         // Query median using pre-computed offset (avoids subquery scan)
         let median_offset = tweet_count / 2;
-        let median_query = r"
-            SELECT favorite_count + retweet_count as engagement
-            FROM tweets
-            WHERE favorite_count IS NOT NULL
+        let median_query = format!(
+            r"
+            SELECT likes + retweets + replies + COALESCE(quotes, 0) as engagement
+            FROM ({TWEET_ENGAGEMENT_SQL})
+            WHERE likes IS NOT NULL
             ORDER BY engagement
             LIMIT 1 OFFSET ?
-        ";
+            "
+        );
 
         let median: i64 = conn
-            .query_row(median_query, [median_offset], |row| row.get(0))
+            .query_row(&median_query, [median_offset], |row| row.get(0))
             .unwrap_or(0);
 
-        Ok((
-            total_likes as u64,
-            total_retweets as u64,
+        Ok(EngagementTotals {
+            likes: likes as u64,
+            retweets: retweets as u64,
+            replies: replies as u64,
+            quotes: quotes.map(|quotes| quotes as u64),
             avg_engagement,
-            median as u64,
-        ))
+            median_engagement: median as u64,
+        })
     }
 
     /// Query monthly engagement trend.
     #[allow(clippy::cast_sign_loss)]
     fn query_monthly_trend(storage: &Storage) -> Result<Vec<MonthlyEngagement>> {
-        let query = r"
+        let query = format!(
+            r"
             SELECT strftime('%Y-%m', created_at) as month,
-                   AVG(COALESCE(favorite_count, 0) + COALESCE(retweet_count, 0)) as avg_engagement
-            FROM tweets
+                   AVG(COALESCE(likes, 0) + COALESCE(retweets, 0) + replies + COALESCE(quotes, 0))
+                       as avg_engagement
+            FROM ({TWEET_ENGAGEMENT_SQL})
             WHERE created_at IS NOT NULL
             GROUP BY month
             ORDER BY month
-        ";
+            "
+        );
 
         let conn = storage.connection();
-        let mut stmt = conn.prepare(query)?;
+        let mut stmt = conn.prepare(&query)?;
         let rows = stmt.query_map([], |row| {
             let month: Option<String> = row.get(0)?;
             let avg: f64 = row.get(1)?;
@@ -741,39 +1089,33 @@ impl ContentStats {
         Ok(buckets)
     }
 
-    /// Query top hashtags from the `hashtags_json` column.
+    /// Query top hashtags from the normalized `tweet_hashtags` table.
     #[allow(clippy::cast_sign_loss)]
     fn query_top_hashtags(storage: &Storage, limit: usize) -> Result<Vec<TagCount>> {
         let conn = storage.connection();
 
-        // The hashtags are stored as JSON array in hashtags_json column
-        // We need to parse them and count
-        let query = "SELECT hashtags_json FROM tweets WHERE hashtags_json IS NOT NULL AND hashtags_json != '[]' AND hashtags_json != ''";
+        // Tags are stored lowercased, so counting is a single indexed GROUP BY
+        let query = if storage.has_stats_summaries() {
+            "SELECT tag, count FROM stats_hashtags ORDER BY count DESC, tag ASC LIMIT ?"
+        } else {
+            r"
+            SELECT tag, COUNT(*) as count
+            FROM tweet_hashtags
+            GROUP BY tag
+            ORDER BY count DESC, tag ASC
+            LIMIT ?
+            "
+        };
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
         let mut stmt = conn.prepare(query)?;
+        let rows = stmt.query_map([limit], |row| {
+            Ok(TagCount {
+                tag: row.get(0)?,
+                count: row.get::<_, i64>(1)? as u64,
+            })
+        })?;
 
-        let mut counts: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
-
-        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
-
-        for json_str in rows.flatten() {
-            // Parse JSON array of hashtags
-            if let Ok(tags) = serde_json::from_str::<Vec<String>>(&json_str) {
-                for tag in tags {
-                    let tag_lower = tag.to_lowercase();
-                    *counts.entry(tag_lower).or_default() += 1;
-                }
-            }
-        }
-
-        // Sort by count and take top N
-        let mut sorted: Vec<_> = counts.into_iter().collect();
-        sorted.sort_by(|a, b| b.1.cmp(&a.1));
-
-        Ok(sorted
-            .into_iter()
-            .take(limit)
-            .map(|(tag, count)| TagCount { tag, count })
-            .collect())
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
     }
 
     /// Query top mentions from the `mentions_json` column.
@@ -812,103 +1154,1738 @@ impl ContentStats {
     }
 }
 
-/// Format length distribution as a horizontal bar chart.
-#[must_use]
-#[allow(clippy::cast_precision_loss)]
-pub fn format_length_distribution(distribution: &[LengthBucket]) -> String {
-    let max_count = distribution.iter().map(|b| b.count).max().unwrap_or(1);
+// ============================================================================
+// Likes Analytics
+// ============================================================================
 
-    distribution
-        .iter()
-        .map(|bucket| {
-            let bar_len = if max_count > 0 {
-                let scaled = bucket.count.saturating_mul(15) / max_count;
-                usize::try_from(scaled).unwrap_or(usize::MAX)
-            } else {
-                0
-            };
-            format!(
-                "{:>7} {} {:>5.1}%",
-                bucket.label,
-                "█".repeat(bar_len),
-                bucket.percentage
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
+/// Twitter snowflake epoch (2010-11-04T01:42:54.657Z) in milliseconds.
+const SNOWFLAKE_EPOCH_MS: i64 = 1_288_834_974_657;
+
+/// Smallest tweet ID issued by the snowflake generator. Older IDs were
+/// sequential and carry no timestamp.
+const FIRST_SNOWFLAKE_ID: u64 = 29_700_859_247;
+
+/// Analytics over liked tweets: whose posts get liked and when.
+#[derive(Debug, Clone, Serialize)]
+pub struct LikesStats {
+    /// Total likes in the archive
+    pub total_likes: u64,
+    /// Likes whose author could be parsed from `expanded_url`
+    pub attributed_likes: u64,
+    /// Number of distinct liked authors
+    pub unique_authors: u64,
+    /// Most-liked authors
+    pub top_authors: Vec<LikedAuthor>,
+    /// Likes per month, bucketed by when the liked tweet was posted
+    ///
+    /// Archives do not record when a like happened, so the liked tweet's
+    /// snowflake timestamp is used as the closest available proxy.
+    pub monthly_volume: Vec<MonthlyCount>,
+    /// Overlap between liked authors and followed accounts
+    pub following_overlap: FollowingOverlap,
 }
 
-/// Format top tags as a compact inline list.
-#[must_use]
-pub fn format_top_tags(tags: &[TagCount], prefix: &str) -> String {
-    if tags.is_empty() {
-        return String::new();
-    }
+/// An author of liked tweets with their like count.
+#[derive(Debug, Clone, Serialize)]
+pub struct LikedAuthor {
+    /// Screen name (lowercased, without the leading @)
+    pub screen_name: String,
+    /// Number of liked tweets by this author
+    pub count: u64,
+    /// Whether the author is followed (None if their account ID is unknown)
+    pub followed: Option<bool>,
+}
 
-    tags.iter()
-        .take(6)
-        .map(|t| format!("{}{} ({})", prefix, t.tag, format_number_u64(t.count)))
-        .collect::<Vec<_>>()
-        .join("  ")
+/// A month with an item count.
+#[derive(Debug, Clone, Serialize)]
+pub struct MonthlyCount {
+    /// Month in YYYY-MM format
+    pub month: String,
+    /// Number of items in this month
+    pub count: u64,
 }
 
-/// Truncate text to approximately `max_len` characters at a word boundary.
-/// Uses character count, not byte count, to properly handle UTF-8.
-#[must_use]
-fn truncate_text(text: &str, max_len: usize) -> String {
-    // Normalize whitespace first
-    let text = text.replace('\n', " ").replace('\r', "");
-    let char_count = text.chars().count();
+/// How liked authors relate to followed accounts.
+///
+/// Following entries only carry account IDs, so screen names are resolved
+/// through mentions and reply targets in your own tweets. Authors that never
+/// appear there cannot be matched and are excluded from the ratio.
+#[derive(Debug, Clone, Serialize)]
+pub struct FollowingOverlap {
+    /// Liked authors whose account ID could be resolved
+    pub resolved_authors: u64,
+    /// Resolved liked authors that are also followed
+    pub followed_authors: u64,
+    /// Percentage of resolved liked authors that are followed
+    pub followed_ratio: f64,
+    /// Likes that went to tweets by followed accounts
+    pub likes_to_followed: u64,
+}
 
-    if char_count <= max_len {
-        return text;
-    }
+impl LikesStats {
+    /// Compute likes analytics from the storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if database queries fail.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn compute(storage: &Storage, top_n: usize) -> Result<Self> {
+        let conn = storage.connection();
 
-    if max_len <= 3 {
-        // Can't fit any text + "...", just truncate without ellipsis
-        return text.chars().take(max_len).collect();
-    }
+        let mut total_likes = 0u64;
+        let mut author_counts: std::collections::HashMap<String, u64> =
+            std::collections::HashMap::new();
+        let mut monthly: std::collections::BTreeMap<String, u64> =
+            std::collections::BTreeMap::new();
 
-    // Take max_len - 3 characters to leave room for "..."
-    let truncated: String = text.chars().take(max_len - 3).collect();
+        let mut stmt = conn.prepare("SELECT tweet_id, expanded_url FROM likes")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
 
-    // Try to find a word boundary (space) to break at
-    truncated.rfind(' ').map_or_else(
-        || format!("{truncated}..."),
-        |last_space| format!("{}...", &truncated[..last_space]),
-    )
-}
+        for row in rows {
+            let (tweet_id, expanded_url) = row?;
+            total_likes += 1;
+            if let Some(author) = expanded_url.as_deref().and_then(liked_author_from_url) {
+                *author_counts.entry(author).or_default() += 1;
+            }
+            if let Some(ts) = snowflake_timestamp(&tweet_id) {
+                *monthly.entry(ts.format("%Y-%m").to_string()).or_default() += 1;
+            }
+        }
 
-/// Format likes histogram as a horizontal bar chart.
-#[must_use]
-#[allow(clippy::cast_precision_loss)]
-pub fn format_likes_histogram(histogram: &[LikesBucket]) -> String {
-    let max_count = histogram.iter().map(|b| b.count).max().unwrap_or(1);
+        let attributed_likes: u64 = author_counts.values().sum();
+        let unique_authors = author_counts.len() as u64;
 
-    histogram
-        .iter()
-        .map(|bucket| {
-            let bar_len = if max_count > 0 {
-                let scaled = bucket.count.saturating_mul(20) / max_count;
-                usize::try_from(scaled).unwrap_or(usize::MAX)
-            } else {
-                0
-            };
-            format!(
-                "{:>7} {} {:>5.1}%",
-                bucket.label,
-                "█".repeat(bar_len),
-                bucket.percentage
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
+        let handle_ids = Self::query_known_handles(storage)?;
+        let following = Self::query_following_ids(storage)?;
 
-/// Generate a sparkline from monthly engagement data.
-#[must_use]
-pub fn sparkline_from_monthly(monthly: &[MonthlyEngagement], width: usize) -> String {
-    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mut resolved_authors = 0u64;
+        let mut followed_authors = 0u64;
+        let mut likes_to_followed = 0u64;
+        let mut authors: Vec<LikedAuthor> = author_counts
+            .into_iter()
+            .map(|(screen_name, count)| {
+                let followed = handle_ids
+                    .get(&screen_name)
+                    .map(|id| following.contains(id));
+                if let Some(is_followed) = followed {
+                    resolved_authors += 1;
+                    if is_followed {
+                        followed_authors += 1;
+                        likes_to_followed += count;
+                    }
+                }
+                LikedAuthor {
+                    screen_name,
+                    count,
+                    followed,
+                }
+            })
+            .collect();
+
+        authors.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.screen_name.cmp(&b.screen_name))
+        });
+        authors.truncate(top_n);
+
+        let followed_ratio = if resolved_authors > 0 {
+            (followed_authors as f64 / resolved_authors as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(Self {
+            total_likes,
+            attributed_likes,
+            unique_authors,
+            top_authors: authors,
+            monthly_volume: monthly
+                .into_iter()
+                .map(|(month, count)| MonthlyCount { month, count })
+                .collect(),
+            following_overlap: FollowingOverlap {
+                resolved_authors,
+                followed_authors,
+                followed_ratio,
+                likes_to_followed,
+            },
+        })
+    }
+
+    /// Build a lowercased screen name to account ID map from mentions and
+    /// reply targets in the user's own tweets.
+    fn query_known_handles(storage: &Storage) -> Result<std::collections::HashMap<String, String>> {
+        let conn = storage.connection();
+        let mut handles = std::collections::HashMap::new();
+
+        let mut stmt = conn.prepare(
+            "SELECT mentions_json FROM tweets WHERE mentions_json IS NOT NULL AND mentions_json != '[]' AND mentions_json != ''",
+        )?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        for json_str in rows.flatten() {
+            if let Ok(mentions) = serde_json::from_str::<Vec<serde_json::Value>>(&json_str) {
+                for mention in mentions {
+                    let screen_name = mention.get("screen_name").and_then(|v| v.as_str());
+                    let id = mention
+                        .get("id")
+                        .and_then(|v| v.as_str())
+                        .filter(|id| !id.is_empty());
+                    if let (Some(screen_name), Some(id)) = (screen_name, id) {
+                        handles.insert(screen_name.to_lowercase(), id.to_string());
+                    }
+                }
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            r"
+            SELECT DISTINCT in_reply_to_screen_name, in_reply_to_user_id
+            FROM tweets
+            WHERE in_reply_to_screen_name IS NOT NULL AND in_reply_to_user_id IS NOT NULL
+            ",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for (screen_name, id) in rows.flatten() {
+            if !id.is_empty() {
+                handles.entry(screen_name.to_lowercase()).or_insert(id);
+            }
+        }
+
+        Ok(handles)
+    }
+
+    /// Load the set of followed account IDs.
+    fn query_following_ids(storage: &Storage) -> Result<std::collections::HashSet<String>> {
+        let conn = storage.connection();
+        let mut stmt = conn.prepare("SELECT account_id FROM following")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.flatten().collect())
+    }
+}
+
+/// Extract the author's screen name from a liked tweet URL.
+///
+/// Handles `https://twitter.com/<user>/status/<id>` and the `x.com` /
+/// `mobile.twitter.com` variants. Returns `None` for anonymous links such as
+/// `https://twitter.com/i/web/status/<id>`, which X uses for most likes.
+#[must_use]
+pub fn liked_author_from_url(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let (host, path) = rest.split_once('/')?;
+    let host = host.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let host = host.strip_prefix("mobile.").unwrap_or(host);
+    if host != "twitter.com" && host != "x.com" {
+        return None;
+    }
+
+    let mut segments = path.split('/');
+    let handle = segments.next()?;
+    if segments.next()? != "status" {
+        return None;
+    }
+
+    let valid = !handle.is_empty()
+        && handle.len() <= 15
+        && handle
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !handle.eq_ignore_ascii_case("i");
+    valid.then(|| handle.to_lowercase())
+}
+
+/// Decode the creation time embedded in a snowflake tweet ID.
+///
+/// Returns `None` for non-numeric IDs and for pre-snowflake IDs (before
+/// November 2010), which carry no timestamp.
+#[must_use]
+pub fn snowflake_timestamp(id: &str) -> Option<DateTime<Utc>> {
+    let id: u64 = id.parse().ok()?;
+    if id < FIRST_SNOWFLAKE_ID {
+        return None;
+    }
+    let offset_ms = i64::try_from(id >> 22).ok()?;
+    DateTime::<Utc>::from_timestamp_millis(SNOWFLAKE_EPOCH_MS.checked_add(offset_ms)?)
+}
+
+// ============================================================================
+// Follower Churn
+// ============================================================================
+
+/// Follower changes across snapshots (one per indexed archive).
+#[derive(Debug, Clone, Serialize)]
+pub struct FollowerChurnStats {
+    /// Per-snapshot follower counts and changes, oldest first
+    pub snapshots: Vec<SnapshotChurn>,
+    /// Current followers who have been following the longest without a break
+    pub longest_standing: Vec<LongStandingFollower>,
+}
+
+/// Follower counts for one snapshot compared with the previous one.
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotChurn {
+    /// Snapshot ID
+    pub snapshot_id: i64,
+    /// When the archive for this snapshot was generated
+    pub archive_generated_at: DateTime<Utc>,
+    /// Number of followers
+    pub followers: u64,
+    /// Followers not present in the previous snapshot
+    pub gained: u64,
+    /// Followers from the previous snapshot that are gone
+    pub lost: u64,
+    /// Number of followed accounts
+    pub following: u64,
+    /// Followers that are also followed back
+    pub mutual: u64,
+    /// Percentage of followers that are followed back (None without a following list)
+    pub follow_back_ratio: Option<f64>,
+}
+
+/// A follower with an unbroken presence across recent snapshots.
+#[derive(Debug, Clone, Serialize)]
+pub struct LongStandingFollower {
+    /// Follower account ID
+    pub account_id: String,
+    /// Profile link from the archive
+    pub user_link: Option<String>,
+    /// Archive date of the first snapshot in the current streak
+    pub since: DateTime<Utc>,
+    /// Number of consecutive snapshots this follower appears in
+    pub snapshots_present: u64,
+}
+
+impl FollowerChurnStats {
+    /// Compute follower churn from recorded snapshots.
+    ///
+    /// Snapshots without a recorded follower list (e.g. `xf index --only tweet`)
+    /// are skipped so they do not show up as mass unfollows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if database queries fail.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn compute(storage: &Storage, top_n: usize) -> Result<Self> {
+        let mut snapshots = Vec::new();
+        let mut previous: Option<std::collections::HashSet<String>> = None;
+        // account_id -> (streak start, consecutive snapshots present)
+        let mut streaks: std::collections::HashMap<String, (DateTime<Utc>, u64)> =
+            std::collections::HashMap::new();
+
+        for snapshot in storage.get_snapshots()? {
+            let followers = storage.get_snapshot_accounts(snapshot.id, SnapshotKind::Follower)?;
+            if followers.is_empty() {
+                continue;
+            }
+            let following = storage.get_snapshot_accounts(snapshot.id, SnapshotKind::Following)?;
+
+            let (gained, lost) = previous.as_ref().map_or((0, 0), |prev| {
+                (
+                    followers.difference(prev).count() as u64,
+                    prev.difference(&followers).count() as u64,
+                )
+            });
+            let mutual = followers.intersection(&following).count() as u64;
+            let follow_back_ratio =
+                (!following.is_empty()).then(|| (mutual as f64 / followers.len() as f64) * 100.0);
+
+            streaks.retain(|id, _| followers.contains(id));
+            for id in &followers {
+                streaks
+                    .entry(id.clone())
+                    .or_insert((snapshot.archive_generated_at, 0))
+                    .1 += 1;
+            }
+
+            snapshots.push(SnapshotChurn {
+                snapshot_id: snapshot.id,
+                archive_generated_at: snapshot.archive_generated_at,
+                followers: followers.len() as u64,
+                gained,
+                lost,
+                following: following.len() as u64,
+                mutual,
+                follow_back_ratio,
+            });
+            previous = Some(followers);
+        }
+
+        let mut standing: Vec<(String, DateTime<Utc>, u64)> = streaks
+            .into_iter()
+            .map(|(id, (since, present))| (id, since, present))
+            .collect();
+        standing.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        standing.truncate(top_n);
+
+        let conn = storage.connection();
+        let mut stmt = conn.prepare("SELECT user_link FROM followers WHERE account_id = ?")?;
+        let mut longest_standing = Vec::with_capacity(standing.len());
+        for (account_id, since, snapshots_present) in standing {
+            let user_link: Option<String> = stmt
+                .query_row([&account_id], |row| row.get(0))
+                .unwrap_or(None);
+            longest_standing.push(LongStandingFollower {
+                account_id,
+                user_link,
+                since,
+                snapshots_present,
+            });
+        }
+
+        Ok(Self {
+            snapshots,
+            longest_standing,
+        })
+    }
+}
+
+// ============================================================================
+// Writing Style
+// ============================================================================
+
+/// Writing-style metrics per year, for a longitudinal view of how tweets changed.
+#[derive(Debug, Clone, Serialize)]
+pub struct StyleStats {
+    /// One entry per calendar year with original (non-retweet) tweets
+    pub years: Vec<YearStyle>,
+}
+
+/// Writing-style metrics for a single year.
+#[derive(Debug, Clone, Serialize)]
+pub struct YearStyle {
+    /// Calendar year
+    pub year: i32,
+    /// Original tweets written this year
+    pub tweet_count: u64,
+    /// Average words per sentence
+    pub avg_sentence_length: f64,
+    /// Number of distinct words used (case-insensitive)
+    pub vocabulary_size: u64,
+    /// Flesch-Kincaid grade level estimate
+    pub reading_grade: f64,
+    /// Percentage of tweets containing a question mark
+    pub question_ratio: f64,
+    /// Percentage of tweets containing an exclamation mark
+    pub exclamation_ratio: f64,
+}
+
+#[derive(Default)]
+struct StyleAccumulator {
+    tweets: u64,
+    sentences: u64,
+    words: u64,
+    syllables: u64,
+    questions: u64,
+    exclamations: u64,
+    vocabulary: std::collections::HashSet<String>,
+}
+
+impl StyleStats {
+    /// Compute writing-style metrics from original tweets.
+    ///
+    /// Retweets are excluded since they are not the user's own writing. URLs,
+    /// mentions, and hashtags are ignored when counting words.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if database queries fail.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn compute(storage: &Storage) -> Result<Self> {
+        let conn = storage.connection();
+        let mut stmt = conn.prepare(
+            r"
+            SELECT CAST(strftime('%Y', created_at) AS INTEGER) as year, full_text
+            FROM tweets
+            WHERE is_retweet = 0 AND created_at IS NOT NULL
+            ",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, Option<i32>>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut by_year: std::collections::BTreeMap<i32, StyleAccumulator> =
+            std::collections::BTreeMap::new();
+        for row in rows {
+            let (Some(year), text) = row? else {
+                continue;
+            };
+            let words = style_words(&text);
+            if words.is_empty() {
+                continue;
+            }
+            let acc = by_year.entry(year).or_default();
+            acc.tweets += 1;
+            acc.sentences += count_sentences(&text);
+            acc.words += words.len() as u64;
+            acc.syllables += words.iter().map(|w| count_syllables(w)).sum::<u64>();
+            acc.questions += u64::from(text.contains('?'));
+            acc.exclamations += u64::from(text.contains('!'));
+            acc.vocabulary.extend(words);
+        }
+
+        let years = by_year
+            .into_iter()
+            .map(|(year, acc)| {
+                let words_per_sentence = acc.words as f64 / acc.sentences.max(1) as f64;
+                let syllables_per_word = acc.syllables as f64 / acc.words.max(1) as f64;
+                YearStyle {
+                    year,
+                    tweet_count: acc.tweets,
+                    avg_sentence_length: words_per_sentence,
+                    vocabulary_size: acc.vocabulary.len() as u64,
+                    reading_grade: 0.39f64.mul_add(
+                        words_per_sentence,
+                        11.8f64.mul_add(syllables_per_word, -15.59),
+                    ),
+                    question_ratio: (acc.questions as f64 / acc.tweets as f64) * 100.0,
+                    exclamation_ratio: (acc.exclamations as f64 / acc.tweets as f64) * 100.0,
+                }
+            })
+            .collect();
+
+        Ok(Self { years })
+    }
+}
+
+/// Split tweet text into lowercased words, skipping URLs, mentions, and hashtags.
+fn style_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter(|token| {
+            !token.starts_with("http") && !token.starts_with('@') && !token.starts_with('#')
+        })
+        .map(|token| {
+            token
+                .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                .trim_matches('\'')
+                .to_lowercase()
+        })
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .collect()
+}
+
+/// Count sentences by words ending in terminal punctuation; trailing text
+/// without it counts as one more.
+fn count_sentences(text: &str) -> u64 {
+    let is_terminal = |token: &str| {
+        token
+            .trim_end_matches(['"', '\'', ')', '”', '’'])
+            .ends_with(['.', '!', '?', '…'])
+    };
+    let tokens: Vec<&str> = text
+        .split_whitespace()
+        .filter(|token| !token.starts_with("http"))
+        .collect();
+    let ended = tokens.iter().filter(|token| is_terminal(token)).count() as u64;
+    let trailing = tokens.last().is_some_and(|token| !is_terminal(token));
+    (ended + u64::from(trailing)).max(1)
+}
+
+/// Estimate English syllables by counting vowel groups.
+fn count_syllables(word: &str) -> u64 {
+    let mut count = 0u64;
+    let mut prev_vowel = false;
+    for c in word.chars() {
+        let vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if vowel && !prev_vowel {
+            count += 1;
+        }
+        prev_vowel = vowel;
+    }
+    // Silent trailing "e" (but not "le" as in "table")
+    if word.len() > 2 && word.ends_with('e') && !word.ends_with("le") && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+// ============================================================================
+// Period Comparison
+// ============================================================================
+
+/// Summary metrics for tweets within a date range.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodStats {
+    /// Period label as given by the user
+    pub label: String,
+    /// Start of the period (inclusive)
+    pub start: DateTime<Utc>,
+    /// End of the period (inclusive)
+    pub end: DateTime<Utc>,
+    /// Tweets in the period
+    pub tweet_count: u64,
+    /// Days with at least one tweet
+    pub active_days: u64,
+    /// Average tweets per active day
+    pub avg_tweets_per_active_day: f64,
+    /// Likes received on tweets in the period
+    pub total_likes: u64,
+    /// Retweets received on tweets in the period
+    pub total_retweets: u64,
+    /// Average engagement (likes + retweets) per tweet
+    pub avg_engagement: f64,
+    /// Percentage of tweets with media
+    pub media_ratio: f64,
+    /// Percentage of tweets with links
+    pub link_ratio: f64,
+    /// Percentage of tweets that are replies
+    pub reply_ratio: f64,
+    /// Top hashtags in the period
+    pub top_hashtags: Vec<TagCount>,
+    /// Tweets per hour of day (0-23, UTC)
+    pub hourly_distribution: [u64; 24],
+    /// Most active hour (0-23, UTC)
+    pub most_active_hour: u8,
+}
+
+/// Side-by-side comparison of two periods.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodComparison {
+    /// The first (baseline) period
+    pub a: PeriodStats,
+    /// The second period
+    pub b: PeriodStats,
+    /// Top hashtags of B that are not among the top hashtags of A
+    pub hashtags_new: Vec<String>,
+    /// Top hashtags of A that dropped out of the top hashtags of B
+    pub hashtags_dropped: Vec<String>,
+}
+
+impl PeriodComparison {
+    /// Compare two periods.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if database queries fail.
+    pub fn compute(
+        storage: &Storage,
+        a: (&str, DateTime<Utc>, DateTime<Utc>),
+        b: (&str, DateTime<Utc>, DateTime<Utc>),
+        top_n: usize,
+    ) -> Result<Self> {
+        let a = PeriodStats::compute(storage, a.0, a.1, a.2, top_n)?;
+        let b = PeriodStats::compute(storage, b.0, b.1, b.2, top_n)?;
+
+        let tags = |stats: &PeriodStats| -> std::collections::HashSet<String> {
+            stats.top_hashtags.iter().map(|t| t.tag.clone()).collect()
+        };
+        let (a_tags, b_tags) = (tags(&a), tags(&b));
+        let hashtags_new = b
+            .top_hashtags
+            .iter()
+            .filter(|t| !a_tags.contains(&t.tag))
+            .map(|t| t.tag.clone())
+            .collect();
+        let hashtags_dropped = a
+            .top_hashtags
+            .iter()
+            .filter(|t| !b_tags.contains(&t.tag))
+            .map(|t| t.tag.clone())
+            .collect();
+
+        Ok(Self {
+            a,
+            b,
+            hashtags_new,
+            hashtags_dropped,
+        })
+    }
+}
+
+impl PeriodStats {
+    /// Compute summary metrics for tweets created between `start` and `end`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if database queries fail.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        clippy::cast_possible_truncation,
+        clippy::too_many_lines
+    )]
+    pub fn compute(
+        storage: &Storage,
+        label: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        top_n: usize,
+    ) -> Result<Self> {
+        let conn = storage.connection();
+        let (from, to) = (start.to_rfc3339(), end.to_rfc3339());
+
+        let (tweet_count, active_days, total_likes, total_retweets, media, links, replies) = conn
+            .query_row(
+                r"
+                SELECT
+                    COUNT(*),
+                    COUNT(DISTINCT date(created_at)),
+                    COALESCE(SUM(favorite_count), 0),
+                    COALESCE(SUM(retweet_count), 0),
+                    COALESCE(SUM(media_json IS NOT NULL AND media_json != '[]' AND media_json != ''), 0),
+                    COALESCE(SUM(urls_json IS NOT NULL AND urls_json != '[]' AND urls_json != ''), 0),
+                    COALESCE(SUM(in_reply_to_status_id IS NOT NULL AND in_reply_to_status_id != ''), 0)
+                FROM tweets
+                WHERE created_at >= ?1 AND created_at <= ?2
+                ",
+                [&from, &to],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, i64>(5)?,
+                        row.get::<_, i64>(6)?,
+                    ))
+                },
+            )?;
+
+        let ratio = |count: i64| {
+            if tweet_count > 0 {
+                (count as f64 / tweet_count as f64) * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        let mut hourly_distribution = [0u64; 24];
+        let mut stmt = conn.prepare(
+            r"
+            SELECT CAST(strftime('%H', created_at) AS INTEGER) as hour, COUNT(*)
+            FROM tweets
+            WHERE created_at >= ?1 AND created_at <= ?2
+            GROUP BY hour
+            ",
+        )?;
+        let rows = stmt.query_map([&from, &to], |row| {
+            Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        for row in rows {
+            if let (Some(hour @ 0..=23), count) = row? {
+                hourly_distribution[hour as usize] = count as u64;
+            }
+        }
+        let most_active_hour = hourly_distribution
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(&a.0)))
+            .map_or(0, |(hour, _)| hour as u8);
+
+        let mut stmt = conn.prepare(
+            r"
+            SELECT h.tag, COUNT(*) as count
+            FROM tweet_hashtags h
+            JOIN tweets t ON t.id = h.tweet_id
+            WHERE t.created_at >= ?1 AND t.created_at <= ?2
+            GROUP BY h.tag
+            ORDER BY count DESC, h.tag ASC
+            LIMIT ?3
+            ",
+        )?;
+        let top_hashtags = stmt
+            .query_map(
+                rusqlite::params![&from, &to, i64::try_from(top_n).unwrap_or(i64::MAX)],
+                |row| {
+                    Ok(TagCount {
+                        tag: row.get(0)?,
+                        count: row.get::<_, i64>(1)? as u64,
+                    })
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            label: label.to_string(),
+            start,
+            end,
+            tweet_count: tweet_count as u64,
+            active_days: active_days as u64,
+            avg_tweets_per_active_day: if active_days > 0 {
+                tweet_count as f64 / active_days as f64
+            } else {
+                0.0
+            },
+            total_likes: total_likes as u64,
+            total_retweets: total_retweets as u64,
+            avg_engagement: if tweet_count > 0 {
+                (total_likes + total_retweets) as f64 / tweet_count as f64
+            } else {
+                0.0
+            },
+            media_ratio: ratio(media),
+            link_ratio: ratio(links),
+            reply_ratio: ratio(replies),
+            top_hashtags,
+            hourly_distribution,
+            most_active_hour,
+        })
+    }
+}
+
+// ============================================================================
+// Search Trend
+// ============================================================================
+
+/// Bucket width for search trends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum TrendInterval {
+    Day,
+    Week,
+    #[default]
+    Month,
+    Year,
+}
+
+impl TrendInterval {
+    /// Start of the bucket containing `date`.
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => {
+                date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            Self::Month => date.with_day(1).unwrap_or(date),
+            Self::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+        }
+    }
+
+    /// Start of the bucket following the one starting at `start`.
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => start + chrono::Duration::days(1),
+            Self::Week => start + chrono::Duration::days(7),
+            Self::Month => start + Months::new(1),
+            Self::Year => start + Months::new(12),
+        }
+    }
+
+    fn label(self, start: NaiveDate) -> String {
+        match self {
+            Self::Day => start.format("%Y-%m-%d").to_string(),
+            Self::Week => start.format("%G-W%V").to_string(),
+            Self::Month => start.format("%Y-%m").to_string(),
+            Self::Year => start.format("%Y").to_string(),
+        }
+    }
+}
+
+/// Match counts for a query, bucketed over time.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendSeries {
+    /// The query as given
+    pub query: String,
+    /// Bucket width
+    pub interval: TrendInterval,
+    /// Total matches, including undated ones
+    pub total_matches: u64,
+    /// Matches without a timestamp (likes), not included in any bucket
+    pub undated_matches: u64,
+    /// One entry per bucket from the first match to the last, gaps filled with zero
+    pub buckets: Vec<TrendBucket>,
+}
+
+/// A single time bucket in a trend series.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendBucket {
+    /// Bucket label (e.g., "2023-05", "2023-W19")
+    pub period: String,
+    /// First day of the bucket
+    pub start: NaiveDate,
+    /// Matches in this bucket
+    pub count: u64,
+}
+
+impl TrendSeries {
+    /// Bucket match timestamps (Unix seconds) into a continuous series.
+    ///
+    /// A timestamp of `0` marks a document without a date and is counted as undated.
+    #[must_use]
+    pub fn from_timestamps(query: &str, interval: TrendInterval, timestamps: &[i64]) -> Self {
+        let mut counts: std::collections::BTreeMap<NaiveDate, u64> =
+            std::collections::BTreeMap::new();
+        let mut undated = 0u64;
+        for &ts in timestamps {
+            match DateTime::from_timestamp(ts, 0) {
+                Some(dt) if ts != 0 => {
+                    *counts
+                        .entry(interval.bucket_start(dt.date_naive()))
+                        .or_default() += 1;
+                }
+                _ => undated += 1,
+            }
+        }
+
+        let mut buckets = Vec::new();
+        if let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) {
+            let mut start = first;
+            while start <= last {
+                buckets.push(TrendBucket {
+                    period: interval.label(start),
+                    start,
+                    count: counts.get(&start).copied().unwrap_or(0),
+                });
+                start = interval.next(start);
+            }
+        }
+
+        Self {
+            query: query.to_string(),
+            interval,
+            total_matches: timestamps.len() as u64,
+            undated_matches: undated,
+            buckets,
+        }
+    }
+
+    /// The bucket with the most matches, if any.
+    #[must_use]
+    pub fn peak(&self) -> Option<&TrendBucket> {
+        self.buckets
+            .iter()
+            .filter(|b| b.count > 0)
+            .max_by_key(|b| b.count)
+    }
+}
+
+// ============================================================================
+// Co-occurrence
+// ============================================================================
+
+/// Common English words skipped when scoring co-occurring terms.
+const COOCCUR_STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "am", "an", "and", "any", "are", "as", "at", "be",
+    "because", "been", "but", "by", "can", "could", "did", "do", "does", "don't", "for", "from",
+    "get", "got", "had", "has", "have", "he", "her", "him", "his", "how", "i", "i'm", "if", "in",
+    "into", "is", "it", "it's", "its", "just", "like", "me", "more", "my", "no", "not", "now",
+    "of", "on", "one", "or", "our", "out", "so", "some", "than", "that", "the", "their", "them",
+    "then", "there", "these", "they", "this", "to", "too", "up", "us", "was", "we", "were", "what",
+    "when", "which", "who", "why", "will", "with", "would", "you", "your",
+];
+
+/// Minimum number of shared tweets for a term to be reported.
+const COOCCUR_MIN_TERM_COUNT: u64 = 2;
+
+/// Hashtags and terms that appear alongside a given hashtag or word.
+#[derive(Debug, Clone, Serialize)]
+pub struct CooccurrenceStats {
+    /// Target as given (e.g., "#rust" or "rust")
+    pub target: String,
+    /// Whether the target was matched as a hashtag or a full-text term
+    pub target_kind: CooccurTarget,
+    /// Tweets containing the target
+    pub target_count: u64,
+    /// Total tweets in the archive
+    pub total_tweets: u64,
+    /// Hashtags that co-occur with the target
+    pub hashtags: Vec<CooccurItem>,
+    /// Words that co-occur with the target (stopwords excluded)
+    pub terms: Vec<CooccurItem>,
+}
+
+/// How a co-occurrence target is matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CooccurTarget {
+    Hashtag,
+    Term,
+}
+
+/// A hashtag or term with its co-occurrence scores.
+#[derive(Debug, Clone, Serialize)]
+pub struct CooccurItem {
+    /// Hashtag (without `#`) or word
+    pub item: String,
+    /// Tweets containing both the target and this item
+    pub count: u64,
+    /// Tweets containing this item anywhere in the archive
+    pub item_count: u64,
+    /// P(target, item) / (P(target) * P(item)); above 1 means they appear together more than chance
+    pub lift: f64,
+    /// Pointwise mutual information, `log2(lift)`
+    pub pmi: f64,
+}
+
+impl CooccurrenceStats {
+    /// Compute co-occurrence for `target`.
+    ///
+    /// A target starting with `#` is looked up in the normalized hashtag table;
+    /// anything else is matched as a full-text term.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if database queries fail.
+    pub fn compute(storage: &Storage, target: &str, top_n: usize) -> Result<Self> {
+        let conn = storage.connection();
+        let trimmed = target.trim();
+        let (target_kind, needle) = trimmed.strip_prefix('#').map_or_else(
+            || (CooccurTarget::Term, trimmed.to_lowercase()),
+            |tag| (CooccurTarget::Hashtag, tag.to_lowercase()),
+        );
+
+        let total_tweets: i64 =
+            conn.query_row("SELECT COUNT(*) FROM tweets", [], |row| row.get(0))?;
+
+        let target_ids: std::collections::HashSet<String> = match target_kind {
+            CooccurTarget::Hashtag => conn
+                .prepare("SELECT tweet_id FROM tweet_hashtags WHERE tag = ?1")?
+                .query_map([&needle], |row| row.get(0))?
+                .collect::<std::result::Result<_, _>>()?,
+            CooccurTarget::Term => {
+                // Quote the term so FTS syntax characters are matched literally
+                let fts_query = format!("full_text : \"{}\"", needle.replace('"', "\"\""));
+                conn.prepare("SELECT tweet_id FROM fts_tweets WHERE fts_tweets MATCH ?1")?
+                    .query_map([&fts_query], |row| row.get(0))?
+                    .collect::<std::result::Result<_, _>>()?
+            }
+        };
+
+        // Hashtags: global counts from the normalized table, joint counts by membership
+        let mut tag_totals: std::collections::HashMap<String, u64> =
+            std::collections::HashMap::new();
+        let mut tag_joint: std::collections::HashMap<String, u64> =
+            std::collections::HashMap::new();
+        let mut stmt = conn.prepare("SELECT tweet_id, tag FROM tweet_hashtags")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (tweet_id, tag) = row?;
+            if target_kind == CooccurTarget::Hashtag && tag == needle {
+                continue;
+            }
+            if target_ids.contains(&tweet_id) {
+                *tag_joint.entry(tag.clone()).or_default() += 1;
+            }
+            *tag_totals.entry(tag).or_default() += 1;
+        }
+
+        // Terms: document frequency across all tweets and within target tweets
+        let mut term_totals: std::collections::HashMap<String, u64> =
+            std::collections::HashMap::new();
+        let mut term_joint: std::collections::HashMap<String, u64> =
+            std::collections::HashMap::new();
+        let mut stmt = conn.prepare("SELECT id, full_text FROM tweets")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (tweet_id, text) = row?;
+            let words: std::collections::HashSet<String> = style_words(&text)
+                .into_iter()
+                .filter(|w| w.chars().count() > 2 && !COOCCUR_STOPWORDS.contains(&w.as_str()))
+                .filter(|w| !(target_kind == CooccurTarget::Term && *w == needle))
+                .collect();
+            let in_target = target_ids.contains(&tweet_id);
+            for word in words {
+                if in_target {
+                    *term_joint.entry(word.clone()).or_default() += 1;
+                }
+                *term_totals.entry(word).or_default() += 1;
+            }
+        }
+
+        let target_count = target_ids.len() as u64;
+        let total = u64::try_from(total_tweets).unwrap_or(0);
+        let hashtags = rank_cooccurrences(tag_joint, &tag_totals, target_count, total, 1, top_n);
+        let terms = rank_cooccurrences(
+            term_joint,
+            &term_totals,
+            target_count,
+            total,
+            COOCCUR_MIN_TERM_COUNT,
+            top_n,
+        );
+
+        Ok(Self {
+            target: trimmed.to_string(),
+            target_kind,
+            target_count,
+            total_tweets: total,
+            hashtags,
+            terms,
+        })
+    }
+}
+
+/// Score joint counts with lift/PMI and keep the `top_n` most frequent.
+#[allow(clippy::cast_precision_loss)]
+fn rank_cooccurrences(
+    joint: std::collections::HashMap<String, u64>,
+    totals: &std::collections::HashMap<String, u64>,
+    target_count: u64,
+    total: u64,
+    min_count: u64,
+    top_n: usize,
+) -> Vec<CooccurItem> {
+    let mut items: Vec<CooccurItem> = joint
+        .into_iter()
+        .filter(|(_, count)| *count >= min_count)
+        .map(|(item, count)| {
+            let item_count = totals.get(&item).copied().unwrap_or(count);
+            let lift = if target_count > 0 && item_count > 0 {
+                (count as f64 * total as f64) / (target_count as f64 * item_count as f64)
+            } else {
+                0.0
+            };
+            let pmi = if lift > 0.0 { lift.log2() } else { 0.0 };
+            CooccurItem {
+                item,
+                count,
+                item_count,
+                lift,
+                pmi,
+            }
+        })
+        .collect();
+
+    items.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.lift.total_cmp(&a.lift))
+            .then_with(|| a.item.cmp(&b.item))
+    });
+    items.truncate(top_n);
+    items
+}
+
+// ============================================================================
+// Sources
+// ============================================================================
+
+/// Source names that mark automated posting: scheduling and feed services,
+/// scripts, and API libraries.
+const AUTOMATED_CLIENTS: &[&str] = &[
+    "ifttt",
+    "zapier",
+    "buffer",
+    "hootsuite",
+    "dlvr.it",
+    "twitterfeed",
+    "sproutsocial",
+    "sprout social",
+    "tweepy",
+    "python",
+    "script",
+    "automat",
+];
+/// Short automation markers, matched as whole words so "Tweetbot" stays a
+/// client app.
+const AUTOMATED_WORDS: &[&str] = &["bot", "bots", "api", "cron", "later"];
+const MOBILE_CLIENTS: &[&str] = &[
+    "iphone",
+    "ipad",
+    "android",
+    "ios",
+    "mobile",
+    "blackberry",
+    "windows phone",
+];
+const WEB_CLIENTS: &[&str] = &["web", "tweetdeck", "mac", "windows", "desktop"];
+
+/// The kind of client a tweet was posted from, guessed from its source name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientKind {
+    /// Phone and tablet apps
+    Mobile,
+    /// The web app and desktop clients
+    Web,
+    /// Schedulers, feed services, bots, and scripts
+    Automated,
+    /// Anything not recognized
+    Other,
+}
+
+impl ClientKind {
+    /// Classify a source name such as "Twitter for iPhone" or "IFTTT".
+    ///
+    /// Automation is checked first, so "Buffer for iPhone" counts as
+    /// automated rather than mobile.
+    #[must_use]
+    pub fn of(source: &str) -> Self {
+        let source = source.to_lowercase();
+        let any = |names: &[&str]| names.iter().any(|name| source.contains(name));
+        let word = source
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| AUTOMATED_WORDS.contains(&word));
+        if word || any(AUTOMATED_CLIENTS) {
+            Self::Automated
+        } else if any(MOBILE_CLIENTS) {
+            Self::Mobile
+        } else if any(WEB_CLIENTS) {
+            Self::Web
+        } else {
+            Self::Other
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Mobile => "mobile",
+            Self::Web => "web",
+            Self::Automated => "automated",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// The clients tweets were posted from, overall and per year.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceStats {
+    /// Total tweets in the archive
+    pub total_tweets: u64,
+    /// Tweets the archive records no source for
+    pub unknown_count: u64,
+    /// Most used sources, most tweets first
+    pub sources: Vec<SourceCount>,
+    /// Tweets per client kind for each calendar year
+    pub years: Vec<YearSources>,
+}
+
+/// Tweets posted from one source.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceCount {
+    /// Source name as recorded in the archive (e.g., "Twitter for iPhone")
+    pub source: String,
+    pub kind: ClientKind,
+    pub count: u64,
+    /// Share of all tweets (0-100)
+    pub percentage: f64,
+    /// First tweet posted from this source
+    pub first_used: Option<DateTime<Utc>>,
+    /// Latest tweet posted from this source
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/// Tweets per client kind in one calendar year.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct YearSources {
+    pub year: i32,
+    pub total: u64,
+    pub mobile: u64,
+    pub web: u64,
+    pub automated: u64,
+    /// Unrecognized sources and tweets without one
+    pub other: u64,
+    /// Source most of the year's tweets were posted from
+    pub top_source: Option<String>,
+}
+
+impl SourceStats {
+    /// Count tweets per source and per client kind by year.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if database queries fail.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn compute(storage: &Storage, top_n: usize) -> Result<Self> {
+        let conn = storage.connection();
+        let mut stmt = conn.prepare("SELECT source, created_at FROM tweets")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })?;
+
+        let mut total_tweets = 0u64;
+        let mut unknown_count = 0u64;
+        let mut sources: std::collections::HashMap<String, SourceCount> =
+            std::collections::HashMap::new();
+        let mut years: std::collections::BTreeMap<i32, YearSources> =
+            std::collections::BTreeMap::new();
+        let mut year_sources: std::collections::HashMap<(i32, String), u64> =
+            std::collections::HashMap::new();
+        for row in rows {
+            let (source, created_at) = row?;
+            total_tweets += 1;
+            let created_at = created_at
+                .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+                .map(|value| value.with_timezone(&Utc));
+            let source = source
+                .map(|source| source.trim().to_string())
+                .filter(|source| !source.is_empty());
+            let kind = source.as_deref().map_or(ClientKind::Other, ClientKind::of);
+
+            if let Some(created_at) = created_at {
+                let year = years.entry(created_at.year()).or_default();
+                year.total += 1;
+                match kind {
+                    ClientKind::Mobile => year.mobile += 1,
+                    ClientKind::Web => year.web += 1,
+                    ClientKind::Automated => year.automated += 1,
+                    ClientKind::Other => year.other += 1,
+                }
+                if let Some(source) = &source {
+                    *year_sources
+                        .entry((created_at.year(), source.clone()))
+                        .or_default() += 1;
+                }
+            }
+
+            let Some(source) = source else {
+                unknown_count += 1;
+                continue;
+            };
+            let entry = sources
+                .entry(source.clone())
+                .or_insert_with(|| SourceCount {
+                    source,
+                    kind,
+                    count: 0,
+                    percentage: 0.0,
+                    first_used: None,
+                    last_used: None,
+                });
+            entry.count += 1;
+            if let Some(created_at) = created_at {
+                entry.first_used = Some(entry.first_used.map_or(created_at, |t| t.min(created_at)));
+                entry.last_used = Some(entry.last_used.map_or(created_at, |t| t.max(created_at)));
+            }
+        }
+
+        let mut top_by_year: std::collections::HashMap<i32, (String, u64)> =
+            std::collections::HashMap::new();
+        for ((year, source), count) in year_sources {
+            let best = top_by_year
+                .entry(year)
+                .or_insert_with(|| (source.clone(), 0));
+            if count > best.1 || (count == best.1 && source < best.0) {
+                *best = (source, count);
+            }
+        }
+        let years = years
+            .into_iter()
+            .map(|(year, mut counts)| {
+                counts.year = year;
+                counts.top_source = top_by_year.remove(&year).map(|(source, _)| source);
+                counts
+            })
+            .collect();
+
+        let mut sources: Vec<SourceCount> = sources.into_values().collect();
+        sources.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.source.cmp(&b.source)));
+        sources.truncate(top_n);
+        for source in &mut sources {
+            source.percentage = source.count as f64 / total_tweets.max(1) as f64 * 100.0;
+        }
+
+        Ok(Self {
+            total_tweets,
+            unknown_count,
+            sources,
+            years,
+        })
+    }
+}
+
+// ============================================================================
+// Parallel Computation
+// ============================================================================
+
+/// Analytics sections to compute with [`Analytics::compute`].
+#[derive(Debug, Clone, Copy, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AnalyticsSelection {
+    pub temporal: bool,
+    pub engagement: bool,
+    pub content: bool,
+    pub likes: bool,
+    pub followers: bool,
+    pub style: bool,
+    /// Length of the top-N lists in each section
+    pub top_n: usize,
+}
+
+/// Computed analytics sections; `None` for sections not selected.
+#[derive(Debug, Clone, Default)]
+pub struct Analytics {
+    pub temporal: Option<TemporalStats>,
+    pub engagement: Option<EngagementStats>,
+    pub content: Option<ContentStats>,
+    pub likes: Option<LikesStats>,
+    pub followers: Option<FollowerChurnStats>,
+    pub style: Option<StyleStats>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SectionKind {
+    Temporal,
+    Engagement,
+    Content,
+    Likes,
+    Followers,
+    Style,
+}
+
+impl SectionKind {
+    fn compute(self, storage: &Storage, top_n: usize) -> Result<Section> {
+        Ok(match self {
+            Self::Temporal => Section::Temporal(TemporalStats::compute(storage)?),
+            Self::Engagement => Section::Engagement(EngagementStats::compute(storage, top_n)?),
+            Self::Content => Section::Content(ContentStats::compute(storage, top_n)?),
+            Self::Likes => Section::Likes(LikesStats::compute(storage, top_n)?),
+            Self::Followers => Section::Followers(FollowerChurnStats::compute(storage, top_n)?),
+            Self::Style => Section::Style(StyleStats::compute(storage)?),
+        })
+    }
+}
+
+// At most one value per section exists, so boxing would buy nothing
+#[allow(clippy::large_enum_variant)]
+enum Section {
+    Temporal(TemporalStats),
+    Engagement(EngagementStats),
+    Content(ContentStats),
+    Likes(LikesStats),
+    Followers(FollowerChurnStats),
+    Style(StyleStats),
+}
+
+impl Analytics {
+    /// Compute the selected sections.
+    ///
+    /// The sections share no state, so with more than one selected each runs
+    /// on its own read-only connection in parallel and the wall time is that
+    /// of the slowest section. In-memory databases compute them in turn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a connection cannot be opened or a query fails.
+    pub fn compute(storage: &Storage, selection: &AnalyticsSelection) -> Result<Self> {
+        let kinds: Vec<SectionKind> = [
+            (selection.temporal, SectionKind::Temporal),
+            (selection.engagement, SectionKind::Engagement),
+            (selection.content, SectionKind::Content),
+            (selection.likes, SectionKind::Likes),
+            (selection.followers, SectionKind::Followers),
+            (selection.style, SectionKind::Style),
+        ]
+        .into_iter()
+        .filter_map(|(selected, kind)| selected.then_some(kind))
+        .collect();
+        let top_n = selection.top_n;
+
+        let readers = if kinds.len() > 1 {
+            storage.open_readers(kinds.len())?
+        } else {
+            Vec::new()
+        };
+        let sections: Vec<Section> = if readers.len() == kinds.len() && !readers.is_empty() {
+            kinds
+                .into_par_iter()
+                .zip(readers)
+                .map(|(kind, reader)| kind.compute(&reader, top_n))
+                .collect::<Result<_>>()?
+        } else {
+            kinds
+                .into_iter()
+                .map(|kind| kind.compute(storage, top_n))
+                .collect::<Result<_>>()?
+        };
+
+        let mut analytics = Self::default();
+        for section in sections {
+            match section {
+                Section::Temporal(stats) => analytics.temporal = Some(stats),
+                Section::Engagement(stats) => analytics.engagement = Some(stats),
+                Section::Content(stats) => analytics.content = Some(stats),
+                Section::Likes(stats) => analytics.likes = Some(stats),
+                Section::Followers(stats) => analytics.followers = Some(stats),
+                Section::Style(stats) => analytics.style = Some(stats),
+            }
+        }
+        Ok(analytics)
+    }
+}
+
+// ============================================================================
+// CSV Export
+// ============================================================================
+
+/// Write the raw series behind each computed analytics section as CSV files.
+///
+/// Only sections that were computed (`Some`) produce files. Returns the paths
+/// of the files written, in a stable order.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be created or a file cannot be written.
+#[allow(clippy::too_many_lines)]
+pub fn write_csv_series(
+    dir: &Path,
+    temporal: Option<&TemporalStats>,
+    engagement: Option<&EngagementStats>,
+    content: Option<&ContentStats>,
+    likes: Option<&LikesStats>,
+    style: Option<&StyleStats>,
+) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<(&str, String)> = Vec::new();
+
+    if let Some(t) = temporal {
+        let mut daily = String::from("date,count\n");
+        for day in &t.daily_counts {
+            let _ = writeln!(daily, "{},{}", day.date, day.count);
+        }
+        files.push(("daily_counts.csv", daily));
+
+        let mut hourly = String::from("hour,count\n");
+        for (hour, count) in t.hourly_distribution.iter().enumerate() {
+            let _ = writeln!(hourly, "{hour},{count}");
+        }
+        files.push(("hourly.csv", hourly));
+
+        let mut dow = String::from("day_of_week,count\n");
+        let days = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+        for (day, count) in days.iter().zip(t.dow_distribution.iter()) {
+            let _ = writeln!(dow, "{day},{count}");
+        }
+        files.push(("day_of_week.csv", dow));
+    }
+
+    if let Some(e) = engagement {
+        let mut monthly = String::from("month,avg_engagement\n");
+        for m in &e.monthly_trend {
+            let _ = writeln!(monthly, "{},{:.4}", m.month, m.avg_engagement);
+        }
+        files.push(("engagement_monthly.csv", monthly));
+
+        let mut histogram = String::from("bucket,min,max,count,percentage\n");
+        for b in &e.likes_histogram {
+            let _ = writeln!(
+                histogram,
+                "{},{},{},{},{:.2}",
+                csv_cell(&b.label),
+                b.min,
+                b.max,
+                b.count,
+                b.percentage
+            );
+        }
+        files.push(("likes_histogram.csv", histogram));
+
+        let mut top =
+            String::from("id,created_at,likes,retweets,replies,quotes,total_engagement,text\n");
+        for tweet in &e.top_tweets {
+            let _ = writeln!(
+                top,
+                "{},{},{},{},{},{},{},{}",
+                tweet.id,
+                tweet.created_at.to_rfc3339(),
+                tweet.likes,
+                tweet.retweets,
+                tweet.replies,
+                tweet.quotes.map(|q| q.to_string()).unwrap_or_default(),
+                tweet.total_engagement,
+                csv_cell(&tweet.text_preview)
+            );
+        }
+        files.push(("top_tweets.csv", top));
+    }
+
+    if let Some(c) = content {
+        let mut lengths = String::from("bucket,count,percentage\n");
+        for b in &c.length_distribution {
+            let _ = writeln!(
+                lengths,
+                "{},{},{:.2}",
+                csv_cell(&b.label),
+                b.count,
+                b.percentage
+            );
+        }
+        files.push(("length_distribution.csv", lengths));
+
+        let mut hashtags = String::from("hashtag,count\n");
+        for t in &c.top_hashtags {
+            let _ = writeln!(hashtags, "{},{}", csv_cell(&t.tag), t.count);
+        }
+        files.push(("top_hashtags.csv", hashtags));
+
+        let mut mentions = String::from("screen_name,count\n");
+        for t in &c.top_mentions {
+            let _ = writeln!(mentions, "{},{}", csv_cell(&t.tag), t.count);
+        }
+        files.push(("top_mentions.csv", mentions));
+    }
+
+    if let Some(l) = likes {
+        let mut monthly = String::from("month,count\n");
+        for m in &l.monthly_volume {
+            let _ = writeln!(monthly, "{},{}", m.month, m.count);
+        }
+        files.push(("likes_monthly.csv", monthly));
+
+        let mut authors = String::from("screen_name,count,followed\n");
+        for a in &l.top_authors {
+            let followed = a.followed.map(|f| f.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                authors,
+                "{},{},{followed}",
+                csv_cell(&a.screen_name),
+                a.count
+            );
+        }
+        files.push(("liked_authors.csv", authors));
+    }
+
+    if let Some(s) = style {
+        let mut yearly = String::from(
+            "year,tweet_count,avg_sentence_length,vocabulary_size,reading_grade,question_ratio,exclamation_ratio\n",
+        );
+        for y in &s.years {
+            let _ = writeln!(
+                yearly,
+                "{},{},{:.2},{},{:.2},{:.2},{:.2}",
+                y.year,
+                y.tweet_count,
+                y.avg_sentence_length,
+                y.vocabulary_size,
+                y.reading_grade,
+                y.question_ratio,
+                y.exclamation_ratio
+            );
+        }
+        files.push(("style_yearly.csv", yearly));
+    }
+
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create output directory {}", dir.display()))?;
+
+    let mut written = Vec::with_capacity(files.len());
+    for (name, body) in files {
+        let path = dir.join(name);
+        std::fs::write(&path, body)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Quote a CSV cell per RFC 4180 when it contains a comma or quote.
+fn csv_cell(value: &str) -> String {
+    let sanitized = csv_escape_text(value);
+    if sanitized.contains(',') || sanitized.contains('"') {
+        format!("\"{sanitized}\"")
+    } else {
+        sanitized
+    }
+}
+
+/// Generate a sparkline from monthly counts.
+#[must_use]
+pub fn sparkline_from_monthly_counts(monthly: &[MonthlyCount], width: usize) -> String {
+    let values: Vec<u64> = monthly.iter().map(|m| m.count).collect();
+    sparkline(&values, width)
+}
+
+/// Format length distribution as a horizontal bar chart.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_length_distribution(distribution: &[LengthBucket]) -> String {
+    let max_count = distribution.iter().map(|b| b.count).max().unwrap_or(1);
+
+    distribution
+        .iter()
+        .map(|bucket| {
+            let bar_len = if max_count > 0 {
+                let scaled = bucket.count.saturating_mul(15) / max_count;
+                usize::try_from(scaled).unwrap_or(usize::MAX)
+            } else {
+                0
+            };
+            format!(
+                "{:>7} {} {:>5.1}%",
+                bucket.label,
+                "█".repeat(bar_len),
+                bucket.percentage
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format top tags as a compact inline list.
+#[must_use]
+pub fn format_top_tags(tags: &[TagCount], prefix: &str) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+
+    tags.iter()
+        .take(6)
+        .map(|t| format!("{}{} ({})", prefix, t.tag, format_number_u64(t.count)))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// Truncate text to approximately `max_len` terminal columns at a word boundary.
+/// Measures display width and never splits a grapheme cluster.
+#[must_use]
+fn truncate_text(text: &str, max_len: usize) -> String {
+    // Normalize whitespace first
+    let text = text.replace('\n', " ").replace('\r', "");
+
+    if display_width(&text) <= max_len {
+        return text;
+    }
+
+    if max_len <= 3 {
+        // Can't fit any text + "...", just truncate without ellipsis
+        return clip_to_width(&text, max_len).to_string();
+    }
+
+    // Keep max_len - 3 columns to leave room for "..."
+    let truncated = clip_to_width(&text, max_len - 3);
+
+    // Try to find a word boundary (space) to break at
+    truncated.rfind(' ').map_or_else(
+        || format!("{truncated}..."),
+        |last_space| format!("{}...", &truncated[..last_space]),
+    )
+}
+
+/// Format likes histogram as a horizontal bar chart.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_likes_histogram(histogram: &[LikesBucket]) -> String {
+    let max_count = histogram.iter().map(|b| b.count).max().unwrap_or(1);
+
+    histogram
+        .iter()
+        .map(|bucket| {
+            let bar_len = if max_count > 0 {
+                let scaled = bucket.count.saturating_mul(20) / max_count;
+                usize::try_from(scaled).unwrap_or(usize::MAX)
+            } else {
+                0
+            };
+            format!(
+                "{:>7} {} {:>5.1}%",
+                bucket.label,
+                "█".repeat(bar_len),
+                bucket.percentage
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Generate a sparkline from monthly engagement data.
+#[must_use]
+pub fn sparkline_from_monthly(monthly: &[MonthlyEngagement], width: usize) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     let values: Vec<u64> = monthly.iter().map(|m| m.avg_engagement as u64).collect();
     sparkline(&values, width)
 }
@@ -1003,8 +2980,9 @@ pub fn format_hourly_sparkline(distribution: &[u64; 24]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{ArchiveInfo, Tweet, TweetMedia, TweetUrl, UserMention};
+    use crate::model::{ArchiveInfo, Following, Like, Tweet, TweetMedia, TweetUrl, UserMention};
     use crate::storage::Storage;
+    use chrono::TimeZone;
     use tracing::debug;
 
     #[test]
@@ -1096,21 +3074,8 @@ mod tests {
             .unwrap()
             .with_timezone(&Utc);
         Tweet {
-            id: id.to_string(),
             created_at,
-            full_text: text.to_string(),
-            source: None,
-            favorite_count: 0,
-            retweet_count: 0,
-            lang: None,
-            in_reply_to_status_id: None,
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: Vec::new(),
-            user_mentions: Vec::new(),
-            urls: Vec::new(),
-            media: Vec::new(),
+            ..Tweet::fixture(id, text)
         }
     }
 
@@ -1150,9 +3115,76 @@ mod tests {
         assert_eq!(stats.hourly_distribution[9], 2);
         assert_eq!(stats.hourly_distribution[21], 1);
         assert_eq!(stats.active_days_count, 1);
+        assert!(stats.activity_shifts.is_empty());
         debug!("test_temporal_hourly_distribution: done");
     }
 
+    /// Two posts a day for `days` days from `start`, at `hours` UTC.
+    fn daily_posts(
+        start: NaiveDate,
+        days: u32,
+        hours: [u32; 2],
+        client: &str,
+    ) -> Vec<(DateTime<Utc>, Option<String>)> {
+        (0..days)
+            .flat_map(|offset| {
+                let day = start + chrono::Duration::days(i64::from(offset));
+                hours.map(|hour| {
+                    (
+                        day.and_hms_opt(hour, 15, 0).unwrap().and_utc(),
+                        Some(client.to_string()),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_activity_shifts_find_a_trip_east() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2023, 5, d).unwrap();
+        let mut posts = daily_posts(
+            day(1) - chrono::Duration::days(40),
+            40,
+            [15, 19],
+            "X Web App",
+        );
+        // A week posting at home hours from eight time zones east
+        posts.extend(daily_posts(day(1), 7, [7, 11], "Twitter for iPhone"));
+        posts.extend(daily_posts(day(8), 20, [15, 19], "X Web App"));
+
+        let shifts = detect_activity_shifts(&posts);
+        assert_eq!(shifts.len(), 1, "{shifts:?}");
+        let shift = &shifts[0];
+        assert_eq!((shift.start, shift.end), (day(1), day(7)));
+        assert_eq!(shift.tweets, 14);
+        assert_eq!(shift.direction(), (8.0, "east"));
+        assert_eq!(shift.client.as_deref(), Some("Twitter for iPhone"));
+        assert!(shift.client_changed);
+    }
+
+    #[test]
+    fn test_activity_shifts_ignore_steady_or_scattered_posting() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        assert!(detect_activity_shifts(&daily_posts(start, 60, [9, 13], "web")).is_empty());
+        // Twelve hours apart every day: no rhythm to shift from
+        assert!(detect_activity_shifts(&daily_posts(start, 60, [3, 15], "web")).is_empty());
+        // A single odd day is not a trip
+        let mut posts = daily_posts(start, 30, [9, 13], "web");
+        posts.extend(daily_posts(
+            start + chrono::Duration::days(30),
+            1,
+            [21, 23],
+            "web",
+        ));
+        posts.extend(daily_posts(
+            start + chrono::Duration::days(31),
+            30,
+            [9, 13],
+            "web",
+        ));
+        assert!(detect_activity_shifts(&posts).is_empty());
+    }
+
     #[test]
     fn test_engagement_histogram_buckets() {
         debug!("test_engagement_histogram_buckets: setup");
@@ -1193,6 +3225,40 @@ mod tests {
         debug!("test_top_tweets_ordering: done");
     }
 
+    #[test]
+    fn test_top_tweets_count_replies_and_quotes() {
+        let mut liked = base_tweet("liked", "2023-01-03T00:00:00Z", "Liked");
+        liked.favorite_count = 10;
+        // Three replies in the archive make 8 likes outrank 10
+        let mut thread = base_tweet("thread", "2023-01-04T00:00:00Z", "Thread");
+        thread.favorite_count = 8;
+        let mut tweets = vec![liked, thread];
+        for i in 0..3 {
+            let mut reply = base_tweet(&format!("r{i}"), "2023-01-04T01:00:00Z", "More");
+            reply.in_reply_to_status_id = Some("thread".to_string());
+            tweets.push(reply);
+        }
+        // Recorded counts take precedence over the archive's own replies
+        let mut quoted = base_tweet("quoted", "2023-01-05T00:00:00Z", "Quoted");
+        quoted.reply_count = Some(20);
+        quoted.quote_count = Some(5);
+        tweets.push(quoted);
+        let storage = storage_with_tweets(&tweets, "user-1");
+
+        let stats = EngagementStats::compute(&storage, 3).unwrap();
+        let top: Vec<(&str, u64)> = stats
+            .top_tweets
+            .iter()
+            .map(|t| (t.id.as_str(), t.total_engagement))
+            .collect();
+        assert_eq!(top, vec![("quoted", 25), ("thread", 11), ("liked", 10)]);
+        assert_eq!(stats.top_tweets[0].quotes, Some(5));
+        assert_eq!(stats.top_tweets[1].replies, 3);
+        assert_eq!(stats.top_tweets[1].quotes, None);
+        assert_eq!(stats.total_replies, 23);
+        assert_eq!(stats.total_quotes, Some(5));
+    }
+
     #[test]
     fn test_content_hashtag_extraction() {
         debug!("test_content_hashtag_extraction: setup");
@@ -1221,6 +3287,7 @@ mod tests {
                     media_type: "photo".to_string(),
                     url: "https://example.com".to_string(),
                     local_path: None,
+                    alt_text: None,
                 }];
             }
             tweets.push(tweet);
@@ -1282,6 +3349,123 @@ mod tests {
         debug!("test_single_tweet_archive: done");
     }
 
+    #[test]
+    fn test_parallel_analytics_match_serial() {
+        let tweets: Vec<Tweet> = (0..40)
+            .map(|i| {
+                let mut tweet = base_tweet(
+                    &format!("t{i}"),
+                    &format!(
+                        "2023-{:02}-{:02}T{:02}:00:00Z",
+                        i % 12 + 1,
+                        i % 28 + 1,
+                        i % 24
+                    ),
+                    &format!("Tweet number {i} about #rust"),
+                );
+                tweet.favorite_count = i;
+                tweet
+            })
+            .collect();
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut storage = Storage::open(dir.path().join("xf.db")).unwrap();
+        storage.store_tweets(&tweets).unwrap();
+        let selection = AnalyticsSelection {
+            temporal: true,
+            engagement: true,
+            content: true,
+            style: true,
+            top_n: 5,
+            ..AnalyticsSelection::default()
+        };
+
+        assert_eq!(storage.open_readers(2).unwrap().len(), 2);
+        let parallel = Analytics::compute(&storage, &selection).unwrap();
+        let temporal = parallel.temporal.unwrap();
+        assert_eq!(
+            temporal.active_days_count,
+            TemporalStats::compute(&storage).unwrap().active_days_count
+        );
+        let engagement = parallel.engagement.unwrap();
+        let serial = EngagementStats::compute(&storage, 5).unwrap();
+        assert_eq!(engagement.top_tweets.len(), serial.top_tweets.len());
+        assert_eq!(engagement.top_tweets[0].id, serial.top_tweets[0].id);
+        assert_eq!(parallel.content.unwrap().total_count, 40);
+        assert!(parallel.style.is_some());
+        assert!(parallel.likes.is_none());
+        assert!(parallel.followers.is_none());
+
+        // In-memory databases fall back to one connection
+        let memory = storage_with_tweets(&tweets, "user-1");
+        assert!(memory.open_readers(2).unwrap().is_empty());
+        let serial = Analytics::compute(&memory, &selection).unwrap();
+        assert_eq!(serial.content.unwrap().total_count, 40);
+    }
+
+    #[test]
+    fn test_stats_summaries_match_live_queries() {
+        let tweets: Vec<Tweet> = (0..30)
+            .map(|i| {
+                let mut tweet = base_tweet(
+                    &format!("t{i}"),
+                    &format!("2023-03-{:02}T{:02}:00:00Z", i % 9 + 1, i % 24),
+                    "Tagged tweet",
+                );
+                tweet.favorite_count = i * 20;
+                tweet.hashtags = vec![format!("tag{}", i % 4), "Rust".to_string()];
+                tweet
+            })
+            .collect();
+        let mut storage = Storage::open_memory().unwrap();
+        storage.store_tweets(&tweets).unwrap();
+        assert!(!storage.has_stats_summaries());
+        let live_temporal = TemporalStats::compute(&storage).unwrap();
+        let live_engagement = EngagementStats::compute(&storage, 5).unwrap();
+        let live_content = ContentStats::compute(&storage, 3).unwrap();
+
+        storage.refresh_stats_summaries().unwrap();
+        assert!(storage.has_stats_summaries());
+        let temporal = TemporalStats::compute(&storage).unwrap();
+        assert_eq!(
+            temporal.daily_counts.len(),
+            live_temporal.daily_counts.len()
+        );
+        assert_eq!(
+            temporal.hourly_distribution,
+            live_temporal.hourly_distribution
+        );
+        assert_eq!(temporal.dow_distribution, live_temporal.dow_distribution);
+        let buckets = |stats: &EngagementStats| -> Vec<u64> {
+            stats.likes_histogram.iter().map(|b| b.count).collect()
+        };
+        assert_eq!(
+            buckets(&EngagementStats::compute(&storage, 5).unwrap()),
+            buckets(&live_engagement)
+        );
+        let tags = |stats: &ContentStats| -> Vec<(String, u64)> {
+            stats
+                .top_hashtags
+                .iter()
+                .map(|t| (t.tag.clone(), t.count))
+                .collect()
+        };
+        let content = ContentStats::compute(&storage, 3).unwrap();
+        assert_eq!(tags(&content), tags(&live_content));
+        assert_eq!(content.top_hashtags[0].tag, "rust");
+        assert_eq!(content.top_hashtags[0].count, 30);
+
+        // New tweets make the summaries stale until the next refresh
+        storage
+            .store_tweets(&[base_tweet("new", "2024-01-01T00:00:00Z", "Later")])
+            .unwrap();
+        assert!(!storage.has_stats_summaries());
+        let temporal = TemporalStats::compute(&storage).unwrap();
+        assert_eq!(
+            temporal.daily_counts.len(),
+            live_temporal.daily_counts.len() + 1
+        );
+    }
+
     #[test]
     fn test_temporal_stats_performance_smoke() {
         debug!("test_temporal_stats_performance_smoke: setup");
@@ -1432,6 +3616,7 @@ mod tests {
         let stats = EngagementStats::compute(&storage, 5).unwrap();
         assert_eq!(stats.total_likes, 10);
         assert_eq!(stats.total_retweets, 10);
+        assert_eq!(stats.total_quotes, None);
         assert_approx(stats.avg_engagement, 10.0, 0.01);
         debug!("test_avg_engagement_matches_totals: done");
     }
@@ -1486,4 +3671,391 @@ mod tests {
         assert!(formatted_likes.contains('0'));
         debug!("test_format_helpers: done");
     }
+
+    #[test]
+    fn test_liked_author_from_url() {
+        assert_eq!(
+            liked_author_from_url("https://twitter.com/RustLang/status/123"),
+            Some("rustlang".to_string())
+        );
+        assert_eq!(
+            liked_author_from_url("https://x.com/some_user/status/456?s=20"),
+            Some("some_user".to_string())
+        );
+        assert_eq!(
+            liked_author_from_url("https://mobile.twitter.com/dev/status/789"),
+            Some("dev".to_string())
+        );
+        assert_eq!(
+            liked_author_from_url("https://twitter.com/i/web/status/123"),
+            None
+        );
+        assert_eq!(
+            liked_author_from_url("https://example.com/a/status/1"),
+            None
+        );
+        assert_eq!(liked_author_from_url("not a url"), None);
+    }
+
+    #[test]
+    fn test_snowflake_timestamp() {
+        let ts = snowflake_timestamp("1212092628029698048").unwrap();
+        assert_eq!(ts.format("%Y-%m-%d").to_string(), "2019-12-31");
+        assert!(snowflake_timestamp("12345").is_none());
+        assert!(snowflake_timestamp("abc").is_none());
+    }
+
+    #[test]
+    fn test_likes_stats_authors_volume_and_overlap() {
+        debug!("test_likes_stats_authors_volume_and_overlap: setup");
+        let mut tweet = base_tweet("t1", "2023-06-01T00:00:00Z", "Hi @alice @bob");
+        tweet.user_mentions = vec![
+            UserMention {
+                id: "100".to_string(),
+                screen_name: "Alice".to_string(),
+                name: None,
+            },
+            UserMention {
+                id: "200".to_string(),
+                screen_name: "bob".to_string(),
+                name: None,
+            },
+        ];
+        let mut storage = storage_with_tweets(&[tweet], "user-1");
+        let like = |id: &str, url: &str| Like {
+            tweet_id: id.to_string(),
+            full_text: Some("liked".to_string()),
+            expanded_url: Some(url.to_string()),
+        };
+        storage
+            .store_likes(&[
+                like("1212092628029698048", "https://twitter.com/alice/status/1"),
+                like("1212092628029698049", "https://twitter.com/Alice/status/2"),
+                like("1650000000000000000", "https://twitter.com/bob/status/3"),
+                like("1650000000000000001", "https://twitter.com/carol/status/4"),
+                like("1650000000000000002", "https://twitter.com/i/web/status/5"),
+            ])
+            .unwrap();
+        storage
+            .store_following(&[Following {
+                account_id: "100".to_string(),
+                user_link: None,
+            }])
+            .unwrap();
+
+        let stats = LikesStats::compute(&storage, 10).unwrap();
+        assert_eq!(stats.total_likes, 5);
+        assert_eq!(stats.attributed_likes, 4);
+        assert_eq!(stats.unique_authors, 3);
+        assert_eq!(stats.top_authors[0].screen_name, "alice");
+        assert_eq!(stats.top_authors[0].count, 2);
+        assert_eq!(stats.top_authors[0].followed, Some(true));
+
+        let months: Vec<&str> = stats
+            .monthly_volume
+            .iter()
+            .map(|m| m.month.as_str())
+            .collect();
+        assert_eq!(months, vec!["2019-12", "2023-04"]);
+        assert_eq!(stats.monthly_volume[1].count, 3);
+
+        let overlap = &stats.following_overlap;
+        assert_eq!(overlap.resolved_authors, 2);
+        assert_eq!(overlap.followed_authors, 1);
+        assert_eq!(overlap.likes_to_followed, 2);
+        assert_approx(overlap.followed_ratio, 50.0, 0.01);
+        debug!("test_likes_stats_authors_volume_and_overlap: done");
+    }
+
+    #[test]
+    fn test_follower_churn_across_snapshots() {
+        debug!("test_follower_churn_across_snapshots: setup");
+        let mut storage = storage_with_tweets(&[], "user-1");
+        let mut info = storage.get_archive_info().unwrap().unwrap();
+        let snapshot_lists: [(&str, &[&str], &[&str]); 3] = [
+            ("2022-01-01T00:00:00Z", &["a", "b", "c"], &["a"]),
+            ("2023-01-01T00:00:00Z", &["a", "b", "d", "e"], &["a", "d"]),
+            ("2024-01-01T00:00:00Z", &["a", "d", "e", "f"], &[]),
+        ];
+        for (generated, followers, following) in snapshot_lists {
+            info.generation_date = DateTime::parse_from_rfc3339(generated)
+                .unwrap()
+                .with_timezone(&Utc);
+            let id = storage.begin_snapshot(&info).unwrap();
+            storage
+                .store_snapshot_accounts(id, SnapshotKind::Follower, followers.iter().copied())
+                .unwrap();
+            storage
+                .store_snapshot_accounts(id, SnapshotKind::Following, following.iter().copied())
+                .unwrap();
+        }
+
+        let stats = FollowerChurnStats::compute(&storage, 2).unwrap();
+        assert_eq!(stats.snapshots.len(), 3);
+        assert_eq!(stats.snapshots[0].gained, 0);
+        assert_eq!((stats.snapshots[1].gained, stats.snapshots[1].lost), (2, 1));
+        assert_eq!((stats.snapshots[2].gained, stats.snapshots[2].lost), (1, 1));
+        assert_approx(stats.snapshots[1].follow_back_ratio.unwrap(), 50.0, 0.01);
+        assert!(stats.snapshots[2].follow_back_ratio.is_none());
+
+        assert_eq!(stats.longest_standing.len(), 2);
+        assert_eq!(stats.longest_standing[0].account_id, "a");
+        assert_eq!(stats.longest_standing[0].snapshots_present, 3);
+        assert_eq!(stats.longest_standing[1].account_id, "d");
+        debug!("test_follower_churn_across_snapshots: done");
+    }
+
+    #[test]
+    fn test_style_helpers() {
+        assert_eq!(count_sentences("Hello there. How are you?"), 2);
+        assert_eq!(count_sentences("No punctuation here"), 1);
+        assert_eq!(count_sentences("Wow!! See https://example.com/a.b now"), 2);
+        assert_eq!(count_syllables("cat"), 1);
+        assert_eq!(count_syllables("table"), 2);
+        assert_eq!(count_syllables("make"), 1);
+        assert_eq!(count_syllables("beautiful"), 3);
+        assert_eq!(
+            style_words("Hi @bob, check #rust at https://t.co/x! Don't stop."),
+            vec!["hi", "check", "at", "don't", "stop"]
+        );
+    }
+
+    #[test]
+    fn test_style_stats_per_year() {
+        debug!("test_style_stats_per_year: setup");
+        let mut retweet = base_tweet("t0", "2022-03-01T00:00:00Z", "RT @x: ignored words here");
+        retweet.is_retweet = true;
+        let tweets = vec![
+            retweet,
+            base_tweet("t1", "2022-03-01T00:00:00Z", "Is this thing on?"),
+            base_tweet("t2", "2022-04-01T00:00:00Z", "It is on. Great!"),
+            base_tweet("t3", "2023-05-01T00:00:00Z", "Hello hello hello"),
+        ];
+        let storage = storage_with_tweets(&tweets, "user-1");
+        let stats = StyleStats::compute(&storage).unwrap();
+        assert_eq!(stats.years.len(), 2);
+
+        let y2022 = &stats.years[0];
+        assert_eq!(y2022.year, 2022);
+        assert_eq!(y2022.tweet_count, 2);
+        // "is this thing on it great" -> 6 distinct words
+        assert_eq!(y2022.vocabulary_size, 6);
+        // 8 words across 3 sentences
+        assert_approx(y2022.avg_sentence_length, 8.0 / 3.0, 0.01);
+        assert_approx(y2022.question_ratio, 50.0, 0.01);
+        assert_approx(y2022.exclamation_ratio, 50.0, 0.01);
+
+        let y2023 = &stats.years[1];
+        assert_eq!(y2023.vocabulary_size, 1);
+        assert_approx(y2023.question_ratio, 0.0, 0.01);
+        debug!("test_style_stats_per_year: done");
+    }
+
+    #[test]
+    fn test_period_comparison() {
+        debug!("test_period_comparison: setup");
+        let mut old_a = base_tweet("a1", "2019-03-01T09:00:00Z", "Old tweet");
+        old_a.favorite_count = 4;
+        old_a.hashtags = vec!["Perl".to_string(), "rust".to_string()];
+        let old_b = base_tweet("a2", "2019-03-01T09:30:00Z", "Another old tweet");
+        let mut new_a = base_tweet("b1", "2023-06-01T21:00:00Z", "New tweet");
+        new_a.favorite_count = 10;
+        new_a.retweet_count = 2;
+        new_a.hashtags = vec!["rust".to_string(), "wasm".to_string()];
+        new_a.in_reply_to_status_id = Some("x".to_string());
+        let storage = storage_with_tweets(&[old_a, old_b, new_a], "user-1");
+
+        let year = |y: i32| {
+            (
+                Utc.with_ymd_and_hms(y, 1, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(y, 12, 31, 23, 59, 59).unwrap(),
+            )
+        };
+        let (a_start, a_end) = year(2019);
+        let (b_start, b_end) = year(2023);
+        let cmp = PeriodComparison::compute(
+            &storage,
+            ("2019", a_start, a_end),
+            ("2023", b_start, b_end),
+            5,
+        )
+        .unwrap();
+
+        assert_eq!(cmp.a.tweet_count, 2);
+        assert_eq!(cmp.a.active_days, 1);
+        assert_eq!(cmp.a.most_active_hour, 9);
+        assert_approx(cmp.a.avg_engagement, 2.0, 0.01);
+        assert_eq!(cmp.b.tweet_count, 1);
+        assert_eq!(cmp.b.most_active_hour, 21);
+        assert_approx(cmp.b.reply_ratio, 100.0, 0.01);
+        assert_eq!(cmp.hashtags_new, vec!["wasm".to_string()]);
+        assert_eq!(cmp.hashtags_dropped, vec!["perl".to_string()]);
+        debug!("test_period_comparison: done");
+    }
+
+    #[test]
+    fn test_follower_churn_reindex_reuses_snapshot() {
+        let mut storage = storage_with_tweets(&[], "user-1");
+        let info = storage.get_archive_info().unwrap().unwrap();
+        let first = storage.begin_snapshot(&info).unwrap();
+        storage
+            .store_snapshot_accounts(first, SnapshotKind::Follower, ["a", "b"])
+            .unwrap();
+        let second = storage.begin_snapshot(&info).unwrap();
+        assert_eq!(first, second);
+        storage
+            .store_snapshot_accounts(second, SnapshotKind::Follower, ["a"])
+            .unwrap();
+
+        let stats = FollowerChurnStats::compute(&storage, 5).unwrap();
+        assert_eq!(stats.snapshots.len(), 1);
+        assert_eq!(stats.snapshots[0].followers, 1);
+    }
+
+    #[test]
+    fn test_trend_series_fills_gaps() {
+        let ts = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().timestamp();
+        let timestamps = [
+            ts("2023-01-15T10:00:00Z"),
+            ts("2023-01-20T10:00:00Z"),
+            ts("2023-04-02T10:00:00Z"),
+            0,
+        ];
+
+        let series = TrendSeries::from_timestamps("rust", TrendInterval::Month, &timestamps);
+        let periods: Vec<&str> = series.buckets.iter().map(|b| b.period.as_str()).collect();
+        assert_eq!(periods, vec!["2023-01", "2023-02", "2023-03", "2023-04"]);
+        let counts: Vec<u64> = series.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 0, 0, 1]);
+        assert_eq!(series.total_matches, 4);
+        assert_eq!(series.undated_matches, 1);
+        assert_eq!(series.peak().unwrap().period, "2023-01");
+
+        let weekly = TrendSeries::from_timestamps("rust", TrendInterval::Week, &timestamps[..2]);
+        assert_eq!(weekly.buckets.len(), 2);
+        assert_eq!(weekly.buckets[0].start.to_string(), "2023-01-09");
+        assert_eq!(weekly.buckets[0].period, "2023-W02");
+
+        let empty = TrendSeries::from_timestamps("rust", TrendInterval::Year, &[]);
+        assert!(empty.buckets.is_empty());
+        assert!(empty.peak().is_none());
+    }
+
+    #[test]
+    fn test_client_kind_classification() {
+        assert_eq!(ClientKind::of("Twitter for iPhone"), ClientKind::Mobile);
+        assert_eq!(ClientKind::of("Twitter for Android"), ClientKind::Mobile);
+        assert_eq!(ClientKind::of("Twitter Web App"), ClientKind::Web);
+        assert_eq!(ClientKind::of("TweetDeck"), ClientKind::Web);
+        assert_eq!(ClientKind::of("IFTTT"), ClientKind::Automated);
+        assert_eq!(ClientKind::of("Buffer for iPhone"), ClientKind::Automated);
+        assert_eq!(ClientKind::of("my-release-bot"), ClientKind::Automated);
+        assert_eq!(ClientKind::of("Tweetbot for iOS"), ClientKind::Mobile);
+        assert_eq!(ClientKind::of("Foursquare"), ClientKind::Other);
+    }
+
+    #[test]
+    fn test_source_stats_by_source_and_year() {
+        let mut tweets = Vec::new();
+        for (id, date, source) in [
+            ("1", "2019-03-01T10:00:00Z", Some("Twitter for iPhone")),
+            ("2", "2019-06-01T10:00:00Z", Some("Twitter for iPhone")),
+            ("3", "2019-07-01T10:00:00Z", Some("IFTTT")),
+            ("4", "2020-01-01T10:00:00Z", Some("IFTTT")),
+            ("5", "2020-02-01T10:00:00Z", Some("IFTTT")),
+            ("6", "2020-03-01T10:00:00Z", Some("Twitter Web App")),
+            ("7", "2020-04-01T10:00:00Z", None),
+        ] {
+            let mut tweet = base_tweet(id, date, "hello");
+            tweet.source = source.map(str::to_string);
+            tweets.push(tweet);
+        }
+        let storage = storage_with_tweets(&tweets, "user-1");
+
+        let stats = SourceStats::compute(&storage, 2).unwrap();
+        assert_eq!(stats.total_tweets, 7);
+        assert_eq!(stats.unknown_count, 1);
+        assert_eq!(stats.sources.len(), 2);
+        let ifttt = &stats.sources[0];
+        assert_eq!(ifttt.source, "IFTTT");
+        assert_eq!(ifttt.kind, ClientKind::Automated);
+        assert_eq!(ifttt.count, 3);
+        assert_approx(ifttt.percentage, 300.0 / 7.0, 0.01);
+        assert_eq!(ifttt.first_used.unwrap().year(), 2019);
+        assert_eq!(ifttt.last_used.unwrap().month(), 2);
+
+        assert_eq!(stats.years.len(), 2);
+        let (y2019, y2020) = (&stats.years[0], &stats.years[1]);
+        assert_eq!((y2019.year, y2019.total), (2019, 3));
+        assert_eq!((y2019.mobile, y2019.automated), (2, 1));
+        assert_eq!(y2019.top_source.as_deref(), Some("Twitter for iPhone"));
+        assert_eq!((y2020.web, y2020.automated, y2020.other), (1, 2, 1));
+        assert_eq!(y2020.top_source.as_deref(), Some("IFTTT"));
+    }
+
+    #[test]
+    fn test_cooccurrence_hashtag_and_term() {
+        let mut t1 = base_tweet("1", "2023-01-01T10:00:00Z", "Shipping async code today");
+        t1.hashtags = vec!["Rust".to_string(), "async".to_string()];
+        let mut t2 = base_tweet("2", "2023-01-02T10:00:00Z", "More async tooling");
+        t2.hashtags = vec!["rust".to_string(), "async".to_string()];
+        let mut t3 = base_tweet("3", "2023-01-03T10:00:00Z", "Python notebooks");
+        t3.hashtags = vec!["python".to_string(), "async".to_string()];
+        let t4 = base_tweet("4", "2023-01-04T10:00:00Z", "Coffee break");
+        let storage = storage_with_tweets(&[t1, t2, t3, t4], "user-1");
+
+        let stats = CooccurrenceStats::compute(&storage, "#rust", 5).unwrap();
+        assert_eq!(stats.target_kind, CooccurTarget::Hashtag);
+        assert_eq!(stats.target_count, 2);
+        assert_eq!(stats.total_tweets, 4);
+        assert_eq!(stats.hashtags.len(), 1);
+        let async_tag = &stats.hashtags[0];
+        assert_eq!(async_tag.item, "async");
+        assert_eq!(async_tag.count, 2);
+        assert_eq!(async_tag.item_count, 3);
+        // lift = (2 * 4) / (2 * 3)
+        assert_approx(async_tag.lift, 4.0 / 3.0, 0.001);
+        assert_approx(async_tag.pmi, (4.0f64 / 3.0).log2(), 0.001);
+        assert_eq!(stats.terms[0].item, "async");
+        assert_eq!(stats.terms[0].count, 2);
+
+        let term = CooccurrenceStats::compute(&storage, "async", 5).unwrap();
+        assert_eq!(term.target_kind, CooccurTarget::Term);
+        assert_eq!(term.target_count, 2);
+        // Equal counts: the rarer (higher-lift) hashtag ranks first
+        assert_eq!(term.hashtags[0].item, "rust");
+        assert_eq!(term.hashtags[1].item, "async");
+        assert!(term.terms.iter().all(|t| t.item != "async"));
+    }
+
+    #[test]
+    fn test_write_csv_series() {
+        let mut tweet = base_tweet("1", "2023-05-01T14:00:00Z", "Hello, world");
+        tweet.favorite_count = 3;
+        tweet.hashtags = vec!["rust".to_string()];
+        let storage = storage_with_tweets(&[tweet], "user-1");
+        let temporal = TemporalStats::compute(&storage).unwrap();
+        let engagement = EngagementStats::compute(&storage, 5).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let written = write_csv_series(
+            dir.path(),
+            Some(&temporal),
+            Some(&engagement),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(written.len(), 6);
+
+        let daily = std::fs::read_to_string(dir.path().join("daily_counts.csv")).unwrap();
+        assert_eq!(daily, "date,count\n2023-05-01,1\n");
+        let hourly = std::fs::read_to_string(dir.path().join("hourly.csv")).unwrap();
+        assert_eq!(hourly.lines().count(), 25);
+        assert!(hourly.contains("\n14,1\n"));
+        let top = std::fs::read_to_string(dir.path().join("top_tweets.csv")).unwrap();
+        assert!(top.contains("\"Hello, world\""));
+        assert!(!dir.path().join("top_hashtags.csv").exists());
+    }
 }
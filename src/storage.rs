@@ -3,22 +3,191 @@
 //! Provides persistent storage with optimized schema for fast queries.
 
 use crate::doctor::{CheckCategory, CheckStatus, HealthCheck, TableStat};
+use crate::embedder::Embedder;
+use crate::hash_embedder::HashEmbedder;
 use crate::model::{
-    ArchiveInfo, ArchiveStats, Block, DirectMessage, DmConversation, DmConversationSummary,
-    Follower, Following, GrokMessage, Like, Mute, Tweet,
+    AccountHandle, Alert, ArchiveInfo, ArchiveStats, AuditEntry, Block, Bookmark, Collection,
+    CollectionItem, CommunityNote, DirectMessage, DmConversation, DmConversationSummary, Follower,
+    Following, GeoBox, GrokMessage, HandleResolver, HiddenDocument, Like, ListRelation,
+    ListSummary, Mute, Note, Pin, Profile, ProfileVersion, RawKind, RawRecord, ScreenNameChange,
+    Snapshot, SnapshotKind, TagDefinition, Tweet, TweetGeo, TweetUrl, UserList,
 };
+use crate::parser::ArchiveParser;
+use crate::unshorten::{is_short_url, unresolved_link};
 use crate::{format_bytes_i64, format_number};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{Connection, params};
-use std::collections::HashMap;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{Connection, OpenFlags, ToSql, params};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use tracing::info;
 
-const SCHEMA_VERSION: i32 = 3;
+/// FTS5 tables rebuilt from source tables by [`Storage::rebuild_fts_tables`].
+pub const DERIVED_FTS_TABLES: [&str; 6] = [
+    "fts_tweets",
+    "fts_likes",
+    "fts_bookmarks",
+    "fts_dms",
+    "fts_grok",
+    "fts_community_notes",
+];
+
+/// Database schema version this build creates and migrates to.
+pub const SCHEMA_VERSION: i32 = 32;
+
+/// Likes histogram bucket (0-7) of a tweet's `favorite_count`, shared by the
+/// live `xf stats` query and the `stats_likes_buckets` summary.
+pub const LIKES_BUCKET_SQL: &str = "CASE
+    WHEN favorite_count = 0 THEN 0
+    WHEN favorite_count BETWEEN 1 AND 5 THEN 1
+    WHEN favorite_count BETWEEN 6 AND 10 THEN 2
+    WHEN favorite_count BETWEEN 11 AND 25 THEN 3
+    WHEN favorite_count BETWEEN 26 AND 50 THEN 4
+    WHEN favorite_count BETWEEN 51 AND 100 THEN 5
+    WHEN favorite_count BETWEEN 101 AND 500 THEN 6
+    ELSE 7
+END";
+/// Per-tweet likes, retweets, replies and quotes received.
+///
+/// Replies come from the export's `reply_count` where it is recorded, and
+/// otherwise from the replies the archive itself holds (your own thread
+/// replies). Quotes are NULL unless the export records them.
+pub const TWEET_ENGAGEMENT_SQL: &str = "SELECT t.id, t.full_text, t.created_at, t.is_retweet,
+    t.favorite_count AS likes, t.retweet_count AS retweets,
+    COALESCE(t.reply_count, r.replies, 0) AS replies, t.quote_count AS quotes
+FROM tweets t
+LEFT JOIN (
+    SELECT in_reply_to_status_id AS id, COUNT(*) AS replies
+    FROM tweets
+    WHERE in_reply_to_status_id IS NOT NULL
+    GROUP BY in_reply_to_status_id
+) r ON r.id = t.id";
+
+/// Rank of each original tweet (retweets excluded) by likes, retweets,
+/// and engagement (likes + retweets + replies + quotes, as in
+/// [`TWEET_ENGAGEMENT_SQL`]); ties share a rank. Shared by the live
+/// `xf tweet --percentile` query and the `stats_engagement_ranks` summary.
+fn engagement_ranks_sql() -> String {
+    format!(
+        "SELECT id AS tweet_id,
+    RANK() OVER (ORDER BY likes DESC) AS likes_rank,
+    RANK() OVER (ORDER BY retweets DESC) AS retweets_rank,
+    RANK() OVER (ORDER BY likes + retweets + replies + COALESCE(quotes, 0) DESC)
+        AS engagement_rank
+FROM ({TWEET_ENGAGEMENT_SQL})
+WHERE is_retweet = 0"
+    )
+}
 // SQLite default limit on host parameters is usually 999 or 32766.
 // We use a safe batch size to avoid "too many SQL variables" errors.
 const SQLITE_BATCH_SIZE: usize = 900;
+// Archive text compresses well even at fast levels; higher levels cost
+// indexing time for a few percent.
+const PACK_ZSTD_LEVEL: i32 = 3;
+// Below this the zstd frame overhead eats most of the saving.
+const PACK_MIN_BYTES: usize = 128;
+
+/// Table and id column holding records of `kind`.
+const fn raw_json_table(kind: RawKind) -> (&'static str, &'static str) {
+    match kind {
+        RawKind::Tweet => ("tweets", "id"),
+        RawKind::Like => ("likes", "tweet_id"),
+        RawKind::DirectMessage => ("direct_messages", "id"),
+    }
+}
+
+/// Text for a compressed column. Values of at least [`PACK_MIN_BYTES`] are
+/// stored as zstd BLOBs when that is smaller, everything else as plain TEXT.
+struct PackedText<'a>(&'a str);
+
+impl ToSql for PackedText<'_> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        let text = self.0;
+        if text.len() >= PACK_MIN_BYTES {
+            let compressed = zstd::bulk::compress(text.as_bytes(), PACK_ZSTD_LEVEL)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            if compressed.len() < text.len() {
+                return Ok(ToSqlOutput::from(compressed));
+            }
+        }
+        Ok(ToSqlOutput::from(text))
+    }
+}
+
+/// Text read from a compressed column, which may hold TEXT or a zstd BLOB
+/// (rows written before the column was compressed stay TEXT).
+struct UnpackedText(String);
+
+impl FromSql for UnpackedText {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let bytes = match value {
+            ValueRef::Text(text) => text.to_vec(),
+            ValueRef::Blob(blob) => {
+                zstd::decode_all(blob).map_err(|e| FromSqlError::Other(e.into()))?
+            }
+            _ => return Err(FromSqlError::InvalidType),
+        };
+        String::from_utf8(bytes)
+            .map(Self)
+            .map_err(|e| FromSqlError::Other(e.into()))
+    }
+}
+
+/// Let SQL read compressed columns: `xf_unpack(message)` is the text either way.
+fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    conn.create_scalar_function(
+        "xf_unpack",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| match ctx.get_raw(0) {
+            ValueRef::Null => Ok(None),
+            value => UnpackedText::column_result(value)
+                .map(|text| Some(text.0))
+                .map_err(|e| rusqlite::Error::UserFunctionError(e.into())),
+        },
+    )
+}
+
+/// Fill the `excluded_documents` temporary table with `(doc_type, doc_id)`
+/// rows and shadow the document tables with views that skip them. See
+/// [`Storage::exclude_documents`].
+fn create_exclusion_views(conn: &Connection, rows: &[(String, String)]) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TEMP TABLE IF NOT EXISTS excluded_documents (
+            doc_type TEXT NOT NULL,
+            doc_id TEXT NOT NULL,
+            PRIMARY KEY (doc_type, doc_id)
+        ) WITHOUT ROWID;
+        ",
+    )?;
+    {
+        let mut stmt = conn.prepare(
+            "INSERT OR IGNORE INTO temp.excluded_documents (doc_type, doc_id) VALUES (?1, ?2)",
+        )?;
+        for (doc_type, doc_id) in rows {
+            stmt.execute(params![doc_type, doc_id])?;
+        }
+    }
+    for (view, doc_type, id_column) in [
+        ("tweets", "tweet", "id"),
+        ("tweet_hashtags", "tweet", "tweet_id"),
+        ("likes", "like", "tweet_id"),
+        ("direct_messages", "dm", "id"),
+        ("grok_messages", "grok", "CAST(id AS TEXT)"),
+    ] {
+        conn.execute_batch(&format!(
+            "CREATE TEMP VIEW IF NOT EXISTS {view} AS
+             SELECT * FROM main.{view} WHERE {id_column} NOT IN (
+                 SELECT doc_id FROM temp.excluded_documents WHERE doc_type = '{doc_type}'
+             );"
+        ))?;
+    }
+    Ok(())
+}
 
 const fn epoch_utc() -> DateTime<Utc> {
     DateTime::<Utc>::from_timestamp(0, 0).unwrap()
@@ -26,8 +195,28 @@ const fn epoch_utc() -> DateTime<Utc> {
 
 fn parse_rfc3339_or_epoch(value: Option<String>) -> DateTime<Utc> {
     value
-        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-        .map_or_else(epoch_utc, |dt| dt.with_timezone(&Utc))
+        .and_then(|s| {
+            DateTime::parse_from_rfc3339(&s)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc))
+                // Rows written by older builds may hold the archive's raw layout
+                .or_else(|| ArchiveParser::parse_date(&s))
+        })
+        .unwrap_or_else(epoch_utc)
+}
+
+/// Matches stored `created_at` values that are epoch zero or not RFC 3339.
+const INVALID_CREATED_AT: &str = "(created_at < '1970-01-02' \
+     OR created_at NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]T*')";
+
+fn community_note_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CommunityNote> {
+    Ok(CommunityNote {
+        id: row.get(0)?,
+        tweet_id: row.get(1)?,
+        text: row.get(2)?,
+        classification: row.get(3)?,
+        created_at: parse_rfc3339_opt(row.get(4)?),
+    })
 }
 
 fn parse_rfc3339_opt(value: Option<String>) -> Option<DateTime<Utc>> {
@@ -36,6 +225,41 @@ fn parse_rfc3339_opt(value: Option<String>) -> Option<DateTime<Utc>> {
         .map(|dt| dt.with_timezone(&Utc))
 }
 
+/// Column list read by [`tweet_from_row`].
+const TWEET_COLUMNS: &str = "id, created_at, full_text, source, favorite_count, retweet_count, \
+     lang, in_reply_to_status_id, in_reply_to_user_id, in_reply_to_screen_name, \
+     is_retweet, hashtags_json, mentions_json, urls_json, media_json, geo_json, \
+     poll_json, reply_count, quote_count";
+
+/// Read an optional JSON column, treating unreadable JSON as missing.
+fn json_column<T: serde::de::DeserializeOwned>(json: Option<String>) -> Option<T> {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+}
+
+fn tweet_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Tweet> {
+    Ok(Tweet {
+        id: row.get(0)?,
+        created_at: parse_rfc3339_or_epoch(row.get::<_, Option<String>>(1)?),
+        full_text: row.get(2)?,
+        source: row.get(3)?,
+        favorite_count: row.get(4)?,
+        retweet_count: row.get(5)?,
+        lang: row.get(6)?,
+        in_reply_to_status_id: row.get(7)?,
+        in_reply_to_user_id: row.get(8)?,
+        in_reply_to_screen_name: row.get(9)?,
+        is_retweet: row.get::<_, i32>(10)? != 0,
+        hashtags: serde_json::from_str(&row.get::<_, String>(11)?).unwrap_or_default(),
+        user_mentions: serde_json::from_str(&row.get::<_, String>(12)?).unwrap_or_default(),
+        urls: serde_json::from_str(&row.get::<_, String>(13)?).unwrap_or_default(),
+        media: serde_json::from_str(&row.get::<_, String>(14)?).unwrap_or_default(),
+        geo: json_column(row.get(15)?),
+        poll: json_column(row.get(16)?),
+        reply_count: row.get(17)?,
+        quote_count: row.get(18)?,
+    })
+}
+
 /// `SQLite` storage manager
 pub struct Storage {
     conn: Connection,
@@ -43,13 +267,242 @@ pub struct Storage {
 
 type EmbeddingRecord = (String, String, Vec<f32>, Option<[u8; 32]>);
 
+/// Document embeddings joined to their vectors: `doc_id`, `doc_type`, F16 bytes.
+const DOC_EMBEDDINGS_SELECT: &str = "SELECT e.doc_id, e.doc_type, v.embedding FROM embeddings e \
+     JOIN embedding_vectors v ON v.content_hash = e.content_hash";
+
+/// Quantize an embedding to little-endian F16 bytes (half the size of f32).
+fn f16_bytes(embedding: &[f32]) -> Vec<u8> {
+    embedding
+        .iter()
+        .flat_map(|&f| half::f16::from_f32(f).to_le_bytes())
+        .collect()
+}
+
+/// Expand stored F16 bytes back to f32; a trailing odd byte is ignored.
+fn f16_to_f32(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(2)
+        .map(|chunk| half::f16::from_le_bytes([chunk[0], chunk[1]]).to_f32())
+        .collect()
+}
+
+/// Key for a vector stored without a content hash.
+fn bytes_hash(bytes: &[u8]) -> [u8; 32] {
+    let digest = ring::digest::digest(&ring::digest::SHA256, bytes);
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(digest.as_ref());
+    hash
+}
+
 /// Summary of FTS rebuild results.
 #[derive(Debug, Clone, Copy)]
 pub struct FtsRebuildStats {
     pub tweets: usize,
     pub likes: usize,
+    pub bookmarks: usize,
     pub dms: usize,
     pub grok: usize,
+    pub community_notes: usize,
+}
+
+/// Summary of a self-thread re-link pass.
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadRelinkStats {
+    /// Self-replies whose parent ID matches no stored tweet.
+    pub broken: usize,
+    pub relinked: usize,
+}
+
+/// Tweets and DMs whose stored date is epoch zero or unreadable.
+#[derive(Debug, Clone, Default)]
+pub struct InvalidDates {
+    pub tweet_ids: HashSet<String>,
+    pub dm_ids: HashSet<String>,
+}
+
+impl InvalidDates {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tweet_ids.len() + self.dm_ids.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tweet_ids.is_empty() && self.dm_ids.is_empty()
+    }
+}
+
+/// Where one tweet ranks among your original tweets.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct EngagementPercentile {
+    /// Original tweets ranked; retweets are left out.
+    pub tweets: u64,
+    pub likes: RankShare,
+    pub retweets: RankShare,
+    /// Likes, retweets, replies and quotes.
+    pub engagement: RankShare,
+}
+
+/// A rank and the share of tweets ranked at or above it.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RankShare {
+    /// 1 for the top tweet; tied tweets share a rank.
+    pub rank: u64,
+    /// `rank` as a percentage of all ranked tweets, so 2.0 reads "top 2%".
+    pub top_percent: f64,
+}
+
+impl RankShare {
+    #[allow(clippy::cast_precision_loss)]
+    fn new(rank: u64, tweets: u64) -> Self {
+        Self {
+            rank,
+            top_percent: rank as f64 / tweets.max(1) as f64 * 100.0,
+        }
+    }
+}
+
+/// Result ordering for FTS5 lookups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FtsOrder {
+    /// FTS5 relevance rank (best match first)
+    #[default]
+    Rank,
+    /// Oldest first
+    Oldest,
+    /// Newest first
+    Newest,
+    /// Most likes + retweets + replies + quotes first (tweets only)
+    Engagement,
+}
+
+/// `SQLite` tuning preset (`storage.profile`), mapped to [`StoragePragmas`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageProfile {
+    /// Pick from system memory: low-memory under 2 GiB, fast from 16 GiB
+    #[default]
+    Auto,
+    /// 64 MB page cache, 256 MB memory map
+    Default,
+    /// 8 MB page cache, no memory map, for Raspberry Pi-class devices
+    LowMemory,
+    /// 256 MB page cache, 1 GiB memory map, fewer WAL checkpoints
+    Fast,
+}
+
+/// Settings applied when a database is opened with a [`StorageProfile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoragePragmas {
+    /// Page cache size in KiB (`PRAGMA cache_size = -N`)
+    pub cache_kib: i64,
+    /// Bytes of the file read through memory-mapped I/O (0 = off)
+    pub mmap_bytes: i64,
+    /// WAL pages written before an automatic checkpoint
+    pub wal_autocheckpoint: i64,
+}
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+impl StorageProfile {
+    /// The profile's name as written in the config file.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Default => "default",
+            Self::LowMemory => "low-memory",
+            Self::Fast => "fast",
+        }
+    }
+
+    /// This profile with `Auto` replaced by the one chosen for this machine.
+    #[must_use]
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto => Self::for_memory(total_memory_bytes()),
+            profile => profile,
+        }
+    }
+
+    /// The profile `Auto` picks with `memory` bytes of RAM (`None` = unknown).
+    #[must_use]
+    pub const fn for_memory(memory: Option<u64>) -> Self {
+        match memory {
+            Some(bytes) if bytes < 2 * GIB => Self::LowMemory,
+            Some(bytes) if bytes >= 16 * GIB => Self::Fast,
+            _ => Self::Default,
+        }
+    }
+
+    /// Pragma values of this profile, resolving `Auto` first.
+    #[must_use]
+    pub fn pragmas(self) -> StoragePragmas {
+        match self.resolve() {
+            Self::LowMemory => StoragePragmas {
+                cache_kib: 8 * 1024,
+                mmap_bytes: 0,
+                wal_autocheckpoint: 1_000,
+            },
+            Self::Fast => StoragePragmas {
+                cache_kib: 256 * 1024,
+                mmap_bytes: 1 << 30,
+                wal_autocheckpoint: 10_000,
+            },
+            Self::Auto | Self::Default => StoragePragmas {
+                cache_kib: 64 * 1024,
+                mmap_bytes: 256 << 20,
+                wal_autocheckpoint: 1_000,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for StorageProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for StorageProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "default" => Ok(Self::Default),
+            "low-memory" | "low_memory" => Ok(Self::LowMemory),
+            "fast" => Ok(Self::Fast),
+            other => Err(format!(
+                "unknown storage profile '{other}' (expected auto, default, low-memory, or fast)"
+            )),
+        }
+    }
+}
+
+/// Physical memory from `/proc/meminfo`; `None` where it can't be read.
+pub(crate) fn total_memory_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_total(&meminfo)
+}
+
+fn parse_mem_total(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Ordering for DM conversation listings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConversationOrder {
+    /// Most recent message first
+    #[default]
+    Recent,
+    /// Earliest first message first
+    Oldest,
+    /// Most messages first
+    Messages,
 }
 
 /// Aggregate counts and date bounds for archive tables.
@@ -69,26 +522,43 @@ pub struct AllCounts {
 }
 
 impl Storage {
-    /// Open or create the database at the given path.
+    /// Open or create the database at the given path, tuned for this
+    /// machine ([`StorageProfile::Auto`]).
     ///
     /// # Errors
     ///
     /// Returns an error if the database cannot be opened or initialized.
     pub fn open(db_path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_profile(db_path, StorageProfile::Auto)
+    }
+
+    /// Open or create the database at the given path with a tuning profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or initialized.
+    pub fn open_with_profile(db_path: impl AsRef<Path>, profile: StorageProfile) -> Result<Self> {
         let conn = Connection::open(db_path.as_ref()).with_context(|| {
             format!("Failed to open database at {}", db_path.as_ref().display())
         })?;
 
-        // Set pragmas for performance
-        conn.execute_batch(
+        let StoragePragmas {
+            cache_kib,
+            mmap_bytes,
+            wal_autocheckpoint,
+        } = profile.pragmas();
+        conn.execute_batch(&format!(
             "
             PRAGMA journal_mode = WAL;
             PRAGMA synchronous = NORMAL;
             PRAGMA foreign_keys = ON;
-            PRAGMA cache_size = -64000;
             PRAGMA temp_store = MEMORY;
-            ",
-        )?;
+            PRAGMA cache_size = -{cache_kib};
+            PRAGMA mmap_size = {mmap_bytes};
+            PRAGMA wal_autocheckpoint = {wal_autocheckpoint};
+            "
+        ))?;
+        register_functions(&conn)?;
 
         let storage = Self { conn };
         storage.migrate()?;
@@ -108,11 +578,57 @@ impl Storage {
             PRAGMA temp_store = MEMORY;
             ",
         )?;
+        register_functions(&conn)?;
         let storage = Self { conn };
         storage.migrate()?;
         Ok(storage)
     }
 
+    /// Open `count` read-only connections to this database, with the same
+    /// cache and mmap settings and excluded documents, for running queries
+    /// on other threads.
+    ///
+    /// Returns none for an in-memory database, which other connections
+    /// can't see.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a connection cannot be opened.
+    pub fn open_readers(&self, count: usize) -> Result<Vec<Self>> {
+        let Some(path) = self.conn.path().filter(|path| !path.is_empty()) else {
+            return Ok(Vec::new());
+        };
+        let excluded = self.excluded_document_rows()?;
+        let cache_size: i64 = self
+            .conn
+            .query_row("PRAGMA cache_size", [], |row| row.get(0))?;
+        let mmap_size: i64 = self
+            .conn
+            .query_row("PRAGMA mmap_size", [], |row| row.get(0))?;
+
+        (0..count)
+            .map(|_| {
+                let conn = Connection::open_with_flags(
+                    path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )
+                .with_context(|| format!("Failed to open database at {path}"))?;
+                conn.execute_batch(&format!(
+                    "
+                    PRAGMA temp_store = MEMORY;
+                    PRAGMA cache_size = {cache_size};
+                    PRAGMA mmap_size = {mmap_size};
+                    "
+                ))?;
+                register_functions(&conn)?;
+                if !excluded.is_empty() {
+                    create_exclusion_views(&conn, &excluded)?;
+                }
+                Ok(Self { conn })
+            })
+            .collect()
+    }
+
     /// Get a reference to the underlying database connection.
     ///
     /// This is useful for modules that need to execute custom queries.
@@ -122,6 +638,7 @@ impl Storage {
     }
 
     /// Run database migrations
+    #[allow(clippy::too_many_lines)]
     fn migrate(&self) -> Result<()> {
         let current_version = self.get_schema_version();
 
@@ -137,13 +654,210 @@ impl Storage {
                 self.conn.execute("DROP TABLE IF EXISTS embeddings", [])?;
             }
 
+            // Replaced by the covering (tag, tweet_id) index.
+            if current_version < 6 {
+                self.conn
+                    .execute("DROP INDEX IF EXISTS idx_tweet_hashtags_tag", [])?;
+            }
+
+            // Vectors moved to a table keyed by content hash; keep the old rows
+            // aside until the new tables exist.
+            let split_embeddings = current_version < 14 && self.has_inline_embeddings()?;
+            if split_embeddings {
+                self.conn.execute_batch(
+                    r"
+                    DROP INDEX IF EXISTS idx_embeddings_type;
+                    DROP INDEX IF EXISTS idx_embeddings_hash;
+                    ALTER TABLE embeddings RENAME TO embeddings_v13;
+                    ",
+                )?;
+            }
+
+            // The content FTS tables gained a `supplemental` column; they are
+            // refilled once the new tables exist
+            if current_version < 27 {
+                self.conn.execute_batch(
+                    r"
+                    DROP TABLE IF EXISTS fts_tweets;
+                    DROP TABLE IF EXISTS fts_likes;
+                    DROP TABLE IF EXISTS fts_bookmarks;
+                    DROP TABLE IF EXISTS fts_dms;
+                    DROP TABLE IF EXISTS fts_grok;
+                    ",
+                )?;
+            }
+
             self.create_schema()?;
+
+            // Geotags and polls are filled in by the next `xf index`
+            for (version, column) in [(16, "geo_json"), (17, "poll_json")] {
+                if current_version < version && !self.has_column("tweets", column)? {
+                    self.conn
+                        .execute(&format!("ALTER TABLE tweets ADD COLUMN {column} TEXT"), [])?;
+                }
+            }
+
+            // Reply and quote counts are filled in by the next `xf index`
+            // for exports that record them
+            for column in ["reply_count", "quote_count"] {
+                if current_version < 30 && !self.has_column("tweets", column)? {
+                    self.conn.execute(
+                        &format!("ALTER TABLE tweets ADD COLUMN {column} INTEGER"),
+                        [],
+                    )?;
+                }
+            }
+
+            // Source JSON is stored by the next `xf index`
+            if current_version < 21 {
+                for table in ["tweets", "likes", "direct_messages"] {
+                    if !self.has_column(table, "raw_json")? {
+                        self.conn.execute(
+                            &format!("ALTER TABLE {table} ADD COLUMN raw_json BLOB"),
+                            [],
+                        )?;
+                    }
+                }
+            }
+
+            // Embeddings stored before models were recorded all came from the
+            // hash embedder, which the column defaults describe
+            if current_version < 29 {
+                for (column, definition) in [
+                    ("model", "TEXT NOT NULL DEFAULT 'fnv1a-384'"),
+                    ("dimension", "INTEGER NOT NULL DEFAULT 384"),
+                ] {
+                    if !self.has_column("embeddings", column)? {
+                        self.conn.execute(
+                            &format!("ALTER TABLE embeddings ADD COLUMN {column} {definition}"),
+                            [],
+                        )?;
+                    }
+                }
+            }
+
+            // Alerts gained the search options `xf search --save` records;
+            // existing alerts ran as plain lexical searches
+            if current_version < 31 {
+                for (column, definition) in [
+                    ("mode", "TEXT NOT NULL DEFAULT 'lexical'"),
+                    ("sort", "TEXT NOT NULL DEFAULT 'relevance'"),
+                    ("filters_json", "TEXT NOT NULL DEFAULT '[]'"),
+                ] {
+                    if !self.has_column("alerts", column)? {
+                        self.conn.execute(
+                            &format!("ALTER TABLE alerts ADD COLUMN {column} {definition}"),
+                            [],
+                        )?;
+                    }
+                }
+            }
+
+            if split_embeddings {
+                self.split_inline_embeddings()?;
+            }
+
+            // Grok messages are stored compressed
+            if current_version < 22 {
+                self.pack_column("grok_messages", "message")?;
+            }
+
+            // The hashtag table is derived from tweets.hashtags_json; fill it for
+            // databases indexed before it existed.
+            if current_version < 5 {
+                self.backfill_tweet_hashtags()?;
+            }
+
+            // Handles can be resolved from already indexed tweets
+            if current_version < 19 {
+                self.rebuild_account_handles()?;
+            }
+
+            // Stats summaries are refreshed by every `xf index` from now on;
+            // engagement ranks joined them in version 24 and count replies
+            // and quotes since version 32
+            if current_version < 32 {
+                self.refresh_stats_summaries()?;
+            }
+
+            // Everything already indexed was known by the last indexed archive
+            if current_version < 13 {
+                if let Some(info) = self.get_archive_info()? {
+                    self.backfill_first_seen(info.generation_date)?;
+                }
+            }
+
+            if current_version < 27 && !self.is_lite_mode() {
+                self.fill_fts_tables()?;
+            }
+
             self.set_schema_version(SCHEMA_VERSION)?;
         }
 
         Ok(())
     }
 
+    /// Compress the plain-text values of `table.column` that [`PackedText`]
+    /// would compress. Freed pages are reclaimed by the next `VACUUM`.
+    fn pack_column(&self, table: &str, column: &str) -> Result<usize> {
+        let rows: Vec<(i64, String)> = self
+            .conn
+            .prepare(&format!(
+                "SELECT rowid, {column} FROM {table}
+                 WHERE typeof({column}) = 'text' AND length(CAST({column} AS BLOB)) >= ?1"
+            ))?
+            .query_map([PACK_MIN_BYTES], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(&format!(
+                "UPDATE {table} SET {column} = ?1 WHERE rowid = ?2"
+            ))?;
+            for (rowid, text) in &rows {
+                stmt.execute(params![PackedText(text), rowid])?;
+            }
+        }
+        tx.commit()?;
+        Ok(rows.len())
+    }
+
+    fn has_inline_embeddings(&self) -> Result<bool> {
+        self.has_column("embeddings", "embedding")
+    }
+
+    fn has_column(&self, table: &str, column: &str) -> Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+            params![table, column],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Move pre-v14 embeddings into the vector and mapping tables. Rows without a
+    /// content hash are dropped; `xf embed` regenerates them.
+    fn split_inline_embeddings(&self) -> Result<()> {
+        self.conn.execute_batch(
+            r"
+            INSERT OR IGNORE INTO embedding_vectors (content_hash, embedding, created_at)
+            SELECT content_hash, embedding, created_at FROM embeddings_v13
+            WHERE content_hash IS NOT NULL;
+            INSERT OR REPLACE INTO embeddings (doc_id, doc_type, content_hash, created_at)
+            SELECT doc_id, doc_type, content_hash, created_at FROM embeddings_v13
+            WHERE content_hash IS NOT NULL;
+            DROP TABLE embeddings_v13;
+            ",
+        )?;
+        Ok(())
+    }
+
+    /// Schema version recorded in the database.
+    #[must_use]
+    pub fn schema_version(&self) -> i32 {
+        self.get_schema_version()
+    }
+
     fn get_schema_version(&self) -> i32 {
         let result: Result<i32, _> = self.conn.query_row(
             "SELECT value FROM meta WHERE key = 'schema_version'",
@@ -166,6 +880,99 @@ impl Storage {
         Ok(())
     }
 
+    /// Record whether the database was built by `xf index --lite`.
+    ///
+    /// Lite databases hold tweets only, without FTS rows or embeddings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the meta table cannot be written.
+    pub fn set_lite_mode(&self, lite: bool) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('index_mode', ?)",
+            params![if lite { "lite" } else { "full" }],
+        )?;
+        Ok(())
+    }
+
+    /// Whether the database was built by `xf index --lite`.
+    #[must_use]
+    pub fn is_lite_mode(&self) -> bool {
+        self.conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'index_mode'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .is_ok_and(|mode| mode == "lite")
+    }
+
+    /// Value stored under `key` in the meta table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the meta table cannot be read.
+    pub fn get_meta(&self, key: &str) -> Result<Option<String>> {
+        match self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = ?", [key], |row| {
+                row.get(0)
+            }) {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Store `value` under `key` in the meta table, replacing any previous value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the meta table cannot be written.
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?, ?)",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Remove `key` from the meta table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the meta table cannot be written.
+    pub fn delete_meta(&self, key: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM meta WHERE key = ?", [key])?;
+        Ok(())
+    }
+
+    /// Populate `tweet_hashtags` from the `hashtags_json` column of existing tweets.
+    fn backfill_tweet_hashtags(&self) -> Result<()> {
+        let mut select = self.conn.prepare(
+            "SELECT id, hashtags_json FROM tweets WHERE hashtags_json NOT IN ('', '[]')",
+        )?;
+        let rows = select
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut insert =
+                tx.prepare("INSERT OR IGNORE INTO tweet_hashtags (tweet_id, tag) VALUES (?, ?)")?;
+            for (id, json) in rows {
+                let tags: Vec<String> = serde_json::from_str(&json).unwrap_or_default();
+                for tag in tags {
+                    insert.execute(params![id, tag.to_lowercase()])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
     #[allow(clippy::too_many_lines)]
     fn create_schema(&self) -> Result<()> {
         self.conn.execute_batch(
@@ -204,13 +1011,26 @@ impl Storage {
                 hashtags_json TEXT,
                 mentions_json TEXT,
                 urls_json TEXT,
-                media_json TEXT
+                media_json TEXT,
+                geo_json TEXT,
+                poll_json TEXT,
+                reply_count INTEGER,
+                quote_count INTEGER,
+                raw_json BLOB
             );
             CREATE INDEX IF NOT EXISTS idx_tweets_created_at ON tweets(created_at);
             CREATE INDEX IF NOT EXISTS idx_tweets_in_reply_to ON tweets(in_reply_to_status_id);
 
             -- Likes
             CREATE TABLE IF NOT EXISTS likes (
+                tweet_id TEXT PRIMARY KEY,
+                full_text TEXT,
+                expanded_url TEXT,
+                raw_json BLOB
+            );
+
+            -- Bookmarks
+            CREATE TABLE IF NOT EXISTS bookmarks (
                 tweet_id TEXT PRIMARY KEY,
                 full_text TEXT,
                 expanded_url TEXT
@@ -235,6 +1055,7 @@ impl Storage {
                 created_at TEXT NOT NULL,
                 urls_json TEXT,
                 media_urls_json TEXT,
+                raw_json BLOB,
                 FOREIGN KEY (conversation_id) REFERENCES dm_conversations(conversation_id)
             );
             CREATE INDEX IF NOT EXISTS idx_dm_conversation ON direct_messages(conversation_id);
@@ -264,6 +1085,22 @@ impl Storage {
                 user_link TEXT
             );
 
+            -- Lists, one row per relation (created, member, subscribed)
+            CREATE TABLE IF NOT EXISTS lists (
+                list_id TEXT NOT NULL,
+                relation TEXT NOT NULL,
+                url TEXT NOT NULL,
+                PRIMARY KEY (list_id, relation)
+            );
+
+            -- List members recorded by the user (archives do not include them):
+            -- account IDs or lowercase handles without the @
+            CREATE TABLE IF NOT EXISTS list_members (
+                list_id TEXT NOT NULL,
+                member TEXT NOT NULL,
+                PRIMARY KEY (list_id, member)
+            );
+
             -- Grok messages
             CREATE TABLE IF NOT EXISTS grok_messages (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -276,38 +1113,261 @@ impl Storage {
             CREATE INDEX IF NOT EXISTS idx_grok_chat_id ON grok_messages(chat_id);
             CREATE INDEX IF NOT EXISTS idx_grok_created_at ON grok_messages(created_at);
 
-            -- Full-text search virtual tables (standalone, not content-synced)
+            -- Full-text search virtual tables (standalone, not content-synced).
+            -- `supplemental` holds text attached to a document: poll options,
+            -- media alt text and the owner's notes
             CREATE VIRTUAL TABLE IF NOT EXISTS fts_tweets USING fts5(
                 tweet_id,
-                full_text
+                full_text,
+                supplemental
             );
 
             CREATE VIRTUAL TABLE IF NOT EXISTS fts_likes USING fts5(
                 tweet_id,
-                full_text
+                full_text,
+                supplemental
+            );
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS fts_bookmarks USING fts5(
+                tweet_id,
+                full_text,
+                supplemental
             );
 
             CREATE VIRTUAL TABLE IF NOT EXISTS fts_dms USING fts5(
                 dm_id,
-                text
+                text,
+                supplemental
             );
 
             CREATE VIRTUAL TABLE IF NOT EXISTS fts_grok USING fts5(
                 grok_id,
-                message
+                message,
+                supplemental
             );
 
-            -- Embeddings for semantic search
+            -- Community Notes (formerly Birdwatch) written by the archive owner
+            CREATE TABLE IF NOT EXISTS community_notes (
+                id TEXT PRIMARY KEY,
+                tweet_id TEXT,
+                text TEXT NOT NULL,
+                classification TEXT,
+                created_at TEXT
+            );
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS fts_community_notes USING fts5(
+                note_id,
+                text
+            );
+
+            -- Embeddings for semantic search: each distinct text is stored once,
+            -- keyed by the hash of its canonical form, and documents point at it
+            CREATE TABLE IF NOT EXISTS embedding_vectors (
+                content_hash BLOB PRIMARY KEY,
+                embedding BLOB NOT NULL,
+                created_at TEXT NOT NULL
+            ) WITHOUT ROWID;
+            -- model and dimension record the embedder that produced the vector
             CREATE TABLE IF NOT EXISTS embeddings (
                 doc_id TEXT NOT NULL,
                 doc_type TEXT NOT NULL,
-                embedding BLOB NOT NULL,
-                content_hash BLOB,
+                content_hash BLOB NOT NULL,
                 created_at TEXT NOT NULL,
+                model TEXT NOT NULL DEFAULT 'fnv1a-384',
+                dimension INTEGER NOT NULL DEFAULT 384,
                 PRIMARY KEY (doc_id, doc_type)
             );
             CREATE INDEX IF NOT EXISTS idx_embeddings_type ON embeddings(doc_type);
             CREATE INDEX IF NOT EXISTS idx_embeddings_hash ON embeddings(content_hash);
+
+            -- Normalized hashtags (lowercased, one row per tweet/tag pair)
+            CREATE TABLE IF NOT EXISTS tweet_hashtags (
+                tweet_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (tweet_id, tag)
+            );
+            CREATE INDEX IF NOT EXISTS idx_tweet_hashtags_tag_tweet ON tweet_hashtags(tag, tweet_id);
+
+            -- Stats summaries (derived, see refresh_stats_summaries)
+            CREATE TABLE IF NOT EXISTS stats_daily (
+                day TEXT PRIMARY KEY,
+                count INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS stats_hourly (
+                hour INTEGER PRIMARY KEY,
+                count INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS stats_hashtags (
+                tag TEXT PRIMARY KEY,
+                count INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_stats_hashtags_count ON stats_hashtags(count DESC, tag);
+            CREATE TABLE IF NOT EXISTS stats_likes_buckets (
+                bucket INTEGER PRIMARY KEY,
+                count INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS stats_engagement_ranks (
+                tweet_id TEXT PRIMARY KEY,
+                likes_rank INTEGER NOT NULL,
+                retweets_rank INTEGER NOT NULL,
+                engagement_rank INTEGER NOT NULL
+            );
+
+            -- Snapshots (one per indexed archive generation)
+            CREATE TABLE IF NOT EXISTS snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                taken_at TEXT NOT NULL,
+                archive_generated_at TEXT NOT NULL UNIQUE
+            );
+
+            -- Follower/following account lists captured per snapshot
+            CREATE TABLE IF NOT EXISTS snapshot_accounts (
+                snapshot_id INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                account_id TEXT NOT NULL,
+                PRIMARY KEY (snapshot_id, kind, account_id),
+                FOREIGN KEY (snapshot_id) REFERENCES snapshots(id)
+            );
+
+            -- Profile as of each indexed archive generation
+            CREATE TABLE IF NOT EXISTS profile_history (
+                archive_generated_at TEXT PRIMARY KEY,
+                username TEXT NOT NULL,
+                display_name TEXT,
+                bio TEXT,
+                website TEXT,
+                location TEXT,
+                avatar_url TEXT,
+                header_url TEXT
+            );
+
+            -- Handle changes from screen-name-change.js
+            CREATE TABLE IF NOT EXISTS screen_name_changes (
+                account_id TEXT NOT NULL,
+                changed_at TEXT NOT NULL,
+                changed_from TEXT NOT NULL,
+                changed_to TEXT NOT NULL,
+                PRIMARY KEY (account_id, changed_at)
+            );
+
+            -- Handles each account was seen using (derived, see rebuild_account_handles)
+            CREATE TABLE IF NOT EXISTS account_handles (
+                account_id TEXT NOT NULL,
+                screen_name TEXT NOT NULL,
+                first_seen TEXT NOT NULL,
+                last_seen TEXT NOT NULL,
+                PRIMARY KEY (account_id, screen_name)
+            );
+
+            -- Generation date (unix seconds) of the earliest indexed archive
+            -- containing each tweet, like, and DM, for `--as-of`
+            CREATE TABLE IF NOT EXISTS document_first_seen (
+                doc_type TEXT NOT NULL,
+                doc_id TEXT NOT NULL,
+                first_seen_at INTEGER NOT NULL,
+                PRIMARY KEY (doc_type, doc_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_document_first_seen_at ON document_first_seen(first_seen_at);
+
+            -- User-defined tag categories and the tweets classified into them
+            CREATE TABLE IF NOT EXISTS tag_definitions (
+                name TEXT PRIMARY KEY,
+                examples_json TEXT NOT NULL,
+                threshold REAL NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS tags (
+                tweet_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                score REAL NOT NULL,
+                PRIMARY KEY (tweet_id, tag)
+            );
+            CREATE INDEX IF NOT EXISTS idx_tags_tag_tweet ON tags(tag, tweet_id);
+
+            -- User notes on documents, with their own full-text index
+            CREATE TABLE IF NOT EXISTS notes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                doc_id TEXT NOT NULL,
+                doc_type TEXT NOT NULL,
+                text TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_notes_doc ON notes(doc_id, doc_type);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS fts_notes USING fts5(
+                note_id,
+                text
+            );
+
+            -- Named collections of saved documents
+            CREATE TABLE IF NOT EXISTS collections (
+                name TEXT PRIMARY KEY,
+                created_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS collection_items (
+                collection TEXT NOT NULL,
+                doc_id TEXT NOT NULL,
+                doc_type TEXT NOT NULL,
+                added_at TEXT NOT NULL,
+                PRIMARY KEY (collection, doc_id, doc_type),
+                FOREIGN KEY (collection) REFERENCES collections(name)
+            );
+
+            -- Documents hidden from results (the archive data is left untouched)
+            CREATE TABLE IF NOT EXISTS hidden_documents (
+                doc_id TEXT NOT NULL,
+                doc_type TEXT NOT NULL,
+                hidden_at TEXT NOT NULL,
+                PRIMARY KEY (doc_type, doc_id)
+            );
+
+            -- Documents pinned to the top of searches ('' query = any search)
+            CREATE TABLE IF NOT EXISTS pins (
+                doc_id TEXT NOT NULL,
+                doc_type TEXT NOT NULL,
+                query TEXT NOT NULL DEFAULT '',
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (doc_id, doc_type, query)
+            );
+
+            -- Named searches from `xf alerts add` and `xf search --save`, and
+            -- the matches each last saw in `xf alerts run`
+            CREATE TABLE IF NOT EXISTS alerts (
+                name TEXT PRIMARY KEY,
+                query TEXT NOT NULL,
+                types TEXT NOT NULL DEFAULT '',
+                mode TEXT NOT NULL DEFAULT 'lexical',
+                sort TEXT NOT NULL DEFAULT 'relevance',
+                filters_json TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL,
+                last_run_at TEXT
+            );
+            CREATE TABLE IF NOT EXISTS alert_matches (
+                alert TEXT NOT NULL,
+                doc_id TEXT NOT NULL,
+                doc_type TEXT NOT NULL,
+                PRIMARY KEY (alert, doc_type, doc_id)
+            );
+
+            -- Audit log of DM reads and exports (written only when audit.enabled)
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                at TEXT NOT NULL,
+                command TEXT NOT NULL,
+                event TEXT NOT NULL,
+                detail TEXT NOT NULL,
+                count INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_audit_log_at ON audit_log(at);
+
+            -- Short links followed by `xf enrich --unshorten`; final_url is
+            -- NULL when the link could not be reached
+            CREATE TABLE IF NOT EXISTS url_resolutions (
+                short_url TEXT PRIMARY KEY,
+                final_url TEXT,
+                error TEXT,
+                resolved_at TEXT NOT NULL
+            );
             ",
         )?;
 
@@ -371,7 +1431,7 @@ impl Storage {
         match result {
             Ok(info) => Ok(Some(info)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
+            Err(err) => Err(err.into()),
         }
     }
 
@@ -381,9 +1441,13 @@ impl Storage {
     ///
     /// Returns an error if any tweet insert fails.
     pub fn store_tweets(&mut self, tweets: &[Tweet]) -> Result<usize> {
+        let index_fts = !self.is_lite_mode();
         let tx = self.conn.transaction()?;
         let mut count = 0;
 
+        // Stats summaries are stale until the next refresh
+        tx.execute("DELETE FROM meta WHERE key = 'stats_summaries'", [])?;
+
         {
             // FTS5 doesn't support INSERT OR REPLACE, so we must delete first to avoid duplicates.
             // Batch delete for performance: one DELETE with IN clause instead of N individual DELETEs.
@@ -395,6 +1459,11 @@ impl Storage {
                         format!("DELETE FROM fts_tweets WHERE tweet_id IN ({placeholders})");
                     let mut delete_stmt = tx.prepare_cached(&delete_sql)?;
                     delete_stmt.execute(rusqlite::params_from_iter(chunk.iter().map(|t| &t.id)))?;
+                    let delete_tags_sql =
+                        format!("DELETE FROM tweet_hashtags WHERE tweet_id IN ({placeholders})");
+                    let mut delete_tags_stmt = tx.prepare_cached(&delete_tags_sql)?;
+                    delete_tags_stmt
+                        .execute(rusqlite::params_from_iter(chunk.iter().map(|t| &t.id)))?;
                 }
             }
 
@@ -403,12 +1472,21 @@ impl Storage {
                 INSERT OR REPLACE INTO tweets
                 (id, created_at, full_text, source, favorite_count, retweet_count, lang,
                  in_reply_to_status_id, in_reply_to_user_id, in_reply_to_screen_name,
-                 is_retweet, hashtags_json, mentions_json, urls_json, media_json)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 is_retweet, hashtags_json, mentions_json, urls_json, media_json, geo_json,
+                 poll_json, reply_count, quote_count)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 ",
             )?;
-            let mut fts_stmt =
-                tx.prepare("INSERT INTO fts_tweets (tweet_id, full_text) VALUES (?, ?)")?;
+            let mut fts_stmt = tx.prepare(
+                "INSERT INTO fts_tweets (tweet_id, full_text, supplemental) VALUES (?, ?, ?)",
+            )?;
+            // The batch delete above can't catch an ID repeated within this batch
+            // (the same tweet in two archive parts), so the later copy replaces
+            // the earlier row, matching INSERT OR REPLACE on the tweets table.
+            let mut fts_replace_stmt = tx.prepare("DELETE FROM fts_tweets WHERE tweet_id = ?")?;
+            let mut fts_seen: HashSet<&str> = HashSet::new();
+            let mut tag_stmt =
+                tx.prepare("INSERT OR IGNORE INTO tweet_hashtags (tweet_id, tag) VALUES (?, ?)")?;
 
             for tweet in tweets {
                 stmt.execute(params![
@@ -427,8 +1505,20 @@ impl Storage {
                     serde_json::to_string(&tweet.user_mentions)?,
                     serde_json::to_string(&tweet.urls)?,
                     serde_json::to_string(&tweet.media)?,
+                    tweet.geo.as_ref().map(serde_json::to_string).transpose()?,
+                    tweet.poll.as_ref().map(serde_json::to_string).transpose()?,
+                    tweet.reply_count,
+                    tweet.quote_count,
                 ])?;
-                fts_stmt.execute(params![&tweet.id, &tweet.full_text])?;
+                if index_fts {
+                    if !fts_seen.insert(&tweet.id) {
+                        fts_replace_stmt.execute(params![&tweet.id])?;
+                    }
+                    fts_stmt.execute(params![&tweet.id, tweet.full_text, tweet.attached_text()])?;
+                }
+                for tag in &tweet.hashtags {
+                    tag_stmt.execute(params![&tweet.id, tag.to_lowercase()])?;
+                }
                 count += 1;
             }
         }
@@ -466,9 +1556,15 @@ impl Storage {
             )?;
             let mut fts_stmt =
                 tx.prepare("INSERT INTO fts_likes (tweet_id, full_text) VALUES (?, ?)")?;
+            let mut fts_replace_stmt = tx.prepare("DELETE FROM fts_likes WHERE tweet_id = ?")?;
+            let mut fts_seen: HashSet<&str> = HashSet::new();
 
             for like in likes {
                 stmt.execute(params![like.tweet_id, like.full_text, like.expanded_url])?;
+                // A repeated like replaces the earlier FTS row, even when it has no text
+                if !fts_seen.insert(&like.tweet_id) {
+                    fts_replace_stmt.execute(params![&like.tweet_id])?;
+                }
                 if let Some(text) = &like.full_text {
                     if !text.is_empty() {
                         fts_stmt.execute(params![&like.tweet_id, text])?;
@@ -483,6 +1579,41 @@ impl Storage {
         Ok(count)
     }
 
+    /// Store bookmarks in a transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any bookmark insert fails.
+    pub fn store_bookmarks(&mut self, bookmarks: &[Bookmark]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO bookmarks (tweet_id, full_text, expanded_url) VALUES (?, ?, ?)",
+            )?;
+            let mut fts_delete_stmt = tx.prepare("DELETE FROM fts_bookmarks WHERE tweet_id = ?")?;
+            let mut fts_stmt =
+                tx.prepare("INSERT INTO fts_bookmarks (tweet_id, full_text) VALUES (?, ?)")?;
+
+            for bookmark in bookmarks {
+                stmt.execute(params![
+                    bookmark.tweet_id,
+                    bookmark.full_text,
+                    bookmark.expanded_url
+                ])?;
+                // Replace the FTS row, even when the bookmark no longer has text
+                fts_delete_stmt.execute(params![&bookmark.tweet_id])?;
+                if let Some(text) = bookmark.full_text.as_deref().filter(|t| !t.is_empty()) {
+                    fts_stmt.execute(params![&bookmark.tweet_id, text])?;
+                }
+            }
+        }
+
+        tx.commit()?;
+        info!("Stored {} bookmarks", bookmarks.len());
+        Ok(bookmarks.len())
+    }
+
     /// Store DM conversations and messages.
     ///
     /// # Errors
@@ -525,6 +1656,8 @@ impl Storage {
             )?;
 
             let mut fts_stmt = tx.prepare("INSERT INTO fts_dms (dm_id, text) VALUES (?, ?)")?;
+            let mut fts_replace_stmt = tx.prepare("DELETE FROM fts_dms WHERE dm_id = ?")?;
+            let mut fts_seen: HashSet<&str> = HashSet::new();
 
             for conv in conversations {
                 // Get participant IDs and date range
@@ -558,6 +1691,9 @@ impl Storage {
                         serde_json::to_string(&msg.urls)?,
                         serde_json::to_string(&msg.media_urls)?,
                     ])?;
+                    if !fts_seen.insert(&msg.id) {
+                        fts_replace_stmt.execute(params![&msg.id])?;
+                    }
                     fts_stmt.execute(params![&msg.id, &msg.text])?;
                     message_count += 1;
                 }
@@ -572,6 +1708,70 @@ impl Storage {
         Ok(message_count)
     }
 
+    /// Attach archive source JSON to already stored records, compressed.
+    ///
+    /// Run after the matching `store_*` call: re-storing a record replaces its
+    /// row and clears the JSON. Records without a stored row are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if compression or an update fails.
+    pub fn store_raw_json(&mut self, kind: RawKind, records: &[RawRecord]) -> Result<usize> {
+        let (table, id_column) = raw_json_table(kind);
+        let tx = self.conn.transaction()?;
+        let mut count = 0;
+
+        {
+            let mut stmt = tx.prepare(&format!(
+                "UPDATE {table} SET raw_json = ? WHERE {id_column} = ?"
+            ))?;
+            for record in records {
+                count += stmt.execute(params![PackedText(&record.json), record.id])?;
+            }
+        }
+
+        tx.commit()?;
+        info!("Stored source JSON for {} {}", count, table);
+        Ok(count)
+    }
+
+    /// Get the archive source JSON of records of `kind`, by id.
+    ///
+    /// Ids without stored JSON (indexed before it was kept, or in lite mode)
+    /// are absent from the map.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails or a stored value is corrupt.
+    pub fn get_raw_json(
+        &self,
+        kind: RawKind,
+        ids: &[&str],
+    ) -> Result<HashMap<String, serde_json::Value>> {
+        let (table, id_column) = raw_json_table(kind);
+        let mut found = HashMap::with_capacity(ids.len());
+
+        for chunk in ids.chunks(SQLITE_BATCH_SIZE) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let sql = format!(
+                "SELECT {id_column}, raw_json FROM {table}
+                 WHERE raw_json IS NOT NULL AND {id_column} IN ({placeholders})"
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk.iter()), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, UnpackedText>(1)?))
+            })?;
+            for row in rows {
+                let (id, json) = row?;
+                let value = serde_json::from_str(&json.0)
+                    .with_context(|| format!("Corrupt source JSON for {id}"))?;
+                found.insert(id, value);
+            }
+        }
+
+        Ok(found)
+    }
+
     /// Store followers.
     ///
     /// # Errors
@@ -670,132 +1870,1672 @@ impl Storage {
         Ok(count)
     }
 
-    /// Store Grok messages.
+    /// Store lists.
     ///
     /// # Errors
     ///
-    /// Returns an error if any Grok message insert fails.
-    pub fn store_grok_messages(&mut self, messages: &[GrokMessage]) -> Result<usize> {
+    /// Returns an error if any list insert fails.
+    pub fn store_lists(&mut self, lists: &[UserList]) -> Result<usize> {
         let tx = self.conn.transaction()?;
         let mut count = 0;
 
         {
-            // Clear existing data to ensure FTS and main table correspondence.
-            // Unlike tweets/likes/dms which have natural primary keys for INSERT OR REPLACE,
-            // grok_messages uses auto-increment, so we clear and repopulate.
-            tx.execute("DELETE FROM fts_grok", [])?;
-            tx.execute("DELETE FROM grok_messages", [])?;
-
             let mut stmt = tx.prepare(
-                r"
-                INSERT INTO grok_messages (chat_id, message, sender, created_at, grok_mode)
-                VALUES (?, ?, ?, ?, ?)
-                ",
+                "INSERT OR REPLACE INTO lists (list_id, relation, url) VALUES (?, ?, ?)",
             )?;
-            let mut fts_stmt =
-                tx.prepare("INSERT INTO fts_grok (grok_id, message) VALUES (?, ?)")?;
 
-            for msg in messages {
-                stmt.execute(params![
-                    msg.chat_id,
-                    msg.message,
-                    msg.sender,
-                    msg.created_at.to_rfc3339(),
-                    msg.grok_mode,
-                ])?;
-                // Use the auto-generated id as grok_id for proper FTS joining
-                let grok_id = tx.last_insert_rowid();
-                fts_stmt.execute(params![grok_id.to_string(), &msg.message])?;
+            for list in lists {
+                stmt.execute(params![list.list_id, list.relation.as_str(), list.url])?;
                 count += 1;
             }
         }
 
         tx.commit()?;
-        info!("Stored {} Grok messages", count);
+        info!("Stored {} lists", count);
         Ok(count)
     }
 
-    /// Get archive statistics.
+    /// Start (or resume) the snapshot for an archive generation.
+    ///
+    /// Re-indexing the same archive reuses its snapshot so repeated runs do not
+    /// show up as churn.
     ///
     /// # Errors
     ///
-    /// Returns an error if statistics queries fail.
-    pub fn get_stats(&self) -> Result<ArchiveStats> {
-        let counts = self.get_all_counts()?;
-        let index_built_at = self.get_index_built_at()?.unwrap_or_else(Utc::now);
-
-        Ok(ArchiveStats {
-            tweets_count: counts.tweets_count,
-            likes_count: counts.likes_count,
-            dms_count: counts.dms_count,
-            dm_conversations_count: counts.dm_conversations_count,
-            followers_count: counts.followers_count,
-            following_count: counts.following_count,
-            blocks_count: counts.blocks_count,
-            mutes_count: counts.mutes_count,
-            grok_messages_count: counts.grok_messages_count,
-            first_tweet_date: counts.first_tweet_date,
-            last_tweet_date: counts.last_tweet_date,
-            index_built_at,
-        })
+    /// Returns an error if the snapshot cannot be created.
+    pub fn begin_snapshot(&self, info: &ArchiveInfo) -> Result<i64> {
+        let generated_at = info.generation_date.to_rfc3339();
+        self.conn.execute(
+            r"
+            INSERT INTO snapshots (taken_at, archive_generated_at) VALUES (?, ?)
+            ON CONFLICT(archive_generated_at) DO UPDATE SET taken_at = excluded.taken_at
+            ",
+            params![Utc::now().to_rfc3339(), generated_at],
+        )?;
+        let id = self.conn.query_row(
+            "SELECT id FROM snapshots WHERE archive_generated_at = ?",
+            params![generated_at],
+            |row| row.get(0),
+        )?;
+        Ok(id)
     }
 
-    /// Get the index build timestamp from the archive metadata, if present.
+    /// Record the account IDs of one kind for a snapshot, replacing any
+    /// previously recorded list.
     ///
     /// # Errors
     ///
-    /// Returns an error if the query fails.
-    pub fn get_index_built_at(&self) -> Result<Option<DateTime<Utc>>> {
-        let result: std::result::Result<String, _> = self.conn.query_row(
-            "SELECT indexed_at FROM archive_info WHERE id = 1",
-            [],
-            |row| row.get(0),
-        );
+    /// Returns an error if any insert fails.
+    pub fn store_snapshot_accounts<'a>(
+        &mut self,
+        snapshot_id: i64,
+        kind: SnapshotKind,
+        account_ids: impl IntoIterator<Item = &'a str>,
+    ) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut count = 0;
 
-        match result {
-            Ok(ts) => Ok(parse_rfc3339_opt(Some(ts))),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(err) => Err(err.into()),
+        {
+            tx.execute(
+                "DELETE FROM snapshot_accounts WHERE snapshot_id = ? AND kind = ?",
+                params![snapshot_id, kind.as_str()],
+            )?;
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO snapshot_accounts (snapshot_id, kind, account_id) VALUES (?, ?, ?)",
+            )?;
+
+            for account_id in account_ids {
+                count += stmt.execute(params![snapshot_id, kind.as_str(), account_id])?;
+            }
         }
+
+        tx.commit()?;
+        info!(
+            "Stored {} {} accounts for snapshot {}",
+            count,
+            kind.as_str(),
+            snapshot_id
+        );
+        Ok(count)
     }
 
-    /// Get all archive counts and tweet date bounds in a single query.
+    /// List snapshots ordered by archive generation date (oldest first).
     ///
     /// # Errors
     ///
-    /// Returns an error if the consolidated query fails.
-    pub fn get_all_counts(&self) -> Result<AllCounts> {
-        let query = r"
-            SELECT
-                (SELECT COUNT(*) FROM tweets) AS tweets_count,
-                (SELECT COUNT(*) FROM likes) AS likes_count,
-                (SELECT COUNT(*) FROM direct_messages) AS dms_count,
-                (SELECT COUNT(*) FROM dm_conversations) AS dm_conversations_count,
-                (SELECT COUNT(*) FROM followers) AS followers_count,
-                (SELECT COUNT(*) FROM following) AS following_count,
-                (SELECT COUNT(*) FROM blocks) AS blocks_count,
-                (SELECT COUNT(*) FROM mutes) AS mutes_count,
-                (SELECT COUNT(*) FROM grok_messages) AS grok_messages_count,
-                (SELECT MIN(created_at) FROM tweets) AS first_tweet_date,
-                (SELECT MAX(created_at) FROM tweets) AS last_tweet_date
-        ";
+    /// Returns an error if the database query fails.
+    pub fn get_snapshots(&self) -> Result<Vec<Snapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, taken_at, archive_generated_at FROM snapshots ORDER BY archive_generated_at, id",
+        )?;
+        let snapshots = stmt
+            .query_map([], |row| {
+                Ok(Snapshot {
+                    id: row.get(0)?,
+                    taken_at: parse_rfc3339_or_epoch(row.get(1)?),
+                    archive_generated_at: parse_rfc3339_or_epoch(row.get(2)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok(self.conn.query_row(query, [], |row| {
-            let first_tweet_date: Option<String> = row.get(9)?;
-            let last_tweet_date: Option<String> = row.get(10)?;
-            Ok(AllCounts {
-                tweets_count: row.get(0)?,
-                likes_count: row.get(1)?,
-                dms_count: row.get(2)?,
-                dm_conversations_count: row.get(3)?,
-                followers_count: row.get(4)?,
-                following_count: row.get(5)?,
-                blocks_count: row.get(6)?,
-                mutes_count: row.get(7)?,
-                grok_messages_count: row.get(8)?,
-                first_tweet_date: parse_rfc3339_opt(first_tweet_date),
-                last_tweet_date: parse_rfc3339_opt(last_tweet_date),
-            })
+        Ok(snapshots)
+    }
+
+    /// Record the profile as of an archive, replacing any version recorded
+    /// for the same archive generation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails.
+    pub fn store_profile_version(
+        &self,
+        info: &ArchiveInfo,
+        profile: Option<&Profile>,
+    ) -> Result<()> {
+        self.conn.execute(
+            r"
+            INSERT OR REPLACE INTO profile_history
+            (archive_generated_at, username, display_name, bio, website, location, avatar_url,
+             header_url)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+            params![
+                info.generation_date.to_rfc3339(),
+                info.username,
+                info.display_name,
+                profile.and_then(|p| p.bio.as_deref()),
+                profile.and_then(|p| p.website.as_deref()),
+                profile.and_then(|p| p.location.as_deref()),
+                profile.and_then(|p| p.avatar_url.as_deref()),
+                profile.and_then(|p| p.header_url.as_deref()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Recorded profile versions, oldest archive first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_profile_history(&self) -> Result<Vec<ProfileVersion>> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT archive_generated_at, username, display_name, bio, website, location,
+                   avatar_url, header_url
+            FROM profile_history
+            ORDER BY archive_generated_at
+            ",
+        )?;
+        let versions = stmt
+            .query_map([], |row| {
+                Ok(ProfileVersion {
+                    archive_generated_at: parse_rfc3339_or_epoch(row.get(0)?),
+                    username: row.get(1)?,
+                    display_name: row.get(2)?,
+                    bio: row.get(3)?,
+                    website: row.get(4)?,
+                    location: row.get(5)?,
+                    avatar_url: row.get(6)?,
+                    header_url: row.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(versions)
+    }
+
+    /// Store handle changes from screen-name-change.js.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails.
+    pub fn store_screen_name_changes(&mut self, changes: &[ScreenNameChange]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                r"
+                INSERT OR REPLACE INTO screen_name_changes
+                (account_id, changed_at, changed_from, changed_to)
+                VALUES (?, ?, ?, ?)
+                ",
+            )?;
+            for change in changes {
+                stmt.execute(params![
+                    change.account_id,
+                    change.changed_at.to_rfc3339(),
+                    change.changed_from,
+                    change.changed_to,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Rebuild the account ID to handle table from tweet mentions and
+    /// replies, handle changes, and the archive owner's profile history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rebuild fails.
+    pub fn rebuild_account_handles(&self) -> Result<()> {
+        self.conn.execute_batch(
+            r"
+            DELETE FROM account_handles;
+            INSERT INTO account_handles (account_id, screen_name, first_seen, last_seen)
+            SELECT account_id, screen_name, MIN(seen_at), MAX(seen_at)
+            FROM (
+                SELECT json_extract(m.value, '$.id') AS account_id,
+                       json_extract(m.value, '$.screen_name') AS screen_name,
+                       t.created_at AS seen_at
+                FROM tweets t, json_each(t.mentions_json) m
+                UNION ALL
+                SELECT in_reply_to_user_id, in_reply_to_screen_name, created_at
+                FROM tweets
+                WHERE in_reply_to_user_id IS NOT NULL
+                UNION ALL
+                SELECT account_id, changed_from, changed_at FROM screen_name_changes
+                UNION ALL
+                SELECT account_id, changed_to, changed_at FROM screen_name_changes
+                UNION ALL
+                SELECT a.account_id, p.username, p.archive_generated_at
+                FROM profile_history p, archive_info a
+            )
+            WHERE account_id IS NOT NULL AND account_id != ''
+              AND screen_name IS NOT NULL AND screen_name != ''
+            GROUP BY account_id, screen_name;
+            ",
+        )?;
+        Ok(())
+    }
+
+    /// Rebuild the summary tables behind `xf stats`: tweets per day and per
+    /// hour, hashtag counts, the likes histogram buckets, and each tweet's
+    /// engagement ranks.
+    ///
+    /// Storing tweets marks the summaries stale; readers fall back to live
+    /// queries until the next refresh.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rebuild fails.
+    pub fn refresh_stats_summaries(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(&format!(
+            r"
+            DELETE FROM stats_daily;
+            INSERT INTO stats_daily (day, count)
+            SELECT DATE(created_at) AS day, COUNT(*) FROM tweets
+            WHERE created_at IS NOT NULL AND day IS NOT NULL
+            GROUP BY day;
+
+            DELETE FROM stats_hourly;
+            INSERT INTO stats_hourly (hour, count)
+            SELECT CAST(strftime('%H', created_at) AS INTEGER) AS hour, COUNT(*) FROM tweets
+            WHERE created_at IS NOT NULL AND hour IS NOT NULL
+            GROUP BY hour;
+
+            DELETE FROM stats_hashtags;
+            INSERT INTO stats_hashtags (tag, count)
+            SELECT tag, COUNT(*) FROM tweet_hashtags GROUP BY tag;
+
+            DELETE FROM stats_likes_buckets;
+            INSERT INTO stats_likes_buckets (bucket, count)
+            SELECT {LIKES_BUCKET_SQL} AS bucket, COUNT(*) FROM tweets
+            WHERE favorite_count IS NOT NULL
+            GROUP BY bucket;
+
+            DELETE FROM stats_engagement_ranks;
+            INSERT INTO stats_engagement_ranks
+                (tweet_id, likes_rank, retweets_rank, engagement_rank)
+            {ranks};
+
+            INSERT OR REPLACE INTO meta (key, value) VALUES ('stats_summaries', '{}');
+            ",
+            Utc::now().to_rfc3339(),
+            ranks = engagement_ranks_sql(),
+        ))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Where a tweet ranks among your original tweets by likes, retweets,
+    /// and both combined. `None` for retweets and unknown IDs.
+    ///
+    /// Reads `stats_engagement_ranks` when the summaries are fresh and ranks
+    /// all tweets live otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_engagement_percentile(&self, id: &str) -> Result<Option<EngagementPercentile>> {
+        let source = if self.has_stats_summaries() {
+            "stats_engagement_ranks".to_string()
+        } else {
+            format!("({})", engagement_ranks_sql())
+        };
+        let ranks = self.conn.query_row(
+            &format!(
+                "SELECT likes_rank, retweets_rank, engagement_rank FROM {source} WHERE tweet_id = ?1"
+            ),
+            [id],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+        );
+        let (likes, retweets, engagement) = match ranks {
+            Ok(ranks) => ranks,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let tweets: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM tweets WHERE is_retweet = 0",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let to_u64 = |n: i64| u64::try_from(n).unwrap_or(0);
+        let tweets = to_u64(tweets);
+        Ok(Some(EngagementPercentile {
+            tweets,
+            likes: RankShare::new(to_u64(likes), tweets),
+            retweets: RankShare::new(to_u64(retweets), tweets),
+            engagement: RankShare::new(to_u64(engagement), tweets),
+        }))
+    }
+
+    /// Whether the stats summary tables reflect the stored tweets.
+    ///
+    /// They never do once [`Self::exclude_documents`] has left documents out.
+    #[must_use]
+    pub fn has_stats_summaries(&self) -> bool {
+        self.conn
+            .query_row(
+                "SELECT 1 FROM meta WHERE key = 'stats_summaries'
+                 AND NOT EXISTS (
+                     SELECT 1 FROM sqlite_temp_master WHERE name = 'excluded_documents'
+                 )",
+                [],
+                |_| Ok(()),
+            )
+            .is_ok()
+    }
+
+    /// Leave documents (IDs by doc type, as from [`Self::get_hidden_ids`])
+    /// out of every later query on this connection and on connections
+    /// from [`Self::open_readers`], so stats don't count hidden documents
+    /// or those after `--as-of`.
+    ///
+    /// Temporary views shadow the `tweets`, `tweet_hashtags`, `likes`,
+    /// `direct_messages`, and `grok_messages` tables, so queries need no
+    /// changes. Does nothing when `excluded` is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the temporary tables or views cannot be created.
+    pub fn exclude_documents(&self, excluded: &HashMap<String, HashSet<String>>) -> Result<()> {
+        let mut rows = Vec::new();
+        for (doc_type, ids) in excluded {
+            for id in ids {
+                if doc_type == "grok" {
+                    if let Some(rowid) = self.grok_row_id(id)? {
+                        rows.push((doc_type.clone(), rowid.to_string()));
+                    }
+                } else {
+                    rows.push((doc_type.clone(), id.clone()));
+                }
+            }
+        }
+        if rows.is_empty() {
+            return Ok(());
+        }
+        create_exclusion_views(&self.conn, &rows)
+    }
+
+    /// Rows of the `excluded_documents` temporary table, if there is one.
+    fn excluded_document_rows(&self) -> Result<Vec<(String, String)>> {
+        let exists = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM sqlite_temp_master WHERE name = 'excluded_documents'",
+                [],
+                |_| Ok(()),
+            )
+            .is_ok();
+        if !exists {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self
+            .conn
+            .prepare("SELECT doc_type, doc_id FROM temp.excluded_documents")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Load the account ID to handle table into a resolver.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_handle_resolver(&self) -> Result<HandleResolver> {
+        let mut stmt = self.conn.prepare(
+            "SELECT account_id, screen_name, first_seen, last_seen FROM account_handles",
+        )?;
+        let handles = stmt
+            .query_map([], |row| {
+                Ok(AccountHandle {
+                    account_id: row.get(0)?,
+                    screen_name: row.get(1)?,
+                    first_seen: parse_rfc3339_or_epoch(row.get(2)?),
+                    last_seen: parse_rfc3339_or_epoch(row.get(3)?),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(HandleResolver::new(handles))
+    }
+
+    /// Account IDs ever seen using `screen_name` (case-insensitive, without `@`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_account_ids_for_handle(&self, screen_name: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT DISTINCT account_id FROM account_handles
+            WHERE screen_name = ?1 COLLATE NOCASE
+            ORDER BY account_id
+            ",
+        )?;
+        let ids = stmt
+            .query_map([screen_name], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(ids)
+    }
+
+    /// Load the account IDs of one kind recorded for a snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_snapshot_accounts(
+        &self,
+        snapshot_id: i64,
+        kind: SnapshotKind,
+    ) -> Result<std::collections::HashSet<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT account_id FROM snapshot_accounts WHERE snapshot_id = ? AND kind = ?",
+        )?;
+        let accounts: std::collections::HashSet<String> = stmt
+            .query_map(params![snapshot_id, kind.as_str()], |row| row.get(0))?
+            .collect::<std::result::Result<_, _>>()?;
+
+        Ok(accounts)
+    }
+
+    /// Record that documents appear in an archive generated at `seen_at`,
+    /// keeping the earliest date for documents seen before.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any insert fails.
+    pub fn record_first_seen<'a>(
+        &mut self,
+        doc_type: &str,
+        doc_ids: impl IntoIterator<Item = &'a str>,
+        seen_at: DateTime<Utc>,
+    ) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut count = 0;
+        {
+            let mut stmt = tx.prepare(
+                r"
+                INSERT INTO document_first_seen (doc_type, doc_id, first_seen_at) VALUES (?, ?, ?)
+                ON CONFLICT(doc_type, doc_id)
+                DO UPDATE SET first_seen_at = MIN(first_seen_at, excluded.first_seen_at)
+                ",
+            )?;
+            for doc_id in doc_ids {
+                count += stmt.execute(params![doc_type, doc_id, seen_at.timestamp()])?;
+            }
+        }
+        tx.commit()?;
+        Ok(count)
+    }
+
+    /// Mark every stored tweet, like, and DM without a first-seen date as
+    /// seen at `seen_at`.
+    fn backfill_first_seen(&self, seen_at: DateTime<Utc>) -> Result<()> {
+        for (doc_type, table, id_column) in [
+            ("tweet", "tweets", "id"),
+            ("like", "likes", "tweet_id"),
+            ("dm", "direct_messages", "id"),
+        ] {
+            self.conn.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO document_first_seen (doc_type, doc_id, first_seen_at)
+                     SELECT ?1, {id_column}, ?2 FROM {table}"
+                ),
+                params![doc_type, seen_at.timestamp()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Generation date of the latest indexed archive generated on or before
+    /// `as_of`, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn first_seen_snapshot(&self, as_of: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        let secs: Option<i64> = self.conn.query_row(
+            "SELECT MAX(first_seen_at) FROM document_first_seen WHERE first_seen_at <= ?",
+            params![as_of.timestamp()],
+            |row| row.get(0),
+        )?;
+        Ok(secs.and_then(|secs| DateTime::from_timestamp(secs, 0)))
+    }
+
+    /// Generation date of the earliest indexed archive, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn earliest_first_seen(&self) -> Result<Option<DateTime<Utc>>> {
+        let secs: Option<i64> = self.conn.query_row(
+            "SELECT MIN(first_seen_at) FROM document_first_seen",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(secs.and_then(|secs| DateTime::from_timestamp(secs, 0)))
+    }
+
+    /// IDs of documents first seen in an archive generated after `as_of`,
+    /// grouped by document type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_ids_first_seen_after(
+        &self,
+        as_of: DateTime<Utc>,
+    ) -> Result<HashMap<String, HashSet<String>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT doc_type, doc_id FROM document_first_seen WHERE first_seen_at > ?")?;
+        let mut ids: HashMap<String, HashSet<String>> = HashMap::new();
+        let rows = stmt.query_map(params![as_of.timestamp()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (doc_type, doc_id) = row?;
+            ids.entry(doc_type).or_default().insert(doc_id);
+        }
+        Ok(ids)
+    }
+
+    /// Create or replace a tag definition.
+    ///
+    /// Redefining a tag keeps its existing assignments until the next
+    /// `xf tag apply`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database insert fails.
+    pub fn define_tag(&self, name: &str, examples: &[String], threshold: f32) -> Result<()> {
+        self.conn.execute(
+            r"
+            INSERT INTO tag_definitions (name, examples_json, threshold, created_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                examples_json = excluded.examples_json,
+                threshold = excluded.threshold
+            ",
+            params![
+                name,
+                serde_json::to_string(examples)?,
+                f64::from(threshold),
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// List tag definitions ordered by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_tag_definitions(&self) -> Result<Vec<TagDefinition>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, examples_json, threshold, created_at FROM tag_definitions ORDER BY name",
+        )?;
+        let definitions = stmt
+            .query_map([], |row| {
+                let examples: String = row.get(1)?;
+                #[allow(clippy::cast_possible_truncation)]
+                let threshold = row.get::<_, f64>(2)? as f32;
+                Ok(TagDefinition {
+                    name: row.get(0)?,
+                    examples: serde_json::from_str(&examples).unwrap_or_default(),
+                    threshold,
+                    created_at: parse_rfc3339_or_epoch(row.get(3)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(definitions)
+    }
+
+    /// Delete a tag definition and its assignments. Returns `false` if the
+    /// tag was not defined.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn delete_tag(&mut self, name: &str) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM tags WHERE tag = ?", params![name])?;
+        let removed = tx.execute("DELETE FROM tag_definitions WHERE name = ?", params![name])?;
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+
+    /// Replace all assignments of `tag` with `assignments` (tweet ID, score).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any insert fails.
+    pub fn store_tag_assignments(
+        &mut self,
+        tag: &str,
+        assignments: &[(String, f32)],
+    ) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut count = 0;
+
+        {
+            tx.execute("DELETE FROM tags WHERE tag = ?", params![tag])?;
+            let mut stmt =
+                tx.prepare("INSERT OR REPLACE INTO tags (tweet_id, tag, score) VALUES (?, ?, ?)")?;
+            for (tweet_id, score) in assignments {
+                count += stmt.execute(params![tweet_id, tag, f64::from(*score)])?;
+            }
+        }
+
+        tx.commit()?;
+        info!("Tagged {} tweets as {}", count, tag);
+        Ok(count)
+    }
+
+    /// Number of tweets carrying each tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_tag_counts(&self) -> Result<HashMap<String, i64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag, COUNT(*) FROM tags GROUP BY tag")?;
+        let counts = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<_, _>>()?;
+
+        Ok(counts)
+    }
+
+    /// IDs of tweets carrying `tag`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_tagged_tweet_ids(&self, tag: &str) -> Result<std::collections::HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tweet_id FROM tags WHERE tag = ?")?;
+        let ids = stmt
+            .query_map(params![tag], |row| row.get(0))?
+            .collect::<std::result::Result<_, _>>()?;
+
+        Ok(ids)
+    }
+
+    /// Attach a note to a document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database insert fails.
+    pub fn add_note(&mut self, doc_id: &str, doc_type: &str, text: &str) -> Result<Note> {
+        let created_at = Utc::now();
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO notes (doc_id, doc_type, text, created_at) VALUES (?, ?, ?, ?)",
+            params![doc_id, doc_type, text, created_at.to_rfc3339()],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.execute(
+            "INSERT INTO fts_notes (note_id, text) VALUES (?, ?)",
+            params![id, text],
+        )?;
+        tx.commit()?;
+        self.refresh_fts_supplemental(doc_type, doc_id)?;
+
+        Ok(Note {
+            id,
+            doc_id: doc_id.to_string(),
+            doc_type: doc_type.to_string(),
+            text: text.to_string(),
+            created_at,
+        })
+    }
+
+    /// Delete a note by ID, returning it; `None` if no such note exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn delete_note(&mut self, id: i64) -> Result<Option<Note>> {
+        let tx = self.conn.transaction()?;
+        let note = match tx.query_row(
+            "SELECT id, doc_id, doc_type, text, created_at FROM notes WHERE id = ?",
+            params![id],
+            note_from_row,
+        ) {
+            Ok(note) => Some(note),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(e.into()),
+        };
+        tx.execute("DELETE FROM fts_notes WHERE note_id = ?", params![id])?;
+        tx.execute("DELETE FROM notes WHERE id = ?", params![id])?;
+        tx.commit()?;
+        if let Some(note) = &note {
+            self.refresh_fts_supplemental(&note.doc_type, &note.doc_id)?;
+        }
+        Ok(note)
+    }
+
+    /// `(doc_id, doc_type)` of every document with notes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn noted_documents(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT DISTINCT doc_id, doc_type FROM notes ORDER BY doc_type, doc_id",
+        )?;
+        let docs = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(docs)
+    }
+
+    /// Supplemental text of a document, as the search indexes hold it: a
+    /// tweet's poll options and media alt text, then the notes on the
+    /// document, one per line. `None` when there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn supplemental_text(&self, doc_type: &str, doc_id: &str) -> Result<Option<String>> {
+        let attached = if doc_type == "tweet" {
+            self.get_tweet(doc_id)?
+                .and_then(|tweet| tweet.attached_text())
+        } else {
+            None
+        };
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT text FROM notes WHERE doc_type = ?1 AND doc_id = ?2 ORDER BY created_at, id",
+        )?;
+        let notes = stmt
+            .query_map(params![doc_type, doc_id], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let lines: Vec<String> = attached.into_iter().chain(notes).collect();
+        Ok((!lines.is_empty()).then(|| lines.join("\n")))
+    }
+
+    /// Rewrite the `supplemental` column of a document's FTS row, after its
+    /// notes changed. Documents without an FTS row are left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub fn refresh_fts_supplemental(&self, doc_type: &str, doc_id: &str) -> Result<()> {
+        let supplemental = self.supplemental_text(doc_type, doc_id)?;
+        let (table, key, id) = match doc_type {
+            "tweet" => ("fts_tweets", "tweet_id", doc_id.to_string()),
+            "like" => ("fts_likes", "tweet_id", doc_id.to_string()),
+            "bookmark" => ("fts_bookmarks", "tweet_id", doc_id.to_string()),
+            "dm" => ("fts_dms", "dm_id", doc_id.to_string()),
+            "grok" => match self.grok_row_id(doc_id)? {
+                Some(rowid) => ("fts_grok", "grok_id", rowid.to_string()),
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        self.conn.execute(
+            &format!("UPDATE {table} SET supplemental = ?1 WHERE {key} = ?2"),
+            params![supplemental, id],
+        )?;
+        Ok(())
+    }
+
+    /// Re-apply every document's notes to the FTS tables, which replacing
+    /// or rebuilding rows resets. Returns the number of noted documents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub fn refresh_note_supplementals(&self) -> Result<usize> {
+        let docs = self.noted_documents()?;
+        for (doc_id, doc_type) in &docs {
+            self.refresh_fts_supplemental(doc_type, doc_id)?;
+        }
+        Ok(docs.len())
+    }
+
+    /// Row ID of the Grok message with a search document ID
+    /// (`<chat id>_<seconds>_<nanoseconds>_<sender>`).
+    fn grok_row_id(&self, doc_id: &str) -> Result<Option<i64>> {
+        let mut parts = doc_id.rsplitn(4, '_');
+        let (Some(sender), Some(nanos), Some(seconds), Some(chat_id)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Ok(None);
+        };
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, created_at FROM grok_messages WHERE chat_id = ?1 AND sender = ?2",
+        )?;
+        let rows = stmt
+            .query_map(params![chat_id, sender], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows.into_iter().find_map(|(id, created_at)| {
+            let created_at = DateTime::parse_from_rfc3339(&created_at).ok()?;
+            (created_at.timestamp().to_string() == seconds
+                && created_at.timestamp_subsec_nanos().to_string() == nanos)
+                .then_some(id)
+        }))
+    }
+
+    /// Get notes, oldest first, optionally only those on one document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_notes(&self, doc_id: Option<&str>, limit: Option<usize>) -> Result<Vec<Note>> {
+        let limit_param: i64 = limit.map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX));
+        let mut stmt = self.conn.prepare_cached(
+            r"
+            SELECT id, doc_id, doc_type, text, created_at FROM notes
+            WHERE ?1 IS NULL OR doc_id = ?1
+            ORDER BY created_at, id
+            LIMIT ?2
+            ",
+        )?;
+        let notes = stmt
+            .query_map(params![doc_id, limit_param], note_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    /// Get the notes on a set of documents, keyed by `(doc_id, doc_type)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_notes_for_docs(
+        &self,
+        doc_ids: &[&str],
+    ) -> Result<HashMap<(String, String), Vec<Note>>> {
+        let mut by_doc: HashMap<(String, String), Vec<Note>> = HashMap::new();
+        for chunk in doc_ids.chunks(500) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let sql = format!(
+                "SELECT id, doc_id, doc_type, text, created_at FROM notes WHERE doc_id IN ({placeholders}) ORDER BY created_at, id"
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let notes = stmt.query_map(rusqlite::params_from_iter(chunk), note_from_row)?;
+            for note in notes {
+                let note = note?;
+                by_doc
+                    .entry((note.doc_id.clone(), note.doc_type.clone()))
+                    .or_default()
+                    .push(note);
+            }
+        }
+
+        Ok(by_doc)
+    }
+
+    /// Full-text search over note text, best match first.
+    ///
+    /// Query words are matched literally (all must appear), so search
+    /// syntax that FTS5 would reject cannot cause an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn search_notes(&self, query: &str, limit: usize) -> Result<Vec<Note>> {
+        let terms: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty() && !matches!(*word, "AND" | "OR" | "NOT"))
+            .map(|word| format!("\"{word}\""))
+            .collect();
+        if terms.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut stmt = self.conn.prepare_cached(
+            r"
+            SELECT n.id, n.doc_id, n.doc_type, n.text, n.created_at
+            FROM fts_notes
+            JOIN notes n ON n.id = fts_notes.note_id
+            WHERE fts_notes MATCH ?
+            ORDER BY rank
+            LIMIT ?
+            ",
+        )?;
+        let notes = stmt
+            .query_map(
+                params![terms.join(" "), i64::try_from(limit).unwrap_or(i64::MAX)],
+                note_from_row,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    /// Add a document to a collection, creating the collection if needed.
+    ///
+    /// Returns `false` if the document was already in the collection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database insert fails.
+    pub fn add_to_collection(&mut self, name: &str, doc_id: &str, doc_type: &str) -> Result<bool> {
+        let now = Utc::now().to_rfc3339();
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO collections (name, created_at) VALUES (?, ?)",
+            params![name, now],
+        )?;
+        let added = tx.execute(
+            r"
+            INSERT OR IGNORE INTO collection_items (collection, doc_id, doc_type, added_at)
+            VALUES (?, ?, ?, ?)
+            ",
+            params![name, doc_id, doc_type, now],
+        )?;
+        tx.commit()?;
+        Ok(added > 0)
+    }
+
+    /// Remove a document (of any type) from a collection, returning how many
+    /// entries were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn remove_from_collection(&self, name: &str, doc_id: &str) -> Result<usize> {
+        let removed = self.conn.execute(
+            "DELETE FROM collection_items WHERE collection = ? AND doc_id = ?",
+            params![name, doc_id],
+        )?;
+        Ok(removed)
+    }
+
+    /// Delete a collection and its items. Returns `false` if it did not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn delete_collection(&mut self, name: &str) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM collection_items WHERE collection = ?",
+            params![name],
+        )?;
+        let removed = tx.execute("DELETE FROM collections WHERE name = ?", params![name])?;
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+
+    /// List collections with their item counts, ordered by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_collections(&self) -> Result<Vec<Collection>> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT c.name, COUNT(i.doc_id), c.created_at
+            FROM collections c
+            LEFT JOIN collection_items i ON i.collection = c.name
+            GROUP BY c.name
+            ORDER BY c.name
+            ",
+        )?;
+        let collections = stmt
+            .query_map([], |row| {
+                Ok(Collection {
+                    name: row.get(0)?,
+                    items: row.get(1)?,
+                    created_at: parse_rfc3339_or_epoch(row.get(2)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(collections)
+    }
+
+    /// Get the documents in a collection in the order they were added, or
+    /// `None` if the collection does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_collection_items(&self, name: &str) -> Result<Option<Vec<CollectionItem>>> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM collections WHERE name = ?)",
+            params![name],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Ok(None);
+        }
+
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT doc_id, doc_type, added_at FROM collection_items
+            WHERE collection = ?
+            ORDER BY added_at, rowid
+            ",
+        )?;
+        let items = stmt
+            .query_map(params![name], |row| {
+                Ok(CollectionItem {
+                    doc_id: row.get(0)?,
+                    doc_type: row.get(1)?,
+                    added_at: parse_rfc3339_or_epoch(row.get(2)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(Some(items))
+    }
+
+    /// Hide a document from results. Returns `false` if it was already hidden.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database insert fails.
+    pub fn hide_document(&self, doc_id: &str, doc_type: &str) -> Result<bool> {
+        let added = self.conn.execute(
+            r"
+            INSERT OR IGNORE INTO hidden_documents (doc_id, doc_type, hidden_at)
+            VALUES (?, ?, ?)
+            ",
+            params![doc_id, doc_type, Utc::now().to_rfc3339()],
+        )?;
+        Ok(added > 0)
+    }
+
+    /// Unhide a document, optionally of one type only, returning how many
+    /// entries were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn unhide_document(&self, doc_id: &str, doc_type: Option<&str>) -> Result<usize> {
+        let removed = match doc_type {
+            Some(doc_type) => self.conn.execute(
+                "DELETE FROM hidden_documents WHERE doc_id = ? AND doc_type = ?",
+                params![doc_id, doc_type],
+            )?,
+            None => self.conn.execute(
+                "DELETE FROM hidden_documents WHERE doc_id = ?",
+                params![doc_id],
+            )?,
+        };
+        Ok(removed)
+    }
+
+    /// Get hidden documents, most recently hidden first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_hidden_documents(&self) -> Result<Vec<HiddenDocument>> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT doc_id, doc_type, hidden_at FROM hidden_documents
+            ORDER BY hidden_at DESC, rowid DESC
+            ",
+        )?;
+        let hidden = stmt
+            .query_map([], |row| {
+                Ok(HiddenDocument {
+                    doc_id: row.get(0)?,
+                    doc_type: row.get(1)?,
+                    hidden_at: parse_rfc3339_or_epoch(row.get(2)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(hidden)
+    }
+
+    /// Get hidden document IDs grouped by document type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_hidden_ids(&self) -> Result<HashMap<String, std::collections::HashSet<String>>> {
+        let mut hidden: HashMap<String, std::collections::HashSet<String>> = HashMap::new();
+        for document in self.get_hidden_documents()? {
+            hidden
+                .entry(document.doc_type)
+                .or_default()
+                .insert(document.doc_id);
+        }
+        Ok(hidden)
+    }
+
+    /// Pin a document, for one query or (with `None`) any search it
+    /// matches. Returns `false` if the pin already existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database insert fails.
+    pub fn add_pin(&self, doc_id: &str, doc_type: &str, query: Option<&str>) -> Result<bool> {
+        let added = self.conn.execute(
+            r"
+            INSERT OR IGNORE INTO pins (doc_id, doc_type, query, created_at)
+            VALUES (?, ?, ?, ?)
+            ",
+            params![
+                doc_id,
+                doc_type,
+                query.unwrap_or_default(),
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(added > 0)
+    }
+
+    /// Remove a document's pins, only the one for `query` if given,
+    /// returning how many were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn remove_pin(&self, doc_id: &str, query: Option<&str>) -> Result<usize> {
+        let removed = match query {
+            Some(query) => self.conn.execute(
+                "DELETE FROM pins WHERE doc_id = ? AND query = ?",
+                params![doc_id, query],
+            )?,
+            None => self
+                .conn
+                .execute("DELETE FROM pins WHERE doc_id = ?", params![doc_id])?,
+        };
+        Ok(removed)
+    }
+
+    /// Get all pins, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_pins(&self) -> Result<Vec<Pin>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT doc_id, doc_type, query, created_at FROM pins ORDER BY created_at, rowid",
+        )?;
+        let pins = stmt
+            .query_map([], |row| {
+                let query: String = row.get(2)?;
+                Ok(Pin {
+                    doc_id: row.get(0)?,
+                    doc_type: row.get(1)?,
+                    query: (!query.is_empty()).then_some(query),
+                    created_at: parse_rfc3339_or_epoch(row.get(3)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(pins)
+    }
+
+    /// Save a search as an alert. Returns `false` if an alert with this
+    /// name already exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database insert fails.
+    pub fn add_alert(&self, alert: &Alert) -> Result<bool> {
+        let added = self.conn.execute(
+            r"
+            INSERT OR IGNORE INTO alerts
+            (name, query, types, mode, sort, filters_json, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ",
+            params![
+                alert.name,
+                alert.query,
+                alert.types.join(","),
+                alert.mode,
+                alert.sort,
+                serde_json::to_string(&alert.filters)?,
+                alert.created_at.to_rfc3339()
+            ],
+        )?;
+        Ok(added > 0)
+    }
+
+    /// Delete an alert and its recorded matches. Returns `false` if there
+    /// was no such alert.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn remove_alert(&mut self, name: &str) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM alert_matches WHERE alert = ?", params![name])?;
+        let removed = tx.execute("DELETE FROM alerts WHERE name = ?", params![name])?;
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+
+    /// Get all alerts, by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_alerts(&self) -> Result<Vec<Alert>> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT name, query, types, mode, sort, filters_json, created_at, last_run_at
+            FROM alerts ORDER BY name
+            ",
+        )?;
+        let alerts = stmt
+            .query_map([], |row| {
+                let types: String = row.get(2)?;
+                let last_run_at: Option<String> = row.get(7)?;
+                Ok(Alert {
+                    name: row.get(0)?,
+                    query: row.get(1)?,
+                    types: types
+                        .split(',')
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    mode: row.get(3)?,
+                    sort: row.get(4)?,
+                    filters: serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or_default(),
+                    created_at: parse_rfc3339_or_epoch(row.get(6)?),
+                    last_run_at: last_run_at.map(|at| parse_rfc3339_or_epoch(Some(at))),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(alerts)
+    }
+
+    /// `(doc_type, doc_id)` of the documents an alert matched on its last run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_alert_matches(&self, name: &str) -> Result<HashSet<(String, String)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT doc_type, doc_id FROM alert_matches WHERE alert = ?")?;
+        let matches = stmt
+            .query_map(params![name], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(matches)
+    }
+
+    /// Replace an alert's recorded matches with `(doc_type, doc_id)` pairs
+    /// and mark it as run now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub fn record_alert_run(&mut self, name: &str, matches: &[(String, String)]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM alert_matches WHERE alert = ?", params![name])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO alert_matches (alert, doc_id, doc_type) VALUES (?, ?, ?)",
+            )?;
+            for (doc_type, doc_id) in matches {
+                stmt.execute(params![name, doc_id, doc_type])?;
+            }
+        }
+        tx.execute(
+            "UPDATE alerts SET last_run_at = ? WHERE name = ?",
+            params![Utc::now().to_rfc3339(), name],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Append an entry to the audit log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database insert fails.
+    pub fn record_audit(
+        &self,
+        command: &str,
+        event: &str,
+        detail: &str,
+        count: usize,
+    ) -> Result<()> {
+        self.conn.execute(
+            r"
+            INSERT INTO audit_log (at, command, event, detail, count)
+            VALUES (?, ?, ?, ?, ?)
+            ",
+            params![
+                Utc::now().to_rfc3339(),
+                command,
+                event,
+                detail,
+                i64::try_from(count).unwrap_or(i64::MAX)
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get audit log entries, newest first, optionally since a time and for
+    /// one command.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_audit_entries(
+        &self,
+        since: Option<DateTime<Utc>>,
+        command: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT id, at, command, event, detail, count FROM audit_log
+            WHERE (?1 IS NULL OR at >= ?1) AND (?2 IS NULL OR command = ?2)
+            ORDER BY id DESC
+            LIMIT ?3
+            ",
+        )?;
+        let entries = stmt
+            .query_map(
+                params![
+                    since.map(|s| s.to_rfc3339()),
+                    command,
+                    i64::try_from(limit).unwrap_or(i64::MAX)
+                ],
+                |row| {
+                    Ok(AuditEntry {
+                        id: row.get(0)?,
+                        at: parse_rfc3339_or_epoch(row.get(1)?),
+                        command: row.get(2)?,
+                        event: row.get(3)?,
+                        detail: row.get(4)?,
+                        count: row.get(5)?,
+                    })
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Short links in tweets, DMs, and likes that have not been resolved,
+    /// sorted. Links that failed before are included with `retry_failed`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_unresolved_short_urls(&self, retry_failed: bool) -> Result<Vec<String>> {
+        let mut links = std::collections::BTreeSet::new();
+        for table in ["tweets", "direct_messages"] {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT urls_json FROM {table} WHERE urls_json LIKE '%http%'"
+            ))?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            for urls_json in rows {
+                let urls: Vec<TweetUrl> = serde_json::from_str(&urls_json?).unwrap_or_default();
+                links.extend(urls.iter().filter_map(unresolved_link).map(String::from));
+            }
+        }
+        let mut stmt = self
+            .conn
+            .prepare("SELECT expanded_url FROM likes WHERE expanded_url IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        for url in rows {
+            let url = url?;
+            if is_short_url(&url) {
+                links.insert(url);
+            }
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT short_url FROM url_resolutions WHERE final_url IS NOT NULL OR ?1 = 0",
+        )?;
+        let done = stmt.query_map([retry_failed], |row| row.get::<_, String>(0))?;
+        for url in done {
+            links.remove(&url?);
+        }
+        Ok(links.into_iter().collect())
+    }
+
+    /// Record where a short link leads, or why it could not be followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub fn record_url_resolution(
+        &self,
+        short_url: &str,
+        final_url: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            r"
+            INSERT OR REPLACE INTO url_resolutions (short_url, final_url, error, resolved_at)
+            VALUES (?, ?, ?, ?)
+            ",
+            params![short_url, final_url, error, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Write resolved links into the stored tweets, DMs, and likes as their
+    /// expanded URLs. Returns the number of records updated.
+    ///
+    /// Re-indexing restores the archive's URLs, so `xf index` calls this
+    /// again afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a query or update fails.
+    pub fn apply_url_resolutions(&self) -> Result<usize> {
+        let resolved: HashMap<String, String> = self
+            .conn
+            .prepare(
+                "SELECT short_url, final_url FROM url_resolutions WHERE final_url IS NOT NULL",
+            )?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        if resolved.is_empty() {
+            return Ok(0);
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut updated = 0;
+        for table in ["tweets", "direct_messages"] {
+            let rows: Vec<(String, String)> = tx
+                .prepare(&format!(
+                    "SELECT id, urls_json FROM {table} WHERE urls_json LIKE '%http%'"
+                ))?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            let mut update =
+                tx.prepare(&format!("UPDATE {table} SET urls_json = ?1 WHERE id = ?2"))?;
+            for (id, urls_json) in rows {
+                let mut urls: Vec<TweetUrl> = serde_json::from_str(&urls_json).unwrap_or_default();
+                let mut changed = false;
+                for url in &mut urls {
+                    if let Some(target) = unresolved_link(url).and_then(|link| resolved.get(link)) {
+                        url.expanded_url = Some(target.clone());
+                        changed = true;
+                    }
+                }
+                if changed {
+                    update.execute(params![serde_json::to_string(&urls)?, id])?;
+                    updated += 1;
+                }
+            }
+        }
+        updated += tx.execute(
+            r"
+            UPDATE likes SET expanded_url = (
+                SELECT final_url FROM url_resolutions WHERE short_url = likes.expanded_url
+            )
+            WHERE expanded_url IN (
+                SELECT short_url FROM url_resolutions WHERE final_url IS NOT NULL
+            )
+            ",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// Store Grok messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any Grok message insert fails.
+    pub fn store_grok_messages(&mut self, messages: &[GrokMessage]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut count = 0;
+
+        {
+            // Clear existing data to ensure FTS and main table correspondence.
+            // Unlike tweets/likes/dms which have natural primary keys for INSERT OR REPLACE,
+            // grok_messages uses auto-increment, so we clear and repopulate.
+            tx.execute("DELETE FROM fts_grok", [])?;
+            tx.execute("DELETE FROM grok_messages", [])?;
+
+            let mut stmt = tx.prepare(
+                r"
+                INSERT INTO grok_messages (chat_id, message, sender, created_at, grok_mode)
+                VALUES (?, ?, ?, ?, ?)
+                ",
+            )?;
+            let mut fts_stmt =
+                tx.prepare("INSERT INTO fts_grok (grok_id, message) VALUES (?, ?)")?;
+
+            for msg in messages {
+                stmt.execute(params![
+                    msg.chat_id,
+                    PackedText(&msg.message),
+                    msg.sender,
+                    msg.created_at.to_rfc3339(),
+                    msg.grok_mode,
+                ])?;
+                // Use the auto-generated id as grok_id for proper FTS joining
+                let grok_id = tx.last_insert_rowid();
+                fts_stmt.execute(params![grok_id.to_string(), &msg.message])?;
+                count += 1;
+            }
+        }
+
+        tx.commit()?;
+        info!("Stored {} Grok messages", count);
+        Ok(count)
+    }
+
+    /// Store Community Notes, replacing any with the same ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails.
+    pub fn store_community_notes(&mut self, notes: &[CommunityNote]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                r"
+                INSERT OR REPLACE INTO community_notes
+                (id, tweet_id, text, classification, created_at)
+                VALUES (?, ?, ?, ?, ?)
+                ",
+            )?;
+            let mut fts_delete_stmt =
+                tx.prepare("DELETE FROM fts_community_notes WHERE note_id = ?")?;
+            let mut fts_stmt =
+                tx.prepare("INSERT INTO fts_community_notes (note_id, text) VALUES (?, ?)")?;
+            for note in notes {
+                stmt.execute(params![
+                    note.id,
+                    note.tweet_id,
+                    note.text,
+                    note.classification,
+                    note.created_at.map(|dt| dt.to_rfc3339()),
+                ])?;
+                fts_delete_stmt.execute([&note.id])?;
+                fts_stmt.execute(params![note.id, note.text])?;
+            }
+        }
+        tx.commit()?;
+        info!("Stored {} community notes", notes.len());
+        Ok(notes.len())
+    }
+
+    /// Get archive statistics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if statistics queries fail.
+    pub fn get_stats(&self) -> Result<ArchiveStats> {
+        let counts = self.get_all_counts()?;
+        let index_built_at = self.get_index_built_at()?.unwrap_or_else(Utc::now);
+
+        Ok(ArchiveStats {
+            tweets_count: counts.tweets_count,
+            likes_count: counts.likes_count,
+            dms_count: counts.dms_count,
+            dm_conversations_count: counts.dm_conversations_count,
+            followers_count: counts.followers_count,
+            following_count: counts.following_count,
+            blocks_count: counts.blocks_count,
+            mutes_count: counts.mutes_count,
+            grok_messages_count: counts.grok_messages_count,
+            first_tweet_date: counts.first_tweet_date,
+            last_tweet_date: counts.last_tweet_date,
+            index_built_at,
+        })
+    }
+
+    /// Get the index build timestamp from the archive metadata, if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_index_built_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let result: std::result::Result<String, _> = self.conn.query_row(
+            "SELECT indexed_at FROM archive_info WHERE id = 1",
+            [],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(ts) => Ok(parse_rfc3339_opt(Some(ts))),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Get all archive counts and tweet date bounds in a single query.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the consolidated query fails.
+    pub fn get_all_counts(&self) -> Result<AllCounts> {
+        let query = r"
+            SELECT
+                (SELECT COUNT(*) FROM tweets) AS tweets_count,
+                (SELECT COUNT(*) FROM likes) AS likes_count,
+                (SELECT COUNT(*) FROM direct_messages) AS dms_count,
+                (SELECT COUNT(*) FROM dm_conversations) AS dm_conversations_count,
+                (SELECT COUNT(*) FROM followers) AS followers_count,
+                (SELECT COUNT(*) FROM following) AS following_count,
+                (SELECT COUNT(*) FROM blocks) AS blocks_count,
+                (SELECT COUNT(*) FROM mutes) AS mutes_count,
+                (SELECT COUNT(*) FROM grok_messages) AS grok_messages_count,
+                (SELECT MIN(created_at) FROM tweets) AS first_tweet_date,
+                (SELECT MAX(created_at) FROM tweets) AS last_tweet_date
+        ";
+
+        Ok(self.conn.query_row(query, [], |row| {
+            let first_tweet_date: Option<String> = row.get(9)?;
+            let last_tweet_date: Option<String> = row.get(10)?;
+            Ok(AllCounts {
+                tweets_count: row.get(0)?,
+                likes_count: row.get(1)?,
+                dms_count: row.get(2)?,
+                dm_conversations_count: row.get(3)?,
+                followers_count: row.get(4)?,
+                following_count: row.get(5)?,
+                blocks_count: row.get(6)?,
+                mutes_count: row.get(7)?,
+                grok_messages_count: row.get(8)?,
+                first_tweet_date: parse_rfc3339_opt(first_tweet_date),
+                last_tweet_date: parse_rfc3339_opt(last_tweet_date),
+            })
         })?)
     }
 
@@ -826,10 +3566,24 @@ impl Storage {
         checks.push(self.check_integrity());
         checks.push(self.check_schema_version());
         checks.extend(self.check_fts_integrity());
-        checks.extend(self.check_fts_orphaned());
-        checks.extend(self.check_fts_missing());
+        if self.is_lite_mode() {
+            // Lite mode leaves the FTS tables empty on purpose
+            checks.push(HealthCheck {
+                category: CheckCategory::Database,
+                name: "Index mode".to_string(),
+                status: CheckStatus::Pass,
+                message: "lite (tweets only; no FTS rows or embeddings)".to_string(),
+                suggestion: None,
+            });
+        } else {
+            checks.extend(self.check_fts_orphaned());
+            checks.extend(self.check_fts_missing());
+            checks.extend(self.check_fts_duplicates());
+            checks.push(self.check_grok_fts_counts());
+        }
         checks.push(self.check_orphaned_dm_messages());
-        checks.push(self.check_grok_fts_counts());
+        checks.push(self.check_duplicate_dm_conversations());
+        checks.push(self.check_invalid_dates());
         checks.push(self.check_table_stats());
 
         checks
@@ -850,6 +3604,34 @@ impl Storage {
         Ok(())
     }
 
+    /// Empty the FTS5 tables that mirror source tables.
+    ///
+    /// [`Self::rebuild_fts_tables`] restores them. Notes are user data, so
+    /// `fts_notes` is left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any delete fails.
+    pub fn clear_fts_tables(&mut self) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for table in DERIVED_FTS_TABLES {
+            tx.execute(&format!("DELETE FROM {table}"), [])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Rewrite the database file without free pages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `VACUUM` or the WAL checkpoint fails.
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn
+            .execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")?;
+        Ok(())
+    }
+
     /// Rebuild all FTS5 tables from source tables.
     ///
     /// This is safe and idempotent because FTS tables are derived data.
@@ -858,11 +3640,22 @@ impl Storage {
     ///
     /// Returns an error if the rebuild fails.
     pub fn rebuild_fts_tables(&mut self) -> Result<FtsRebuildStats> {
-        let tx = self.conn.transaction()?;
+        self.fill_fts_tables()
+    }
+
+    fn fill_fts_tables(&self) -> Result<FtsRebuildStats> {
+        let tx = self.conn.unchecked_transaction()?;
 
         tx.execute("DELETE FROM fts_tweets", [])?;
         let tweets = tx.execute(
-            "INSERT INTO fts_tweets (tweet_id, full_text) SELECT id, full_text FROM tweets",
+            "INSERT INTO fts_tweets (tweet_id, full_text, supplemental)
+             SELECT id, full_text, nullif(ltrim(coalesce(char(10) || (
+                 SELECT group_concat(json_extract(o.value, '$.label'), char(10))
+                 FROM json_each(poll_json, '$.options') o), '') || coalesce(char(10) || (
+                 SELECT group_concat(json_extract(m.value, '$.alt_text'), char(10))
+                 FROM json_each(media_json) m
+                 WHERE json_extract(m.value, '$.alt_text') != ''), ''), char(10)), '')
+             FROM tweets",
             [],
         )?;
 
@@ -873,6 +3666,13 @@ impl Storage {
             [],
         )?;
 
+        tx.execute("DELETE FROM fts_bookmarks", [])?;
+        let bookmarks = tx.execute(
+            "INSERT INTO fts_bookmarks (tweet_id, full_text)
+             SELECT tweet_id, full_text FROM bookmarks WHERE full_text IS NOT NULL AND full_text != ''",
+            [],
+        )?;
+
         tx.execute("DELETE FROM fts_dms", [])?;
         let dms = tx.execute(
             "INSERT INTO fts_dms (dm_id, text) SELECT id, text FROM direct_messages",
@@ -882,17 +3682,26 @@ impl Storage {
         tx.execute("DELETE FROM fts_grok", [])?;
         let grok = tx.execute(
             "INSERT INTO fts_grok (grok_id, message)
-             SELECT CAST(id AS TEXT), message FROM grok_messages",
+             SELECT CAST(id AS TEXT), xf_unpack(message) FROM grok_messages",
+            [],
+        )?;
+
+        tx.execute("DELETE FROM fts_community_notes", [])?;
+        let community_notes = tx.execute(
+            "INSERT INTO fts_community_notes (note_id, text) SELECT id, text FROM community_notes",
             [],
         )?;
 
         tx.commit()?;
+        self.refresh_note_supplementals()?;
 
         Ok(FtsRebuildStats {
             tweets,
             likes,
+            bookmarks,
             dms,
             grok,
+            community_notes,
         })
     }
 
@@ -943,7 +3752,249 @@ impl Storage {
         )?;
 
         tx.commit()?;
-        Ok(rebuilt)
+        Ok(rebuilt)
+    }
+
+    /// Find tweets and DMs whose `created_at` is epoch zero or unreadable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either query fails.
+    pub fn find_invalid_dates(&self) -> Result<InvalidDates> {
+        let ids = |table: &str| -> Result<HashSet<String>> {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT id FROM {table} WHERE {INVALID_CREATED_AT}"
+            ))?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<HashSet<String>>>()?;
+            Ok(ids)
+        };
+        Ok(InvalidDates {
+            tweet_ids: ids("tweets")?,
+            dm_ids: ids("direct_messages")?,
+        })
+    }
+
+    /// Overwrite the dates of the given tweets and DMs, then refresh DM
+    /// conversation date ranges.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any update fails.
+    pub fn backfill_dates(
+        &mut self,
+        tweets: &[(&str, DateTime<Utc>)],
+        dms: &[(&str, DateTime<Utc>)],
+    ) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut updated = 0;
+        for (table, dates) in [("tweets", tweets), ("direct_messages", dms)] {
+            let mut stmt =
+                tx.prepare(&format!("UPDATE {table} SET created_at = ?1 WHERE id = ?2"))?;
+            for (id, created_at) in dates {
+                updated += stmt.execute(params![created_at.to_rfc3339(), id])?;
+            }
+        }
+        tx.commit()?;
+        if !tweets.is_empty() {
+            self.refresh_stats_summaries()?;
+        }
+        if !dms.is_empty() {
+            self.rebuild_dm_conversations()?;
+        }
+        Ok(updated)
+    }
+
+    /// Re-link self-replies whose parent ID lost precision.
+    ///
+    /// Some exports write `in_reply_to_status_id` as a JSON number, which
+    /// rounds 19-digit IDs to the nearest double and breaks thread lookups.
+    /// For each reply to `screen_name` whose parent isn't stored, the parent
+    /// becomes the account's latest earlier tweet whose ID rounds to the same
+    /// double. Replies with no such tweet (e.g. a deleted parent) are left as-is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any SQL statement fails.
+    pub fn relink_self_threads(&mut self, screen_name: &str) -> Result<ThreadRelinkStats> {
+        let parse_time = |value: &str| DateTime::parse_from_rfc3339(value).ok();
+
+        let broken: Vec<(String, String, String)> = {
+            let mut stmt = self.conn.prepare(
+                r"
+                SELECT t.id, t.in_reply_to_status_id, t.created_at
+                FROM tweets t
+                WHERE lower(t.in_reply_to_screen_name) = lower(?1)
+                  AND t.in_reply_to_status_id IS NOT NULL AND t.in_reply_to_status_id != ''
+                  AND NOT EXISTS (SELECT 1 FROM tweets p WHERE p.id = t.in_reply_to_status_id)
+                ",
+            )?;
+            stmt.query_map(params![screen_name.trim_start_matches('@')], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<rusqlite::Result<_>>()?
+        };
+        if broken.is_empty() {
+            return Ok(ThreadRelinkStats {
+                broken: 0,
+                relinked: 0,
+            });
+        }
+
+        // Own tweets keyed by the double their ID rounds to
+        let mut by_rounded: HashMap<u64, Vec<(String, String)>> = HashMap::new();
+        {
+            let mut stmt = self.conn.prepare("SELECT id, created_at FROM tweets")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (id, created_at) = row?;
+                if let Ok(rounded) = id.parse::<f64>() {
+                    by_rounded
+                        .entry(rounded.to_bits())
+                        .or_default()
+                        .push((id, created_at));
+                }
+            }
+        }
+
+        let tx = self.conn.transaction()?;
+        let mut relinked = 0;
+        {
+            let mut update =
+                tx.prepare("UPDATE tweets SET in_reply_to_status_id = ? WHERE id = ?")?;
+            for (id, parent_id, created_at) in &broken {
+                let (Ok(rounded), Some(reply_at)) =
+                    (parent_id.parse::<f64>(), parse_time(created_at))
+                else {
+                    continue;
+                };
+                let parent = by_rounded
+                    .get(&rounded.to_bits())
+                    .into_iter()
+                    .flatten()
+                    .filter(|(candidate, _)| candidate != id)
+                    .filter_map(|(candidate, at)| Some((candidate, parse_time(at)?)))
+                    .filter(|(_, at)| *at <= reply_at)
+                    .max_by_key(|(_, at)| *at);
+                if let Some((parent, _)) = parent {
+                    update.execute(params![parent, id])?;
+                    relinked += 1;
+                }
+            }
+        }
+        tx.commit()?;
+
+        Ok(ThreadRelinkStats {
+            broken: broken.len(),
+            relinked,
+        })
+    }
+
+    /// Find DM conversations that are one chat split across several IDs.
+    ///
+    /// Conversations are duplicates when they have the same participant set
+    /// (in any order) and their message time ranges overlap. Each group lists
+    /// the conversation to keep first: the one that starts earliest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn duplicate_dm_conversations(&self) -> Result<Vec<Vec<String>>> {
+        // (conversation_id, first message, last message)
+        type Span = (String, DateTime<Utc>, DateTime<Utc>);
+
+        let mut stmt = self.conn.prepare(
+            "SELECT conversation_id, participant_ids, first_message_at, last_message_at
+             FROM dm_conversations",
+        )?;
+        let mut by_participants: HashMap<String, Vec<Span>> = HashMap::new();
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, participants, first, last) = row?;
+            let mut participants: Vec<&str> = participants
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .filter(|p| !p.is_empty())
+                .collect();
+            participants.sort_unstable();
+            participants.dedup();
+            by_participants
+                .entry(participants.join(","))
+                .or_default()
+                .push((
+                    id,
+                    parse_rfc3339_or_epoch(first),
+                    parse_rfc3339_or_epoch(last),
+                ));
+        }
+
+        let mut groups = Vec::new();
+        for (_, mut conversations) in by_participants {
+            conversations.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+            let mut group: Vec<String> = Vec::new();
+            let mut group_end = DateTime::<Utc>::MIN_UTC;
+            for (id, first, last) in conversations {
+                if !group.is_empty() && first > group_end {
+                    if group.len() > 1 {
+                        groups.push(std::mem::take(&mut group));
+                    } else {
+                        group.clear();
+                    }
+                }
+                group_end = if group.is_empty() {
+                    last
+                } else {
+                    group_end.max(last)
+                };
+                group.push(id);
+            }
+            if group.len() > 1 {
+                groups.push(group);
+            }
+        }
+        groups.sort();
+        Ok(groups)
+    }
+
+    /// Merge each group from [`Storage::duplicate_dm_conversations`] into
+    /// its first conversation, moving the messages and rebuilding summaries.
+    ///
+    /// Returns the number of conversation IDs merged away.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any SQL statement fails.
+    pub fn merge_dm_conversations(&mut self, groups: &[Vec<String>]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut merged = 0;
+        {
+            let mut update = tx.prepare(
+                "UPDATE direct_messages SET conversation_id = ?1 WHERE conversation_id = ?2",
+            )?;
+            for group in groups {
+                let Some((keep, duplicates)) = group.split_first() else {
+                    continue;
+                };
+                for duplicate in duplicates {
+                    update.execute(params![keep, duplicate])?;
+                    merged += 1;
+                }
+            }
+        }
+        tx.commit()?;
+        self.rebuild_dm_conversations()?;
+        Ok(merged)
     }
 
     /// Collect per-table row counts and optional size statistics.
@@ -955,6 +4006,7 @@ impl Storage {
         let tables = [
             "tweets",
             "likes",
+            "bookmarks",
             "direct_messages",
             "dm_conversations",
             "grok_messages",
@@ -964,8 +4016,11 @@ impl Storage {
             "mutes",
             "fts_tweets",
             "fts_likes",
+            "fts_bookmarks",
             "fts_dms",
             "fts_grok",
+            "community_notes",
+            "fts_community_notes",
         ];
 
         let has_dbstat = self.dbstat_available();
@@ -988,6 +4043,66 @@ impl Storage {
         Ok(stats)
     }
 
+    /// Bytes used by each table, largest first.
+    ///
+    /// A table's indexes are counted with it, and FTS5 shadow tables
+    /// (`fts_tweets_data`, ...) with their virtual table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `SQLite` was built without the `dbstat` table.
+    pub fn table_sizes(&self) -> Result<Vec<(String, i64)>> {
+        if !self.dbstat_available() {
+            anyhow::bail!("SQLite was built without the dbstat virtual table");
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM sqlite_master WHERE sql LIKE 'CREATE VIRTUAL TABLE%'")?;
+        let virtual_tables: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(m.tbl_name, d.name), SUM(d.pgsize)
+             FROM dbstat d LEFT JOIN sqlite_master m ON m.name = d.name
+             GROUP BY 1",
+        )?;
+        let mut sizes: HashMap<String, i64> = HashMap::new();
+        for row in stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))? {
+            let (table, bytes): (String, i64) = row?;
+            let owner = virtual_tables
+                .iter()
+                .find(|vt| {
+                    table
+                        .strip_prefix(vt.as_str())
+                        .is_some_and(|rest| rest.starts_with('_'))
+                })
+                .cloned()
+                .unwrap_or(table);
+            *sizes.entry(owner).or_default() += bytes;
+        }
+
+        let mut sizes: Vec<(String, i64)> = sizes.into_iter().collect();
+        sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(sizes)
+    }
+
+    /// Bytes held by free pages, reclaimable with `VACUUM`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pragmas cannot be read.
+    pub fn free_bytes(&self) -> Result<i64> {
+        let free_pages: i64 = self
+            .conn
+            .query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+        let page_size: i64 = self
+            .conn
+            .query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok(free_pages * page_size)
+    }
+
     fn check_integrity(&self) -> HealthCheck {
         match self
             .conn
@@ -1049,10 +4164,9 @@ impl Storage {
     }
 
     fn check_fts_integrity(&self) -> Vec<HealthCheck> {
-        let tables = ["fts_tweets", "fts_likes", "fts_dms", "fts_grok"];
-        let mut checks = Vec::with_capacity(tables.len());
+        let mut checks = Vec::with_capacity(DERIVED_FTS_TABLES.len());
 
-        for table in tables {
+        for table in DERIVED_FTS_TABLES {
             let sql = format!("INSERT INTO {table}({table}) VALUES('integrity-check')");
             let result = self.conn.execute(&sql, []);
             let (status, message, suggestion) = match result {
@@ -1088,6 +4202,11 @@ impl Storage {
                 "SELECT COUNT(*) FROM fts_likes fts LEFT JOIN likes l ON fts.tweet_id = l.tweet_id WHERE l.tweet_id IS NULL",
                 "Run 'xf index --force' to rebuild FTS tables.",
             ),
+            self.check_count(
+                "FTS orphaned rows (bookmarks)",
+                "SELECT COUNT(*) FROM fts_bookmarks fts LEFT JOIN bookmarks b ON fts.tweet_id = b.tweet_id WHERE b.tweet_id IS NULL",
+                "Run 'xf index --force' to rebuild FTS tables.",
+            ),
             self.check_count(
                 "FTS orphaned rows (dms)",
                 "SELECT COUNT(*) FROM fts_dms fts LEFT JOIN direct_messages dm ON fts.dm_id = dm.id WHERE dm.id IS NULL",
@@ -1108,6 +4227,11 @@ impl Storage {
                 "SELECT COUNT(*) FROM likes l LEFT JOIN fts_likes fts ON fts.tweet_id = l.tweet_id WHERE l.full_text IS NOT NULL AND l.full_text != '' AND fts.tweet_id IS NULL",
                 "Run 'xf index --force' to rebuild FTS tables.",
             ),
+            self.check_count(
+                "FTS missing rows (bookmarks)",
+                "SELECT COUNT(*) FROM bookmarks b LEFT JOIN fts_bookmarks fts ON fts.tweet_id = b.tweet_id WHERE b.full_text IS NOT NULL AND b.full_text != '' AND fts.tweet_id IS NULL",
+                "Run 'xf index --force' to rebuild FTS tables.",
+            ),
             self.check_count(
                 "FTS missing rows (dms)",
                 "SELECT COUNT(*) FROM direct_messages dm LEFT JOIN fts_dms fts ON fts.dm_id = dm.id WHERE fts.dm_id IS NULL",
@@ -1116,6 +4240,32 @@ impl Storage {
         ]
     }
 
+    /// Extra FTS rows for IDs that are already indexed once.
+    fn check_fts_duplicates(&self) -> Vec<HealthCheck> {
+        vec![
+            self.check_count(
+                "FTS duplicate rows (tweets)",
+                "SELECT COUNT(*) - COUNT(DISTINCT tweet_id) FROM fts_tweets",
+                "Run 'xf doctor --fix' to rebuild FTS tables.",
+            ),
+            self.check_count(
+                "FTS duplicate rows (likes)",
+                "SELECT COUNT(*) - COUNT(DISTINCT tweet_id) FROM fts_likes",
+                "Run 'xf doctor --fix' to rebuild FTS tables.",
+            ),
+            self.check_count(
+                "FTS duplicate rows (bookmarks)",
+                "SELECT COUNT(*) - COUNT(DISTINCT tweet_id) FROM fts_bookmarks",
+                "Run 'xf doctor --fix' to rebuild FTS tables.",
+            ),
+            self.check_count(
+                "FTS duplicate rows (dms)",
+                "SELECT COUNT(*) - COUNT(DISTINCT dm_id) FROM fts_dms",
+                "Run 'xf doctor --fix' to rebuild FTS tables.",
+            ),
+        ]
+    }
+
     fn check_orphaned_dm_messages(&self) -> HealthCheck {
         self.check_count(
             "Orphaned DM messages",
@@ -1124,6 +4274,49 @@ impl Storage {
         )
     }
 
+    fn check_invalid_dates(&self) -> HealthCheck {
+        self.check_count(
+            "Epoch-zero dates",
+            &format!(
+                "SELECT (SELECT COUNT(*) FROM tweets WHERE {INVALID_CREATED_AT}) + \
+                 (SELECT COUNT(*) FROM direct_messages WHERE {INVALID_CREATED_AT})"
+            ),
+            "Run 'xf backfill-dates' to re-parse them from the archive.",
+        )
+    }
+
+    fn check_duplicate_dm_conversations(&self) -> HealthCheck {
+        let name = "Duplicate DM conversations".to_string();
+        match self.duplicate_dm_conversations() {
+            Ok(groups) if groups.is_empty() => HealthCheck {
+                category: CheckCategory::Database,
+                name,
+                status: CheckStatus::Pass,
+                message: "None found".to_string(),
+                suggestion: None,
+            },
+            Ok(groups) => {
+                let extra: usize = groups.iter().map(|group| group.len() - 1).sum();
+                HealthCheck {
+                    category: CheckCategory::Database,
+                    name,
+                    status: CheckStatus::Warning,
+                    message: format!(
+                        "{extra} conversations repeat another with the same participants and overlapping dates"
+                    ),
+                    suggestion: Some("Run 'xf doctor --fix' to merge them.".to_string()),
+                }
+            }
+            Err(err) => HealthCheck {
+                category: CheckCategory::Database,
+                name,
+                status: CheckStatus::Error,
+                message: format!("Query failed: {err}"),
+                suggestion: None,
+            },
+        }
+    }
+
     fn check_grok_fts_counts(&self) -> HealthCheck {
         let grok_count = self.table_row_count("grok_messages");
         let fts_count = self.table_row_count("fts_grok");
@@ -1219,12 +4412,8 @@ impl Storage {
     }
 
     fn dbstat_available(&self) -> bool {
-        let result: std::result::Result<i64, _> = self.conn.query_row(
-            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='dbstat'",
-            [],
-            |row| row.get(0),
-        );
-        result.unwrap_or(0) > 0
+        // dbstat is an eponymous virtual table, so it never shows up in sqlite_master
+        self.conn.prepare("SELECT 1 FROM dbstat LIMIT 1").is_ok()
     }
 
     fn table_size_bytes(&self, table: &str) -> Option<i64> {
@@ -1241,19 +4430,49 @@ impl Storage {
     ///
     /// Returns an error if the database query fails.
     pub fn search_tweets(&self, query: &str, limit: usize) -> Result<Vec<Tweet>> {
+        self.search_tweets_ordered(query, limit, FtsOrder::Rank)
+    }
+
+    /// Search tweets using FTS5 with an explicit result order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn search_tweets_ordered(
+        &self,
+        query: &str,
+        limit: usize,
+        order: FtsOrder,
+    ) -> Result<Vec<Tweet>> {
         let limit = limit_to_i64(limit);
-        let mut stmt = self.conn.prepare(
+        let order_by = match order {
+            FtsOrder::Rank => "rank",
+            FtsOrder::Oldest => "t.created_at ASC",
+            FtsOrder::Newest => "t.created_at DESC",
+            FtsOrder::Engagement => "e.engagement DESC, rank",
+        };
+        let engagement_join = if matches!(order, FtsOrder::Engagement) {
+            format!(
+                "JOIN (SELECT id, likes + retweets + replies + COALESCE(quotes, 0) AS engagement
+                       FROM ({TWEET_ENGAGEMENT_SQL})) e ON e.id = t.id"
+            )
+        } else {
+            String::new()
+        };
+        let mut stmt = self.conn.prepare(&format!(
             r"
             SELECT t.id, t.created_at, t.full_text, t.source, t.favorite_count, t.retweet_count,
                    t.lang, t.in_reply_to_status_id, t.in_reply_to_user_id, t.in_reply_to_screen_name,
-                   t.is_retweet, t.hashtags_json, t.mentions_json, t.urls_json, t.media_json
+                   t.is_retweet, t.hashtags_json, t.mentions_json, t.urls_json, t.media_json,
+                   t.geo_json, t.poll_json, t.reply_count, t.quote_count
             FROM tweets t
             JOIN fts_tweets fts ON t.id = fts.tweet_id
+            {engagement_join}
             WHERE fts_tweets MATCH ?
-            ORDER BY rank
+            ORDER BY {order_by}
             LIMIT ?
-            ",
-        )?;
+            "
+        ))?;
 
         let tweets = stmt
             .query_map(params![query, limit], |row| {
@@ -1274,6 +4493,10 @@ impl Storage {
                         .unwrap_or_default(),
                     urls: serde_json::from_str(&row.get::<_, String>(13)?).unwrap_or_default(),
                     media: serde_json::from_str(&row.get::<_, String>(14)?).unwrap_or_default(),
+                    geo: json_column(row.get(15)?),
+                    poll: json_column(row.get(16)?),
+                    reply_count: row.get(17)?,
+                    quote_count: row.get(18)?,
                 })
             })?
             .filter_map(std::result::Result::ok)
@@ -1288,17 +4511,40 @@ impl Storage {
     ///
     /// Returns an error if the database query fails.
     pub fn search_likes(&self, query: &str, limit: usize) -> Result<Vec<Like>> {
+        self.search_likes_ordered(query, limit, FtsOrder::Rank)
+    }
+
+    /// Search likes using FTS5 with an explicit result order.
+    ///
+    /// Likes carry no timestamp, so date ordering uses the liked tweet's ID,
+    /// which increases with the time the tweet was posted.
+    /// [`FtsOrder::Engagement`] falls back to rank.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn search_likes_ordered(
+        &self,
+        query: &str,
+        limit: usize,
+        order: FtsOrder,
+    ) -> Result<Vec<Like>> {
         let limit = limit_to_i64(limit);
-        let mut stmt = self.conn.prepare(
+        let order_by = match order {
+            FtsOrder::Rank | FtsOrder::Engagement => "rank",
+            FtsOrder::Oldest => "CAST(l.tweet_id AS INTEGER) ASC",
+            FtsOrder::Newest => "CAST(l.tweet_id AS INTEGER) DESC",
+        };
+        let mut stmt = self.conn.prepare(&format!(
             r"
             SELECT l.tweet_id, l.full_text, l.expanded_url
             FROM likes l
             JOIN fts_likes fts ON l.tweet_id = fts.tweet_id
             WHERE fts_likes MATCH ?
-            ORDER BY rank
+            ORDER BY {order_by}
             LIMIT ?
-            ",
-        )?;
+            "
+        ))?;
 
         let likes = stmt
             .query_map(params![query, limit], |row| {
@@ -1320,18 +4566,39 @@ impl Storage {
     ///
     /// Returns an error if the database query fails.
     pub fn search_dms(&self, query: &str, limit: usize) -> Result<Vec<DirectMessage>> {
+        self.search_dms_ordered(query, limit, FtsOrder::Rank)
+    }
+
+    /// Search DMs using FTS5 with an explicit result order.
+    ///
+    /// [`FtsOrder::Engagement`] falls back to rank.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn search_dms_ordered(
+        &self,
+        query: &str,
+        limit: usize,
+        order: FtsOrder,
+    ) -> Result<Vec<DirectMessage>> {
         let limit = limit_to_i64(limit);
-        let mut stmt = self.conn.prepare(
+        let order_by = match order {
+            FtsOrder::Rank | FtsOrder::Engagement => "rank",
+            FtsOrder::Oldest => "dm.created_at ASC",
+            FtsOrder::Newest => "dm.created_at DESC",
+        };
+        let mut stmt = self.conn.prepare(&format!(
             r"
             SELECT dm.id, dm.conversation_id, dm.sender_id, dm.recipient_id, dm.text,
                    dm.created_at, dm.urls_json, dm.media_urls_json
             FROM direct_messages dm
             JOIN fts_dms fts ON dm.id = fts.dm_id
             WHERE fts_dms MATCH ?
-            ORDER BY rank
+            ORDER BY {order_by}
             LIMIT ?
-            ",
-        )?;
+            "
+        ))?;
 
         let dms = stmt
             .query_map(params![query, limit], |row| {
@@ -1383,84 +4650,323 @@ impl Storage {
             .filter_map(std::result::Result::ok)
             .collect();
 
-        Ok(messages)
+        Ok(messages)
+    }
+
+    /// Search Grok messages using FTS5.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn search_grok(&self, query: &str, limit: usize) -> Result<Vec<GrokMessage>> {
+        let limit = limit_to_i64(limit);
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT g.chat_id, g.message, g.sender, g.created_at, g.grok_mode
+            FROM grok_messages g
+            JOIN fts_grok fts ON g.id = CAST(fts.grok_id AS INTEGER)
+            WHERE fts_grok MATCH ?
+            ORDER BY rank
+            LIMIT ?
+            ",
+        )?;
+
+        let messages = stmt
+            .query_map(params![query, limit], |row| {
+                Ok(GrokMessage {
+                    chat_id: row.get(0)?,
+                    message: row.get::<_, UnpackedText>(1)?.0,
+                    sender: row.get(2)?,
+                    created_at: parse_rfc3339_or_epoch(row.get::<_, Option<String>>(3)?),
+                    grok_mode: row.get(4)?,
+                })
+            })?
+            .filter_map(std::result::Result::ok)
+            .collect();
+
+        Ok(messages)
+    }
+
+    /// Get a tweet by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_tweet(&self, id: &str) -> Result<Option<Tweet>> {
+        let result = self.conn.query_row(
+            r"
+            SELECT id, created_at, full_text, source, favorite_count, retweet_count,
+                   lang, in_reply_to_status_id, in_reply_to_user_id, in_reply_to_screen_name,
+                   is_retweet, hashtags_json, mentions_json, urls_json, media_json, geo_json,
+                   poll_json, reply_count, quote_count
+            FROM tweets WHERE id = ?
+            ",
+            params![id],
+            |row| {
+                Ok(Tweet {
+                    id: row.get(0)?,
+                    created_at: parse_rfc3339_or_epoch(row.get::<_, Option<String>>(1)?),
+                    full_text: row.get(2)?,
+                    source: row.get(3)?,
+                    favorite_count: row.get(4)?,
+                    retweet_count: row.get(5)?,
+                    lang: row.get(6)?,
+                    in_reply_to_status_id: row.get(7)?,
+                    in_reply_to_user_id: row.get(8)?,
+                    in_reply_to_screen_name: row.get(9)?,
+                    is_retweet: row.get::<_, i32>(10)? != 0,
+                    hashtags: serde_json::from_str(&row.get::<_, String>(11)?).unwrap_or_default(),
+                    user_mentions: serde_json::from_str(&row.get::<_, String>(12)?)
+                        .unwrap_or_default(),
+                    urls: serde_json::from_str(&row.get::<_, String>(13)?).unwrap_or_default(),
+                    media: serde_json::from_str(&row.get::<_, String>(14)?).unwrap_or_default(),
+                    geo: json_column(row.get(15)?),
+                    poll: json_column(row.get(16)?),
+                    reply_count: row.get(17)?,
+                    quote_count: row.get(18)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(tweet) => Ok(Some(tweet)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get many tweets by ID in batched queries.
+    ///
+    /// Tweets are returned in the order their IDs first appear in `ids`;
+    /// duplicate and unknown IDs are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_tweets_by_ids(&self, ids: &[&str]) -> Result<Vec<Tweet>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut found: HashMap<String, Tweet> = HashMap::with_capacity(ids.len());
+
+        for chunk in ids.chunks(SQLITE_BATCH_SIZE) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let sql = format!("SELECT {TWEET_COLUMNS} FROM tweets WHERE id IN ({placeholders})");
+
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk.iter()), tweet_from_row)?;
+            for tweet in rows {
+                let tweet = tweet?;
+                found.insert(tweet.id.clone(), tweet);
+            }
+        }
+
+        Ok(ids.iter().filter_map(|id| found.remove(*id)).collect())
+    }
+
+    /// Engagement of the tweets among `ids`: likes + retweets + replies +
+    /// quotes, counted as in [`TWEET_ENGAGEMENT_SQL`]. Unknown IDs are
+    /// left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_tweet_engagement(&self, ids: &[&str]) -> Result<HashMap<String, i64>> {
+        let mut engagement = HashMap::with_capacity(ids.len());
+        for chunk in ids.chunks(SQLITE_BATCH_SIZE) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT id, likes + retweets + replies + COALESCE(quotes, 0)
+                 FROM ({TWEET_ENGAGEMENT_SQL}) WHERE id IN ({placeholders})"
+            ))?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk.iter()), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?;
+            for row in rows {
+                let (id, total) = row?;
+                engagement.insert(id, total);
+            }
+        }
+        Ok(engagement)
+    }
+
+    /// Get tweets created between `start` and `end` (inclusive), newest first.
+    ///
+    /// Uses the `created_at` index, so only the requested range is read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_tweets_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Tweet>> {
+        let sql = format!(
+            "SELECT {TWEET_COLUMNS} FROM tweets
+             WHERE created_at >= ?1 AND created_at <= ?2
+             ORDER BY created_at DESC"
+        );
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let tweets = stmt
+            .query_map(
+                params![start.to_rfc3339(), end.to_rfc3339()],
+                tweet_from_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tweets)
+    }
+
+    /// IDs of geotagged tweets matching a place name and/or inside a box.
+    ///
+    /// `place` matches case-insensitively anywhere in the place name or
+    /// country. Tweets tagged with a place but no coordinates never fall
+    /// inside a box.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_geotagged_tweet_ids(
+        &self,
+        place: Option<&str>,
+        bbox: Option<&GeoBox>,
+    ) -> Result<HashSet<String>> {
+        let place = place.map(str::to_lowercase);
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, geo_json FROM tweets WHERE geo_json IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                json_column::<TweetGeo>(row.get(1)?),
+            ))
+        })?;
+
+        let mut ids = HashSet::new();
+        for row in rows {
+            let (id, Some(geo)) = row? else {
+                continue;
+            };
+            if let Some(place) = &place {
+                let matches = [&geo.place, &geo.country]
+                    .into_iter()
+                    .flatten()
+                    .any(|name| name.to_lowercase().contains(place.as_str()));
+                if !matches {
+                    continue;
+                }
+            }
+            if let Some(bbox) = bbox {
+                let (Some(lat), Some(lon)) = (geo.latitude, geo.longitude) else {
+                    continue;
+                };
+                if !bbox.contains(lat, lon) {
+                    continue;
+                }
+            }
+            ids.insert(id);
+        }
+        Ok(ids)
+    }
+
+    /// IDs of tweets posted from a client whose source name contains
+    /// `pattern`, case-insensitively (e.g. "iphone", "ifttt").
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_tweet_ids_by_source(&self, pattern: &str) -> Result<HashSet<String>> {
+        let pattern = pattern.to_lowercase();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, source FROM tweets WHERE source IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut ids = HashSet::new();
+        for row in rows {
+            let (id, source) = row?;
+            if source.to_lowercase().contains(&pattern) {
+                ids.insert(id);
+            }
+        }
+        Ok(ids)
     }
 
-    /// Search Grok messages using FTS5.
+    /// IDs of tweets with media, split by alt text: with `described` set,
+    /// tweets whose media all have alt text; otherwise tweets with at least
+    /// one media item lacking it.
     ///
     /// # Errors
     ///
     /// Returns an error if the database query fails.
-    pub fn search_grok(&self, query: &str, limit: usize) -> Result<Vec<GrokMessage>> {
-        let limit = limit_to_i64(limit);
+    pub fn get_tweet_ids_by_alt_text(&self, described: bool) -> Result<HashSet<String>> {
+        let sql = format!(
+            r"
+            SELECT id FROM tweets
+            WHERE json_array_length(media_json) > 0
+              AND {} EXISTS (
+                  SELECT 1 FROM json_each(media_json) m
+                  WHERE coalesce(json_extract(m.value, '$.alt_text'), '') = ''
+              )
+            ",
+            if described { "NOT" } else { "" }
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<HashSet<String>>>()?;
+        Ok(ids)
+    }
+
+    /// Places tweeted from, most tweets first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_top_places(&self, limit: usize) -> Result<Vec<(String, i64)>> {
         let mut stmt = self.conn.prepare(
             r"
-            SELECT g.chat_id, g.message, g.sender, g.created_at, g.grok_mode
-            FROM grok_messages g
-            JOIN fts_grok fts ON g.id = CAST(fts.grok_id AS INTEGER)
-            WHERE fts_grok MATCH ?
-            ORDER BY rank
+            SELECT json_extract(geo_json, '$.place') AS place, COUNT(*) AS n
+            FROM tweets
+            WHERE place IS NOT NULL
+            GROUP BY place
+            ORDER BY n DESC, place
             LIMIT ?
             ",
         )?;
-
-        let messages = stmt
-            .query_map(params![query, limit], |row| {
-                Ok(GrokMessage {
-                    chat_id: row.get(0)?,
-                    message: row.get(1)?,
-                    sender: row.get(2)?,
-                    created_at: parse_rfc3339_or_epoch(row.get::<_, Option<String>>(3)?),
-                    grok_mode: row.get(4)?,
-                })
+        let places = stmt
+            .query_map(params![limit_to_i64(limit)], |row| {
+                Ok((row.get(0)?, row.get(1)?))
             })?
-            .filter_map(std::result::Result::ok)
-            .collect();
-
-        Ok(messages)
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(places)
     }
 
-    /// Get a tweet by ID.
+    /// Get tweets using a hashtag, newest first.
+    ///
+    /// Matching is case-insensitive and a leading `#` is optional. Lookups go
+    /// through the normalized `tweet_hashtags` table instead of scanning
+    /// `hashtags_json`.
     ///
     /// # Errors
     ///
     /// Returns an error if the database query fails.
-    pub fn get_tweet(&self, id: &str) -> Result<Option<Tweet>> {
-        let result = self.conn.query_row(
-            r"
-            SELECT id, created_at, full_text, source, favorite_count, retweet_count,
-                   lang, in_reply_to_status_id, in_reply_to_user_id, in_reply_to_screen_name,
-                   is_retweet, hashtags_json, mentions_json, urls_json, media_json
-            FROM tweets WHERE id = ?
-            ",
-            params![id],
-            |row| {
-                Ok(Tweet {
-                    id: row.get(0)?,
-                    created_at: parse_rfc3339_or_epoch(row.get::<_, Option<String>>(1)?),
-                    full_text: row.get(2)?,
-                    source: row.get(3)?,
-                    favorite_count: row.get(4)?,
-                    retweet_count: row.get(5)?,
-                    lang: row.get(6)?,
-                    in_reply_to_status_id: row.get(7)?,
-                    in_reply_to_user_id: row.get(8)?,
-                    in_reply_to_screen_name: row.get(9)?,
-                    is_retweet: row.get::<_, i32>(10)? != 0,
-                    hashtags: serde_json::from_str(&row.get::<_, String>(11)?).unwrap_or_default(),
-                    user_mentions: serde_json::from_str(&row.get::<_, String>(12)?)
-                        .unwrap_or_default(),
-                    urls: serde_json::from_str(&row.get::<_, String>(13)?).unwrap_or_default(),
-                    media: serde_json::from_str(&row.get::<_, String>(14)?).unwrap_or_default(),
-                })
-            },
+    pub fn get_tweets_with_hashtag(&self, tag: &str) -> Result<Vec<Tweet>> {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        let sql = format!(
+            "SELECT {TWEET_COLUMNS} FROM tweet_hashtags h
+             JOIN tweets t ON t.id = h.tweet_id
+             WHERE h.tag = ?1
+             ORDER BY t.created_at DESC"
         );
-
-        match result {
-            Ok(tweet) => Ok(Some(tweet)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let tweets = stmt
+            .query_map(params![tag], tweet_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tweets)
     }
 
     /// Get replies to a tweet by parent ID, ordered by creation time.
@@ -1473,7 +4979,8 @@ impl Storage {
             r"
             SELECT id, created_at, full_text, source, favorite_count, retweet_count,
                    lang, in_reply_to_status_id, in_reply_to_user_id, in_reply_to_screen_name,
-                   is_retweet, hashtags_json, mentions_json, urls_json, media_json
+                   is_retweet, hashtags_json, mentions_json, urls_json, media_json, geo_json,
+                   poll_json, reply_count, quote_count
             FROM tweets
             WHERE in_reply_to_status_id = ?
             ORDER BY created_at ASC
@@ -1499,6 +5006,10 @@ impl Storage {
                         .unwrap_or_default(),
                     urls: serde_json::from_str(&row.get::<_, String>(13)?).unwrap_or_default(),
                     media: serde_json::from_str(&row.get::<_, String>(14)?).unwrap_or_default(),
+                    geo: json_column(row.get(15)?),
+                    poll: json_column(row.get(16)?),
+                    reply_count: row.get(17)?,
+                    quote_count: row.get(18)?,
                 })
             })?
             .filter_map(std::result::Result::ok)
@@ -1555,7 +5066,8 @@ impl Storage {
                    t.favorite_count, t.retweet_count, t.lang,
                    t.in_reply_to_status_id, t.in_reply_to_user_id,
                    t.in_reply_to_screen_name, t.is_retweet,
-                   t.hashtags_json, t.mentions_json, t.urls_json, t.media_json
+                   t.hashtags_json, t.mentions_json, t.urls_json, t.media_json, t.geo_json,
+                   t.poll_json, t.reply_count, t.quote_count
             FROM tweets t
             WHERE t.id IN (SELECT id FROM thread_ids)
             ORDER BY t.created_at ASC
@@ -1581,6 +5093,10 @@ impl Storage {
                         .unwrap_or_default(),
                     urls: serde_json::from_str(&row.get::<_, String>(13)?).unwrap_or_default(),
                     media: serde_json::from_str(&row.get::<_, String>(14)?).unwrap_or_default(),
+                    geo: json_column(row.get(15)?),
+                    poll: json_column(row.get(16)?),
+                    reply_count: row.get(17)?,
+                    quote_count: row.get(18)?,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -1598,7 +5114,8 @@ impl Storage {
         // SQLite treats LIMIT -1 as "no limit", so we use that for unlimited queries.
         const QUERY: &str = r"SELECT id, created_at, full_text, source, favorite_count, retweet_count,
                    lang, in_reply_to_status_id, in_reply_to_user_id, in_reply_to_screen_name,
-                   is_retweet, hashtags_json, mentions_json, urls_json, media_json
+                   is_retweet, hashtags_json, mentions_json, urls_json, media_json, geo_json,
+                   poll_json, reply_count, quote_count
                 FROM tweets ORDER BY created_at DESC LIMIT ?";
 
         let limit_param: i64 = limit.map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX));
@@ -1622,6 +5139,10 @@ impl Storage {
                         .unwrap_or_default(),
                     urls: serde_json::from_str(&row.get::<_, String>(13)?).unwrap_or_default(),
                     media: serde_json::from_str(&row.get::<_, String>(14)?).unwrap_or_default(),
+                    geo: json_column(row.get(15)?),
+                    poll: json_column(row.get(16)?),
+                    reply_count: row.get(17)?,
+                    quote_count: row.get(18)?,
                 })
             })?
             .filter_map(std::result::Result::ok)
@@ -1654,6 +5175,48 @@ impl Storage {
         Ok(likes)
     }
 
+    /// Get all bookmarks, optionally limited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_all_bookmarks(&self, limit: Option<usize>) -> Result<Vec<Bookmark>> {
+        const QUERY: &str = "SELECT tweet_id, full_text, expanded_url FROM bookmarks LIMIT ?";
+        let limit_param: i64 = limit.map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX));
+
+        let mut stmt = self.conn.prepare_cached(QUERY)?;
+        let bookmarks = stmt
+            .query_map([limit_param], |row| {
+                Ok(Bookmark {
+                    tweet_id: row.get(0)?,
+                    full_text: row.get(1)?,
+                    expanded_url: row.get(2)?,
+                })
+            })?
+            .filter_map(std::result::Result::ok)
+            .collect();
+
+        Ok(bookmarks)
+    }
+
+    /// Get your tweets that also appear in likes, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_self_liked_tweets(&self) -> Result<Vec<Tweet>> {
+        let sql = format!(
+            "SELECT {TWEET_COLUMNS} FROM tweets
+             WHERE id IN (SELECT tweet_id FROM likes)
+             ORDER BY created_at DESC"
+        );
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let tweets = stmt
+            .query_map([], tweet_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tweets)
+    }
+
     /// Get all DM conversations with messages, optionally limited.
     ///
     /// # Errors
@@ -1685,6 +5248,88 @@ impl Storage {
         Ok(dms)
     }
 
+    /// Get Community Notes, newest first, optionally limited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_all_community_notes(&self, limit: Option<usize>) -> Result<Vec<CommunityNote>> {
+        const QUERY: &str = r"SELECT id, tweet_id, text, classification, created_at
+                FROM community_notes ORDER BY created_at DESC LIMIT ?";
+        let limit_param: i64 = limit.map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX));
+
+        let mut stmt = self.conn.prepare_cached(QUERY)?;
+        let notes = stmt
+            .query_map([limit_param], community_note_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(notes)
+    }
+
+    /// Search Community Notes using FTS5.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn search_community_notes_ordered(
+        &self,
+        query: &str,
+        limit: usize,
+        order: FtsOrder,
+    ) -> Result<Vec<CommunityNote>> {
+        let limit = limit_to_i64(limit);
+        let order_by = match order {
+            FtsOrder::Rank | FtsOrder::Engagement => "rank",
+            FtsOrder::Oldest => "n.created_at ASC",
+            FtsOrder::Newest => "n.created_at DESC",
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            r"
+            SELECT n.id, n.tweet_id, n.text, n.classification, n.created_at
+            FROM community_notes n
+            JOIN fts_community_notes fts ON n.id = fts.note_id
+            WHERE fts_community_notes MATCH ?
+            ORDER BY {order_by}
+            LIMIT ?
+            "
+        ))?;
+        let notes = stmt
+            .query_map(params![query, limit], community_note_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(notes)
+    }
+
+    /// IDs of DM conversations active at some point between `since` and
+    /// `until`: the last message is on or after `since` and the first on or
+    /// before `until`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_conversation_ids_active_between(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<HashSet<String>> {
+        let mut stmt = self.conn.prepare(
+            r"
+            SELECT conversation_id
+            FROM dm_conversations
+            WHERE (?1 IS NULL OR last_message_at >= ?1)
+              AND (?2 IS NULL OR first_message_at <= ?2)
+            ",
+        )?;
+        let ids = stmt
+            .query_map(
+                params![
+                    since.map(|dt| dt.to_rfc3339()),
+                    until.map(|dt| dt.to_rfc3339())
+                ],
+                |row| row.get(0),
+            )?
+            .collect::<rusqlite::Result<HashSet<String>>>()?;
+        Ok(ids)
+    }
+
     /// Get DM conversation summaries, optionally limited.
     ///
     /// # Errors
@@ -1694,31 +5339,67 @@ impl Storage {
         &self,
         limit: Option<usize>,
     ) -> Result<Vec<DmConversationSummary>> {
-        const QUERY: &str = r"SELECT conversation_id, participant_ids, message_count,
+        self.find_dm_conversations(&[], 0, ConversationOrder::Recent, limit)
+    }
+
+    /// Get DM conversation summaries that include any of `participant_ids`
+    /// (all conversations when empty) and have at least `min_messages`
+    /// messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn find_dm_conversations(
+        &self,
+        participant_ids: &[String],
+        min_messages: u64,
+        order: ConversationOrder,
+        limit: Option<usize>,
+    ) -> Result<Vec<DmConversationSummary>> {
+        let order_by = match order {
+            ConversationOrder::Recent => "last_message_at DESC",
+            ConversationOrder::Oldest => "first_message_at ASC",
+            ConversationOrder::Messages => "message_count DESC, last_message_at DESC",
+        };
+        let query = format!(
+            r"SELECT conversation_id, participant_ids, message_count,
                    first_message_at, last_message_at
                 FROM dm_conversations
-                ORDER BY last_message_at DESC LIMIT ?";
+                WHERE message_count >= ?1
+                  AND (json_array_length(?2) = 0 OR EXISTS (
+                      SELECT 1 FROM json_each(?2) p
+                      WHERE instr(',' || participant_ids || ',', ',' || p.value || ',') > 0))
+                ORDER BY {order_by} LIMIT ?3"
+        );
         let limit_param: i64 = limit.map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX));
+        let min_messages = i64::try_from(min_messages).unwrap_or(i64::MAX);
 
-        let mut stmt = self.conn.prepare_cached(QUERY)?;
+        let mut stmt = self.conn.prepare_cached(&query)?;
         let summaries = stmt
-            .query_map([limit_param], |row| {
-                let participants: String = row.get(1)?;
-                let participant_ids = participants
-                    .split(',')
-                    .map(str::trim)
-                    .filter(|s| !s.is_empty())
-                    .map(str::to_string)
-                    .collect();
-
-                Ok(DmConversationSummary {
-                    conversation_id: row.get(0)?,
-                    participant_ids,
-                    message_count: row.get(2)?,
-                    first_message_at: parse_rfc3339_opt(row.get::<_, Option<String>>(3)?),
-                    last_message_at: parse_rfc3339_opt(row.get::<_, Option<String>>(4)?),
-                })
-            })?
+            .query_map(
+                params![
+                    min_messages,
+                    serde_json::to_string(participant_ids)?,
+                    limit_param
+                ],
+                |row| {
+                    let participants: String = row.get(1)?;
+                    let participant_ids = participants
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string)
+                        .collect();
+
+                    Ok(DmConversationSummary {
+                        conversation_id: row.get(0)?,
+                        participant_ids,
+                        message_count: row.get(2)?,
+                        first_message_at: parse_rfc3339_opt(row.get::<_, Option<String>>(3)?),
+                        last_message_at: parse_rfc3339_opt(row.get::<_, Option<String>>(4)?),
+                    })
+                },
+            )?
             .filter_map(std::result::Result::ok)
             .collect();
 
@@ -1817,6 +5498,120 @@ impl Storage {
         Ok(mutes)
     }
 
+    /// Get stored lists with their relations and recorded member counts,
+    /// optionally limited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_lists(&self, limit: Option<usize>) -> Result<Vec<ListSummary>> {
+        const QUERY: &str = r"
+            SELECT l.list_id, min(l.url), group_concat(l.relation),
+                   (SELECT COUNT(*) FROM list_members m WHERE m.list_id = l.list_id)
+            FROM lists l
+            GROUP BY l.list_id
+            ORDER BY l.list_id
+            LIMIT ?";
+        let limit_param: i64 = limit.map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX));
+
+        let mut stmt = self.conn.prepare_cached(QUERY)?;
+        let lists = stmt
+            .query_map([limit_param], |row| {
+                let relations: String = row.get(2)?;
+                let mut relations: Vec<ListRelation> = relations
+                    .split(',')
+                    .filter_map(ListRelation::parse)
+                    .collect();
+                relations.sort_unstable();
+                Ok(ListSummary {
+                    list_id: row.get(0)?,
+                    url: row.get(1)?,
+                    relations,
+                    members: row.get::<_, i64>(3)?.try_into().unwrap_or(0),
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(lists)
+    }
+
+    /// Number of recorded members of `list_id`, or `None` if it is not a
+    /// stored list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn list_member_count(&self, list_id: &str) -> Result<Option<usize>> {
+        let count: Option<i64> = self.conn.query_row(
+            r"
+            SELECT CASE WHEN EXISTS(SELECT 1 FROM lists WHERE list_id = ?1)
+                   THEN (SELECT COUNT(*) FROM list_members WHERE list_id = ?1) END
+            ",
+            [list_id],
+            |row| row.get(0),
+        )?;
+        Ok(count.map(|n| usize::try_from(n).unwrap_or(0)))
+    }
+
+    /// Replace the recorded members of `list_id`.
+    ///
+    /// Each line is an account ID or a handle (with or without the @); blank
+    /// lines and `#` comments are skipped. Returns the number of members.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the members cannot be written.
+    pub fn set_list_members(&self, list_id: &str, members: &[String]) -> Result<usize> {
+        let members: HashSet<String> = members
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.trim_start_matches('@').to_lowercase())
+            .collect();
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM list_members WHERE list_id = ?", [list_id])?;
+        {
+            let mut stmt =
+                tx.prepare("INSERT INTO list_members (list_id, member) VALUES (?, ?)")?;
+            for member in &members {
+                stmt.execute(params![list_id, member])?;
+            }
+        }
+        tx.commit()?;
+        Ok(members.len())
+    }
+
+    /// IDs of tweets replying to or mentioning a recorded member of
+    /// `list_id`. Handles also match the accounts seen using them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn get_list_tweet_ids(&self, list_id: &str) -> Result<HashSet<String>> {
+        const QUERY: &str = r"
+            WITH members(member) AS (
+                SELECT member FROM list_members WHERE list_id = ?1
+                UNION
+                SELECT h.account_id FROM account_handles h
+                JOIN list_members m ON m.member = lower(h.screen_name)
+                WHERE m.list_id = ?1
+            )
+            SELECT t.id FROM tweets t
+            WHERE t.in_reply_to_user_id IN members
+               OR lower(t.in_reply_to_screen_name) IN members
+               OR EXISTS (
+                   SELECT 1 FROM json_each(t.mentions_json) j
+                   WHERE json_extract(j.value, '$.id') IN members
+                      OR lower(json_extract(j.value, '$.screen_name')) IN members
+               )";
+        let mut stmt = self.conn.prepare_cached(QUERY)?;
+        let ids = stmt
+            .query_map([list_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<HashSet<String>>>()?;
+        Ok(ids)
+    }
+
     /// Get all Grok messages, optionally limited.
     ///
     /// # Errors
@@ -1832,7 +5627,7 @@ impl Storage {
             .query_map([limit_param], |row| {
                 Ok(GrokMessage {
                     chat_id: row.get(0)?,
-                    message: row.get(1)?,
+                    message: row.get::<_, UnpackedText>(1)?.0,
                     sender: row.get(2)?,
                     created_at: parse_rfc3339_or_epoch(row.get::<_, Option<String>>(3)?),
                     grok_mode: row.get(4)?,
@@ -1850,7 +5645,10 @@ impl Storage {
 
     /// Store an embedding for a document.
     ///
-    /// The embedding is stored as a BLOB with F16 quantization.
+    /// The embedding is stored as a BLOB with F16 quantization, once per
+    /// content hash; documents with identical content share it. Without a
+    /// content hash the vector is keyed by a hash of its own bytes. It is
+    /// recorded as produced by the default hash embedder.
     ///
     /// # Errors
     ///
@@ -1862,42 +5660,85 @@ impl Storage {
         embedding: &[f32],
         content_hash: Option<&[u8; 32]>,
     ) -> Result<()> {
-        use half::f16;
+        self.store_embeddings_batch(
+            &[(
+                doc_id.to_string(),
+                doc_type.to_string(),
+                embedding.to_vec(),
+                content_hash.copied(),
+            )],
+            HashEmbedder::default().id(),
+        )?;
+        Ok(())
+    }
 
-        // Convert f32 to f16 for storage (50% smaller)
-        let f16_bytes: Vec<u8> = embedding
-            .iter()
-            .flat_map(|&f| f16::from_f32(f).to_le_bytes())
-            .collect();
+    /// Store multiple embeddings produced by the embedder with ID `model`.
+    ///
+    /// More efficient than calling `store_embedding` repeatedly. Vectors whose
+    /// content hash is already stored are not written again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any database insert fails.
+    pub fn store_embeddings_batch(
+        &self,
+        embeddings: &[EmbeddingRecord],
+        model: &str,
+    ) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut count = 0;
 
-        self.conn.execute(
-            r"
-            INSERT OR REPLACE INTO embeddings
-            (doc_id, doc_type, embedding, content_hash, created_at)
-            VALUES (?, ?, ?, ?, ?)
-            ",
-            params![
-                doc_id,
-                doc_type,
-                f16_bytes,
-                content_hash.map(<[u8; 32]>::as_slice),
-                Utc::now().to_rfc3339(),
-            ],
-        )?;
+        {
+            let mut insert_vector = tx.prepare(
+                r"
+                INSERT OR IGNORE INTO embedding_vectors (content_hash, embedding, created_at)
+                VALUES (?, ?, ?)
+                ",
+            )?;
+            let mut insert_doc = tx.prepare(
+                r"
+                INSERT OR REPLACE INTO embeddings
+                    (doc_id, doc_type, content_hash, created_at, model, dimension)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ",
+            )?;
 
-        Ok(())
+            let now = Utc::now().to_rfc3339();
+
+            for (doc_id, doc_type, embedding, content_hash) in embeddings {
+                let bytes = f16_bytes(embedding);
+                let hash = content_hash.unwrap_or_else(|| bytes_hash(&bytes));
+
+                insert_vector.execute(params![hash.as_slice(), bytes, &now])?;
+                insert_doc.execute(params![
+                    doc_id,
+                    doc_type,
+                    hash.as_slice(),
+                    &now,
+                    model,
+                    embedding.len()
+                ])?;
+                count += 1;
+            }
+        }
+
+        tx.commit()?;
+        Ok(count)
     }
 
-    /// Store multiple embeddings in a batch.
-    ///
-    /// More efficient than calling `store_embedding` repeatedly.
+    /// Point documents at vectors from the embedder with ID `model` that are
+    /// already stored, without re-sending the vectors. Returns how many
+    /// documents were linked; lookups whose hash has no stored vector are
+    /// skipped.
     ///
     /// # Errors
     ///
     /// Returns an error if any database insert fails.
-    pub fn store_embeddings_batch(&self, embeddings: &[EmbeddingRecord]) -> Result<usize> {
-        use half::f16;
-
+    pub fn link_embeddings(
+        &self,
+        links: &[(String, String, [u8; 32])],
+        model: &str,
+    ) -> Result<usize> {
         let tx = self.conn.unchecked_transaction()?;
         let mut count = 0;
 
@@ -1905,27 +5746,16 @@ impl Storage {
             let mut stmt = tx.prepare(
                 r"
                 INSERT OR REPLACE INTO embeddings
-                (doc_id, doc_type, embedding, content_hash, created_at)
-                VALUES (?, ?, ?, ?, ?)
+                    (doc_id, doc_type, content_hash, created_at, model, dimension)
+                SELECT ?1, ?2, content_hash, ?3, ?5, length(embedding) / 2
+                FROM embedding_vectors WHERE content_hash = ?4
                 ",
             )?;
 
             let now = Utc::now().to_rfc3339();
 
-            for (doc_id, doc_type, embedding, content_hash) in embeddings {
-                let f16_bytes: Vec<u8> = embedding
-                    .iter()
-                    .flat_map(|&f| f16::from_f32(f).to_le_bytes())
-                    .collect();
-
-                stmt.execute(params![
-                    doc_id,
-                    doc_type,
-                    f16_bytes,
-                    content_hash.as_ref().map(<[u8; 32]>::as_slice),
-                    &now,
-                ])?;
-                count += 1;
+            for (doc_id, doc_type, hash) in links {
+                count += stmt.execute(params![doc_id, doc_type, &now, hash.as_slice(), model])?;
             }
         }
 
@@ -1940,31 +5770,19 @@ impl Storage {
     /// # Errors
     ///
     /// Returns an error if the database query fails.
-    ///
-    /// # Panics
-    ///
-    /// Panics if stored embedding bytes are not aligned to 2-byte F16 chunks.
     pub fn get_embedding(&self, doc_id: &str, doc_type: &str) -> Result<Option<Vec<f32>>> {
-        use half::f16;
-
         let result: rusqlite::Result<Vec<u8>> = self.conn.query_row(
-            "SELECT embedding FROM embeddings WHERE doc_id = ? AND doc_type = ?",
+            r"
+            SELECT v.embedding FROM embeddings e
+            JOIN embedding_vectors v ON v.content_hash = e.content_hash
+            WHERE e.doc_id = ? AND e.doc_type = ?
+            ",
             params![doc_id, doc_type],
             |row| row.get(0),
         );
 
         match result {
-            Ok(bytes) => {
-                // Convert F16 bytes back to f32
-                let floats: Vec<f32> = bytes
-                    .chunks_exact(2)
-                    .map(|chunk| {
-                        let arr: [u8; 2] = chunk.try_into().unwrap();
-                        f16::from_le_bytes(arr).to_f32()
-                    })
-                    .collect();
-                Ok(Some(floats))
-            }
+            Ok(bytes) => Ok(Some(f16_to_f32(&bytes))),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e.into()),
         }
@@ -1972,27 +5790,27 @@ impl Storage {
 
     /// Get the content hash for a document's embedding.
     ///
-    /// Returns None if the document is missing or has no stored hash.
+    /// Returns None if the document has no embedding.
     ///
     /// # Errors
     ///
     /// Returns an error if the database query fails.
     pub fn get_embedding_hash(&self, doc_id: &str, doc_type: &str) -> Result<Option<[u8; 32]>> {
-        let result: rusqlite::Result<Option<Vec<u8>>> = self.conn.query_row(
+        let result: rusqlite::Result<Vec<u8>> = self.conn.query_row(
             "SELECT content_hash FROM embeddings WHERE doc_id = ? AND doc_type = ?",
             params![doc_id, doc_type],
             |row| row.get(0),
         );
 
         match result {
-            Ok(Some(bytes)) => {
+            Ok(bytes) => {
                 let arr: [u8; 32] = bytes
                     .as_slice()
                     .try_into()
                     .map_err(|_| anyhow::anyhow!("Invalid content_hash length"))?;
                 Ok(Some(arr))
             }
-            Ok(None) | Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e.into()),
         }
     }
@@ -2001,36 +5819,21 @@ impl Storage {
     ///
     /// Returns None if no embedding with the given hash exists.
     ///
-    /// Content hashes are global; embeddings can be reused across types when the
-    /// canonicalized text is identical.
+    /// Content hashes are global; one vector serves every document, of any
+    /// type, whose canonicalized text is identical.
     ///
     /// # Errors
     ///
     /// Returns an error if the database query fails.
-    ///
-    /// # Panics
-    ///
-    /// Panics if stored embedding bytes are not aligned to 2-byte F16 chunks.
     pub fn get_embedding_by_hash(&self, content_hash: &[u8; 32]) -> Result<Option<Vec<f32>>> {
-        use half::f16;
-
         let result: rusqlite::Result<Vec<u8>> = self.conn.query_row(
-            "SELECT embedding FROM embeddings WHERE content_hash = ? LIMIT 1",
+            "SELECT embedding FROM embedding_vectors WHERE content_hash = ?",
             params![content_hash.as_slice()],
             |row| row.get(0),
         );
 
         match result {
-            Ok(bytes) => {
-                let floats: Vec<f32> = bytes
-                    .chunks_exact(2)
-                    .map(|chunk| {
-                        let arr: [u8; 2] = chunk.try_into().unwrap();
-                        f16::from_le_bytes(arr).to_f32()
-                    })
-                    .collect();
-                Ok(Some(floats))
-            }
+            Ok(bytes) => Ok(Some(f16_to_f32(&bytes))),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e.into()),
         }
@@ -2044,9 +5847,9 @@ impl Storage {
     pub fn load_embedding_hashes_by_doc(
         &self,
     ) -> Result<HashMap<String, HashMap<String, [u8; 32]>>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT doc_id, doc_type, content_hash FROM embeddings WHERE content_hash IS NOT NULL",
-        )?;
+        let mut stmt = self
+            .conn
+            .prepare("SELECT doc_id, doc_type, content_hash FROM embeddings")?;
 
         let rows = stmt.query_map([], |row| {
             let doc_id: String = row.get(0)?;
@@ -2097,9 +5900,8 @@ impl Storage {
         let chunk_size = SQLITE_BATCH_SIZE / 2;
 
         for chunk in lookups.chunks(chunk_size.max(1)) {
-            let mut sql = String::from(
-                "SELECT doc_id, doc_type, content_hash FROM embeddings WHERE content_hash IS NOT NULL AND (",
-            );
+            let mut sql =
+                String::from("SELECT doc_id, doc_type, content_hash FROM embeddings WHERE (");
             for i in 0..chunk.len() {
                 if i > 0 {
                     sql.push_str(" OR ");
@@ -2148,16 +5950,10 @@ impl Storage {
     /// # Errors
     ///
     /// Returns an error if the database query fails or stored embeddings are invalid.
-    ///
-    /// # Panics
-    ///
-    /// Panics if stored embedding bytes are not aligned to 2-byte F16 chunks.
     pub fn load_embeddings_by_hashes(
         &self,
         hashes: &[[u8; 32]],
     ) -> Result<HashMap<[u8; 32], Vec<f32>>> {
-        use half::f16;
-
         if hashes.is_empty() {
             return Ok(HashMap::new());
         }
@@ -2166,7 +5962,7 @@ impl Storage {
 
         for chunk in hashes.chunks(SQLITE_BATCH_SIZE) {
             let mut sql = String::from(
-                "SELECT content_hash, embedding FROM embeddings WHERE content_hash IN (",
+                "SELECT content_hash, embedding FROM embedding_vectors WHERE content_hash IN (",
             );
             for i in 0..chunk.len() {
                 if i > 0 {
@@ -2194,19 +5990,12 @@ impl Storage {
                     }
                 };
                 let bytes: Vec<u8> = row.get(1)?;
-                let floats: Vec<f32> = bytes
-                    .chunks_exact(2)
-                    .map(|chunk| {
-                        let arr: [u8; 2] = chunk.try_into().unwrap();
-                        f16::from_le_bytes(arr).to_f32()
-                    })
-                    .collect();
-                Ok((hash, floats))
+                Ok((hash, f16_to_f32(&bytes)))
             })?;
 
             for row in rows {
                 let (hash, embedding) = row?;
-                results.entry(hash).or_insert(embedding);
+                results.insert(hash, embedding);
             }
         }
 
@@ -2221,32 +6010,14 @@ impl Storage {
     /// # Errors
     ///
     /// Returns an error if the database query fails.
-    ///
-    /// # Panics
-    ///
-    /// Panics if stored embedding bytes are not aligned to 2-byte F16 chunks.
     pub fn load_all_embeddings(&self) -> Result<Vec<(String, String, Vec<f32>)>> {
-        use half::f16;
-
-        let mut stmt = self
-            .conn
-            .prepare("SELECT doc_id, doc_type, embedding FROM embeddings")?;
+        let mut stmt = self.conn.prepare(DOC_EMBEDDINGS_SELECT)?;
 
         let rows = stmt.query_map([], |row| {
             let doc_id: String = row.get(0)?;
             let doc_type: String = row.get(1)?;
             let bytes: Vec<u8> = row.get(2)?;
-
-            // Convert F16 bytes to f32
-            let floats: Vec<f32> = bytes
-                .chunks_exact(2)
-                .map(|chunk| {
-                    let arr: [u8; 2] = chunk.try_into().unwrap();
-                    f16::from_le_bytes(arr).to_f32()
-                })
-                .collect();
-
-            Ok((doc_id, doc_type, floats))
+            Ok((doc_id, doc_type, f16_to_f32(&bytes)))
         })?;
 
         let embeddings: Vec<_> = rows.filter_map(std::result::Result::ok).collect();
@@ -2262,9 +6033,7 @@ impl Storage {
     ///
     /// Returns an error if the database query fails.
     pub fn load_all_embeddings_raw(&self) -> Result<Vec<(String, String, Vec<u8>)>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT doc_id, doc_type, embedding FROM embeddings")?;
+        let mut stmt = self.conn.prepare(DOC_EMBEDDINGS_SELECT)?;
 
         let rows = stmt.query_map([], |row| {
             let doc_id: String = row.get(0)?;
@@ -2282,30 +6051,15 @@ impl Storage {
     /// # Errors
     ///
     /// Returns an error if the database query fails.
-    ///
-    /// # Panics
-    ///
-    /// Panics if stored embedding bytes are not aligned to 2-byte F16 chunks.
     pub fn load_embeddings_by_type(&self, doc_type: &str) -> Result<Vec<(String, Vec<f32>)>> {
-        use half::f16;
-
         let mut stmt = self
             .conn
-            .prepare("SELECT doc_id, embedding FROM embeddings WHERE doc_type = ?")?;
+            .prepare(&format!("{DOC_EMBEDDINGS_SELECT} WHERE e.doc_type = ?"))?;
 
         let rows = stmt.query_map(params![doc_type], |row| {
             let doc_id: String = row.get(0)?;
-            let bytes: Vec<u8> = row.get(1)?;
-
-            let floats: Vec<f32> = bytes
-                .chunks_exact(2)
-                .map(|chunk| {
-                    let arr: [u8; 2] = chunk.try_into().unwrap();
-                    f16::from_le_bytes(arr).to_f32()
-                })
-                .collect();
-
-            Ok((doc_id, floats))
+            let bytes: Vec<u8> = row.get(2)?;
+            Ok((doc_id, f16_to_f32(&bytes)))
         })?;
 
         let embeddings: Vec<_> = rows.filter_map(std::result::Result::ok).collect();
@@ -2321,14 +6075,14 @@ impl Storage {
     /// Returns an error if the database query fails.
     pub fn embedding_exists_by_hash(&self, content_hash: &[u8; 32]) -> Result<bool> {
         let count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM embeddings WHERE content_hash = ?",
+            "SELECT COUNT(*) FROM embedding_vectors WHERE content_hash = ?",
             params![content_hash.as_slice()],
             |row| row.get(0),
         )?;
         Ok(count > 0)
     }
 
-    /// Get the total count of embeddings.
+    /// Get the total count of embedded documents.
     ///
     /// # Errors
     ///
@@ -2340,15 +6094,135 @@ impl Storage {
         Ok(count)
     }
 
+    /// Embedder ID and dimension of the stored embeddings, or `None` when
+    /// there are none.
+    ///
+    /// Embedding generation replaces every embedding when the model
+    /// changes, so one row speaks for all of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn embedding_model(&self) -> Result<Option<(String, usize)>> {
+        match self.conn.query_row(
+            "SELECT model, dimension FROM embeddings LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ) {
+            Ok(model) => Ok(Some(model)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get the count of distinct stored vectors, which is lower than
+    /// [`Storage::embedding_count`] when documents share content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub fn embedding_vector_count(&self) -> Result<i64> {
+        let count: i64 =
+            self.conn
+                .query_row("SELECT COUNT(*) FROM embedding_vectors", [], |row| {
+                    row.get(0)
+                })?;
+        Ok(count)
+    }
+
+    /// Delete stored vectors no document points at any more, returning how
+    /// many were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn prune_embedding_vectors(&self) -> Result<usize> {
+        let removed = self.conn.execute(
+            r"
+            DELETE FROM embedding_vectors
+            WHERE content_hash NOT IN (SELECT content_hash FROM embeddings)
+            ",
+            [],
+        )?;
+        Ok(removed)
+    }
+
     /// Delete all embeddings.
     ///
     /// # Errors
     ///
     /// Returns an error if the database delete fails.
     pub fn clear_embeddings(&self) -> Result<()> {
-        self.conn.execute("DELETE FROM embeddings", [])?;
+        self.conn
+            .execute_batch("DELETE FROM embeddings; DELETE FROM embedding_vectors;")?;
         Ok(())
     }
+
+    /// Delete all embeddings of one document type, returning how many were removed.
+    ///
+    /// Vectors still shared with documents of other types are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database delete fails.
+    pub fn delete_embeddings_by_type(&self, doc_type: &str) -> Result<usize> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM embeddings WHERE doc_type = ?1", [doc_type])?;
+        if removed > 0 {
+            self.prune_embedding_vectors()?;
+        }
+        Ok(removed)
+    }
+
+    /// Delete embeddings of `doc_type` whose document ID is not in `keep`.
+    ///
+    /// Returns the number of embeddings removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query or delete fails.
+    pub fn retain_embeddings_by_type(
+        &self,
+        doc_type: &str,
+        keep: &HashSet<String>,
+    ) -> Result<usize> {
+        let stale: Vec<String> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT doc_id FROM embeddings WHERE doc_type = ?1")?;
+            stmt.query_map([doc_type], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+                .into_iter()
+                .filter(|doc_id| !keep.contains(doc_id))
+                .collect()
+        };
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt =
+                tx.prepare("DELETE FROM embeddings WHERE doc_id = ?1 AND doc_type = ?2")?;
+            for doc_id in &stale {
+                stmt.execute(params![doc_id, doc_type])?;
+            }
+        }
+        tx.commit()?;
+        self.prune_embedding_vectors()?;
+        Ok(stale.len())
+    }
+}
+
+fn note_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Note> {
+    Ok(Note {
+        id: row.get(0)?,
+        doc_id: row.get(1)?,
+        doc_type: row.get(2)?,
+        text: row.get(3)?,
+        created_at: parse_rfc3339_or_epoch(row.get(4)?),
+    })
 }
 
 fn format_table_stats(stats: &[TableStat]) -> String {
@@ -2375,29 +6249,13 @@ fn limit_to_i64(limit: usize) -> i64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::TweetUrl;
+    use crate::model::{PollOption, TweetMedia, TweetPoll, TweetUrl, UserMention};
     use chrono::Duration;
     use rusqlite::params;
     use std::time::Instant;
 
     fn create_test_tweet(id: &str, text: &str) -> Tweet {
-        Tweet {
-            id: id.to_string(),
-            created_at: Utc::now(),
-            full_text: text.to_string(),
-            source: None,
-            favorite_count: 0,
-            retweet_count: 0,
-            lang: None,
-            in_reply_to_status_id: None,
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: vec![],
-            user_mentions: vec![],
-            urls: vec![],
-            media: vec![],
-        }
+        Tweet::fixture(id, text)
     }
 
     fn create_test_like(tweet_id: &str, text: Option<&str>) -> Like {
@@ -2469,6 +6327,22 @@ mod tests {
         assert!(tweet.is_none());
     }
 
+    #[test]
+    fn test_get_tweets_batch_preserves_order() {
+        let mut storage = Storage::open_memory().unwrap();
+        let tweets: Vec<Tweet> = (1..=5)
+            .map(|i| create_test_tweet(&i.to_string(), &format!("Tweet {i}")))
+            .collect();
+        storage.store_tweets(&tweets).unwrap();
+
+        let found = storage
+            .get_tweets_by_ids(&["4", "missing", "2", "4", "1"])
+            .unwrap();
+        let ids: Vec<&str> = found.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["4", "2", "1"]);
+        assert!(storage.get_tweets_by_ids(&[]).unwrap().is_empty());
+    }
+
     #[test]
     fn test_get_tweet_thread() {
         let mut storage = Storage::open_memory().unwrap();
@@ -2487,72 +6361,29 @@ mod tests {
             .with_timezone(&Utc);
 
         let root = Tweet {
-            id: "1".to_string(),
             created_at: root_date,
-            full_text: "Root tweet".to_string(),
-            source: None,
-            favorite_count: 0,
-            retweet_count: 0,
-            lang: None,
-            in_reply_to_status_id: None,
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: vec![],
-            user_mentions: vec![],
-            urls: vec![],
-            media: vec![],
+            ..Tweet::fixture("1", "Root tweet")
         };
         let reply = Tweet {
             id: "2".to_string(),
             created_at: reply_first_date,
             full_text: "Reply tweet".to_string(),
-            source: None,
-            favorite_count: 0,
-            retweet_count: 0,
-            lang: None,
             in_reply_to_status_id: Some("1".to_string()),
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: vec![],
-            user_mentions: vec![],
-            urls: vec![],
-            media: vec![],
+            ..root.clone()
         };
         let reply2 = Tweet {
             id: "3".to_string(),
             created_at: reply_followup_date,
             full_text: "Reply to reply".to_string(),
-            source: None,
-            favorite_count: 0,
-            retweet_count: 0,
-            lang: None,
             in_reply_to_status_id: Some("2".to_string()),
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: vec![],
-            user_mentions: vec![],
-            urls: vec![],
-            media: vec![],
+            ..root.clone()
         };
         let branch = Tweet {
             id: "4".to_string(),
             created_at: branch_date,
             full_text: "Branch reply".to_string(),
-            source: None,
-            favorite_count: 0,
-            retweet_count: 0,
-            lang: None,
             in_reply_to_status_id: Some("1".to_string()),
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: vec![],
-            user_mentions: vec![],
-            urls: vec![],
-            media: vec![],
+            ..root.clone()
         };
 
         storage
@@ -2606,6 +6437,44 @@ mod tests {
         assert_eq!(stats.likes_count, 3);
     }
 
+    #[test]
+    fn test_search_tweets_ordered() {
+        let mut storage = Storage::open_memory().unwrap();
+
+        let base = Utc::now();
+        let mut old = create_test_tweet("1", "rust rust rust");
+        old.created_at = base - chrono::Duration::days(2);
+        let mut popular = create_test_tweet("2", "rust and more");
+        popular.created_at = base - chrono::Duration::days(1);
+        popular.favorite_count = 50;
+        let mut newest = create_test_tweet("3", "learning rust");
+        newest.created_at = base;
+        newest.reply_count = Some(40);
+        newest.quote_count = Some(20);
+        storage.store_tweets(&[old, popular, newest]).unwrap();
+
+        let ids = |order| -> Vec<String> {
+            storage
+                .search_tweets_ordered("rust", 10, order)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.id)
+                .collect()
+        };
+        assert_eq!(ids(FtsOrder::Oldest), vec!["1", "2", "3"]);
+        assert_eq!(ids(FtsOrder::Newest), vec!["3", "2", "1"]);
+        // Replies and quotes count: 40 + 20 beats 50 likes
+        assert_eq!(ids(FtsOrder::Engagement), vec!["3", "2", "1"]);
+        assert_eq!(ids(FtsOrder::Rank).len(), 3);
+
+        let engagement = storage
+            .get_tweet_engagement(&["2", "3", "missing"])
+            .unwrap();
+        assert_eq!(engagement.len(), 2);
+        assert_eq!(engagement["2"], 50);
+        assert_eq!(engagement["3"], 60);
+    }
+
     #[test]
     fn test_search_likes_fts() {
         let mut storage = Storage::open_memory().unwrap();
@@ -2639,6 +6508,155 @@ mod tests {
         assert_eq!(results.len(), 0);
     }
 
+    #[test]
+    fn test_store_bookmarks_replaces_fts_rows() {
+        let mut storage = Storage::open_memory().unwrap();
+        let bookmark = |text: Option<&str>| Bookmark {
+            tweet_id: "b1".to_string(),
+            full_text: text.map(String::from),
+            expanded_url: None,
+        };
+
+        storage
+            .store_bookmarks(&[bookmark(Some("Saved Rust thread"))])
+            .unwrap();
+        storage
+            .store_bookmarks(&[bookmark(Some("Saved Rust thread"))])
+            .unwrap();
+        assert_eq!(storage.table_row_count("fts_bookmarks").unwrap(), 1);
+        assert_eq!(storage.get_all_bookmarks(None).unwrap().len(), 1);
+
+        storage.store_bookmarks(&[bookmark(None)]).unwrap();
+        assert_eq!(storage.table_row_count("fts_bookmarks").unwrap(), 0);
+
+        storage
+            .store_bookmarks(&[bookmark(Some("Back again"))])
+            .unwrap();
+        storage
+            .conn
+            .execute("DELETE FROM fts_bookmarks", [])
+            .unwrap();
+        assert_eq!(storage.rebuild_fts_tables().unwrap().bookmarks, 1);
+    }
+
+    #[test]
+    fn test_url_resolutions_are_pending_then_applied() {
+        let mut storage = Storage::open_memory().unwrap();
+
+        let mut tweet = create_test_tweet("1", "read this https://t.co/aaa");
+        tweet.urls = vec![
+            TweetUrl {
+                url: "https://t.co/aaa".to_string(),
+                expanded_url: None,
+                display_url: None,
+            },
+            TweetUrl {
+                url: "https://t.co/bbb".to_string(),
+                expanded_url: Some("https://example.com/known".to_string()),
+                display_url: None,
+            },
+        ];
+        storage.store_tweets(&[tweet]).unwrap();
+        let mut like = create_test_like("2", None);
+        like.expanded_url = Some("https://bit.ly/ccc".to_string());
+        storage.store_likes(&[like]).unwrap();
+
+        assert_eq!(
+            storage.get_unresolved_short_urls(false).unwrap(),
+            vec!["https://bit.ly/ccc", "https://t.co/aaa"]
+        );
+
+        storage
+            .record_url_resolution("https://t.co/aaa", Some("https://example.com/a"), None)
+            .unwrap();
+        storage
+            .record_url_resolution("https://bit.ly/ccc", None, Some("timed out"))
+            .unwrap();
+        assert!(storage.get_unresolved_short_urls(false).unwrap().is_empty());
+        assert_eq!(
+            storage.get_unresolved_short_urls(true).unwrap(),
+            vec!["https://bit.ly/ccc"]
+        );
+
+        assert_eq!(storage.apply_url_resolutions().unwrap(), 1);
+        let urls = storage.get_tweet("1").unwrap().unwrap().urls;
+        assert_eq!(
+            urls[0].expanded_url.as_deref(),
+            Some("https://example.com/a")
+        );
+        assert_eq!(
+            urls[1].expanded_url.as_deref(),
+            Some("https://example.com/known")
+        );
+    }
+
+    #[test]
+    fn test_get_self_liked_tweets() {
+        let mut storage = Storage::open_memory().unwrap();
+
+        let mut older = create_test_tweet("1", "liked my own tweet");
+        older.created_at = Utc::now() - Duration::days(1);
+        let newer = create_test_tweet("2", "liked this one too");
+        let unliked = create_test_tweet("3", "nobody liked this");
+        storage.store_tweets(&[older, newer, unliked]).unwrap();
+        storage
+            .store_likes(&[
+                create_test_like("1", None),
+                create_test_like("2", None),
+                create_test_like("99", Some("someone else's tweet")),
+            ])
+            .unwrap();
+
+        let ids: Vec<String> = storage
+            .get_self_liked_tweets()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec!["2", "1"]);
+    }
+
+    #[test]
+    fn test_get_engagement_percentile() {
+        let mut storage = Storage::open_memory().unwrap();
+
+        let mut tweets: Vec<Tweet> = (0..10)
+            .map(|i| {
+                let mut tweet = create_test_tweet(&i.to_string(), "tweet");
+                tweet.favorite_count = i * 10;
+                tweet.retweet_count = 10 - i;
+                tweet
+            })
+            .collect();
+        // Replies lift tweet 0 above tweet 9 by engagement
+        tweets[0].reply_count = Some(200);
+        let mut retweet = create_test_tweet("rt", "RT someone else");
+        retweet.is_retweet = true;
+        retweet.favorite_count = 1000;
+        tweets.push(retweet);
+        storage.store_tweets(&tweets).unwrap();
+
+        // Live ranks before the summaries exist, then from the summary table
+        let live = storage.get_engagement_percentile("9").unwrap().unwrap();
+        storage.refresh_stats_summaries().unwrap();
+        let cached = storage.get_engagement_percentile("9").unwrap().unwrap();
+        for percentile in [live, cached] {
+            assert_eq!(percentile.tweets, 10);
+            assert_eq!(percentile.likes.rank, 1);
+            assert!((percentile.likes.top_percent - 10.0).abs() < f64::EPSILON);
+            assert_eq!(percentile.retweets.rank, 10);
+            assert_eq!(percentile.engagement.rank, 2);
+        }
+
+        assert!(storage.get_engagement_percentile("rt").unwrap().is_none());
+        assert!(
+            storage
+                .get_engagement_percentile("missing")
+                .unwrap()
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_store_dm_conversations() {
         let mut storage = Storage::open_memory().unwrap();
@@ -2727,6 +6745,90 @@ mod tests {
         assert_eq!(messages[1].id, "dm2");
     }
 
+    #[test]
+    fn test_conversation_ids_active_between() {
+        let mut storage = Storage::open_memory().unwrap();
+        let date = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let conversation = |id: &str, first: &str, last: &str| DmConversation {
+            conversation_id: id.to_string(),
+            messages: [("a", first), ("b", last)]
+                .into_iter()
+                .map(|(suffix, created_at)| DirectMessage {
+                    conversation_id: id.to_string(),
+                    created_at: date(created_at),
+                    ..create_test_dm(&format!("{id}-{suffix}"), "hi")
+                })
+                .collect(),
+        };
+        storage
+            .store_dm_conversations(&[
+                conversation("spans", "2020-02-01T00:00:00Z", "2020-04-01T00:00:00Z"),
+                conversation("before", "2019-01-01T00:00:00Z", "2020-02-28T00:00:00Z"),
+                conversation("after", "2020-04-01T00:00:00Z", "2021-01-01T00:00:00Z"),
+            ])
+            .unwrap();
+
+        let march = storage
+            .get_conversation_ids_active_between(
+                Some(date("2020-03-01T00:00:00Z")),
+                Some(date("2020-03-31T23:59:59Z")),
+            )
+            .unwrap();
+        assert_eq!(march, HashSet::from(["spans".to_string()]));
+
+        let since = storage
+            .get_conversation_ids_active_between(Some(date("2020-03-01T00:00:00Z")), None)
+            .unwrap();
+        assert_eq!(since.len(), 2);
+        assert!(!since.contains("before"));
+    }
+
+    #[test]
+    fn test_find_dm_conversations_filters_and_orders() {
+        let mut storage = Storage::open_memory().unwrap();
+        let base = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let conversation = |id: &str, other: &str, count: i64, start_days: i64| DmConversation {
+            conversation_id: id.to_string(),
+            messages: (0..count)
+                .map(|i| DirectMessage {
+                    conversation_id: id.to_string(),
+                    sender_id: "1".to_string(),
+                    recipient_id: other.to_string(),
+                    created_at: base + Duration::days(start_days + i),
+                    ..create_test_dm(&format!("{id}-{i}"), "hi")
+                })
+                .collect(),
+        };
+        storage
+            .store_dm_conversations(&[
+                conversation("1-10", "10", 3, 0),
+                conversation("1-100", "100", 1, 10),
+                conversation("1-20", "20", 5, 1),
+            ])
+            .unwrap();
+        let ids = |summaries: Vec<DmConversationSummary>| -> Vec<String> {
+            summaries.into_iter().map(|s| s.conversation_id).collect()
+        };
+
+        let recent = storage
+            .find_dm_conversations(&[], 0, ConversationOrder::Recent, None)
+            .unwrap();
+        assert_eq!(ids(recent), ["1-100", "1-20", "1-10"]);
+
+        let busiest = storage
+            .find_dm_conversations(&[], 2, ConversationOrder::Messages, None)
+            .unwrap();
+        assert_eq!(ids(busiest), ["1-20", "1-10"]);
+
+        // "10" must not match the participant "100"
+        let with = storage
+            .find_dm_conversations(&["10".to_string()], 0, ConversationOrder::Oldest, None)
+            .unwrap();
+        assert_eq!(ids(with), ["1-10"]);
+    }
+
     #[test]
     fn test_get_conversation_messages_empty() {
         // Empty/missing conversation_id should return empty vec, not error
@@ -2903,8 +7005,54 @@ mod tests {
         let count = storage.store_blocks(&blocks).unwrap();
         assert_eq!(count, 1);
 
-        let stats = storage.get_stats().unwrap();
-        assert_eq!(stats.blocks_count, 1);
+        let stats = storage.get_stats().unwrap();
+        assert_eq!(stats.blocks_count, 1);
+    }
+
+    #[test]
+    fn test_lists_and_member_tweets() {
+        let mut storage = Storage::open_memory().unwrap();
+
+        let list = |relation| UserList {
+            list_id: "99".to_string(),
+            url: "https://twitter.com/i/lists/99".to_string(),
+            relation,
+        };
+        storage
+            .store_lists(&[list(ListRelation::Subscribed), list(ListRelation::Created)])
+            .unwrap();
+        assert_eq!(storage.list_member_count("99").unwrap(), Some(0));
+        assert_eq!(storage.list_member_count("100").unwrap(), None);
+
+        let mut reply = create_test_tweet("1", "replying");
+        reply.in_reply_to_user_id = Some("42".to_string());
+        let mut mention = create_test_tweet("2", "hi @Alice");
+        mention.user_mentions = vec![UserMention {
+            id: "7".to_string(),
+            screen_name: "Alice".to_string(),
+            name: None,
+        }];
+        let other = create_test_tweet("3", "nothing");
+        storage.store_tweets(&[reply, mention, other]).unwrap();
+
+        let members = vec![
+            "42".to_string(),
+            "@ALICE".to_string(),
+            String::new(),
+            "# comment".to_string(),
+        ];
+        assert_eq!(storage.set_list_members("99", &members).unwrap(), 2);
+
+        let lists = storage.get_lists(None).unwrap();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(
+            lists[0].relations,
+            vec![ListRelation::Created, ListRelation::Subscribed]
+        );
+        assert_eq!(lists[0].members, 2);
+
+        let ids = storage.get_list_tweet_ids("99").unwrap();
+        assert_eq!(ids, HashSet::from(["1".to_string(), "2".to_string()]));
     }
 
     #[test]
@@ -3131,38 +7279,12 @@ mod tests {
 
         let tweets = vec![
             Tweet {
-                id: "1".to_string(),
                 created_at: early_date,
-                full_text: "Early tweet".to_string(),
-                source: None,
-                favorite_count: 0,
-                retweet_count: 0,
-                lang: None,
-                in_reply_to_status_id: None,
-                in_reply_to_user_id: None,
-                in_reply_to_screen_name: None,
-                is_retweet: false,
-                hashtags: vec![],
-                user_mentions: vec![],
-                urls: vec![],
-                media: vec![],
+                ..create_test_tweet("1", "Early tweet")
             },
             Tweet {
-                id: "2".to_string(),
                 created_at: late_date,
-                full_text: "Late tweet".to_string(),
-                source: None,
-                favorite_count: 0,
-                retweet_count: 0,
-                lang: None,
-                in_reply_to_status_id: None,
-                in_reply_to_user_id: None,
-                in_reply_to_screen_name: None,
-                is_retweet: false,
-                hashtags: vec![],
-                user_mentions: vec![],
-                urls: vec![],
-                media: vec![],
+                ..create_test_tweet("2", "Late tweet")
             },
         ];
         storage.store_tweets(&tweets).unwrap();
@@ -3345,21 +7467,11 @@ mod tests {
 
         // Store updated tweet with same ID
         let updated = Tweet {
-            id: "1".to_string(),
-            created_at: Utc::now(),
-            full_text: "Updated text".to_string(),
             source: Some("test".to_string()),
             favorite_count: 100, // Changed
             retweet_count: 50,   // Changed
             lang: Some("en".to_string()),
-            in_reply_to_status_id: None,
-            in_reply_to_user_id: None,
-            in_reply_to_screen_name: None,
-            is_retweet: false,
-            hashtags: vec![],
-            user_mentions: vec![],
-            urls: vec![],
-            media: vec![],
+            ..Tweet::fixture("1", "Updated text")
         };
         storage.store_tweets(&[updated]).unwrap();
 
@@ -3401,38 +7513,12 @@ mod tests {
 
         let tweets = vec![
             Tweet {
-                id: "1".to_string(),
                 created_at: early_date,
-                full_text: "Early tweet".to_string(),
-                source: None,
-                favorite_count: 0,
-                retweet_count: 0,
-                lang: None,
-                in_reply_to_status_id: None,
-                in_reply_to_user_id: None,
-                in_reply_to_screen_name: None,
-                is_retweet: false,
-                hashtags: vec![],
-                user_mentions: vec![],
-                urls: vec![],
-                media: vec![],
+                ..Tweet::fixture("1", "Early tweet")
             },
             Tweet {
-                id: "2".to_string(),
                 created_at: late_date,
-                full_text: "Late tweet".to_string(),
-                source: None,
-                favorite_count: 0,
-                retweet_count: 0,
-                lang: None,
-                in_reply_to_status_id: None,
-                in_reply_to_user_id: None,
-                in_reply_to_screen_name: None,
-                is_retweet: false,
-                hashtags: vec![],
-                user_mentions: vec![],
-                urls: vec![],
-                media: vec![],
+                ..Tweet::fixture("2", "Late tweet")
             },
         ];
 
@@ -3475,22 +7561,608 @@ mod tests {
             ",
         )
         .unwrap();
+        register_functions(&conn).unwrap();
+
+        let storage = Storage { conn };
+        storage.migrate().unwrap();
+
+        let version = storage.get_schema_version();
+        assert_eq!(version, SCHEMA_VERSION);
+
+        let schema_sql: String = storage
+            .conn
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type='table' AND name='embeddings'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(schema_sql.contains("PRIMARY KEY (doc_id, doc_type)"));
+    }
+
+    #[test]
+    fn test_migrate_backfills_tweet_hashtags() {
+        let storage = Storage::open_memory().unwrap();
+        storage
+            .conn
+            .execute_batch(
+                r#"
+                INSERT INTO tweets (id, created_at, full_text, favorite_count, retweet_count,
+                                    is_retweet, hashtags_json, mentions_json, urls_json, media_json)
+                VALUES ('1', '2023-01-01T00:00:00+00:00', 'hi', 0, 0, 0,
+                        '["Rust","rust","WASM"]', '[]', '[]', '[]');
+                DELETE FROM tweet_hashtags;
+                UPDATE meta SET value = '4' WHERE key = 'schema_version';
+                "#,
+            )
+            .unwrap();
+
+        storage.migrate().unwrap();
+
+        let tags: Vec<String> = storage
+            .conn
+            .prepare("SELECT tag FROM tweet_hashtags WHERE tweet_id = '1' ORDER BY tag")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(tags, vec!["rust".to_string(), "wasm".to_string()]);
+
+        // Stats summaries are built from the backfilled tags
+        assert!(storage.has_stats_summaries());
+        let rust: i64 = storage
+            .conn
+            .query_row(
+                "SELECT count FROM stats_hashtags WHERE tag = 'rust'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(rust, 1);
+    }
+
+    #[test]
+    fn test_storage_profiles() {
+        assert_eq!(
+            StorageProfile::for_memory(Some(GIB)),
+            StorageProfile::LowMemory
+        );
+        assert_eq!(
+            StorageProfile::for_memory(Some(8 * GIB)),
+            StorageProfile::Default
+        );
+        assert_eq!(
+            StorageProfile::for_memory(Some(32 * GIB)),
+            StorageProfile::Fast
+        );
+        assert_eq!(StorageProfile::for_memory(None), StorageProfile::Default);
+        assert_eq!(
+            parse_mem_total("MemTotal:       16318412 kB\nMemFree: 1 kB\n"),
+            Some(16_318_412 * 1024)
+        );
+        assert_eq!(
+            "Low-Memory".parse::<StorageProfile>(),
+            Ok(StorageProfile::LowMemory)
+        );
+        assert!("huge".parse::<StorageProfile>().is_err());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let storage =
+            Storage::open_with_profile(dir.path().join("xf.db"), StorageProfile::Fast).unwrap();
+        let pragma = |name: &str| -> i64 {
+            storage
+                .conn
+                .query_row(&format!("PRAGMA {name}"), [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(pragma("cache_size"), -256 * 1024);
+        assert_eq!(pragma("wal_autocheckpoint"), 10_000);
+    }
+
+    #[test]
+    fn test_grok_messages_stored_compressed() {
+        let mut storage = Storage::open_memory().unwrap();
+        let long = "Grok explains tokenizers at length. ".repeat(20);
+        storage
+            .store_grok_messages(&[
+                create_test_grok_message("c1", &long),
+                create_test_grok_message("c1", "short reply"),
+            ])
+            .unwrap();
+
+        let types: Vec<String> = storage
+            .conn
+            .prepare("SELECT typeof(message) FROM grok_messages ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(types, vec!["blob", "text"]);
+        let stored: i64 = storage
+            .conn
+            .query_row(
+                "SELECT length(message) FROM grok_messages WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(usize::try_from(stored).unwrap() < long.len() / 4);
+
+        let mut messages: Vec<String> = storage
+            .get_all_grok_messages(None)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.message)
+            .collect();
+        messages.sort();
+        assert_eq!(messages, vec![long.clone(), "short reply".to_string()]);
+
+        // SQL reads through xf_unpack, e.g. when FTS rows are rebuilt
+        storage.rebuild_fts_tables().unwrap();
+        let found = storage.search_grok("tokenizers", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].message, long);
+    }
+
+    #[test]
+    fn test_migrate_compresses_grok_messages() {
+        let storage = Storage::open_memory().unwrap();
+        let long = "An uncompressed message from an older database. ".repeat(10);
+        storage
+            .conn
+            .execute(
+                "INSERT INTO grok_messages (chat_id, message, sender, created_at)
+                 VALUES ('c1', ?1, 'user', '2024-01-01T00:00:00+00:00')",
+                [&long],
+            )
+            .unwrap();
+        storage
+            .conn
+            .execute(
+                "UPDATE meta SET value = '21' WHERE key = 'schema_version'",
+                [],
+            )
+            .unwrap();
+
+        storage.migrate().unwrap();
+
+        let kind: String = storage
+            .conn
+            .query_row("SELECT typeof(message) FROM grok_messages", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(kind, "blob");
+        let messages = storage.get_all_grok_messages(None).unwrap();
+        assert_eq!(messages[0].message, long);
+    }
+
+    #[test]
+    fn test_raw_json_round_trip() {
+        let mut storage = Storage::open_memory().unwrap();
+        storage
+            .store_tweets(&[create_test_tweet("1", "one"), create_test_tweet("2", "two")])
+            .unwrap();
+        let raw = |id: &str, json: &str| RawRecord {
+            id: id.to_string(),
+            json: json.to_string(),
+        };
+
+        let stored = storage
+            .store_raw_json(
+                RawKind::Tweet,
+                &[
+                    raw("1", r#"{"id_str":"1","card":{"name":"summary"}}"#),
+                    raw("404", r#"{"id_str":"404"}"#),
+                ],
+            )
+            .unwrap();
+        assert_eq!(stored, 1);
+
+        let found = storage.get_raw_json(RawKind::Tweet, &["1", "2"]).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found["1"]["card"]["name"], "summary");
+
+        // Re-storing the tweet drops its JSON until it is stored again
+        storage
+            .store_tweets(&[create_test_tweet("1", "one")])
+            .unwrap();
+        assert!(
+            storage
+                .get_raw_json(RawKind::Tweet, &["1"])
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            storage
+                .get_raw_json(RawKind::Like, &["1"])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_migrate_adds_raw_json_columns() {
+        let storage = Storage::open_memory().unwrap();
+        storage
+            .conn
+            .execute_batch(
+                r"
+                ALTER TABLE tweets DROP COLUMN raw_json;
+                ALTER TABLE likes DROP COLUMN raw_json;
+                ALTER TABLE direct_messages DROP COLUMN raw_json;
+                UPDATE meta SET value = '20' WHERE key = 'schema_version';
+                ",
+            )
+            .unwrap();
+
+        storage.migrate().unwrap();
+
+        for table in ["tweets", "likes", "direct_messages"] {
+            assert!(storage.has_column(table, "raw_json").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_migrate_records_hash_embedder_model() {
+        let storage = Storage::open_memory().unwrap();
+        storage
+            .store_embedding("1", "tweet", &[1.0], Some(&[1_u8; 32]))
+            .unwrap();
+        storage
+            .conn
+            .execute_batch(
+                r"
+                ALTER TABLE embeddings DROP COLUMN model;
+                ALTER TABLE embeddings DROP COLUMN dimension;
+                UPDATE meta SET value = '28' WHERE key = 'schema_version';
+                ",
+            )
+            .unwrap();
+
+        storage.migrate().unwrap();
+
+        assert_eq!(
+            storage.embedding_model().unwrap(),
+            Some(("fnv1a-384".to_string(), 384))
+        );
+    }
+
+    #[test]
+    fn test_migrate_adds_reply_and_quote_columns() {
+        let storage = Storage::open_memory().unwrap();
+        storage
+            .conn
+            .execute_batch(
+                r"
+                ALTER TABLE tweets DROP COLUMN reply_count;
+                ALTER TABLE tweets DROP COLUMN quote_count;
+                UPDATE meta SET value = '29' WHERE key = 'schema_version';
+                ",
+            )
+            .unwrap();
+
+        storage.migrate().unwrap();
+
+        assert!(storage.has_column("tweets", "reply_count").unwrap());
+        assert!(storage.has_column("tweets", "quote_count").unwrap());
+    }
+
+    #[test]
+    fn test_migrate_adds_geo_column() {
+        let storage = Storage::open_memory().unwrap();
+        storage
+            .conn
+            .execute_batch(
+                r"
+                ALTER TABLE tweets DROP COLUMN geo_json;
+                UPDATE meta SET value = '15' WHERE key = 'schema_version';
+                ",
+            )
+            .unwrap();
+
+        storage.migrate().unwrap();
+
+        assert!(storage.has_column("tweets", "geo_json").unwrap());
+    }
+
+    #[test]
+    fn test_alt_text_lookup_and_fts() {
+        let mut storage = Storage::open_memory().unwrap();
+        let media = |id: &str, alt: Option<&str>| TweetMedia {
+            id: id.to_string(),
+            media_type: "photo".to_string(),
+            url: format!("https://example.com/{id}.jpg"),
+            local_path: None,
+            alt_text: alt.map(String::from),
+        };
+        let mut tweets = vec![
+            create_test_tweet("1", "described"),
+            create_test_tweet("2", "half described"),
+            create_test_tweet("3", "undescribed"),
+            create_test_tweet("4", "text only"),
+        ];
+        tweets[0].media = vec![media("a", Some("A red bicycle"))];
+        tweets[1].media = vec![media("b", Some("A cat")), media("c", None)];
+        tweets[2].media = vec![media("d", None)];
+        storage.store_tweets(&tweets).unwrap();
+
+        let sorted = |ids: HashSet<String>| {
+            let mut ids: Vec<String> = ids.into_iter().collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            sorted(storage.get_tweet_ids_by_alt_text(true).unwrap()),
+            vec!["1"]
+        );
+        assert_eq!(
+            sorted(storage.get_tweet_ids_by_alt_text(false).unwrap()),
+            vec!["2", "3"]
+        );
+
+        assert_eq!(storage.search_tweets("bicycle", 10).unwrap()[0].id, "1");
+        storage.rebuild_fts_tables().unwrap();
+        assert_eq!(storage.search_tweets("bicycle", 10).unwrap()[0].id, "1");
+        assert_eq!(storage.search_tweets("cat", 10).unwrap()[0].id, "2");
+    }
+
+    #[test]
+    fn test_profile_history() {
+        let storage = Storage::open_memory().unwrap();
+        let info = |date: &str, name: &str| ArchiveInfo {
+            account_id: "1".to_string(),
+            username: "me".to_string(),
+            display_name: Some(name.to_string()),
+            archive_size_bytes: 0,
+            generation_date: DateTime::parse_from_rfc3339(date)
+                .unwrap()
+                .with_timezone(&Utc),
+            is_partial: false,
+        };
+        let profile = |bio: &str| Profile {
+            bio: Some(bio.to_string()),
+            website: None,
+            location: None,
+            avatar_url: None,
+            header_url: None,
+        };
+        let newer = info("2024-01-01T00:00:00Z", "Me");
+        storage
+            .store_profile_version(&newer, Some(&profile("new bio")))
+            .unwrap();
+        storage
+            .store_profile_version(&info("2020-01-01T00:00:00Z", "Me"), Some(&profile("old")))
+            .unwrap();
+        // Re-indexing an archive replaces its version
+        storage
+            .store_profile_version(&newer, Some(&profile("newer bio")))
+            .unwrap();
+
+        let history = storage.get_profile_history().unwrap();
+        let bios: Vec<_> = history.iter().map(|p| p.bio.as_deref()).collect();
+        assert_eq!(bios, [Some("old"), Some("newer bio")]);
+        assert_eq!(history[1].display_name.as_deref(), Some("Me"));
+    }
+
+    #[test]
+    fn test_account_handles_resolve_by_date() {
+        let mut storage = Storage::open_memory().unwrap();
+        let date = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let mut tweet = create_test_tweet("1", "hi @early");
+        tweet.created_at = date("2018-01-01T00:00:00Z");
+        tweet.user_mentions = vec![UserMention {
+            id: "42".to_string(),
+            screen_name: "early".to_string(),
+            name: None,
+        }];
+        let mut reply = create_test_tweet("2", "hey");
+        reply.created_at = date("2022-01-01T00:00:00Z");
+        reply.in_reply_to_user_id = Some("42".to_string());
+        reply.in_reply_to_screen_name = Some("later".to_string());
+        storage.store_tweets(&[tweet, reply]).unwrap();
+        storage
+            .store_screen_name_changes(&[ScreenNameChange {
+                account_id: "42".to_string(),
+                changed_at: date("2020-06-01T00:00:00Z"),
+                changed_from: "early".to_string(),
+                changed_to: "later".to_string(),
+            }])
+            .unwrap();
+        storage.rebuild_account_handles().unwrap();
+
+        let resolver = storage.get_handle_resolver().unwrap();
+        assert_eq!(
+            resolver.resolve("42", date("2017-01-01T00:00:00Z")),
+            Some("early")
+        );
+        assert_eq!(
+            resolver.resolve("42", date("2019-01-01T00:00:00Z")),
+            Some("early")
+        );
+        assert_eq!(
+            resolver.resolve("42", date("2021-01-01T00:00:00Z")),
+            Some("later")
+        );
+        assert_eq!(resolver.latest("42"), Some("later"));
+        assert_eq!(resolver.resolve("7", date("2021-01-01T00:00:00Z")), None);
+
+        // Rebuilding replaces rather than accumulates
+        storage.rebuild_account_handles().unwrap();
+        assert_eq!(
+            storage.get_handle_resolver().unwrap().latest_names().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_community_notes_store_list_and_match() {
+        let mut storage = Storage::open_memory().unwrap();
+        let note = |id: &str, text: &str, date: &str| CommunityNote {
+            id: id.to_string(),
+            tweet_id: Some("99".to_string()),
+            text: text.to_string(),
+            classification: None,
+            created_at: DateTime::parse_from_rfc3339(date)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc)),
+        };
+        storage
+            .store_community_notes(&[
+                note("1", "The photo is from 2015", "2023-01-01T00:00:00Z"),
+                note("2", "Quote is fabricated", "2024-01-01T00:00:00Z"),
+            ])
+            .unwrap();
+        // Re-indexing replaces the note and its FTS row
+        storage
+            .store_community_notes(&[note("1", "The photo is from 2016", "2023-01-01T00:00:00Z")])
+            .unwrap();
+
+        let all = storage.get_all_community_notes(None).unwrap();
+        let ids: Vec<_> = all.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["2", "1"]);
+
+        let matches = storage
+            .search_community_notes_ordered("photo", 10, FtsOrder::Rank)
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].text, "The photo is from 2016");
+
+        storage.clear_fts_tables().unwrap();
+        let stats = storage.rebuild_fts_tables().unwrap();
+        assert_eq!(stats.community_notes, 2);
+    }
+
+    #[test]
+    fn test_poll_options_in_fts() {
+        let mut storage = Storage::open_memory().unwrap();
+        let mut tweet = create_test_tweet("1", "Settle this");
+        tweet.poll = Some(TweetPoll {
+            options: vec![PollOption {
+                label: "Tabs".to_string(),
+                votes: Some(3),
+            }],
+            end_datetime: None,
+            counts_are_final: true,
+        });
+        storage.store_tweets(&[tweet.clone()]).unwrap();
+
+        let found = storage.search_tweets("tabs", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].poll, tweet.poll);
+        assert_eq!(found[0].full_text, "Settle this");
+
+        storage.rebuild_fts_tables().unwrap();
+        assert_eq!(storage.search_tweets("tabs", 10).unwrap().len(), 1);
+        assert_eq!(storage.search_tweets("settle", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_geotagged_tweet_ids_and_top_places() {
+        let mut storage = Storage::open_memory().unwrap();
+        let geo = |place: &str, country: &str, point: Option<(f64, f64)>| TweetGeo {
+            place: Some(place.to_string()),
+            country: Some(country.to_string()),
+            latitude: point.map(|(lat, _)| lat),
+            longitude: point.map(|(_, lon)| lon),
+        };
+        let mut tweets = vec![
+            create_test_tweet("1", "pdx"),
+            create_test_tweet("2", "pdx again"),
+            create_test_tweet("3", "berlin"),
+            create_test_tweet("4", "no point"),
+            create_test_tweet("5", "nowhere"),
+        ];
+        tweets[0].geo = Some(geo("Portland, OR", "United States", Some((45.52, -122.68))));
+        tweets[1].geo = Some(geo("Portland, OR", "United States", Some((45.50, -122.60))));
+        tweets[2].geo = Some(geo("Berlin", "Germany", Some((52.5, 13.4))));
+        tweets[3].geo = Some(geo("Paris", "France", None));
+        storage.store_tweets(&tweets).unwrap();
 
-        let storage = Storage { conn };
-        storage.migrate().unwrap();
+        assert_eq!(storage.get_tweet("3").unwrap().unwrap().geo, tweets[2].geo);
 
-        let version = storage.get_schema_version();
-        assert_eq!(version, SCHEMA_VERSION);
+        let ids = |place: Option<&str>, bbox: Option<&GeoBox>| {
+            let mut ids: Vec<String> = storage
+                .get_geotagged_tweet_ids(place, bbox)
+                .unwrap()
+                .into_iter()
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(Some("portland"), None), vec!["1", "2"]);
+        assert_eq!(ids(Some("germany"), None), vec!["3"]);
+        let oregon = GeoBox::parse("-123,45,-122,46").unwrap();
+        assert_eq!(ids(None, Some(&oregon)), vec!["1", "2"]);
+        assert_eq!(ids(Some("berlin"), Some(&oregon)), Vec::<String>::new());
+        assert_eq!(ids(None, None), vec!["1", "2", "3", "4"]);
+
+        let pacific = GeoBox::parse("170, -20, -170, 20").unwrap();
+        assert!(pacific.contains(0.0, 179.0) && pacific.contains(0.0, -179.0));
+        assert!(!pacific.contains(0.0, 0.0));
+        assert!(GeoBox::parse("1,2,3").is_err());
+        assert!(GeoBox::parse("0,95,1,96").is_err());
 
-        let schema_sql: String = storage
+        assert_eq!(
+            storage.get_top_places(2).unwrap(),
+            vec![("Portland, OR".to_string(), 2), ("Berlin".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_get_tweets_between_and_with_hashtag() {
+        let mut storage = Storage::open_memory().unwrap();
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let mut tweets = vec![
+            create_test_tweet("1", "January"),
+            create_test_tweet("2", "February"),
+            create_test_tweet("3", "March"),
+        ];
+        tweets[0].created_at = at("2024-01-15T12:00:00Z");
+        tweets[1].created_at = at("2024-02-15T12:00:00Z");
+        tweets[2].created_at = at("2024-03-15T12:00:00Z");
+        tweets[0].hashtags = vec!["Rust".to_string()];
+        tweets[2].hashtags = vec!["rust".to_string(), "wasm".to_string()];
+        storage.store_tweets(&tweets).unwrap();
+
+        let between = storage
+            .get_tweets_between(at("2024-02-01T00:00:00Z"), at("2024-03-31T23:59:59Z"))
+            .unwrap();
+        let ids: Vec<&str> = between.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "2"]);
+
+        let tagged = storage.get_tweets_with_hashtag("#RUST").unwrap();
+        let ids: Vec<&str> = tagged.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "1"]);
+        assert!(storage.get_tweets_with_hashtag("go").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_replaces_hashtag_index() {
+        let storage = Storage::open_memory().unwrap();
+        storage
             .conn
-            .query_row(
-                "SELECT sql FROM sqlite_master WHERE type='table' AND name='embeddings'",
-                [],
-                |row| row.get(0),
+            .execute_batch(
+                r"
+                CREATE INDEX idx_tweet_hashtags_tag ON tweet_hashtags(tag);
+                UPDATE meta SET value = '5' WHERE key = 'schema_version';
+                ",
             )
             .unwrap();
-        assert!(schema_sql.contains("PRIMARY KEY (doc_id, doc_type)"));
+
+        storage.migrate().unwrap();
+
+        let indexes: Vec<String> = storage
+            .conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = 'tweet_hashtags' AND name LIKE 'idx_%'")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(indexes, vec!["idx_tweet_hashtags_tag_tweet".to_string()]);
     }
 
     #[test]
@@ -3710,6 +8382,349 @@ mod tests {
         assert_vec_approx(&likes[0].1, &emb_like);
     }
 
+    #[test]
+    fn test_delete_embeddings_by_type() {
+        let storage = Storage::open_memory().unwrap();
+        storage
+            .store_embedding("1", "tweet", &[0.1, 0.2], Some(&[1_u8; 32]))
+            .unwrap();
+        storage
+            .store_embedding("2", "like", &[0.3, 0.4], Some(&[2_u8; 32]))
+            .unwrap();
+
+        assert_eq!(storage.delete_embeddings_by_type("like").unwrap(), 1);
+        assert_eq!(storage.delete_embeddings_by_type("like").unwrap(), 0);
+        assert_eq!(storage.embedding_count().unwrap(), 1);
+        assert_eq!(storage.load_embeddings_by_type("tweet").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_embeddings_record_model_and_dimension() {
+        let storage = Storage::open_memory().unwrap();
+        assert_eq!(storage.embedding_model().unwrap(), None);
+
+        storage
+            .store_embeddings_batch(
+                &[(
+                    "1".to_string(),
+                    "tweet".to_string(),
+                    vec![0.6, 0.8, 0.0],
+                    Some([3_u8; 32]),
+                )],
+                "onnx:all-MiniLM-L6-v2",
+            )
+            .unwrap();
+        storage
+            .link_embeddings(
+                &[("2".to_string(), "like".to_string(), [3_u8; 32])],
+                "onnx:all-MiniLM-L6-v2",
+            )
+            .unwrap();
+
+        let models: Vec<(String, usize)> = storage
+            .conn
+            .prepare("SELECT model, dimension FROM embeddings")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(models, vec![("onnx:all-MiniLM-L6-v2".to_string(), 3); 2]);
+        assert_eq!(
+            storage.embedding_model().unwrap(),
+            Some(("onnx:all-MiniLM-L6-v2".to_string(), 3))
+        );
+    }
+
+    #[test]
+    fn test_identical_content_shares_one_vector() {
+        let storage = Storage::open_memory().unwrap();
+        let hash = [5_u8; 32];
+        storage
+            .store_embedding("1", "tweet", &[0.5, 0.25], Some(&hash))
+            .unwrap();
+        storage
+            .store_embedding("1", "like", &[0.5, 0.25], Some(&hash))
+            .unwrap();
+        assert_eq!(
+            storage
+                .link_embeddings(
+                    &[
+                        ("9".to_string(), "dm".to_string(), hash),
+                        ("8".to_string(), "dm".to_string(), [6_u8; 32]),
+                    ],
+                    "fnv1a-384",
+                )
+                .unwrap(),
+            1
+        );
+
+        assert_eq!(storage.embedding_count().unwrap(), 3);
+        assert_eq!(storage.embedding_vector_count().unwrap(), 1);
+        assert_eq!(
+            storage.get_embedding("9", "dm").unwrap(),
+            Some(vec![0.5, 0.25])
+        );
+
+        // The vector outlives its first owners and goes with the last one
+        storage.delete_embeddings_by_type("tweet").unwrap();
+        storage.delete_embeddings_by_type("like").unwrap();
+        assert_eq!(storage.embedding_vector_count().unwrap(), 1);
+        storage.delete_embeddings_by_type("dm").unwrap();
+        assert_eq!(storage.embedding_vector_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migrate_splits_inline_embeddings() {
+        let storage = Storage::open_memory().unwrap();
+        storage
+            .conn
+            .execute_batch(
+                r"
+                DROP TABLE embeddings;
+                DROP TABLE embedding_vectors;
+                CREATE TABLE embeddings (
+                    doc_id TEXT NOT NULL,
+                    doc_type TEXT NOT NULL,
+                    embedding BLOB NOT NULL,
+                    content_hash BLOB,
+                    created_at TEXT NOT NULL,
+                    PRIMARY KEY (doc_id, doc_type)
+                );
+                CREATE INDEX idx_embeddings_type ON embeddings(doc_type);
+                CREATE INDEX idx_embeddings_hash ON embeddings(content_hash);
+                INSERT INTO embeddings VALUES
+                    ('1', 'tweet', x'003c', x'01', '2024-01-01T00:00:00+00:00'),
+                    ('1', 'like', x'003c', x'01', '2024-01-01T00:00:00+00:00'),
+                    ('2', 'dm', x'0038', NULL, '2024-01-01T00:00:00+00:00');
+                UPDATE meta SET value = '13' WHERE key = 'schema_version';
+                ",
+            )
+            .unwrap();
+
+        storage.migrate().unwrap();
+
+        assert_eq!(storage.get_schema_version(), SCHEMA_VERSION);
+        assert_eq!(storage.embedding_count().unwrap(), 2);
+        assert_eq!(storage.embedding_vector_count().unwrap(), 1);
+        assert_eq!(storage.get_embedding("1", "like").unwrap(), Some(vec![1.0]));
+        let indexes: i64 = storage
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND tbl_name = 'embeddings'
+                 AND name LIKE 'idx_embeddings_%'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(indexes, 2);
+    }
+
+    #[test]
+    fn test_tag_definitions_and_assignments() {
+        let mut storage = Storage::open_memory().unwrap();
+        storage
+            .define_tag("work", &["standup notes".to_string()], 0.3)
+            .unwrap();
+        storage
+            .define_tag("work", &["sprint planning".to_string()], 0.4)
+            .unwrap();
+
+        let definitions = storage.get_tag_definitions().unwrap();
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].examples, vec!["sprint planning"]);
+        assert!((definitions[0].threshold - 0.4).abs() < 1e-6);
+
+        let assigned = vec![("1".to_string(), 0.5), ("2".to_string(), 0.6)];
+        assert_eq!(storage.store_tag_assignments("work", &assigned).unwrap(), 2);
+        // Re-applying replaces the previous assignments
+        let assigned = vec![("2".to_string(), 0.6)];
+        assert_eq!(storage.store_tag_assignments("work", &assigned).unwrap(), 1);
+        assert_eq!(storage.get_tag_counts().unwrap()["work"], 1);
+        assert!(storage.get_tagged_tweet_ids("work").unwrap().contains("2"));
+
+        assert!(storage.delete_tag("work").unwrap());
+        assert!(!storage.delete_tag("work").unwrap());
+        assert!(storage.get_tagged_tweet_ids("work").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_notes_are_searchable() {
+        let mut storage = Storage::open_memory().unwrap();
+        let first = storage
+            .add_note("111", "tweet", "Source for the rust talk")
+            .unwrap();
+        storage
+            .add_note("222", "dm", "follow up about the job")
+            .unwrap();
+        storage
+            .add_note("111", "tweet", "quoted in newsletter")
+            .unwrap();
+
+        let hits = storage.search_notes("RUST talk", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].doc_id, "111");
+        // FTS5 syntax characters are matched as plain words
+        assert_eq!(storage.search_notes("job\"(", 10).unwrap().len(), 1);
+        assert!(storage.search_notes("  ", 10).unwrap().is_empty());
+
+        assert_eq!(storage.get_notes(Some("111"), None).unwrap().len(), 2);
+        assert_eq!(storage.get_notes(None, Some(1)).unwrap().len(), 1);
+        let by_doc = storage.get_notes_for_docs(&["111", "333"]).unwrap();
+        assert_eq!(by_doc[&("111".to_string(), "tweet".to_string())].len(), 2);
+
+        assert_eq!(storage.delete_note(first.id).unwrap().unwrap().id, first.id);
+        assert!(storage.delete_note(first.id).unwrap().is_none());
+        assert!(storage.search_notes("rust", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_collections() {
+        let mut storage = Storage::open_memory().unwrap();
+        assert!(storage.add_to_collection("reading", "1", "tweet").unwrap());
+        assert!(storage.add_to_collection("reading", "2", "like").unwrap());
+        assert!(!storage.add_to_collection("reading", "1", "tweet").unwrap());
+        storage.add_to_collection("talk", "1", "tweet").unwrap();
+
+        let collections = storage.get_collections().unwrap();
+        let names: Vec<&str> = collections.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["reading", "talk"]);
+        assert_eq!(collections[0].items, 2);
+
+        let items = storage.get_collection_items("reading").unwrap().unwrap();
+        let ids: Vec<&str> = items.iter().map(|i| i.doc_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert!(storage.get_collection_items("missing").unwrap().is_none());
+
+        assert_eq!(storage.remove_from_collection("reading", "2").unwrap(), 1);
+        // An emptied collection still exists until deleted
+        assert_eq!(storage.get_collections().unwrap()[0].items, 1);
+        assert!(storage.delete_collection("talk").unwrap());
+        assert!(!storage.delete_collection("talk").unwrap());
+        assert_eq!(storage.get_collections().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_hidden_documents() {
+        let storage = Storage::open_memory().unwrap();
+        assert!(storage.hide_document("1", "tweet").unwrap());
+        assert!(storage.hide_document("1", "like").unwrap());
+        assert!(!storage.hide_document("1", "tweet").unwrap());
+        storage.hide_document("7", "dm").unwrap();
+
+        let hidden = storage.get_hidden_ids().unwrap();
+        assert!(hidden["tweet"].contains("1"));
+        assert!(hidden["dm"].contains("7"));
+        assert_eq!(storage.get_hidden_documents().unwrap().len(), 3);
+
+        assert_eq!(storage.unhide_document("1", Some("like")).unwrap(), 1);
+        assert_eq!(storage.unhide_document("1", None).unwrap(), 1);
+        assert_eq!(storage.unhide_document("1", None).unwrap(), 0);
+        assert_eq!(storage.get_hidden_documents().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_pins() {
+        let storage = Storage::open_memory().unwrap();
+        assert!(storage.add_pin("1", "tweet", None).unwrap());
+        assert!(storage.add_pin("1", "tweet", Some("rust async")).unwrap());
+        assert!(!storage.add_pin("1", "tweet", None).unwrap());
+        storage.add_pin("2", "like", Some("rust async")).unwrap();
+
+        let pins = storage.get_pins().unwrap();
+        assert_eq!(pins.len(), 3);
+        assert!(pins[0].query.is_none());
+        assert_eq!(pins[1].query.as_deref(), Some("rust async"));
+
+        assert_eq!(storage.remove_pin("1", Some("rust async")).unwrap(), 1);
+        assert_eq!(storage.remove_pin("1", None).unwrap(), 1);
+        assert_eq!(storage.remove_pin("1", None).unwrap(), 0);
+        assert_eq!(storage.get_pins().unwrap().len(), 1);
+    }
+
+    fn alert(name: &str, query: &str, types: &[&str]) -> Alert {
+        Alert {
+            name: name.to_string(),
+            query: query.to_string(),
+            types: types.iter().map(ToString::to_string).collect(),
+            mode: "lexical".to_string(),
+            sort: "relevance".to_string(),
+            filters: Vec::new(),
+            created_at: Utc::now(),
+            last_run_at: None,
+        }
+    }
+
+    #[test]
+    fn test_alerts() {
+        let mut storage = Storage::open_memory().unwrap();
+        assert!(
+            storage
+                .add_alert(&alert("jobs", "rust AND hiring", &[]))
+                .unwrap()
+        );
+        let saved = Alert {
+            mode: "hybrid".to_string(),
+            sort: "date".to_string(),
+            filters: vec!["--since".to_string(), "last month".to_string()],
+            ..alert("bob", "deploy", &["dm"])
+        };
+        assert!(storage.add_alert(&saved).unwrap());
+        assert!(!storage.add_alert(&alert("jobs", "other", &[])).unwrap());
+
+        let alerts = storage.get_alerts().unwrap();
+        assert_eq!(alerts[0].name, "bob");
+        assert_eq!(alerts[0].types, ["dm"]);
+        assert_eq!(
+            (alerts[0].mode.as_str(), alerts[0].sort.as_str()),
+            ("hybrid", "date")
+        );
+        assert_eq!(alerts[0].filters, saved.filters);
+        assert!(alerts[1].types.is_empty() && alerts[1].last_run_at.is_none());
+        assert!(alerts[1].filters.is_empty());
+
+        let matches = vec![
+            ("tweet".to_string(), "1".to_string()),
+            ("like".to_string(), "1".to_string()),
+        ];
+        storage.record_alert_run("jobs", &matches).unwrap();
+        storage.record_alert_run("jobs", &matches[..1]).unwrap();
+        let seen = storage.get_alert_matches("jobs").unwrap();
+        assert_eq!(seen.len(), 1);
+        assert!(seen.contains(&("tweet".to_string(), "1".to_string())));
+        assert!(storage.get_alerts().unwrap()[1].last_run_at.is_some());
+
+        assert!(storage.remove_alert("jobs").unwrap());
+        assert!(!storage.remove_alert("jobs").unwrap());
+        assert!(storage.get_alert_matches("jobs").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let storage = Storage::open_memory().unwrap();
+        storage
+            .record_audit("search", "read_dms", "query \"lunch\"", 3)
+            .unwrap();
+        storage
+            .record_audit("export", "export", "dms to stdout", 40)
+            .unwrap();
+
+        let entries = storage.get_audit_entries(None, None, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "export");
+        assert_eq!(entries[1].count, 3);
+
+        let searches = storage.get_audit_entries(None, Some("search"), 10).unwrap();
+        assert_eq!(searches.len(), 1);
+        let future = Utc::now() + chrono::Duration::days(1);
+        assert!(
+            storage
+                .get_audit_entries(Some(future), None, 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_database_health_checks_pass() {
         let storage = Storage::open_memory().unwrap();
@@ -3728,6 +8743,257 @@ mod tests {
         assert_eq!(schema.status, CheckStatus::Pass);
     }
 
+    #[test]
+    fn test_invalid_dates_found_checked_and_backfilled() {
+        let mut storage = Storage::open_memory().unwrap();
+        storage
+            .store_tweets(&[
+                create_test_tweet("1", "fine"),
+                create_test_tweet("2", "epoch"),
+                create_test_tweet("3", "legacy"),
+            ])
+            .unwrap();
+        storage
+            .store_dm_conversations(&[DmConversation {
+                conversation_id: "test_conv".to_string(),
+                messages: vec![create_test_dm("d1", "hi")],
+            }])
+            .unwrap();
+        storage
+            .conn
+            .execute_batch(
+                "UPDATE tweets SET created_at = '1970-01-01T00:00:00+00:00' WHERE id = '2';
+                 UPDATE tweets SET created_at = 'Wed Oct 10 20:19:24 +0000 2018' WHERE id = '3';
+                 UPDATE direct_messages SET created_at = '' WHERE id = 'd1';",
+            )
+            .unwrap();
+
+        let invalid = storage.find_invalid_dates().unwrap();
+        assert_eq!(invalid.len(), 3);
+        assert!(invalid.tweet_ids.contains("2") && invalid.tweet_ids.contains("3"));
+        assert!(invalid.dm_ids.contains("d1"));
+        let check = storage.check_invalid_dates();
+        assert_eq!(check.status, CheckStatus::Warning);
+        assert_eq!(check.message, "3 rows");
+        // Legacy rows still read back with their real date
+        let legacy = storage.get_tweet("3").unwrap().unwrap();
+        assert_eq!(legacy.created_at.to_rfc3339(), "2018-10-10T20:19:24+00:00");
+
+        let date = DateTime::parse_from_rfc3339("2018-10-10T20:19:24Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let updated = storage
+            .backfill_dates(&[("2", date), ("3", date)], &[("d1", date)])
+            .unwrap();
+        assert_eq!(updated, 3);
+        assert!(storage.find_invalid_dates().unwrap().is_empty());
+        assert_eq!(storage.check_invalid_dates().status, CheckStatus::Pass);
+        let summary = storage.get_dm_conversation_summaries(None).unwrap();
+        assert_eq!(summary[0].first_message_at, Some(date));
+    }
+
+    #[test]
+    fn test_table_sizes_fold_indexes_and_fts_shadow_tables() {
+        let storage = Storage::open_memory().unwrap();
+        let sizes = storage.table_sizes().unwrap();
+        let names: Vec<&str> = sizes.iter().map(|(name, _)| name.as_str()).collect();
+
+        assert!(names.contains(&"tweets"));
+        assert!(names.contains(&"fts_tweets"));
+        assert!(!names.iter().any(|name| name.starts_with("fts_tweets_")));
+        assert!(!names.iter().any(|name| name.starts_with("idx_")));
+        assert!(sizes.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert_eq!(storage.free_bytes().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_clear_fts_tables_then_rebuild() {
+        let mut storage = Storage::open_memory().unwrap();
+        storage
+            .store_tweets(&[create_test_tweet("1", "hello")])
+            .unwrap();
+        let fts_rows = |storage: &Storage| storage.table_row_count("fts_tweets").unwrap();
+        assert_eq!(fts_rows(&storage), 1);
+
+        storage.clear_fts_tables().unwrap();
+        storage.vacuum().unwrap();
+        assert_eq!(fts_rows(&storage), 0);
+        assert_eq!(storage.table_row_count("tweets").unwrap(), 1);
+
+        storage.rebuild_fts_tables().unwrap();
+        assert_eq!(fts_rows(&storage), 1);
+    }
+
+    #[test]
+    fn test_lite_mode_skips_tweet_fts() {
+        let mut storage = Storage::open_memory().unwrap();
+        assert!(!storage.is_lite_mode());
+        storage.set_lite_mode(true).unwrap();
+        assert!(storage.is_lite_mode());
+
+        storage
+            .store_tweets(&[create_test_tweet("1", "hello")])
+            .unwrap();
+        assert_eq!(storage.table_row_count("tweets").unwrap(), 1);
+        assert_eq!(storage.table_row_count("fts_tweets").unwrap(), 0);
+
+        let checks = storage.database_health_checks();
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Pass));
+        assert!(checks.iter().any(|c| c.name == "Index mode"));
+    }
+
+    #[test]
+    fn test_repeated_ids_in_batch_keep_one_fts_row() {
+        let mut storage = Storage::open_memory().unwrap();
+        storage
+            .store_tweets(&[
+                create_test_tweet("1", "first copy"),
+                create_test_tweet("1", "second copy"),
+            ])
+            .unwrap();
+        storage
+            .store_likes(&[
+                create_test_like("2", Some("liked")),
+                create_test_like("2", Some("liked")),
+            ])
+            .unwrap();
+        assert_eq!(storage.table_row_count("fts_tweets").unwrap(), 1);
+        assert_eq!(storage.table_row_count("fts_likes").unwrap(), 1);
+        let text: String = storage
+            .connection()
+            .query_row("SELECT full_text FROM fts_tweets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(text, "second copy");
+
+        storage
+            .connection()
+            .execute(
+                "INSERT INTO fts_tweets (tweet_id, full_text) VALUES ('1', 'stale copy')",
+                [],
+            )
+            .unwrap();
+        let checks = storage.database_health_checks();
+        let duplicates = checks
+            .iter()
+            .find(|c| c.name == "FTS duplicate rows (tweets)")
+            .expect("duplicate check missing");
+        assert_eq!(duplicates.status, CheckStatus::Warning);
+        assert_eq!(duplicates.message, "1 rows");
+    }
+
+    #[test]
+    fn test_relink_self_threads_matches_rounded_ids() {
+        let mut storage = Storage::open_memory().unwrap();
+        let mut parent = create_test_tweet("1234567890123456789", "thread start");
+        parent.created_at = Utc::now() - Duration::minutes(5);
+        let mut reply = create_test_tweet("1234567890123457000", "thread continues");
+        reply.in_reply_to_status_id = Some("1234567890123456800".to_string());
+        reply.in_reply_to_screen_name = Some("TestUser".to_string());
+        let mut other = create_test_tweet("1234567890123458000", "reply to someone else");
+        other.in_reply_to_status_id = Some("1111111111111111111".to_string());
+        other.in_reply_to_screen_name = Some("someone".to_string());
+        storage.store_tweets(&[parent, reply, other]).unwrap();
+
+        let stats = storage.relink_self_threads("@testuser").unwrap();
+        assert_eq!(stats.broken, 1);
+        assert_eq!(stats.relinked, 1);
+
+        let thread = storage.get_tweet_thread("1234567890123457000").unwrap();
+        let ids: Vec<&str> = thread.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["1234567890123456789", "1234567890123457000"]);
+        let untouched = storage.get_tweet("1234567890123458000").unwrap().unwrap();
+        assert_eq!(
+            untouched.in_reply_to_status_id.as_deref(),
+            Some("1111111111111111111")
+        );
+
+        let again = storage.relink_self_threads("testuser").unwrap();
+        assert_eq!(again.broken, 0);
+    }
+
+    #[test]
+    fn test_merge_duplicate_dm_conversations() {
+        let mut storage = Storage::open_memory().unwrap();
+        let start = Utc::now() - Duration::days(30);
+        let conversation = |id: &str, messages: &[(&str, &str, i64)]| DmConversation {
+            conversation_id: id.to_string(),
+            messages: messages
+                .iter()
+                .map(|(msg_id, sender, day)| {
+                    let mut dm = create_test_dm(msg_id, "hi");
+                    dm.conversation_id = id.to_string();
+                    if *sender == "user2" {
+                        dm.sender_id = "user2".to_string();
+                        dm.recipient_id = "user1".to_string();
+                    }
+                    dm.created_at = start + Duration::days(*day);
+                    dm
+                })
+                .collect(),
+        };
+        storage
+            .store_dm_conversations(&[
+                conversation("user1-user2", &[("1", "user1", 0), ("3", "user1", 2)]),
+                conversation("user2-user1", &[("2", "user2", 1), ("4", "user2", 3)]),
+                // Same pair, but months apart from the others: not a duplicate
+                conversation("user1-user2-old", &[("5", "user1", -90)]),
+            ])
+            .unwrap();
+
+        let groups = storage.duplicate_dm_conversations().unwrap();
+        assert_eq!(
+            groups,
+            vec![vec!["user1-user2".to_string(), "user2-user1".to_string()]]
+        );
+        let check = storage.check_duplicate_dm_conversations();
+        assert_eq!(check.status, CheckStatus::Warning);
+
+        assert_eq!(storage.merge_dm_conversations(&groups).unwrap(), 1);
+        let messages = storage.get_conversation_messages("user1-user2").unwrap();
+        assert_eq!(messages.len(), 4);
+        assert!(
+            storage
+                .get_conversation_messages("user2-user1")
+                .unwrap()
+                .is_empty()
+        );
+        assert!(storage.duplicate_dm_conversations().unwrap().is_empty());
+        assert_eq!(storage.table_row_count("dm_conversations").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_first_seen_keeps_earliest_archive() {
+        let mut storage = Storage::open_memory().unwrap();
+        let older = Utc::now() - Duration::days(365);
+        let newer = Utc::now() - Duration::days(30);
+
+        storage
+            .record_first_seen("tweet", ["1", "2"], newer)
+            .unwrap();
+        storage.record_first_seen("tweet", ["1"], older).unwrap();
+        storage.record_first_seen("dm", ["9"], newer).unwrap();
+
+        let between = older + Duration::days(1);
+        let earliest = storage.earliest_first_seen().unwrap();
+        assert_eq!(earliest.unwrap().timestamp(), older.timestamp());
+        let snapshot = storage.first_seen_snapshot(between).unwrap();
+        assert_eq!(snapshot.unwrap().timestamp(), older.timestamp());
+        let unknown = storage.get_ids_first_seen_after(between).unwrap();
+        assert_eq!(unknown["tweet"], HashSet::from(["2".to_string()]));
+        assert_eq!(unknown["dm"], HashSet::from(["9".to_string()]));
+
+        let before = storage
+            .first_seen_snapshot(older - Duration::days(1))
+            .unwrap();
+        assert!(before.is_none());
+        assert!(
+            storage
+                .get_ids_first_seen_after(Utc::now())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_database_health_orphaned_fts() {
         let storage = Storage::open_memory().unwrap();
@@ -0,0 +1,411 @@
+//! Thread and conversation summaries (`xf summarize`).
+//!
+//! A tweet thread or DM conversation is flattened into a numbered
+//! transcript. With an LLM endpoint configured the model writes the summary;
+//! otherwise an extractive summary picks the most representative messages
+//! by term weight, so the command always works offline.
+
+use crate::ask::cited_numbers;
+use crate::canonicalize::is_stopword;
+use crate::llm::ChatMessage;
+use crate::model::{DirectMessage, Tweet};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+/// Most key points reported by either method.
+pub const MAX_KEY_POINTS: usize = 5;
+
+/// Number of frequent terms named in an extractive summary.
+const SUMMARY_TERMS: usize = 5;
+
+/// What was summarized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    Thread,
+    Conversation,
+}
+
+/// How the summary was produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryMethod {
+    Llm,
+    Extractive,
+}
+
+/// One message in a thread or conversation, oldest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptEntry {
+    pub id: String,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// A key point tied to the message it came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyPoint {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// Summary of a thread or conversation.
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub source: SourceKind,
+    pub id: String,
+    pub method: SummaryMethod,
+    pub message_count: usize,
+    pub first_at: Option<DateTime<Utc>>,
+    pub last_at: Option<DateTime<Utc>>,
+    pub summary: String,
+    pub key_points: Vec<KeyPoint>,
+}
+
+impl Summary {
+    /// Assemble a summary for `transcript`.
+    #[must_use]
+    pub fn new(
+        source: SourceKind,
+        id: &str,
+        method: SummaryMethod,
+        transcript: &[TranscriptEntry],
+        (summary, key_points): (String, Vec<KeyPoint>),
+    ) -> Self {
+        Self {
+            source,
+            id: id.to_string(),
+            method,
+            message_count: transcript.len(),
+            first_at: transcript.first().map(|e| e.created_at),
+            last_at: transcript.last().map(|e| e.created_at),
+            summary,
+            key_points,
+        }
+    }
+}
+
+/// Transcript of a tweet thread (all authored by the archive owner).
+#[must_use]
+pub fn thread_transcript(tweets: &[Tweet]) -> Vec<TranscriptEntry> {
+    let mut entries: Vec<TranscriptEntry> = tweets
+        .iter()
+        .map(|tweet| TranscriptEntry {
+            id: tweet.id.clone(),
+            author: "me".to_string(),
+            created_at: tweet.created_at,
+            text: flatten(&tweet.full_text),
+        })
+        .collect();
+    entries.sort_by_key(|e| e.created_at);
+    entries
+}
+
+/// Transcript of a DM conversation; the archive owner is labelled `me`.
+#[must_use]
+pub fn conversation_transcript(
+    messages: &[DirectMessage],
+    account_id: Option<&str>,
+) -> Vec<TranscriptEntry> {
+    let mut entries: Vec<TranscriptEntry> = messages
+        .iter()
+        .filter(|dm| !dm.text.trim().is_empty())
+        .map(|dm| TranscriptEntry {
+            id: dm.id.clone(),
+            author: if Some(dm.sender_id.as_str()) == account_id {
+                "me".to_string()
+            } else {
+                dm.sender_id.clone()
+            },
+            created_at: dm.created_at,
+            text: flatten(&dm.text),
+        })
+        .collect();
+    entries.sort_by_key(|e| e.created_at);
+    entries
+}
+
+/// Build the chat messages asking the model for a summary.
+///
+/// Messages are numbered from 1; the transcript is cut at `max_chars`.
+#[must_use]
+pub fn build_messages(
+    transcript: &[TranscriptEntry],
+    source: SourceKind,
+    max_chars: usize,
+) -> Vec<ChatMessage> {
+    let what = match source {
+        SourceKind::Thread => "a thread of the user's own tweets",
+        SourceKind::Conversation => "a direct message conversation",
+    };
+    let system = format!(
+        "You summarize {what} from the user's X archive. Reply in exactly this format:\n\
+         SUMMARY: <two or three sentences>\n\
+         KEY POINTS:\n\
+         - [n] <point>\n\
+         Give at most {MAX_KEY_POINTS} key points, each citing the number of the message it comes from."
+    );
+
+    let mut body = String::new();
+    for (i, entry) in transcript.iter().enumerate() {
+        let mut line = String::new();
+        let _ = writeln!(
+            line,
+            "[{}] {} {}: {}",
+            i + 1,
+            entry.created_at.format("%Y-%m-%d %H:%M"),
+            entry.author,
+            entry.text
+        );
+        if i > 0 && body.len() + line.len() > max_chars {
+            body.push_str("(transcript truncated)\n");
+            break;
+        }
+        body.push_str(&line);
+    }
+
+    vec![ChatMessage::system(system), ChatMessage::user(body)]
+}
+
+/// Split a model reply into the summary and key points.
+///
+/// Replies that ignore the requested format become the summary verbatim.
+#[must_use]
+pub fn parse_llm_reply(reply: &str, transcript: &[TranscriptEntry]) -> (String, Vec<KeyPoint>) {
+    let mut summary_lines = Vec::new();
+    let mut key_points = Vec::new();
+    let mut in_points = false;
+    let mut saw_format = false;
+
+    for line in reply.lines() {
+        let trimmed = line.trim();
+        let upper = trimmed.to_uppercase();
+        if let Some(rest) = upper.strip_prefix("SUMMARY:") {
+            saw_format = true;
+            let offset = trimmed.len() - rest.len();
+            summary_lines.push(trimmed[offset..].trim().to_string());
+        } else if upper.starts_with("KEY POINTS") {
+            saw_format = true;
+            in_points = true;
+        } else if in_points {
+            let Some(point) = trimmed
+                .strip_prefix('-')
+                .or_else(|| trimmed.strip_prefix('*'))
+            else {
+                continue;
+            };
+            let entry = cited_numbers(point)
+                .first()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| transcript.get(i));
+            if let Some(entry) = entry {
+                key_points.push(KeyPoint {
+                    id: entry.id.clone(),
+                    created_at: entry.created_at,
+                    text: strip_citations(point),
+                });
+            }
+        } else if !trimmed.is_empty() {
+            summary_lines.push(trimmed.to_string());
+        }
+    }
+
+    if !saw_format {
+        return (reply.trim().to_string(), Vec::new());
+    }
+    key_points.truncate(MAX_KEY_POINTS);
+    (summary_lines.join(" ").trim().to_string(), key_points)
+}
+
+/// Summarize without a model: the highest-weighted messages become key points.
+///
+/// Messages are weighted by how often their (non-stopword) terms occur across
+/// the whole transcript, normalized by message length.
+#[must_use]
+pub fn extractive_summary(transcript: &[TranscriptEntry]) -> (String, Vec<KeyPoint>) {
+    let terms_per_entry: Vec<HashSet<String>> =
+        transcript.iter().map(|e| content_terms(&e.text)).collect();
+
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    for terms in &terms_per_entry {
+        for term in terms {
+            *frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut scored: Vec<(usize, f64)> = terms_per_entry
+        .iter()
+        .enumerate()
+        .filter(|(_, terms)| !terms.is_empty())
+        .map(|(i, terms)| {
+            let weight: usize = terms.iter().map(|t| frequency[t.as_str()]).sum();
+            (i, weight as f64 / (terms.len() as f64).sqrt())
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored.truncate(MAX_KEY_POINTS);
+    scored.sort_by_key(|(i, _)| *i);
+
+    let key_points = scored
+        .into_iter()
+        .map(|(i, _)| KeyPoint {
+            id: transcript[i].id.clone(),
+            created_at: transcript[i].created_at,
+            text: transcript[i].text.clone(),
+        })
+        .collect();
+
+    let mut top_terms: Vec<(&str, usize)> = frequency
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .collect();
+    top_terms.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let top_terms: Vec<&str> = top_terms
+        .into_iter()
+        .take(SUMMARY_TERMS)
+        .map(|(term, _)| term)
+        .collect();
+
+    let mut summary = match (transcript.first(), transcript.last()) {
+        (Some(first), Some(last)) => format!(
+            "{} messages from {} to {}.",
+            transcript.len(),
+            first.created_at.format("%Y-%m-%d"),
+            last.created_at.format("%Y-%m-%d")
+        ),
+        _ => "No messages.".to_string(),
+    };
+    if !top_terms.is_empty() {
+        let _ = write!(summary, " Most discussed: {}.", top_terms.join(", "));
+    }
+
+    (summary, key_points)
+}
+
+fn content_terms(text: &str) -> HashSet<String> {
+    text.split_whitespace()
+        .filter(|word| !word.contains("://") && !word.starts_with('@'))
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| word.chars().count() >= 3 && !is_stopword(word))
+        .collect()
+}
+
+fn strip_citations(point: &str) -> String {
+    let mut text = String::with_capacity(point.len());
+    let mut rest = point;
+    while let Some(start) = rest.find('[') {
+        let Some(len) = rest[start..].find(']') else {
+            break;
+        };
+        let inner = &rest[start + 1..start + len];
+        text.push_str(&rest[..start]);
+        if !inner
+            .split(',')
+            .all(|part| part.trim().parse::<usize>().is_ok())
+        {
+            text.push_str(&rest[start..=start + len]);
+        }
+        rest = &rest[start + len + 1..];
+    }
+    text.push_str(rest);
+    flatten(&text)
+}
+
+fn flatten(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn transcript(texts: &[&str]) -> Vec<TranscriptEntry> {
+        let base = Utc.with_ymd_and_hms(2022, 5, 1, 9, 0, 0).unwrap();
+        texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| TranscriptEntry {
+                id: format!("m{}", i + 1),
+                author: "me".to_string(),
+                created_at: base + Duration::hours(i64::try_from(i).unwrap()),
+                text: (*text).to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_llm_reply() {
+        let entries = transcript(&["plan the trip", "book flights", "hotel in Lisbon"]);
+        let reply = "SUMMARY: Planning a trip to Lisbon.\nKEY POINTS:\n- [2] Flights get booked first\n* Hotel is in Lisbon [3]\n- [9] out of range";
+        let (summary, points) = parse_llm_reply(reply, &entries);
+        assert_eq!(summary, "Planning a trip to Lisbon.");
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].id, "m2");
+        assert_eq!(points[0].text, "Flights get booked first");
+        assert_eq!(points[1].id, "m3");
+        assert_eq!(points[1].text, "Hotel is in Lisbon");
+
+        let (free_form, none) = parse_llm_reply("  Just a paragraph.  ", &entries);
+        assert_eq!(free_form, "Just a paragraph.");
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_extractive_summary_prefers_central_messages() {
+        let entries = transcript(&[
+            "ok",
+            "rust async runtimes are confusing",
+            "tokio is the async runtime most people use for rust",
+            "lunch?",
+            "async rust with tokio finally clicked",
+        ]);
+        let (summary, points) = extractive_summary(&entries);
+        assert!(summary.starts_with("5 messages from 2022-05-01 to 2022-05-01."));
+        assert!(summary.contains("Most discussed: async, rust, tokio"));
+
+        let ids: Vec<&str> = points.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["m2", "m3", "m4", "m5"]);
+        assert!(!ids.contains(&"m1"), "stopword-only messages are skipped");
+    }
+
+    #[test]
+    fn test_conversation_transcript_labels_owner() {
+        let base = Utc.with_ymd_and_hms(2022, 5, 1, 9, 0, 0).unwrap();
+        let dm = |id: &str, sender: &str, minutes: i64| DirectMessage {
+            id: id.to_string(),
+            conversation_id: "c1".to_string(),
+            sender_id: sender.to_string(),
+            recipient_id: "x".to_string(),
+            text: format!("message {id}"),
+            created_at: base + Duration::minutes(minutes),
+            urls: vec![],
+            media_urls: vec![],
+        };
+        let entries = conversation_transcript(&[dm("b", "42", 5), dm("a", "7", 0)], Some("42"));
+        assert_eq!(entries[0].id, "a");
+        assert_eq!(entries[0].author, "7");
+        assert_eq!(entries[1].author, "me");
+    }
+
+    #[test]
+    fn test_build_messages_truncates_transcript() {
+        let entries = transcript(&["first message", &"long ".repeat(100)]);
+        let messages = build_messages(&entries, SourceKind::Thread, 60);
+        assert!(messages[0].content.contains("thread"));
+        assert!(
+            messages[1]
+                .content
+                .starts_with("[1] 2022-05-01 09:00 me: first message")
+        );
+        assert!(messages[1].content.ends_with("(transcript truncated)\n"));
+    }
+}
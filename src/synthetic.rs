@@ -0,0 +1,399 @@
+//! Synthetic X archives for tests, fuzzing and `xf selftest`.
+//!
+//! [`SyntheticArchive`] renders a deterministic archive from a seed in the
+//! same JS-wrapped layout X exports, optionally with a [`Defect`] that
+//! mimics what shows up in the field: files cut off mid-download, records
+//! whose fields changed type, and single fields megabytes long.
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+
+/// Something wrong with a synthetic archive, applied to every data file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defect {
+    /// Each file is cut off partway through its JSON.
+    Truncated,
+    /// Every third tweet, like and DM has a missing or mistyped field that
+    /// the parser must skip; the rest are valid.
+    Malformed,
+    /// The first tweet, DM and Grok message carry a text of this many bytes.
+    HugeField(usize),
+}
+
+/// Record counts the parser should report for a [`SyntheticArchive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExpectedCounts {
+    pub tweets: usize,
+    pub likes: usize,
+    pub dm_messages: usize,
+    pub grok_messages: usize,
+    pub followers: usize,
+}
+
+/// A deterministic synthetic archive.
+///
+/// The same fields always render the same files, so failures found with a
+/// seed reproduce exactly.
+#[derive(Debug, Clone)]
+pub struct SyntheticArchive {
+    pub seed: u64,
+    pub tweets: usize,
+    pub likes: usize,
+    pub conversations: usize,
+    pub messages_per_conversation: usize,
+    pub grok_messages: usize,
+    pub followers: usize,
+    pub defect: Option<Defect>,
+}
+
+impl Default for SyntheticArchive {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            tweets: 50,
+            likes: 20,
+            conversations: 3,
+            messages_per_conversation: 4,
+            grok_messages: 6,
+            followers: 5,
+            defect: None,
+        }
+    }
+}
+
+/// Account ID of the archive owner.
+pub const OWNER_ID: &str = "999999999";
+/// Handle of the archive owner.
+pub const OWNER_HANDLE: &str = "synthetic_user";
+
+const WORDS: &[&str] = &[
+    "rust", "search", "archive", "index", "coffee", "weather", "thread", "release", "database",
+    "meeting", "tomorrow", "music", "garden", "travel", "compiler", "notebook", "café", "naïve",
+    "東京", "🚀", "résumé", "Zürich",
+];
+
+const HASHTAGS: &[&str] = &["rustlang", "programming", "coffee", "travel"];
+
+impl SyntheticArchive {
+    /// An archive of the default size generated from `seed`.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    /// This archive with `defect` applied.
+    #[must_use]
+    pub const fn with_defect(mut self, defect: Defect) -> Self {
+        self.defect = Some(defect);
+        self
+    }
+
+    /// Counts a correct parser reports, or `None` for a truncated archive,
+    /// whose files don't parse at all.
+    #[must_use]
+    pub fn expected(&self) -> Option<ExpectedCounts> {
+        let kept = |n: usize| match self.defect {
+            Some(Defect::Malformed) => n - n / 3,
+            _ => n,
+        };
+        match self.defect {
+            Some(Defect::Truncated) => None,
+            _ => Some(ExpectedCounts {
+                tweets: kept(self.tweets),
+                likes: kept(self.likes),
+                dm_messages: (0..self.conversations)
+                    .map(|_| kept(self.messages_per_conversation))
+                    .sum(),
+                grok_messages: self.grok_messages,
+                followers: self.followers,
+            }),
+        }
+    }
+
+    /// Render the archive as `(file name, content)` pairs for its `data`
+    /// directory.
+    #[must_use]
+    pub fn files(&self) -> Vec<(&'static str, String)> {
+        let mut rng = SplitMix64(self.seed);
+        let mut files = vec![
+            ("manifest.js", wrap("manifest", &Self::manifest())),
+            ("tweets.js", wrap("tweets", &self.tweets_json(&mut rng))),
+            ("like.js", wrap("like", &self.likes_json(&mut rng))),
+            (
+                "direct-messages.js",
+                wrap("direct_messages", &self.dms_json(&mut rng)),
+            ),
+            (
+                "grok-chat-item.js",
+                wrap("grok_chat_item", &self.grok_json(&mut rng)),
+            ),
+            ("follower.js", wrap("follower", &self.followers_json())),
+        ];
+
+        if self.defect == Some(Defect::Truncated) {
+            for (name, content) in &mut files {
+                if *name != "manifest.js" {
+                    truncate(content, &mut rng);
+                }
+            }
+        }
+        files
+    }
+
+    /// Write the archive under `dir` (creating `dir/data`) and return `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or a file cannot be written.
+    pub fn write(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        let data_dir = dir.join("data");
+        std::fs::create_dir_all(&data_dir)?;
+        for (name, content) in self.files() {
+            std::fs::write(data_dir.join(name), content)?;
+        }
+        Ok(dir.to_path_buf())
+    }
+
+    const fn is_malformed(&self, i: usize) -> bool {
+        matches!(self.defect, Some(Defect::Malformed)) && i % 3 == 2
+    }
+
+    fn huge_text(&self, i: usize) -> Option<String> {
+        match self.defect {
+            Some(Defect::HugeField(bytes)) if i == 0 => Some("x".repeat(bytes)),
+            _ => None,
+        }
+    }
+
+    fn manifest() -> Value {
+        json!({
+            "userInfo": {
+                "accountId": OWNER_ID,
+                "userName": OWNER_HANDLE,
+                "displayName": "Synthetic User"
+            },
+            "archiveInfo": {
+                "sizeBytes": "0",
+                "generationDate": "2025-01-01T00:00:00.000Z",
+                "isPartialArchive": false
+            }
+        })
+    }
+
+    fn tweets_json(&self, rng: &mut SplitMix64) -> Value {
+        let items = (0..self.tweets)
+            .map(|i| {
+                let id = (1_000_000_000_000_u64 + i as u64).to_string();
+                let created_at = date_at(rng, i)
+                    .format("%a %b %d %H:%M:%S %z %Y")
+                    .to_string();
+                let hashtag = HASHTAGS[rng.below(HASHTAGS.len())];
+                let text = self
+                    .huge_text(i)
+                    .unwrap_or_else(|| format!("{} #{hashtag}", sentence(rng, 12)));
+                let mut tweet = json!({
+                    "id": id,
+                    "id_str": id,
+                    "created_at": created_at,
+                    "full_text": text,
+                    "source": "<a href=\"https://mobile.x.com\" rel=\"nofollow\">X for iPhone</a>",
+                    "favorite_count": rng.below(500).to_string(),
+                    "retweet_count": rng.below(50).to_string(),
+                    "lang": "en",
+                    "entities": {
+                        "hashtags": [{ "text": hashtag }],
+                        "user_mentions": [],
+                        "urls": []
+                    }
+                });
+                if i > 0 && rng.below(5) == 0 {
+                    let parent = (1_000_000_000_000_u64 + rng.below(i) as u64).to_string();
+                    tweet["in_reply_to_status_id_str"] = json!(parent);
+                    tweet["in_reply_to_user_id_str"] = json!(OWNER_ID);
+                    tweet["in_reply_to_screen_name"] = json!(OWNER_HANDLE);
+                }
+                if self.is_malformed(i) {
+                    malform(&mut tweet, i, &["id_str", "created_at", "full_text"]);
+                    tweet["entities"] = json!("not an object");
+                }
+                json!({ "tweet": tweet })
+            })
+            .collect();
+        Value::Array(items)
+    }
+
+    fn likes_json(&self, rng: &mut SplitMix64) -> Value {
+        let items = (0..self.likes)
+            .map(|i| {
+                let mut like = json!({
+                    "tweetId": (2_000_000_000_000_u64 + i as u64).to_string(),
+                    "fullText": sentence(rng, 10),
+                    "expandedUrl": format!("https://x.com/i/web/status/{}", 2_000_000_000_000_u64 + i as u64)
+                });
+                if self.is_malformed(i) {
+                    like["tweetId"] = json!(i);
+                }
+                json!({ "like": like })
+            })
+            .collect();
+        Value::Array(items)
+    }
+
+    fn dms_json(&self, rng: &mut SplitMix64) -> Value {
+        let mut next_message = 0;
+        let items = (0..self.conversations)
+            .map(|c| {
+                let other = (100_000 + c).to_string();
+                let messages: Vec<Value> = (0..self.messages_per_conversation)
+                    .map(|i| {
+                        let from_owner = i % 2 == 0;
+                        let (sender, recipient) = if from_owner {
+                            (OWNER_ID, other.as_str())
+                        } else {
+                            (other.as_str(), OWNER_ID)
+                        };
+                        let text = self
+                            .huge_text(next_message)
+                            .unwrap_or_else(|| sentence(rng, 8));
+                        let mut message = json!({
+                            "id": (4_000_000_000_000_u64 + next_message as u64).to_string(),
+                            "senderId": sender,
+                            "recipientId": recipient,
+                            "text": text,
+                            "createdAt": iso(date_at(rng, next_message)),
+                            "mediaUrls": [],
+                            "urls": []
+                        });
+                        next_message += 1;
+                        if self.is_malformed(i) {
+                            malform(&mut message, i, &["id", "text", "createdAt"]);
+                        }
+                        json!({ "messageCreate": message })
+                    })
+                    .collect();
+                json!({
+                    "dmConversation": {
+                        "conversationId": format!("{OWNER_ID}-{other}"),
+                        "messages": messages
+                    }
+                })
+            })
+            .collect();
+        Value::Array(items)
+    }
+
+    fn grok_json(&self, rng: &mut SplitMix64) -> Value {
+        let items = (0..self.grok_messages)
+            .map(|i| {
+                let message = self.huge_text(i).unwrap_or_else(|| sentence(rng, 9));
+                json!({
+                    "grokChatItem": {
+                        "chatId": (5_000_000_000_000_u64 + (i / 2) as u64).to_string(),
+                        "message": message,
+                        "sender": if i % 2 == 0 { "user" } else { "grok" },
+                        "createdAt": iso(date_at(rng, i)),
+                        "grokMode": "default"
+                    }
+                })
+            })
+            .collect();
+        Value::Array(items)
+    }
+
+    fn followers_json(&self) -> Value {
+        let items = (0..self.followers)
+            .map(|i| {
+                let id = 300_000 + i;
+                json!({ "follower": {
+                    "accountId": id.to_string(),
+                    "userLink": format!("https://twitter.com/intent/user?user_id={id}")
+                }})
+            })
+            .collect();
+        Value::Array(items)
+    }
+}
+
+/// Break one of `fields`: drop it, or give it a number, object or garbage
+/// string instead of the string X writes.
+fn malform(record: &mut Value, i: usize, fields: &[&str]) {
+    let field = fields[i % fields.len()];
+    match (i / fields.len()) % 4 {
+        0 => {
+            if let Some(object) = record.as_object_mut() {
+                object.remove(field);
+            }
+        }
+        1 => record[field] = json!(i),
+        2 => record[field] = json!({ "unexpected": true }),
+        _ if field.contains("reated") => record[field] = json!("not a date"),
+        _ => record[field] = Value::Null,
+    }
+}
+
+fn wrap(name: &str, value: &Value) -> String {
+    let json = serde_json::to_string_pretty(value).unwrap_or_default();
+    format!("window.YTD.{name}.part0 = {json}")
+}
+
+/// Cut `content` at a char boundary between a quarter and three quarters
+/// of the way through, after the `=` so the prefix survives.
+fn truncate(content: &mut String, rng: &mut SplitMix64) {
+    let start = content
+        .find('=')
+        .map_or(0, |eq| eq + 2)
+        .max(content.len() / 4);
+    let span = (content.len() * 3 / 4).saturating_sub(start).max(1);
+    let mut cut = (start + rng.below(span)).min(content.len());
+    while !content.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    content.truncate(cut);
+}
+
+fn date_at(rng: &mut SplitMix64, i: usize) -> DateTime<Utc> {
+    let base = DateTime::from_timestamp(1_609_459_200, 0).unwrap_or_default(); // 2021-01-01
+    let minutes = i64::try_from(i)
+        .unwrap_or(i64::MAX / 1440)
+        .saturating_mul(1440)
+        + i64::try_from(rng.below(1440)).unwrap_or(0);
+    base + Duration::minutes(minutes)
+}
+
+fn iso(date: DateTime<Utc>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn sentence(rng: &mut SplitMix64, max_words: usize) -> String {
+    let words = 3 + rng.below(max_words.saturating_sub(2));
+    (0..words)
+        .map(|_| WORDS[rng.below(WORDS.len())])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `SplitMix64`: tiny, seedable and good enough for test data.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-ish value in `0..n` (`0` when `n` is zero).
+    #[allow(clippy::cast_possible_truncation)]
+    const fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+        (self.next() % n as u64) as usize
+    }
+}
@@ -0,0 +1,152 @@
+//! User-defined tag categories (`xf tag`).
+//!
+//! A tag is described by a few example texts or tweet IDs. Applying tags
+//! embeds the examples, averages them into a centroid, and assigns the tag
+//! to every tweet whose stored embedding is close enough to it. Assignments
+//! live in the `tags` table and filter searches via `--tag`.
+
+use crate::canonicalize::{StopwordMode, canonicalize_for_embedding_with};
+use crate::embedder::{Embedder, dot_product, l2_normalize};
+use crate::storage::Storage;
+use anyhow::Result;
+
+/// Default minimum cosine similarity between a tweet and a tag's centroid.
+pub const DEFAULT_TAG_THRESHOLD: f32 = 0.3;
+
+/// Normalize a tag name to lowercase; `None` unless it is non-empty and
+/// made of letters, digits, `-` and `_`.
+#[must_use]
+pub fn normalize_tag_name(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(name)
+}
+
+/// Embed a tag's examples with `embedder`, the model the stored tweet
+/// embeddings were built with.
+///
+/// An example that is the ID of a tweet in the archive stands for that
+/// tweet's text. Examples that canonicalize to nothing are skipped.
+///
+/// # Errors
+///
+/// Returns an error if a tweet lookup or embedding fails.
+pub fn example_embeddings(
+    storage: &Storage,
+    examples: &[String],
+    stopwords: StopwordMode,
+    embedder: &dyn Embedder,
+) -> Result<Vec<Vec<f32>>> {
+    let mut embeddings = Vec::with_capacity(examples.len());
+
+    for example in examples {
+        let tweet_text = if example.chars().all(|c| c.is_ascii_digit()) {
+            storage.get_tweet(example)?.map(|tweet| tweet.full_text)
+        } else {
+            None
+        };
+        let text = tweet_text.as_deref().unwrap_or(example);
+        let canonical = canonicalize_for_embedding_with(text, stopwords);
+        if canonical.is_empty() {
+            continue;
+        }
+        embeddings.push(embedder.embed(&canonical)?);
+    }
+
+    Ok(embeddings)
+}
+
+/// Average embeddings into a unit-length centroid (`None` if there are none).
+#[must_use]
+pub fn centroid(embeddings: &[Vec<f32>]) -> Option<Vec<f32>> {
+    let dimension = embeddings.first()?.len();
+    let mut sum = vec![0.0_f32; dimension];
+    for embedding in embeddings.iter().filter(|e| e.len() == dimension) {
+        for (total, value) in sum.iter_mut().zip(embedding) {
+            *total += value;
+        }
+    }
+    l2_normalize(&mut sum);
+    sum.iter().any(|v| *v != 0.0).then_some(sum)
+}
+
+/// Tweets whose embedding scores at least `threshold` against `centroid`,
+/// best first.
+#[must_use]
+pub fn classify(
+    tweets: &[(String, Vec<f32>)],
+    centroid: &[f32],
+    threshold: f32,
+) -> Vec<(String, f32)> {
+    let mut matches: Vec<(String, f32)> = tweets
+        .iter()
+        .filter(|(_, embedding)| embedding.len() == centroid.len())
+        .filter_map(|(id, embedding)| {
+            let score = dot_product(embedding, centroid);
+            (score >= threshold).then(|| (id.clone(), score))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_embedder::HashEmbedder;
+
+    fn embed(text: &str) -> Vec<f32> {
+        HashEmbedder::default()
+            .embed(&canonicalize_for_embedding_with(text, StopwordMode::Keep))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_normalize_tag_name() {
+        assert_eq!(normalize_tag_name(" Work "), Some("work".to_string()));
+        assert_eq!(
+            normalize_tag_name("side-project_2"),
+            Some("side-project_2".to_string())
+        );
+        assert_eq!(normalize_tag_name(""), None);
+        assert_eq!(normalize_tag_name("two words"), None);
+    }
+
+    #[test]
+    fn test_centroid_is_unit_length() {
+        let c = centroid(&[vec![1.0, 0.0], vec![0.0, 1.0]]).unwrap();
+        assert!((dot_product(&c, &c) - 1.0).abs() < 1e-6);
+        assert!((c[0] - c[1]).abs() < 1e-6);
+        assert!(centroid(&[]).is_none());
+    }
+
+    #[test]
+    fn test_classify_prefers_similar_tweets() {
+        let examples = [
+            embed("sprint planning meeting with the team"),
+            embed("quarterly planning meeting notes"),
+        ];
+        let c = centroid(&examples).unwrap();
+        let tweets = vec![
+            (
+                "1".to_string(),
+                embed("long planning meeting with the team today"),
+            ),
+            (
+                "2".to_string(),
+                embed("sourdough bread recipe for the weekend"),
+            ),
+        ];
+
+        let all = classify(&tweets, &c, -1.0);
+        assert_eq!(all[0].0, "1");
+        assert!(all[0].1 > all[1].1);
+
+        let tagged = classify(&tweets, &c, DEFAULT_TAG_THRESHOLD);
+        let ids: Vec<&str> = tagged.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["1"]);
+    }
+}
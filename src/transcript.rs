@@ -0,0 +1,243 @@
+//! Chat-style DM transcripts (`xf export dms --transcript`).
+//!
+//! Renders conversations as readable transcripts for printing or records:
+//! plain text in the style of a phone chat export, Markdown, or a
+//! standalone HTML page. Times are UTC and media is shown as a placeholder,
+//! or by file name once `--with-media` has copied it next to the transcript.
+
+use crate::model::DirectMessage;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::hash::BuildHasher;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Transcript layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum TranscriptStyle {
+    /// Plain text lines: `[2021-03-04 10:12] alice: hi`
+    Whatsapp,
+    /// Markdown, one section per conversation
+    Markdown,
+    /// Standalone HTML page
+    Html,
+}
+
+/// Render `messages` as a transcript, one section per conversation.
+///
+/// Senders are shown by their entry in `names`, or by account ID.
+/// Conversations are ordered by their first message, messages by time.
+#[must_use]
+pub fn render<S: BuildHasher>(
+    style: TranscriptStyle,
+    messages: &[DirectMessage],
+    names: &HashMap<String, String, S>,
+) -> String {
+    let conversations = group_conversations(messages);
+    let name = |id: &str| names.get(id).cloned().unwrap_or_else(|| id.to_string());
+
+    let mut out = String::new();
+    if style == TranscriptStyle::Html {
+        out.push_str(HTML_HEAD);
+    }
+    for (index, (conversation_id, messages)) in conversations.iter().enumerate() {
+        let mut participants: Vec<String> = messages
+            .iter()
+            .flat_map(|dm| [name(&dm.sender_id), name(&dm.recipient_id)])
+            .collect();
+        participants.sort();
+        participants.dedup();
+        let participants = participants.join(", ");
+
+        match style {
+            TranscriptStyle::Whatsapp => {
+                if conversations.len() > 1 {
+                    if index > 0 {
+                        out.push('\n');
+                    }
+                    let _ = writeln!(out, "== Conversation {conversation_id} ({participants}) ==");
+                }
+            }
+            TranscriptStyle::Markdown => {
+                if index > 0 {
+                    out.push('\n');
+                }
+                let _ = writeln!(out, "## Conversation {conversation_id}\n");
+                let _ = writeln!(
+                    out,
+                    "Participants: {participants}. {} messages, times in UTC.\n",
+                    messages.len()
+                );
+            }
+            TranscriptStyle::Html => {
+                let _ = writeln!(
+                    out,
+                    "<section>\n<h2>Conversation {}</h2>\n<p class=\"meta\">Participants: {}. {} messages, times in UTC.</p>",
+                    escape_html(conversation_id),
+                    escape_html(&participants),
+                    messages.len()
+                );
+            }
+        }
+
+        for dm in messages {
+            let time = dm.created_at.format(TIME_FORMAT);
+            let sender = name(&dm.sender_id);
+            match style {
+                TranscriptStyle::Whatsapp => {
+                    let _ = writeln!(out, "[{time}] {sender}: {}", dm.text);
+                    for url in &dm.media_urls {
+                        if url.contains("://") {
+                            let _ = writeln!(out, "[{time}] {sender}: <Media omitted>");
+                        } else {
+                            let _ = writeln!(out, "[{time}] {sender}: <attached: {url}>");
+                        }
+                    }
+                }
+                TranscriptStyle::Markdown => {
+                    let text = dm.text.trim_end().replace('\n', "  \n");
+                    let _ = writeln!(out, "**[{time}] {sender}:** {text}  ");
+                    for url in &dm.media_urls {
+                        let _ = writeln!(out, "*[media]({url})*  ");
+                    }
+                }
+                TranscriptStyle::Html => {
+                    let text = escape_html(dm.text.trim_end()).replace('\n', "<br>");
+                    let _ = write!(
+                        out,
+                        "<p><time>{time}</time> <strong>{}</strong>: {text}",
+                        escape_html(&sender)
+                    );
+                    for url in &dm.media_urls {
+                        let _ = write!(out, " <a href=\"{}\">[media]</a>", escape_html(url));
+                    }
+                    out.push_str("</p>\n");
+                }
+            }
+        }
+
+        if style == TranscriptStyle::Html {
+            out.push_str("</section>\n");
+        }
+    }
+    if style == TranscriptStyle::Html {
+        out.push_str("</body>\n</html>\n");
+    }
+    out
+}
+
+/// Messages grouped by conversation, each sorted by time, ordered by the
+/// conversation's first message.
+fn group_conversations(messages: &[DirectMessage]) -> Vec<(&str, Vec<&DirectMessage>)> {
+    let mut by_id: HashMap<&str, Vec<&DirectMessage>> = HashMap::new();
+    for dm in messages {
+        by_id.entry(&dm.conversation_id).or_default().push(dm);
+    }
+    let mut conversations: Vec<(&str, Vec<&DirectMessage>)> = by_id.into_iter().collect();
+    for (_, messages) in &mut conversations {
+        messages.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+    }
+    conversations.sort_by(|a, b| {
+        a.1[0]
+            .created_at
+            .cmp(&b.1[0].created_at)
+            .then_with(|| a.0.cmp(b.0))
+    });
+    conversations
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_HEAD: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Direct messages</title>
+<style>
+body { font-family: sans-serif; max-width: 48em; margin: 2em auto; line-height: 1.4; }
+time { color: #666; font-family: monospace; }
+.meta { color: #666; }
+</style>
+</head>
+<body>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn dm(id: &str, conversation: &str, sender: &str, minute: u32, text: &str) -> DirectMessage {
+        DirectMessage {
+            id: id.to_string(),
+            conversation_id: conversation.to_string(),
+            sender_id: sender.to_string(),
+            recipient_id: if sender == "1" { "2" } else { "1" }.to_string(),
+            text: text.to_string(),
+            created_at: Utc.with_ymd_and_hms(2021, 3, 4, 10, minute, 0).unwrap(),
+            urls: Vec::new(),
+            media_urls: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_whatsapp_style_orders_messages_and_names_senders() {
+        let mut photo = dm("3", "c1", "1", 15, "look");
+        photo
+            .media_urls
+            .push("https://example.com/a.jpg".to_string());
+        let messages = vec![
+            photo,
+            dm("1", "c1", "2", 12, "hi"),
+            dm("2", "c1", "1", 13, "hey"),
+        ];
+        let names = HashMap::from([("1".to_string(), "@me".to_string())]);
+
+        let out = render(TranscriptStyle::Whatsapp, &messages, &names);
+        assert_eq!(
+            out,
+            "[2021-03-04 10:12] 2: hi\n\
+             [2021-03-04 10:13] @me: hey\n\
+             [2021-03-04 10:15] @me: look\n\
+             [2021-03-04 10:15] @me: <Media omitted>\n"
+        );
+    }
+
+    #[test]
+    fn test_whatsapp_style_names_copied_media() {
+        let mut photo = dm("3", "c1", "1", 15, "look");
+        photo.media_urls.push("media/3.jpg".to_string());
+        let out = render(TranscriptStyle::Whatsapp, &[photo], &HashMap::new());
+        assert!(out.ends_with("[2021-03-04 10:15] 1: <attached: media/3.jpg>\n"));
+    }
+
+    #[test]
+    fn test_markdown_and_html_sections() {
+        let messages = vec![
+            dm("1", "c2", "2", 20, "later"),
+            dm("2", "c1", "2", 10, "a <b>"),
+        ];
+        let names = HashMap::new();
+
+        let markdown = render(TranscriptStyle::Markdown, &messages, &names);
+        let c1 = markdown.find("## Conversation c1").unwrap();
+        let c2 = markdown.find("## Conversation c2").unwrap();
+        assert!(c1 < c2);
+        assert!(markdown.contains("**[2021-03-04 10:10] 2:** a <b>  \n"));
+
+        let html = render(TranscriptStyle::Html, &messages, &names);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<strong>2</strong>: a &lt;b&gt;</p>"));
+        assert_eq!(html.matches("<section>").count(), 2);
+    }
+}
@@ -0,0 +1,619 @@
+//! Full-screen archive browser (`xf tui`).
+//!
+//! A search box over a result list, a preview of the selected result, and a
+//! detail view for the thread or DM conversation it belongs to. All reads go
+//! through [`App`], so searches behave like `xf search` and hidden documents
+//! stay hidden. [`Browser`] holds the screen state and reacts to keys; it
+//! draws to any ratatui backend, which keeps it testable without a terminal.
+
+use anyhow::Result;
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap};
+use std::collections::{HashMap, HashSet};
+
+use crate::accessible::count_of;
+use crate::api::{DocType, SearchRequest};
+use crate::app::{App, SearchMode};
+use crate::model::{ResultMeta, SearchResult, SearchResultType, Tweet};
+use crate::{DirectMessage, format_number};
+
+/// Most results one search loads into the list.
+const RESULT_LIMIT: usize = 200;
+
+/// Type filters, cycled with Tab.
+const FILTERS: [(&str, Option<DocType>); 6] = [
+    ("All", None),
+    ("Tweets", Some(DocType::Tweet)),
+    ("Likes", Some(DocType::Like)),
+    ("Bookmarks", Some(DocType::Bookmark)),
+    ("DMs", Some(DocType::DirectMessage)),
+    ("Grok", Some(DocType::GrokMessage)),
+];
+
+/// Browse the archive until the user quits.
+///
+/// Runs a search for `query` first when given.
+///
+/// # Errors
+///
+/// Returns an error if the terminal cannot be drawn to or read from.
+pub fn run(app: &App, query: Option<String>, mode: SearchMode) -> Result<()> {
+    let mut browser = Browser::new(mode);
+    if let Some(query) = query {
+        browser.query = query;
+        browser.search(app);
+    }
+    let mut terminal = ratatui::init();
+    let outcome = browser.event_loop(app, &mut terminal);
+    ratatui::restore();
+    outcome
+}
+
+/// Which pane keys go to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Search,
+    Results,
+    Detail,
+}
+
+/// What the event loop should do after a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Continue,
+    Quit,
+}
+
+/// An opened thread or conversation.
+struct Detail {
+    title: String,
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+}
+
+/// Screen state of the browser.
+pub struct Browser {
+    mode: SearchMode,
+    query: String,
+    filter: usize,
+    focus: Focus,
+    results: Vec<SearchResult>,
+    list: ListState,
+    detail: Option<Detail>,
+    status: String,
+    /// Rows of the result list at the last draw, for Page Up/Down
+    page_rows: usize,
+}
+
+impl Browser {
+    /// An empty browser searching in `mode`.
+    #[must_use]
+    pub fn new(mode: SearchMode) -> Self {
+        Self {
+            mode,
+            query: String::new(),
+            filter: 0,
+            focus: Focus::Search,
+            results: Vec::new(),
+            list: ListState::default(),
+            detail: None,
+            status: "Type a query and press Enter".to_string(),
+            page_rows: 10,
+        }
+    }
+
+    fn event_loop(&mut self, app: &App, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && self.handle_key(app, key) == Action::Quit {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// React to one key press.
+    pub fn handle_key(&mut self, app: &App, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        match key.code {
+            KeyCode::Tab => return self.cycle_filter(app, 1),
+            KeyCode::BackTab => return self.cycle_filter(app, FILTERS.len() - 1),
+            _ => {}
+        }
+        match self.focus {
+            Focus::Search => self.search_key(app, key),
+            Focus::Results => self.results_key(app, key),
+            Focus::Detail => self.detail_key(key),
+        }
+    }
+
+    fn search_key(&mut self, app: &App, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.query.push(c);
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+            }
+            KeyCode::Enter => {
+                self.search(app);
+                if !self.results.is_empty() {
+                    self.focus = Focus::Results;
+                }
+            }
+            KeyCode::Esc | KeyCode::Down if !self.results.is_empty() => {
+                self.focus = Focus::Results;
+            }
+            KeyCode::Esc => return Action::Quit,
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn results_key(&mut self, app: &App, key: KeyEvent) -> Action {
+        let last = self.results.len().saturating_sub(1);
+        let selected = self.list.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Char('/') | KeyCode::Esc => self.focus = Focus::Search,
+            KeyCode::Up | KeyCode::Char('k') => self.list.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down | KeyCode::Char('j') => self.list.select(Some((selected + 1).min(last))),
+            KeyCode::PageUp => self
+                .list
+                .select(Some(selected.saturating_sub(self.page_rows))),
+            KeyCode::PageDown => self
+                .list
+                .select(Some((selected + self.page_rows).min(last))),
+            KeyCode::Home | KeyCode::Char('g') => self.list.select(Some(0)),
+            KeyCode::End | KeyCode::Char('G') => self.list.select(Some(last)),
+            KeyCode::Enter => self.open(app),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn detail_key(&mut self, key: KeyEvent) -> Action {
+        let Some(detail) = &mut self.detail else {
+            self.focus = Focus::Results;
+            return Action::Continue;
+        };
+        let page = u16::try_from(self.page_rows).unwrap_or(u16::MAX);
+        let bottom = u16::try_from(detail.lines.len().saturating_sub(1)).unwrap_or(u16::MAX);
+        match key.code {
+            KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('q') => {
+                self.detail = None;
+                self.focus = Focus::Results;
+            }
+            KeyCode::Up | KeyCode::Char('k') => detail.scroll = detail.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => detail.scroll = (detail.scroll + 1).min(bottom),
+            KeyCode::PageUp => detail.scroll = detail.scroll.saturating_sub(page),
+            KeyCode::PageDown => detail.scroll = detail.scroll.saturating_add(page).min(bottom),
+            KeyCode::Home | KeyCode::Char('g') => detail.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => detail.scroll = bottom,
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn cycle_filter(&mut self, app: &App, step: usize) -> Action {
+        self.filter = (self.filter + step) % FILTERS.len();
+        if !self.query.trim().is_empty() {
+            self.search(app);
+        }
+        Action::Continue
+    }
+
+    /// Search for the query with the current type filter.
+    fn search(&mut self, app: &App) {
+        self.detail = None;
+        let query = self.query.trim();
+        if query.is_empty() {
+            self.results.clear();
+            self.list.select(None);
+            self.status = "Type a query and press Enter".to_string();
+            return;
+        }
+        let request = SearchRequest {
+            types: FILTERS[self.filter].1.into_iter().collect(),
+            limit: RESULT_LIMIT,
+            ..SearchRequest::new(query)
+        };
+        match app.search_visible(request, self.mode) {
+            Ok(results) => {
+                self.status = format!(
+                    "{} for \"{query}\" ({})",
+                    count_of(results.len() as u64, "result"),
+                    self.mode
+                );
+                self.results = results;
+                self.list.select((!self.results.is_empty()).then_some(0));
+            }
+            Err(err) => {
+                self.status = format!("Search failed: {err:#}");
+                self.results.clear();
+                self.list.select(None);
+            }
+        }
+    }
+
+    /// Open the thread or conversation of the selected result.
+    fn open(&mut self, app: &App) {
+        let Some(result) = self.list.selected().and_then(|i| self.results.get(i)) else {
+            return;
+        };
+        let opened = match (result.result_type, result.meta()) {
+            (SearchResultType::Tweet, _) => thread_detail(app, &result.id),
+            (SearchResultType::DirectMessage, Some(ResultMeta::DirectMessage(dm))) => {
+                conversation_detail(app, &dm.conversation_id, &result.id)
+            }
+            (result_type, _) => {
+                self.status = format!("Nothing to open for {result_type} results");
+                return;
+            }
+        };
+        match opened {
+            Ok(detail) => {
+                self.detail = Some(detail);
+                self.focus = Focus::Detail;
+            }
+            Err(err) => self.status = format!("Could not open: {err:#}"),
+        }
+    }
+
+    /// Draw the whole screen.
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [search_area, tabs_area, body, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, side_area] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(body);
+
+        let search = Paragraph::new(self.query.as_str()).block(pane(
+            format!(" Search ({}) ", self.mode),
+            self.focus == Focus::Search,
+        ));
+        frame.render_widget(search, search_area);
+        if self.focus == Focus::Search {
+            let width = u16::try_from(crate::display_width(&self.query)).unwrap_or(u16::MAX);
+            frame.set_cursor_position((
+                search_area.x.saturating_add(1).saturating_add(width),
+                search_area.y + 1,
+            ));
+        }
+
+        let tabs = Tabs::new(FILTERS.map(|(name, _)| name))
+            .select(self.filter)
+            .highlight_style(Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        frame.render_widget(tabs, tabs_area);
+
+        self.page_rows = usize::from(list_area.height.saturating_sub(2)).max(1);
+        let items: Vec<ListItem> = self.results.iter().map(result_item).collect();
+        let list = List::new(items)
+            .block(pane(" Results ".to_string(), self.focus == Focus::Results))
+            .highlight_style(
+                Style::new()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        self.draw_side(frame, side_area);
+
+        let help = match self.focus {
+            Focus::Search => "Enter search  Tab type  Esc results",
+            Focus::Results => "Enter open  / search  Tab type  ↑↓ move  q quit",
+            Focus::Detail => "↑↓ scroll  Esc back",
+        };
+        let status = Line::from(vec![
+            Span::raw(self.status.clone()),
+            Span::raw("  "),
+            Span::styled(help, Style::new().fg(Color::DarkGray)),
+        ]);
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn draw_side(&self, frame: &mut Frame, area: Rect) {
+        if let Some(detail) = &self.detail {
+            let paragraph = Paragraph::new(Text::from(detail.lines.clone()))
+                .block(pane(format!(" {} ", detail.title), true))
+                .wrap(Wrap { trim: false })
+                .scroll((detail.scroll, 0));
+            frame.render_widget(paragraph, area);
+            return;
+        }
+        let text = self
+            .list
+            .selected()
+            .and_then(|i| self.results.get(i))
+            .map(preview)
+            .unwrap_or_default();
+        let paragraph = Paragraph::new(text)
+            .block(pane(" Preview ".to_string(), false))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
+}
+
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    };
+    Block::new()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+}
+
+fn dim(text: String) -> Span<'static> {
+    Span::styled(text, Style::new().fg(Color::DarkGray))
+}
+
+/// One row of the result list: type, date and the text's first line.
+fn result_item(result: &SearchResult) -> ListItem<'static> {
+    let text = result.text.split_whitespace().collect::<Vec<_>>().join(" ");
+    ListItem::new(Line::from(vec![
+        Span::styled(
+            format!("{:<8} ", result.result_type.to_string()),
+            Style::new().fg(Color::Yellow),
+        ),
+        dim(format!("{} ", result.created_at.format("%Y-%m-%d"))),
+        Span::raw(text),
+    ]))
+}
+
+/// The selected result in full, with its metadata.
+fn preview(result: &SearchResult) -> Text<'static> {
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            result.result_type.to_string(),
+            Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!(
+            "  {}",
+            result.created_at.format("%Y-%m-%d %H:%M UTC")
+        )),
+    ])];
+    lines.push(Line::from(dim(format!("ID {}", result.id))));
+    match result.meta() {
+        Some(ResultMeta::Tweet(tweet)) => {
+            lines.push(Line::from(format!(
+                "♥ {}  ↻ {}",
+                format_number(tweet.favorite_count),
+                format_number(tweet.retweet_count)
+            )));
+            if let Some(to) = tweet.in_reply_to {
+                lines.push(Line::from(format!("Reply to @{to}")));
+            }
+            if let Some(source) = tweet.source {
+                lines.push(Line::from(dim(format!("via {source}"))));
+            }
+        }
+        Some(ResultMeta::Like(like)) => lines.extend(like.expanded_url.map(Line::from)),
+        Some(ResultMeta::Bookmark(bookmark)) => {
+            lines.extend(bookmark.expanded_url.map(Line::from));
+        }
+        Some(ResultMeta::DirectMessage(dm)) => lines.push(Line::from(format!(
+            "From {} to {}",
+            dm.sender_id, dm.recipient_id
+        ))),
+        Some(ResultMeta::GrokMessage(grok)) => {
+            lines.push(Line::from(format!("From {}", grok.sender)));
+        }
+        None => {}
+    }
+    lines.push(Line::default());
+    lines.extend(result.text.lines().map(|line| Line::from(line.to_string())));
+    Text::from(lines)
+}
+
+/// IDs of `doc_type` documents hidden with `xf hide`.
+fn hidden_of(app: &App, doc_type: &str) -> Result<HashSet<String>> {
+    let mut hidden: HashMap<String, HashSet<String>> = app.hidden_ids()?;
+    Ok(hidden.remove(doc_type).unwrap_or_default())
+}
+
+/// Header line of one entry in a detail view, highlighted when it is the
+/// result that was opened.
+fn entry_header(text: String, opened: bool) -> Line<'static> {
+    let style = if opened {
+        Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+    } else {
+        Style::new().fg(Color::DarkGray)
+    };
+    Line::from(Span::styled(text, style))
+}
+
+/// Lines of a detail view, scrolled so the opened entry is on top.
+fn detail(title: String, entries: Vec<(Line<'static>, &str, bool)>) -> Detail {
+    let mut lines = Vec::new();
+    let mut scroll = 0;
+    for (header, text, opened) in entries {
+        if opened {
+            scroll = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+        }
+        lines.push(header);
+        lines.extend(text.lines().map(|line| Line::from(line.to_string())));
+        lines.push(Line::default());
+    }
+    Detail {
+        title,
+        lines,
+        scroll,
+    }
+}
+
+fn thread_detail(app: &App, id: &str) -> Result<Detail> {
+    let hidden = hidden_of(app, "tweet")?;
+    let thread: Vec<Tweet> = app
+        .thread(id)?
+        .into_iter()
+        .filter(|tweet| !hidden.contains(&tweet.id))
+        .collect();
+    let title = format!("Thread, {}", count_of(thread.len() as u64, "tweet"));
+    let entries = thread
+        .iter()
+        .map(|tweet| {
+            let header = format!(
+                "{}  ♥ {}  ↻ {}",
+                tweet.created_at.format("%Y-%m-%d %H:%M"),
+                format_number(tweet.favorite_count),
+                format_number(tweet.retweet_count)
+            );
+            (
+                entry_header(header, tweet.id == id),
+                tweet.full_text.as_str(),
+                tweet.id == id,
+            )
+        })
+        .collect();
+    Ok(detail(title, entries))
+}
+
+fn conversation_detail(app: &App, conversation_id: &str, id: &str) -> Result<Detail> {
+    let hidden = hidden_of(app, "dm")?;
+    let messages: Vec<DirectMessage> = app
+        .conversation(conversation_id)?
+        .into_iter()
+        .filter(|message| !hidden.contains(&message.id))
+        .collect();
+    let title = format!(
+        "Conversation, {}",
+        count_of(messages.len() as u64, "message")
+    );
+    let entries = messages
+        .iter()
+        .map(|message| {
+            let header = format!(
+                "{}  {}",
+                message.created_at.format("%Y-%m-%d %H:%M"),
+                message.sender_id
+            );
+            (
+                entry_header(header, message.id == id),
+                message.text.as_str(),
+                message.id == id,
+            )
+        })
+        .collect();
+    Ok(detail(title, entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use tempfile::TempDir;
+
+    fn indexed_app(dir: &TempDir) -> App {
+        let data = dir.path().join("archive").join("data");
+        std::fs::create_dir_all(&data).unwrap();
+        std::fs::write(
+            data.join("manifest.js"),
+            r#"window.__THAR_CONFIG = {"userInfo": {"accountId": "1", "userName": "me"},
+                "archiveInfo": {"generationDate": "2024-01-01T00:00:00.000Z"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            data.join("tweets.js"),
+            r#"window.YTD.tweets.part0 = [
+                {"tweet": {"id_str": "7", "created_at": "Fri Jan 09 15:12:21 +0000 2026",
+                    "full_text": "hello from rust"}},
+                {"tweet": {"id_str": "8", "created_at": "Fri Jan 09 15:14:00 +0000 2026",
+                    "full_text": "more rust in the thread", "in_reply_to_status_id_str": "7"}}
+            ]"#,
+        )
+        .unwrap();
+        App::index(
+            dir.path().join("archive"),
+            dir.path().join("xf.db"),
+            dir.path().join("index"),
+            |_| {},
+        )
+        .unwrap()
+    }
+
+    fn press(browser: &mut Browser, app: &App, code: KeyCode) -> Action {
+        browser.handle_key(app, KeyEvent::from(code))
+    }
+
+    fn screen(browser: &mut Browser) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect()
+    }
+
+    #[test]
+    fn test_search_filter_and_open_a_thread() {
+        let dir = TempDir::new().unwrap();
+        let app = indexed_app(&dir);
+        let mut browser = Browser::new(SearchMode::Lexical);
+
+        for c in "rust".chars() {
+            press(&mut browser, &app, KeyCode::Char(c));
+        }
+        press(&mut browser, &app, KeyCode::Enter);
+        assert_eq!(browser.results.len(), 2);
+        assert_eq!(browser.focus, Focus::Results);
+        assert!(screen(&mut browser).contains("2 results for \"rust\""));
+
+        // No likes match; back to all types
+        press(&mut browser, &app, KeyCode::Tab);
+        press(&mut browser, &app, KeyCode::Tab);
+        assert!(browser.results.is_empty());
+        press(&mut browser, &app, KeyCode::BackTab);
+        press(&mut browser, &app, KeyCode::BackTab);
+        assert_eq!(browser.results.len(), 2);
+
+        press(&mut browser, &app, KeyCode::Down);
+        press(&mut browser, &app, KeyCode::Enter);
+        assert_eq!(browser.focus, Focus::Detail);
+        let shown = screen(&mut browser);
+        assert!(shown.contains("Thread, 2 tweets"));
+        assert!(shown.contains("hello from rust") && shown.contains("more rust in the thread"));
+
+        press(&mut browser, &app, KeyCode::Esc);
+        assert_eq!(browser.focus, Focus::Results);
+        assert!(screen(&mut browser).contains("Preview"));
+        assert_eq!(press(&mut browser, &app, KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn test_hidden_tweets_stay_out_of_results_and_threads() {
+        let dir = TempDir::new().unwrap();
+        let app = indexed_app(&dir);
+        crate::storage::Storage::open(dir.path().join("xf.db"))
+            .unwrap()
+            .hide_document("7", "tweet")
+            .unwrap();
+        let mut browser = Browser::new(SearchMode::Lexical);
+        browser.query = "rust".to_string();
+        browser.search(&app);
+        assert_eq!(browser.results.len(), 1);
+
+        browser.focus = Focus::Results;
+        press(&mut browser, &app, KeyCode::Enter);
+        assert!(screen(&mut browser).contains("Thread, 1 tweet "));
+    }
+}
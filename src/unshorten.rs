@@ -0,0 +1,155 @@
+//! Short link resolution (`xf enrich --unshorten`).
+//!
+//! Archives store most links as `t.co` URLs, and the expanded URL X
+//! recorded is sometimes missing or is itself another shortener's link.
+//! This module follows such links to their final destination. It is the
+//! only part of indexing-side enrichment that touches the network, and it
+//! runs only when asked to.
+
+use crate::model::TweetUrl;
+use anyhow::{Context, Result};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Hosts whose links only redirect elsewhere.
+pub const SHORTENER_HOSTS: &[&str] = &[
+    "t.co",
+    "bit.ly",
+    "buff.ly",
+    "dlvr.it",
+    "fb.me",
+    "goo.gl",
+    "ift.tt",
+    "lnkd.in",
+    "ow.ly",
+    "tinyurl.com",
+    "trib.al",
+];
+
+/// Most redirects followed for one link.
+const MAX_REDIRECTS: u32 = 10;
+
+/// Lowercased host of an `http(s)` URL, without `www.` or a port.
+#[must_use]
+pub fn url_host(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    let host = host.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+/// Whether `url` points at a known link shortener.
+#[must_use]
+pub fn is_short_url(url: &str) -> bool {
+    url_host(url).is_some_and(|host| SHORTENER_HOSTS.contains(&host.as_str()))
+}
+
+/// The short link to resolve for a stored URL entity, if any: the `t.co`
+/// URL when no expanded URL was recorded, or an expanded URL that is
+/// itself a short link.
+#[must_use]
+pub fn unresolved_link(url: &TweetUrl) -> Option<&str> {
+    let link = url
+        .expanded_url
+        .as_deref()
+        .filter(|expanded| !expanded.is_empty())
+        .unwrap_or(&url.url);
+    is_short_url(link).then_some(link)
+}
+
+/// Follows short links over HTTP, at most `rate` requests per second.
+pub struct Resolver {
+    agent: ureq::Agent,
+    interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Resolver {
+    #[must_use]
+    pub fn new(rate: f64, timeout: Duration) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(timeout)
+            .redirects(MAX_REDIRECTS)
+            .user_agent(concat!("xf/", env!("CARGO_PKG_VERSION")))
+            .build();
+        let interval = if rate > 0.0 {
+            Duration::from_secs_f64(1.0 / rate)
+        } else {
+            Duration::ZERO
+        };
+        Self {
+            agent,
+            interval,
+            last_request: None,
+        }
+    }
+
+    /// The URL `url` finally redirects to.
+    ///
+    /// Error statuses at the destination still count as resolved: the
+    /// link's target is known even if that page is gone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the link cannot be reached.
+    pub fn resolve(&mut self, url: &str) -> Result<String> {
+        if let Some(wait) = self
+            .last_request
+            .and_then(|last| self.interval.checked_sub(last.elapsed()))
+        {
+            thread::sleep(wait);
+        }
+        self.last_request = Some(Instant::now());
+
+        match self.agent.head(url).call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => {
+                Ok(response.get_url().to_string())
+            }
+            Err(err) => Err(err).with_context(|| format!("Failed to reach {url}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_host_strips_scheme_www_and_port() {
+        assert_eq!(url_host("https://t.co/abc").as_deref(), Some("t.co"));
+        assert_eq!(
+            url_host("http://WWW.Example.com:8080/a?b").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(url_host("ftp://t.co/abc"), None);
+        assert_eq!(url_host("https://"), None);
+    }
+
+    #[test]
+    fn unresolved_link_prefers_short_expanded_urls() {
+        let entity = |expanded: Option<&str>| TweetUrl {
+            url: "https://t.co/abc".to_string(),
+            expanded_url: expanded.map(String::from),
+            display_url: None,
+        };
+        assert_eq!(unresolved_link(&entity(None)), Some("https://t.co/abc"));
+        assert_eq!(unresolved_link(&entity(Some(""))), Some("https://t.co/abc"));
+        assert_eq!(
+            unresolved_link(&entity(Some("https://bit.ly/x"))),
+            Some("https://bit.ly/x")
+        );
+        assert_eq!(unresolved_link(&entity(Some("https://example.com/"))), None);
+    }
+
+    #[test]
+    fn is_short_url_matches_known_shorteners_only() {
+        assert!(is_short_url("https://t.co/xyz"));
+        assert!(is_short_url("http://bit.ly/abc"));
+        assert!(!is_short_url("https://example.com/t.co"));
+        assert!(!is_short_url("https://x.com/user/status/1"));
+    }
+}
@@ -3,14 +3,25 @@
 //! Provides in-memory vector search with SIMD-accelerated dot product.
 //! Vectors are loaded from `SQLite` at startup and searched using cosine
 //! similarity (which equals dot product for L2-normalized vectors).
+//!
+//! The CLI searches a memory-mapped sidecar file (`vector.idx`) instead, so
+//! a cold semantic search does not have to decode every embedding first.
+//! Large indexes also get an HNSW graph (`vector.hnsw`) so a search visits
+//! a few thousand records rather than every one.
 
 use crate::embedder::dot_product_simd;
+use crate::search::DocType;
 use crate::storage::Storage;
-use anyhow::{Result, ensure};
+use anyhow::{Context, Result, ensure};
 use fmmap::{MmapFile, MmapFileExt};
+use rayon::prelude::*;
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::Path;
 use std::str;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime};
+use tracing::{info, warn};
 
 #[allow(dead_code)]
 const VECTOR_INDEX_MAGIC: [u8; 4] = *b"XFVI";
@@ -69,6 +80,8 @@ fn encode_doc_type(doc_type: &str) -> Option<u8> {
         "like" => Some(1),
         "dm" => Some(2),
         "grok" => Some(3),
+        "bookmark" => Some(4),
+        "dm_conv" => Some(5),
         _ => None,
     }
 }
@@ -80,6 +93,8 @@ const fn decode_doc_type(value: u8) -> Option<&'static str> {
         1 => Some("like"),
         2 => Some("dm"),
         3 => Some("grok"),
+        4 => Some("bookmark"),
+        5 => Some("dm_conv"),
         _ => None,
     }
 }
@@ -93,12 +108,14 @@ fn intern_doc_type(doc_type: &str) -> &'static str {
         "like" => "like",
         "dm" => "dm",
         "grok" => "grok",
+        "bookmark" => "bookmark",
+        "dm_conv" => "dm_conv",
         _ => "tweet", // Default for unknown types (including "tweet")
     }
 }
 
 fn validate_doc_type(value: u8) -> Result<()> {
-    ensure!(value <= 3, "invalid doc_type encoding");
+    ensure!(value <= 5, "invalid doc_type encoding");
     Ok(())
 }
 
@@ -178,13 +195,13 @@ fn validate_vector_index_layout(bytes: &[u8]) -> Result<()> {
 
 #[derive(Debug, Clone, Copy)]
 struct DocTypeFilter {
-    allowed: [bool; 4],
+    allowed: [bool; 6],
 }
 
 impl DocTypeFilter {
     fn new(doc_types: Option<&[&str]>) -> Option<Self> {
         let doc_types = doc_types?;
-        let mut allowed = [false; 4];
+        let mut allowed = [false; 6];
         for doc_type in doc_types {
             if let Some(code) = encode_doc_type(doc_type) {
                 let idx = usize::from(code);
@@ -204,6 +221,7 @@ impl DocTypeFilter {
 
 fn dot_product_f16_simd(query: &[f32], embedding: &[u8]) -> Option<f32> {
     use half::f16;
+    use half::slice::HalfFloatSliceExt;
     use wide::f32x8;
 
     if embedding.len() != query.len().saturating_mul(2) {
@@ -216,12 +234,14 @@ fn dot_product_f16_simd(query: &[f32], embedding: &[u8]) -> Option<f32> {
     let mut sum = f32x8::ZERO;
     let mut idx = 0usize;
     while idx + 8 <= query.len() {
-        let mut emb = [0.0f32; 8];
-        for (lane, value) in emb.iter_mut().enumerate() {
+        // Converting eight lanes at once uses F16C where the CPU has it
+        let mut halves = [f16::ZERO; 8];
+        for (lane, half) in halves.iter_mut().enumerate() {
             let byte_idx = (idx + lane) * 2;
-            let arr = [embedding[byte_idx], embedding[byte_idx + 1]];
-            *value = f16::from_le_bytes(arr).to_f32();
+            *half = f16::from_le_bytes([embedding[byte_idx], embedding[byte_idx + 1]]);
         }
+        let mut emb = [0.0f32; 8];
+        halves.convert_to_f32_slice(&mut emb);
 
         let mut q_arr = [0.0f32; 8];
         q_arr.copy_from_slice(&query[idx..idx + 8]);
@@ -261,12 +281,24 @@ pub const VECTOR_INDEX_FILENAME: &str = "vector.idx";
 ///
 /// Records are sorted by (`doc_type`, `doc_id`) for deterministic ordering.
 ///
+/// With at least [`HNSW_MIN_RECORDS`] records, an HNSW graph over them is
+/// written next to it ([`VECTOR_GRAPH_FILENAME`]) so searches visit a few
+/// thousand records instead of all of them.
+///
 /// # Errors
 ///
 /// Returns an error if loading embeddings fails or if file I/O fails.
 pub fn write_vector_index(
     index_path: &std::path::Path,
     storage: &Storage,
+) -> Result<WriteVectorIndexStats> {
+    write_vector_index_with(index_path, storage, HNSW_MIN_RECORDS)
+}
+
+fn write_vector_index_with(
+    index_path: &std::path::Path,
+    storage: &Storage,
+    graph_min_records: usize,
 ) -> Result<WriteVectorIndexStats> {
     use std::fs::File;
     use std::io::{BufWriter, Write};
@@ -277,6 +309,7 @@ pub fn write_vector_index(
         return Ok(WriteVectorIndexStats {
             record_count: 0,
             file_size: 0,
+            graph_size: None,
         });
     }
 
@@ -384,11 +417,24 @@ pub fn write_vector_index(
     // Atomic rename
     std::fs::rename(&temp_path, &final_path)?;
 
+    // A graph left from an earlier build no longer matches the new file
+    let graph_path = index_path.join(VECTOR_GRAPH_FILENAME);
+    let graph_size = if embeddings.len() >= graph_min_records {
+        let vectors: Vec<&[u8]> = embeddings.iter().map(|e| e.2.as_slice()).collect();
+        Some(write_vector_graph(&graph_path, &vectors, file_size)?)
+    } else {
+        if graph_path.exists() {
+            std::fs::remove_file(&graph_path)?;
+        }
+        None
+    };
+
     #[allow(clippy::cast_possible_truncation)]
     Ok(WriteVectorIndexStats {
         // Safe: record_count comes from embeddings.len() which is already usize
         record_count: record_count as usize,
         file_size,
+        graph_size,
     })
 }
 
@@ -399,6 +445,8 @@ pub struct WriteVectorIndexStats {
     pub record_count: usize,
     /// Total file size in bytes.
     pub file_size: u64,
+    /// Size of the HNSW graph file, if one was built.
+    pub graph_size: Option<u64>,
 }
 
 /// Result of a vector search.
@@ -406,7 +454,7 @@ pub struct WriteVectorIndexStats {
 pub struct VectorSearchResult {
     /// Document ID.
     pub doc_id: String,
-    /// Document type (tweet, like, dm, grok).
+    /// Document type (tweet, like, bookmark, dm, grok).
     ///
     /// Uses `&'static str` to avoid allocations for the fixed set of types.
     pub doc_type: &'static str,
@@ -485,13 +533,32 @@ impl Ord for IndexHeapEntry {
     }
 }
 
+/// Records per shard for parallel search.
+const SHARD_RECORDS: usize = 4096;
+
+/// Below this many records, shards are scanned on the calling thread.
+const PARALLEL_THRESHOLD: usize = 10_000;
+
+/// Contiguous run of records sharing one doc type.
+#[derive(Debug, Clone)]
+struct VectorShard {
+    doc_type: u8,
+    records: std::ops::Range<usize>,
+}
+
 /// Mmap-backed vector index for fast similarity search without `SQLite`.
+///
+/// Records are split into shards by doc type (the file is sorted by type),
+/// and each type into chunks of [`SHARD_RECORDS`], so a type filter skips
+/// whole shards and large indexes are scanned on all cores.
 pub struct MmapVectorIndex {
     mmap: MmapFile,
     record_count: usize,
     dimension: usize,
     embedding_len: usize,
     offsets_range: std::ops::Range<usize>,
+    shards: Vec<VectorShard>,
+    graph: Option<Box<VectorGraph>>,
 }
 
 impl MmapVectorIndex {
@@ -523,13 +590,97 @@ impl MmapVectorIndex {
             .checked_add(offsets_len)
             .ok_or_else(|| anyhow::anyhow!("offset table end overflow"))?;
 
-        Ok(Self {
+        let mut index = Self {
             mmap,
             record_count,
             dimension,
             embedding_len,
             offsets_range: offsets_start..offsets_end,
-        })
+            shards: Vec::new(),
+            graph: None,
+        };
+        index.shards = index.build_shards();
+
+        let graph_path = path.with_file_name(VECTOR_GRAPH_FILENAME);
+        if graph_path.exists() {
+            let file_len = index.mmap.as_slice().len() as u64;
+            match VectorGraph::open(&graph_path, record_count, file_len) {
+                Ok(graph) => index.graph = Some(Box::new(graph)),
+                Err(err) => warn!("Ignoring vector graph, searching exactly: {err}"),
+            }
+        }
+        Ok(index)
+    }
+
+    /// Whether searches use the HNSW graph ([`VECTOR_GRAPH_FILENAME`])
+    /// rather than scanning every record.
+    #[must_use]
+    pub const fn is_approximate(&self) -> bool {
+        self.graph.is_some()
+    }
+
+    /// Doc type code and F16 embedding bytes of record `i`.
+    fn record_embedding(&self, i: usize) -> Option<(u8, &[u8])> {
+        let record = self.mmap.as_slice().get(self.record_offset(i)?..)?;
+        let doc_id_len = u16::from_le_bytes([*record.get(2)?, *record.get(3)?]) as usize;
+        let start = 4 + doc_id_len;
+        Some((record[0], record.get(start..start + self.embedding_len)?))
+    }
+
+    /// Byte offset of record `i`, read from the offset table.
+    fn record_offset(&self, i: usize) -> Option<usize> {
+        let start = self.offsets_range.start.checked_add(i.checked_mul(8)?)?;
+        let chunk = self.mmap.as_slice().get(start..start + 8)?;
+        usize::try_from(u64::from_le_bytes(chunk.try_into().ok()?)).ok()
+    }
+
+    /// Doc type code of record `i`.
+    fn record_type(&self, i: usize) -> Option<u8> {
+        self.mmap.as_slice().get(self.record_offset(i)?).copied()
+    }
+
+    /// Split records into per-type shards of at most [`SHARD_RECORDS`].
+    ///
+    /// Type boundaries are found by binary search, relying on the
+    /// (`doc_type`, `doc_id`) ordering written by [`write_vector_index`].
+    fn build_shards(&self) -> Vec<VectorShard> {
+        // First record whose type code is >= `code`
+        let lower_bound = |code: u8| {
+            let (mut lo, mut hi) = (0, self.record_count);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if self.record_type(mid).unwrap_or(u8::MAX) < code {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            lo
+        };
+
+        let mut shards = Vec::new();
+        let mut start = 0;
+        for code in 0..=u8::MAX {
+            if start >= self.record_count {
+                break;
+            }
+            let end = if code == u8::MAX {
+                self.record_count
+            } else {
+                lower_bound(code + 1)
+            };
+            let mut chunk_start = start;
+            while chunk_start < end {
+                let chunk_end = (chunk_start + SHARD_RECORDS).min(end);
+                shards.push(VectorShard {
+                    doc_type: code,
+                    records: chunk_start..chunk_end,
+                });
+                chunk_start = chunk_end;
+            }
+            start = end;
+        }
+        shards
     }
 
     /// Get number of records in the index.
@@ -550,105 +701,970 @@ impl MmapVectorIndex {
         self.dimension
     }
 
-    /// Search for the top-k most similar vectors.
+    /// Count records per document type.
+    #[must_use]
+    pub fn type_counts(&self) -> std::collections::HashMap<String, usize> {
+        let mut counts = std::collections::HashMap::new();
+        let bytes = self.mmap.as_slice();
+        let Some(offsets_bytes) = bytes.get(self.offsets_range.clone()) else {
+            return counts;
+        };
+        for chunk in offsets_bytes.chunks_exact(8) {
+            let offset = usize::try_from(u64::from_le_bytes([
+                chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7],
+            ]))
+            .unwrap_or(usize::MAX);
+            if let Some(doc_type) = bytes.get(offset).copied().and_then(decode_doc_type) {
+                *counts.entry(doc_type.to_string()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Search for the top-k most similar vectors.
+    #[must_use]
+    pub fn search_top_k(
+        &self,
+        query: &[f32],
+        k: usize,
+        doc_types: Option<&[&str]>,
+    ) -> Vec<VectorSearchResult> {
+        if k == 0 || self.record_count == 0 || query.len() != self.dimension {
+            return Vec::new();
+        }
+
+        let filter = DocTypeFilter::new(doc_types);
+        if let Some(entries) = self.search_graph(query, k, filter) {
+            return self.collect_results(entries);
+        }
+
+        // Phase 1: Scan shards (in parallel for large indexes), keeping only
+        // offsets in per-shard heaps, then merge into a single top-k heap
+        let shards: Vec<&VectorShard> = self
+            .shards
+            .iter()
+            .filter(|shard| filter.is_none_or(|f| f.allows(shard.doc_type)))
+            .collect();
+        let scan = |shard: &&VectorShard| self.scan_shard(shard, query, k, filter);
+        let partial: Vec<Vec<HeapEntry>> = if self.record_count >= PARALLEL_THRESHOLD {
+            shards.par_iter().map(scan).collect()
+        } else {
+            shards.iter().map(scan).collect()
+        };
+
+        let mut heap: BinaryHeap<HeapEntry> = BinaryHeap::with_capacity(k + 1);
+        for entry in partial.into_iter().flatten() {
+            heap.push(entry);
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // Phase 2: Extract top-k and parse Strings only for final results
+        self.collect_results(heap)
+    }
+
+    /// Top-k records from the HNSW graph, or `None` without a graph or when
+    /// too few of the visited records pass `filter` (a narrow type filter),
+    /// in which case the caller scans the filtered shards exactly.
+    fn search_graph(
+        &self,
+        query: &[f32],
+        k: usize,
+        filter: Option<DocTypeFilter>,
+    ) -> Option<Vec<HeapEntry>> {
+        let graph = self.graph.as_ref()?;
+        let found = graph.search(k.max(HNSW_EF_SEARCH), |node| {
+            let (_, embedding) = self.record_embedding(node as usize)?;
+            dot_product_f16_simd(query, embedding)
+        });
+
+        let mut entries = Vec::with_capacity(k);
+        for scored in found {
+            let i = scored.node as usize;
+            let Some((doc_type, _)) = self.record_embedding(i) else {
+                continue;
+            };
+            if filter.is_some_and(|f| !f.allows(doc_type)) || decode_doc_type(doc_type).is_none() {
+                continue;
+            }
+            entries.push(HeapEntry {
+                score: scored.score,
+                offset: self.record_offset(i)?,
+            });
+            if entries.len() == k {
+                break;
+            }
+        }
+
+        let allowed: usize = self
+            .shards
+            .iter()
+            .filter(|shard| filter.is_none_or(|f| f.allows(shard.doc_type)))
+            .map(|shard| shard.records.len())
+            .sum();
+        (entries.len() >= k.min(allowed)).then_some(entries)
+    }
+
+    /// Resolve heap entries to results, best first.
+    fn collect_results(
+        &self,
+        entries: impl IntoIterator<Item = HeapEntry>,
+    ) -> Vec<VectorSearchResult> {
+        let bytes = self.mmap.as_slice();
+        let mut results: Vec<VectorSearchResult> = Vec::new();
+        for entry in entries {
+            let Some(record) = bytes.get(entry.offset..) else {
+                continue;
+            };
+            let doc_type_code = record[0];
+            let Some(doc_type) = decode_doc_type(doc_type_code) else {
+                continue;
+            };
+            let doc_id_len = u16::from_le_bytes([record[2], record[3]]) as usize;
+            let doc_id_bytes = &record[4..4 + doc_id_len];
+            let Ok(doc_id) = str::from_utf8(doc_id_bytes) else {
+                continue;
+            };
+            results.push(VectorSearchResult {
+                doc_id: doc_id.to_string(),
+                doc_type, // Already &'static str from decode_doc_type
+                score: entry.score,
+            });
+        }
+
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.doc_id.cmp(&b.doc_id))
+                .then_with(|| a.doc_type.cmp(b.doc_type))
+        });
+
+        results
+    }
+
+    /// Score the records of one shard, returning its top-k heap entries.
+    fn scan_shard(
+        &self,
+        shard: &VectorShard,
+        query: &[f32],
+        k: usize,
+        filter: Option<DocTypeFilter>,
+    ) -> Vec<HeapEntry> {
+        let bytes = self.mmap.as_slice();
+        let mut heap: BinaryHeap<HeapEntry> = BinaryHeap::with_capacity(k + 1);
+
+        for i in shard.records.clone() {
+            let Some(offset) = self.record_offset(i) else {
+                continue;
+            };
+            let Some(record) = bytes.get(offset..) else {
+                continue;
+            };
+            if record.len() < 4 {
+                continue;
+            }
+
+            let doc_type_code = record[0];
+            if let Some(filter) = &filter {
+                if !filter.allows(doc_type_code) {
+                    continue;
+                }
+            }
+            if decode_doc_type(doc_type_code).is_none() {
+                continue;
+            }
+
+            let doc_id_len = u16::from_le_bytes([record[2], record[3]]) as usize;
+            let doc_id_start = 4usize;
+            let doc_id_end = doc_id_start.saturating_add(doc_id_len);
+            let embedding_end = doc_id_end.saturating_add(self.embedding_len);
+            if record.len() < embedding_end {
+                continue;
+            }
+
+            // Validate UTF-8 without allocating
+            let doc_id_bytes = &record[doc_id_start..doc_id_end];
+            if str::from_utf8(doc_id_bytes).is_err() {
+                continue;
+            }
+
+            let embedding_bytes = &record[doc_id_end..embedding_end];
+            let Some(score) = dot_product_f16_simd(query, embedding_bytes) else {
+                continue;
+            };
+
+            heap.push(HeapEntry { score, offset });
+
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        heap.into_vec()
+    }
+}
+
+/// Default filename for the approximate nearest-neighbour graph over the
+/// records of [`VECTOR_INDEX_FILENAME`].
+pub const VECTOR_GRAPH_FILENAME: &str = "vector.hnsw";
+
+const VECTOR_GRAPH_MAGIC: [u8; 4] = *b"XFVG";
+const VECTOR_GRAPH_VERSION: u16 = 1;
+const VECTOR_GRAPH_HEADER_LEN: usize = 32;
+
+/// Indexes with fewer records are only scanned exactly: a sharded scan of
+/// this many vectors already takes a few milliseconds.
+pub const HNSW_MIN_RECORDS: usize = 50_000;
+
+/// Neighbours kept per node on the upper layers (twice as many on layer 0).
+const HNSW_M: usize = 16;
+
+/// Candidates considered while linking a new node.
+const HNSW_EF_CONSTRUCTION: usize = 100;
+
+/// Candidates considered while searching (raised to `k` when larger).
+const HNSW_EF_SEARCH: usize = 64;
+
+/// Highest layer a node can be placed on.
+const HNSW_MAX_LEVEL: usize = 15;
+
+/// Unused neighbour slot.
+const NO_NEIGHBOR: u32 = u32::MAX;
+
+/// Graph node and its similarity to the query being searched or inserted.
+#[derive(Debug, Clone, Copy)]
+struct Scored {
+    score: f32,
+    node: u32,
+}
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Max-heap on score; lower node wins ties
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// Layer of node `node`, drawn from the usual exponential distribution
+/// (`-ln(U) / ln(M)`) with a hash of the node number in place of a random
+/// draw, so every build places nodes on the same layers.
+fn hnsw_level(node: usize) -> usize {
+    // splitmix64
+    let mut x = (node as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    #[allow(clippy::cast_precision_loss)]
+    let uniform = ((x >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+    #[allow(clippy::cast_precision_loss)]
+    let level = -uniform.ln() / (HNSW_M as f64).ln();
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let level = level.floor() as usize;
+    level.min(HNSW_MAX_LEVEL)
+}
+
+/// Best-first search of one layer from `entry`, returning up to `ef` nodes,
+/// most similar first.
+///
+/// `neighbors` fills the buffer with a node's links on the layer; `score`
+/// returns a node's similarity to the query.
+fn search_hnsw_layer(
+    entry: &[Scored],
+    ef: usize,
+    mut neighbors: impl FnMut(u32, &mut Vec<u32>),
+    score: impl Fn(u32) -> Option<f32>,
+) -> Vec<Scored> {
+    let mut visited: std::collections::HashSet<u32> = entry.iter().map(|s| s.node).collect();
+    let mut candidates: BinaryHeap<Scored> = entry.iter().copied().collect();
+    let mut found: BinaryHeap<std::cmp::Reverse<Scored>> =
+        entry.iter().copied().map(std::cmp::Reverse).collect();
+    let mut links = Vec::new();
+
+    while let Some(current) = candidates.pop() {
+        if found.len() >= ef && found.peek().is_some_and(|worst| current < worst.0) {
+            break;
+        }
+        neighbors(current.node, &mut links);
+        for &node in &links {
+            if node == NO_NEIGHBOR || !visited.insert(node) {
+                continue;
+            }
+            let Some(score) = score(node) else {
+                continue;
+            };
+            let next = Scored { score, node };
+            if found.len() < ef || found.peek().is_some_and(|worst| next > worst.0) {
+                candidates.push(next);
+                found.push(std::cmp::Reverse(next));
+                if found.len() > ef {
+                    found.pop();
+                }
+            }
+        }
+    }
+
+    let mut found: Vec<Scored> = found.into_iter().map(|r| r.0).collect();
+    found.sort_unstable_by(|a, b| b.cmp(a));
+    found
+}
+
+/// Follow the most similar neighbour on one layer until none improves on
+/// the current node.
+fn greedy_hnsw_step(
+    mut current: Scored,
+    mut neighbors: impl FnMut(u32, &mut Vec<u32>),
+    score: impl Fn(u32) -> Option<f32>,
+) -> Scored {
+    let mut links = Vec::new();
+    loop {
+        let before = current.node;
+        neighbors(current.node, &mut links);
+        for &node in &links {
+            if node == NO_NEIGHBOR {
+                continue;
+            }
+            if let Some(score) = score(node) {
+                let next = Scored { score, node };
+                if next > current {
+                    current = next;
+                }
+            }
+        }
+        if current.node == before {
+            return current;
+        }
+    }
+}
+
+fn decode_f16_embedding(bytes: &[u8]) -> Vec<f32> {
+    use half::slice::HalfFloatSliceExt;
+
+    let halves: Vec<half::f16> = bytes
+        .chunks_exact(2)
+        .map(|chunk| half::f16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect();
+    let mut values = vec![0.0; halves.len()];
+    halves.convert_to_f32_slice(&mut values);
+    values
+}
+
+/// HNSW graph built in memory by [`build_hnsw`].
+struct HnswBuild {
+    entry: u32,
+    max_level: usize,
+    /// Links per node per layer (index 0 is layer 0).
+    links: Vec<Vec<Vec<u32>>>,
+}
+
+/// Build an HNSW graph over F16 embeddings, linking nodes in parallel.
+///
+/// Node layers are deterministic, but the links chosen depend on the order
+/// in which worker threads insert nodes.
+fn build_hnsw(embeddings: &[&[u8]]) -> HnswBuild {
+    use parking_lot::{Mutex as PlMutex, RwLock};
+
+    let levels: Vec<usize> = (0..embeddings.len()).map(hnsw_level).collect();
+    let links: Vec<Vec<RwLock<Vec<u32>>>> = levels
+        .iter()
+        .map(|&level| (0..=level).map(|_| RwLock::new(Vec::new())).collect())
+        .collect();
+    let entry = PlMutex::new((0u32, levels.first().copied().unwrap_or(0)));
+
+    let score_with = |query: &[f32], node: u32| {
+        embeddings
+            .get(node as usize)
+            .and_then(|embedding| dot_product_f16_simd(query, embedding))
+    };
+    let read_links = |node: u32, layer: usize, out: &mut Vec<u32>| {
+        out.clear();
+        if let Some(list) = links.get(node as usize).and_then(|l| l.get(layer)) {
+            out.extend_from_slice(&list.read());
+        }
+    };
+
+    let insert = |node: usize| {
+        let level = levels[node];
+        let query = decode_f16_embedding(embeddings[node]);
+        let score = |other: u32| score_with(&query, other);
+        let (entry_node, top) = *entry.lock();
+        let Some(entry_score) = score(entry_node) else {
+            return;
+        };
+        let mut current = Scored {
+            score: entry_score,
+            node: entry_node,
+        };
+        for layer in (level + 1..=top).rev() {
+            current = greedy_hnsw_step(current, |n, out| read_links(n, layer, out), score);
+        }
+
+        let mut entry_points = vec![current];
+        for layer in (0..=level.min(top)).rev() {
+            let found = search_hnsw_layer(
+                &entry_points,
+                HNSW_EF_CONSTRUCTION,
+                |n, out| read_links(n, layer, out),
+                score,
+            );
+            let selected = select_hnsw_neighbors(&found, HNSW_M, embeddings);
+            let max_links = if layer == 0 { HNSW_M * 2 } else { HNSW_M };
+            #[allow(clippy::cast_possible_truncation)]
+            let node_id = node as u32;
+            *links[node][layer].write() = selected.iter().map(|s| s.node).collect();
+
+            for neighbor in &selected {
+                let mut list = links[neighbor.node as usize][layer].write();
+                list.push(node_id);
+                if list.len() > max_links {
+                    // Keep the neighbour's most similar links
+                    let base = decode_f16_embedding(embeddings[neighbor.node as usize]);
+                    let mut scored: Vec<Scored> = list
+                        .iter()
+                        .filter_map(|&n| {
+                            Some(Scored {
+                                score: score_with(&base, n)?,
+                                node: n,
+                            })
+                        })
+                        .collect();
+                    scored.sort_unstable_by(|a, b| b.cmp(a));
+                    scored.truncate(max_links);
+                    *list = scored.into_iter().map(|s| s.node).collect();
+                }
+            }
+            entry_points = found;
+        }
+
+        if level > top {
+            let mut entry = entry.lock();
+            if level > entry.1 {
+                #[allow(clippy::cast_possible_truncation)]
+                let node_id = node as u32;
+                *entry = (node_id, level);
+            }
+        }
+    };
+
+    // A few nodes go in one at a time so parallel inserts start from a
+    // connected graph
+    let serial = embeddings.len().min(HNSW_M * 4);
+    (1..serial).for_each(insert);
+    (serial.max(1)..embeddings.len())
+        .into_par_iter()
+        .for_each(insert);
+
+    let (entry, max_level) = entry.into_inner();
+    HnswBuild {
+        entry,
+        max_level,
+        links: links
+            .into_iter()
+            .map(|layers| layers.into_iter().map(RwLock::into_inner).collect())
+            .collect(),
+    }
+}
+
+/// Pick up to `m` links for a node from `candidates` (most similar first),
+/// skipping candidates closer to an already picked link than to the node so
+/// links spread out in different directions. Skipped candidates fill any
+/// remaining slots.
+fn select_hnsw_neighbors(candidates: &[Scored], m: usize, embeddings: &[&[u8]]) -> Vec<Scored> {
+    let mut selected: Vec<Scored> = Vec::with_capacity(m);
+    let mut skipped = Vec::new();
+    for &candidate in candidates {
+        if selected.len() >= m {
+            break;
+        }
+        let vector = decode_f16_embedding(embeddings[candidate.node as usize]);
+        let diverse = selected.iter().all(|picked| {
+            dot_product_f16_simd(&vector, embeddings[picked.node as usize])
+                .is_none_or(|similarity| similarity < candidate.score)
+        });
+        if diverse {
+            selected.push(candidate);
+        } else {
+            skipped.push(candidate);
+        }
+    }
+    let missing = m.saturating_sub(selected.len());
+    selected.extend(skipped.into_iter().take(missing));
+    selected
+}
+
+/// Write the HNSW graph for the records of a freshly written vector index.
+///
+/// # File Format
+///
+/// - Header (32 bytes): magic, version, `M`, record count, entry node, top
+///   layer, and the length of the vector index file it was built for
+/// - Layer 0: `2 * M` u32 links per record, padded with `u32::MAX`
+/// - Upper layers: for each node above layer 0, its node number, its top
+///   layer, and `M` padded links per layer from 1 up
+fn write_vector_graph(path: &Path, embeddings: &[&[u8]], vector_file_len: u64) -> Result<u64> {
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    let start = Instant::now();
+    let graph = build_hnsw(embeddings);
+    let m = u16::try_from(HNSW_M).map_err(|_| anyhow::anyhow!("HNSW M exceeds u16"))?;
+    let max_level =
+        u16::try_from(graph.max_level).map_err(|_| anyhow::anyhow!("HNSW level exceeds u16"))?;
+
+    let temp_path = path.with_extension("hnsw.tmp");
+    let mut writer = BufWriter::new(File::create(&temp_path)?);
+    writer.write_all(&VECTOR_GRAPH_MAGIC)?;
+    writer.write_all(&VECTOR_GRAPH_VERSION.to_le_bytes())?;
+    writer.write_all(&m.to_le_bytes())?;
+    writer.write_all(&(embeddings.len() as u64).to_le_bytes())?;
+    writer.write_all(&graph.entry.to_le_bytes())?;
+    writer.write_all(&max_level.to_le_bytes())?;
+    writer.write_all(&[0u8; 2])?; // reserved
+    writer.write_all(&vector_file_len.to_le_bytes())?;
+
+    let write_padded = |writer: &mut BufWriter<File>, links: &[u32], width: usize| {
+        for slot in 0..width {
+            let link = links.get(slot).copied().unwrap_or(NO_NEIGHBOR);
+            writer.write_all(&link.to_le_bytes())?;
+        }
+        Ok::<_, std::io::Error>(())
+    };
+    for layers in &graph.links {
+        write_padded(&mut writer, &layers[0], HNSW_M * 2)?;
+    }
+    for (node, layers) in graph.links.iter().enumerate() {
+        if layers.len() < 2 {
+            continue;
+        }
+        let node = u32::try_from(node).map_err(|_| anyhow::anyhow!("node overflow"))?;
+        let top = u32::try_from(layers.len() - 1).map_err(|_| anyhow::anyhow!("level overflow"))?;
+        writer.write_all(&node.to_le_bytes())?;
+        writer.write_all(&top.to_le_bytes())?;
+        for links in &layers[1..] {
+            write_padded(&mut writer, links, HNSW_M)?;
+        }
+    }
+
+    writer.flush()?;
+    let file = writer.into_inner()?;
+    file.sync_all()?;
+    drop(file);
+    let file_size = std::fs::metadata(&temp_path)?.len();
+    std::fs::rename(&temp_path, path)?;
+    info!(
+        "Built HNSW graph over {} vectors in {:?}",
+        embeddings.len(),
+        start.elapsed()
+    );
+    Ok(file_size)
+}
+
+/// Memory-mapped HNSW graph over the records of an [`MmapVectorIndex`].
+///
+/// Layer 0 is read straight from the map; the much smaller upper layers are
+/// loaded into memory.
+struct VectorGraph {
+    mmap: MmapFile,
+    m: usize,
+    entry: u32,
+    max_level: usize,
+    upper: HashMap<u32, Vec<u32>>,
+}
+
+impl VectorGraph {
+    /// Open the graph at `path`, built for a vector index of `record_count`
+    /// records in a file of `vector_file_len` bytes.
+    fn open(path: &Path, record_count: usize, vector_file_len: u64) -> Result<Self> {
+        let mmap = MmapFile::open(path)?;
+        let bytes = mmap.as_slice();
+        ensure!(
+            bytes.len() >= VECTOR_GRAPH_HEADER_LEN,
+            "vector graph header truncated"
+        );
+        ensure!(
+            bytes[0..4] == VECTOR_GRAPH_MAGIC,
+            "vector graph magic mismatch"
+        );
+        let u16_at = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+        let u32_at = |at: usize| {
+            u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+        };
+        let u64_at =
+            |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap_or_default());
+        ensure!(
+            u16_at(4) == VECTOR_GRAPH_VERSION,
+            "unsupported vector graph version"
+        );
+        let m = usize::from(u16_at(6));
+        ensure!(m > 0, "vector graph M must be non-zero");
+        ensure!(
+            u64_at(8) == record_count as u64 && u64_at(24) == vector_file_len,
+            "vector graph was built for a different vector index"
+        );
+        let entry = u32_at(16);
+        ensure!(
+            (entry as usize) < record_count,
+            "vector graph entry out of range"
+        );
+        let max_level = usize::from(u16_at(20));
+
+        let layer0_end = record_count
+            .checked_mul(m * 2 * 4)
+            .and_then(|len| len.checked_add(VECTOR_GRAPH_HEADER_LEN))
+            .ok_or_else(|| anyhow::anyhow!("vector graph length overflow"))?;
+        ensure!(layer0_end <= bytes.len(), "vector graph layer 0 truncated");
+
+        let mut upper = HashMap::new();
+        let mut at = layer0_end;
+        while at < bytes.len() {
+            ensure!(at + 8 <= bytes.len(), "vector graph upper layers truncated");
+            let node = u32_at(at);
+            let levels = u32_at(at + 4) as usize;
+            ensure!(
+                (node as usize) < record_count && (1..=max_level).contains(&levels),
+                "vector graph node out of range"
+            );
+            at += 8;
+            let end = at + levels * m * 4;
+            ensure!(end <= bytes.len(), "vector graph upper layers truncated");
+            let links: Vec<u32> = (at..end).step_by(4).map(u32_at).collect();
+            upper.insert(node, links);
+            at = end;
+        }
+
+        Ok(Self {
+            mmap,
+            m,
+            entry,
+            max_level,
+            upper,
+        })
+    }
+
+    /// Fill `out` with the links of `node` on `layer`.
+    fn links(&self, node: u32, layer: usize, out: &mut Vec<u32>) {
+        out.clear();
+        if layer == 0 {
+            let width = self.m * 2;
+            let start = VECTOR_GRAPH_HEADER_LEN + node as usize * width * 4;
+            if let Some(bytes) = self.mmap.as_slice().get(start..start + width * 4) {
+                out.extend(
+                    bytes
+                        .chunks_exact(4)
+                        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+                );
+            }
+        } else if let Some(links) = self.upper.get(&node) {
+            let start = (layer - 1) * self.m;
+            if let Some(layer_links) = links.get(start..start + self.m) {
+                out.extend_from_slice(layer_links);
+            }
+        }
+    }
+
+    /// Up to `ef` records most similar to the query, most similar first.
+    fn search(&self, ef: usize, score: impl Fn(u32) -> Option<f32>) -> Vec<Scored> {
+        let Some(entry_score) = score(self.entry) else {
+            return Vec::new();
+        };
+        let mut current = Scored {
+            score: entry_score,
+            node: self.entry,
+        };
+        for layer in (1..=self.max_level).rev() {
+            current = greedy_hnsw_step(current, |n, out| self.links(n, layer, out), &score);
+        }
+        search_hnsw_layer(&[current], ef, |n, out| self.links(n, 0, out), &score)
+    }
+}
+
+/// Vector index used by semantic search.
+///
+/// Prefers the memory-mapped `vector.idx` sidecar, which persists across CLI
+/// invocations, and falls back to vectors decoded from `SQLite`.
+pub enum SemanticIndex {
+    /// Memory-mapped sidecar file.
+    Mapped(MmapVectorIndex),
+    /// Vectors loaded into memory from `SQLite`.
+    InMemory(VectorIndex),
+}
+
+impl SemanticIndex {
+    /// Open the sidecar in `index_path`, rebuilding it from storage first if it
+    /// is missing, unreadable, or holds a different number of embeddings than
+    /// the database.
+    ///
+    /// If the sidecar cannot be written (for example, a read-only index
+    /// directory), embeddings are loaded from `SQLite` instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the embedding count or the fallback load fails.
+    pub fn open(index_path: &std::path::Path, storage: &Storage) -> Result<Self> {
+        let file_path = index_path.join(VECTOR_INDEX_FILENAME);
+        let expected = usize::try_from(storage.embedding_count()?).unwrap_or(usize::MAX);
+        if expected == 0 {
+            return Ok(Self::InMemory(VectorIndex::load_from_storage(storage)?));
+        }
+
+        match MmapVectorIndex::open(&file_path) {
+            Ok(index) if index.len() == expected => return Ok(Self::Mapped(index)),
+            Ok(index) => info!(
+                "Vector index file has {} records, database has {expected}; rebuilding",
+                index.len()
+            ),
+            Err(err) if file_path.exists() => {
+                warn!("Vector index file is unusable, rebuilding: {err}");
+            }
+            Err(_) => info!("Vector index file missing; building it"),
+        }
+
+        if index_path.is_dir() {
+            match write_vector_index(index_path, storage)
+                .and_then(|_| MmapVectorIndex::open(&file_path))
+            {
+                Ok(index) => return Ok(Self::Mapped(index)),
+                Err(err) => warn!("Could not refresh vector index file, using database: {err}"),
+            }
+        }
+
+        Ok(Self::InMemory(VectorIndex::load_from_storage(storage)?))
+    }
+
+    /// Get number of vectors in the index.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Mapped(index) => index.len(),
+            Self::InMemory(index) => index.len(),
+        }
+    }
+
+    /// Check if the index is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the embedding dimension.
+    #[must_use]
+    pub const fn dimension(&self) -> usize {
+        match self {
+            Self::Mapped(index) => index.dimension(),
+            Self::InMemory(index) => index.dimension(),
+        }
+    }
+
+    /// Count vectors per document type.
+    #[must_use]
+    pub fn type_counts(&self) -> std::collections::HashMap<String, usize> {
+        match self {
+            Self::Mapped(index) => index.type_counts(),
+            Self::InMemory(index) => index.type_counts(),
+        }
+    }
+
+    /// Search for the top-k most similar vectors.
+    #[must_use]
+    pub fn search_top_k(
+        &self,
+        query: &[f32],
+        k: usize,
+        doc_types: Option<&[&str]>,
+    ) -> Vec<VectorSearchResult> {
+        match self {
+            Self::Mapped(index) => index.search_top_k(query, k, doc_types),
+            Self::InMemory(index) => index.search_top_k_parallel(query, k, doc_types),
+        }
+    }
+}
+
+/// A [`SemanticIndex`] loaded on first use and shared by later searches.
+///
+/// The CLI keeps one in a static for the life of the process; a host that
+/// keeps archives open, such as a desktop app, keeps one per archive.
+/// Loading is guarded so concurrent first searches load only once.
+pub struct VectorIndexCache {
+    index: OnceLock<SemanticIndex>,
+    meta: OnceLock<CacheMeta>,
+    init_lock: Mutex<()>,
+}
+
+impl Default for VectorIndexCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VectorIndexCache {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            index: OnceLock::new(),
+            meta: OnceLock::new(),
+            init_lock: Mutex::new(()),
+        }
+    }
+
+    /// What was loaded, once [`Self::load`] has succeeded.
+    #[must_use]
+    pub fn meta(&self) -> Option<&CacheMeta> {
+        self.meta.get()
+    }
+
+    /// The loaded index, without loading it.
+    #[must_use]
+    pub fn index(&self) -> Option<&SemanticIndex> {
+        self.index.get()
+    }
+
+    fn warn_if_stale(&self, storage: &Storage, db_path: &Path) {
+        let Some(meta) = self.meta.get() else {
+            return;
+        };
+        match meta.is_stale(storage, db_path) {
+            Ok(true) => {
+                warn!("VectorIndex cache may be stale; reopen the archive to reload embeddings.");
+            }
+            Ok(false) => {}
+            Err(err) => warn!("VectorIndex cache staleness check failed: {err}"),
+        }
+    }
+
+    /// Whether any embeddings exist for `doc_types` (`None` means any type).
+    ///
+    /// Before the index is loaded this optimistically returns `true`.
     #[must_use]
-    pub fn search_top_k(
-        &self,
-        query: &[f32],
-        k: usize,
-        doc_types: Option<&[&str]>,
-    ) -> Vec<VectorSearchResult> {
-        if k == 0 || self.record_count == 0 || query.len() != self.dimension {
-            return Vec::new();
+    pub fn has_embeddings_for(&self, doc_types: Option<&[DocType]>) -> bool {
+        let Some(meta) = self.meta.get() else {
+            return true;
+        };
+        if meta.embedding_count == 0 {
+            return false;
         }
 
-        let filter = DocTypeFilter::new(doc_types);
-        let bytes = self.mmap.as_slice();
-        let Some(offsets_bytes) = bytes.get(self.offsets_range.clone()) else {
-            return Vec::new();
+        let Some(types) = doc_types else {
+            return true;
         };
 
-        let mut heap: BinaryHeap<HeapEntry> = BinaryHeap::with_capacity(k + 1);
+        types.iter().any(|doc_type| {
+            meta.type_counts
+                .get(doc_type.as_str())
+                .copied()
+                .unwrap_or(0)
+                > 0
+        })
+    }
 
-        // Phase 1: Scan all records, keeping only offsets in heap (no String allocations)
-        for chunk in offsets_bytes.chunks_exact(8) {
-            let offset = usize::try_from(u64::from_le_bytes([
-                chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7],
-            ]))
-            .unwrap_or(usize::MAX);
-            let Some(record) = bytes.get(offset..) else {
-                continue;
-            };
-            if record.len() < 4 {
-                continue;
-            }
+    /// Load the index on first call; later calls return the same index.
+    ///
+    /// The flag is `true` when this call did the loading. A warning is
+    /// logged when embeddings changed on disk after the index was loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index cannot be loaded or the database
+    /// modification time cannot be read.
+    pub fn load<'a>(
+        &'a self,
+        storage: &Storage,
+        db_path: &Path,
+        index_path: &Path,
+    ) -> Result<(&'a SemanticIndex, bool)> {
+        if let Some(index) = self.index.get() {
+            self.warn_if_stale(storage, db_path);
+            return Ok((index, false));
+        }
 
-            let doc_type_code = record[0];
-            if let Some(filter) = &filter {
-                if !filter.allows(doc_type_code) {
-                    continue;
-                }
-            }
-            if decode_doc_type(doc_type_code).is_none() {
-                continue;
-            }
+        let _guard = self
+            .init_lock
+            .lock()
+            .map_err(|err| anyhow::anyhow!("lock vector index initialization: {err}"))?;
+        if let Some(index) = self.index.get() {
+            self.warn_if_stale(storage, db_path);
+            return Ok((index, false));
+        }
 
-            let doc_id_len = u16::from_le_bytes([record[2], record[3]]) as usize;
-            let doc_id_start = 4usize;
-            let doc_id_end = doc_id_start.saturating_add(doc_id_len);
-            let embedding_end = doc_id_end.saturating_add(self.embedding_len);
-            if record.len() < embedding_end {
-                continue;
-            }
+        info!("Loading VectorIndex (first search)...");
+        let start = Instant::now();
 
-            // Validate UTF-8 without allocating
-            let doc_id_bytes = &record[doc_id_start..doc_id_end];
-            if str::from_utf8(doc_id_bytes).is_err() {
-                continue;
-            }
+        // Map the on-disk sidecar (rebuilt if stale), falling back to storage
+        let index = SemanticIndex::open(index_path, storage)?;
 
-            let embedding_bytes = &record[doc_id_end..embedding_end];
-            let Some(score) = dot_product_f16_simd(query, embedding_bytes) else {
-                continue;
-            };
+        // Collect stats for staleness checking
+        let type_counts = index.type_counts();
+        let embedding_count = index.len();
 
-            heap.push(HeapEntry { score, offset });
+        let db_mtime = db_path
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .context("read database mtime")?;
+        let meta = CacheMeta {
+            db_mtime,
+            embedding_count,
+            type_counts,
+        };
+        let _ = self.meta.set(meta);
 
-            if heap.len() > k {
-                heap.pop();
-            }
-        }
+        let _ = self.index.set(index);
+        info!(
+            "VectorIndex loaded: {} embeddings in {:?}",
+            embedding_count,
+            start.elapsed()
+        );
 
-        // Phase 2: Extract top-k and parse Strings only for final results
-        let mut results: Vec<VectorSearchResult> = Vec::with_capacity(heap.len());
-        for entry in heap {
-            let Some(record) = bytes.get(entry.offset..) else {
-                continue;
-            };
-            let doc_type_code = record[0];
-            let Some(doc_type) = decode_doc_type(doc_type_code) else {
-                continue;
-            };
-            let doc_id_len = u16::from_le_bytes([record[2], record[3]]) as usize;
-            let doc_id_bytes = &record[4..4 + doc_id_len];
-            let Ok(doc_id) = str::from_utf8(doc_id_bytes) else {
-                continue;
-            };
-            results.push(VectorSearchResult {
-                doc_id: doc_id.to_string(),
-                doc_type, // Already &'static str from decode_doc_type
-                score: entry.score,
-            });
-        }
+        self.index
+            .get()
+            .map(|idx| (idx, true))
+            .ok_or_else(|| anyhow::anyhow!("VectorIndex cache not initialized"))
+    }
+}
 
-        results.sort_by(|a, b| {
-            b.score
-                .total_cmp(&a.score)
-                .then_with(|| a.doc_id.cmp(&b.doc_id))
-                .then_with(|| a.doc_type.cmp(b.doc_type))
-        });
+/// What a [`VectorIndexCache`] loaded, for detecting stale embeddings.
+#[derive(Debug, Clone)]
+pub struct CacheMeta {
+    /// Database modification time at load
+    pub db_mtime: SystemTime,
+    pub embedding_count: usize,
+    /// Embeddings per document type
+    pub type_counts: HashMap<String, usize>,
+}
 
-        results
+impl CacheMeta {
+    /// Whether the database changed since the index was loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database metadata or embedding count cannot
+    /// be read.
+    pub fn is_stale(&self, storage: &Storage, db_path: &Path) -> Result<bool> {
+        let db_mtime = db_path
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .context("read database mtime")?;
+        if db_mtime != self.db_mtime {
+            return Ok(true);
+        }
+
+        let current_count =
+            usize::try_from(storage.embedding_count()?).context("convert embedding count")?;
+        Ok(current_count != self.embedding_count)
     }
 }
 
@@ -706,7 +1722,6 @@ impl VectorIndex {
         use half::f16;
         use std::fs::File;
         use std::io::Read;
-        use tracing::warn;
 
         let file_path = index_path.join(VECTOR_INDEX_FILENAME);
         if !file_path.exists() {
@@ -908,7 +1923,6 @@ impl VectorIndex {
     ///
     /// Uses rayon to parallelize the search across multiple CPU cores.
     /// Falls back to sequential search for small indices.
-    #[cfg(feature = "parallel-search")]
     #[must_use]
     pub fn search_top_k_parallel(
         &self,
@@ -916,9 +1930,6 @@ impl VectorIndex {
         k: usize,
         doc_types: Option<&[&str]>,
     ) -> Vec<VectorSearchResult> {
-        use rayon::prelude::*;
-
-        const PARALLEL_THRESHOLD: usize = 10_000;
         const CHUNK_SIZE: usize = 1024;
 
         if self.vectors.len() < PARALLEL_THRESHOLD {
@@ -1334,6 +2345,8 @@ mod tests {
         assert_eq!(encode_doc_type("like"), Some(1));
         assert_eq!(encode_doc_type("dm"), Some(2));
         assert_eq!(encode_doc_type("grok"), Some(3));
+        assert_eq!(encode_doc_type("bookmark"), Some(4));
+        assert_eq!(encode_doc_type("dm_conv"), Some(5));
         assert_eq!(encode_doc_type("unknown"), None);
     }
 
@@ -1343,12 +2356,14 @@ mod tests {
         assert_eq!(decode_doc_type(1), Some("like"));
         assert_eq!(decode_doc_type(2), Some("dm"));
         assert_eq!(decode_doc_type(3), Some("grok"));
-        assert_eq!(decode_doc_type(4), None);
+        assert_eq!(decode_doc_type(4), Some("bookmark"));
+        assert_eq!(decode_doc_type(5), Some("dm_conv"));
+        assert_eq!(decode_doc_type(6), None);
     }
 
     #[test]
     fn test_doc_type_roundtrip() {
-        for doc_type in &["tweet", "like", "dm", "grok"] {
+        for doc_type in &["tweet", "like", "bookmark", "dm", "grok", "dm_conv"] {
             let encoded = encode_doc_type(doc_type).unwrap();
             let decoded = decode_doc_type(encoded).unwrap();
             assert_eq!(*doc_type, decoded);
@@ -1644,6 +2659,50 @@ mod tests {
         assert_eq!(index.len(), 1, "Should fall back to storage");
     }
 
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_semantic_index_refreshes_stale_sidecar() {
+        let storage = Storage::open_memory().unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let embedding: Vec<f32> = (0..384).map(|i| i as f32 / 384.0).collect();
+        storage
+            .store_embedding("doc1", "tweet", &embedding, None)
+            .unwrap();
+
+        // Missing sidecar is built on first open
+        let index = SemanticIndex::open(temp_dir.path(), &storage).unwrap();
+        assert!(matches!(index, SemanticIndex::Mapped(_)));
+        assert_eq!(index.len(), 1);
+        assert!(temp_dir.path().join(VECTOR_INDEX_FILENAME).exists());
+        drop(index);
+
+        // A sidecar with fewer records than the database is rebuilt
+        storage
+            .store_embedding("doc2", "like", &embedding, None)
+            .unwrap();
+        let index = SemanticIndex::open(temp_dir.path(), &storage).unwrap();
+        assert!(matches!(index, SemanticIndex::Mapped(_)));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.type_counts().get("like"), Some(&1));
+
+        let results = index.search_top_k(&embedding, 5, Some(&["tweet"]));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, "doc1");
+    }
+
+    #[test]
+    fn test_semantic_index_falls_back_without_index_dir() {
+        let storage = Storage::open_memory().unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        storage
+            .store_embedding("doc1", "tweet", &[0.6, 0.8], None)
+            .unwrap();
+
+        let index = SemanticIndex::open(&temp_dir.path().join("missing"), &storage).unwrap();
+        assert!(matches!(index, SemanticIndex::InMemory(_)));
+        assert_eq!(index.len(), 1);
+    }
+
     // ========================================================================
     // xf-70: Vector index regression tests
     // ========================================================================
@@ -1819,6 +2878,53 @@ mod tests {
         }
     }
 
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_sharded_search_matches_sequential() {
+        let storage = Storage::open_memory().unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        // Enough records to take the parallel path and span several shards
+        let count = PARALLEL_THRESHOLD + 2_000;
+        for i in 0..count {
+            let embedding: Vec<f32> = (0..16).map(|j| ((i * 17 + j) as f32).sin()).collect();
+            let doc_type = if i % 3 == 0 { "like" } else { "tweet" };
+            storage
+                .store_embedding(&format!("doc{i}"), doc_type, &embedding, None)
+                .unwrap();
+        }
+        write_vector_index(temp_dir.path(), &storage).unwrap();
+
+        let mmap_index =
+            MmapVectorIndex::open(&temp_dir.path().join(VECTOR_INDEX_FILENAME)).unwrap();
+        assert!(mmap_index.shards.len() > 2);
+        assert!(
+            mmap_index
+                .shards
+                .iter()
+                .all(|shard| shard.records.len() <= SHARD_RECORDS)
+        );
+        let file_index = VectorIndex::load_from_file(temp_dir.path())
+            .unwrap()
+            .expect("Should load from file");
+
+        let query: Vec<f32> = (0..16).map(|j| (j as f32).cos()).collect();
+        for filter in [None, Some(&["like"][..])] {
+            let expected = file_index.search_top_k(&query, 25, filter);
+            for results in [
+                mmap_index.search_top_k(&query, 25, filter),
+                file_index.search_top_k_parallel(&query, 25, filter),
+            ] {
+                assert_eq!(results.len(), expected.len());
+                for (r, e) in results.iter().zip(&expected) {
+                    assert_eq!(r.doc_id, e.doc_id);
+                    assert_eq!(r.doc_type, e.doc_type);
+                    assert!((r.score - e.score).abs() < 1e-5);
+                }
+            }
+        }
+    }
+
     #[test]
     #[allow(clippy::cast_precision_loss)]
     fn test_search_isomorphism_with_type_filter() {
@@ -1880,6 +2986,60 @@ mod tests {
         }
     }
 
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_vector_graph_finds_exact_neighbors() {
+        let storage = Storage::open_memory().unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let vector = |seed: usize| {
+            let mut v: Vec<f32> = (0..32)
+                .map(|j| (seed as f32).mul_add(12.9898, j as f32 * 78.233).sin())
+                .collect();
+            l2_normalize(&mut v);
+            v
+        };
+        for i in 0..2000 {
+            let doc_type = if i % 100 == 0 { "grok" } else { "tweet" };
+            storage
+                .store_embedding(&format!("doc{i}"), doc_type, &vector(i), None)
+                .unwrap();
+        }
+
+        let stats = write_vector_index_with(temp_dir.path(), &storage, 0).unwrap();
+        assert!(stats.graph_size.is_some());
+        let approximate =
+            MmapVectorIndex::open(&temp_dir.path().join(VECTOR_INDEX_FILENAME)).unwrap();
+        assert!(approximate.is_approximate());
+
+        let exact = VectorIndex::load_from_storage(&storage).unwrap();
+        let mut hits = 0;
+        for q in 0..20 {
+            let query = vector(50_000 + q);
+            let expected: std::collections::HashSet<String> = exact
+                .search_top_k(&query, 10, None)
+                .into_iter()
+                .map(|r| r.doc_id)
+                .collect();
+            hits += approximate
+                .search_top_k(&query, 10, None)
+                .iter()
+                .filter(|r| expected.contains(&r.doc_id))
+                .count();
+        }
+        assert!(hits >= 190, "recall {hits}/200");
+
+        // A narrow type filter falls back to scanning its records
+        let grok = approximate.search_top_k(&vector(5), 30, Some(&["grok"]));
+        assert_eq!(grok.len(), 20);
+
+        // Rewriting below the threshold removes the stale graph
+        write_vector_index(temp_dir.path(), &storage).unwrap();
+        assert!(!temp_dir.path().join(VECTOR_GRAPH_FILENAME).exists());
+        let exact_mapped =
+            MmapVectorIndex::open(&temp_dir.path().join(VECTOR_INDEX_FILENAME)).unwrap();
+        assert!(!exact_mapped.is_approximate());
+    }
+
     #[test]
     #[allow(clippy::cast_precision_loss)]
     fn test_search_order_determinism() {
@@ -1922,3 +3082,267 @@ mod tests {
         }
     }
 }
+
+#[cfg(test)]
+mod cache_tests {
+    use super::{CacheMeta, VectorIndexCache};
+    use crate::storage::Storage;
+    use anyhow::{Context, Result};
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::time::SystemTime;
+    use tempfile::tempdir;
+
+    fn create_storage() -> Result<(tempfile::TempDir, PathBuf, PathBuf, Storage)> {
+        let dir = tempdir().context("create temp dir")?;
+        let db_path = dir.path().join("xf-test.db");
+        let index_path = dir.path().join("index");
+        std::fs::create_dir_all(&index_path).context("create index dir")?;
+        let storage = Storage::open(&db_path)?;
+        Ok((dir, db_path, index_path, storage))
+    }
+
+    fn build_meta(storage: &Storage, db_path: &Path) -> Result<CacheMeta> {
+        let db_mtime = db_path
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .context("read database mtime")?;
+        let embedding_count =
+            usize::try_from(storage.embedding_count()?).context("convert embedding count")?;
+        Ok(CacheMeta {
+            db_mtime,
+            embedding_count,
+            type_counts: HashMap::new(),
+        })
+    }
+
+    fn store_sample_embedding(storage: &Storage, doc_id: &str) -> Result<()> {
+        storage.store_embedding(doc_id, "tweet", &[0.1, 0.2], None)
+    }
+
+    #[test]
+    fn test_fresh_when_no_changes() -> Result<()> {
+        let (_dir, db_path, _index_path, storage) = create_storage()?;
+        store_sample_embedding(&storage, "doc-1")?;
+        let meta = build_meta(&storage, &db_path)?;
+        assert!(!meta.is_stale(&storage, &db_path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_after_embedding_added() -> Result<()> {
+        let (_dir, db_path, _index_path, storage) = create_storage()?;
+        store_sample_embedding(&storage, "doc-1")?;
+        let meta = build_meta(&storage, &db_path)?;
+        store_sample_embedding(&storage, "doc-2")?;
+        assert!(meta.is_stale(&storage, &db_path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_after_embedding_deleted() -> Result<()> {
+        let (_dir, db_path, _index_path, storage) = create_storage()?;
+        store_sample_embedding(&storage, "doc-1")?;
+        store_sample_embedding(&storage, "doc-2")?;
+        let meta = build_meta(&storage, &db_path)?;
+        storage.clear_embeddings()?;
+        assert!(meta.is_stale(&storage, &db_path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_after_db_file_modified() -> Result<()> {
+        let (_dir, db_path, _index_path, storage) = create_storage()?;
+        store_sample_embedding(&storage, "doc-1")?;
+        let mut meta = build_meta(&storage, &db_path)?;
+        meta.db_mtime = SystemTime::UNIX_EPOCH;
+        assert!(meta.is_stale(&storage, &db_path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_with_missing_db_file() -> Result<()> {
+        let (_dir, _db_path, _index_path, storage) = create_storage()?;
+        let missing_path = PathBuf::from("missing-xf-db.sqlite");
+        let meta = CacheMeta {
+            db_mtime: SystemTime::UNIX_EPOCH,
+            embedding_count: 0,
+            type_counts: HashMap::new(),
+        };
+        assert!(meta.is_stale(&storage, &missing_path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_vector_index_initializes_once() -> Result<()> {
+        let (_dir, db_path, index_path, storage) = create_storage()?;
+        store_sample_embedding(&storage, "doc-1")?;
+        let cache = VectorIndexCache::new();
+        let (first, loaded_first) = cache.load(&storage, &db_path, &index_path)?;
+        let (second, loaded_second) = cache.load(&storage, &db_path, &index_path)?;
+        assert!(loaded_first);
+        assert!(!loaded_second);
+        assert!(std::ptr::eq(first, second));
+        Ok(())
+    }
+
+    #[test]
+    fn test_vector_index_handles_empty_embeddings() -> Result<()> {
+        let (_dir, db_path, index_path, storage) = create_storage()?;
+        let cache = VectorIndexCache::new();
+        let (_index, loaded) = cache.load(&storage, &db_path, &index_path)?;
+        assert!(loaded);
+        let meta = cache.meta().context("cache meta missing")?;
+        assert_eq!(meta.embedding_count, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_cache_access_after_init() -> Result<()> {
+        let (_dir, db_path, index_path, storage) = create_storage()?;
+        store_sample_embedding(&storage, "doc-1")?;
+        let cache = Arc::new(VectorIndexCache::new());
+        let _ = cache.load(&storage, &db_path, &index_path)?;
+
+        let mut handles = Vec::new();
+        for _ in 0..4 {
+            let cache = Arc::clone(&cache);
+            handles.push(std::thread::spawn(move || cache.index().is_some()));
+        }
+
+        for handle in handles {
+            assert!(handle.join().unwrap_or(false));
+        }
+
+        Ok(())
+    }
+
+    // =========================================================================
+    // Cache Performance Tests (xf-pkc)
+    // =========================================================================
+
+    /// Perf smoke check: verify second load is faster than first (cache hit).
+    ///
+    /// Marked as #[ignore] because timing is environment-dependent.
+    /// Run manually with: `cargo test test_cache_hit_faster_than_cold -- --ignored`
+    #[test]
+    #[ignore = "Perf comparison is environment-dependent; run manually for validation"]
+    fn test_cache_hit_faster_than_cold() -> Result<()> {
+        use std::time::Instant;
+
+        let (_dir, db_path, index_path, storage) = create_storage()?;
+
+        // Add multiple embeddings to make the timing difference more noticeable
+        for i in 0..100 {
+            storage.store_embedding(&format!("doc-{i}"), "tweet", &[0.1, 0.2, 0.3], None)?;
+        }
+
+        let cache = VectorIndexCache::new();
+
+        // First load (cold)
+        let cold_start = Instant::now();
+        let (_, loaded_first) = cache.load(&storage, &db_path, &index_path)?;
+        let cold_duration = cold_start.elapsed();
+        assert!(loaded_first, "First load should be fresh");
+
+        // Second load (warm - should be instant)
+        let warm_start = Instant::now();
+        let (_, loaded_second) = cache.load(&storage, &db_path, &index_path)?;
+        let warm_duration = warm_start.elapsed();
+        assert!(!loaded_second, "Second load should be cached");
+
+        eprintln!(
+            "Cache perf: cold={cold_duration:?} warm={warm_duration:?} (speedup={:.1}x)",
+            cold_duration.as_secs_f64() / warm_duration.as_secs_f64().max(1e-9)
+        );
+
+        // Warm should be faster - we just verify the cache is working, not a specific speedup.
+        // In practice, speedup is typically 1000x+, but timing tests are environment-dependent.
+        assert!(
+            warm_duration < cold_duration,
+            "Cached load ({warm_duration:?}) should be faster than cold load ({cold_duration:?})"
+        );
+
+        Ok(())
+    }
+
+    /// Test loading with a moderately large embedding set.
+    ///
+    /// This tests that the cache handles thousands of embeddings without
+    /// memory issues. The size is bounded to avoid CI timeouts.
+    #[test]
+    fn test_large_embedding_set() -> Result<()> {
+        let (_dir, db_path, index_path, storage) = create_storage()?;
+
+        // Add 5000 embeddings - large enough to be meaningful, small enough for CI
+        let embedding_count = 5000;
+        for i in 0..embedding_count {
+            let doc_type = match i % 4 {
+                0 => "tweet",
+                1 => "like",
+                2 => "dm",
+                _ => "grok",
+            };
+            storage.store_embedding(&format!("doc-{i}"), doc_type, &[0.1, 0.2, 0.3], None)?;
+        }
+
+        let cache = VectorIndexCache::new();
+        let (index, loaded) = cache.load(&storage, &db_path, &index_path)?;
+
+        assert!(loaded, "Should load fresh");
+        assert_eq!(index.len(), embedding_count);
+
+        // Verify type counts are tracked
+        let meta = cache.meta().context("meta should be set")?;
+        assert_eq!(meta.embedding_count, embedding_count);
+        assert!(
+            !meta.type_counts.is_empty(),
+            "type_counts should be populated"
+        );
+
+        // Verify search still works
+        let query = vec![0.1, 0.2, 0.3];
+        let results = index.search_top_k(&query, 10, None);
+        assert_eq!(results.len(), 10);
+
+        Ok(())
+    }
+
+    /// Test that `loaded_now` flag is correctly set on first vs subsequent loads.
+    #[test]
+    fn test_loaded_now_flag_semantics() -> Result<()> {
+        let (_dir, db_path, index_path, storage) = create_storage()?;
+        store_sample_embedding(&storage, "doc-1")?;
+
+        let cache = VectorIndexCache::new();
+
+        // First load: loaded_now should be true
+        let (_, loaded_now_1) = cache.load(&storage, &db_path, &index_path)?;
+        assert!(loaded_now_1, "First load: loaded_now should be true");
+
+        // Second load: loaded_now should be false
+        let (_, loaded_now_2) = cache.load(&storage, &db_path, &index_path)?;
+        assert!(!loaded_now_2, "Second load: loaded_now should be false");
+
+        // Third load: still false
+        let (_, loaded_now_3) = cache.load(&storage, &db_path, &index_path)?;
+        assert!(!loaded_now_3, "Third load: loaded_now should be false");
+
+        // Fourth load from a different thread: still false
+        let cache = Arc::new(cache);
+        let cache_clone = Arc::clone(&cache);
+        let storage_clone = Storage::open(&db_path)?;
+        let handle = std::thread::spawn(move || -> Result<bool> {
+            let (_, loaded_now) = cache_clone.load(&storage_clone, &db_path, &index_path)?;
+            Ok(loaded_now)
+        });
+        let loaded_now_thread = handle.join().expect("thread should complete")?;
+        assert!(
+            !loaded_now_thread,
+            "Load from thread: loaded_now should be false"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,448 @@
+//! Archive integrity verification (`xf verify`).
+//!
+//! X archives ship a `data/manifest.js` that lists every data file, with the
+//! number of records in it, under `dataTypes.<type>.files`, plus the folder
+//! holding each type's media. Verification checks an archive against that
+//! list, either extracted or still zipped: each listed file must be present,
+//! parse completely, and hold the records the manifest promises. A download
+//! cut short shows up here as a missing or truncated file instead of as a
+//! confusing failure halfway through indexing.
+
+use crate::doctor::{CheckCategory, CheckStatus, HealthCheck};
+use crate::format_bytes;
+use crate::import::{ArchiveFile, long_path};
+use crate::parser::parse_js_data;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Path of the manifest inside an archive.
+pub const MANIFEST_PATH: &str = "data/manifest.js";
+
+/// A data file listed in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestFile {
+    /// Manifest data type, e.g. `tweets`.
+    pub data_type: String,
+    /// Path inside the archive, e.g. `data/tweets.js`.
+    pub file_name: String,
+    /// Number of records the file should hold.
+    pub count: Option<usize>,
+}
+
+/// The parts of `manifest.js` that describe the archive's contents.
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    pub files: Vec<ManifestFile>,
+    /// Media folders, e.g. `data/tweets_media`.
+    pub media_dirs: Vec<String>,
+    /// Total archive size the manifest reports.
+    pub size_bytes: Option<u64>,
+}
+
+/// Parse the contents of `manifest.js`.
+///
+/// # Errors
+///
+/// Returns an error if the file is not a JS-wrapped JSON object.
+pub fn parse_manifest(content: &str) -> Result<Manifest> {
+    let data = parse_js_data(content)?;
+    if !data.is_object() {
+        anyhow::bail!("Invalid manifest format: expected JSON object");
+    }
+
+    let mut manifest = Manifest {
+        size_bytes: number(&data["archiveInfo"]["sizeBytes"]),
+        ..Manifest::default()
+    };
+    if let Some(types) = data["dataTypes"].as_object() {
+        for (data_type, info) in types {
+            for file in info["files"].as_array().into_iter().flatten() {
+                let Some(file_name) = file["fileName"].as_str() else {
+                    continue;
+                };
+                manifest.files.push(ManifestFile {
+                    data_type: data_type.clone(),
+                    file_name: file_name.trim_start_matches("./").to_string(),
+                    count: number(&file["count"]).and_then(|n| usize::try_from(n).ok()),
+                });
+            }
+            if let Some(dir) = info["mediaDirectory"].as_str() {
+                manifest.media_dirs.push(
+                    dir.trim_start_matches("./")
+                        .trim_end_matches('/')
+                        .to_string(),
+                );
+            }
+        }
+    }
+    manifest.files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    manifest.media_dirs.sort();
+    Ok(manifest)
+}
+
+/// Manifest numbers are usually strings (`"count": "3715"`).
+fn number(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// An archive to verify: an extracted directory or the downloaded zip.
+pub enum ArchiveSource {
+    Dir(PathBuf),
+    Zip(Box<zip::ZipArchive<ArchiveFile>>),
+}
+
+impl ArchiveSource {
+    /// Open a directory or zip file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a zip file cannot be opened or read.
+    pub fn open(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            return Ok(Self::Dir(path.to_path_buf()));
+        }
+        let file = ArchiveFile::open(path)
+            .with_context(|| format!("Failed to open '{}'", path.display()))?;
+        let archive = zip::ZipArchive::new(file)
+            .with_context(|| format!("Failed to read zip file '{}'", path.display()))?;
+        Ok(Self::Zip(Box::new(archive)))
+    }
+
+    /// Read a file, or `None` if the archive doesn't have it.
+    fn read(&mut self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        match self {
+            Self::Dir(root) => match fs::read(long_path(&root.join(name))) {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e),
+            },
+            Self::Zip(archive) => {
+                let mut entry = match archive.by_name(name) {
+                    Ok(entry) => entry,
+                    Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+                    Err(e) => return Err(e.into()),
+                };
+                let mut bytes = Vec::new();
+                // Reading to the end checks the entry's CRC
+                io::Read::read_to_end(&mut entry, &mut bytes)?;
+                Ok(Some(bytes))
+            }
+        }
+    }
+
+    /// Whether a folder exists and holds anything.
+    fn has_dir(&self, name: &str) -> bool {
+        match self {
+            Self::Dir(root) => fs::read_dir(long_path(&root.join(name)))
+                .is_ok_and(|mut entries| entries.next().is_some()),
+            Self::Zip(archive) => {
+                let prefix = format!("{name}/");
+                archive
+                    .file_names()
+                    .any(|file| file.starts_with(&prefix) && file.len() > prefix.len())
+            }
+        }
+    }
+
+    /// Total size of the archive's files.
+    fn total_size(&mut self) -> u64 {
+        match self {
+            Self::Dir(root) => WalkDir::new(root)
+                .into_iter()
+                .filter_map(std::result::Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+                .filter_map(|entry| entry.metadata().ok())
+                .map(|metadata| metadata.len())
+                .sum(),
+            Self::Zip(archive) => (0..archive.len())
+                .filter_map(|i| archive.by_index_raw(i).ok().map(|entry| entry.size()))
+                .sum(),
+        }
+    }
+}
+
+fn check(
+    name: String,
+    status: CheckStatus,
+    message: String,
+    suggestion: Option<&str>,
+) -> HealthCheck {
+    HealthCheck {
+        category: CheckCategory::Archive,
+        name,
+        status,
+        message,
+        suggestion: suggestion.map(String::from),
+    }
+}
+
+const REDOWNLOAD: &str =
+    "Download the archive again from x.com/settings/download_your_data and re-extract it";
+
+/// Verify an archive against its manifest.
+#[must_use]
+pub fn verify(source: &mut ArchiveSource) -> Vec<HealthCheck> {
+    let manifest_name = "Manifest".to_string();
+    let manifest = match source.read(MANIFEST_PATH) {
+        Ok(Some(bytes)) => String::from_utf8(bytes)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| parse_manifest(&content)),
+        Ok(None) => {
+            return vec![check(
+                manifest_name,
+                CheckStatus::Error,
+                format!("{MANIFEST_PATH} not found"),
+                Some("Point xf verify at the archive's top folder (the one holding data/)"),
+            )];
+        }
+        Err(e) => Err(e.into()),
+    };
+    let manifest = match manifest {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            return vec![check(
+                manifest_name,
+                CheckStatus::Error,
+                format!("Unreadable: {e:#}"),
+                Some(REDOWNLOAD),
+            )];
+        }
+    };
+
+    let mut checks = Vec::new();
+    if manifest.files.is_empty() {
+        checks.push(check(
+            manifest_name,
+            CheckStatus::Warning,
+            "Lists no data files; nothing to compare against".to_string(),
+            None,
+        ));
+    } else {
+        checks.push(check(
+            manifest_name,
+            CheckStatus::Pass,
+            format!(
+                "Lists {} data files and {} media folders",
+                manifest.files.len(),
+                manifest.media_dirs.len()
+            ),
+            None,
+        ));
+    }
+
+    for file in &manifest.files {
+        checks.push(verify_file(source, file));
+    }
+
+    for dir in &manifest.media_dirs {
+        let name = format!("Media: {dir}");
+        if source.has_dir(dir) {
+            checks.push(check(name, CheckStatus::Pass, "Present".to_string(), None));
+        } else {
+            checks.push(check(
+                name,
+                CheckStatus::Warning,
+                "Missing or empty".to_string(),
+                Some("If you imported with --no-media, add media with xf import --media-only"),
+            ));
+        }
+    }
+
+    if let Some(expected) = manifest.size_bytes.filter(|size| *size > 0) {
+        let actual = source.total_size();
+        // Allow for rounding and files the manifest doesn't count
+        if actual < expected / 10 * 9 {
+            checks.push(check(
+                "Size".to_string(),
+                CheckStatus::Warning,
+                format!(
+                    "{} present, manifest reports {}",
+                    format_bytes(actual),
+                    format_bytes(expected)
+                ),
+                Some("Media folders or data files may be missing"),
+            ));
+        } else {
+            checks.push(check(
+                "Size".to_string(),
+                CheckStatus::Pass,
+                format!("{} present", format_bytes(actual)),
+                None,
+            ));
+        }
+    }
+
+    checks
+}
+
+fn verify_file(source: &mut ArchiveSource, file: &ManifestFile) -> HealthCheck {
+    let name = format!("File: {}", file.file_name);
+    let expected = file
+        .count
+        .map(|count| format!(" ({count} {} records)", file.data_type))
+        .unwrap_or_default();
+
+    let bytes = match source.read(&file.file_name) {
+        Ok(Some(bytes)) => bytes,
+        Ok(None) => {
+            return check(
+                name,
+                CheckStatus::Error,
+                format!("Missing{expected}"),
+                Some(REDOWNLOAD),
+            );
+        }
+        Err(e) => {
+            return check(
+                name,
+                CheckStatus::Error,
+                format!("Unreadable: {e}"),
+                Some(REDOWNLOAD),
+            );
+        }
+    };
+
+    let records = String::from_utf8(bytes)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| parse_js_data(&content))
+        .map(|data| data.as_array().map_or(1, Vec::len));
+    let records = match records {
+        Ok(records) => records,
+        Err(e) => {
+            return check(
+                name,
+                CheckStatus::Error,
+                format!("Truncated or corrupt: {e:#}"),
+                Some(REDOWNLOAD),
+            );
+        }
+    };
+
+    match file.count {
+        Some(count) if records < count => check(
+            name,
+            CheckStatus::Error,
+            format!("Has {records} of {count} records (truncated)"),
+            Some(REDOWNLOAD),
+        ),
+        Some(count) if records > count => check(
+            name,
+            CheckStatus::Warning,
+            format!("Has {records} records; manifest lists {count}"),
+            None,
+        ),
+        _ => check(name, CheckStatus::Pass, format!("{records} records"), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const MANIFEST: &str = r#"window.__THAR_CONFIG = {
+        "archiveInfo": {"sizeBytes": "100"},
+        "dataTypes": {
+            "tweets": {
+                "mediaDirectory": "data/tweets_media",
+                "files": [{"fileName": "data/tweets.js", "globalName": "YTD.tweets.part0", "count": "2"}]
+            },
+            "like": {
+                "files": [{"fileName": "data/like.js", "globalName": "YTD.like.part0", "count": "1"}]
+            },
+            "follower": {
+                "files": [{"fileName": "data/follower.js", "globalName": "YTD.follower.part0", "count": "0"}]
+            }
+        }
+    }"#;
+
+    fn status_of<'a>(checks: &'a [HealthCheck], name: &str) -> &'a HealthCheck {
+        checks.iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = parse_manifest(MANIFEST).unwrap();
+        assert_eq!(manifest.size_bytes, Some(100));
+        assert_eq!(manifest.media_dirs, vec!["data/tweets_media"]);
+        let files: Vec<(&str, Option<usize>)> = manifest
+            .files
+            .iter()
+            .map(|f| (f.file_name.as_str(), f.count))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("data/follower.js", Some(0)),
+                ("data/like.js", Some(1)),
+                ("data/tweets.js", Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_directory_finds_missing_and_truncated_files() {
+        let dir = TempDir::new().unwrap();
+        let data = dir.path().join("data");
+        fs::create_dir_all(&data).unwrap();
+        fs::write(data.join("manifest.js"), MANIFEST).unwrap();
+        // One record short, and cut off mid-array
+        fs::write(data.join("tweets.js"), "window.YTD.tweets.part0 = [{}]").unwrap();
+        fs::write(data.join("like.js"), "window.YTD.like.part0 = [{\"like\": ").unwrap();
+
+        let checks = verify(&mut ArchiveSource::open(dir.path()).unwrap());
+        assert_eq!(status_of(&checks, "Manifest").status, CheckStatus::Pass);
+        let tweets = status_of(&checks, "File: data/tweets.js");
+        assert_eq!(tweets.status, CheckStatus::Error);
+        assert!(tweets.message.contains("1 of 2"));
+        let likes = status_of(&checks, "File: data/like.js");
+        assert!(likes.message.starts_with("Truncated or corrupt"));
+        let followers = status_of(&checks, "File: data/follower.js");
+        assert!(followers.message.starts_with("Missing"));
+        assert_eq!(
+            status_of(&checks, "Media: data/tweets_media").status,
+            CheckStatus::Warning
+        );
+    }
+
+    #[test]
+    fn test_verify_zip() {
+        let dir = TempDir::new().unwrap();
+        let zip_path = dir.path().join("archive.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in [
+            ("data/manifest.js", MANIFEST),
+            ("data/tweets.js", "window.YTD.tweets.part0 = [{}, {}]"),
+            ("data/like.js", "window.YTD.like.part0 = [{}]"),
+            ("data/follower.js", "window.YTD.follower.part0 = []"),
+            ("data/tweets_media/1-a.jpg", "jpg"),
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let checks = verify(&mut ArchiveSource::open(&zip_path).unwrap());
+        assert!(
+            checks.iter().all(|c| c.status == CheckStatus::Pass),
+            "{checks:?}"
+        );
+        assert_eq!(checks.len(), 6);
+    }
+
+    #[test]
+    fn test_verify_without_manifest() {
+        let dir = TempDir::new().unwrap();
+        let checks = verify(&mut ArchiveSource::open(dir.path()).unwrap());
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Error);
+    }
+}
@@ -0,0 +1,299 @@
+//! Index health watchdog.
+//!
+//! Commands that use the search index note the problems they run into: a
+//! search that failed, a search index whose document count drifted from the
+//! database's, or stored embeddings the configured model cannot be compared
+//! with. Each is kept in the database's `meta` table with the time it was
+//! first seen, so later commands can print a one-line banner with the fix
+//! instead of failing with a confusing error further on. A successful
+//! `xf index` clears them all.
+
+use crate::doctor::{CheckCategory, CheckStatus, HealthCheck};
+use crate::storage::Storage;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+/// Meta key for the database/search-index document count gap left by the
+/// last `xf index`. Skipped data types make a gap normal, so drift is a
+/// change in the gap rather than any gap at all.
+const INDEX_GAP_KEY: &str = "watchdog.index_gap";
+
+/// A problem noticed while using the search index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anomaly {
+    /// A search failed inside the index.
+    SearchError,
+    /// The search index and the database disagree on the document count.
+    DocumentDrift,
+    /// The stored embeddings came from a model other than the configured one.
+    EmbeddingMismatch,
+}
+
+impl Anomaly {
+    pub const ALL: [Self; 3] = [
+        Self::SearchError,
+        Self::DocumentDrift,
+        Self::EmbeddingMismatch,
+    ];
+
+    const fn key(self) -> &'static str {
+        match self {
+            Self::SearchError => "watchdog.search_error",
+            Self::DocumentDrift => "watchdog.document_drift",
+            Self::EmbeddingMismatch => "watchdog.embedding_mismatch",
+        }
+    }
+
+    /// Command that repairs the anomaly.
+    #[must_use]
+    pub const fn fix(self) -> &'static str {
+        match self {
+            Self::SearchError | Self::DocumentDrift => "xf index --force",
+            Self::EmbeddingMismatch => "xf embed",
+        }
+    }
+
+    const fn title(self) -> &'static str {
+        match self {
+            Self::SearchError => "Search errors",
+            Self::DocumentDrift => "Index out of sync",
+            Self::EmbeddingMismatch => "Embeddings out of date",
+        }
+    }
+}
+
+/// An anomaly with when it was first seen and what was seen last.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnomalyRecord {
+    pub anomaly: Anomaly,
+    pub since: DateTime<Utc>,
+    pub detail: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredAnomaly {
+    since: DateTime<Utc>,
+    detail: String,
+}
+
+/// Record `anomaly`, keeping the time it was first seen if it is already
+/// recorded.
+///
+/// # Errors
+///
+/// Returns an error if the meta table cannot be read or written.
+pub fn record(storage: &Storage, anomaly: Anomaly, detail: &str) -> Result<()> {
+    let since = load(storage, anomaly)?.map_or_else(Utc::now, |record| record.since);
+    let stored = StoredAnomaly {
+        since,
+        detail: detail.to_string(),
+    };
+    storage.set_meta(anomaly.key(), &serde_json::to_string(&stored)?)
+}
+
+/// Forget `anomaly` once it no longer applies.
+///
+/// # Errors
+///
+/// Returns an error if the meta table cannot be read or written.
+pub fn clear(storage: &Storage, anomaly: Anomaly) -> Result<()> {
+    // Check first so that healthy commands never take a write lock
+    if storage.get_meta(anomaly.key())?.is_some() {
+        storage.delete_meta(anomaly.key())?;
+    }
+    Ok(())
+}
+
+/// Recorded anomalies, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the meta table cannot be read.
+pub fn active(storage: &Storage) -> Result<Vec<AnomalyRecord>> {
+    let mut records = Vec::new();
+    for anomaly in Anomaly::ALL {
+        records.extend(load(storage, anomaly)?);
+    }
+    records.sort_by_key(|record| record.since);
+    Ok(records)
+}
+
+fn load(storage: &Storage, anomaly: Anomaly) -> Result<Option<AnomalyRecord>> {
+    // An unreadable entry is as good as none; the next check rewrites it
+    Ok(storage
+        .get_meta(anomaly.key())?
+        .and_then(|value| serde_json::from_str::<StoredAnomaly>(&value).ok())
+        .map(|stored| AnomalyRecord {
+            anomaly,
+            since: stored.since,
+            detail: stored.detail,
+        }))
+}
+
+/// Note the state of a freshly built search index holding `index_docs`
+/// documents, and clear every anomaly.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be queried or written.
+pub fn record_index_baseline(storage: &Storage, index_docs: u64) -> Result<()> {
+    let gap = storage.indexable_document_count()? - i64::try_from(index_docs).unwrap_or(i64::MAX);
+    storage.set_meta(INDEX_GAP_KEY, &gap.to_string())?;
+    for anomaly in Anomaly::ALL {
+        clear(storage, anomaly)?;
+    }
+    Ok(())
+}
+
+/// Compare a search index holding `index_docs` documents with the database,
+/// recording drift or clearing it. Indexes built before the watchdog have no
+/// baseline and are not checked.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be queried or written.
+pub fn check_document_count(storage: &Storage, index_docs: u64) -> Result<()> {
+    let Some(baseline) = storage
+        .get_meta(INDEX_GAP_KEY)?
+        .and_then(|value| value.parse::<i64>().ok())
+    else {
+        return Ok(());
+    };
+    let expected = storage.indexable_document_count()? - baseline;
+    let actual = i64::try_from(index_docs).unwrap_or(i64::MAX);
+    if actual == expected {
+        return clear(storage, Anomaly::DocumentDrift);
+    }
+    let documents = |count: i64| {
+        let noun = if count == 1 { "document" } else { "documents" };
+        format!("{} {noun}", crate::format_number(count))
+    };
+    let detail = if actual < expected {
+        format!(
+            "the search index is missing {}",
+            documents(expected - actual)
+        )
+    } else {
+        format!(
+            "the search index has {} no longer in the database",
+            documents(actual - expected)
+        )
+    };
+    record(storage, Anomaly::DocumentDrift, &detail)
+}
+
+/// One line describing the oldest anomaly and its fix, mentioning how many
+/// more there are.
+#[must_use]
+pub fn banner(records: &[AnomalyRecord]) -> Option<String> {
+    let first = records.first()?;
+    let mut line = format!(
+        "{} since {}: {} — run {}",
+        first.anomaly.title(),
+        first.since.format("%Y-%m-%d"),
+        first.detail,
+        first.anomaly.fix()
+    );
+    if records.len() > 1 {
+        let _ = write!(line, " ({} more: xf doctor)", records.len() - 1);
+    }
+    Some(line)
+}
+
+/// Recorded anomalies as `xf doctor` checks.
+#[must_use]
+pub fn health_checks(storage: &Storage) -> Vec<HealthCheck> {
+    let records = match active(storage) {
+        Ok(records) => records,
+        Err(err) => {
+            return vec![HealthCheck {
+                category: CheckCategory::Index,
+                name: "Watchdog".to_string(),
+                status: CheckStatus::Warning,
+                message: format!("Failed to read recorded anomalies: {err}"),
+                suggestion: None,
+            }];
+        }
+    };
+    if records.is_empty() {
+        return vec![HealthCheck {
+            category: CheckCategory::Index,
+            name: "Watchdog".to_string(),
+            status: CheckStatus::Pass,
+            message: "No anomalies recorded by recent commands".to_string(),
+            suggestion: None,
+        }];
+    }
+    records
+        .into_iter()
+        .map(|record| HealthCheck {
+            category: CheckCategory::Index,
+            name: format!("Watchdog: {}", record.anomaly.title()),
+            status: CheckStatus::Warning,
+            message: format!(
+                "{} (since {})",
+                record.detail,
+                record.since.format("%Y-%m-%d")
+            ),
+            suggestion: Some(format!("Run '{}'", record.anomaly.fix())),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_keeps_first_seen_and_clears() {
+        let storage = Storage::open_memory().unwrap();
+        assert!(active(&storage).unwrap().is_empty());
+
+        record(&storage, Anomaly::SearchError, "first").unwrap();
+        let since = active(&storage).unwrap()[0].since;
+        record(&storage, Anomaly::SearchError, "second").unwrap();
+        let records = active(&storage).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].since, since);
+        assert_eq!(records[0].detail, "second");
+
+        record(&storage, Anomaly::EmbeddingMismatch, "model changed").unwrap();
+        let line = banner(&active(&storage).unwrap()).unwrap();
+        assert!(line.starts_with("Search errors since "));
+        assert!(line.ends_with("run xf index --force (1 more: xf doctor)"));
+
+        clear(&storage, Anomaly::SearchError).unwrap();
+        let records = active(&storage).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].anomaly, Anomaly::EmbeddingMismatch);
+        assert!(banner(&[]).is_none());
+    }
+
+    #[test]
+    fn test_document_drift_against_baseline() {
+        let storage = Storage::open_memory().unwrap();
+        let db_docs = u64::try_from(storage.indexable_document_count().unwrap()).unwrap();
+
+        // Without a baseline nothing is checked
+        check_document_count(&storage, db_docs + 5).unwrap();
+        assert!(active(&storage).unwrap().is_empty());
+
+        record(&storage, Anomaly::SearchError, "failed").unwrap();
+        record_index_baseline(&storage, db_docs).unwrap();
+        assert!(active(&storage).unwrap().is_empty());
+
+        check_document_count(&storage, db_docs + 3).unwrap();
+        let records = active(&storage).unwrap();
+        assert_eq!(records[0].anomaly, Anomaly::DocumentDrift);
+        assert_eq!(
+            records[0].detail,
+            "the search index has 3 documents no longer in the database"
+        );
+
+        check_document_count(&storage, db_docs).unwrap();
+        assert!(active(&storage).unwrap().is_empty());
+    }
+}
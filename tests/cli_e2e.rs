@@ -111,9 +111,11 @@ fn create_empty_archive() -> (TempDir, PathBuf) {
     )
 }
 
-/// Get the xf command ready for testing
+/// Get the xf command ready for testing, with English output
 fn xf_cmd() -> Command {
-    cargo_bin_cmd!("xf")
+    let mut cmd = cargo_bin_cmd!("xf");
+    cmd.env("LC_ALL", "C");
+    cmd
 }
 
 fn parse_search_results(output: &std::process::Output) -> Vec<SearchResult> {
@@ -502,6 +504,42 @@ fn test_cli_no_args() {
     test_log!("test_cli_no_args completed in {:?}", start.elapsed());
 }
 
+#[test]
+fn test_cli_schema_without_archive() {
+    test_log!("Starting test_cli_schema_without_archive");
+    let start = Instant::now();
+    let temp = TempDir::new().expect("temp dir");
+
+    let output = xf_cmd()
+        .args(["search", "--schema", "--db"])
+        .arg(temp.path().join("missing.db"))
+        .output()
+        .expect("run search --schema");
+    assert!(output.status.success());
+    let schema: Value = serde_json::from_slice(&output.stdout).expect("json schema");
+    assert_eq!(schema["type"], "array");
+    assert_eq!(schema["x-xf-version"], env!("CARGO_PKG_VERSION"));
+
+    let output = xf_cmd()
+        .args(["export", "--schema"])
+        .output()
+        .expect("run export --schema");
+    assert!(output.status.success());
+    let schema: Value = serde_json::from_slice(&output.stdout).expect("json schema");
+    assert!(schema["properties"]["tweets"].is_object());
+
+    xf_cmd()
+        .args(["export", "calendar", "--schema"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No schema for calendar exports"));
+
+    test_log!(
+        "test_cli_schema_without_archive completed in {:?}",
+        start.elapsed()
+    );
+}
+
 // =============================================================================
 // Index Command Tests
 // =============================================================================
@@ -642,6 +680,206 @@ fn create_indexed_archive() -> (TempDir, TempDir, PathBuf, PathBuf) {
     (archive_temp, output_dir, db_path, index_path)
 }
 
+#[test]
+fn test_index_lite_mode() {
+    test_log!("Starting test_index_lite_mode");
+    let start = Instant::now();
+
+    let (_archive_temp, archive_path) = create_minimal_archive();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let db_path = output_dir.path().join("test.db");
+    let index_path = output_dir.path().join("test_index");
+
+    let mut cmd = xf_cmd();
+    cmd.arg("index")
+        .arg(&archive_path)
+        .arg("--lite")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Lite mode"));
+
+    let storage = Storage::open(&db_path).expect("open db");
+    let counts = storage.get_all_counts().expect("counts");
+    assert_eq!(counts.tweets_count, 3);
+    assert_eq!(counts.likes_count, 0);
+    assert_eq!(storage.embedding_count().expect("embedding count"), 0);
+    assert!(storage.is_lite_mode());
+    drop(storage);
+    assert!(!index_path.join("vector.idx").exists());
+
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["search", "rust", "--format", "json", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .output()
+        .expect("run search");
+    assert!(!parse_search_results(&output).is_empty());
+
+    // Nothing is missing as far as doctor is concerned
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["doctor", "--format", "json", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .output()
+        .expect("run doctor");
+    let report: Value = serde_json::from_slice(&output.stdout).expect("json output");
+    let checks = report["checks"].as_array().expect("checks");
+    let problems: Vec<&Value> = checks
+        .iter()
+        .filter(|check| {
+            check["category"] != "archive"
+                && check["category"] != "performance"
+                && check["status"] != "pass"
+        })
+        .collect();
+    assert!(problems.is_empty(), "{problems:?}");
+
+    test_log!("test_index_lite_mode completed in {:?}", start.elapsed());
+}
+
+#[test]
+fn test_index_and_search_with_sqlite_engine() {
+    test_log!("Starting test_index_and_search_with_sqlite_engine");
+    let start = Instant::now();
+
+    let (_archive_temp, archive_path) = create_minimal_archive();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let db_path = output_dir.path().join("test.db");
+    let index_path = output_dir.path().join("test_index");
+    let xf_with = |args: &[&str]| {
+        xf_cmd()
+            .args(args)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("run xf")
+    };
+
+    let output = xf_with(&[
+        "index",
+        archive_path.to_str().unwrap(),
+        "--engine",
+        "sqlite",
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(!index_path.join("meta.json").exists());
+
+    let output = xf_with(&["search", "rust", "--engine", "sqlite", "--format", "json"]);
+    let mut ids: Vec<String> = parse_search_results(&output)
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    ids.sort();
+
+    // Same matches as the Tantivy index built from the same archive
+    let (_tantivy_archive, _tantivy_dir, tantivy_db, tantivy_index) = create_indexed_archive();
+    let output = xf_cmd()
+        .args(["search", "rust", "--format", "json", "--db"])
+        .arg(&tantivy_db)
+        .arg("--index")
+        .arg(&tantivy_index)
+        .output()
+        .expect("run search");
+    let mut expected: Vec<String> = parse_search_results(&output)
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    expected.sort();
+    assert!(!ids.is_empty());
+    assert_eq!(ids, expected);
+
+    // Without --engine, the missing Tantivy index is reported
+    let output = xf_with(&["search", "rust"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--engine sqlite"));
+
+    let output = xf_with(&["verify-index", "--engine", "sqlite", "--format", "json"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let output = xf_with(&[
+        "index",
+        archive_path.to_str().unwrap(),
+        "--lite",
+        "--engine",
+        "sqlite",
+    ]);
+    assert!(!output.status.success());
+
+    test_log!(
+        "test_index_and_search_with_sqlite_engine completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_verify_index_against_manifest() {
+    test_log!("Starting test_verify_index_against_manifest");
+    let start = Instant::now();
+
+    let (archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let verify_index = |extra: &[&str]| {
+        xf_cmd()
+            .arg("verify-index")
+            .args(extra)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .arg("--format")
+            .arg("json")
+            .output()
+            .expect("run verify-index")
+    };
+
+    let output = verify_index(&[]);
+    assert!(output.status.success(), "{output:?}");
+    let report: Value = serde_json::from_slice(&output.stdout).expect("parse JSON");
+    assert_eq!(report["summary"]["errors"], 0);
+    assert_eq!(report["fingerprint"].as_str().map(str::len), Some(64));
+
+    // The same manifest compares equal; an edited archive file does not
+    let manifest = index_path.join("xf-index-manifest.json");
+    let output = verify_index(&["--against", manifest.to_str().unwrap()]);
+    assert!(output.status.success(), "{output:?}");
+
+    fs::write(
+        archive_temp.path().join("data").join("like.js"),
+        "window.YTD.like.part0 = []",
+    )
+    .expect("edit like.js");
+    let output = verify_index(&[]);
+    assert!(!output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).expect("parse JSON");
+    let sources = report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "Source files")
+        .unwrap();
+    assert_eq!(sources["status"], "error");
+    assert!(
+        sources["message"]
+            .as_str()
+            .unwrap()
+            .contains("data/like.js changed")
+    );
+
+    test_log!(
+        "test_verify_index_against_manifest completed in {:?}",
+        start.elapsed()
+    );
+}
+
 #[test]
 fn test_search_basic_query() {
     test_log!("Starting test_search_basic_query");
@@ -765,6 +1003,129 @@ fn test_search_type_filter_likes() {
     );
 }
 
+#[test]
+fn test_search_type_filter_bookmarks() {
+    test_log!("Starting test_search_type_filter_bookmarks");
+    let start = Instant::now();
+
+    let (archive_temp, archive_path) =
+        create_test_archive(None, Some(SAMPLE_LIKES), None, None, None);
+    fs::write(
+        archive_temp.path().join("data").join("bookmark.js"),
+        r#"window.YTD.bookmark.part0 = [
+            {"bookmark": {"tweetId": "555", "fullText": "Bookmarked notes on database sharding"}}
+        ]"#,
+    )
+    .expect("Failed to write bookmark.js");
+    let output_dir = TempDir::new().expect("Failed to create output directory");
+    let db_path = output_dir.path().join("test.db");
+    let index_path = output_dir.path().join("index");
+
+    xf_cmd()
+        .arg("index")
+        .arg(&archive_path)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 bookmarks"));
+
+    // "database" matches a like and the bookmark; only the bookmark is kept
+    for engine in ["tantivy", "sqlite"] {
+        test_log!("Searching bookmarks with engine {engine}");
+        let output = xf_cmd()
+            .args([
+                "search", "database", "--types", "bookmark", "--format", "json",
+            ])
+            .args(["--engine", engine])
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run search");
+        assert!(output.status.success());
+        let results: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Invalid JSON");
+        let results = results.as_array().expect("Expected an array");
+        assert_eq!(results.len(), 1, "engine {engine}");
+        assert_eq!(results[0]["id"], "555");
+        assert_eq!(results[0]["result_type"], "bookmark");
+    }
+
+    test_log!(
+        "test_search_type_filter_bookmarks completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_grep_finds_partial_words_and_counts_by_type() {
+    test_log!("Starting test_grep_finds_partial_words_and_counts_by_type");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let grep = |args: &[&str]| {
+        let output = xf_cmd()
+            .arg("grep")
+            .args(args)
+            .args(["--format", "json"])
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run grep");
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).expect("Invalid JSON")
+    };
+
+    // Text inside words, which the tokenizers cannot match
+    let results = grep(&["ust prog"]);
+    let results = results.as_array().expect("Expected an array");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["result_type"], "tweet");
+    assert_eq!(results[0]["match_ranges"][0]["start"], 41);
+
+    let counts = grep(&["-c", "-i", "RUST"]);
+    assert_eq!(counts["by_type"]["tweet"], 1);
+    assert_eq!(counts["by_type"]["like"], 1);
+    assert_eq!(counts["matches"], 2);
+
+    let counts = grep(&["-c", "-E", r"data(base)?\b", "--types", "like"]);
+    assert_eq!(counts["matches"], 1);
+    assert_eq!(counts["scanned"], 2);
+
+    // Hidden documents only with --hidden
+    xf_cmd()
+        .args(["hide", "1234567890123456789", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success();
+    assert_eq!(grep(&["ust prog"]).as_array().map(Vec::len), Some(0));
+    assert_eq!(
+        grep(&["ust prog", "--hidden"]).as_array().map(Vec::len),
+        Some(1)
+    );
+
+    xf_cmd()
+        .args(["grep", "-E", "("])
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid regular expression"));
+
+    test_log!(
+        "test_grep_finds_partial_words_and_counts_by_type completed in {:?}",
+        start.elapsed()
+    );
+}
+
 #[test]
 fn test_search_with_named_period_filters() {
     test_log!("Starting test_search_with_named_period_filters");
@@ -811,79 +1172,1697 @@ fn test_search_verbose_date_parse_output() {
         .arg("2025-01-09")
         .arg("--verbose")
         .arg("--db")
-        .arg(&db_path)
-        .arg("--index")
-        .arg(&index_path)
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Parsed --since '2025-01-09' as"));
+
+    test_log!(
+        "test_search_verbose_date_parse_output completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_search_invalid_date_expression() {
+    test_log!("Starting test_search_invalid_date_expression");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+
+    test_log!("Searching with invalid date expression");
+
+    let mut cmd = xf_cmd();
+    cmd.arg("search")
+        .arg("rust")
+        .arg("--since")
+        .arg("notadate")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("could not be parsed"));
+
+    test_log!(
+        "test_search_invalid_date_expression completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_ask_requires_endpoint_and_dry_run_shows_prompt() {
+    test_log!("Starting test_ask_requires_endpoint_and_dry_run_shows_prompt");
+    let start = Instant::now();
+
+    let (_archive_temp, output_dir, db_path, index_path) = create_indexed_archive();
+
+    // Without an endpoint, nothing is sent and the user is told how to configure one
+    let mut cmd = xf_cmd();
+    cmd.arg("ask")
+        .arg("what did I say about rust?")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .env("XDG_CONFIG_HOME", output_dir.path())
+        .env_remove("XF_LLM_ENDPOINT")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No LLM endpoint configured"));
+
+    // --dry-run prints the assembled prompt with numbered excerpts
+    let mut cmd = xf_cmd();
+    cmd.arg("ask")
+        .arg("what did I say about rust?")
+        .arg("--dry-run")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .env("XDG_CONFIG_HOME", output_dir.path())
+        .env_remove("XF_LLM_ENDPOINT")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[1] tweet"))
+        .stdout(predicate::str::contains(
+            "Question: what did I say about rust?",
+        ));
+
+    test_log!(
+        "test_ask_requires_endpoint_and_dry_run_shows_prompt completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_summarize_extractive_and_unknown_id() {
+    test_log!("Starting test_summarize_extractive_and_unknown_id");
+    let start = Instant::now();
+
+    let (_archive_temp, output_dir, db_path, _index_path) = create_indexed_archive();
+
+    // Without an endpoint the summary is built extractively
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .arg("summarize")
+        .arg("1234567890123456789")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--format")
+        .arg("json")
+        .env("XDG_CONFIG_HOME", output_dir.path())
+        .env_remove("XF_LLM_ENDPOINT")
+        .output()
+        .expect("Failed to run summarize");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("summarize JSON output");
+    assert_eq!(json["source"], "thread");
+    assert_eq!(json["method"], "extractive");
+    assert_eq!(json["message_count"], 1);
+    assert_eq!(json["key_points"][0]["id"], "1234567890123456789");
+
+    let mut cmd = xf_cmd();
+    cmd.arg("summarize")
+        .arg("no-such-id")
+        .arg("--db")
+        .arg(&db_path)
+        .env("XDG_CONFIG_HOME", output_dir.path())
+        .env_remove("XF_LLM_ENDPOINT")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Nothing to summarize"));
+
+    test_log!(
+        "test_summarize_extractive_and_unknown_id completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_tag_define_apply_and_search_filter() {
+    test_log!("Starting test_tag_define_apply_and_search_filter");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+
+    let mut cmd = xf_cmd();
+    cmd.args(["tag", "define", "databases", "--examples"])
+        .arg("SQLite embedded database storage")
+        .arg("local database for data storage")
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success();
+
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["tag", "apply", "--format", "json", "--db"])
+        .arg(&db_path)
+        .output()
+        .expect("Failed to run tag apply");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("tag apply JSON output");
+    assert_eq!(json[0]["name"], "databases");
+    assert_eq!(json[0]["tweets"], 1);
+
+    // Only the SQLite tweet carries the tag
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["search", "search OR database OR rust", "--tag", "databases"])
+        .args(["--format", "json", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .output()
+        .expect("Failed to run search");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("search JSON output");
+    let ids: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|r| r["id"].as_str())
+        .collect();
+    assert_eq!(ids, vec!["1234567890123456791"]);
+
+    let mut cmd = xf_cmd();
+    cmd.args(["search", "rust", "--tag", "databse", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown tag"));
+
+    test_log!(
+        "test_tag_define_apply_and_search_filter completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_note_add_search_show_and_export() {
+    test_log!("Starting test_note_add_search_show_and_export");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+
+    let mut cmd = xf_cmd();
+    cmd.args([
+        "note",
+        "add",
+        "1234567890123456790",
+        "benchmark for the zeppelin report",
+    ])
+    .arg("--db")
+    .arg(&db_path)
+    .arg("--index")
+    .arg(&index_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Added note 1"));
+
+    // The note's words find the document even though its text lacks them
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["search", "zeppelin", "--format", "json", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .output()
+        .expect("Failed to run search");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("search JSON output");
+    assert_eq!(json[0]["id"], "1234567890123456790");
+    assert_eq!(
+        json[0]["metadata"]["notes"][0],
+        "benchmark for the zeppelin report"
+    );
+
+    let mut cmd = xf_cmd();
+    cmd.args(["tweet", "1234567890123456790", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "benchmark for the zeppelin report",
+        ));
+
+    let mut cmd = xf_cmd();
+    cmd.args(["export", "notes", "--format", "compact", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""doc_type":"tweet""#));
+
+    let mut cmd = xf_cmd();
+    cmd.args(["note", "add", "999", "missing", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+
+    test_log!(
+        "test_note_add_search_show_and_export completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_search_scope_separates_notes_from_text() {
+    test_log!("Starting test_search_scope_separates_notes_from_text");
+    let start = Instant::now();
+
+    let (_archive_temp, archive_path) = create_minimal_archive();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let db_path = output_dir.path().join("test.db");
+    let index_path = output_dir.path().join("test_index");
+    let index = || {
+        xf_cmd()
+            .arg("index")
+            .arg(&archive_path)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .assert()
+            .success();
+    };
+    index();
+
+    xf_cmd()
+        .args([
+            "note",
+            "add",
+            "1234567890123456790",
+            "zeppelin report source",
+        ])
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success();
+    // Notes survive indexing the archive again
+    index();
+
+    let ids = |query: &str, scope: &str, engine: &str| -> Vec<String> {
+        let output = xf_cmd()
+            .args(["search", query, "--mode", "lexical", "--format", "json"])
+            .args(["--scope", scope, "--engine", engine])
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run search");
+        assert!(output.status.success(), "{query} {scope} {engine}");
+        let results: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Invalid JSON");
+        results
+            .as_array()
+            .expect("Expected an array")
+            .iter()
+            .map(|r| r["id"].as_str().unwrap_or_default().to_string())
+            .collect()
+    };
+    for engine in ["tantivy", "sqlite"] {
+        test_log!("Searching scopes with engine {engine}");
+        // A partial word only matches through the indexed note
+        assert_eq!(
+            ids("zeppel", "supplemental", engine),
+            ["1234567890123456790"],
+            "engine {engine}"
+        );
+        assert_eq!(ids("zeppel", "all", engine).len(), 1, "engine {engine}");
+        assert!(
+            ids("zeppelin", "main", engine).is_empty(),
+            "engine {engine}"
+        );
+        assert!(
+            ids("rust", "supplemental", engine).is_empty(),
+            "engine {engine}"
+        );
+    }
+
+    xf_cmd()
+        .args(["note", "remove", "1", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success();
+    for engine in ["tantivy", "sqlite"] {
+        assert!(ids("zeppel", "all", engine).is_empty(), "engine {engine}");
+    }
+
+    test_log!(
+        "test_search_scope_separates_notes_from_text completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_search_by_list_members() {
+    test_log!("Starting test_search_by_list_members");
+    let start = Instant::now();
+
+    let tweets = SAMPLE_TWEETS.replacen(
+        r#""user_mentions": []"#,
+        r#""user_mentions": [{"id_str": "77", "screen_name": "Ferris"}]"#,
+        1,
+    );
+    let (archive_temp, archive_path) = create_test_archive(Some(&tweets), None, None, None, None);
+    fs::write(
+        archive_temp.path().join("data/lists-created.js"),
+        r#"window.YTD.lists_created.part0 = [
+            {"userListInfo": {"url": "https://twitter.com/i/lists/4242"}}
+        ]"#,
+    )
+    .expect("Failed to write lists-created.js");
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let db_path = output_dir.path().join("test.db");
+    let index_path = output_dir.path().join("test_index");
+    xf_cmd()
+        .arg("index")
+        .arg(&archive_path)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success();
+
+    let search = |list: &str| {
+        xf_cmd()
+            .args(["search", "", "--list", list, "--format", "json", "--db"])
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run search")
+    };
+    // Archives carry no members, so the list cannot filter yet
+    let output = search("4242");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--members-file"));
+
+    let members = output_dir.path().join("members.txt");
+    fs::write(&members, "# curated\n@ferris\n").expect("Failed to write members");
+    xf_cmd()
+        .args(["list", "lists", "--list", "4242", "--members-file"])
+        .arg(&members)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4242"))
+        .stdout(predicate::str::contains("created"))
+        .stdout(predicate::str::contains("1 members"));
+
+    let output = search("4242");
+    assert!(output.status.success());
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Invalid JSON");
+    let ids: Vec<&str> = results
+        .as_array()
+        .expect("Expected an array")
+        .iter()
+        .filter_map(|r| r["id"].as_str())
+        .collect();
+    assert_eq!(ids, ["1234567890123456789"]);
+
+    assert!(!search("999").status.success());
+
+    test_log!(
+        "test_search_by_list_members completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_export_include_raw() {
+    test_log!("Starting test_export_include_raw");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, _index_path) = create_indexed_archive();
+
+    let output = xf_cmd()
+        .args(["export", "tweets", "--include-raw", "--db"])
+        .arg(&db_path)
+        .output()
+        .expect("Failed to run export");
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).expect("export JSON output");
+    let tweet = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["id"] == "1234567890123456789")
+        .expect("exported tweet");
+    // The archive's own representation, not xf's model
+    assert_eq!(tweet["favorite_count"], 42);
+    assert_eq!(tweet["raw"]["favorite_count"], "42");
+    assert_eq!(tweet["raw"]["entities"]["hashtags"][0]["text"], "rust");
+
+    let output = xf_cmd()
+        .args(["export", "likes", "--db"])
+        .arg(&db_path)
+        .output()
+        .expect("Failed to run export");
+    let json: Value = serde_json::from_slice(&output.stdout).expect("export JSON output");
+    assert!(json[0].get("raw").is_none());
+
+    xf_cmd()
+        .args(["export", "followers", "--include-raw", "--db"])
+        .arg(&db_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--include-raw only applies"));
+
+    test_log!("test_export_include_raw completed in {:?}", start.elapsed());
+}
+
+#[test]
+fn test_tweet_percentile() {
+    test_log!("Starting test_tweet_percentile");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, _index_path) = create_indexed_archive();
+
+    // 125 likes + retweets beats the other two sample tweets (49 and 67)
+    let output = xf_cmd()
+        .args([
+            "tweet",
+            "1234567890123456790",
+            "--percentile",
+            "--format",
+            "json",
+            "--db",
+        ])
+        .arg(&db_path)
+        .output()
+        .expect("Failed to run tweet");
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).expect("tweet JSON");
+    assert_eq!(json["percentile"]["tweets"], 3);
+    assert_eq!(json["percentile"]["engagement"]["rank"], 1);
+    assert_eq!(json["percentile"]["likes"]["rank"], 1);
+
+    xf_cmd()
+        .args([
+            "tweet",
+            "1234567890123456789",
+            "--percentile",
+            "--no-color",
+            "--db",
+        ])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Top 100% of your tweets by likes, retweets, replies and quotes (#3 of 3)",
+        ));
+
+    test_log!("test_tweet_percentile completed in {:?}", start.elapsed());
+}
+
+#[test]
+fn test_export_dms_with_media() {
+    test_log!("Starting test_export_dms_with_media");
+    let start = Instant::now();
+
+    let dms = r#"window.YTD.direct_messages.part0 = [
+        {"dmConversation": {"conversationId": "111111111-999999999", "messages": [
+            {"messageCreate": {"id": "500", "senderId": "111111111", "recipientId": "999999999",
+                "text": "photo https://t.co/p", "createdAt": "2025-01-11T12:00:00.000Z", "urls": [],
+                "mediaUrls": ["https://ton.twitter.com/1.1/ton/data/dm/500/600/AbC.jpg"]}},
+            {"messageCreate": {"id": "501", "senderId": "999999999", "recipientId": "111111111",
+                "text": "lost https://t.co/q", "createdAt": "2025-01-11T13:00:00.000Z", "urls": [],
+                "mediaUrls": ["https://ton.twitter.com/1.1/ton/data/dm/501/601/gone.png"]}}
+        ]}}
+    ]"#;
+    let (_archive_temp, archive_path) =
+        create_test_archive(Some(SAMPLE_TWEETS), None, None, None, Some(dms));
+    let media_dir = archive_path.join("data/direct_messages_media");
+    fs::create_dir_all(&media_dir).expect("create media dir");
+    fs::write(media_dir.join("500-AbC.jpg"), b"jpeg").expect("write media");
+    let output_dir = TempDir::new().expect("Failed to create output directory");
+    let db_path = output_dir.path().join("test.db");
+    let export_dir = output_dir.path().join("export");
+
+    xf_cmd()
+        .arg("index")
+        .arg(&archive_path)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(output_dir.path().join("index"))
+        .assert()
+        .success();
+
+    xf_cmd()
+        .args(["export", "dms", "--with-media", "--archive"])
+        .arg(&archive_path)
+        .arg("-o")
+        .arg(&export_dir)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Copied 1 attachments"))
+        .stderr(predicate::str::contains("1 attachments were not found"));
+
+    assert_eq!(
+        fs::read(export_dir.join("media/500.jpg")).expect("copied media"),
+        b"jpeg"
+    );
+    let json: Value =
+        serde_json::from_str(&fs::read_to_string(export_dir.join("dms.json")).expect("dms.json"))
+            .expect("export JSON");
+    let media = |id: &str| {
+        json.as_array()
+            .unwrap()
+            .iter()
+            .find(|dm| dm["id"] == id)
+            .map(|dm| dm["media_urls"][0].clone())
+    };
+    assert_eq!(media("500"), Some(Value::from("media/500.jpg")));
+    assert!(media("501").is_some_and(|url| url.as_str().unwrap().starts_with("https://")));
+
+    xf_cmd()
+        .args([
+            "export",
+            "dms",
+            "--transcript",
+            "whatsapp",
+            "--with-media",
+            "--archive",
+        ])
+        .arg(&archive_path)
+        .arg("-o")
+        .arg(&export_dir)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success();
+    let transcript = fs::read_to_string(export_dir.join("dms.txt")).expect("dms.txt");
+    assert!(transcript.contains("<attached: media/500.jpg>"));
+
+    xf_cmd()
+        .args(["export", "tweets", "--with-media", "-o"])
+        .arg(&export_dir)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only apply to DM exports"));
+
+    test_log!(
+        "test_export_dms_with_media completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_enrich_unshorten_dry_run() {
+    test_log!("Starting test_enrich_unshorten_dry_run");
+    let start = Instant::now();
+
+    let likes = r#"window.YTD.like.part0 = [
+        {"like": {"tweetId": "1", "fullText": "short", "expandedUrl": "https://bit.ly/abc"}},
+        {"like": {"tweetId": "2", "fullText": "long", "expandedUrl": "https://example.com/a"}}
+    ]"#;
+    let (_archive_temp, archive_path) =
+        create_test_archive(Some(SAMPLE_TWEETS), Some(likes), None, None, None);
+    let output_dir = TempDir::new().expect("Failed to create output directory");
+    let db_path = output_dir.path().join("test.db");
+
+    xf_cmd()
+        .arg("index")
+        .arg(&archive_path)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(output_dir.path().join("index"))
+        .assert()
+        .success();
+
+    xf_cmd()
+        .arg("enrich")
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Nothing to enrich"));
+
+    // A dry run only counts links, so no requests are made
+    let output = xf_cmd()
+        .args([
+            "enrich",
+            "--unshorten",
+            "--dry-run",
+            "--format",
+            "json",
+            "--db",
+        ])
+        .arg(&db_path)
+        .output()
+        .expect("Failed to run enrich");
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).expect("enrich JSON");
+    assert_eq!(json["pending"], 1);
+    assert_eq!(json["resolved"], 0);
+    assert_eq!(json["records_updated"], 0);
+
+    test_log!(
+        "test_enrich_unshorten_dry_run completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_search_year_histogram() {
+    test_log!("Starting test_search_year_histogram");
+    let start = Instant::now();
+
+    // 15 matching tweets in 2023 and 10 in 2024
+    let tweets: Vec<String> = (0..25)
+        .map(|i| {
+            let year = if i < 15 { 2023 } else { 2024 };
+            format!(
+                r#"{{"tweet": {{"id_str": "{}", "created_at": "Mon Mar 06 12:00:00 +0000 {year}",
+                    "full_text": "coffee note {i}", "favorite_count": "0", "retweet_count": "0",
+                    "entities": {{"hashtags": [], "user_mentions": [], "urls": []}}}}}}"#,
+                1000 + i
+            )
+        })
+        .collect();
+    let tweets = format!("window.YTD.tweets.part0 = [{}]", tweets.join(","));
+    let (_archive_temp, archive_path) = create_test_archive(Some(&tweets), None, None, None, None);
+    let output_dir = TempDir::new().expect("Failed to create output directory");
+    let db_path = output_dir.path().join("test.db");
+    let index_path = output_dir.path().join("index");
+
+    xf_cmd()
+        .arg("index")
+        .arg(&archive_path)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success();
+
+    // The histogram counts every match, not just the 3 shown
+    let search = |extra: &[&str]| {
+        let output = xf_cmd()
+            .args([
+                "search",
+                "coffee",
+                "--mode",
+                "lexical",
+                "--limit",
+                "3",
+                "--no-color",
+            ])
+            .args(extra)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run search");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let stdout = search(&[]);
+    assert!(stdout.contains("Matches by year:"));
+    assert!(stdout.contains("2023     15"));
+    assert!(stdout.contains("2024     10"));
+
+    assert!(!search(&["--no-histogram"]).contains("Matches by year:"));
+    // Everything left falls in one year
+    assert!(!search(&["--since", "2024-01-01"]).contains("Matches by year:"));
+
+    test_log!(
+        "test_search_year_histogram completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_collect_add_show_and_export() {
+    test_log!("Starting test_collect_add_show_and_export");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+
+    let mut cmd = xf_cmd();
+    cmd.args([
+        "collect",
+        "add",
+        "1234567890123456789",
+        "1234567890123456790",
+        "--to",
+        "talk",
+    ])
+    .arg("--db")
+    .arg(&db_path)
+    .arg("--index")
+    .arg(&index_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Added 2 document(s)"));
+
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["collect", "list", "--format", "json", "--db"])
+        .arg(&db_path)
+        .output()
+        .expect("Failed to run collect list");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("collect list JSON output");
+    assert_eq!(json[0]["name"], "talk");
+    assert_eq!(json[0]["items"], 2);
+
+    let mut cmd = xf_cmd();
+    cmd.args(["collect", "remove", "1234567890123456790", "--from", "talk"])
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success();
+
+    let mut cmd = xf_cmd();
+    cmd.args(["collect", "export", "talk", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("# talk"))
+        .stdout(predicate::str::contains("## tweet 1234567890123456789"))
+        .stdout(predicate::str::contains("1234567890123456790").not());
+
+    let mut cmd = xf_cmd();
+    cmd.args(["collect", "show", "tlak", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Did you mean: talk"));
+
+    test_log!(
+        "test_collect_add_show_and_export completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_hide_filters_search_export_and_stats() {
+    test_log!("Starting test_hide_filters_search_export_and_stats");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let hidden_id = "1234567890123456789";
+
+    let mut cmd = xf_cmd();
+    cmd.args(["hide", hidden_id, "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Hid 1 document(s)"));
+
+    let search = |extra: &[&str]| {
+        let mut cmd = xf_cmd();
+        let output = cmd
+            .args(["search", "Hello", "--types", "tweet", "--format", "json"])
+            .args(extra)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run search");
+        assert!(output.status.success());
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).expect("search JSON output")
+    };
+    let ids = |json: serde_json::Value| -> Vec<String> {
+        json.as_array()
+            .expect("search results array")
+            .iter()
+            .filter_map(|r| r["id"].as_str().map(String::from))
+            .collect()
+    };
+    assert!(!ids(search(&[])).contains(&hidden_id.to_string()));
+    assert_eq!(search(&["--hidden"])[0]["id"], hidden_id);
+
+    let mut cmd = xf_cmd();
+    cmd.args(["export", "tweets", "--format", "compact", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(hidden_id).not());
+
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["stats", "--detailed", "--format", "json", "--db"])
+        .arg(&db_path)
+        .output()
+        .expect("Failed to run stats");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stats JSON");
+    assert_eq!(json["stats"]["tweets_count"].as_i64(), Some(2));
+    let top_tweets = json["engagement"]["top_tweets"]
+        .as_array()
+        .expect("top tweets array");
+    assert_eq!(top_tweets.len(), 2);
+    assert!(top_tweets.iter().all(|tweet| tweet["id"] != hidden_id));
+
+    let mut cmd = xf_cmd();
+    cmd.args(["hide", hidden_id, "--undo", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success();
+    assert_eq!(search(&[])[0]["id"], hidden_id);
+
+    test_log!(
+        "test_hide_filters_search_export_and_stats completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_alerts_report_only_new_matches() {
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let run = |args: &[&str]| {
+        xf_cmd()
+            .args(args)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run command")
+    };
+    let tweet_id = "1234567890123456789";
+
+    assert!(
+        run(&[
+            "alerts",
+            "add",
+            "rusty",
+            "rust OR sqlite",
+            "--types",
+            "tweet"
+        ])
+        .status
+        .success()
+    );
+    assert!(!run(&["alerts", "add", "rusty", "other"]).status.success());
+    // Hidden now, so the first run does not record it
+    assert!(run(&["hide", tweet_id]).status.success());
+
+    let first = run(&["alerts", "run", "--format", "json"]);
+    assert!(first.status.success());
+    let reports: Value = serde_json::from_slice(&first.stdout).unwrap();
+    assert_eq!(reports[0]["first_run"], true);
+    assert_eq!(reports[0]["new"].as_array().unwrap().len(), 0);
+
+    assert!(run(&["alerts", "run"]).status.success());
+
+    // Unhiding makes it a new match, signalled by the exit code
+    assert!(run(&["hide", tweet_id, "--undo"]).status.success());
+    let changed = run(&["alerts", "run", "rusty", "--format", "json"]);
+    assert_eq!(changed.status.code(), Some(1));
+    let reports: Value = serde_json::from_slice(&changed.stdout).unwrap();
+    assert_eq!(reports[0]["new"][0]["id"], tweet_id);
+    assert_eq!(reports[0]["first_run"], false);
+
+    assert!(run(&["alerts", "run"]).status.success());
+    assert!(!run(&["alerts", "run", "rusti"]).status.success());
+    assert!(run(&["alerts", "remove", "rusty"]).status.success());
+}
+
+#[test]
+fn test_pin_for_query() {
+    test_log!("Starting test_pin_for_query");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let pinned_id = "1234567890123456791";
+
+    let mut cmd = xf_cmd();
+    cmd.args(["pin", pinned_id, "--query", "Rust  Programming", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#"for "rust programming""#));
+
+    let search = |query: &str| {
+        let mut cmd = xf_cmd();
+        let output = cmd
+            .args(["search", query, "--mode", "lexical", "--format", "json"])
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run search");
+        assert!(output.status.success());
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).expect("search JSON output")
+    };
+
+    // The pinned tweet leads even though it does not mention the query
+    let json = search("rust programming");
+    assert_eq!(json[0]["id"], pinned_id);
+    assert_eq!(json[0]["metadata"]["pinned"], true);
+    assert_ne!(search("tantivy")[0]["id"], pinned_id);
+
+    let mut cmd = xf_cmd();
+    cmd.args(["pin", pinned_id, "--undo", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success();
+    assert_ne!(search("rust programming")[0]["id"], pinned_id);
+
+    test_log!("test_pin_for_query completed in {:?}", start.elapsed());
+}
+
+#[test]
+fn test_audit_records_exports_when_enabled() {
+    test_log!("Starting test_audit_records_exports_when_enabled");
+    let start = Instant::now();
+
+    let (_archive_temp, output_dir, db_path, _index_path) = create_indexed_archive();
+    let config_home = TempDir::new().expect("Failed to create config directory");
+    let export_path = output_dir.path().join("tweets.json");
+
+    let mut cmd = xf_cmd();
+    cmd.args(["config", "--set", "audit.enabled=true"])
+        .env("XDG_CONFIG_HOME", config_home.path())
+        .assert()
+        .success();
+
+    let mut cmd = xf_cmd();
+    cmd.args(["export", "tweets", "-o"])
+        .arg(&export_path)
+        .arg("--db")
+        .arg(&db_path)
+        .env("XDG_CONFIG_HOME", config_home.path())
+        .assert()
+        .success();
+
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["audit", "--command", "export", "--format", "json", "--db"])
+        .arg(&db_path)
+        .env("XDG_CONFIG_HOME", config_home.path())
+        .output()
+        .expect("Failed to run audit");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("audit JSON");
+    assert_eq!(json.as_array().map(Vec::len), Some(1));
+    assert_eq!(json[0]["event"], "export");
+    assert_eq!(json[0]["count"], 3);
+    assert!(
+        json[0]["detail"]
+            .as_str()
+            .is_some_and(|d| d.starts_with("tweets to ") && d.ends_with("tweets.json"))
+    );
+
+    test_log!(
+        "test_audit_records_exports_when_enabled completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_missing_embedding_model_is_reported() {
+    test_log!("Starting test_missing_embedding_model_is_reported");
+    let start = Instant::now();
+
+    let (_archive_temp, output_dir, db_path, index_path) = create_indexed_archive();
+    let config_home = TempDir::new().expect("Failed to create config directory");
+    let model_dir = output_dir.path().join("no-such-model");
+
+    let mut cmd = xf_cmd();
+    cmd.args(["config", "--set"])
+        .arg(format!("embeddings.model={}", model_dir.display()))
+        .env("XDG_CONFIG_HOME", config_home.path())
+        .assert()
+        .success();
+
+    let mut cmd = xf_cmd();
+    cmd.args(["search", "rust", "--mode", "semantic", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .env("XDG_CONFIG_HOME", config_home.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Embedding model unavailable"));
+
+    let mut cmd = xf_cmd();
+    cmd.args(["search", "rust", "--mode", "lexical", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .env("XDG_CONFIG_HOME", config_home.path())
+        .assert()
+        .success();
+
+    test_log!(
+        "test_missing_embedding_model_is_reported completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_serve_mcp_answers_search_over_stdio() {
+    test_log!("Starting test_serve_mcp_answers_search_over_stdio");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{}}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"search","arguments":{"query":"rust","limit":3}}}"#,
+    ];
+
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["serve", "--mcp", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .write_stdin(requests.join("\n") + "\n")
+        .output()
+        .expect("Failed to run xf serve");
+    assert!(output.status.success());
+
+    let responses: Vec<Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Response is not JSON"))
+        .collect();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["result"]["isError"], false);
+    let results: Vec<SearchResult> = serde_json::from_str(
+        responses[1]["result"]["content"][0]["text"]
+            .as_str()
+            .expect("Tool result has no text"),
+    )
+    .expect("Tool result is not a result list");
+    assert!(!results.is_empty() && results.len() <= 3);
+
+    let mut cmd = xf_cmd();
+    cmd.arg("serve")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Nothing to serve"));
+
+    test_log!(
+        "test_serve_mcp_answers_search_over_stdio completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_tui_needs_a_terminal() {
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let mut cmd = xf_cmd();
+    cmd.args(["tui", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No terminal"));
+}
+
+#[test]
+fn test_serve_http_answers_json_requests() {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    test_log!("Starting test_serve_http_answers_json_requests");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let mut server = std::process::Command::new(env!("CARGO_BIN_EXE_xf"))
+        .args(["serve", "--http", "--addr", "127.0.0.1:0", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .env("LC_ALL", "C")
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to start xf serve");
+
+    // "Serving <db> on http://127.0.0.1:<port>"
+    let mut banner = String::new();
+    BufReader::new(server.stdout.take().expect("No stdout"))
+        .read_line(&mut banner)
+        .expect("Failed to read banner");
+    let addr = banner
+        .trim()
+        .rsplit("http://")
+        .next()
+        .expect("Banner has no address")
+        .to_string();
+
+    let get_from = |host: &str, path: &str| {
+        let mut stream = std::net::TcpStream::connect(&addr).expect("Failed to connect");
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n\r\n"
+        )
+        .expect("Failed to send request");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .expect("Failed to read response");
+        response
+    };
+    let get = |path: &str| get_from(&addr, path);
+    let stats = get("/stats");
+    let search = get("/search?q=rust&limit=1");
+    let missing = get("/tweet/0");
+    // A page on another domain resolved to this address (DNS rebinding)
+    let port = addr.rsplit(':').next().expect("Address has no port");
+    let rebound = get_from(&format!("attacker.example:{port}"), "/stats");
+    server.kill().expect("Failed to stop xf serve");
+    server.wait().ok();
+
+    assert!(stats.starts_with("HTTP/1.1 200"), "{stats}");
+    let (_, body) = stats.split_once("\r\n\r\n").expect("No body");
+    let body: Value = serde_json::from_str(body).expect("Stats are not JSON");
+    assert!(body["tweets_count"].as_i64().unwrap_or(0) > 0);
+    let (_, body) = search.split_once("\r\n\r\n").expect("No body");
+    let results: Vec<SearchResult> = serde_json::from_str(body).expect("Results are not JSON");
+    assert_eq!(results.len(), 1);
+    assert!(missing.starts_with("HTTP/1.1 404"), "{missing}");
+    assert!(rebound.starts_with("HTTP/1.1 403"), "{rebound}");
+
+    test_log!(
+        "test_serve_http_answers_json_requests completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_extract_about_subject() {
+    test_log!("Starting test_extract_about_subject");
+    let start = Instant::now();
+
+    let (_archive_temp, output_dir, db_path, _index_path) = create_indexed_archive();
+    let bundle_path = output_dir.path().join("subject.json");
+
+    let mut cmd = xf_cmd();
+    cmd.args(["extract", "--about", "@Tantivy", "-o"])
+        .arg(&bundle_path)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Extracted 1 tweets"));
+
+    let bundle: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&bundle_path).expect("read bundle"))
+            .expect("bundle JSON");
+    assert_eq!(bundle["about"], "@Tantivy");
+    assert_eq!(bundle["tweets"][0]["id"], "1234567890123456790");
+    assert_eq!(bundle["tweets"].as_array().map(Vec::len), Some(1));
+
+    let mut cmd = xf_cmd();
+    cmd.args(["extract", "--about", "@", "--db"])
+        .arg(&db_path)
+        .assert()
+        .failure();
+
+    test_log!(
+        "test_extract_about_subject completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_overlap_finds_self_likes_and_dm_shares() {
+    test_log!("Starting test_overlap_finds_self_likes_and_dm_shares");
+    let start = Instant::now();
+
+    let likes = r#"window.YTD.like.part0 = [
+        {"like": {"tweetId": "1234567890123456789", "fullText": "Hello world!"}},
+        {"like": {"tweetId": "9876543210987654321", "fullText": "Someone else"}}
+    ]"#;
+    let dms = r#"window.YTD.direct_messages.part0 = [
+        {"dmConversation": {"conversationId": "111111111-999999999", "messages": [
+            {"messageCreate": {"id": "m1", "senderId": "111111111", "recipientId": "999999999",
+                "text": "you said this https://t.co/abc", "createdAt": "2025-01-11T12:00:00.000Z",
+                "urls": [{"url": "https://t.co/abc",
+                          "expanded": "https://x.com/test_user/status/1234567890123456790"}],
+                "mediaUrls": []}},
+            {"messageCreate": {"id": "m2", "senderId": "999999999", "recipientId": "111111111",
+                "text": "and https://x.com/test_user/status/1234567890123456791",
+                "createdAt": "2025-01-11T13:00:00.000Z", "urls": [], "mediaUrls": []}}
+        ]}}
+    ]"#;
+    let (_archive_temp, archive_path) =
+        create_test_archive(Some(SAMPLE_TWEETS), Some(likes), None, None, Some(dms));
+    let output_dir = TempDir::new().expect("Failed to create output directory");
+    let db_path = output_dir.path().join("test.db");
+
+    let mut cmd = xf_cmd();
+    cmd.arg("index")
+        .arg(&archive_path)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(output_dir.path().join("index"))
+        .assert()
+        .success();
+
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["overlap", "--format", "json", "--db"])
+        .arg(&db_path)
+        .output()
+        .expect("Failed to run overlap");
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("overlap JSON");
+    assert_eq!(report["account_id"], "999999999");
+    assert_eq!(report["self_likes"].as_array().map(Vec::len), Some(1));
+    assert_eq!(report["self_likes"][0]["id"], "1234567890123456789");
+    // The owner's own message linking a tweet is not a share from someone else
+    assert_eq!(report["dm_quotes"].as_array().map(Vec::len), Some(1));
+    assert_eq!(report["dm_quotes"][0]["tweet"]["id"], "1234567890123456790");
+    assert_eq!(report["dm_quotes"][0]["sender_id"], "111111111");
+
+    let mut cmd = xf_cmd();
+    cmd.args(["overlap", "--no-color", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Self-likes (1 of your tweets you liked)",
+        ))
+        .stdout(predicate::str::contains("Learning about Tantivy"));
+
+    test_log!(
+        "test_overlap_finds_self_likes_and_dm_shares completed in {:?}",
+        start.elapsed()
+    );
+}
+
+// =============================================================================
+// Import Command Tests
+// =============================================================================
+
+/// Write a zipped archive with the sample tweets and manifest plus `extra` entries.
+fn create_archive_zip(dir: &Path, extra: &[(&str, &[u8])]) -> PathBuf {
+    use std::io::Write;
+
+    let zip_path = dir.join("archive.zip");
+    let file = fs::File::create(&zip_path).expect("create zip");
+    let mut writer = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    let entries = [
+        ("data/tweets.js", SAMPLE_TWEETS.as_bytes()),
+        ("data/manifest.js", SAMPLE_MANIFEST.as_bytes()),
+    ];
+    for (name, data) in entries.iter().chain(extra) {
+        writer.start_file(*name, options).expect("start zip entry");
+        writer.write_all(data).expect("write zip entry");
+    }
+    writer.finish().expect("finish zip");
+    zip_path
+}
+
+#[test]
+fn test_import_enforces_max_size() {
+    test_log!("Starting test_import_enforces_max_size");
+    let start = Instant::now();
+
+    let temp = TempDir::new().expect("temp dir");
+    let zip_path = create_archive_zip(temp.path(), &[]);
+    let out = temp.path().join("extracted");
+
+    let mut cmd = xf_cmd();
+    cmd.arg("import")
+        .arg(&zip_path)
+        .arg("-o")
+        .arg(&out)
+        .args(["--no-index", "--max-size", "100B"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Archive too large"));
+    assert!(!out.exists());
+
+    let mut cmd = xf_cmd();
+    cmd.arg("import")
+        .arg(&zip_path)
+        .arg("-o")
+        .arg(&out)
+        .args(["--no-index", "--max-size", "lots"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --max-size"));
+
+    let mut cmd = xf_cmd();
+    cmd.arg("import")
+        .arg(&zip_path)
+        .arg("-o")
+        .arg(&out)
+        .args(["--no-index", "--max-size", "1MB"])
+        .assert()
+        .success();
+    assert!(out.join("data/tweets.js").exists());
+
+    test_log!(
+        "test_import_enforces_max_size completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_import_no_media_then_media_only() {
+    test_log!("Starting test_import_no_media_then_media_only");
+    let start = Instant::now();
+
+    let temp = TempDir::new().expect("temp dir");
+    let zip_path = create_archive_zip(
+        temp.path(),
+        &[
+            ("data/tweets_media/1-photo.jpg", b"jpg"),
+            ("data/direct_messages_media/2-clip.mp4", b"mp4"),
+        ],
+    );
+    let out = temp.path().join("extracted");
+
+    let mut cmd = xf_cmd();
+    cmd.arg("import")
+        .arg(&zip_path)
+        .arg("-o")
+        .arg(&out)
+        .args(["--no-index", "--no-media"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Left out 2 media files"));
+    assert!(out.join("data/tweets.js").exists());
+    assert!(!out.join("data/tweets_media").exists());
+
+    let mut cmd = xf_cmd();
+    cmd.arg("import")
+        .arg(&zip_path)
+        .arg("-o")
+        .arg(&out)
+        .arg("--media-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("index is unchanged"));
+    assert!(out.join("data/tweets_media/1-photo.jpg").exists());
+    assert!(out.join("data/direct_messages_media/2-clip.mp4").exists());
+
+    let mut cmd = xf_cmd();
+    cmd.arg("import")
+        .arg(&zip_path)
+        .args(["--no-media", "--media-only"])
+        .assert()
+        .failure();
+
+    test_log!(
+        "test_import_no_media_then_media_only completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_verify_reports_truncated_files() {
+    test_log!("Starting test_verify_reports_truncated_files");
+    let start = Instant::now();
+
+    let temp = TempDir::new().expect("temp dir");
+    let data = temp.path().join("data");
+    fs::create_dir_all(&data).expect("create data dir");
+    fs::write(
+        data.join("manifest.js"),
+        r#"window.__THAR_CONFIG = {"dataTypes": {
+            "tweets": {"files": [{"fileName": "data/tweets.js", "count": "2"}]},
+            "like": {"files": [{"fileName": "data/like.js", "count": "1"}]}
+        }}"#,
+    )
+    .expect("write manifest");
+    fs::write(data.join("tweets.js"), "window.YTD.tweets.part0 = [{}, {}]").expect("write tweets");
+    fs::write(
+        data.join("like.js"),
+        "window.YTD.like.part0 = [{\"like\": {",
+    )
+    .expect("write likes");
+
+    let mut cmd = xf_cmd();
+    cmd.arg("verify")
+        .arg(temp.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("File: data/tweets.js: 2 records"))
+        .stdout(predicate::str::contains(
+            "File: data/like.js: Truncated or corrupt",
+        ));
+
+    fs::write(data.join("like.js"), "window.YTD.like.part0 = [{}]").expect("write likes");
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["--format", "json", "verify"])
+        .arg(temp.path())
+        .output()
+        .expect("run verify");
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).expect("json output");
+    assert_eq!(json["summary"]["errors"], 0);
+    assert_eq!(json["summary"]["passed"], 3);
+
+    test_log!(
+        "test_verify_reports_truncated_files completed in {:?}",
+        start.elapsed()
+    );
+}
+
+// =============================================================================
+// Stats Command Tests
+// =============================================================================
+
+#[test]
+fn test_stats_after_indexing() {
+    test_log!("Starting test_stats_after_indexing");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+
+    test_log!("Getting stats");
+
+    let mut cmd = xf_cmd();
+    cmd.arg("stats")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success()
+        // Should contain count information
+        .stdout(
+            predicate::str::contains("tweet")
+                .or(predicate::str::contains("Tweet"))
+                .or(predicate::str::contains("3")),
+        );
+
+    test_log!(
+        "test_stats_after_indexing completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_stats_as_of_snapshot() {
+    test_log!("Starting test_stats_as_of_snapshot");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let stats_as_of = |date: &str| {
+        xf_cmd()
+            .args(["--format", "json", "stats", "--as-of", date, "--db"])
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("run stats")
+    };
+
+    let output = stats_as_of("2100-01-01");
+    assert!(output.status.success());
+    let stats: Value = serde_json::from_slice(&output.stdout).expect("json output");
+    assert_eq!(stats["tweets_count"], 3);
+
+    let output = stats_as_of("2000-01-01");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No archive snapshot"));
+
+    test_log!(
+        "test_stats_as_of_snapshot completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_info_after_indexing() {
+    test_log!("Starting test_info_after_indexing");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+
+    let mut cmd = xf_cmd();
+    let output = cmd
+        .args(["--format", "json", "info", "--db"])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .output()
+        .expect("run info");
+    assert!(output.status.success());
+    let info: Value = serde_json::from_slice(&output.stdout).expect("json output");
+    assert_eq!(info["database"]["exists"], true);
+    assert!(info["database"]["bytes"].as_u64().unwrap_or(0) > 0);
+    assert_eq!(info["documents"]["tweets"], 3);
+    assert_eq!(info["schema_version"], info["expected_schema_version"]);
+    assert!(info["indexed_at"].is_string());
+    assert!(info["search_index"]["documents"].as_u64().unwrap_or(0) > 0);
+    assert!(
+        info["config"]["flag_overrides"]
+            .as_array()
+            .is_some_and(|flags| flags.len() == 2)
+    );
+
+    let mut cmd = xf_cmd();
+    cmd.arg("info")
+        .arg("--db")
+        .arg("/nonexistent/path/to/db.db")
         .assert()
         .success()
-        .stderr(predicate::str::contains("Parsed --since '2025-01-09' as"));
+        .stdout(predicate::str::contains("not found"));
 
     test_log!(
-        "test_search_verbose_date_parse_output completed in {:?}",
+        "test_info_after_indexing completed in {:?}",
         start.elapsed()
     );
 }
 
 #[test]
-fn test_search_invalid_date_expression() {
-    test_log!("Starting test_search_invalid_date_expression");
+fn test_du_after_indexing() {
+    test_log!("Starting test_du_after_indexing");
     let start = Instant::now();
 
-    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
-
-    test_log!("Searching with invalid date expression");
+    let (archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let media = archive_temp.path().join("data").join("tweets_media");
+    fs::create_dir_all(&media).expect("create media dir");
+    fs::write(media.join("1-photo.jpg"), vec![0u8; 4096]).expect("write media");
 
     let mut cmd = xf_cmd();
-    cmd.arg("search")
-        .arg("rust")
-        .arg("--since")
-        .arg("notadate")
-        .arg("--db")
+    let output = cmd
+        .args(["--format", "json", "du", "--db"])
         .arg(&db_path)
         .arg("--index")
         .arg(&index_path)
-        .assert()
-        .failure()
-        .stderr(predicate::str::contains("could not be parsed"));
+        .arg("--archive")
+        .arg(archive_temp.path())
+        .output()
+        .expect("run du");
+    assert!(output.status.success());
+    let du: Value = serde_json::from_slice(&output.stdout).expect("json output");
 
-    test_log!(
-        "test_search_invalid_date_expression completed in {:?}",
-        start.elapsed()
+    let tables = du["database"]["tables"].as_array().expect("tables");
+    assert!(tables.iter().any(|table| table["name"] == "tweets"));
+    assert!(tables.iter().any(|table| table["name"] == "fts_tweets"));
+    assert!(
+        !du["index"]["segments"]
+            .as_array()
+            .expect("segments")
+            .is_empty()
     );
-}
+    assert_eq!(du["archive"]["media"][0]["name"], "data/tweets_media");
+    assert_eq!(du["archive"]["media_bytes"], 4096);
+    let parts = ["database", "index", "archive"]
+        .iter()
+        .map(|part| du[part]["bytes"].as_u64().expect("bytes"))
+        .sum::<u64>();
+    assert_eq!(du["total_bytes"].as_u64(), Some(parts));
 
-// =============================================================================
-// Stats Command Tests
-// =============================================================================
+    test_log!("test_du_after_indexing completed in {:?}", start.elapsed());
+}
 
 #[test]
-fn test_stats_after_indexing() {
-    test_log!("Starting test_stats_after_indexing");
+fn test_prune_then_rebuild() {
+    test_log!("Starting test_prune_then_rebuild");
     let start = Instant::now();
 
     let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let prune = |args: &[&str]| {
+        let mut cmd = xf_cmd();
+        let output = cmd
+            .args(["--format", "json", "prune"])
+            .args(args)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("run prune");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice::<Value>(&output.stdout).expect("json output")
+    };
 
-    test_log!("Getting stats");
+    let dry_run = prune(&["--embeddings", "--fts", "--index-segments", "--dry-run"]);
+    assert_eq!(dry_run["pruned"].as_array().map(Vec::len), Some(3));
+    assert!(index_path.join("meta.json").exists());
+
+    let pruned = prune(&["--fts", "--index-segments"]);
+    assert_eq!(pruned["pruned"][0]["rebuild"], "xf doctor --fix");
+    assert!(!index_path.join("meta.json").exists());
+    let storage = Storage::open(&db_path).expect("open db");
+    let fts_rows: i64 = storage
+        .connection()
+        .query_row("SELECT COUNT(*) FROM fts_tweets", [], |row| row.get(0))
+        .expect("count fts");
+    assert_eq!(fts_rows, 0);
+    assert_eq!(storage.get_all_counts().expect("counts").tweets_count, 3);
+    drop(storage);
 
     let mut cmd = xf_cmd();
-    cmd.arg("stats")
+    cmd.arg("prune")
         .arg("--db")
         .arg(&db_path)
-        .arg("--index")
-        .arg(&index_path)
         .assert()
-        .success()
-        // Should contain count information
-        .stdout(
-            predicate::str::contains("tweet")
-                .or(predicate::str::contains("Tweet"))
-                .or(predicate::str::contains("3")),
-        );
+        .failure()
+        .stderr(predicate::str::contains("Nothing to prune"));
 
-    test_log!(
-        "test_stats_after_indexing completed in {:?}",
-        start.elapsed()
-    );
+    test_log!("test_prune_then_rebuild completed in {:?}", start.elapsed());
 }
 
 #[test]
@@ -1027,6 +3006,200 @@ fn test_stats_json_detailed_output() {
     );
 }
 
+#[test]
+fn test_stats_sources_and_source_filter() {
+    test_log!("Starting test_stats_sources_and_source_filter");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let run = |args: &[&str]| {
+        xf_cmd()
+            .args(args)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run xf")
+    };
+
+    let json = parse_stats_json(&run(&["stats", "--sources", "--format", "json"]));
+    let sources = &json["sources"];
+    assert_eq!(sources["total_tweets"].as_u64(), Some(3));
+    assert_eq!(sources["sources"][0]["source"], "X Web App");
+    assert_eq!(sources["sources"][0]["kind"], "web");
+    assert_eq!(sources["years"][0]["web"].as_u64(), Some(3));
+
+    let output = run(&[
+        "search", "rust", "--mode", "lexical", "--source", "web app", "--format", "json",
+    ]);
+    assert!(output.status.success());
+    let results: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results.len(), 1);
+    let output = run(&[
+        "search", "rust", "--mode", "lexical", "--source", "iphone", "--format", "json",
+    ]);
+    let results: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(results.is_empty());
+
+    let output = run(&["list", "tweets", "--source", "iPhone"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Showing 0 tweets"));
+    let output = run(&["list", "likes", "--source", "web"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--source only applies to tweets"));
+
+    test_log!(
+        "test_stats_sources_and_source_filter completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_stats_labels_follow_locale() {
+    test_log!("Starting test_stats_labels_follow_locale");
+    let start = Instant::now();
+
+    let (_temp_dir, archive_path) = create_minimal_archive();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let db_path = output_dir.path().join("test.db");
+    let index_path = output_dir.path().join("test_index");
+
+    xf_cmd()
+        .arg("index")
+        .arg(&archive_path)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success();
+
+    xf_cmd()
+        .env("LC_ALL", "es_ES.UTF-8")
+        .arg("stats")
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Resumen"))
+        .stdout(predicate::str::contains("Seguidores:"));
+
+    // JSON keys are never translated
+    xf_cmd()
+        .env("LC_ALL", "ja_JP.UTF-8")
+        .arg("stats")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"tweets_count\""));
+
+    test_log!(
+        "test_stats_labels_follow_locale completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_accessible_output_is_plain_and_labeled() {
+    test_log!("Starting test_accessible_output_is_plain_and_labeled");
+    let start = Instant::now();
+
+    let (_temp_dir, archive_path) = create_minimal_archive();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let db_path = output_dir.path().join("test.db");
+    let index_path = output_dir.path().join("test_index");
+
+    xf_cmd()
+        .arg("index")
+        .arg(&archive_path)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success();
+
+    // Box drawing, chart blocks and ANSI escapes
+    let decoration = || predicate::str::is_match("[─═█\x1b]").unwrap();
+
+    xf_cmd()
+        .arg("--accessible")
+        .arg("search")
+        .arg("hello")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Result 1 of \d+\. Type: \w+").unwrap())
+        .stdout(predicate::str::contains("Text: "))
+        .stdout(decoration().not());
+
+    xf_cmd()
+        .env("XF_ACCESSIBLE", "1")
+        .arg("stats")
+        .arg("--detailed")
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tweets: "))
+        .stdout(predicate::str::contains("Tweets by day of week: Sunday"))
+        .stdout(decoration().not());
+
+    let output = xf_cmd()
+        .arg("--accessible")
+        .arg("doctor")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .arg("--benchmark")
+        .arg("skip")
+        .output()
+        .expect("Failed to run command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Check 1 of "), "Output: {stdout}");
+    assert!(stdout.contains("Summary: "), "Output: {stdout}");
+    assert!(
+        !stdout.contains('✓') && !stdout.contains('═'),
+        "Output: {stdout}"
+    );
+
+    test_log!(
+        "test_accessible_output_is_plain_and_labeled completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_quickstart_and_errors_are_localized() {
+    test_log!("Starting test_quickstart_and_errors_are_localized");
+
+    xf_cmd()
+        .env("LC_ALL", "ja_JP.UTF-8")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("はじめに"));
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    xf_cmd()
+        .env("LC_ALL", "es_MX.UTF-8")
+        .arg("stats")
+        .arg("--db")
+        .arg(temp_dir.path().join("missing.db"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Todavía no hay ningún archivo indexado",
+        ))
+        .stderr(predicate::str::contains("Sugerencia:"));
+}
+
 // =============================================================================
 // Output Format Tests
 // =============================================================================
@@ -1068,6 +3241,48 @@ fn test_search_json_output() {
     test_log!("test_search_json_output completed in {:?}", start.elapsed());
 }
 
+#[test]
+fn test_search_queries_file_jsonl() {
+    let (_archive_temp, output_dir, db_path, index_path) = create_indexed_archive();
+    let queries = output_dir.path().join("queries.txt");
+    fs::write(&queries, "rust\n\n# skipped\nsqlite\nzzzznotfound\n").unwrap();
+
+    let output = xf_cmd()
+        .args(["search", "--queries-file"])
+        .arg(&queries)
+        .args([
+            "--format", "jsonl", "--mode", "lexical", "--limit", "2", "--db",
+        ])
+        .arg(&db_path)
+        .arg("--index")
+        .arg(&index_path)
+        .output()
+        .expect("Failed to run command");
+    assert!(output.status.success());
+
+    let lines: Vec<Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is a JSON object"))
+        .collect();
+    let tags: Vec<&str> = lines
+        .iter()
+        .map(|line| line["query"].as_str().unwrap())
+        .collect();
+    assert_eq!(tags, ["rust", "rust", "sqlite"]);
+    assert!(
+        lines
+            .iter()
+            .all(|line| line["text"].as_str().is_some() && line["id"].as_str().is_some())
+    );
+
+    // Queries come from the file, not the command line
+    xf_cmd()
+        .args(["search", "rust", "--queries-file"])
+        .arg(&queries)
+        .assert()
+        .failure();
+}
+
 #[test]
 fn test_search_semantic_score_semantics() {
     test_log!("Starting test_search_semantic_score_semantics");
@@ -1137,6 +3352,61 @@ fn test_search_semantic_score_semantics() {
     );
 }
 
+#[test]
+fn test_search_auto_mode_explains_choice() {
+    test_log!("Starting test_search_auto_mode_explains_choice");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let run = |query: &str| {
+        xf_cmd()
+            .args([
+                "search",
+                query,
+                "--mode",
+                "auto",
+                "--verbose",
+                "--format",
+                "json",
+            ])
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run command")
+    };
+
+    let output = run("rust");
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Auto mode chose lexical search: single keyword"),
+        "stderr: {stderr}"
+    );
+    assert!(!parse_search_results(&output).is_empty());
+
+    let output = run("\"rust programming\"");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Auto mode chose lexical search: query uses quotes or operators"),
+        "stderr: {stderr}"
+    );
+
+    let output = run("zyzzyva quokka");
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Auto mode chose semantic search"),
+        "stderr: {stderr}"
+    );
+
+    test_log!(
+        "test_search_auto_mode_explains_choice completed in {:?}",
+        start.elapsed()
+    );
+}
+
 #[test]
 fn test_search_hybrid_score_semantics() {
     test_log!("Starting test_search_hybrid_score_semantics");
@@ -1510,6 +3780,167 @@ fn test_doctor_json_output() {
     test_log!("test_doctor_json_output completed in {:?}", start.elapsed());
 }
 
+#[test]
+fn test_watchdog_banner_after_index_drift() {
+    test_log!("Starting test_watchdog_banner_after_index_drift");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let run = |args: &[&str]| {
+        xf_cmd()
+            .args(args)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run xf")
+    };
+
+    let output = run(&["search", "rust", "--mode", "lexical"]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("out of sync"));
+
+    // Drop a tweet behind the search index's back
+    xf::Storage::open(&db_path)
+        .unwrap()
+        .connection()
+        .execute("DELETE FROM tweets WHERE id = '1234567890123456791'", [])
+        .unwrap();
+
+    // The first search notices the drift, the next one warns about it
+    let output = run(&["search", "rust", "--mode", "lexical"]);
+    assert!(output.status.success());
+    let output = run(&["search", "rust", "--mode", "lexical"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Index out of sync since")
+            && stderr.contains("the search index has 1 document no longer in the database")
+            && stderr.contains("run xf index --force"),
+        "stderr: {stderr}"
+    );
+
+    let output = run(&["--quiet", "doctor", "--format", "json"]);
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let checks = json["checks"].as_array().unwrap();
+    assert!(
+        checks
+            .iter()
+            .any(|check| check["name"] == "Watchdog: Index out of sync")
+    );
+
+    test_log!(
+        "test_watchdog_banner_after_index_drift completed in {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_saved_search_run_list_remove() {
+    test_log!("Starting test_saved_search_run_list_remove");
+    let start = Instant::now();
+
+    let (_archive_temp, _output_dir, db_path, index_path) = create_indexed_archive();
+    let run = |args: &[&str]| {
+        xf_cmd()
+            .args(args)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--index")
+            .arg(&index_path)
+            .output()
+            .expect("Failed to run xf")
+    };
+
+    let output = run(&[
+        "search",
+        "rust",
+        "--mode",
+        "lexical",
+        "--types",
+        "tweet",
+        "--source",
+        "web app",
+        "--no-replies",
+        "--save",
+        "rusty",
+        "--format",
+        "json",
+    ]);
+    assert!(output.status.success());
+    let saved_results: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(saved_results.len(), 1);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Saved search 'rusty'"));
+
+    let output = run(&["search", "other", "--save", "rusty"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+
+    // A search that fails is not saved
+    let output = run(&[
+        "search",
+        "rust",
+        "--as-of",
+        "not a date",
+        "--save",
+        "broken",
+    ]);
+    assert!(!output.status.success());
+
+    let output = run(&["saved", "list", "--format", "json"]);
+    let searches: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(searches.len(), 1);
+    assert_eq!(searches[0]["query"], "rust");
+    assert_eq!(searches[0]["mode"], "lexical");
+    assert_eq!(searches[0]["types"][0], "tweet");
+    assert!(searches[0]["last_run_at"].is_null());
+
+    // Running it repeats the search with the saved filters
+    let output = run(&["saved", "run", "rusty", "--format", "json"]);
+    assert!(output.status.success());
+    let results: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results, saved_results);
+    let output = run(&["saved", "list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("\"rust\" --types tweet --mode lexical --source \"web app\" --no-replies"),
+        "stdout: {stdout}"
+    );
+
+    // It is an alert too, and a saved run doesn't count as the alert's run
+    let output = run(&["alerts", "run", "rusty", "--format", "json"]);
+    assert!(output.status.success());
+    let reports: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(reports[0]["first_run"], true);
+    assert_eq!(reports[0]["total"].as_u64(), Some(1));
+
+    // Alert runs apply every saved filter, not just dates and replies
+    let output = run(&[
+        "search", "rust", "--mode", "lexical", "--source", "iphone", "--save", "phone",
+    ]);
+    assert!(output.status.success());
+    let output = run(&["alerts", "run", "phone", "--format", "json"]);
+    let reports: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(reports[0]["total"].as_u64(), Some(0));
+    let output = run(&["saved", "remove", "phone"]);
+    assert!(output.status.success());
+
+    let output = run(&["saved", "run", "rustu"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Did you mean: rusty"));
+
+    let output = run(&["saved", "remove", "rusty"]);
+    assert!(output.status.success());
+    let output = run(&["saved", "list", "--format", "json"]);
+    let searches: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(searches.is_empty());
+
+    test_log!(
+        "test_saved_search_run_list_remove completed in {:?}",
+        start.elapsed()
+    );
+}
+
 #[test]
 fn test_doctor_without_archive() {
     test_log!("Starting test_doctor_without_archive");
@@ -1581,6 +4012,60 @@ fn test_doctor_performance_check() {
     test_log!("test_doctor_performance_check completed in {:?}", elapsed);
 }
 
+// =============================================================================
+// Selftest Command Tests
+// =============================================================================
+
+#[test]
+fn test_selftest_passes() {
+    test_log!("Starting test_selftest_passes");
+    let start = Instant::now();
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let workdir = temp_dir.path().join("selftest");
+
+    let output = xf_cmd()
+        .arg("selftest")
+        .arg("--workdir")
+        .arg(&workdir)
+        .arg("--format")
+        .arg("json")
+        .output()
+        .expect("Failed to run command");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "Selftest failed. Output: {stdout}");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).expect("Selftest should print JSON");
+    assert_eq!(json["failed"], 0, "Output: {stdout}");
+    assert_eq!(
+        json["steps"].as_array().map(Vec::len),
+        Some(8),
+        "Every step should run"
+    );
+    assert!(!workdir.exists(), "Workdir should be removed after a pass");
+
+    test_log!("test_selftest_passes completed in {:?}", start.elapsed());
+}
+
+#[test]
+fn test_selftest_refuses_non_empty_workdir() {
+    test_log!("Starting test_selftest_refuses_non_empty_workdir");
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("keep.txt"), "data").unwrap();
+
+    xf_cmd()
+        .arg("selftest")
+        .arg("--workdir")
+        .arg(temp_dir.path())
+        .assert()
+        .failure();
+
+    assert!(temp_dir.path().join("keep.txt").exists());
+}
+
 // =============================================================================
 // Shell Command Tests (xf-11.3.4)
 // =============================================================================
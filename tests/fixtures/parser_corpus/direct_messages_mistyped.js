@@ -0,0 +1,10 @@
+window.YTD.direct_messages.part0 = [
+  {"dmConversation": {"conversationId": "1-2", "messages": [
+    {"messageCreate": {"id": "10", "senderId": "1", "recipientId": "2", "text": "kept", "createdAt": "2025-01-08T12:00:00.000Z", "mediaUrls": [3, null, "https://ton.x.com/i/ton/data/dm/10/11/a.jpg"], "urls": "none"}},
+    {"messageCreate": {"id": 11, "senderId": "1", "recipientId": "2", "text": "numeric id", "createdAt": "2025-01-08T12:00:00.000Z"}},
+    {"reactionCreate": {"id": "12"}},
+    {"messageCreate": null}
+  ]}},
+  {"dmConversation": {"conversationId": 7, "messages": []}},
+  {"dmConversation": {"conversationId": "3-4", "messages": {"not": "an array"}}}
+]
@@ -0,0 +1 @@
+﻿window.YTD.tweets.part0 = [{"tweet": {"id_str": "1", "created_at": "Wed Jan 08 12:00:00 +0000 2025", "full_text": "starts with a byte order mark"}}]
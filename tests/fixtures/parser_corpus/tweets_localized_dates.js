@@ -0,0 +1,7 @@
+window.YTD.tweets.part0 = [
+  {"tweet": {"id_str": "1", "created_at": "mer. janv. 08 12:00:00 +0000 2025", "full_text": "French weekday and month"}},
+  {"tweet": {"id_str": "2", "created_at": "Mi 08 Okt 12:00:00 +0000 2025", "full_text": "German, day before month"}},
+  {"tweet": {"id_str": "3", "created_at": "Wed  Jan  08  12:00:00  2025", "full_text": "extra spaces, no offset"}},
+  {"tweet": {"id_str": "4", "created_at": "1736337600000", "full_text": "unix milliseconds"}},
+  {"tweet": {"id_str": "5", "created_at": "Thu Jan 01 00:00:00 +0000 1970", "full_text": "epoch placeholder is dropped"}}
+]
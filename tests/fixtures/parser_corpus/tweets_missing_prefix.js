@@ -0,0 +1 @@
+[{"tweet": {"id_str": "1"}}]
@@ -0,0 +1,9 @@
+window.YTD.tweets.part0 = [
+  {"tweet": {"id_str": 1, "created_at": "Wed Jan 08 12:00:00 +0000 2025", "full_text": "numeric id_str"}},
+  {"tweet": {"id_str": "2", "created_at": "Wed Jan 08 12:00:00 +0000 2025", "full_text": {"text": "object"}}},
+  {"tweet": {"id_str": "3", "created_at": null, "full_text": "null date"}},
+  {"tweet": {"id_str": "4", "created_at": "Wed Jan 08 12:00:00 +0000 2025", "full_text": "kept", "favorite_count": {"n": 1}, "entities": "none", "extended_entities": {"media": 7}, "geo": {"coordinates": ["NaN", "x"]}, "card": {"name": "poll2choice_text_only", "binding_values": 5}}},
+  {"tweet": null},
+  "not an object",
+  42
+]
@@ -251,6 +251,10 @@ fn test_search_ranking() {
             user_mentions: vec![],
             urls: vec![],
             media: vec![],
+            geo: None,
+            poll: None,
+            reply_count: None,
+            quote_count: None,
         },
         Tweet {
             id: "2".to_string(),
@@ -268,6 +272,10 @@ fn test_search_ranking() {
             user_mentions: vec![],
             urls: vec![],
             media: vec![],
+            geo: None,
+            poll: None,
+            reply_count: None,
+            quote_count: None,
         },
     ];
 
@@ -326,6 +334,10 @@ fn test_unicode_content() {
             user_mentions: vec![],
             urls: vec![],
             media: vec![],
+            geo: None,
+            poll: None,
+            reply_count: None,
+            quote_count: None,
         },
         Tweet {
             id: "unicode2".to_string(),
@@ -343,6 +355,10 @@ fn test_unicode_content() {
             user_mentions: vec![],
             urls: vec![],
             media: vec![],
+            geo: None,
+            poll: None,
+            reply_count: None,
+            quote_count: None,
         },
     ];
 
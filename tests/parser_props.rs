@@ -0,0 +1,224 @@
+//! Property tests and corpus fixtures for the archive and date parsers.
+//!
+//! X changes its export format without notice, so these tests feed the
+//! parsers synthetic archives (valid, truncated, malformed and with huge
+//! fields), arbitrary bytes, and the hand-written edge cases in
+//! `tests/fixtures/parser_corpus`. The parsers may reject input, but never
+//! panic and never lose valid records next to broken ones.
+//!
+//! Coverage-guided fuzz targets for the same entry points live in `fuzz/`.
+
+use chrono::{DateTime, Utc};
+use proptest::prelude::*;
+use std::path::Path;
+use tempfile::TempDir;
+use xf::date_parser::{parse_date_flexible, parse_human_date, parse_period};
+use xf::parser::{ArchiveParser, parse_js_data};
+use xf::synthetic::{Defect, SyntheticArchive};
+
+fn corpus_dir() -> &'static Path {
+    Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/parser_corpus"
+    ))
+}
+
+/// Records parsed from each corpus file, or `None` where parsing must fail.
+const CORPUS_EXPECTATIONS: &[(&str, Option<usize>)] = &[
+    ("direct_messages_mistyped.js", Some(1)),
+    ("tweets_bom.js", Some(1)),
+    ("tweets_crlf_semicolon.js", Some(1)),
+    ("tweets_empty.js", None),
+    ("tweets_localized_dates.js", Some(4)),
+    ("tweets_missing_prefix.js", None),
+    ("tweets_mistyped_fields.js", Some(1)),
+    ("tweets_not_array.js", Some(0)),
+    ("tweets_truncated.js", None),
+];
+
+fn parse_corpus_file(name: &str, content: &str) -> anyhow::Result<usize> {
+    if name.starts_with("direct_messages") {
+        ArchiveParser::direct_messages_from_js(content).map(|dms| dms.len())
+    } else {
+        ArchiveParser::tweets_from_js(content).map(|tweets| tweets.len())
+    }
+}
+
+#[test]
+fn test_corpus_fixtures_parse_as_expected() {
+    let mut names: Vec<String> = std::fs::read_dir(corpus_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    let expected: Vec<&str> = CORPUS_EXPECTATIONS.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, expected, "every corpus file needs an expectation");
+
+    for (name, expected) in CORPUS_EXPECTATIONS {
+        let content = std::fs::read_to_string(corpus_dir().join(name)).unwrap();
+        let parsed = parse_corpus_file(name, &content).ok();
+        assert_eq!(parsed, *expected, "{name}");
+    }
+}
+
+#[test]
+fn test_synthetic_archive_parses_completely() {
+    let dir = TempDir::new().unwrap();
+    let archive = SyntheticArchive::new(7);
+    let parser = ArchiveParser::new(archive.write(dir.path()).unwrap());
+    let expected = archive.expected().unwrap();
+
+    let manifest = parser.parse_manifest().unwrap();
+    assert_eq!(manifest.username, xf::synthetic::OWNER_HANDLE);
+    assert_eq!(parser.parse_tweets().unwrap().len(), expected.tweets);
+    assert_eq!(parser.parse_likes().unwrap().len(), expected.likes);
+    let messages: usize = parser
+        .parse_direct_messages()
+        .unwrap()
+        .iter()
+        .map(|c| c.messages.len())
+        .sum();
+    assert_eq!(messages, expected.dm_messages);
+    assert_eq!(
+        parser.parse_grok_messages().unwrap().len(),
+        expected.grok_messages
+    );
+    assert_eq!(parser.parse_followers().unwrap().len(), expected.followers);
+}
+
+#[test]
+fn test_synthetic_archive_huge_field_survives() {
+    let dir = TempDir::new().unwrap();
+    let archive = SyntheticArchive::new(3).with_defect(Defect::HugeField(4 << 20));
+    let parser = ArchiveParser::new(archive.write(dir.path()).unwrap());
+
+    let tweets = parser.parse_tweets().unwrap();
+    assert_eq!(tweets.len(), archive.tweets);
+    assert!(tweets.iter().any(|t| t.full_text.len() == 4 << 20));
+}
+
+fn synthetic_archive() -> impl Strategy<Value = SyntheticArchive> {
+    (
+        any::<u64>(),
+        0..40_usize,
+        0..20_usize,
+        0..4_usize,
+        0..6_usize,
+        prop::option::of(prop_oneof![
+            Just(Defect::Truncated),
+            Just(Defect::Malformed),
+            (1..100_000_usize).prop_map(Defect::HugeField),
+        ]),
+    )
+        .prop_map(
+            |(seed, tweets, likes, conversations, messages_per_conversation, defect)| {
+                SyntheticArchive {
+                    seed,
+                    tweets,
+                    likes,
+                    conversations,
+                    messages_per_conversation,
+                    defect,
+                    ..SyntheticArchive::default()
+                }
+            },
+        )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_synthetic_archives_parse_to_expected_counts(archive in synthetic_archive()) {
+        let files = archive.files();
+        let content = |name: &str| {
+            files.iter().find(|(file, _)| *file == name).map(|(_, c)| c.as_str()).unwrap()
+        };
+
+        let tweets = ArchiveParser::tweets_from_js(content("tweets.js"));
+        let likes = ArchiveParser::likes_from_js(content("like.js"));
+        let dms = ArchiveParser::direct_messages_from_js(content("direct-messages.js"));
+
+        match archive.expected() {
+            Some(expected) => {
+                prop_assert_eq!(tweets.unwrap().len(), expected.tweets);
+                prop_assert_eq!(likes.unwrap().len(), expected.likes);
+                prop_assert_eq!(dms.unwrap().len(), expected.dm_messages);
+            }
+            // A cut can land right after a complete record, so only an empty
+            // array is guaranteed to survive truncation intact
+            None => {
+                if archive.tweets > 0 {
+                    prop_assert!(tweets.is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prop_parse_js_data_never_panics(content in ".{0,400}") {
+        let _ = parse_js_data(&content);
+        let _ = ArchiveParser::tweets_from_js(&content);
+        let _ = ArchiveParser::likes_from_js(&content);
+        let _ = ArchiveParser::direct_messages_from_js(&content);
+    }
+
+    #[test]
+    fn prop_parse_js_data_reads_any_json_after_prefix(
+        name in "[a-z_]{1,20}",
+        part in 0..10_u32,
+        value in prop::collection::vec(any::<i64>(), 0..10),
+    ) {
+        let json = serde_json::to_string(&value).unwrap();
+        let parsed = parse_js_data(&format!("window.YTD.{name}.part{part} = {json};")).unwrap();
+        prop_assert_eq!(parsed, serde_json::json!(value));
+    }
+
+    #[test]
+    fn prop_tweet_records_with_any_field_values_never_panic(
+        id in prop_oneof![Just(serde_json::json!(null)), any::<i64>().prop_map(|n| serde_json::json!(n)), ".{0,30}".prop_map(|s| serde_json::json!(s))],
+        created_at in ".{0,40}",
+        text in ".{0,200}",
+        count in prop_oneof![Just(serde_json::json!("12")), Just(serde_json::json!({})), any::<f64>().prop_map(|n| serde_json::json!(n))],
+    ) {
+        let record = serde_json::json!([{ "tweet": {
+            "id_str": id,
+            "created_at": created_at,
+            "full_text": text,
+            "favorite_count": count,
+            "entities": { "hashtags": [count], "urls": count },
+            "geo": { "coordinates": [count, count] },
+            "card": { "name": "poll4choice", "binding_values": [{ "key": "choice1_label", "value": count }] }
+        }}]);
+        let _ = ArchiveParser::tweets_from_js(&format!("window.YTD.tweets.part0 = {record}"));
+    }
+
+    #[test]
+    fn prop_parse_date_never_panics(input in ".{0,60}") {
+        let _ = ArchiveParser::parse_date(&input);
+    }
+
+    #[test]
+    fn prop_x_dates_round_trip(seconds in 1_i64..4_102_444_800) {
+        let date = DateTime::<Utc>::from_timestamp(seconds, 0).unwrap();
+        let x_format = date.format("%a %b %d %H:%M:%S %z %Y").to_string();
+        prop_assert_eq!(ArchiveParser::parse_date(&x_format), Some(date));
+        prop_assert_eq!(ArchiveParser::parse_date(&date.to_rfc3339()), Some(date));
+    }
+
+    #[test]
+    fn prop_date_parser_never_panics(input in ".{0,40}", prefer_end in any::<bool>()) {
+        let _ = parse_human_date(&input, prefer_end);
+        let _ = parse_date_flexible(&input, prefer_end);
+        let _ = parse_period(&input);
+    }
+
+    #[test]
+    fn prop_iso_dates_parse_to_day_bounds(year in 1970_i32..2100, month in 1_u32..=12, day in 1_u32..=28) {
+        let input = format!("{year:04}-{month:02}-{day:02}");
+        let start = parse_date_flexible(&input, false).unwrap();
+        let end = parse_date_flexible(&input, true).unwrap();
+        prop_assert_eq!(start.format("%Y-%m-%d %H:%M:%S").to_string(), format!("{input} 00:00:00"));
+        prop_assert_eq!(end.format("%Y-%m-%d %H:%M:%S").to_string(), format!("{input} 23:59:59"));
+    }
+}